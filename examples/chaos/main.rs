@@ -0,0 +1,414 @@
+//  CHAOS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:57:35
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Shows (and checks) how the store behaves when its backend misbehaves,
+//!   by running a scripted workload against a server whose database fails,
+//!   slows down and runs out of connections on purpose.
+//
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use error_trace::trace;
+use policy_store::auth::no_op::NoOpResolver;
+use policy_store::clients::reqwest::spec::ApiErrorCode;
+use policy_store::clients::reqwest::{Client, Error, ErrorKind, RetryPolicy};
+use policy_store::databases::fault_injection::{Faults, FaultyDatabase};
+use policy_store::databases::sqlite::SQLiteDatabase;
+use policy_store::servers::axum::AxumServer;
+use policy_store::spec::Server as _;
+use policy_store::spec::audit::{AuditRecord, verify_chain};
+use policy_store::spec::metadata::{AttachedMetadata, Metadata};
+use tokio::task::JoinSet;
+use tracing::{Level, error, info};
+
+
+/***** CONSTANTS *****/
+/// How long the client waits for a request while queries are slow.
+const SLOW_TIMEOUT: Duration = Duration::from_millis(100);
+/// How long queries take while they are slow. Longer than [`SLOW_TIMEOUT`], so the client gives
+/// up while the backend is still busy.
+const SLOW_LATENCY: Duration = Duration::from_millis(200);
+/// How long every query holds on to its connection while the pool is exhausted.
+const POOL_LATENCY: Duration = Duration::from_millis(500);
+/// How long the client waits for a request while the pool is exhausted.
+const POOL_TIMEOUT: Duration = Duration::from_secs(2);
+
+
+
+
+
+/***** ARGUMENTS *****/
+/// Defines the arguments for this binary.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Whether to enable INFO- and DEBUG-level logging.
+    #[clap(long)]
+    debug: bool,
+    /// Whether to enable TRACE-level logging. Implies '--debug'.
+    #[clap(long)]
+    trace: bool,
+
+    /// The address/port on which to bind the server.
+    #[clap(short, long, default_value = "127.0.0.1:8080")]
+    address: SocketAddr,
+    /// The path to the database file to create/use.
+    #[clap(short, long, default_value = "./chaos.db")]
+    database: PathBuf,
+    /// The number of times to run the workload. Give '0' to keep going until interrupted, e.g.,
+    /// as a soak test.
+    #[clap(short, long, default_value = "1")]
+    rounds: u64,
+    /// The number of requests to send in every phase of the workload.
+    #[clap(long, default_value = "50")]
+    requests: usize,
+    /// The chance that a database call fails while failures are injected, between 0 and 1.
+    #[clap(long, default_value = "0.3")]
+    failure_rate: f64,
+    /// The number of requests to send at once when exhausting the connection pool. Should be
+    /// (well) over the number of connections in the pool.
+    #[clap(long, default_value = "64")]
+    concurrency: usize,
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates some metadata to add versions with.
+///
+/// # Arguments
+/// - `phase`: The phase of the workload adding the version, used as its name.
+///
+/// # Returns
+/// Some [`AttachedMetadata`] for the main branch.
+fn metadata(phase: &str) -> AttachedMetadata {
+    AttachedMetadata {
+        name: phase.into(),
+        description: "Added by the chaos example".into(),
+        language: "chaos".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    }
+}
+
+/// Waits until the server answers requests.
+///
+/// # Arguments
+/// - `client`: The [`Client`] to ask with.
+///
+/// # Errors
+/// This function errors if the server still isn't up after a few seconds.
+async fn wait_until_up(client: &Client) -> Result<(), String> {
+    for _ in 0..50 {
+        if client.get_active_version().await.is_ok() {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    Err("Server did not come up within 5 seconds".into())
+}
+
+/// Checks that whatever the faults did, the store is still consistent.
+///
+/// # Arguments
+/// - `client`: The [`Client`] to check with.
+/// - `acked`: The versions that the server reported as added. These must all be there.
+///
+/// # Errors
+/// This function errors if any acknowledged version is missing, if any version lacks its
+/// content, or if the audit log does not verify.
+async fn check_consistency(client: &Client, acked: &[u64]) -> Result<(), String> {
+    // Every version we were told about must be there, and whole
+    let versions: HashMap<u64, Metadata> = client.get_versions().await.map_err(|err| trace!(("Failed to get versions"), err).to_string())?;
+    if let Some(version) = acked.iter().find(|version| !versions.contains_key(version)) {
+        return Err(format!("Version {version} was acknowledged, but is missing from the store"));
+    }
+    for version in versions.keys() {
+        client
+            .get_version_content::<bool>(*version)
+            .await
+            .map_err(|err| trace!(("Version {version} is listed, but its content cannot be retrieved"), err).to_string())?;
+    }
+
+    // And the audit log must not have any holes or half-written records
+    let mut records: Vec<AuditRecord> = Vec::new();
+    loop {
+        let since: u64 = records.last().map(|record| record.seq).unwrap_or(0);
+        let res = client.get_changes::<bool>(since, None).await.map_err(|err| trace!(("Failed to get changes"), err).to_string())?;
+        if res.changes.is_empty() {
+            break;
+        }
+        records.extend(res.changes.into_iter().map(|change| change.record));
+    }
+    verify_chain(&records).map_err(|err| trace!(("Audit log does not verify"), err).to_string())?;
+    Ok(())
+}
+
+/// Decides whether an error is one the client should expect when giving up on a slow server.
+///
+/// # Arguments
+/// - `err`: The [`Error`] to judge.
+///
+/// # Returns
+/// True if the request timed out, either at the client or at the server.
+fn is_timeout(err: &Error) -> bool { err.kind() == ErrorKind::Transport || err.code() == Some(ApiErrorCode::DeadlineExceeded) }
+
+
+
+
+
+/***** PHASES *****/
+/// Adds versions without any faults, as a baseline.
+///
+/// # Arguments
+/// - `args`: The [`Arguments`] describing the workload.
+/// - `client`: The [`Client`] to send requests with.
+/// - `acked`: The versions acknowledged so far, which is extended with the new ones.
+///
+/// # Errors
+/// This function errors if any request fails.
+async fn baseline(args: &Arguments, client: &Client, acked: &mut Vec<u64>) -> Result<(), String> {
+    for _ in 0..args.requests {
+        let version: u64 =
+            client.add_version(metadata("baseline"), true).await.map_err(|err| trace!(("Failed to add version without faults"), err).to_string())?;
+        acked.push(version);
+    }
+    if let Some(version) = acked.last() {
+        client.activate(*version).await.map_err(|err| trace!(("Failed to activate version {version} without faults"), err).to_string())?;
+    }
+    println!("  baseline:        {} version(s) added", args.requests);
+    Ok(())
+}
+
+/// Adds versions while database calls fail at random.
+///
+/// # Arguments
+/// - `args`: The [`Arguments`] describing the workload.
+/// - `faults`: The [`Faults`] to inject.
+/// - `client`: The [`Client`] to send requests with.
+/// - `acked`: The versions acknowledged so far, which is extended with the new ones.
+///
+/// # Errors
+/// This function errors if a failure reaches the client as anything but a server error.
+async fn failures(args: &Arguments, faults: &Faults, client: &Client, acked: &mut Vec<u64>) -> Result<(), String> {
+    faults.set_failure_rate(args.failure_rate);
+    let (mut ok, mut failed): (usize, usize) = (0, 0);
+    for _ in 0..args.requests {
+        match client.add_version(metadata("failures"), true).await {
+            Ok(version) => {
+                acked.push(version);
+                ok += 1;
+            },
+            Err(err) if err.kind() == ErrorKind::Server => failed += 1,
+            Err(err) => {
+                faults.clear();
+                return Err(trace!(("Database failure reached the client as a non-server error"), err).to_string());
+            },
+        }
+    }
+    faults.clear();
+    println!("  failures:        {ok} version(s) added, {failed} refused with a server error");
+    Ok(())
+}
+
+/// Adds versions while database calls are slower than the client is willing to wait.
+///
+/// # Arguments
+/// - `args`: The [`Arguments`] describing the workload.
+/// - `faults`: The [`Faults`] to inject.
+/// - `client`: The [`Client`] to send requests with.
+/// - `acked`: The versions acknowledged so far, which is extended with the new ones.
+///
+/// # Errors
+/// This function errors if a request fails with anything but a timeout, or if more versions
+/// landed than were sent.
+async fn slow_queries(args: &Arguments, faults: &Faults, client: &Client, acked: &mut Vec<u64>) -> Result<(), String> {
+    let before: usize = client.get_versions().await.map_err(|err| trace!(("Failed to get versions"), err).to_string())?.len();
+
+    // NOTE: Trailing latency, so the backend has done its work by the time the client gives up
+    faults.set_trailing_latency(SLOW_LATENCY);
+    let impatient: Client = client.clone().with_timeout(SLOW_TIMEOUT);
+    let (mut ok, mut timed_out): (usize, usize) = (0, 0);
+    for _ in 0..args.requests {
+        match impatient.add_version(metadata("slow-queries"), true).await {
+            Ok(version) => {
+                acked.push(version);
+                ok += 1;
+            },
+            Err(err) if is_timeout(&err) => timed_out += 1,
+            Err(err) => {
+                faults.clear();
+                return Err(trace!(("Slow query reached the client as something else than a timeout"), err).to_string());
+            },
+        }
+    }
+    faults.clear();
+
+    // Let the abandoned calls finish, then see which of them made it anyway
+    tokio::time::sleep(SLOW_LATENCY * 2).await;
+    let after: usize = client.get_versions().await.map_err(|err| trace!(("Failed to get versions"), err).to_string())?.len();
+    let landed: usize = after.saturating_sub(before);
+    if landed > args.requests {
+        return Err(format!("Sent {} version(s) while queries were slow, but {landed} landed", args.requests));
+    }
+    println!("  slow queries:    {ok} version(s) added, {timed_out} timed out (of which {} landed anyway)", landed.saturating_sub(ok));
+    Ok(())
+}
+
+/// Reads the active version from many clients at once while every query holds on to its
+/// connection for a while, such that requests have to queue for the pool.
+///
+/// # Arguments
+/// - `args`: The [`Arguments`] describing the workload.
+/// - `faults`: The [`Faults`] to inject.
+/// - `client`: The [`Client`] to send requests with.
+///
+/// # Errors
+/// This function errors if a request fails with anything but a timeout, or if the server does
+/// not recover once the pool frees up.
+async fn pool_exhaustion(args: &Arguments, faults: &Faults, client: &Client) -> Result<(), String> {
+    faults.set_trailing_latency(POOL_LATENCY);
+    let patient: Client = client.clone().with_timeout(POOL_TIMEOUT);
+    let mut requests: JoinSet<(Duration, Result<Option<u64>, Error>)> = JoinSet::new();
+    for _ in 0..args.concurrency {
+        let patient: Client = patient.clone();
+        requests.spawn(async move {
+            let start: Instant = Instant::now();
+            let res = patient.get_active_version().await;
+            (start.elapsed(), res)
+        });
+    }
+
+    // Collect how they fared
+    let (mut ok, mut timed_out, mut slowest): (usize, usize, Duration) = (0, 0, Duration::ZERO);
+    while let Some(res) = requests.join_next().await {
+        let (took, res): (Duration, Result<Option<u64>, Error>) = res.map_err(|err| trace!(("Request task panicked"), err).to_string())?;
+        match res {
+            Ok(_) => {
+                ok += 1;
+                slowest = slowest.max(took);
+            },
+            Err(err) if is_timeout(&err) => timed_out += 1,
+            Err(err) => {
+                faults.clear();
+                return Err(trace!(("Exhausted pool reached the client as something else than a timeout"), err).to_string());
+            },
+        }
+    }
+    faults.clear();
+
+    // The server should be back to normal once the queue drains
+    tokio::time::sleep(POOL_LATENCY).await;
+    let start: Instant = Instant::now();
+    client.get_active_version().await.map_err(|err| trace!(("Server did not recover after exhausting the pool"), err).to_string())?;
+    println!(
+        "  pool exhaustion: {ok} read(s) served (slowest after {}ms), {timed_out} timed out; recovered in {}ms",
+        slowest.as_millis(),
+        start.elapsed().as_millis()
+    );
+    Ok(())
+}
+
+/// Runs all phases of the workload for the requested number of rounds.
+///
+/// # Arguments
+/// - `args`: The [`Arguments`] describing the workload.
+/// - `faults`: The [`Faults`] to inject.
+/// - `client`: The [`Client`] to send requests with.
+///
+/// # Errors
+/// This function errors as soon as any phase does, or if the store is inconsistent after a
+/// round.
+async fn workload(args: &Arguments, faults: &Faults, client: &Client) -> Result<(), String> {
+    wait_until_up(client).await?;
+
+    let mut acked: Vec<u64> = Vec::new();
+    let mut round: u64 = 1;
+    while args.rounds == 0 || round <= args.rounds {
+        println!("Round {round}:");
+        baseline(args, client, &mut acked).await?;
+        failures(args, faults, client, &mut acked).await?;
+        slow_queries(args, faults, client, &mut acked).await?;
+        pool_exhaustion(args, faults, client).await?;
+        check_consistency(client, &acked).await?;
+        println!("  store is consistent ({} acknowledged version(s))", acked.len());
+        round += 1;
+    }
+    Ok(())
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    // Parse the arguments
+    let args = Arguments::parse();
+
+    // Setup the logger
+    tracing_subscriber::fmt()
+        .with_max_level(if args.trace {
+            Level::TRACE
+        } else if args.debug {
+            Level::DEBUG
+        } else {
+            Level::WARN
+        })
+        .init();
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+
+    // Setup the database, with a way to break it
+    let db: SQLiteDatabase<bool> = match SQLiteDatabase::with_migrations_from_dir_async(
+        &args.database,
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("lib").join("databases").join("sqlite").join("migrations"),
+    )
+    .await
+    {
+        Ok(db) => db,
+        Err(err) => {
+            error!("{}", trace!(("Failed to create database connector"), err));
+            std::process::exit(1);
+        },
+    };
+    let db: FaultyDatabase<SQLiteDatabase<bool>> = FaultyDatabase::new(db);
+    let faults: Arc<Faults> = db.faults().clone();
+
+    // Serve it while running the workload against it
+    // NOTE: No retries, as we want to see every failure
+    let server = AxumServer::new(args.address, NoOpResolver::new(), db);
+    let client: Client = Client::new(format!("http://{}", args.address)).with_retry_policy(RetryPolicy::none());
+    tokio::select! {
+        res = server.serve() => {
+            if let Err(err) = res {
+                error!("{}", trace!(("Failed to serve the server"), err));
+            } else {
+                error!("Server stopped before the workload was done");
+            }
+            std::process::exit(1);
+        },
+        res = workload(&args, &faults, &client) => match res {
+            Ok(()) => println!("All rounds passed"),
+            Err(err) => {
+                error!("Chaos workload failed: {err}");
+                std::process::exit(1);
+            },
+        },
+    }
+}
@@ -0,0 +1,246 @@
+//  CLI.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:31:38
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Shows a small tool for operators to inspect, repair and import into a
+//!   store's SQLite database directly, e.g., when upgrading across
+//!   releases.
+//
+
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use error_trace::trace;
+use policy_store::databases::sqlite::SQLiteDatabase;
+use policy_store::databases::sqlite::legacy::{Imported, LegacyDatabase, import};
+use policy_store::spec::DatabaseConnector as _;
+use policy_store::spec::consistency::Finding;
+use policy_store::spec::metadata::User;
+use serde_json::Value;
+use tracing::{Level, error, info};
+
+
+/***** ARGUMENTS *****/
+/// Defines the arguments for this binary.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Whether to enable INFO- and DEBUG-level logging.
+    #[clap(long, global = true)]
+    debug: bool,
+    /// Whether to enable TRACE-level logging. Implies '--debug'.
+    #[clap(long, global = true)]
+    trace: bool,
+
+    /// The thing to do.
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Defines the subcommands of this binary.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Checks the consistency of a database, and optionally repairs what can be repaired safely.
+    Doctor {
+        /// The path to the database file to check.
+        #[clap(short, long, default_value = "./policies.db")]
+        database: PathBuf,
+        /// The folder with the migrations to bring the database up-to-date with before checking
+        /// it. Defaults to the migrations shipped with this release.
+        #[clap(short, long)]
+        migrations: Option<PathBuf>,
+        /// If given, repairs orphaned activations and missing content hashes.
+        #[clap(long)]
+        fix: bool,
+        /// If given, prints the report as JSON instead of as text.
+        #[clap(long)]
+        json: bool,
+    },
+    /// Imports the checker database of the legacy (v1) policy reasoner into a database.
+    Import {
+        /// The path to the legacy database file to import.
+        legacy: PathBuf,
+        /// The path to the database file to import into. Created if it does not exist.
+        #[clap(short, long, default_value = "./policies.db")]
+        database: PathBuf,
+        /// The folder with the migrations to bring the database up-to-date with before importing.
+        /// Defaults to the migrations shipped with this release.
+        #[clap(short, long)]
+        migrations: Option<PathBuf>,
+        /// The name to record the legacy database under in the origins of the imported versions.
+        #[clap(long, default_value = "policy-reasoner-v1")]
+        store: String,
+        /// The policy language to record the imported versions as written in.
+        #[clap(long, default_value = "eflint-json")]
+        language: String,
+        /// The identifier of the user to import as. Becomes the creator of the imported versions.
+        #[clap(long, default_value = "policy-store-cli")]
+        user: String,
+    },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Opens a database, bringing it up-to-date with the migrations in the given folder.
+///
+/// Exits the process if it fails to.
+///
+/// # Arguments
+/// - `database`: The path to the database file.
+/// - `migrations`: The folder with the migrations, if not the ones shipped with this release.
+///
+/// # Returns
+/// A connector to the database, reading policies as plain JSON (as we don't know what reasoner
+/// they're for).
+async fn open(database: &Path, migrations: Option<PathBuf>) -> SQLiteDatabase<Value> {
+    let migrations: PathBuf =
+        migrations.unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("lib").join("databases").join("sqlite").join("migrations"));
+    match SQLiteDatabase::with_migrations_from_dir_async(database, &migrations).await {
+        Ok(db) => db,
+        Err(err) => {
+            error!("{}", trace!(("Failed to open database {:?}", database.display()), err));
+            std::process::exit(1);
+        },
+    }
+}
+
+/// Prints a list of findings.
+///
+/// # Arguments
+/// - `title`: Something to say before the findings (if there are any).
+/// - `findings`: The [`Finding`]s to print.
+fn print_findings(title: &str, findings: &[Finding]) {
+    if findings.is_empty() {
+        return;
+    }
+    println!("{title}:");
+    for finding in findings {
+        println!(" - {finding}");
+    }
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    // Parse the arguments
+    let args = Arguments::parse();
+
+    // Setup the logger
+    tracing_subscriber::fmt()
+        .with_max_level(if args.trace {
+            Level::TRACE
+        } else if args.debug {
+            Level::DEBUG
+        } else {
+            Level::WARN
+        })
+        .init();
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+
+    match args.command {
+        Command::Doctor { database, migrations, fix, json } => {
+            // NOTE: Opening a database creates it if it doesn't exist, which isn't what operators want here
+            if !database.exists() {
+                error!("Database {:?} does not exist", database.display());
+                std::process::exit(1);
+            }
+            let db: SQLiteDatabase<Value> = open(&database, migrations).await;
+
+            // Check it
+            let findings: Vec<Finding> = match db.verify().await {
+                Ok(findings) => findings,
+                Err(err) => {
+                    error!("{}", trace!(("Failed to check database {:?}", database.display()), err));
+                    std::process::exit(1);
+                },
+            };
+
+            // Repair what we can, and check again to see what's left
+            let (repaired, remaining): (Vec<Finding>, Vec<Finding>) = if fix && !findings.is_empty() {
+                let repaired: Vec<Finding> = match db.repair(&findings).await {
+                    Ok(repaired) => repaired,
+                    Err(err) => {
+                        error!("{}", trace!(("Failed to repair database {:?}", database.display()), err));
+                        std::process::exit(1);
+                    },
+                };
+                match db.verify().await {
+                    Ok(remaining) => (repaired, remaining),
+                    Err(err) => {
+                        error!("{}", trace!(("Failed to re-check database {:?}", database.display()), err));
+                        std::process::exit(1);
+                    },
+                }
+            } else {
+                (Vec::new(), findings)
+            };
+
+            // Report
+            if json {
+                println!("{}", serde_json::json!({ "database": database, "repaired": repaired, "remaining": remaining }));
+            } else {
+                print_findings("Repaired", &repaired);
+                print_findings("Found", &remaining);
+                if remaining.is_empty() {
+                    println!("Database {:?} is consistent", database.display());
+                } else if !fix {
+                    println!("Run again with '--fix' to repair orphaned activations and missing hashes; anything else must be repaired by hand");
+                }
+            }
+            if !remaining.is_empty() {
+                std::process::exit(2);
+            }
+        },
+
+        Command::Import { legacy, database, migrations, store, language, user } => {
+            // Read everything first, so we don't touch the database if that fails
+            let legacy_db: LegacyDatabase = match LegacyDatabase::read(&legacy) {
+                Ok(legacy_db) => legacy_db,
+                Err(err) => {
+                    error!("{}", trace!(("Failed to read legacy database {:?}", legacy.display()), err));
+                    std::process::exit(1);
+                },
+            };
+            let db: SQLiteDatabase<Value> = open(&database, migrations).await;
+
+            // Import it
+            let user = User { id: user.clone(), name: user, issuer: None, roles: Vec::new() };
+            let mut conn = match db.connect(&user).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    error!("{}", trace!(("Failed to connect to database {:?}", database.display()), err));
+                    std::process::exit(1);
+                },
+            };
+            let imported: Imported = match import(&mut conn, &store, &language, legacy_db).await {
+                Ok(imported) => imported,
+                Err(err) => {
+                    error!("{}", trace!(("Failed to import legacy database {:?} into {:?}", legacy.display(), database.display()), err));
+                    std::process::exit(1);
+                },
+            };
+
+            // Report
+            for (legacy, version) in &imported.versions {
+                println!("Imported legacy version {legacy} as version {version}");
+            }
+            match imported.active {
+                Some(version) => println!("Activated version {version}"),
+                None => println!("No version was active in the legacy database"),
+            }
+        },
+    }
+}
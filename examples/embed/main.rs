@@ -0,0 +1,130 @@
+//  EMBED.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:34:38
+//  Last edited:
+//    17 Oct 2026, 02:34:31
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Shows how to embed the policy store in a larger `axum` application.
+//
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use clap::Parser;
+use error_trace::trace;
+use policy_store::auth::no_op::NoOpResolver;
+use policy_store::databases::sqlite::SQLiteDatabase;
+use policy_store::servers::axum::AxumServer;
+use policy_store::servers::axum::axum::Router;
+use policy_store::servers::axum::axum::extract::State;
+use policy_store::servers::axum::axum::routing::get;
+use tokio::net::TcpListener;
+use tracing::{Level, error, info};
+
+
+/***** ARGUMENTS *****/
+/// Defines the arguments for this binary.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Whether to enable INFO- and DEBUG-level logging.
+    #[clap(long)]
+    debug: bool,
+    /// Whether to enable TRACE-level logging. Implies '--debug'.
+    #[clap(long)]
+    trace: bool,
+
+    /// The address/port on which to bind the application.
+    #[clap(short, long, default_value = "127.0.0.1:8080")]
+    address:  SocketAddr,
+    /// The path to the database file to create/use.
+    #[clap(short, long, default_value = "./policies.db")]
+    database: PathBuf,
+    /// The prefix under which to serve the policy store.
+    #[clap(short, long, default_value = "/store")]
+    prefix:   String,
+}
+
+
+
+
+
+/***** STATE *****/
+/// The state of the larger application.
+#[derive(Clone, Debug)]
+struct AppState {
+    /// When the application was started.
+    started: Instant,
+}
+
+
+
+
+
+/***** HANDLERS *****/
+/// Reports how long the application has been running.
+async fn health(State(state): State<AppState>) -> String { format!("up for {}s\n", state.started.elapsed().as_secs()) }
+
+
+
+
+
+/***** ENTRYPOINT *****/
+#[tokio::main]
+async fn main() {
+    // Parse the arguments
+    let args = Arguments::parse();
+
+    // Setup the logger
+    tracing_subscriber::fmt()
+        .with_max_level(if args.trace {
+            Level::TRACE
+        } else if args.debug {
+            Level::DEBUG
+        } else {
+            Level::WARN
+        })
+        .init();
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+
+    // Setup the policy store
+    let db: SQLiteDatabase<bool> = match SQLiteDatabase::with_migrations_from_dir_async(
+        &args.database,
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("lib").join("databases").join("sqlite").join("migrations"),
+    )
+    .await
+    {
+        Ok(db) => db,
+        Err(err) => {
+            error!("{}", trace!(("Failed to create database connector"), err));
+            std::process::exit(1);
+        },
+    };
+    let server = AxumServer::new(args.address, NoOpResolver::new(), db);
+    // NOTE: The router can't refuse to start, so check the backend before building it
+    if let Err(err) = server.verify_backend().await {
+        error!("{}", trace!(("Refusing to serve the policy store"), err));
+        std::process::exit(1);
+    }
+    let store: Router<AppState> = server.into_router();
+
+    // Embed it in the larger application
+    let app: Router = Router::new().route("/health", get(health)).nest(&args.prefix, store).with_state(AppState { started: Instant::now() });
+    let listener: TcpListener = match TcpListener::bind(args.address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!("{}", trace!(("Failed to bind application on address '{}'", args.address), err));
+            std::process::exit(1);
+        },
+    };
+    info!("Serving application on '{}' (policy store under '{}')", args.address, args.prefix);
+    if let Err(err) = policy_store::servers::axum::axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await {
+        error!("{}", trace!(("Failed to serve the application"), err));
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,159 @@
+//  FILE-SYNC.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:12:22
+//  Last edited:
+//    17 Oct 2026, 02:36:26
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Shows a sidecar that keeps the active policy of a store in sync with
+//!   a policy document in a file, such that the active policy can be
+//!   declared (e.g., by GitOps).
+//!
+//!   Note that it only reads the file; it does not watch the Kubernetes
+//!   API. See `sidecar.yaml` for an example deployment that mounts the file
+//!   from a ConfigMap.
+//
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+use error_trace::trace;
+use policy_store::clients::reqwest::{Client, PolicyDocument, Reconciled};
+use serde_json::Value;
+use tracing::{Level, debug, error, info, warn};
+
+
+/***** ARGUMENTS *****/
+/// Defines the arguments for this binary.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Whether to enable INFO- and DEBUG-level logging.
+    #[clap(long)]
+    debug: bool,
+    /// Whether to enable TRACE-level logging. Implies '--debug'.
+    #[clap(long)]
+    trace: bool,
+
+    /// The base URL of the store to keep in sync.
+    #[clap(short, long, default_value = "http://127.0.0.1:8080")]
+    store: String,
+    /// A file with the bearer token to authenticate with, if any. It is read again before every
+    /// sync, such that rotated (e.g., service account) tokens are picked up.
+    #[clap(long)]
+    token_file: Option<PathBuf>,
+    /// The policy document to sync (e.g., as mounted from a ConfigMap). It has the same shape as the
+    /// body of a request to add a version, i.e., an object with 'metadata' and 'contents'.
+    #[clap(short, long, default_value = "/etc/policy-store/policy.json")]
+    document: PathBuf,
+    /// How many seconds to wait between syncs.
+    #[clap(short, long, default_value = "30")]
+    interval: u64,
+    /// If given, syncs only once and then exits (e.g., to run as a Job).
+    #[clap(long)]
+    once: bool,
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Syncs the document to the store once, if needed.
+///
+/// # Arguments
+/// - `args`: The [`Arguments`] to sync with.
+/// - `client`: The [`Client`] to sync to, without authentication.
+/// - `last`: The raw document and version that were synced last, if any. Updated on success.
+///
+/// # Errors
+/// This function errors with a message to show if anything failed.
+async fn sync(args: &Arguments, client: &Client, last: &mut Option<(Vec<u8>, u64)>) -> Result<(), String> {
+    // Read the latest of everything
+    let client: Client = match &args.token_file {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(token) => client.clone().with_token(token.trim()),
+            Err(err) => return Err(trace!(("Failed to read token file {:?}", path.display()), err).to_string()),
+        },
+        None => client.clone(),
+    };
+    let raw: Vec<u8> = match std::fs::read(&args.document) {
+        Ok(raw) => raw,
+        Err(err) => return Err(trace!(("Failed to read policy document {:?}", args.document.display()), err).to_string()),
+    };
+
+    // Skip if neither the document nor the active policy changed since last time
+    if let Some((last_raw, last_version)) = last {
+        if *last_raw == raw {
+            match client.get_active_version().await {
+                Ok(Some(active)) if active == *last_version => {
+                    debug!("Policy document unchanged and still active as version {active}");
+                    return Ok(());
+                },
+                Ok(active) => warn!("Active policy drifted to {active:?} (expected {last_version}); reconciling"),
+                Err(err) => return Err(trace!(("Failed to get active policy of {:?}", args.store), err).to_string()),
+            }
+        }
+    }
+
+    // Otherwise, reconcile
+    let document: PolicyDocument<Value> = match serde_json::from_slice(&raw) {
+        Ok(document) => document,
+        Err(err) => return Err(trace!(("Failed to parse policy document {:?}", args.document.display()), err).to_string()),
+    };
+    match client.reconcile(&document).await {
+        Ok(res) => {
+            match res {
+                Reconciled::UpToDate { version } => debug!("Policy {:?} is active as version {version}", document.metadata.name),
+                Reconciled::Activated { version } => info!("Re-activated policy {:?} as version {version}", document.metadata.name),
+                Reconciled::Added { version } => info!("Added and activated policy {:?} as version {version}", document.metadata.name),
+            }
+            *last = Some((raw, res.version()));
+            Ok(())
+        },
+        Err(err) => Err(trace!(("Failed to reconcile policy {:?} into {:?}", document.metadata.name, args.store), err).to_string()),
+    }
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    // Parse the arguments
+    let args = Arguments::parse();
+
+    // Setup the logger
+    tracing_subscriber::fmt()
+        .with_max_level(if args.trace {
+            Level::TRACE
+        } else if args.debug {
+            Level::DEBUG
+        } else {
+            Level::INFO
+        })
+        .init();
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+
+    // Keep syncing
+    let client = Client::new(&args.store);
+    let mut last: Option<(Vec<u8>, u64)> = None;
+    loop {
+        if let Err(err) = sync(&args, &client, &mut last).await {
+            error!("{err}");
+            if args.once {
+                std::process::exit(1);
+            }
+        }
+        if args.once {
+            return;
+        }
+        tokio::time::sleep(Duration::from_secs(args.interval)).await;
+    }
+}
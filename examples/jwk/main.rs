@@ -4,7 +4,7 @@
 //  Created:
 //    11 Nov 2024, 12:20:52
 //  Last edited:
-//    11 Nov 2024, 12:28:53
+//    16 Oct 2026, 19:30:16
 //  Auto updated?
 //    Yes
 //
@@ -14,13 +14,18 @@
 
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use chrono::NaiveTime;
 use clap::Parser;
 use error_trace::trace;
 use jwk_auth::keyresolver::KidResolver;
 use policy_store::auth::jwk::JwkResolver;
 use policy_store::databases::sqlite::SQLiteDatabase;
-use policy_store::servers::axum::AxumServer;
+#[cfg(feature = "axum-server-dev-auth")]
+use policy_store::servers::axum::DevTokenMinter;
+use policy_store::servers::axum::spec::{ACTIVATE_PATH, DEACTIVATE_PATH};
+use policy_store::servers::axum::{AxumServer, IpCidr, SyslogForwarder};
 use policy_store::spec::Server as _;
 use tokio::signal::unix::{SignalKind, signal};
 use tracing::{Level, debug, error, info, warn};
@@ -39,13 +44,58 @@ struct Arguments {
 
     /// The address/port on which to bind the server.
     #[clap(short, long, default_value = "127.0.0.1:8080")]
-    address:  SocketAddr,
+    address: SocketAddr,
     /// The path to the database file to create/use.
     #[clap(short, long, default_value = "./policies.db")]
     database: PathBuf,
     /// The path to the key file to use.
     #[clap(short, long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/jwk/key.json"))]
-    key:      PathBuf,
+    key: PathBuf,
+    /// The JWT claim to read the user ID from. May be given multiple times, in which case the
+    /// first claim present in a token is used.
+    #[clap(long = "initiator-claim", default_value = "username")]
+    initiator_claims: Vec<String>,
+    /// The JWT claim to read the user's name from. May be given multiple times, in which case the
+    /// first claim present in a token is used.
+    #[clap(long = "name-claim", default_value = "name")]
+    name_claims: Vec<String>,
+    /// The (nested, e.g., 'realm_access.roles') JWT claim to read the user's roles from. May be
+    /// given multiple times.
+    #[clap(long = "role-claim")]
+    role_claims: Vec<String>,
+    /// If given, only users with this role may (de)activate policies. May be given multiple
+    /// times to allow any of the roles.
+    #[clap(long = "activate-role")]
+    activate_roles: Vec<String>,
+    /// The roles that allow users to call the administrative endpoints (e.g., to pseudonymize
+    /// users). May be given multiple times to allow any of the roles. Defaults to `admin`.
+    #[clap(long = "admin-role")]
+    admin_roles: Vec<String>,
+    /// If given, forwards the audit log to the syslog collector at this address (over UDP).
+    #[clap(long)]
+    syslog: Option<SocketAddr>,
+    /// The address or range (e.g., '10.0.0.0/8') of proxies whose 'Forwarded' and 'X-Forwarded-For'
+    /// headers are trusted. May be given multiple times.
+    #[clap(long = "trusted-proxy")]
+    trusted_proxies: Vec<IpCidr>,
+    /// If given, runs database maintenance every day at this time (UTC, e.g., '03:00:00').
+    #[clap(long)]
+    maintenance_at: Option<NaiveTime>,
+    /// If given, logs queries that take at least this many milliseconds as slow.
+    #[clap(long)]
+    slow_query_ms: Option<u64>,
+    /// If given, adds security headers (HSTS, nosniff and a restrictive CSP) to every response.
+    #[clap(long)]
+    security_headers: bool,
+    /// If given, logs the raw body of requests that fail to parse (requires '--trace').
+    #[clap(long)]
+    log_bodies: bool,
+    /// If given, serves 'POST /v2/dev/token' to mint tokens for any user, signed with the
+    /// example key (ID 'A'). Pass '--role-claim roles' to read their roles. NEVER use this in
+    /// production.
+    #[cfg(feature = "axum-server-dev-auth")]
+    #[clap(long)]
+    dev_tokens: bool,
 }
 
 
@@ -78,7 +128,15 @@ async fn main() {
             std::process::exit(1);
         },
     };
-    let auth = JwkResolver::new("username", resv);
+    #[cfg(feature = "axum-server-dev-auth")]
+    let dev_tokens: Option<DevTokenMinter> = args.dev_tokens.then(|| {
+        // NOTE: The kid resolver pads secrets to 32 bytes, so this matches the example key
+        DevTokenMinter::new(Some("A".into()), &[0; 32]).with_initiator_claim(args.initiator_claims.first().map(String::as_str).unwrap_or("username"))
+    });
+    let auth = JwkResolver::new("username", resv)
+        .with_initiator_claims(args.initiator_claims)
+        .with_name_claims(args.name_claims)
+        .with_role_claims(args.role_claims);
 
     // Setup the database
     let db: SQLiteDatabase<bool> = match SQLiteDatabase::with_migrations_from_dir_async(
@@ -93,9 +151,35 @@ async fn main() {
             std::process::exit(1);
         },
     };
+    let db: SQLiteDatabase<bool> = match args.slow_query_ms {
+        Some(ms) => db.with_slow_query_threshold(Duration::from_millis(ms)),
+        None => db,
+    };
 
     // OK, setup the server
-    let server = AxumServer::new(args.address, auth, db);
+    let mut server = AxumServer::new(args.address, auth, db).with_trusted_proxies(args.trusted_proxies);
+    if let Some(syslog) = args.syslog {
+        server = server.with_syslog(SyslogForwarder::new(syslog));
+    }
+    if let Some(at) = args.maintenance_at {
+        server = server.with_maintenance(at);
+    }
+    if args.security_headers {
+        server = server.with_security_headers();
+    }
+    if args.log_bodies {
+        server = server.with_raw_body_logging();
+    }
+    #[cfg(feature = "axum-server-dev-auth")]
+    if let Some(minter) = dev_tokens {
+        server = server.with_dev_tokens(minter);
+    }
+    if !args.activate_roles.is_empty() {
+        server = server.with_required_roles(&ACTIVATE_PATH, &args.activate_roles).with_required_roles(&DEACTIVATE_PATH, &args.activate_roles);
+    }
+    if !args.admin_roles.is_empty() {
+        server = server.with_admin_roles(&args.admin_roles);
+    }
     tokio::select! {
         res = server.serve() => match res {
             Ok(_) => info!("Done"),
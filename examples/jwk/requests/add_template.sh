@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/templates -X POST -H "Authorization: Bearer $(cat "$(dirname $0)/../token.txt")" -H 'Content-Type: application/json' -d '{ "template": { "name": "allow", "description": "Allows or denies everything", "language": "boolean-v1", "parameters": [{ "name": "allow", "description": "Whether to allow" }], "skeleton": "{{allow}}" } }'
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/dev/token -X POST -H 'Content-Type: application/json' -d '{"user":"amy","name":"Amy","roles":["admin"]}'
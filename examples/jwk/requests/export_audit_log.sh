@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/audit/export?format=ndjson -X GET -H "Authorization: Bearer $(cat "$(dirname $0)/../token.txt")"
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/policies/1/freeze -X POST -H "Authorization: Bearer $(cat "$(dirname $0)/../token.txt")" -H 'Content-Type: application/json' -d '{"reason":"suspected to be malicious"}'
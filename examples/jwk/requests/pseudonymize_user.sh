@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/users/johnsmith/pseudonymize -X POST -H "Authorization: Bearer $(cat "$(dirname $0)/../token.txt")"
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/keys/rotated -X DELETE -H "Authorization: Bearer $(cat "$(dirname $0)/../token.txt")"
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/revocations -X POST -H "Authorization: Bearer $(cat "$(dirname $0)/../token.txt")" -H 'Content-Type: application/json' -d '{"revocation":{"kind":"user","user":"amy","before":"2026-10-16T12:00:00Z"}}'
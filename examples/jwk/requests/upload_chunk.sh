@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/uploads/1 -X PATCH -H "Authorization: Bearer $(cat "$(dirname $0)/../token.txt")" -H 'Upload-Offset: 0' --data-binary 'true'
@@ -0,0 +1,329 @@
+//  LOADTEST.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:58:13
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Drives a mixed workload of uploads, activations and reads against a
+//!   store and reports the latency percentiles of each, e.g., to size the
+//!   backend of a deployment.
+//
+
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use error_trace::trace;
+use policy_store::clients::reqwest::{Client, RetryPolicy};
+use policy_store::spec::metadata::AttachedMetadata;
+use serde_json::{Value, json};
+use tokio::task::JoinSet;
+use tracing::{Level, debug, error, info, warn};
+
+
+/***** ARGUMENTS *****/
+/// Defines the arguments for this binary.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Whether to enable INFO- and DEBUG-level logging.
+    #[clap(long)]
+    debug: bool,
+    /// Whether to enable TRACE-level logging. Implies '--debug'.
+    #[clap(long)]
+    trace: bool,
+
+    /// The base URL of the store to load. Note that the workload adds and activates versions, so
+    /// never point this at a store in production.
+    #[clap(short, long, default_value = "http://127.0.0.1:8080")]
+    target: String,
+    /// The bearer token to authenticate with, if any.
+    #[clap(long)]
+    token: Option<String>,
+    /// For how many seconds to run the workload.
+    #[clap(short, long, default_value = "30")]
+    duration: u64,
+    /// The number of clients sending requests at the same time.
+    #[clap(short, long, default_value = "16")]
+    concurrency: usize,
+    /// The relative weight of reading the active policy in the workload.
+    #[clap(long, default_value = "90")]
+    reads: u32,
+    /// The relative weight of uploading a new version in the workload.
+    #[clap(long, default_value = "8")]
+    uploads: u32,
+    /// The relative weight of activating a version in the workload.
+    #[clap(long, default_value = "2")]
+    activations: u32,
+    /// The branch to upload versions to.
+    #[clap(long, default_value = "loadtest")]
+    branch: String,
+}
+
+
+
+
+
+/***** WORKLOAD *****/
+/// The operations in the workload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Operation {
+    /// Reads the active policy, like a reasoner would.
+    Read,
+    /// Uploads a new version.
+    Upload,
+    /// Activates the version last uploaded by the same client.
+    Activate,
+}
+impl Operation {
+    /// All operations, in the order they are reported.
+    const ALL: [Self; 3] = [Self::Read, Self::Upload, Self::Activate];
+
+    /// Returns a name for the operation to report it under.
+    ///
+    /// # Returns
+    /// A static string naming it.
+    #[inline]
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Upload => "upload",
+            Self::Activate => "activate",
+        }
+    }
+
+    /// Returns the index of the operation in [`Operation::ALL`].
+    ///
+    /// # Returns
+    /// An index to keep per-operation statistics at.
+    #[inline]
+    fn index(&self) -> usize {
+        match self {
+            Self::Read => 0,
+            Self::Upload => 1,
+            Self::Activate => 2,
+        }
+    }
+}
+
+/// The latencies and failures of one operation.
+#[derive(Clone, Debug, Default)]
+struct Stats {
+    /// How long every successful request took.
+    latencies: Vec<Duration>,
+    /// The number of requests that failed.
+    failures:  usize,
+}
+impl Stats {
+    /// Returns the latency below which the given fraction of the successful requests finished.
+    ///
+    /// # Arguments
+    /// - `p`: The percentile, between 0 and 100.
+    ///
+    /// # Returns
+    /// The latency at that percentile, or [`Duration::ZERO`] if nothing succeeded. Assumes the
+    /// latencies are sorted.
+    fn percentile(&self, p: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let i: usize = ((p / 100.0) * (self.latencies.len() - 1) as f64).round() as usize;
+        self.latencies[i.min(self.latencies.len() - 1)]
+    }
+}
+
+/// Picks operations at random according to their weights.
+///
+/// Uses a simple generator seeded per client, so every run sends the same sequence.
+#[derive(Debug)]
+struct Picker {
+    /// The cumulative weights of the operations in [`Operation::ALL`].
+    bounds: [u32; 3],
+    /// The state of the generator.
+    state:  u64,
+}
+impl Picker {
+    /// Constructor for the Picker.
+    ///
+    /// # Arguments
+    /// - `args`: The [`Arguments`] with the weights of each operation. Their sum must fit in a
+    ///   [`u32`] and be non-zero.
+    /// - `seed`: Something to distinguish clients by.
+    ///
+    /// # Returns
+    /// A new Picker.
+    #[inline]
+    fn new(args: &Arguments, seed: u64) -> Self {
+        // NOTE: `main()` already rejected weights that overflow, so saturating never kicks in
+        let bounds: [u32; 3] =
+            [args.reads, args.reads.saturating_add(args.uploads), args.reads.saturating_add(args.uploads).saturating_add(args.activations)];
+        Self { bounds, state: 0x2545_F491_4F6C_DD1D ^ (seed + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15) }
+    }
+
+    /// Picks the next operation (with xorshift64*).
+    ///
+    /// # Returns
+    /// The [`Operation`] to run next.
+    fn pick(&mut self) -> Operation {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        let roll: u32 = (self.state.wrapping_mul(0x2545_F491_4F6C_DD1D) % self.bounds[2] as u64) as u32;
+        Operation::ALL[self.bounds.iter().position(|bound| roll < *bound).unwrap_or(0)]
+    }
+}
+
+/// Runs the workload of a single client until the deadline.
+///
+/// # Arguments
+/// - `id`: The index of the client.
+/// - `client`: The [`Client`] to send requests with.
+/// - `picker`: The [`Picker`] deciding what to send.
+/// - `branch`: The branch to upload versions to.
+/// - `deadline`: When to stop sending requests.
+///
+/// # Returns
+/// The [`Stats`] of every operation, indexed like [`Operation::ALL`].
+async fn run_client(id: usize, client: Client, mut picker: Picker, branch: String, deadline: Instant) -> [Stats; 3] {
+    let mut stats: [Stats; 3] = Default::default();
+    let mut last: Option<u64> = None;
+    let mut n: u64 = 0;
+    while Instant::now() < deadline {
+        // Can't activate anything before having uploaded something
+        let mut op: Operation = picker.pick();
+        if op == Operation::Activate && last.is_none() {
+            op = Operation::Upload;
+        }
+
+        // Send it
+        let start: Instant = Instant::now();
+        let res: Result<(), String> = match op {
+            Operation::Read => {
+                client.get_active_content::<Value>().await.map(|_| ()).map_err(|err| trace!(("Failed to read active policy"), err).to_string())
+            },
+            Operation::Upload => {
+                let metadata = AttachedMetadata {
+                    name: format!("loadtest-{id}-{n}"),
+                    description: "Uploaded by the load-test example".into(),
+                    language: "json".into(),
+                    branch: branch.clone(),
+                    schema: None,
+                    confidential: false,
+                };
+                n += 1;
+                match client.add_version(metadata, json!({ "client": id, "n": n })).await {
+                    Ok(version) => {
+                        last = Some(version);
+                        Ok(())
+                    },
+                    Err(err) => Err(trace!(("Failed to upload version"), err).to_string()),
+                }
+            },
+            Operation::Activate => {
+                // SAFETY: Checked above
+                let version: u64 = last.unwrap();
+                client.activate(version).await.map_err(|err| trace!(("Failed to activate version {version}"), err).to_string())
+            },
+        };
+        let took: Duration = start.elapsed();
+
+        // Remember how it went
+        match res {
+            Ok(()) => stats[op.index()].latencies.push(took),
+            Err(err) => {
+                debug!("Client {id}: {err}");
+                stats[op.index()].failures += 1;
+            },
+        }
+    }
+    stats
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    // Parse the arguments
+    let args = Arguments::parse();
+
+    // Setup the logger
+    tracing_subscriber::fmt()
+        .with_max_level(if args.trace {
+            Level::TRACE
+        } else if args.debug {
+            Level::DEBUG
+        } else {
+            Level::WARN
+        })
+        .init();
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+    match args.reads.checked_add(args.uploads).and_then(|weight| weight.checked_add(args.activations)) {
+        Some(0) => {
+            error!("At least one of '--reads', '--uploads' or '--activations' must have a non-zero weight");
+            std::process::exit(1);
+        },
+        Some(_) => {},
+        None => {
+            error!("The weights of '--reads', '--uploads' and '--activations' must add up to at most {}", u32::MAX);
+            std::process::exit(1);
+        },
+    }
+
+    // Setup the client
+    // NOTE: No retries, as we want to measure every request on its own
+    let mut client = Client::new(&args.target).with_retry_policy(RetryPolicy::none());
+    if let Some(token) = &args.token {
+        client = client.with_token(token);
+    }
+    if let Err(err) = client.get_active_version().await {
+        error!("{}", trace!(("Failed to reach store at {:?}", args.target), err));
+        std::process::exit(1);
+    }
+
+    // Run all clients at once
+    println!("Loading {:?} with {} client(s) for {}s...", args.target, args.concurrency, args.duration);
+    let start: Instant = Instant::now();
+    let deadline: Instant = start + Duration::from_secs(args.duration);
+    let mut clients: JoinSet<[Stats; 3]> = JoinSet::new();
+    for id in 0..args.concurrency {
+        clients.spawn(run_client(id, client.clone(), Picker::new(&args, id as u64), args.branch.clone(), deadline));
+    }
+    let mut stats: [Stats; 3] = Default::default();
+    while let Some(res) = clients.join_next().await {
+        match res {
+            Ok(res) => {
+                for (total, res) in stats.iter_mut().zip(res) {
+                    total.latencies.extend(res.latencies);
+                    total.failures += res.failures;
+                }
+            },
+            Err(err) => warn!("{}", trace!(("Client task panicked; its requests are not reported"), err)),
+        }
+    }
+    let elapsed: f64 = start.elapsed().as_secs_f64();
+
+    // Report
+    println!();
+    println!("{:<10} {:>8} {:>8} {:>9} {:>9} {:>9} {:>9} {:>9}", "OPERATION", "OK", "FAILED", "REQ/S", "P50", "P90", "P99", "MAX");
+    for op in Operation::ALL {
+        let stats: &mut Stats = &mut stats[op.index()];
+        stats.latencies.sort();
+        println!(
+            "{:<10} {:>8} {:>8} {:>9.1} {:>7.1}ms {:>7.1}ms {:>7.1}ms {:>7.1}ms",
+            op.name(),
+            stats.latencies.len(),
+            stats.failures,
+            stats.latencies.len() as f64 / elapsed,
+            stats.percentile(50.0).as_secs_f64() * 1000.0,
+            stats.percentile(90.0).as_secs_f64() * 1000.0,
+            stats.percentile(99.0).as_secs_f64() * 1000.0,
+            stats.percentile(100.0).as_secs_f64() * 1000.0,
+        );
+    }
+}
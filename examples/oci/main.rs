@@ -0,0 +1,152 @@
+//  OCI.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:36:04
+//  Last edited:
+//    16 Oct 2026, 17:36:04
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Shows how to push policy versions to an OCI registry and pull them
+//!   back into a store, e.g., to distribute and sign them like images.
+//
+
+use clap::{Parser, Subcommand};
+use error_trace::trace;
+use policy_store::clients::reqwest::Client;
+use policy_store::clients::reqwest::oci::{OciClient, OciReference};
+use tracing::{Level, error, info};
+
+
+/***** ARGUMENTS *****/
+/// Defines the arguments for this binary.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Whether to enable INFO- and DEBUG-level logging.
+    #[clap(long, global = true)]
+    debug: bool,
+    /// Whether to enable TRACE-level logging. Implies '--debug'.
+    #[clap(long, global = true)]
+    trace: bool,
+
+    /// The base URL of the store.
+    #[clap(long, global = true, default_value = "http://127.0.0.1:8080")]
+    store:    String,
+    /// The bearer token to authenticate with at the store, if any.
+    #[clap(long, global = true)]
+    token:    Option<String>,
+    /// The username to authenticate with at the registry, if any.
+    #[clap(long, global = true)]
+    username: Option<String>,
+    /// The password to authenticate with at the registry. Only used if '--username' is given.
+    #[clap(long, global = true, default_value = "")]
+    password: String,
+    /// If given, talks plain HTTP to the registry.
+    #[clap(long, global = true)]
+    insecure: bool,
+
+    /// The thing to do.
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Defines the subcommands of this binary.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Pushes a policy version from the store to a registry.
+    Push {
+        /// The version to push.
+        version:   u64,
+        /// Where to push it, e.g., 'registry.example.com/policies/prod:v3'.
+        reference: String,
+    },
+    /// Pulls a policy version from a registry into the store.
+    Pull {
+        /// What to pull, e.g., 'registry.example.com/policies/prod:v3'.
+        reference: String,
+        /// If given, also activates the pulled version.
+        #[clap(long)]
+        activate:  bool,
+    },
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    // Parse the arguments
+    let args = Arguments::parse();
+
+    // Setup the logger
+    tracing_subscriber::fmt()
+        .with_max_level(if args.trace {
+            Level::TRACE
+        } else if args.debug {
+            Level::DEBUG
+        } else {
+            Level::WARN
+        })
+        .init();
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+
+    // Setup the clients
+    let mut store = Client::new(&args.store);
+    if let Some(token) = args.token {
+        store = store.with_token(token);
+    }
+    let mut registry = OciClient::new();
+    if let Some(username) = args.username {
+        registry = registry.with_credentials(username, args.password);
+    }
+    if args.insecure {
+        registry = registry.insecure();
+    }
+
+    match args.command {
+        Command::Push { version, reference } => {
+            let reference: OciReference = match OciReference::parse(&reference) {
+                Ok(reference) => reference,
+                Err(err) => {
+                    error!("{}", trace!(("Invalid reference"), err));
+                    std::process::exit(1);
+                },
+            };
+            match store.push_to_registry(version, &registry, &reference).await {
+                Ok(digest) => println!("Pushed policy version {version} to {reference} ({digest})"),
+                Err(err) => {
+                    error!("{}", trace!(("Failed to push policy version {version} to {reference}"), err));
+                    std::process::exit(1);
+                },
+            }
+        },
+
+        Command::Pull { reference, activate } => {
+            let reference: OciReference = match OciReference::parse(&reference) {
+                Ok(reference) => reference,
+                Err(err) => {
+                    error!("{}", trace!(("Invalid reference"), err));
+                    std::process::exit(1);
+                },
+            };
+            let version: u64 = match store.pull_from_registry(&registry, &reference).await {
+                Ok(version) => version,
+                Err(err) => {
+                    error!("{}", trace!(("Failed to pull policy version from {reference}"), err));
+                    std::process::exit(1);
+                },
+            };
+            if activate {
+                if let Err(err) = store.activate(version).await {
+                    error!("{}", trace!(("Failed to activate pulled policy version {version}"), err));
+                    std::process::exit(1);
+                }
+            }
+            println!("Pulled {reference} as policy version {version}");
+        },
+    }
+}
@@ -0,0 +1,105 @@
+//  PROMOTE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:11:02
+//  Last edited:
+//    17 Oct 2026, 01:52:39
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Shows how to promote a policy version from one store to another,
+//!   e.g., from staging to production.
+//
+
+use clap::Parser;
+use error_trace::trace;
+use policy_store::clients::reqwest::Client;
+use serde_json::Value;
+use tracing::{Level, error, info};
+
+
+/***** ARGUMENTS *****/
+/// Defines the arguments for this binary.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Whether to enable INFO- and DEBUG-level logging.
+    #[clap(long)]
+    debug: bool,
+    /// Whether to enable TRACE-level logging. Implies '--debug'.
+    #[clap(long)]
+    trace: bool,
+
+    /// The version to promote.
+    version: u64,
+    /// The base URL of the store to copy the version from.
+    #[clap(long)]
+    from: String,
+    /// The bearer token to authenticate with at the source store, if any.
+    #[clap(long)]
+    from_token: Option<String>,
+    /// The name to record the source store under in the copy's provenance. Defaults to '--from'.
+    #[clap(long)]
+    from_name: Option<String>,
+    /// The base URL of the store to copy the version to.
+    #[clap(long)]
+    to: String,
+    /// The bearer token to authenticate with at the target store, if any. Its user must be an
+    /// administrator there, and the copy is recorded as created by them.
+    #[clap(long)]
+    to_token: Option<String>,
+    /// If given, also activates the copy in the target store.
+    #[clap(long)]
+    activate: bool,
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    // Parse the arguments
+    let args = Arguments::parse();
+
+    // Setup the logger
+    tracing_subscriber::fmt()
+        .with_max_level(if args.trace {
+            Level::TRACE
+        } else if args.debug {
+            Level::DEBUG
+        } else {
+            Level::WARN
+        })
+        .init();
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+
+    // Setup the clients
+    let mut source = Client::new(&args.from);
+    if let Some(token) = args.from_token {
+        source = source.with_token(token);
+    }
+    let mut target = Client::new(&args.to);
+    if let Some(token) = args.to_token {
+        target = target.with_token(token);
+    }
+
+    // Copy the version over
+    let name: &str = args.from_name.as_deref().unwrap_or(&args.from);
+    let version: u64 = match source.promote_to::<Value>(args.version, name, &target).await {
+        Ok(version) => version,
+        Err(err) => {
+            error!("{}", trace!(("Failed to promote policy version {} from {:?} to {:?}", args.version, args.from, args.to), err));
+            std::process::exit(1);
+        },
+    };
+    if args.activate {
+        if let Err(err) = target.activate(version).await {
+            error!("{}", trace!(("Failed to activate promoted policy version {version} at {:?}", args.to), err));
+            std::process::exit(1);
+        }
+    }
+    println!("Promoted policy version {} to {:?} as version {version}", args.version, args.to);
+}
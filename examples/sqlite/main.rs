@@ -4,7 +4,7 @@
 //  Created:
 //    24 Oct 2024, 13:55:22
 //  Last edited:
-//    06 Nov 2024, 14:10:15
+//    16 Oct 2026, 18:42:39
 //  Auto updated?
 //    Yes
 //
@@ -14,12 +14,14 @@
 
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use chrono::NaiveTime;
 use clap::Parser;
 use error_trace::trace;
 use policy_store::auth::no_op::NoOpResolver;
 use policy_store::databases::sqlite::SQLiteDatabase;
-use policy_store::servers::axum::AxumServer;
+use policy_store::servers::axum::{AxumServer, BootstrapPolicy, IpCidr, NoActivePolicy, SyslogForwarder};
 use policy_store::spec::Server as _;
 use tokio::signal::unix::{SignalKind, signal};
 use tracing::{Level, debug, error, info, warn};
@@ -38,10 +40,47 @@ struct Arguments {
 
     /// The address/port on which to bind the server.
     #[clap(short, long, default_value = "127.0.0.1:8080")]
-    address:  SocketAddr,
+    address: SocketAddr,
     /// The path to the database file to create/use.
     #[clap(short, long, default_value = "./policies.db")]
     database: PathBuf,
+    /// If given, forwards the audit log to the syslog collector at this address (over UDP).
+    #[clap(long)]
+    syslog: Option<SocketAddr>,
+    /// The address or range (e.g., '10.0.0.0/8') of proxies whose 'Forwarded' and 'X-Forwarded-For'
+    /// headers are trusted. May be given multiple times.
+    #[clap(long = "trusted-proxy")]
+    trusted_proxies: Vec<IpCidr>,
+    /// If given, runs database maintenance every day at this time (UTC, e.g., '03:00:00').
+    #[clap(long)]
+    maintenance_at: Option<NaiveTime>,
+    /// If given, logs queries that take at least this many milliseconds as slow.
+    #[clap(long)]
+    slow_query_ms: Option<u64>,
+    /// The number of database connections to create (and check) before serving anything.
+    #[clap(long, default_value = "1")]
+    warm_up: usize,
+    /// If given, adds security headers (HSTS, nosniff and a restrictive CSP) to every response.
+    #[clap(long)]
+    security_headers: bool,
+    /// If given, logs the raw body of requests that fail to parse (requires '--trace').
+    #[clap(long)]
+    log_bodies: bool,
+    /// If given, checks the consistency of the database before serving anything.
+    #[clap(long)]
+    verify: bool,
+    /// If given, adds and activates the policy in this JSON file when starting against an empty
+    /// database. It has the same shape as the body of an add-version request.
+    #[clap(long)]
+    bootstrap: Option<PathBuf>,
+    /// If given, serves the content of this version as the active content while no policy is
+    /// active (instead of replying 404).
+    #[clap(long, conflicts_with = "deny_all_fallback")]
+    fallback_version: Option<u64>,
+    /// If given, serves the built-in deny-all policy as the active content while no policy is
+    /// active (instead of replying 404).
+    #[clap(long)]
+    deny_all_fallback: bool,
 }
 
 
@@ -82,9 +121,46 @@ async fn main() {
             std::process::exit(1);
         },
     };
+    let db: SQLiteDatabase<bool> = match args.slow_query_ms {
+        Some(ms) => db.with_slow_query_threshold(Duration::from_millis(ms)),
+        None => db,
+    };
+    if let Err(err) = db.warm_up(args.warm_up).await {
+        error!("{}", trace!(("Failed to warm up database connector"), err));
+        std::process::exit(1);
+    }
 
     // OK, setup the server
-    let server = AxumServer::new(args.address, auth, db);
+    let mut server = AxumServer::new(args.address, auth, db).with_trusted_proxies(args.trusted_proxies);
+    if let Some(syslog) = args.syslog {
+        server = server.with_syslog(SyslogForwarder::new(syslog));
+    }
+    if let Some(at) = args.maintenance_at {
+        server = server.with_maintenance(at);
+    }
+    if args.security_headers {
+        server = server.with_security_headers();
+    }
+    if args.log_bodies {
+        server = server.with_raw_body_logging();
+    }
+    if args.verify {
+        server = server.with_startup_verification();
+    }
+    if let Some(path) = args.bootstrap {
+        match BootstrapPolicy::from_path(&path) {
+            Ok(policy) => server = server.with_bootstrap_policy(policy),
+            Err(err) => {
+                error!("{}", trace!(("Failed to load bootstrap policy"), err));
+                std::process::exit(1);
+            },
+        }
+    }
+    if let Some(version) = args.fallback_version {
+        server = server.with_no_active_policy(NoActivePolicy::Fallback(version));
+    } else if args.deny_all_fallback {
+        server = server.with_no_active_policy(NoActivePolicy::DenyAll);
+    }
     tokio::select! {
         res = server.serve() => match res {
             Ok(_) => info!("Done"),
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/policies/1/decisions -X POST -H 'Content-Type: application/json' -d '{"id":"decision-1"}'
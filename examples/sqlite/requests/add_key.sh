@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/keys -X POST -H 'Content-Type: application/json' -d '{"key":{"id":"rotated","kind":"oct","material":"c2VjcmV0LXNlY3JldC1zZWNyZXQtc2VjcmV0LXNlY3JldA"}}'
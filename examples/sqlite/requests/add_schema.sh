@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/schemas -X POST -H 'Content-Type: application/json' -d '{ "schema": { "name": "boolean", "description": "Policies that are a single boolean", "schema": { "type": "boolean" } } }'
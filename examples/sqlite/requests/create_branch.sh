@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/branches -X POST -H 'Content-Type: application/json' -d '{ "name": "experiment", "from": 1 }'
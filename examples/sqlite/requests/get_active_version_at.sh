@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/policies/active?at=2024-12-01T00:00:00Z -X GET
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/branches -X GET
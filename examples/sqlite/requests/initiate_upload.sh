@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/uploads -X POST -H 'Content-Type: application/json' -d '{ "metadata": { "name": "foo", "description": "Hello, world!", "language": "boolean-v1" }, "length": 4 }'
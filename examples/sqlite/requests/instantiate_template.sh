@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/policies/from-template/allow -X POST -H 'Content-Type: application/json' -d '{ "name": "foo", "description": "Hello, world!", "values": { "allow": true } }'
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/branches/main/promote -X POST -H 'Content-Type: application/json' -d '{ "version": 2 }'
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/maintenance/prune-activations -X POST -H 'Content-Type: application/json' -d '{"before":"2026-01-01T00:00:00Z"}'
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+curl -v localhost:8080/v2/uploads/1 -X PATCH -H 'Upload-Offset: 0' --data-binary 'true'
@@ -0,0 +1,425 @@
+//  AUTHRESOLVER.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:36:36
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Provides the actual [`AuthResolver`] implementation.
+//
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter, Result as FResult};
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use serde_json::{Value, json};
+use specifications::AuthResolver;
+use specifications::authresolver::HttpError;
+use specifications::metadata::User;
+use thiserror::Error;
+use tracing::{Level, debug, info, span};
+
+use crate::cache::Cache;
+
+
+/***** ERRORS *****/
+/// Represents server-side errors which the client can't fix.
+#[derive(Debug, Error)]
+pub enum ServerError {
+    /// Failed to send the introspection request, or to receive its response.
+    #[error("Failed to send introspection request to {endpoint:?}")]
+    Request {
+        endpoint: String,
+        #[source]
+        err:      reqwest::Error,
+    },
+    /// The identity provider did not like our introspection request.
+    #[error("Introspection endpoint {endpoint:?} replied with {status} (body: {body:?})")]
+    Status { endpoint: String, status: StatusCode, body: String },
+    /// The identity provider replied with something that isn't an introspection response.
+    #[error("Failed to deserialize introspection response from {endpoint:?}")]
+    Deserialize {
+        endpoint: String,
+        #[source]
+        err:      reqwest::Error,
+    },
+}
+
+/// Represents client-side errors which the server can't fix.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The given 'Authorization'-header did not contain valid UTF-8.
+    #[error("Value of header {header:?} in request is non-UTF-8")]
+    AuthHeaderNonUtf8 {
+        header: &'static str,
+        #[source]
+        err:    http::header::ToStrError,
+    },
+    /// No 'Authorization' header found in request.
+    #[error("Missing header {header:?} in request")]
+    AuthHeaderNotFound { header: &'static str },
+    /// The identity provider said the token isn't active (e.g., expired, revoked or never issued).
+    #[error("Token in header {header:?} is not active")]
+    InactiveToken { header: &'static str },
+    /// The initiator claim of the token had an invalid type.
+    #[error("Initiator claim {claim:?} of token in header {header:?} has an invalid type: only strings and integers allowed (value: {value:?})")]
+    IllegalType { header: &'static str, claim: String, value: String },
+    /// The token did not have any of the initiator claims we're looking for.
+    #[error("None of the initiator claims {claims:?} found for token in header {header:?}")]
+    MissingInitiatorClaim { header: &'static str, claims: Vec<String> },
+    /// The given 'Authorization'-header was missing the 'Bearer '-part.
+    #[error("Missing \"Bearer \" in header {header:?} in request")]
+    MissingBearer { header: &'static str },
+}
+impl HttpError for ClientError {
+    #[inline]
+    fn status_code(&self) -> StatusCode {
+        use ClientError::*;
+        match self {
+            AuthHeaderNonUtf8 { .. } | AuthHeaderNotFound { .. } | IllegalType { .. } | MissingInitiatorClaim { .. } | MissingBearer { .. } => {
+                StatusCode::BAD_REQUEST
+            },
+            InactiveToken { .. } => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    fn body(&self) -> Value {
+        use ClientError::*;
+        match self {
+            AuthHeaderNonUtf8 { header, .. } | AuthHeaderNotFound { header } | InactiveToken { header } | MissingBearer { header } => {
+                json!({ "header": header })
+            },
+            IllegalType { header, claim, .. } => json!({ "header": header, "claim": claim }),
+            MissingInitiatorClaim { header, claims } => json!({ "header": header, "claims": claims }),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Given a (potentially present) `Auth`-header, attempts to extract the bearer token from it.
+///
+/// # Arguments
+/// - `name`: The name of the Authorization header. Only used for debugging in this function.
+/// - `value`: The [`HeaderValue`] representing what is in the header (or [`None`]) if it isn't
+///   present!).
+///
+/// # Returns
+/// A [`str`] representation of the token.
+///
+/// # Errors
+/// This function may error if the header isn't present, or doesn't bear a token (e.g., missing
+/// "Bearer" in the token field).
+fn extract_token<'h>(name: &'static str, value: Option<&'h HeaderValue>) -> Result<&'h str, ClientError> {
+    let header_val: &str = match value {
+        Some(v) => v.to_str().map_err(|err| ClientError::AuthHeaderNonUtf8 { header: name, err })?,
+        None => return Err(ClientError::AuthHeaderNotFound { header: name }),
+    };
+    // NOTE: We don't log the value on errors, as it may still be a secret
+    header_val.strip_prefix("Bearer ").ok_or(ClientError::MissingBearer { header: name })
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Authorizes HTTP requests by asking an identity provider about their (opaque) bearer tokens,
+/// as defined in [RFC 7662](https://datatracker.ietf.org/doc/html/rfc7662).
+///
+/// Answers are cached until the token expires or the [cache TTL](IntrospectionResolver::with_cache_ttl())
+/// passes, whichever comes first, such that not every request costs a roundtrip to the identity
+/// provider.
+pub struct IntrospectionResolver {
+    /// The URL of the introspection endpoint.
+    endpoint: String,
+    /// The ID with which we authenticate to the identity provider.
+    client_id: String,
+    /// The secret with which we authenticate to the identity provider.
+    client_secret: String,
+    /// Determines which claims we check to find the user in question, in order of preference.
+    initiator_claims: Vec<String>,
+    /// Determines which claims we check to find the user's display name, in order of preference.
+    name_claims: Vec<String>,
+    /// Determines which claims we read the user's roles from.
+    role_claims: Vec<String>,
+    /// How long to cache answers at most.
+    cache_ttl: Duration,
+    /// The answers cached so far.
+    cache: Cache,
+    /// The client used to send introspection requests.
+    client: reqwest::Client,
+}
+// NOTE: Implemented manually to keep the client secret out of logs
+impl Debug for IntrospectionResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        f.debug_struct("IntrospectionResolver")
+            .field("endpoint", &self.endpoint)
+            .field("client_id", &self.client_id)
+            .field("initiator_claims", &self.initiator_claims)
+            .field("name_claims", &self.name_claims)
+            .field("role_claims", &self.role_claims)
+            .field("cache_ttl", &self.cache_ttl)
+            .finish_non_exhaustive()
+    }
+}
+impl IntrospectionResolver {
+    /// Constructor for the IntrospectionResolver.
+    ///
+    /// By default, the user ID is read from the `username`, `sub` or `client_id` claim (in that
+    /// order), the name from the `name` claim, and the roles from the `scope` claim. Answers are
+    /// cached for at most a minute, for up to 10000 tokens.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The URL of the introspection endpoint of the identity provider.
+    /// - `client_id`: The ID with which to authenticate to the identity provider.
+    /// - `client_secret`: The secret with which to authenticate to the identity provider.
+    ///
+    /// # Returns
+    /// A new instance of Self, ready to rumble.
+    #[inline]
+    pub fn new(endpoint: impl Into<String>, client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            initiator_claims: vec!["username".into(), "sub".into(), "client_id".into()],
+            name_claims: vec!["name".into()],
+            role_claims: vec!["scope".into()],
+            cache_ttl: Duration::from_secs(60),
+            cache: Cache::new(10000),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Reads the user ID from the first of the given claims present in an introspection
+    /// response.
+    ///
+    /// # Arguments
+    /// - `claims`: The names of the claims to read the user ID from, in order of preference.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_initiator_claims(mut self, claims: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.initiator_claims = claims.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Reads the user's display name from the first of the given claims present in an
+    /// introspection response.
+    ///
+    /// Claims that aren't strings are skipped. If none of them is present, the user ID doubles
+    /// as name.
+    ///
+    /// # Arguments
+    /// - `claims`: The names of the claims to read the name from, in order of preference.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_name_claims(mut self, claims: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.name_claims = claims.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Reads the user's [roles](User::roles) from the given claims in an introspection response.
+    ///
+    /// A claim may either be a list of strings, or a string with space-separated roles (like the
+    /// `scope` claim). The roles in all claims are combined; missing claims and values of other
+    /// types are skipped.
+    ///
+    /// # Arguments
+    /// - `claims`: The names of the claims to read roles from.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_role_claims(mut self, claims: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.role_claims = claims.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Changes how long answers of the identity provider are cached at most.
+    ///
+    /// Note that revoked tokens are accepted for as long as they are cached.
+    ///
+    /// # Arguments
+    /// - `ttl`: The maximum time to cache answers. Give [`Duration::ZERO`] to disable caching.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Changes how many tokens are cached at most.
+    ///
+    /// # Arguments
+    /// - `capacity`: The maximum number of tokens to cache.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache = Cache::new(capacity);
+        self
+    }
+
+    /// Changes the client used to send introspection requests, e.g., to configure timeouts or
+    /// custom root certificates.
+    ///
+    /// # Arguments
+    /// - `client`: The [`reqwest::Client`] to use.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+
+
+    /// Asks the identity provider about a token.
+    ///
+    /// # Arguments
+    /// - `token`: The token to ask about.
+    ///
+    /// # Returns
+    /// The claims in the introspection response.
+    ///
+    /// # Errors
+    /// This function errors if we failed to reach the identity provider, or if it replied with
+    /// something other than an introspection response.
+    async fn introspect(&self, token: &str) -> Result<HashMap<String, serde_json::Value>, ServerError> {
+        let res = self
+            .client
+            .post(&self.endpoint)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .form(&[("token", token), ("token_type_hint", "access_token")])
+            .send()
+            .await
+            .map_err(|err| ServerError::Request { endpoint: self.endpoint.clone(), err })?;
+        let status: StatusCode = res.status();
+        if !status.is_success() {
+            return Err(ServerError::Status { endpoint: self.endpoint.clone(), status, body: res.text().await.unwrap_or_default() });
+        }
+        res.json().await.map_err(|err| ServerError::Deserialize { endpoint: self.endpoint.clone(), err })
+    }
+
+    /// Finds the user identified by the claims of an active token.
+    ///
+    /// # Arguments
+    /// - `claims`: The claims in the introspection response.
+    ///
+    /// # Returns
+    /// The [`User`] with an ID from the first present initiator claim, a name from the first
+    /// present name claim and roles from all role claims. Its issuer is taken from the `iss`
+    /// claim, if any.
+    ///
+    /// # Errors
+    /// This function errors if none of the initiator claims is present, or if the first one that
+    /// is has a type other than a string or number.
+    fn resolve_user(&self, claims: &HashMap<String, serde_json::Value>) -> Result<User, ClientError> {
+        let Some((claim, initiator)) = self.initiator_claims.iter().find_map(|claim| claims.get(claim).map(|value| (claim, value))) else {
+            return Err(ClientError::MissingInitiatorClaim { header: AUTHORIZATION.as_str(), claims: self.initiator_claims.clone() });
+        };
+        let id: String = match initiator {
+            serde_json::Value::Number(v) => v.to_string(),
+            serde_json::Value::String(v) => v.clone(),
+            other => return Err(ClientError::IllegalType { header: AUTHORIZATION.as_str(), claim: claim.clone(), value: format!("{other:?}") }),
+        };
+        debug!("Found user ID {id:?} in claim {claim:?}");
+
+        // Find the name, if any
+        let name: String = self
+            .name_claims
+            .iter()
+            .find_map(|claim| match claims.get(claim) {
+                Some(serde_json::Value::String(name)) => Some(name.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| id.clone());
+
+        // Collect the roles from all claims that have any
+        let mut roles: Vec<String> = Vec::new();
+        for claim in &self.role_claims {
+            let found: Vec<&str> = match claims.get(claim) {
+                Some(serde_json::Value::String(value)) => value.split_whitespace().collect(),
+                Some(serde_json::Value::Array(values)) => values.iter().filter_map(serde_json::Value::as_str).collect(),
+                _ => continue,
+            };
+            for role in found {
+                if !roles.iter().any(|r| r == role) {
+                    roles.push(role.into());
+                }
+            }
+        }
+        // The issuer tells apart users with the same ID from different organizations
+        let issuer: Option<String> = claims.get("iss").and_then(serde_json::Value::as_str).map(String::from);
+        Ok(User { id, name, issuer, roles })
+    }
+}
+impl AuthResolver for IntrospectionResolver {
+    type Context = User;
+    type ClientError = ClientError;
+    type ServerError = ServerError;
+
+
+    fn authorize(
+        &self,
+        headers: &HeaderMap<HeaderValue>,
+    ) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>> {
+        async move {
+            let _span = span!(Level::INFO, "IntrospectionResolver::authorize");
+            info!("Handling token introspection for incoming request");
+
+            // Fetch the token from the header
+            let token: &str = match extract_token(AUTHORIZATION.as_str(), headers.get(AUTHORIZATION.as_str())) {
+                Ok(token) => token,
+                Err(err) => return Ok(Err(err)),
+            };
+
+            // See if we know it already
+            if let Some(user) = self.cache.get(token) {
+                debug!("Found token in cache");
+                return Ok(user.ok_or(ClientError::InactiveToken { header: AUTHORIZATION.as_str() }));
+            }
+
+            // Else, ask the IdP
+            debug!("Introspecting token at {:?}...", self.endpoint);
+            let claims: HashMap<String, serde_json::Value> = self.introspect(token).await?;
+            let mut until: Instant = Instant::now() + self.cache_ttl;
+            if let Some(exp) = claims.get("exp").and_then(serde_json::Value::as_u64) {
+                // Don't cache beyond the token's expiry
+                let left: Duration = Duration::from_secs(exp).saturating_sub(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default());
+                until = until.min(Instant::now() + left);
+            }
+            if claims.get("active") != Some(&serde_json::Value::Bool(true)) {
+                debug!("Token is inactive");
+                self.cache.insert(token, Instant::now() + self.cache_ttl, None);
+                return Ok(Err(ClientError::InactiveToken { header: AUTHORIZATION.as_str() }));
+            }
+            let user: User = match self.resolve_user(&claims) {
+                Ok(user) => user,
+                Err(err) => return Ok(Err(err)),
+            };
+            self.cache.insert(token, until, Some(user.clone()));
+            Ok(Ok(user))
+        }
+    }
+}
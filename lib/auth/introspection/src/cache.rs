@@ -0,0 +1,106 @@
+//  CACHE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:36:36
+//  Last edited:
+//    16 Oct 2026, 19:44:00
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the cache of introspection results.
+//
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use sha2::{Digest as _, Sha256};
+use specifications::metadata::User;
+use tracing::debug;
+
+
+/***** AUXILLARY *****/
+/// A remembered introspection result.
+#[derive(Debug)]
+struct Entry {
+    /// When this entry expires.
+    until: Instant,
+    /// The user the token belongs to, or [`None`] if it's inactive.
+    user:  Option<User>,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Remembers what the identity provider said about tokens until they expire.
+///
+/// Tokens are only stored as hashes, such that dumping the cache doesn't leak them.
+#[derive(Debug)]
+pub(crate) struct Cache {
+    /// The maximum number of tokens to remember.
+    capacity: usize,
+    /// Maps token hashes to what the identity provider said about them.
+    entries:  Mutex<HashMap<[u8; 32], Entry>>,
+}
+impl Cache {
+    /// Constructor for the Cache.
+    ///
+    /// # Arguments
+    /// - `capacity`: The maximum number of tokens to remember.
+    ///
+    /// # Returns
+    /// A new, empty Cache.
+    #[inline]
+    pub(crate) fn new(capacity: usize) -> Self { Self { capacity, entries: Mutex::new(HashMap::new()) } }
+
+    /// Looks up a token.
+    ///
+    /// # Arguments
+    /// - `token`: The token to look up.
+    ///
+    /// # Returns
+    /// [`None`] if we don't know (anymore) what the identity provider thinks of the token, or
+    /// else `Some` of the user it belongs to ([`None`] if it's inactive).
+    pub(crate) fn get(&self, token: &str) -> Option<Option<User>> {
+        let key: [u8; 32] = Sha256::digest(token).into();
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        match entries.get(&key) {
+            Some(entry) if Instant::now() < entry.until => Some(entry.user.clone()),
+            Some(_) => {
+                entries.remove(&key);
+                None
+            },
+            None => None,
+        }
+    }
+
+    /// Remembers a token.
+    ///
+    /// If the cache is full, expired entries are evicted first. If that isn't enough, the cache
+    /// is cleared.
+    ///
+    /// # Arguments
+    /// - `token`: The token to remember.
+    /// - `until`: When to forget it again.
+    /// - `user`: The user it belongs to, or [`None`] if it's inactive.
+    pub(crate) fn insert(&self, token: &str, until: Instant, user: Option<User>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key: [u8; 32] = Sha256::digest(token).into();
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let now = Instant::now();
+            entries.retain(|_, entry| now < entry.until);
+            if entries.len() >= self.capacity {
+                debug!("Introspection cache full ({} entries); clearing it", entries.len());
+                entries.clear();
+            }
+        }
+        entries.insert(key, Entry { until, user });
+    }
+}
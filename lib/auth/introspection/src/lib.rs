@@ -0,0 +1,23 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:36:36
+//  Last edited:
+//    16 Oct 2026, 16:36:36
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements an [`AuthResolver`](specifications::AuthResolver) that
+//!   validates opaque tokens using OAuth 2.0 Token Introspection
+//!   (RFC 7662), for identity providers that don't issue self-contained
+//!   JWTs.
+//
+
+// Modules
+mod authresolver;
+mod cache;
+
+// Use some of it into the main namespace
+pub use authresolver::*;
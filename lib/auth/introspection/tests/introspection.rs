@@ -0,0 +1,115 @@
+//  INTROSPECTION.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:36:36
+//  Last edited:
+//    16 Oct 2026, 16:36:36
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the [`IntrospectionResolver`] against a fake identity
+//!   provider.
+//
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Form, Json, Router};
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use introspection_auth::{ClientError, IntrospectionResolver};
+use serde_json::{Value, json};
+use specifications::AuthResolver as _;
+use specifications::authresolver::HttpError as _;
+use tokio::net::TcpListener;
+
+
+/***** HELPERS *****/
+/// Starts a fake identity provider that knows one active token, `good`.
+///
+/// # Returns
+/// The URL of its introspection endpoint, and a counter of how often it was called.
+async fn start_idp() -> (String, Arc<AtomicUsize>) {
+    async fn introspect(State(calls): State<Arc<AtomicUsize>>, Form(form): Form<HashMap<String, String>>) -> Json<Value> {
+        calls.fetch_add(1, Ordering::SeqCst);
+        match form.get("token").map(String::as_str) {
+            Some("good") => Json(json!({ "active": true, "sub": "amy", "name": "Amy", "scope": "read activate read" })),
+            _ => Json(json!({ "active": false })),
+        }
+    }
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let router: Router = Router::new().route("/introspect", post(introspect)).with_state(calls.clone());
+    let listener: TcpListener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let addr: SocketAddr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (format!("http://{addr}/introspect"), calls)
+}
+
+/// Returns headers carrying the given bearer token.
+fn bearer(token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}")).unwrap());
+    headers
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_active_token() {
+    let (endpoint, calls) = start_idp().await;
+    let resolver = IntrospectionResolver::new(endpoint, "policy-store", "secret");
+
+    let user = resolver.authorize(&bearer("good")).await.unwrap().unwrap();
+    assert_eq!(user.id, "amy");
+    assert_eq!(user.name, "Amy");
+    assert_eq!(user.roles, ["read", "activate"]);
+
+    // The second time, the answer comes from the cache
+    resolver.authorize(&bearer("good")).await.unwrap().unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_inactive_token() {
+    let (endpoint, calls) = start_idp().await;
+    let resolver = IntrospectionResolver::new(endpoint, "policy-store", "secret");
+
+    for _ in 0..2 {
+        let err = resolver.authorize(&bearer("bad")).await.unwrap().unwrap_err();
+        assert!(matches!(err, ClientError::InactiveToken { .. }));
+        assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_uncached() {
+    let (endpoint, calls) = start_idp().await;
+    let resolver = IntrospectionResolver::new(endpoint, "policy-store", "secret").with_cache_capacity(0);
+
+    resolver.authorize(&bearer("good")).await.unwrap().unwrap();
+    resolver.authorize(&bearer("good")).await.unwrap().unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_missing_bearer() {
+    let resolver = IntrospectionResolver::new("http://127.0.0.1:1/introspect", "policy-store", "secret");
+
+    let err = resolver.authorize(&HeaderMap::new()).await.unwrap().unwrap_err();
+    assert!(matches!(err, ClientError::AuthHeaderNotFound { .. }));
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_static("Basic Zm9vOmJhcg=="));
+    let err = resolver.authorize(&headers).await.unwrap().unwrap_err();
+    assert!(matches!(err, ClientError::MissingBearer { .. }));
+}
@@ -0,0 +1,61 @@
+//  AUTHORIZE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:32:22
+//  Last edited:
+//    16 Oct 2026, 16:32:22
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Fuzzes authorizing requests with the `JwkResolver`, from the raw
+//!   `Authorization`-header up to the initiator claim.
+//
+
+#![no_main]
+
+use std::future::Future as _;
+use std::pin::pin;
+use std::sync::LazyLock;
+use std::task::{Context, Poll, Waker};
+
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, HeaderValue};
+use jwk_auth::JwkResolver;
+use jwk_auth::keyresolver::KidResolver;
+use libfuzzer_sys::fuzz_target;
+use specifications::AuthResolver as _;
+
+
+/***** CONSTANTS *****/
+/// The key set to validate tokens with. It's the one of the `jwk` example, such that seeding the
+/// corpus with tokens made for it lets the fuzzer get past the signature check.
+const KEYS: &str = include_str!("../../../../../examples/jwk/key.json");
+
+
+
+
+
+/***** GLOBALS *****/
+/// The resolver under test, shared by all runs.
+static RESOLVER: LazyLock<JwkResolver<KidResolver>> =
+    LazyLock::new(|| JwkResolver::new("username", KidResolver::parse("keys.json", KEYS).expect("Failed to parse built-in key set")));
+
+
+
+
+
+/***** ENTRYPOINT *****/
+fuzz_target!(|data: &[u8]| {
+    let Ok(value) = HeaderValue::from_bytes(data) else { return };
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, value);
+
+    // The `KidResolver` never awaits anything, so a single poll is enough to get an answer
+    let fut = pin!(RESOLVER.authorize(&headers));
+    match fut.poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(_) => {},
+        Poll::Pending => panic!("JwkResolver::authorize() with a KidResolver unexpectedly returned Poll::Pending"),
+    }
+});
@@ -0,0 +1,29 @@
+//  EXTRACT JWT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:32:22
+//  Last edited:
+//    16 Oct 2026, 16:32:22
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Fuzzes extracting a JWT from an `Authorization`-header and decoding
+//!   its header.
+//
+
+#![no_main]
+
+use http::HeaderValue;
+use jwk_auth::extract_jwt;
+use libfuzzer_sys::fuzz_target;
+
+
+/***** ENTRYPOINT *****/
+fuzz_target!(|data: &[u8]| {
+    let Ok(value) = HeaderValue::from_bytes(data) else { return };
+    if let Ok(raw) = extract_jwt("Authorization", Some(&value)) {
+        let _ = jsonwebtoken::decode_header(raw);
+    }
+});
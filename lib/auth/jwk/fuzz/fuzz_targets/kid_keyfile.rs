@@ -0,0 +1,24 @@
+//  KID KEYFILE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:32:22
+//  Last edited:
+//    16 Oct 2026, 16:32:22
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Fuzzes parsing key sets for the `KidResolver`.
+//
+
+#![no_main]
+
+use jwk_auth::keyresolver::KidResolver;
+use libfuzzer_sys::fuzz_target;
+
+
+/***** ENTRYPOINT *****/
+fuzz_target!(|data: &str| {
+    let _ = KidResolver::parse("fuzz.json", data);
+});
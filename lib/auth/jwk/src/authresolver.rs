@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 10:37:53
 //  Last edited:
-//    11 Nov 2024, 12:24:50
+//    17 Oct 2026, 02:29:12
 //  Auto updated?
 //    Yes
 //
@@ -18,9 +18,11 @@ use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FResult};
 use std::future::Future;
 
+use chrono::{DateTime, Utc};
 use http::header::AUTHORIZATION;
 use http::{HeaderMap, HeaderValue, StatusCode};
 use jsonwebtoken::{Header, Validation};
+use serde_json::{Value, json};
 use specifications::AuthResolver;
 use specifications::authresolver::HttpError;
 use specifications::metadata::User;
@@ -28,6 +30,7 @@ use thiserror::Error;
 use tracing::{Level, debug, info, span};
 
 use crate::keyresolver::KeyResolver;
+use crate::revocation::{NoRevocations, RevocationList};
 
 
 /***** ERRORS *****/
@@ -45,6 +48,9 @@ impl Error for KeyResolveErrorWrapper {
 impl HttpError for KeyResolveErrorWrapper {
     #[inline]
     fn status_code(&self) -> StatusCode { self.0.status_code() }
+
+    #[inline]
+    fn body(&self) -> Value { self.0.body() }
 }
 
 
@@ -58,6 +64,12 @@ pub enum ServerError {
         #[source]
         err: Box<dyn 'static + Error>,
     },
+    /// The embedded [`RevocationList`] failed to check whether a token is revoked.
+    #[error("Failed to check token revocation")]
+    RevocationCheck {
+        #[source]
+        err: Box<dyn 'static + Error>,
+    },
 }
 // Allows key resolvers to use 'Infallible' as error type
 impl From<Infallible> for ServerError {
@@ -89,9 +101,15 @@ pub enum ClientError {
     /// The JWT initiator claim had an invalid type.
     #[error("JWT initiator claim {claim:?} in header {header:?} has an invalid type: only strings and integers allowed (value: {value:?})")]
     JwtIllegalType { header: &'static str, claim: String, value: String },
-    /// The JWT did not have the initiator claim we're looking for.
-    #[error("Initiator claim {claim:?} not found in JWT in header {header:?}")]
-    JwtMissingInitiatorClaim { header: &'static str, claim: String },
+    /// The JWT did not have any of the initiator claims we're looking for.
+    #[error("None of the initiator claims {claims:?} found in JWT in header {header:?}")]
+    JwtMissingInitiatorClaim { header: &'static str, claims: Vec<String> },
+    /// The JWT was not issued by any of the issuers we trust.
+    #[error("JWT in header {header:?} was issued by untrusted issuer {issuer:?}")]
+    JwtUnknownIssuer { header: &'static str, issuer: Option<String> },
+    /// The JWT in the given header is valid, but has been revoked.
+    #[error("JWT in header {header:?} of user {user:?} has been revoked")]
+    JwtRevoked { header: &'static str, user: String },
     /// Failed to validate the JWT in the given header.
     #[error("Failed to validate JWT in header {header:?}")]
     JwtValidate {
@@ -120,10 +138,27 @@ impl HttpError for ClientError {
             | JwtIllegalType { .. }
             | JwtMissingInitiatorClaim { .. }
             | MissingBearer { .. } => StatusCode::BAD_REQUEST,
-            JwtValidate { .. } => StatusCode::UNAUTHORIZED,
+            JwtRevoked { .. } | JwtUnknownIssuer { .. } | JwtValidate { .. } => StatusCode::UNAUTHORIZED,
             KeyResolve { err } => err.status_code(),
         }
     }
+
+    fn body(&self) -> Value {
+        use ClientError::*;
+        // NOTE: We never send back (parts of) the token itself
+        match self {
+            AuthHeaderNonUtf8 { header, .. }
+            | AuthHeaderNotFound { header }
+            | IllegalJwt { header, .. }
+            | JwtValidate { header, .. }
+            | MissingBearer { header, .. } => json!({ "header": header }),
+            JwtIllegalType { header, claim, .. } => json!({ "header": header, "claim": claim }),
+            JwtMissingInitiatorClaim { header, claims } => json!({ "header": header, "claims": claims }),
+            JwtRevoked { header, user } => json!({ "header": header, "user": user }),
+            JwtUnknownIssuer { header, issuer } => json!({ "header": header, "issuer": issuer }),
+            KeyResolve { err } => err.body(),
+        }
+    }
 }
 // Allows key resolvers to use 'Infallible' as error type
 impl From<Infallible> for ClientError {
@@ -149,7 +184,7 @@ impl From<Infallible> for ClientError {
 /// # Errors
 /// This function may error if the header isn't present, or doesn't bear a valid token (e.g.,
 /// missing "Bearer" in the token field).
-fn extract_jwt<'h>(name: &'static str, value: Option<&'h HeaderValue>) -> Result<&'h str, ClientError> {
+pub fn extract_jwt<'h>(name: &'static str, value: Option<&'h HeaderValue>) -> Result<&'h str, ClientError> {
     // Get the header value as a string
     let header_val: &str = match value {
         Some(v) => match v.to_str() {
@@ -174,18 +209,61 @@ fn extract_jwt<'h>(name: &'static str, value: Option<&'h HeaderValue>) -> Result
 
 
 
+/// Rejects the user of a validated JWT if the token has been revoked.
+///
+/// # Arguments
+/// - `revocations`: The [`RevocationList`] to check.
+/// - `claims`: The claims in the JWT.
+/// - `user`: The [`User`] identified by the JWT.
+///
+/// # Returns
+/// The `user` if the token has not been revoked, or a [`ClientError::JwtRevoked`] if it has.
+///
+/// # Errors
+/// This function errors if we failed to check the `revocations`.
+pub(crate) async fn check_revocation<R>(
+    revocations: &R,
+    claims: &HashMap<String, serde_json::Value>,
+    user: User,
+) -> Result<Result<User, ClientError>, ServerError>
+where
+    R: RevocationList,
+    ServerError: From<R::Error>,
+{
+    let jti: Option<&str> = claims.get("jti").and_then(serde_json::Value::as_str);
+    let issued: Option<DateTime<Utc>> = claims.get("iat").and_then(serde_json::Value::as_i64).and_then(|iat| DateTime::from_timestamp(iat, 0));
+    if revocations.is_revoked(jti, &user.id, issued).await? {
+        return Ok(Err(ClientError::JwtRevoked { header: AUTHORIZATION.as_str(), user: user.id }));
+    }
+    Ok(Ok(user))
+}
+
+
+
+
+
 /***** LIBRARY *****/
 /// Authorizes HTTP requests by finding JWKs in the headers.
 #[derive(Debug)]
-pub struct JwkResolver<K> {
-    /// Determines which JWT claims we check to find the user in question.
-    initiator_claim: String,
+pub struct JwkResolver<K, R = NoRevocations> {
+    /// Determines which JWT claims we check to find the user in question, in order of preference.
+    initiator_claims: Vec<String>,
+    /// Determines which JWT claims we check to find the user's display name, in order of
+    /// preference.
+    name_claims: Vec<String>,
+    /// Determines which (nested) JWT claims we read the user's roles from.
+    role_claims: Vec<String>,
     /// The keystore that we use to verify JWTs
     resolver: K,
+    /// The list of revoked tokens that we reject even if they're validly signed.
+    revocations: R,
 }
 impl<K> JwkResolver<K> {
     /// Constructor for the JwkResolver.
     ///
+    /// By default, the user's name is read from the `name` claim. See
+    /// [`JwkResolver::with_name_claims()`] to change this.
+    ///
     /// # Arguments
     /// - `initiator_claim`: The name of the claim that we use to read the user ID.
     /// - `resolver`: Something implementing [`KeyResolver`] that resolves JWT headers to
@@ -194,13 +272,224 @@ impl<K> JwkResolver<K> {
     /// # Returns
     /// A new instance of Self, ready to rumble.
     #[inline]
-    pub fn new(initiator_claim: impl Into<String>, resolver: K) -> Self { Self { initiator_claim: initiator_claim.into(), resolver } }
+    pub fn new(initiator_claim: impl Into<String>, resolver: K) -> Self {
+        Self {
+            initiator_claims: vec![initiator_claim.into()],
+            name_claims: vec!["name".into()],
+            role_claims: Vec::new(),
+            resolver,
+            revocations: NoRevocations,
+        }
+    }
+}
+impl<K, R> JwkResolver<K, R> {
+    /// Rejects tokens that are on the given list of revoked tokens, even if they're validly
+    /// signed.
+    ///
+    /// By default, no tokens are revoked.
+    ///
+    /// # Arguments
+    /// - `revocations`: Something implementing [`RevocationList`] that tells us which tokens
+    ///   are revoked.
+    ///
+    /// # Returns
+    /// A new JwkResolver that checks the given list.
+    #[inline]
+    pub fn with_revocations<R2>(self, revocations: R2) -> JwkResolver<K, R2> {
+        JwkResolver {
+            initiator_claims: self.initiator_claims,
+            name_claims: self.name_claims,
+            role_claims: self.role_claims,
+            resolver: self.resolver,
+            revocations,
+        }
+    }
+
+    /// Reads the user ID from the first of the given claims present in a JWT, e.g., to accept
+    /// both user tokens (`preferred_username`, `sub`) and service tokens (`client_id`).
+    ///
+    /// This replaces the claim given in [`JwkResolver::new()`].
+    ///
+    /// # Arguments
+    /// - `claims`: The names of the claims to read the user ID from, in order of preference.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_initiator_claims(mut self, claims: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.initiator_claims = claims.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Reads the user's display name from the first of the given claims present in a JWT.
+    ///
+    /// Claims that aren't strings are skipped. If none of them is present, the user ID doubles
+    /// as name.
+    ///
+    /// # Arguments
+    /// - `claims`: The names of the claims to read the name from, in order of preference.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_name_claims(mut self, claims: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.name_claims = claims.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Reads the user's [roles](User::roles) from the given claims in a JWT.
+    ///
+    /// Claims may be nested in objects by separating the names with dots (e.g.,
+    /// `realm_access.roles` for Keycloak realm roles). A claim may either be a list of strings, or
+    /// a string with space-separated roles (like the `scope` claim). The roles in all claims are
+    /// combined; missing claims and values of other types are skipped.
+    ///
+    /// # Arguments
+    /// - `claims`: The names of the claims to read roles from.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_role_claims(mut self, claims: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.role_claims = claims.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Finds the user identified by the claims of a validated JWT.
+    ///
+    /// # Arguments
+    /// - `claims`: The claims in the JWT.
+    ///
+    /// # Returns
+    /// The [`User`] with an ID from the first present initiator claim, a name from the first
+    /// present name claim and roles from all role claims. Its issuer is taken from the `iss`
+    /// claim, if any.
+    ///
+    /// # Errors
+    /// This function errors if none of the initiator claims is present, or if the first one that
+    /// is has a type other than a string or number.
+    pub(crate) fn resolve_user(&self, claims: &HashMap<String, serde_json::Value>) -> Result<User, ClientError> {
+        let Some((claim, initiator)) = self.initiator_claims.iter().find_map(|claim| claims.get(claim).map(|value| (claim, value))) else {
+            return Err(ClientError::JwtMissingInitiatorClaim { header: AUTHORIZATION.as_str(), claims: self.initiator_claims.clone() });
+        };
+        let id: String = match initiator {
+            serde_json::Value::Number(v) => v.to_string(),
+            serde_json::Value::String(v) => v.clone(),
+            other => {
+                return Err(ClientError::JwtIllegalType { header: AUTHORIZATION.as_str(), claim: claim.clone(), value: format!("{other:?}") });
+            },
+        };
+        debug!("Found user ID {id:?} in claim {claim:?}");
+
+        // Find the name, if any
+        let name: String = self
+            .name_claims
+            .iter()
+            .find_map(|claim| match claims.get(claim) {
+                Some(serde_json::Value::String(name)) => Some(name.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| id.clone());
+
+        // Collect the roles from all claims that have any
+        let mut roles: Vec<String> = Vec::new();
+        for path in &self.role_claims {
+            let mut parts = path.split('.');
+            let value: Option<&serde_json::Value> =
+                parts.next().and_then(|root| parts.try_fold(claims.get(root)?, |value, part| value.as_object()?.get(part)));
+            let found: Vec<&str> = match value {
+                Some(serde_json::Value::String(value)) => value.split_whitespace().collect(),
+                Some(serde_json::Value::Array(values)) => values.iter().filter_map(serde_json::Value::as_str).collect(),
+                _ => {
+                    debug!("No roles found in claim {path:?}");
+                    continue;
+                },
+            };
+            for role in found {
+                if !roles.iter().any(|r| r == role) {
+                    roles.push(role.into());
+                }
+            }
+        }
+        // The issuer tells apart users with the same ID from different organizations
+        let issuer: Option<String> = claims.get("iss").and_then(serde_json::Value::as_str).map(String::from);
+        Ok(User { id, name, issuer, roles })
+    }
+
+    /// Validates a JWT against the keys found by our [`KeyResolver`].
+    ///
+    /// # Arguments
+    /// - `raw_jwt`: The JWT to validate.
+    /// - `issuer`: If given, the JWT must have been issued by this issuer (i.e., have it as `iss`
+    ///   claim).
+    ///
+    /// # Returns
+    /// The claims in the JWT.
+    ///
+    /// # Errors
+    /// This function errors if we failed to resolve the keys due to some server-side error. If the
+    /// JWT is malformed or doesn't validate against any of the keys, a [`ClientError`] is
+    /// returned instead.
+    pub(crate) async fn validate(
+        &self,
+        raw_jwt: &str,
+        issuer: Option<&str>,
+    ) -> Result<Result<HashMap<String, serde_json::Value>, ClientError>, ServerError>
+    where
+        K: Sync + KeyResolver,
+        ClientError: From<K::ClientError>,
+        ServerError: From<K::ServerError>,
+    {
+        // Fetch the header from the JWT
+        let header: Header = match jsonwebtoken::decode_header(raw_jwt).map_err(|err| ClientError::IllegalJwt {
+            header: AUTHORIZATION.as_str(),
+            raw: raw_jwt.into(),
+            err,
+        }) {
+            Ok(header) => header,
+            Err(err) => return Ok(Err(err)),
+        };
+        debug!("JWT header: {header:?}");
+
+        // Check if the key makes sense
+        debug!("Resolving key(s) in keystore...");
+        let decoding_keys = match self.resolver.resolve_keys(&header).await? {
+            Ok(keys) => keys,
+            Err(err) => return Ok(Err(err.into())),
+        };
+        let mut validation = Validation::new(header.alg);
+        if let Some(issuer) = issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        debug!("Validating JWT with {:?} against {} key(s)...", header.alg, decoding_keys.len());
+        let mut result = None;
+        for decoding_key in &decoding_keys {
+            match jsonwebtoken::decode::<HashMap<String, serde_json::Value>>(raw_jwt, decoding_key, &validation) {
+                Ok(res) => {
+                    result = Some(Ok(res));
+                    break;
+                },
+                Err(err) => result = Some(Err(err)),
+            }
+        }
+        match result {
+            Some(Ok(res)) => {
+                debug!("Validating OK");
+                Ok(Ok(res.claims))
+            },
+            Some(Err(err)) => Ok(Err(ClientError::JwtValidate { header: AUTHORIZATION.as_str(), err })),
+            None => {
+                Ok(Err(ClientError::JwtValidate { header: AUTHORIZATION.as_str(), err: jsonwebtoken::errors::ErrorKind::InvalidSignature.into() }))
+            },
+        }
+    }
 }
-impl<K> AuthResolver for JwkResolver<K>
+impl<K, R> AuthResolver for JwkResolver<K, R>
 where
     K: Sync + KeyResolver,
+    R: Sync + RevocationList,
     ClientError: From<K::ClientError>,
-    ServerError: From<K::ServerError>,
+    ServerError: From<K::ServerError> + From<R::Error>,
 {
     type Context = User;
     type ClientError = ClientError;
@@ -222,43 +511,21 @@ where
             };
             debug!("Received JWT: {raw_jwt:?}");
 
-            // Fetch the header from the JWT
-            let header: Header = match jsonwebtoken::decode_header(raw_jwt).map_err(|err| ClientError::IllegalJwt {
-                header: AUTHORIZATION.as_str(),
-                raw: raw_jwt.into(),
-                err,
-            }) {
-                Ok(header) => header,
+            // Validate it against our keys
+            let claims: HashMap<String, serde_json::Value> = match self.validate(raw_jwt, None).await? {
+                Ok(claims) => claims,
                 Err(err) => return Ok(Err(err)),
             };
-            debug!("JWT header: {header:?}");
-
-            // Check if the key makes sense
-            debug!("Resolving key in keystore...");
-            let decoding_key = match self.resolver.resolve_key(&header).await? {
-                Ok(key) => key,
-                Err(err) => return Ok(Err(err.into())),
-            };
-            let validation = Validation::new(header.alg);
-            debug!("Validating JWT with {:?}...", header.alg);
-            let result = match jsonwebtoken::decode::<HashMap<String, serde_json::Value>>(raw_jwt, &decoding_key, &validation) {
-                Ok(res) => res,
-                Err(err) => return Ok(Err(ClientError::JwtValidate { header: AUTHORIZATION.as_str(), err })),
+            let user: User = match self.resolve_user(&claims) {
+                Ok(user) => user,
+                Err(err) => return Ok(Err(err)),
             };
-            debug!("Validating OK");
-
-            match result.claims.get(&self.initiator_claim) {
-                Some(initiator) => match initiator {
-                    serde_json::Value::Number(v) => Ok(Ok(User { id: v.to_string(), name: "John Smith".into() })),
-                    serde_json::Value::String(v) => Ok(Ok(User { id: v.clone(), name: "John Smith".into() })),
-                    other => Ok(Err(ClientError::JwtIllegalType {
-                        header: AUTHORIZATION.as_str(),
-                        claim:  self.initiator_claim.clone(),
-                        value:  format!("{other:?}"),
-                    })),
-                },
-                None => Ok(Err(ClientError::JwtMissingInitiatorClaim { header: AUTHORIZATION.as_str(), claim: self.initiator_claim.clone() })),
-            }
+
+            // Check whether the token has been revoked since
+            check_revocation(&self.revocations, &claims, user).await
         }
     }
+
+    #[inline]
+    fn invalidate(&self) { self.resolver.invalidate(); }
 }
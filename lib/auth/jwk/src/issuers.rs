@@ -0,0 +1,183 @@
+//  ISSUERS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:08:08
+//  Last edited:
+//    17 Oct 2026, 02:29:12
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Provides an [`AuthResolver`] that trusts multiple JWT issuers, each
+//!   with their own keys and claims.
+//
+
+use std::collections::HashMap;
+use std::future::Future;
+
+use base64ct::{Base64UrlUnpadded, Encoding as _};
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, HeaderValue};
+use specifications::AuthResolver;
+use specifications::metadata::User;
+use tracing::{Level, debug, info, span};
+
+use crate::authresolver::{ClientError, JwkResolver, ServerError, check_revocation, extract_jwt};
+use crate::keyresolver::KeyResolver;
+use crate::revocation::{NoRevocations, RevocationList};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Reads the issuer of a JWT _without_ validating it.
+///
+/// Only meant to decide which issuer's keys to validate the JWT with.
+///
+/// # Arguments
+/// - `raw_jwt`: The JWT to read the issuer of.
+///
+/// # Returns
+/// The `iss` claim in the JWT, or [`None`] if it has none (or it's not a string).
+///
+/// # Errors
+/// This function errors if the JWT is malformed.
+fn peek_issuer(raw_jwt: &str) -> Result<Option<String>, ClientError> {
+    let illegal = || ClientError::IllegalJwt {
+        header: AUTHORIZATION.as_str(),
+        raw:    raw_jwt.into(),
+        err:    jsonwebtoken::errors::ErrorKind::InvalidToken.into(),
+    };
+    let payload: &str = raw_jwt.split('.').nth(1).ok_or_else(illegal)?;
+    let payload: Vec<u8> = Base64UrlUnpadded::decode_vec(payload).map_err(|_| illegal())?;
+    let claims: HashMap<String, serde_json::Value> = serde_json::from_slice(&payload).map_err(|_| illegal())?;
+    Ok(claims.get("iss").and_then(serde_json::Value::as_str).map(String::from))
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Authorizes HTTP requests by JWTs from any of a set of trusted issuers.
+///
+/// JWTs are dispatched on their `iss` claim to the [`JwkResolver`] configured for that issuer,
+/// which validates them with its own keys and finds the user with its own claims. This allows a
+/// single store to authenticate users from the identity providers of several organizations.
+/// Tokens from any other issuer (or without one) are rejected.
+///
+/// Revoked tokens are checked once, for all issuers; see
+/// [`MultiIssuerResolver::with_revocations()`].
+#[derive(Debug)]
+pub struct MultiIssuerResolver<K, R = NoRevocations> {
+    /// The resolvers for each trusted issuer, by the issuer's `iss` claim.
+    issuers:     HashMap<String, JwkResolver<K>>,
+    /// The list of revoked tokens that we reject even if they're validly signed.
+    revocations: R,
+}
+impl<K> Default for MultiIssuerResolver<K> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<K> MultiIssuerResolver<K> {
+    /// Constructor for the MultiIssuerResolver.
+    ///
+    /// Note that it does not trust any issuer until they're added with
+    /// [`MultiIssuerResolver::with_issuer()`].
+    ///
+    /// # Returns
+    /// A new instance of Self that rejects every token.
+    #[inline]
+    pub fn new() -> Self { Self { issuers: HashMap::new(), revocations: NoRevocations } }
+}
+impl<K, R> MultiIssuerResolver<K, R> {
+    /// Trusts JWTs from the given issuer.
+    ///
+    /// Replaces the resolver of the issuer if it was already trusted.
+    ///
+    /// # Arguments
+    /// - `issuer`: The issuer to trust, as it appears in the `iss` claim of its JWTs.
+    /// - `resolver`: A [`JwkResolver`] that validates the issuer's JWTs and finds the user in
+    ///   them.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_issuer(mut self, issuer: impl Into<String>, resolver: JwkResolver<K>) -> Self {
+        self.issuers.insert(issuer.into(), resolver);
+        self
+    }
+
+    /// Rejects tokens that are on the given list of revoked tokens, even if they're validly
+    /// signed by a trusted issuer.
+    ///
+    /// By default, no tokens are revoked.
+    ///
+    /// # Arguments
+    /// - `revocations`: Something implementing [`RevocationList`] that tells us which tokens
+    ///   are revoked.
+    ///
+    /// # Returns
+    /// A new MultiIssuerResolver that checks the given list.
+    #[inline]
+    pub fn with_revocations<R2>(self, revocations: R2) -> MultiIssuerResolver<K, R2> { MultiIssuerResolver { issuers: self.issuers, revocations } }
+}
+impl<K, R> AuthResolver for MultiIssuerResolver<K, R>
+where
+    K: Sync + KeyResolver,
+    R: Sync + RevocationList,
+    ClientError: From<K::ClientError>,
+    ServerError: From<K::ServerError> + From<R::Error>,
+{
+    type Context = User;
+    type ClientError = ClientError;
+    type ServerError = ServerError;
+
+
+    fn authorize(
+        &self,
+        headers: &HeaderMap<HeaderValue>,
+    ) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>> {
+        async move {
+            let _span = span!(Level::INFO, "MultiIssuerResolver::authorize");
+            info!("Handling multi-issuer JWT authentication for incoming request");
+
+            // Fetch the JWT from the header
+            let raw_jwt = match extract_jwt(AUTHORIZATION.as_str(), headers.get(AUTHORIZATION.as_str())) {
+                Ok(jwt) => jwt,
+                Err(err) => return Ok(Err(err)),
+            };
+            debug!("Received JWT: {raw_jwt:?}");
+
+            // Find who claims to have issued it
+            let issuer: Option<String> = match peek_issuer(raw_jwt) {
+                Ok(issuer) => issuer,
+                Err(err) => return Ok(Err(err)),
+            };
+            let Some((issuer, resolver)) = issuer.as_ref().and_then(|issuer| self.issuers.get_key_value(issuer)) else {
+                debug!("Rejecting JWT from untrusted issuer {issuer:?}");
+                return Ok(Err(ClientError::JwtUnknownIssuer { header: AUTHORIZATION.as_str(), issuer }));
+            };
+            debug!("Validating JWT as issued by {issuer:?}...");
+
+            // Validate it with that issuer's keys (which also checks the claim wasn't tampered with)
+            let claims: HashMap<String, serde_json::Value> = match resolver.validate(raw_jwt, Some(issuer)).await? {
+                Ok(claims) => claims,
+                Err(err) => return Ok(Err(err)),
+            };
+            let user: User = match resolver.resolve_user(&claims) {
+                Ok(user) => user,
+                Err(err) => return Ok(Err(err)),
+            };
+
+            // Check whether the token has been revoked since
+            check_revocation(&self.revocations, &claims, user).await
+        }
+    }
+
+    #[inline]
+    fn invalidate(&self) {
+        for resolver in self.issuers.values() {
+            resolver.invalidate();
+        }
+    }
+}
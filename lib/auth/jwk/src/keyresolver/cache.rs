@@ -0,0 +1,280 @@
+//  CACHE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:39:28
+//  Last edited:
+//    17 Oct 2026, 02:29:12
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a decorator for [`KeyResolver`]s that remembers the
+//!   keys they resolved.
+//
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use http::StatusCode;
+use jsonwebtoken::{Algorithm, DecodingKey, Header};
+use serde_json::{Value, json};
+use specifications::authresolver::HttpError;
+use thiserror::Error;
+use tracing::{Level, debug, span};
+
+use super::KeyResolver;
+use crate::KeyResolveErrorWrapper;
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`CachedKeyResolver`] which are the server's fault.
+#[derive(Debug, Error)]
+pub enum ServerError<E> {
+    /// The nested resolver recently failed to resolve this key ID, and we remembered that.
+    #[error("Failed to resolve key with ID {kid:?} (cached): {reason}")]
+    Cached { kid: String, reason: String },
+    /// The nested resolver failed to resolve a key.
+    #[error(transparent)]
+    Inner(E),
+}
+impl<E: 'static + std::error::Error> From<ServerError<E>> for crate::authresolver::ServerError {
+    #[inline]
+    fn from(value: ServerError<E>) -> Self { Self::KeyResolve { err: Box::new(value) } }
+}
+
+/// Defines the errors originating from the [`CachedKeyResolver`] which are the client's fault.
+#[derive(Debug, Error)]
+pub enum ClientError<E> {
+    /// The nested resolver resolved a key ID to no keys at all.
+    #[error("No key found with ID {kid:?}")]
+    NoKeys { kid: String },
+    /// The nested resolver failed to resolve a key.
+    #[error(transparent)]
+    Inner(E),
+}
+impl<E: HttpError> HttpError for ClientError<E> {
+    #[inline]
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NoKeys { .. } => StatusCode::UNAUTHORIZED,
+            Self::Inner(err) => err.status_code(),
+        }
+    }
+
+    #[inline]
+    fn body(&self) -> Value {
+        match self {
+            Self::NoKeys { kid } => json!({ "kid": kid }),
+            Self::Inner(err) => err.body(),
+        }
+    }
+}
+impl<E: 'static + HttpError> From<ClientError<E>> for crate::authresolver::ClientError {
+    #[inline]
+    fn from(value: ClientError<E>) -> Self { Self::KeyResolve { err: KeyResolveErrorWrapper(Box::new(value)) } }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// A remembered result of resolving a key ID.
+struct Entry {
+    /// When this entry expires.
+    until:  Instant,
+    /// The keys resolved, or the reason why the nested resolver failed.
+    result: Result<Vec<DecodingKey>, String>,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Wraps another [`KeyResolver`] and memoizes the keys it resolves by key ID and algorithm.
+///
+/// Server-side failures are remembered too (for a shorter time), such that an unreachable backend
+/// isn't hammered on every request. Client-side failures (e.g., unknown key IDs) are never
+/// remembered, as that would allow anyone to lock out a key that is about to be added. JWTs
+/// without a key ID are always passed on as-is.
+pub struct CachedKeyResolver<K> {
+    /// The resolver to ask when we don't know a key.
+    resolver: K,
+    /// How long to remember resolved keys.
+    ttl: Duration,
+    /// How long to remember failures to resolve keys.
+    negative_ttl: Duration,
+    /// The maximum number of key IDs to remember.
+    capacity: usize,
+    /// Maps key IDs and the algorithm they're used with to what they resolved to.
+    entries: Mutex<HashMap<(String, Algorithm), Entry>>,
+}
+impl<K> CachedKeyResolver<K> {
+    /// Constructor for the CachedKeyResolver.
+    ///
+    /// By default, keys are remembered for 5 minutes and server-side failures for 30 seconds, for
+    /// up to 1024 key IDs.
+    ///
+    /// # Arguments
+    /// - `resolver`: The nested [`KeyResolver`] to cache.
+    ///
+    /// # Returns
+    /// A new CachedKeyResolver with an empty cache.
+    #[inline]
+    pub fn new(resolver: K) -> Self {
+        Self { resolver, ttl: Duration::from_secs(300), negative_ttl: Duration::from_secs(30), capacity: 1024, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Sets how long resolved keys are remembered.
+    ///
+    /// # Arguments
+    /// - `ttl`: The [`Duration`] after which a key is resolved again.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets how long server-side failures to resolve keys are remembered.
+    ///
+    /// # Arguments
+    /// - `ttl`: The [`Duration`] after which a failed key ID is tried again. Zero disables
+    ///   negative caching.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Sets the maximum number of key IDs remembered.
+    ///
+    /// # Arguments
+    /// - `capacity`: The maximum number of entries. Zero disables caching altogether.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Returns the nested resolver.
+    #[inline]
+    pub fn resolver(&self) -> &K { &self.resolver }
+
+    /// Forgets all cached keys, e.g., after a key rotation.
+    ///
+    /// This is also what [`KeyResolver::invalidate()`] does, which the server calls whenever it
+    /// revokes a key.
+    #[inline]
+    pub fn clear(&self) { self.entries.lock().unwrap_or_else(|err| err.into_inner()).clear(); }
+
+    /// Remembers the result of resolving a key ID for an algorithm.
+    ///
+    /// If the cache is full, expired entries are evicted first. If that isn't enough, the cache
+    /// is cleared.
+    fn insert(&self, key: (String, Algorithm), result: Result<Vec<DecodingKey>, String>) {
+        let ttl: Duration = if result.is_ok() { self.ttl } else { self.negative_ttl };
+        if self.capacity == 0 || ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let now = Instant::now();
+            entries.retain(|_, entry| now < entry.until);
+            if entries.len() >= self.capacity {
+                debug!("Key cache full ({} entries); clearing it", entries.len());
+                entries.clear();
+            }
+        }
+        entries.insert(key, Entry { until: Instant::now() + ttl, result });
+    }
+}
+impl<K> KeyResolver for CachedKeyResolver<K>
+where
+    K: Sync + KeyResolver,
+    K::ClientError: HttpError,
+{
+    type ClientError = ClientError<K::ClientError>;
+    type ServerError = ServerError<K::ServerError>;
+
+
+    fn resolve_key(&self, header: &Header) -> impl Send + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>> {
+        async move {
+            let kid: &str = match header.kid.as_deref() {
+                Some(kid) => kid,
+                None => return Ok(self.resolver.resolve_key(header).await.map_err(ServerError::Inner)?.map_err(ClientError::Inner)),
+            };
+            match self.resolve_keys(header).await? {
+                Ok(keys) => match keys.into_iter().next() {
+                    Some(key) => Ok(Ok(key)),
+                    None => Ok(Err(ClientError::NoKeys { kid: kid.into() })),
+                },
+                Err(err) => Ok(Err(err)),
+            }
+        }
+    }
+
+    fn resolve_keys(&self, header: &Header) -> impl Send + Future<Output = Result<Result<Vec<DecodingKey>, Self::ClientError>, Self::ServerError>> {
+        async move {
+            let _span = span!(Level::INFO, "CachedKeyResolver::resolve_keys");
+
+            // Without a key ID, there's nothing to cache by
+            let kid: &str = match header.kid.as_deref() {
+                Some(kid) => kid,
+                None => return Ok(self.resolver.resolve_keys(header).await.map_err(ServerError::Inner)?.map_err(ClientError::Inner)),
+            };
+
+            // See if we know this one (the same key ID may resolve differently per algorithm)
+            let key: (String, Algorithm) = (kid.into(), header.alg);
+            {
+                let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+                match entries.get(&key) {
+                    Some(entry) if Instant::now() < entry.until => {
+                        debug!("Resolved key with ID {kid:?} for {:?} from cache", header.alg);
+                        return match &entry.result {
+                            Ok(keys) => Ok(Ok(keys.clone())),
+                            Err(reason) => Err(ServerError::Cached { kid: kid.into(), reason: reason.clone() }),
+                        };
+                    },
+                    Some(_) => {
+                        entries.remove(&key);
+                    },
+                    None => {},
+                }
+            }
+
+            // Else, ask the nested resolver and remember what it says. Client errors are not
+            // remembered, lest a client poisons the cache for everyone else.
+            debug!("Key with ID {kid:?} for {:?} not in cache; resolving it", header.alg);
+            match self.resolver.resolve_keys(header).await {
+                Ok(Ok(keys)) => {
+                    self.insert(key, Ok(keys.clone()));
+                    Ok(Ok(keys))
+                },
+                Ok(Err(err)) => Ok(Err(ClientError::Inner(err))),
+                Err(err) => {
+                    self.insert(key, Err(err.to_string()));
+                    Err(ServerError::Inner(err))
+                },
+            }
+        }
+    }
+
+    #[inline]
+    fn invalidate(&self) {
+        self.clear();
+        self.resolver.invalidate();
+    }
+}
@@ -0,0 +1,188 @@
+//  DATABASE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:44:37
+//  Last edited:
+//    16 Oct 2026, 16:57:08
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a resolver that looks up keys in the policy store's own
+//!   database.
+//
+
+use std::error::Error;
+use std::future::Future;
+
+use http::StatusCode;
+use jsonwebtoken::{DecodingKey, Header};
+use serde_json::{Value, json};
+use specifications::authresolver::HttpError;
+use specifications::databaseconn::{DatabaseConnection, DatabaseConnector};
+use specifications::key::{Key, KeyKind};
+use specifications::metadata::User;
+use thiserror::Error;
+use tracing::{Level, debug, span};
+
+use super::KeyResolver;
+use crate::KeyResolveErrorWrapper;
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`DatabaseKeyResolver`] which are the server's fault.
+#[derive(Debug, Error)]
+pub enum ServerError {
+    /// Failed to connect to the backend database.
+    #[error("Failed to connect to the backend database")]
+    Connect {
+        #[source]
+        err: Box<dyn 'static + Error>,
+    },
+    /// Failed to retrieve a key from the backend database.
+    #[error("Failed to retrieve key {kid:?} from the backend database")]
+    GetKey {
+        kid: String,
+        #[source]
+        err: Box<dyn 'static + Error>,
+    },
+    /// A key in the database could not be parsed.
+    #[error("Key {kid:?} in the backend database is not a valid {kind:?} key")]
+    KeyParse {
+        kid:  String,
+        kind: KeyKind,
+        #[source]
+        err:  jsonwebtoken::errors::Error,
+    },
+}
+impl From<ServerError> for crate::authresolver::ServerError {
+    #[inline]
+    fn from(value: ServerError) -> Self { Self::KeyResolve { err: Box::new(value) } }
+}
+
+/// Defines the errors originating from the [`DatabaseKeyResolver`] which are the client's fault.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// Missing Key ID field in the JWT header.
+    #[error("Missing key ID field in given JWT header")]
+    HeaderKidNotFound,
+    /// The suggested key ID was registered, but has since been revoked.
+    #[error("Key with ID {kid:?} has been revoked")]
+    RevokedKeyId { kid: String },
+    /// The suggested key ID wasn't registered.
+    #[error("Unknown key with ID {kid:?}")]
+    UnknownKeyId { kid: String },
+}
+impl HttpError for ClientError {
+    #[inline]
+    fn status_code(&self) -> StatusCode {
+        use ClientError::*;
+        match self {
+            HeaderKidNotFound => StatusCode::BAD_REQUEST,
+            RevokedKeyId { .. } => StatusCode::UNAUTHORIZED,
+            UnknownKeyId { .. } => StatusCode::NOT_FOUND,
+        }
+    }
+
+    #[inline]
+    fn body(&self) -> Value {
+        use ClientError::*;
+        match self {
+            HeaderKidNotFound => Value::Null,
+            RevokedKeyId { kid } | UnknownKeyId { kid } => json!({ "kid": kid }),
+        }
+    }
+}
+impl From<ClientError> for crate::authresolver::ClientError {
+    #[inline]
+    fn from(value: ClientError) -> Self { Self::KeyResolve { err: KeyResolveErrorWrapper(Box::new(value)) } }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Resolves keys for the JWT by ID, as registered in the policy store's own database.
+///
+/// Keys are managed through the store's key endpoints, meaning that they can be rotated or
+/// revoked without restarting the server. Consider wrapping this resolver in a
+/// [`CachedKeyResolver`](super::CachedKeyResolver) to avoid a database roundtrip per request;
+/// note, however, that revocations then take up to its TTL to have effect.
+pub struct DatabaseKeyResolver<D> {
+    /// The database to look keys up in.
+    data: D,
+    /// The user on who's behalf we connect to the database.
+    user: User,
+}
+impl<D> DatabaseKeyResolver<D> {
+    /// Constructor for the DatabaseKeyResolver.
+    ///
+    /// # Arguments
+    /// - `data`: The [`DatabaseConnector`] to look keys up in.
+    /// - `user`: The (service) [`User`] on who's behalf we connect to the database.
+    ///
+    /// # Returns
+    /// A new DatabaseKeyResolver that can resolve keys by ID.
+    #[inline]
+    pub const fn new(data: D, user: User) -> Self { Self { data, user } }
+
+    /// Returns the database this resolver looks keys up in.
+    ///
+    /// # Returns
+    /// A reference to the internal [`DatabaseConnector`].
+    #[inline]
+    pub const fn data(&self) -> &D { &self.data }
+}
+impl<D> KeyResolver for DatabaseKeyResolver<D>
+where
+    D: Sync + DatabaseConnector,
+    D::Error: 'static,
+    for<'s> D::Connection<'s>: Send,
+    for<'s> <D::Connection<'s> as DatabaseConnection>::Error: 'static,
+{
+    type ClientError = ClientError;
+    type ServerError = ServerError;
+
+
+    fn resolve_key(&self, header: &Header) -> impl Send + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>> {
+        let kid: Option<String> = header.kid.clone();
+        async move {
+            let _span = span!(Level::INFO, "DatabaseKeyResolver::resolve_key");
+
+            // Unpack the key ID in the header
+            let kid: String = match kid {
+                Some(kid) => kid,
+                None => return Ok(Err(ClientError::HeaderKidNotFound)),
+            };
+
+            // Get the key
+            let mut conn = self.data.connect(&self.user).await.map_err(|err| ServerError::Connect { err: Box::new(err) })?;
+            let key: Key = match conn.get_key(&kid).await {
+                Ok(Some(key)) => key,
+                Ok(None) => return Ok(Err(ClientError::UnknownKeyId { kid })),
+                Err(err) => return Err(ServerError::GetKey { kid, err: Box::new(err) }),
+            };
+            if key.revoked.is_some() {
+                return Ok(Err(ClientError::RevokedKeyId { kid }));
+            }
+
+            // Parse it
+            let material: &str = &key.attached.material;
+            let res = match key.attached.kind {
+                KeyKind::Oct => DecodingKey::from_base64_secret(material),
+                KeyKind::Rsa => DecodingKey::from_rsa_pem(material.as_bytes()),
+                KeyKind::Ec => DecodingKey::from_ec_pem(material.as_bytes()),
+                KeyKind::Ed => DecodingKey::from_ed_pem(material.as_bytes()),
+            };
+            match res {
+                Ok(res) => {
+                    debug!("Resolved key with ID {kid:?}");
+                    Ok(Ok(res))
+                },
+                Err(err) => Err(ServerError::KeyParse { kid, kind: key.attached.kind, err }),
+            }
+        }
+    }
+}
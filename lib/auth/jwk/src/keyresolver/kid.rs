@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 11:16:54
 //  Last edited:
-//    11 Nov 2024, 12:30:00
+//    16 Oct 2026, 16:57:08
 //  Auto updated?
 //    Yes
 //
@@ -22,6 +22,7 @@ use base64ct::Encoding as _;
 use http::StatusCode;
 use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
 use jsonwebtoken::{DecodingKey, Header};
+use serde_json::{Value, json};
 use specifications::authresolver::HttpError;
 use thiserror::Error;
 use tracing::{Level, debug, span, warn};
@@ -85,6 +86,15 @@ impl HttpError for ClientError {
             UnknownKeyId { .. } => StatusCode::NOT_FOUND,
         }
     }
+
+    #[inline]
+    fn body(&self) -> Value {
+        use ClientError::*;
+        match self {
+            HeaderKidNotFound => Value::Null,
+            UnknownKeyId { kid } => json!({ "kid": kid }),
+        }
+    }
 }
 impl From<ClientError> for crate::authresolver::ClientError {
     #[inline]
@@ -119,7 +129,24 @@ impl KidResolver {
         // Read the contents of the file
         let path: &Path = path.as_ref();
         let r = fs::read_to_string(path).map_err(|err| ServerError::FileRead { path: path.into(), err })?;
-        let keyfile: JwkSet = serde_json::from_str(&r).map_err(|err| ServerError::FileDeserialize { path: path.into(), err })?;
+        Self::parse(path, &r)
+    }
+
+    /// Constructor for the KidResolver that parses an already loaded key set.
+    ///
+    /// # Arguments
+    /// - `path`: The path where the key set was loaded from. Only used for debugging.
+    /// - `raw`: The raw JSON key set to parse.
+    ///
+    /// # Returns
+    /// A new KidResolver that can resolve keys by ID.
+    ///
+    /// # Errors
+    /// This function can fail if `raw` wasn't parsable as a JSON key set, or if any of the keys
+    /// in it isn't a Base64-encoded octet key.
+    pub fn parse(path: impl AsRef<Path>, raw: &str) -> Result<Self, ServerError> {
+        let path: &Path = path.as_ref();
+        let keyfile: JwkSet = serde_json::from_str(raw).map_err(|err| ServerError::FileDeserialize { path: path.into(), err })?;
 
         // Parse the keys as we go
         let mut store = HashMap::with_capacity(keyfile.keys.len());
@@ -157,7 +184,7 @@ impl KeyResolver for KidResolver {
     type ServerError = Infallible;
 
 
-    fn resolve_key(&self, header: &Header) -> impl Send + Sync + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>> {
+    fn resolve_key(&self, header: &Header) -> impl Send + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>> {
         async move {
             let _span = span!(Level::INFO, "KidResolver::resolve_key");
 
@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 10:58:43
 //  Last edited:
-//    24 Oct 2024, 11:13:24
+//    17 Oct 2026, 02:29:12
 //  Auto updated?
 //    Yes
 //
@@ -13,16 +13,26 @@
 //
 
 // Modules
+pub mod cache;
+#[cfg(feature = "database")]
+pub mod database;
 #[cfg(feature = "kid")]
 pub mod kid;
+#[cfg(feature = "pem")]
+pub mod pem;
 
 // Imports
 use std::error::Error;
 use std::future::Future;
 
+pub use cache::CachedKeyResolver;
+#[cfg(feature = "database")]
+pub use database::DatabaseKeyResolver;
 use jsonwebtoken::{DecodingKey, Header};
 #[cfg(feature = "kid")]
 pub use kid::KidResolver;
+#[cfg(feature = "pem")]
+pub use pem::PemResolver;
 
 
 /***** LIBRARY *****/
@@ -55,5 +65,34 @@ pub trait KeyResolver {
     ///
     /// The first will always result in a (vague) 500 INTERNAL SERVER ERROR to the user, whereas
     /// the second may communicate custom status codes.
-    fn resolve_key(&self, header: &Header) -> impl Send + Sync + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>>;
+    fn resolve_key(&self, header: &Header) -> impl Send + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>>;
+
+    /// Provides all keys that might decode the JWT based on its header.
+    ///
+    /// The JWT is accepted if it validates against any of them. By default, this simply returns
+    /// the key found by [`KeyResolver::resolve_key()`]; resolvers that cannot always pinpoint a
+    /// single key (e.g., because the JWT carries no key ID) may override it.
+    ///
+    /// # Arguments
+    /// - `header`: The JWT [`Header`] that tells us which keys to find.
+    ///
+    /// # Returns
+    /// A non-empty list of [`DecodingKey`]s to try.
+    ///
+    /// # Errors
+    /// This function errors in the same way as [`KeyResolver::resolve_key()`].
+    #[inline]
+    fn resolve_keys(&self, header: &Header) -> impl Send + Future<Output = Result<Result<Vec<DecodingKey>, Self::ClientError>, Self::ServerError>>
+    where
+        Self: Sync,
+    {
+        async move { Ok(self.resolve_key(header).await?.map(|key| vec![key])) }
+    }
+
+    /// Forgets any keys remembered so far, such that revoked keys are no longer trusted.
+    ///
+    /// By default, this does nothing; resolvers that cache keys (or nest resolvers that do) should
+    /// override it.
+    #[inline]
+    fn invalidate(&self) {}
 }
@@ -0,0 +1,252 @@
+//  PEM.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:38:26
+//  Last edited:
+//    16 Oct 2026, 16:57:08
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a resolver for public keys stored as PEM- or DER-files.
+//
+
+use std::convert::Infallible;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use http::StatusCode;
+use jsonwebtoken::{Algorithm, DecodingKey, Header};
+use serde_json::{Value, json};
+use specifications::authresolver::HttpError;
+use thiserror::Error;
+use tracing::{Level, debug, span, warn};
+
+use super::KeyResolver;
+use crate::KeyResolveErrorWrapper;
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`PemResolver`] which are the server's fault.
+#[derive(Debug, Error)]
+pub enum ServerError {
+    /// Failed to read a key file to memory.
+    #[error("Failed to read key file {:?}", path.display())]
+    FileRead {
+        path: PathBuf,
+        #[source]
+        err:  std::io::Error,
+    },
+    /// The key file has no name we can use as key ID.
+    #[error("Cannot derive a key ID from key file {:?}", path.display())]
+    KeyNoId { path: PathBuf },
+    /// The key file did not contain a supported public key.
+    #[error("Key file {:?} does not contain an RSA, EC or Ed25519 public key", path.display())]
+    KeyParse {
+        path: PathBuf,
+        #[source]
+        err:  jsonwebtoken::errors::Error,
+    },
+}
+impl From<ServerError> for crate::authresolver::ServerError {
+    #[inline]
+    fn from(value: ServerError) -> Self { Self::KeyResolve { err: Box::new(value) } }
+}
+
+/// Defines the errors originating from the [`PemResolver`] which are the client's fault.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// There is no key that can verify a JWT signed with the given algorithm.
+    #[error("No key known for algorithm {alg:?}")]
+    NoKeyForAlgorithm { alg: Algorithm },
+    /// The suggested key ID wasn't found in the given JWT.
+    #[error("Unknown key with ID {kid:?}")]
+    UnknownKeyId { kid: String },
+}
+impl HttpError for ClientError {
+    #[inline]
+    fn status_code(&self) -> StatusCode {
+        use ClientError::*;
+        match self {
+            NoKeyForAlgorithm { .. } => StatusCode::UNAUTHORIZED,
+            UnknownKeyId { .. } => StatusCode::NOT_FOUND,
+        }
+    }
+
+    #[inline]
+    fn body(&self) -> Value {
+        use ClientError::*;
+        match self {
+            NoKeyForAlgorithm { alg } => json!({ "alg": alg }),
+            UnknownKeyId { kid } => json!({ "kid": kid }),
+        }
+    }
+}
+impl From<ClientError> for crate::authresolver::ClientError {
+    #[inline]
+    fn from(value: ClientError) -> Self { Self::KeyResolve { err: KeyResolveErrorWrapper(Box::new(value)) } }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// The kinds of public keys supported by the [`PemResolver`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KeyKind {
+    /// An RSA key, used for the `RS*` and `PS*` algorithms.
+    Rsa,
+    /// An elliptic curve key, used for the `ES*` algorithms.
+    Ec,
+    /// An Ed25519 key, used for the `EdDSA` algorithm.
+    Ed,
+}
+impl KeyKind {
+    /// Returns the kind of key needed to verify JWTs signed with the given algorithm.
+    ///
+    /// # Arguments
+    /// - `alg`: The [`Algorithm`] to find the kind of key for.
+    ///
+    /// # Returns
+    /// The matching KeyKind, or [`None`] if the algorithm is symmetric.
+    #[inline]
+    pub fn for_algorithm(alg: Algorithm) -> Option<Self> {
+        use Algorithm::*;
+        match alg {
+            RS256 | RS384 | RS512 | PS256 | PS384 | PS512 => Some(Self::Rsa),
+            ES256 | ES384 => Some(Self::Ec),
+            EdDSA => Some(Self::Ed),
+            HS256 | HS384 | HS512 => None,
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Resolves public keys loaded from PEM- or DER-files.
+///
+/// Every key is identified by the stem of the file it was loaded from (e.g., `keys/prod.pem` has
+/// key ID `prod`). If a JWT carries a key ID, only that key is used; otherwise, all keys of the
+/// right kind for the JWT's algorithm are tried.
+pub struct PemResolver {
+    /// The keys, together with their ID and kind.
+    keys: Vec<(String, KeyKind, DecodingKey)>,
+}
+impl PemResolver {
+    /// Constructor for the PemResolver.
+    ///
+    /// # Arguments
+    /// - `paths`: The paths of the PEM-files to load. Each must contain one RSA, EC or Ed25519
+    ///   public key.
+    ///
+    /// # Returns
+    /// A new PemResolver that can resolve the given keys.
+    ///
+    /// # Errors
+    /// This function can fail if it failed to read any of the files or if any of them did not
+    /// contain a supported public key.
+    pub fn new(paths: impl IntoIterator<Item = impl AsRef<Path>>) -> Result<Self, ServerError> {
+        let _span = span!(Level::INFO, "PemResolver::new");
+
+        let mut res = Self { keys: Vec::new() };
+        for path in paths {
+            let path: &Path = path.as_ref();
+            let raw: Vec<u8> = fs::read(path).map_err(|err| ServerError::FileRead { path: path.into(), err })?;
+
+            // Try the kinds of keys one-by-one
+            let (kind, key): (KeyKind, DecodingKey) = match DecodingKey::from_rsa_pem(&raw) {
+                Ok(key) => (KeyKind::Rsa, key),
+                Err(_) => match DecodingKey::from_ec_pem(&raw) {
+                    Ok(key) => (KeyKind::Ec, key),
+                    Err(_) => match DecodingKey::from_ed_pem(&raw) {
+                        Ok(key) => (KeyKind::Ed, key),
+                        Err(err) => return Err(ServerError::KeyParse { path: path.into(), err }),
+                    },
+                },
+            };
+            res.push(path, kind, key)?;
+        }
+        debug!("Loaded {} key(s)", res.keys.len());
+        Ok(res)
+    }
+
+    /// Adds a DER-encoded public key to the resolver.
+    ///
+    /// Unlike PEM, DER does not tell us what kind of key it is, so it has to be given.
+    ///
+    /// # Arguments
+    /// - `path`: The path of the DER-file to load.
+    /// - `kind`: The [`KeyKind`] of the key in it.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    ///
+    /// # Errors
+    /// This function can fail if it failed to read the file.
+    pub fn with_der(mut self, path: impl AsRef<Path>, kind: KeyKind) -> Result<Self, ServerError> {
+        let path: &Path = path.as_ref();
+        let raw: Vec<u8> = fs::read(path).map_err(|err| ServerError::FileRead { path: path.into(), err })?;
+        let key: DecodingKey = match kind {
+            KeyKind::Rsa => DecodingKey::from_rsa_der(&raw),
+            KeyKind::Ec => DecodingKey::from_ec_der(&raw),
+            KeyKind::Ed => DecodingKey::from_ed_der(&raw),
+        };
+        self.push(path, kind, key)?;
+        Ok(self)
+    }
+
+    /// Stores a key under the ID derived from its path.
+    fn push(&mut self, path: &Path, kind: KeyKind, key: DecodingKey) -> Result<(), ServerError> {
+        let kid: String = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(kid) => kid.into(),
+            None => return Err(ServerError::KeyNoId { path: path.into() }),
+        };
+        debug!("Key {kid:?}: {kind:?}");
+        if self.keys.iter().any(|(id, _, _)| *id == kid) {
+            warn!("Found duplicate key with ID {kid:?}");
+        }
+        self.keys.push((kid, kind, key));
+        Ok(())
+    }
+}
+impl KeyResolver for PemResolver {
+    type ClientError = ClientError;
+    type ServerError = Infallible;
+
+
+    fn resolve_key(&self, header: &Header) -> impl Send + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>> {
+        async move { Ok(self.resolve_keys(header).await?.map(|mut keys| keys.swap_remove(0))) }
+    }
+
+    fn resolve_keys(&self, header: &Header) -> impl Send + Future<Output = Result<Result<Vec<DecodingKey>, Self::ClientError>, Self::ServerError>> {
+        async move {
+            let _span = span!(Level::INFO, "PemResolver::resolve_keys");
+
+            // If there's a key ID, use that one only
+            let kind: Option<KeyKind> = KeyKind::for_algorithm(header.alg);
+            if let Some(kid) = &header.kid {
+                return match self.keys.iter().rev().find(|(id, k, _)| id == kid && Some(*k) == kind) {
+                    Some((_, _, key)) => {
+                        debug!("Resolved key with ID {kid:?}");
+                        Ok(Ok(vec![key.clone()]))
+                    },
+                    None => Ok(Err(ClientError::UnknownKeyId { kid: kid.clone() })),
+                };
+            }
+
+            // Otherwise, try all that make sense
+            let keys: Vec<DecodingKey> = self.keys.iter().filter(|(_, k, _)| Some(*k) == kind).map(|(_, _, key)| key.clone()).collect();
+            if keys.is_empty() {
+                return Ok(Err(ClientError::NoKeyForAlgorithm { alg: header.alg }));
+            }
+            debug!("Resolved {} candidate key(s) for {:?}", keys.len(), header.alg);
+            Ok(Ok(keys))
+        }
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 10:37:34
 //  Last edited:
-//    23 Oct 2024, 10:59:20
+//    16 Oct 2026, 18:08:08
 //  Auto updated?
 //    Yes
 //
@@ -15,7 +15,10 @@
 
 // Modules
 mod authresolver;
+mod issuers;
 pub mod keyresolver;
+pub mod revocation;
 
 // Use some of it into the main namespace
 pub use authresolver::*;
+pub use issuers::*;
@@ -0,0 +1,107 @@
+//  DATABASE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:48:23
+//  Last edited:
+//    16 Oct 2026, 16:48:23
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a revocation list that is stored in the policy store's own
+//!   database.
+//
+
+use std::error::Error;
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+use specifications::databaseconn::{DatabaseConnection, DatabaseConnector};
+use specifications::metadata::User;
+use thiserror::Error;
+use tracing::{Level, debug, span};
+
+use super::RevocationList;
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`DatabaseRevocationList`] which are the server's fault.
+#[derive(Debug, Error)]
+pub enum ServerError {
+    /// Failed to connect to the backend database.
+    #[error("Failed to connect to the backend database")]
+    Connect {
+        #[source]
+        err: Box<dyn 'static + Error>,
+    },
+    /// Failed to check the revocations in the backend database.
+    #[error("Failed to check whether tokens of user {user:?} are revoked in the backend database")]
+    IsRevoked {
+        user: String,
+        #[source]
+        err:  Box<dyn 'static + Error>,
+    },
+}
+impl From<ServerError> for crate::authresolver::ServerError {
+    #[inline]
+    fn from(value: ServerError) -> Self { Self::RevocationCheck { err: Box::new(value) } }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Looks up revoked tokens in the policy store's own database.
+///
+/// Tokens are revoked through the store's revocation endpoints, and take effect immediately.
+pub struct DatabaseRevocationList<D> {
+    /// The database to look revocations up in.
+    data: D,
+    /// The user on who's behalf we connect to the database.
+    user: User,
+}
+impl<D> DatabaseRevocationList<D> {
+    /// Constructor for the DatabaseRevocationList.
+    ///
+    /// # Arguments
+    /// - `data`: The [`DatabaseConnector`] to look revocations up in.
+    /// - `user`: The (service) [`User`] on who's behalf we connect to the database.
+    ///
+    /// # Returns
+    /// A new DatabaseRevocationList.
+    #[inline]
+    pub const fn new(data: D, user: User) -> Self { Self { data, user } }
+
+    /// Returns the database this list looks revocations up in.
+    ///
+    /// # Returns
+    /// A reference to the internal [`DatabaseConnector`].
+    #[inline]
+    pub const fn data(&self) -> &D { &self.data }
+}
+impl<D> RevocationList for DatabaseRevocationList<D>
+where
+    D: Sync + DatabaseConnector,
+    D::Error: 'static,
+    for<'s> D::Connection<'s>: Send,
+    for<'s> <D::Connection<'s> as DatabaseConnection>::Error: 'static,
+{
+    type Error = ServerError;
+
+
+    fn is_revoked(&self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "DatabaseRevocationList::is_revoked", user = user);
+
+            let mut conn = self.data.connect(&self.user).await.map_err(|err| ServerError::Connect { err: Box::new(err) })?;
+            let revoked: bool =
+                conn.is_revoked(jti, user, issued).await.map_err(|err| ServerError::IsRevoked { user: user.into(), err: Box::new(err) })?;
+            if revoked {
+                debug!("Token {jti:?} of user {user:?} has been revoked");
+            }
+            Ok(revoked)
+        }
+    }
+}
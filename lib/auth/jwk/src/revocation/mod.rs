@@ -0,0 +1,70 @@
+//  REVOCATION.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:48:23
+//  Last edited:
+//    16 Oct 2026, 16:48:23
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Provides lists of revoked tokens that the [`JwkResolver`](crate::JwkResolver)
+//!   checks before accepting a token.
+//
+
+// Modules
+#[cfg(feature = "database")]
+pub mod database;
+
+// Imports
+use std::convert::Infallible;
+use std::error::Error;
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "database")]
+pub use database::DatabaseRevocationList;
+
+
+/***** LIBRARY *****/
+/// The trait implemented by various backends that know which tokens are revoked.
+///
+/// Like the [`KeyResolver`](crate::keyresolver::KeyResolver), any reference to `self` is done
+/// immutably only.
+pub trait RevocationList {
+    /// The errors produced by the RevocationList.
+    type Error: Error;
+
+
+    /// Checks whether a (validly signed) token has been revoked.
+    ///
+    /// # Arguments
+    /// - `jti`: The identifier of the token (i.e., its `jti` claim), if it has any.
+    /// - `user`: The identifier of the user the token belongs to.
+    /// - `issued`: The time the token was issued (i.e., its `iat` claim), if known.
+    ///
+    /// # Returns
+    /// True if the token must be rejected, or false otherwise.
+    ///
+    /// # Errors
+    /// This function may error if we failed to find out whether the token is revoked. This will
+    /// always result in a (vague) 500 INTERNAL SERVER ERROR to the user.
+    fn is_revoked(&self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>>;
+}
+
+
+
+/// A [`RevocationList`] that doesn't revoke anything.
+///
+/// This is the default for the [`JwkResolver`](crate::JwkResolver).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoRevocations;
+impl RevocationList for NoRevocations {
+    type Error = Infallible;
+
+    #[inline]
+    fn is_revoked(&self, _jti: Option<&str>, _user: &str, _issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        async move { Ok(false) }
+    }
+}
@@ -0,0 +1,149 @@
+//  CACHE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:39:28
+//  Last edited:
+//    17 Oct 2026, 02:29:12
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the [`CachedKeyResolver`].
+//
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use http::StatusCode;
+use jsonwebtoken::{Algorithm, DecodingKey, Header};
+use jwk_auth::keyresolver::cache::{ClientError, ServerError};
+use jwk_auth::keyresolver::{CachedKeyResolver, KeyResolver};
+use specifications::authresolver::HttpError;
+use thiserror::Error;
+
+
+/***** HELPERS *****/
+/// The error returned by the [`CountingResolver`] for unknown keys.
+#[derive(Debug, Error)]
+#[error("Unknown key")]
+struct UnknownKey;
+impl HttpError for UnknownKey {
+    #[inline]
+    fn status_code(&self) -> StatusCode { StatusCode::NOT_FOUND }
+}
+
+/// The error returned by the [`CountingResolver`] for key `down`.
+#[derive(Debug, Error)]
+#[error("Backend down")]
+struct BackendDown;
+
+/// A resolver that knows key `good`, fails for key `down`, resolves key `empty` to nothing and
+/// counts how often it's asked.
+#[derive(Default)]
+struct CountingResolver {
+    calls: AtomicUsize,
+}
+impl KeyResolver for CountingResolver {
+    type ClientError = UnknownKey;
+    type ServerError = BackendDown;
+
+    fn resolve_key(&self, _header: &Header) -> impl Send + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>> {
+        async move { unreachable!() }
+    }
+
+    fn resolve_keys(&self, header: &Header) -> impl Send + Future<Output = Result<Result<Vec<DecodingKey>, Self::ClientError>, Self::ServerError>> {
+        async move {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match header.kid.as_deref() {
+                Some("good") => Ok(Ok(vec![DecodingKey::from_secret(b"secret")])),
+                Some("down") => Err(BackendDown),
+                Some("empty") => Ok(Ok(vec![])),
+                _ => Ok(Err(UnknownKey)),
+            }
+        }
+    }
+}
+
+/// Returns how often the resolver nested in the given one was called.
+fn resolver_calls(resolver: &CachedKeyResolver<CountingResolver>) -> usize { resolver.resolver().calls.load(Ordering::SeqCst) }
+
+/// Returns a header with the given key ID.
+fn header(kid: Option<&str>) -> Header { Header { kid: kid.map(String::from), ..Default::default() } }
+
+/// Returns a header with the given key ID and algorithm.
+fn header_alg(kid: &str, alg: Algorithm) -> Header { Header { kid: Some(kid.into()), ..Header::new(alg) } }
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_positive() {
+    let resolver = CachedKeyResolver::new(CountingResolver::default());
+    for _ in 0..3 {
+        assert!(resolver.resolve_key(&header(Some("good"))).await.unwrap().is_ok());
+    }
+    assert_eq!(resolver_calls(&resolver), 1);
+
+    // Clearing forgets it, as does invalidating it
+    resolver.clear();
+    assert!(resolver.resolve_key(&header(Some("good"))).await.unwrap().is_ok());
+    assert_eq!(resolver_calls(&resolver), 2);
+    resolver.invalidate();
+    assert!(resolver.resolve_key(&header(Some("good"))).await.unwrap().is_ok());
+    assert_eq!(resolver_calls(&resolver), 3);
+}
+
+#[tokio::test]
+async fn test_per_algorithm() {
+    let resolver = CachedKeyResolver::new(CountingResolver::default());
+    assert!(resolver.resolve_key(&header_alg("good", Algorithm::HS256)).await.unwrap().is_ok());
+    assert!(resolver.resolve_key(&header_alg("good", Algorithm::HS512)).await.unwrap().is_ok());
+    assert!(resolver.resolve_key(&header_alg("good", Algorithm::HS256)).await.unwrap().is_ok());
+    assert_eq!(resolver_calls(&resolver), 2);
+}
+
+#[tokio::test]
+async fn test_client_errors_not_cached() {
+    let resolver = CachedKeyResolver::new(CountingResolver::default());
+    for _ in 0..2 {
+        let err = resolver.resolve_key(&header(Some("bad"))).await.unwrap().err().unwrap();
+        assert!(matches!(err, ClientError::Inner(UnknownKey)));
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+    }
+    assert_eq!(resolver_calls(&resolver), 2);
+}
+
+#[tokio::test]
+async fn test_server_errors_cached() {
+    let resolver = CachedKeyResolver::new(CountingResolver::default());
+    let err = resolver.resolve_key(&header(Some("down"))).await.err().unwrap();
+    assert!(matches!(err, ServerError::Inner(BackendDown)));
+    let err = resolver.resolve_key(&header(Some("down"))).await.err().unwrap();
+    assert!(matches!(err, ServerError::Cached { .. }));
+    assert_eq!(resolver_calls(&resolver), 1);
+
+    // Unless disabled
+    let resolver = CachedKeyResolver::new(CountingResolver::default()).with_negative_ttl(Duration::ZERO);
+    assert!(resolver.resolve_key(&header(Some("down"))).await.is_err());
+    assert!(resolver.resolve_key(&header(Some("down"))).await.is_err());
+    assert_eq!(resolver_calls(&resolver), 2);
+}
+
+#[tokio::test]
+async fn test_no_keys() {
+    let resolver = CachedKeyResolver::new(CountingResolver::default());
+    let err = resolver.resolve_key(&header(Some("empty"))).await.unwrap().err().unwrap();
+    assert!(matches!(err, ClientError::NoKeys { .. }));
+}
+
+#[tokio::test]
+async fn test_no_kid() {
+    let resolver = CachedKeyResolver::new(CountingResolver::default());
+    assert!(resolver.resolve_keys(&header(None)).await.unwrap().is_err());
+    assert!(resolver.resolve_keys(&header(None)).await.unwrap().is_err());
+    assert_eq!(resolver_calls(&resolver), 2);
+}
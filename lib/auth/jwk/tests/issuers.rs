@@ -0,0 +1,97 @@
+//  ISSUERS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:08:08
+//  Last edited:
+//    16 Oct 2026, 18:08:08
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the [`MultiIssuerResolver`] dispatches JWTs to the
+//!   resolvers of their issuers.
+//
+
+use std::convert::Infallible;
+use std::future::Future;
+
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header};
+use jwk_auth::keyresolver::KeyResolver;
+use jwk_auth::{ClientError, JwkResolver, MultiIssuerResolver};
+use serde_json::{Value, json};
+use specifications::AuthResolver as _;
+use specifications::authresolver::HttpError as _;
+
+
+/***** HELPERS *****/
+/// A resolver that verifies everything with the given secret.
+struct SecretResolver(&'static [u8]);
+impl KeyResolver for SecretResolver {
+    type ClientError = Infallible;
+    type ServerError = Infallible;
+
+    fn resolve_key(&self, _header: &Header) -> impl Send + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>> {
+        async move { Ok(Ok(DecodingKey::from_secret(self.0))) }
+    }
+}
+
+/// Returns headers carrying a token with the given claims, signed with the given secret.
+fn bearer(secret: &[u8], claims: Value) -> HeaderMap {
+    let mut claims = claims;
+    claims["exp"] = json!(u32::MAX);
+    let token = jsonwebtoken::encode(&Header::default(), &claims, &EncodingKey::from_secret(secret)).unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}")).unwrap());
+    headers
+}
+
+/// Returns a resolver trusting two organizations, which identify users by different claims.
+fn resolver() -> MultiIssuerResolver<SecretResolver> {
+    MultiIssuerResolver::new()
+        .with_issuer("https://idp.a.example.com", JwkResolver::new("sub", SecretResolver(b"secret-a")))
+        .with_issuer("https://idp.b.example.com", JwkResolver::new("preferred_username", SecretResolver(b"secret-b")).with_role_claims(["groups"]))
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_dispatch_on_issuer() {
+    let resolver = resolver();
+
+    let user = resolver.authorize(&bearer(b"secret-a", json!({ "iss": "https://idp.a.example.com", "sub": "amy" }))).await.unwrap().unwrap();
+    assert_eq!(user.id, "amy");
+    assert_eq!(user.issuer.as_deref(), Some("https://idp.a.example.com"));
+
+    let user = resolver
+        .authorize(&bearer(b"secret-b", json!({ "iss": "https://idp.b.example.com", "preferred_username": "bob", "groups": ["admin"] })))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.id, "bob");
+    assert_eq!(user.issuer.as_deref(), Some("https://idp.b.example.com"));
+    assert_eq!(user.roles, vec!["admin".to_string()]);
+}
+
+#[tokio::test]
+async fn test_untrusted_issuer() {
+    let resolver = resolver();
+    for claims in [json!({ "iss": "https://idp.c.example.com", "sub": "amy" }), json!({ "sub": "amy" })] {
+        let err = resolver.authorize(&bearer(b"secret-a", claims)).await.unwrap().unwrap_err();
+        assert!(matches!(err, ClientError::JwtUnknownIssuer { .. }), "Expected an untrusted issuer, got {err:?}");
+        assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test]
+async fn test_issuer_keys_are_not_shared() {
+    // Organization B cannot sign tokens claiming to come from organization A
+    let resolver = resolver();
+    let err = resolver.authorize(&bearer(b"secret-b", json!({ "iss": "https://idp.a.example.com", "sub": "amy" }))).await.unwrap().unwrap_err();
+    assert!(matches!(err, ClientError::JwtValidate { .. }), "Expected an invalid token, got {err:?}");
+}
@@ -0,0 +1,101 @@
+//  REVOCATION.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:48:23
+//  Last edited:
+//    16 Oct 2026, 16:48:23
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the [`JwkResolver`] checks its [`RevocationList`].
+//
+
+use std::convert::Infallible;
+use std::future::Future;
+
+use chrono::{DateTime, TimeZone as _, Utc};
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header};
+use jwk_auth::keyresolver::KeyResolver;
+use jwk_auth::revocation::RevocationList;
+use jwk_auth::{ClientError, JwkResolver};
+use serde_json::{Value, json};
+use specifications::AuthResolver as _;
+use specifications::authresolver::HttpError as _;
+
+
+/***** HELPERS *****/
+/// A resolver that verifies everything with the same secret.
+struct SecretResolver;
+impl KeyResolver for SecretResolver {
+    type ClientError = Infallible;
+    type ServerError = Infallible;
+
+    fn resolve_key(&self, _header: &Header) -> impl Send + Future<Output = Result<Result<DecodingKey, Self::ClientError>, Self::ServerError>> {
+        async move { Ok(Ok(DecodingKey::from_secret(b"secret"))) }
+    }
+}
+
+/// A revocation list that revokes token `revoked`, and all tokens of `amy` issued before noon.
+struct StaticRevocations;
+impl RevocationList for StaticRevocations {
+    type Error = Infallible;
+
+    fn is_revoked(&self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        async move {
+            let noon = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+            Ok(jti == Some("revoked") || (user == "amy" && issued.map_or(true, |issued| issued < noon)))
+        }
+    }
+}
+
+/// Returns headers carrying a token with the given claims.
+fn bearer(claims: Value) -> HeaderMap {
+    let mut claims = claims;
+    claims["exp"] = json!(u32::MAX);
+    let token = jsonwebtoken::encode(&Header::default(), &claims, &EncodingKey::from_secret(b"secret")).unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}")).unwrap());
+    headers
+}
+
+/// Asserts that the resolver rejects the given headers because the token is revoked.
+async fn assert_revoked(resolver: &JwkResolver<SecretResolver, StaticRevocations>, headers: &HeaderMap) {
+    let err = resolver.authorize(headers).await.unwrap().unwrap_err();
+    assert!(matches!(err, ClientError::JwtRevoked { .. }), "Expected a revoked token, got {err:?}");
+    assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_no_revocations() {
+    let resolver = JwkResolver::new("sub", SecretResolver);
+    let user = resolver.authorize(&bearer(json!({ "sub": "amy", "jti": "revoked" }))).await.unwrap().unwrap();
+    assert_eq!(user.id, "amy");
+}
+
+#[tokio::test]
+async fn test_revoked_token() {
+    let resolver = JwkResolver::new("sub", SecretResolver).with_revocations(StaticRevocations);
+    assert_revoked(&resolver, &bearer(json!({ "sub": "bob", "jti": "revoked" }))).await;
+    let user = resolver.authorize(&bearer(json!({ "sub": "bob", "jti": "other" }))).await.unwrap().unwrap();
+    assert_eq!(user.id, "bob");
+}
+
+#[tokio::test]
+async fn test_revoked_user() {
+    let resolver = JwkResolver::new("sub", SecretResolver).with_revocations(StaticRevocations);
+    let before = Utc.with_ymd_and_hms(2026, 10, 16, 11, 0, 0).unwrap().timestamp();
+    let after = Utc.with_ymd_and_hms(2026, 10, 16, 13, 0, 0).unwrap().timestamp();
+    assert_revoked(&resolver, &bearer(json!({ "sub": "amy", "iat": before }))).await;
+    assert_revoked(&resolver, &bearer(json!({ "sub": "amy" }))).await;
+    let user = resolver.authorize(&bearer(json!({ "sub": "amy", "iat": after }))).await.unwrap().unwrap();
+    assert_eq!(user.id, "amy");
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    24 Oct 2024, 13:50:43
 //  Last edited:
-//    24 Oct 2024, 14:01:06
+//    16 Oct 2026, 22:45:19
 //  Auto updated?
 //    Yes
 //
@@ -23,7 +23,10 @@ use specifications::metadata::User;
 /***** LIBRARY *****/
 /// Defines an [`AuthResolver`] that doesn't authorize people whatsoever.
 #[derive(Clone, Copy, Debug)]
-pub struct NoOpResolver;
+pub struct NoOpResolver {
+    /// The roles given to every user.
+    roles: &'static [&'static str],
+}
 impl Default for NoOpResolver {
     #[inline]
     fn default() -> Self { Self::new() }
@@ -34,7 +37,20 @@ impl NoOpResolver {
     /// # Returns
     /// A new NoOpResolver ready to do absolutely nothing.
     #[inline]
-    pub const fn new() -> Self { Self }
+    pub const fn new() -> Self { Self { roles: &[] } }
+
+    /// Gives every user the given roles.
+    ///
+    /// # Arguments
+    /// - `roles`: The roles to give. Replaces any given before.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub const fn with_roles(mut self, roles: &'static [&'static str]) -> Self {
+        self.roles = roles;
+        self
+    }
 }
 impl AuthResolver for NoOpResolver {
     type Context = User;
@@ -43,6 +59,7 @@ impl AuthResolver for NoOpResolver {
 
     #[inline]
     fn authorize(&self, _headers: &HeaderMap) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>> {
-        async move { Ok(Ok(User { id: "johnsmith".into(), name: "John Smith".into() })) }
+        let roles: Vec<String> = self.roles.iter().map(|role| (*role).into()).collect();
+        async move { Ok(Ok(User { id: "johnsmith".into(), name: "John Smith".into(), issuer: None, roles })) }
     }
 }
@@ -0,0 +1,217 @@
+//  AUTHRESOLVER.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:53:22
+//  Last edited:
+//    16 Oct 2026, 16:57:08
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Provides the actual [`AuthResolver`] implementation.
+//
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs::File;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use http::{HeaderMap, HeaderName, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use specifications::AuthResolver;
+use specifications::authresolver::HttpError;
+use specifications::metadata::User;
+use thiserror::Error;
+use tracing::{Level, debug, info, span};
+
+
+/***** ERRORS *****/
+/// Defines errors that occur when loading a [`UserDirectory`].
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The same user was listed twice.
+    #[error("User {id:?} is listed more than once in user directory {path:?}")]
+    DuplicateUser { path: PathBuf, id: String },
+    /// Failed to open the user directory file.
+    #[error("Failed to open user directory file {path:?}")]
+    FileOpen {
+        path: PathBuf,
+        #[source]
+        err:  std::io::Error,
+    },
+    /// Failed to deserialize the user directory file.
+    #[error("Failed to deserialize user directory file {path:?}")]
+    FileDeserialize {
+        path: PathBuf,
+        #[source]
+        err:  serde_json::Error,
+    },
+}
+
+/// Represents client-side errors which the server can't fix.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The user header did not contain valid UTF-8.
+    #[error("Value of header {header:?} in request is non-UTF-8")]
+    HeaderNonUtf8 {
+        header: HeaderName,
+        #[source]
+        err:    http::header::ToStrError,
+    },
+    /// No user header found in request.
+    #[error("Missing header {header:?} in request")]
+    HeaderNotFound { header: HeaderName },
+    /// The user in the header isn't in the directory.
+    #[error("Unknown user {user:?} in header {header:?}")]
+    UnknownUser { header: HeaderName, user: String },
+}
+impl HttpError for ClientError {
+    #[inline]
+    fn status_code(&self) -> StatusCode {
+        use ClientError::*;
+        match self {
+            HeaderNonUtf8 { .. } | HeaderNotFound { .. } => StatusCode::BAD_REQUEST,
+            UnknownUser { .. } => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    fn body(&self) -> Value {
+        use ClientError::*;
+        match self {
+            HeaderNonUtf8 { header, .. } | HeaderNotFound { header } => json!({ "header": header.as_str() }),
+            UnknownUser { header, user } => json!({ "header": header.as_str(), "user": user }),
+        }
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Defines the layout of a user directory file.
+///
+/// For example:
+/// ```json
+/// {
+///     "users": [
+///         { "id": "amy", "name": "Amy", "roles": ["admin"] },
+///         { "id": "bob", "name": "Bob" }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserDirectory {
+    /// The users that are allowed in.
+    pub users: Vec<User>,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Authorizes HTTP requests by reading the user's identifier from a header, and looking them up in
+/// a static directory of users.
+///
+/// This is a safer middle ground than the `NoOpResolver` for internal networks, as it rejects
+/// unknown users. Note, however, that it does **not** authenticate anyone: any client that can
+/// reach the server can claim to be any user in the directory. Only use it behind a trusted proxy
+/// that sets (and overwrites) the header itself.
+#[derive(Clone, Debug)]
+pub struct StaticUserResolver {
+    /// The header to read the user's identifier from.
+    header: HeaderName,
+    /// The users that are allowed in, by identifier.
+    users:  HashMap<String, User>,
+}
+impl StaticUserResolver {
+    /// Constructor for the StaticUserResolver.
+    ///
+    /// By default, the user's identifier is read from the `X-User-Id` header. See
+    /// [`StaticUserResolver::with_header()`] to change this.
+    ///
+    /// # Arguments
+    /// - `users`: The [`User`]s that are allowed in. If an identifier occurs more than once, the
+    ///   last user wins.
+    ///
+    /// # Returns
+    /// A new StaticUserResolver that only accepts the given `users`.
+    #[inline]
+    pub fn new(users: impl IntoIterator<Item = User>) -> Self {
+        Self { header: HeaderName::from_static("x-user-id"), users: users.into_iter().map(|user| (user.id.clone(), user)).collect() }
+    }
+
+    /// Constructor for the StaticUserResolver that reads its users from a [`UserDirectory`] file.
+    ///
+    /// # Arguments
+    /// - `path`: The path to the JSON file listing the users.
+    ///
+    /// # Returns
+    /// A new StaticUserResolver that only accepts the users in the file.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read or parse the file, or if it lists a user twice.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path: &Path = path.as_ref();
+        let _span = span!(Level::INFO, "StaticUserResolver::from_path", path = path.display().to_string());
+
+        // Read the file
+        let handle: File = File::open(path).map_err(|err| Error::FileOpen { path: path.into(), err })?;
+        let dir: UserDirectory = serde_json::from_reader(handle).map_err(|err| Error::FileDeserialize { path: path.into(), err })?;
+
+        // Index the users, refusing duplicates as they're probably a mistake
+        let mut users: HashMap<String, User> = HashMap::with_capacity(dir.users.len());
+        for user in dir.users {
+            debug!("Found user {:?}", user.id);
+            if users.contains_key(&user.id) {
+                return Err(Error::DuplicateUser { path: path.into(), id: user.id });
+            }
+            users.insert(user.id.clone(), user);
+        }
+        info!("Loaded {} user(s) from {:?}", users.len(), path.display());
+        Ok(Self { header: HeaderName::from_static("x-user-id"), users })
+    }
+
+    /// Reads the user's identifier from another header.
+    ///
+    /// # Arguments
+    /// - `header`: The name of the header to read.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_header(mut self, header: HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Returns the users that are allowed in.
+    ///
+    /// # Returns
+    /// An iterator over the [`User`]s in the directory, in no particular order.
+    #[inline]
+    pub fn users(&self) -> impl Iterator<Item = &User> { self.users.values() }
+}
+impl AuthResolver for StaticUserResolver {
+    type Context = User;
+    type ClientError = ClientError;
+    type ServerError = Infallible;
+
+    fn authorize(&self, headers: &HeaderMap) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>> {
+        let res: Result<User, ClientError> = match headers.get(&self.header) {
+            Some(value) => match value.to_str() {
+                Ok(id) => match self.users.get(id) {
+                    Some(user) => Ok(user.clone()),
+                    None => Err(ClientError::UnknownUser { header: self.header.clone(), user: id.into() }),
+                },
+                Err(err) => Err(ClientError::HeaderNonUtf8 { header: self.header.clone(), err }),
+            },
+            None => Err(ClientError::HeaderNotFound { header: self.header.clone() }),
+        };
+        async move { Ok(res) }
+    }
+}
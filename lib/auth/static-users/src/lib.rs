@@ -0,0 +1,21 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:53:22
+//  Last edited:
+//    16 Oct 2026, 16:53:22
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements an [`AuthResolver`](specifications::AuthResolver) that
+//!   identifies users by a header set by a trusted proxy, and only
+//!   accepts those listed in a static user directory.
+//
+
+// Modules
+mod authresolver;
+
+// Use some of it into the main namespace
+pub use authresolver::*;
@@ -0,0 +1,89 @@
+//  STATIC USERS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:53:22
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the [`StaticUserResolver`].
+//
+
+use std::path::PathBuf;
+
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use specifications::AuthResolver as _;
+use specifications::authresolver::HttpError as _;
+use specifications::metadata::User;
+use static_users_auth::{ClientError, Error, StaticUserResolver};
+
+
+/***** HELPERS *****/
+/// Writes a user directory file with the given contents.
+///
+/// # Returns
+/// The path to the file.
+fn directory(name: &str, contents: &str) -> PathBuf {
+    let path: PathBuf = std::env::temp_dir().join(format!("policy-store-{name}-{}.json", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Returns headers carrying the given user ID in the given header.
+fn user_header(name: &'static str, id: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(HeaderName::from_static(name), HeaderValue::from_str(id).unwrap());
+    headers
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_static_users_known() {
+    let path = directory("static-known", r#"{ "users": [{ "id": "amy", "name": "Amy", "roles": ["admin"] }, { "id": "bob", "name": "Bob" }] }"#);
+    let auth = StaticUserResolver::from_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(auth.users().count(), 2);
+
+    let user: User = auth.authorize(&user_header("x-user-id", "amy")).await.unwrap().unwrap();
+    assert_eq!(user.id, "amy");
+    assert_eq!(user.name, "Amy");
+    assert_eq!(user.roles, vec!["admin".to_string()]);
+    let user: User = auth.authorize(&user_header("x-user-id", "bob")).await.unwrap().unwrap();
+    assert!(user.roles.is_empty());
+}
+
+#[tokio::test]
+async fn test_static_users_rejected() {
+    let auth = StaticUserResolver::new([User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() }]);
+
+    let err = auth.authorize(&user_header("x-user-id", "mallory")).await.unwrap().unwrap_err();
+    assert!(matches!(err, ClientError::UnknownUser { ref user, .. } if user == "mallory"));
+    assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+
+    let err = auth.authorize(&HeaderMap::new()).await.unwrap().unwrap_err();
+    assert!(matches!(err, ClientError::HeaderNotFound { .. }));
+    assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_static_users_custom_header() {
+    let auth = StaticUserResolver::new([User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() }])
+        .with_header(HeaderName::from_static("x-remote-user"));
+    assert!(auth.authorize(&user_header("x-remote-user", "amy")).await.unwrap().is_ok());
+    assert!(auth.authorize(&user_header("x-user-id", "amy")).await.unwrap().is_err());
+}
+
+#[test]
+fn test_static_users_duplicate() {
+    let path = directory("static-duplicate", r#"{ "users": [{ "id": "amy", "name": "Amy" }, { "id": "amy", "name": "Other Amy" }] }"#);
+    let res = StaticUserResolver::from_path(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(res, Err(Error::DuplicateUser { ref id, .. }) if id == "amy"));
+}
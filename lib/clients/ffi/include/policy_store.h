@@ -0,0 +1,86 @@
+/* POLICY_STORE.h
+ *   by Lut99
+ *
+ * Description:
+ *   C header for the `policy-store-ffi` library, which exposes the main
+ *   operations of the policy store client through a C ABI.
+ *
+ *   Link against `libpolicy_store_ffi.so` (or the static `libpolicy_store_ffi.a`).
+ *   Unless noted otherwise, errors are reported through the returned
+ *   `ps_status`, with a human-readable message in `ps_last_error()`.
+**/
+
+#ifndef POLICY_STORE_H
+#define POLICY_STORE_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+
+/***** TYPES *****/
+/* The result of every fallible operation. */
+typedef enum ps_status {
+    /* The operation succeeded. */
+    PS_OK = 0,
+    /* The operation succeeded, but there is no active policy. */
+    PS_NO_ACTIVE = 1,
+    /* One of the arguments was null or otherwise invalid. */
+    PS_INVALID_ARGUMENT = -1,
+    /* Failed to reach the server or to understand its reply. */
+    PS_TRANSPORT = -2,
+    /* The server replied with an error. */
+    PS_SERVER = -3,
+} ps_status;
+
+/* An opaque handle to a client connected to one policy store. */
+typedef struct ps_client ps_client;
+
+
+
+/***** ERRORS *****/
+/* Returns the message of the last error on the calling thread, or NULL if
+ * none occurred yet. Owned by the library; valid until the next call on this
+ * thread. */
+const char* ps_last_error(void);
+
+/* Frees a string returned by this library. `s` may be NULL. */
+void ps_string_free(char* s);
+
+
+
+/***** CLIENT *****/
+/* Creates a new client for the policy store at `base_url` (e.g.,
+ * "http://localhost:3030"), authenticating with bearer `token` unless it is
+ * NULL. Returns NULL on failure. Free with `ps_client_free()`. */
+ps_client* ps_client_new(const char* base_url, const char* token);
+
+/* Frees a client. `client` may be NULL. */
+void ps_client_free(ps_client* client);
+
+
+
+/***** OPERATIONS *****/
+/* Adds a new policy version to the default branch. `content` is a JSON
+ * document. On success, the assigned version is written to `version` unless
+ * it is NULL. */
+ps_status ps_add_version(const ps_client* client, const char* name, const char* description, const char* language, const char* content,
+                         uint64_t* version);
+
+/* Activates a policy version. */
+ps_status ps_activate(const ps_client* client, uint64_t version);
+
+/* Retrieves the contents of the active policy version as a JSON document,
+ * which must be freed with `ps_string_free()`. The active version is written
+ * to `version` unless it is NULL. Returns PS_NO_ACTIVE (leaving the outputs
+ * untouched) if no version is active. */
+ps_status ps_get_active_content(const ps_client* client, uint64_t* version, char** content);
+
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
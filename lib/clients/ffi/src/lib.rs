@@ -0,0 +1,322 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:57:13
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Exposes the main operations of the `reqwest-client` through a C ABI,
+//!   such that non-Rust components can push and retrieve policies.
+//!
+//!   See `include/policy_store.h` for the matching C header. With the
+//!   `python` feature, it also builds the `policy_store` Python module (see
+//!   `pyproject.toml`).
+//
+
+// Modules
+#[cfg(feature = "python")]
+mod python;
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+use error_trace::ErrorTrace as _;
+use reqwest_client::Error;
+use reqwest_client::blocking::Client;
+use serde_json::Value;
+use specifications::metadata::{AttachedMetadata, default_branch};
+
+
+/***** CONSTANTS *****/
+thread_local! {
+    /// The message of the last error that occurred on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Remembers an error as the last error of this thread.
+///
+/// # Arguments
+/// - `msg`: The message to remember.
+fn set_last_error(msg: impl Into<String>) {
+    // NOTE: Interior nul-bytes would make the message invalid, so we replace them
+    let msg: String = msg.into().replace('\0', "\\0");
+    // SAFETY: We just removed all nul-bytes
+    LAST_ERROR.with_borrow_mut(|last| *last = Some(CString::new(msg).unwrap()));
+}
+
+/// Converts a client error into a [`ps_status`], remembering it as the last error.
+///
+/// # Arguments
+/// - `err`: The [`Error`] to convert.
+///
+/// # Returns
+/// The matching [`ps_status`].
+fn status_of(err: Error) -> ps_status {
+    set_last_error(err.trace().to_string());
+    match err {
+        Error::Build { .. } | Error::Serialize { .. } => ps_status::PS_INVALID_ARGUMENT,
+        Error::CircuitOpen { .. } | Error::Request { .. } | Error::Response { .. } => ps_status::PS_TRANSPORT,
+        Error::Status { .. } => ps_status::PS_SERVER,
+    }
+}
+
+/// Reads a C string argument.
+///
+/// # Arguments
+/// - `what`: The name of the argument (for errors).
+/// - `arg`: The pointer to read.
+///
+/// # Returns
+/// The argument as a string, or [`None`] if it was null or not valid UTF-8 (in which case the
+/// last error is set).
+///
+/// # Safety
+/// `arg` must be null or point to a nul-terminated string.
+unsafe fn str_arg<'a>(what: &str, arg: *const c_char) -> Option<&'a str> {
+    if arg.is_null() {
+        set_last_error(format!("Argument {what:?} may not be null"));
+        return None;
+    }
+    match unsafe { CStr::from_ptr(arg) }.to_str() {
+        Ok(arg) => Some(arg),
+        Err(err) => {
+            set_last_error(format!("Argument {what:?} is not valid UTF-8: {err}"));
+            None
+        },
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// The result of every fallible operation.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub enum ps_status {
+    /// The operation succeeded.
+    PS_OK = 0,
+    /// The operation succeeded, but there is no active policy.
+    PS_NO_ACTIVE = 1,
+    /// One of the arguments was null or otherwise invalid.
+    PS_INVALID_ARGUMENT = -1,
+    /// Failed to reach the server or to understand its reply.
+    PS_TRANSPORT = -2,
+    /// The server replied with an error.
+    PS_SERVER = -3,
+}
+
+/// An opaque handle to a client connected to one policy store.
+#[allow(non_camel_case_types)]
+pub struct ps_client(Client);
+
+
+
+/// Returns the message of the last error that occurred on the calling thread.
+///
+/// # Returns
+/// A nul-terminated string, or null if no error occurred yet. It is owned by the library and
+/// remains valid until the next call on this thread.
+#[no_mangle]
+pub extern "C" fn ps_last_error() -> *const c_char { LAST_ERROR.with_borrow(|last| last.as_ref().map(|last| last.as_ptr()).unwrap_or(ptr::null())) }
+
+/// Frees a string returned by this library.
+///
+/// # Arguments
+/// - `s`: The string to free. May be null, in which case nothing happens.
+///
+/// # Safety
+/// `s` must be null or have been returned by this library, and must not be freed before.
+#[no_mangle]
+pub unsafe extern "C" fn ps_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+
+
+/// Creates a new client.
+///
+/// # Arguments
+/// - `base_url`: The base URL of the policy store (e.g., `http://localhost:3030`).
+/// - `token`: The bearer token to authenticate with. May be null to not authenticate.
+///
+/// # Returns
+/// A new client that must be freed with [`ps_client_free()`], or null if the arguments were
+/// invalid or the client could not be created (see [`ps_last_error()`]).
+///
+/// # Safety
+/// `base_url` and `token` must be null or point to nul-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn ps_client_new(base_url: *const c_char, token: *const c_char) -> *mut ps_client {
+    let Some(base_url) = (unsafe { str_arg("base_url", base_url) }) else { return ptr::null_mut() };
+    let mut client = reqwest_client::Client::new(base_url);
+    if !token.is_null() {
+        let Some(token) = (unsafe { str_arg("token", token) }) else { return ptr::null_mut() };
+        client = client.with_token(token);
+    }
+    match Client::new(client) {
+        Ok(client) => Box::into_raw(Box::new(ps_client(client))),
+        Err(err) => {
+            set_last_error(format!("Failed to create runtime: {err}"));
+            ptr::null_mut()
+        },
+    }
+}
+
+/// Frees a client.
+///
+/// # Arguments
+/// - `client`: The client to free. May be null, in which case nothing happens.
+///
+/// # Safety
+/// `client` must be null or have been returned by [`ps_client_new()`], and must not be freed before.
+#[no_mangle]
+pub unsafe extern "C" fn ps_client_free(client: *mut ps_client) {
+    if !client.is_null() {
+        drop(unsafe { Box::from_raw(client) });
+    }
+}
+
+
+
+/// Adds a new policy version to the default branch.
+///
+/// # Arguments
+/// - `client`: The client to send the request with.
+/// - `name`: Some name for the policy.
+/// - `description`: Some description of the policy.
+/// - `language`: The language the policy is written in.
+/// - `content`: The contents of the policy, as a JSON document.
+/// - `version`: Will be set to the version number assigned to the new policy. May be null.
+///
+/// # Returns
+/// [`ps_status::PS_OK`] on success, or an error status (see [`ps_last_error()`]).
+///
+/// # Safety
+/// `client` must be a valid client, the strings must be null or nul-terminated and `version`
+/// must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ps_add_version(
+    client: *const ps_client,
+    name: *const c_char,
+    description: *const c_char,
+    language: *const c_char,
+    content: *const c_char,
+    version: *mut u64,
+) -> ps_status {
+    let Some(client) = (unsafe { client.as_ref() }) else {
+        set_last_error("Argument \"client\" may not be null");
+        return ps_status::PS_INVALID_ARGUMENT;
+    };
+    let (Some(name), Some(description), Some(language), Some(content)) =
+        (unsafe { (str_arg("name", name), str_arg("description", description), str_arg("language", language), str_arg("content", content)) })
+    else {
+        return ps_status::PS_INVALID_ARGUMENT;
+    };
+    let content: Value = match serde_json::from_str(content) {
+        Ok(content) => content,
+        Err(err) => {
+            set_last_error(format!("Argument \"content\" is not valid JSON: {err}"));
+            return ps_status::PS_INVALID_ARGUMENT;
+        },
+    };
+
+    // Send it
+    let metadata = AttachedMetadata {
+        name: name.into(),
+        description: description.into(),
+        language: language.into(),
+        branch: default_branch(),
+        schema: None,
+        confidential: false,
+    };
+    match client.0.add_version(metadata, content) {
+        Ok(res) => {
+            if let Some(version) = unsafe { version.as_mut() } {
+                *version = res;
+            }
+            ps_status::PS_OK
+        },
+        Err(err) => status_of(err),
+    }
+}
+
+/// Activates a policy version.
+///
+/// # Arguments
+/// - `client`: The client to send the request with.
+/// - `version`: The version to activate.
+///
+/// # Returns
+/// [`ps_status::PS_OK`] on success, or an error status (see [`ps_last_error()`]).
+///
+/// # Safety
+/// `client` must be a valid client.
+#[no_mangle]
+pub unsafe extern "C" fn ps_activate(client: *const ps_client, version: u64) -> ps_status {
+    let Some(client) = (unsafe { client.as_ref() }) else {
+        set_last_error("Argument \"client\" may not be null");
+        return ps_status::PS_INVALID_ARGUMENT;
+    };
+    match client.0.activate(version) {
+        Ok(()) => ps_status::PS_OK,
+        Err(err) => status_of(err),
+    }
+}
+
+/// Retrieves the contents of the active policy version.
+///
+/// # Arguments
+/// - `client`: The client to send the requests with.
+/// - `version`: Will be set to the active version. May be null.
+/// - `content`: Will be set to the contents of the active version, as a JSON document that must
+///   be freed with [`ps_string_free()`].
+///
+/// # Returns
+/// [`ps_status::PS_OK`] on success, [`ps_status::PS_NO_ACTIVE`] if no version is active (in
+/// which case the outputs are left untouched), or an error status (see [`ps_last_error()`]).
+///
+/// # Safety
+/// `client` must be a valid client, `version` must be null or valid for writes and `content`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ps_get_active_content(client: *const ps_client, version: *mut u64, content: *mut *mut c_char) -> ps_status {
+    let Some(client) = (unsafe { client.as_ref() }) else {
+        set_last_error("Argument \"client\" may not be null");
+        return ps_status::PS_INVALID_ARGUMENT;
+    };
+    if content.is_null() {
+        set_last_error("Argument \"content\" may not be null");
+        return ps_status::PS_INVALID_ARGUMENT;
+    }
+
+    // Get it
+    let (active, res): (u64, Value) = match client.0.get_active_content() {
+        Ok(Some(res)) => res,
+        Ok(None) => return ps_status::PS_NO_ACTIVE,
+        Err(err) => return status_of(err),
+    };
+
+    // Write it
+    // SAFETY: Serializing JSON never fails, and never produces nul-bytes
+    let res: CString = CString::new(serde_json::to_string(&res).unwrap()).unwrap();
+    if let Some(version) = unsafe { version.as_mut() } {
+        *version = active;
+    }
+    unsafe { *content = res.into_raw() };
+    ps_status::PS_OK
+}
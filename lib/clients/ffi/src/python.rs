@@ -0,0 +1,182 @@
+//  PYTHON.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:59:14
+//  Last edited:
+//    16 Oct 2026, 16:06:50
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the `policy_store` Python module, for scripting the
+//!   administration of a policy store.
+//
+
+use std::collections::HashMap;
+
+use error_trace::ErrorTrace as _;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString};
+use reqwest_client::Error;
+use reqwest_client::blocking::Client;
+use serde::Serialize;
+use serde_json::Value;
+use specifications::canonical::canonicalize;
+use specifications::metadata::{AttachedMetadata, Metadata, default_branch};
+
+
+/***** ERRORS *****/
+create_exception!(policy_store, PolicyStoreError, PyException, "Raised when a request to the policy store fails.");
+
+/// Converts a client error into a [`PolicyStoreError`].
+///
+/// # Arguments
+/// - `err`: The [`Error`] to convert.
+///
+/// # Returns
+/// A [`PyErr`] carrying the full trace of `err`.
+fn to_py_err(err: Error) -> PyErr { PolicyStoreError::new_err(err.trace().to_string()) }
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Converts something serializable into a Python object by going through Python's `json` module.
+///
+/// # Arguments
+/// - `py`: The Python interpreter.
+/// - `value`: The value to convert.
+///
+/// # Returns
+/// The value as a (nested) Python dict, list or primitive.
+///
+/// # Errors
+/// This function errors if the value could not be serialized.
+fn to_python<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let value: String = serde_json::to_string(value).map_err(|err| PyValueError::new_err(format!("Failed to serialize value: {err}")))?;
+    py.import("json")?.call_method1("loads", (value,))
+}
+
+/// Converts a Python object into JSON by going through Python's `json` module.
+///
+/// # Arguments
+/// - `value`: The object to convert.
+///
+/// # Returns
+/// The object as a JSON [`Value`].
+///
+/// # Errors
+/// This function errors if the object is not JSON-serializable.
+fn from_python(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let value: String = value.py().import("json")?.call_method1("dumps", (value,))?.extract()?;
+    serde_json::from_str(&value).map_err(|err| PyValueError::new_err(format!("Failed to deserialize value: {err}")))
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A client connected to one policy store.
+///
+/// All requests block, but release the GIL while waiting for the server.
+#[pyclass(name = "Client", module = "policy_store", frozen)]
+pub struct PyClient(Client);
+
+#[pymethods]
+impl PyClient {
+    /// Creates a new client for the policy store at `base_url`, authenticating with bearer `token`
+    /// if given.
+    #[new]
+    #[pyo3(signature = (base_url, token = None))]
+    fn new(base_url: &str, token: Option<&str>) -> PyResult<Self> {
+        let mut client = reqwest_client::Client::new(base_url);
+        if let Some(token) = token {
+            client = client.with_token(token);
+        }
+        Client::new(client).map(Self).map_err(|err| PyRuntimeError::new_err(format!("Failed to create runtime: {err}")))
+    }
+
+
+
+    /// Returns a dict mapping every version to its metadata.
+    fn list<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let versions: HashMap<u64, Metadata> = py.detach(|| self.0.get_versions()).map_err(to_py_err)?;
+        let res: Bound<'py, PyDict> = PyDict::new(py);
+        for (version, metadata) in versions {
+            res.set_item(version, to_python(py, &metadata)?)?;
+        }
+        Ok(res)
+    }
+
+    /// Adds a new policy version with the given (JSON-serializable) content, returning its
+    /// version number.
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (name, description, language, content, branch = None, schema = None))]
+    fn push(
+        &self,
+        py: Python<'_>,
+        name: String,
+        description: String,
+        language: String,
+        content: &Bound<'_, PyAny>,
+        branch: Option<String>,
+        schema: Option<String>,
+    ) -> PyResult<u64> {
+        let content: Value = from_python(content)?;
+        let metadata = AttachedMetadata { name, description, language, branch: branch.unwrap_or_else(default_branch), schema };
+        py.detach(|| self.0.add_version(metadata, content)).map_err(to_py_err)
+    }
+
+    /// Activates a policy version.
+    fn activate(&self, py: Python<'_>, version: u64) -> PyResult<()> { py.detach(|| self.0.activate(version)).map_err(to_py_err) }
+
+    /// Deactivates the active policy version, if any.
+    fn deactivate(&self, py: Python<'_>) -> PyResult<()> { py.detach(|| self.0.deactivate()).map_err(to_py_err) }
+
+    /// Returns the active version, or `None` if no version is active.
+    fn active(&self, py: Python<'_>) -> PyResult<Option<u64>> { py.detach(|| self.0.get_active_version()).map_err(to_py_err) }
+
+    /// Returns the content of a policy version.
+    fn content<'py>(&self, py: Python<'py>, version: u64) -> PyResult<Bound<'py, PyAny>> {
+        let content: Value = py.detach(|| self.0.get_version_content(version)).map_err(to_py_err)?;
+        to_python(py, &content)
+    }
+
+    /// Returns a unified diff between the (canonicalized) contents of two policy versions.
+    fn diff(&self, py: Python<'_>, old: u64, new: u64) -> PyResult<String> {
+        let (old_content, new_content): (Value, Value) =
+            py.detach(|| Ok::<_, Error>((self.0.get_version_content(old)?, self.0.get_version_content(new)?))).map_err(to_py_err)?;
+
+        // Pretty-print them canonically such that only actual changes show up
+        // SAFETY: Serializing JSON never fails
+        let old_content: String = serde_json::to_string_pretty(&canonicalize(&old_content)).unwrap();
+        let new_content: String = serde_json::to_string_pretty(&canonicalize(&new_content)).unwrap();
+
+        // Let Python's difflib do the rest
+        let kwargs: Bound<'_, PyDict> = PyDict::new(py);
+        kwargs.set_item("fromfile", format!("version {old}"))?;
+        kwargs.set_item("tofile", format!("version {new}"))?;
+        kwargs.set_item("lineterm", "")?;
+        let lines = py.import("difflib")?.call_method(
+            "unified_diff",
+            (old_content.lines().collect::<Vec<&str>>(), new_content.lines().collect::<Vec<&str>>()),
+            Some(&kwargs),
+        )?;
+        PyString::new(py, "\n").call_method1("join", (lines,))?.extract()
+    }
+}
+
+
+
+/// The `policy_store` Python module.
+#[pymodule]
+fn policy_store(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyClient>()?;
+    m.add("PolicyStoreError", m.py().get_type::<PolicyStoreError>())?;
+    Ok(())
+}
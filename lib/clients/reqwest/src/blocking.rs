@@ -0,0 +1,173 @@
+//  BLOCKING.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:06:50
+//  Last edited:
+//    16 Oct 2026, 18:29:08
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a synchronous facade over the [`Client`](crate::Client),
+//!   for callers that don't run in a tokio runtime.
+//
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use specifications::metadata::{AttachedMetadata, Metadata};
+use specifications::provenance::Provenance;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::client::{Error, Revalidated};
+use crate::reconcile::{PolicyDocument, Reconciled};
+
+
+/***** LIBRARY *****/
+/// A synchronous version of the [`Client`](crate::Client).
+///
+/// Every client owns a small single-threaded runtime on which its requests are run. It may be
+/// shared between threads, but must not be used from within an async context.
+#[derive(Debug)]
+pub struct Client {
+    /// The asynchronous client doing the actual work.
+    client:  crate::Client,
+    /// The runtime to run it on.
+    runtime: Runtime,
+}
+
+// Constructors
+impl Client {
+    /// Constructor for a blocking Client.
+    ///
+    /// # Arguments
+    /// - `client`: The asynchronous [`Client`](crate::Client) to wrap, configured as desired.
+    ///
+    /// # Returns
+    /// A new Client that blocks on every request.
+    ///
+    /// # Errors
+    /// This function errors if the runtime could not be created.
+    #[inline]
+    pub fn new(client: crate::Client) -> std::io::Result<Self> { Ok(Self { client, runtime: Builder::new_current_thread().enable_all().build()? }) }
+}
+
+// Requests
+impl Client {
+    /// Adds a new policy version.
+    ///
+    /// See [`Client::add_version()`](crate::Client::add_version()).
+    #[inline]
+    pub fn add_version<C: Serialize>(&self, metadata: AttachedMetadata, contents: C) -> Result<u64, Error> {
+        self.runtime.block_on(self.client.add_version(metadata, contents))
+    }
+
+    /// Activates a policy version.
+    ///
+    /// See [`Client::activate()`](crate::Client::activate()).
+    #[inline]
+    pub fn activate(&self, version: u64) -> Result<(), Error> { self.runtime.block_on(self.client.activate(version)) }
+
+    /// Deactivates the active policy version, if any.
+    ///
+    /// See [`Client::deactivate()`](crate::Client::deactivate()).
+    #[inline]
+    pub fn deactivate(&self) -> Result<(), Error> { self.runtime.block_on(self.client.deactivate()) }
+
+    /// Activates the built-in policy version that denies all requests.
+    ///
+    /// See [`Client::deny_all()`](crate::Client::deny_all()).
+    #[inline]
+    pub fn deny_all(&self) -> Result<u64, Error> { self.runtime.block_on(self.client.deny_all()) }
+
+    /// Freezes a policy version, such that it cannot be activated or promoted until it is
+    /// unfrozen.
+    ///
+    /// See [`Client::freeze_version()`](crate::Client::freeze_version()).
+    #[inline]
+    pub fn freeze_version(&self, version: u64, reason: impl Into<String>) -> Result<(), Error> {
+        self.runtime.block_on(self.client.freeze_version(version, reason))
+    }
+
+    /// Unfreezes a frozen policy version.
+    ///
+    /// See [`Client::unfreeze_version()`](crate::Client::unfreeze_version()).
+    #[inline]
+    pub fn unfreeze_version(&self, version: u64) -> Result<(), Error> { self.runtime.block_on(self.client.unfreeze_version(version)) }
+
+    /// Retrieves the metadata of all policy versions.
+    ///
+    /// See [`Client::get_versions()`](crate::Client::get_versions()).
+    #[inline]
+    pub fn get_versions(&self) -> Result<HashMap<u64, Metadata>, Error> { self.runtime.block_on(self.client.get_versions()) }
+
+    /// Retrieves the active policy version.
+    ///
+    /// See [`Client::get_active_version()`](crate::Client::get_active_version()).
+    #[inline]
+    pub fn get_active_version(&self) -> Result<Option<u64>, Error> { self.runtime.block_on(self.client.get_active_version()) }
+
+    /// Retrieves the active policy version, unless it is still the one with the given entity tag.
+    ///
+    /// See [`Client::revalidate_active_version()`](crate::Client::revalidate_active_version()).
+    #[inline]
+    pub fn revalidate_active_version(&self, etag: Option<&str>) -> Result<Revalidated<Option<u64>>, Error> {
+        self.runtime.block_on(self.client.revalidate_active_version(etag))
+    }
+
+    /// Retrieves the metadata of a policy version.
+    ///
+    /// See [`Client::get_version_metadata()`](crate::Client::get_version_metadata()).
+    #[inline]
+    pub fn get_version_metadata(&self, version: u64) -> Result<Metadata, Error> { self.runtime.block_on(self.client.get_version_metadata(version)) }
+
+    /// Retrieves the contents of a policy version.
+    ///
+    /// See [`Client::get_version_content()`](crate::Client::get_version_content()).
+    #[inline]
+    pub fn get_version_content<C: DeserializeOwned>(&self, version: u64) -> Result<C, Error> {
+        self.runtime.block_on(self.client.get_version_content(version))
+    }
+
+    /// Retrieves the provenance of a policy version.
+    ///
+    /// See [`Client::get_version_provenance()`](crate::Client::get_version_provenance()).
+    #[inline]
+    pub fn get_version_provenance(&self, version: u64) -> Result<Provenance, Error> {
+        self.runtime.block_on(self.client.get_version_provenance(version))
+    }
+
+    /// Retrieves the contents of the active policy version.
+    ///
+    /// See [`Client::get_active_content()`](crate::Client::get_active_content()).
+    #[inline]
+    pub fn get_active_content<C: DeserializeOwned>(&self) -> Result<Option<(u64, C)>, Error> {
+        self.runtime.block_on(self.client.get_active_content())
+    }
+
+    /// Imports a policy version copied over from another store.
+    ///
+    /// See [`Client::import_version()`](crate::Client::import_version()).
+    #[inline]
+    pub fn import_version<C: Serialize>(&self, store: impl Into<String>, metadata: Metadata, contents: C) -> Result<u64, Error> {
+        self.runtime.block_on(self.client.import_version(store, metadata, contents))
+    }
+
+    /// Copies a policy version from this client's server to another.
+    ///
+    /// See [`Client::promote_to()`](crate::Client::promote_to()).
+    #[inline]
+    pub fn promote_to<C: DeserializeOwned + Serialize>(&self, version: u64, store: &str, target: &Client) -> Result<u64, Error> {
+        self.runtime.block_on(self.client.promote_to::<C>(version, store, &target.client))
+    }
+
+    /// Makes the given document the active policy of the server, adding it if needed.
+    ///
+    /// See [`Client::reconcile()`](crate::Client::reconcile()).
+    #[inline]
+    pub fn reconcile<C: Serialize>(&self, document: &PolicyDocument<C>) -> Result<Reconciled, Error> {
+        self.runtime.block_on(self.client.reconcile(document))
+    }
+}
@@ -0,0 +1,174 @@
+//  CACHE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:04:33
+//  Last edited:
+//    17 Oct 2026, 01:48:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the [`ActivePolicyCache`], which keeps a local copy of
+//!   the active policy up-to-date.
+//
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+#[cfg(target_arch = "wasm32")]
+use tokio::sync::oneshot;
+use tokio::sync::watch;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::task::JoinHandle;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::{Interval, MissedTickBehavior};
+use tracing::{debug, warn};
+
+use crate::client::{Client, Error, Revalidated};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Checks whether the active policy changed and, if so, publishes the new one.
+///
+/// # Arguments
+/// - `client`: The [`Client`] to check with.
+/// - `etag`: The entity tag of the currently published policy, if any. Updated when a new one is
+///   published.
+/// - `sender`: The [`watch::Sender`] to publish on.
+///
+/// # Errors
+/// This function errors if any request failed. Nothing is published in that case.
+async fn refresh<C: DeserializeOwned>(
+    client: &Client,
+    etag: &mut Option<String>,
+    sender: &watch::Sender<Option<Arc<ActivePolicy<C>>>>,
+) -> Result<(), Error> {
+    let (version, new_etag): (Option<u64>, Option<String>) = match client.revalidate_active_version(etag.as_deref()).await? {
+        Revalidated::NotModified => return Ok(()),
+        Revalidated::Modified { value, etag } => (value, etag),
+    };
+
+    // Only fetch the content if the version actually changed
+    if sender.borrow().as_ref().map(|policy| policy.version) != version {
+        let policy: Option<Arc<ActivePolicy<C>>> = match version {
+            Some(version) => Some(Arc::new(ActivePolicy { version, content: client.get_version_content(version).await? })),
+            None => None,
+        };
+        debug!("Active policy changed to {version:?}");
+        sender.send_replace(policy);
+    }
+    *etag = new_etag;
+    Ok(())
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A policy version that is (or was) active.
+#[derive(Clone, Debug)]
+pub struct ActivePolicy<C> {
+    /// The version of the policy.
+    pub version: u64,
+    /// The contents of the policy.
+    pub content: C,
+}
+
+
+
+/// Keeps a local copy of the active policy up-to-date by polling the server.
+///
+/// Polls are revalidated with the `ETag` of the last reply, so they are cheap while nothing
+/// changes. If a poll fails, the last known policy is kept and the next poll tries again.
+///
+/// The background task stops when the cache is dropped.
+#[derive(Debug)]
+pub struct ActivePolicyCache<C> {
+    /// Receives the current policy.
+    receiver: watch::Receiver<Option<Arc<ActivePolicy<C>>>>,
+    /// The task polling the server.
+    #[cfg(not(target_arch = "wasm32"))]
+    handle:   JoinHandle<()>,
+    /// Stops the task polling the server when dropped.
+    ///
+    /// Tasks cannot be aborted in the browser, so it stops before its next poll instead.
+    #[cfg(target_arch = "wasm32")]
+    _stop:    oneshot::Sender<()>,
+}
+impl<C: 'static + Send + Sync + DeserializeOwned> ActivePolicyCache<C> {
+    /// Constructor for an ActivePolicyCache.
+    ///
+    /// Fetches the active policy once before returning, so the cache is ready to use. Outside of
+    /// the browser, must be called from within a tokio runtime.
+    ///
+    /// # Arguments
+    /// - `client`: The [`Client`] to poll with.
+    /// - `interval`: How often to poll.
+    ///
+    /// # Returns
+    /// A new ActivePolicyCache.
+    ///
+    /// # Errors
+    /// This function errors if the initial fetch failed.
+    pub async fn new(client: Client, interval: Duration) -> Result<Self, Error> {
+        let (sender, receiver) = watch::channel(None);
+        let mut etag: Option<String> = None;
+        refresh(&client, &mut etag, &sender).await?;
+
+        // Poll in the background
+        #[cfg(not(target_arch = "wasm32"))]
+        let handle: JoinHandle<()> = tokio::spawn(async move {
+            let mut ticker: Interval = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(err) = refresh(&client, &mut etag, &sender).await {
+                    warn!("Failed to refresh active policy; keeping the last known one: {err}");
+                }
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        return Ok(Self { receiver, handle });
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let (stop, mut stopped) = oneshot::channel::<()>();
+            wasm_bindgen_futures::spawn_local(async move {
+                loop {
+                    gloo_timers::future::sleep(interval).await;
+                    if !matches!(stopped.try_recv(), Err(oneshot::error::TryRecvError::Empty)) {
+                        break;
+                    }
+                    if let Err(err) = refresh(&client, &mut etag, &sender).await {
+                        warn!("Failed to refresh active policy; keeping the last known one: {err}");
+                    }
+                }
+            });
+            Ok(Self { receiver, _stop: stop })
+        }
+    }
+}
+impl<C> ActivePolicyCache<C> {
+    /// Returns the current active policy.
+    ///
+    /// # Returns
+    /// The [`ActivePolicy`], or [`None`] if no policy is active.
+    #[inline]
+    pub fn current(&self) -> Option<Arc<ActivePolicy<C>>> { self.receiver.borrow().clone() }
+
+    /// Returns a channel that is notified whenever the active policy changes.
+    ///
+    /// # Returns
+    /// A [`watch::Receiver`] holding the current policy.
+    #[inline]
+    pub fn subscribe(&self) -> watch::Receiver<Option<Arc<ActivePolicy<C>>>> { self.receiver.clone() }
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl<C> Drop for ActivePolicyCache<C> {
+    #[inline]
+    fn drop(&mut self) { self.handle.abort(); }
+}
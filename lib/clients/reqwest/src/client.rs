@@ -0,0 +1,694 @@
+//  CLIENT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:57:13
+//  Last edited:
+//    17 Oct 2026, 03:32:33
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the [`Client`] itself.
+//
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum_server_spec::{
+    ACTIVATE_PATH, ADD_VERSION_PATH, ActivateRequest, AddVersionRequest, AddVersionResponse, ApiErrorCode, ContentSignature, DEACTIVATE_PATH,
+    DENY_ALL_PATH, DenyAllResponse, EndpointPath, ErrorResponse, FREEZE_VERSION_PATH, FreezeVersionRequest, GET_ACTIVE_VERSION_PATH,
+    GET_CHANGES_PATH, GET_VERSION_CONTENT_PATH, GET_VERSION_METADATA_PATH, GET_VERSION_PROVENANCE_PATH, GET_VERSIONS_PATH, GetActiveVersionResponse,
+    GetChangesQuery, GetChangesResponse, GetVersionContentResponse, GetVersionMetadataResponse, GetVersionProvenanceResponse, GetVersionsResponse,
+    IMPORT_VERSION_PATH, ImportDryRunResponse, ImportVersionQuery, ImportVersionRequest, PROMOTE_STANDBY_PATH, PromoteStandbyResponse,
+    UNFREEZE_VERSION_PATH,
+};
+use reqwest::header::{AUTHORIZATION, ETAG, IF_NONE_MATCH};
+use reqwest::{Request, RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use specifications::metadata::{AttachedMetadata, Metadata};
+use specifications::provenance::Provenance;
+use thiserror::Error;
+use tracing::debug;
+
+use crate::policy::{Circuit, CircuitBreaker, RetryPolicy};
+
+
+/***** ERRORS *****/
+/// Defines who is to blame for an [`Error`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorKind {
+    /// The client did something wrong, e.g., sent an invalid request or refused to send one.
+    Client,
+    /// The server could not be reached, or didn't reply in time.
+    Transport,
+    /// The server failed to handle a (presumably) valid request.
+    Server,
+}
+
+/// Defines errors originating from the [`Client`].
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Failed to build a request, e.g., because the base URL is invalid.
+    #[error("Failed to build request")]
+    Build {
+        #[source]
+        err: reqwest::Error,
+    },
+    /// Did not send a request because the server failed too often recently.
+    #[error("Not sending request to {url:?} because the circuit breaker is open")]
+    CircuitOpen { url: String },
+    /// Failed to send a request or to receive its response.
+    #[error("Failed to send request to {url:?}")]
+    Request {
+        url: String,
+        #[source]
+        err: reqwest::Error,
+    },
+    /// Failed to deserialize the body of a response.
+    #[error("Failed to deserialize response of {url:?}")]
+    Response {
+        url: String,
+        #[source]
+        err: reqwest::Error,
+    },
+    /// Failed to serialize the contents of a policy.
+    #[error("Failed to serialize policy contents")]
+    Serialize {
+        #[source]
+        err: serde_json::Error,
+    },
+    /// The server replied with a non-2xx status code.
+    #[error("Request to {url:?} failed with status {status}{}", if message.is_empty() { String::new() } else { format!(": {message}") })]
+    Status { url: String, status: StatusCode, code: ApiErrorCode, message: String, request_id: Option<String> },
+}
+impl Error {
+    /// Returns who is to blame for this error.
+    ///
+    /// # Returns
+    /// An [`ErrorKind`] describing the source of the failure. Replies with a 4xx status code are
+    /// blamed on the client; any other non-2xx status code on the server.
+    #[inline]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Build { .. } | Self::CircuitOpen { .. } | Self::Serialize { .. } => ErrorKind::Client,
+            Self::Request { .. } => ErrorKind::Transport,
+            Self::Status { status, .. } if status.is_client_error() => ErrorKind::Client,
+            Self::Status { .. } | Self::Response { .. } => ErrorKind::Server,
+        }
+    }
+
+    /// Returns whether it makes sense to retry a request that failed with this error.
+    ///
+    /// # Returns
+    /// True for transport failures and for status codes that ask to try again later.
+    #[inline]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Request { .. } => true,
+            Self::Status { status, .. } => matches!(
+                *status,
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+            ),
+            Self::Build { .. } | Self::CircuitOpen { .. } | Self::Response { .. } | Self::Serialize { .. } => false,
+        }
+    }
+
+    /// Returns the status code the server replied with, if this error is due to one.
+    ///
+    /// # Returns
+    /// The [`StatusCode`] of the response, or [`None`] if the server did not (properly) reply.
+    #[inline]
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Status { status, .. } => Some(*status),
+            Self::Build { .. } | Self::CircuitOpen { .. } | Self::Request { .. } | Self::Response { .. } | Self::Serialize { .. } => None,
+        }
+    }
+
+    /// Returns what went wrong according to the server, if this error is due to a non-2xx reply.
+    ///
+    /// # Returns
+    /// The [`ApiErrorCode`] of the response, or [`None`] if the server did not (properly) reply.
+    /// If the server replied without a (known) code, one is derived from the status code.
+    #[inline]
+    pub fn code(&self) -> Option<ApiErrorCode> {
+        match self {
+            Self::Status { code, .. } => Some(*code),
+            Self::Build { .. } | Self::CircuitOpen { .. } | Self::Request { .. } | Self::Response { .. } | Self::Serialize { .. } => None,
+        }
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// The result of a conditional request.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Revalidated<T> {
+    /// The resource did not change since the given entity tag.
+    NotModified,
+    /// The resource changed, or no entity tag was given.
+    Modified {
+        /// The current value of the resource.
+        value: T,
+        /// The entity tag of the current value, if the server sent one.
+        etag:  Option<String>,
+    },
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A typed client for the HTTP API of an `axum-server`.
+///
+/// Clones share the same connection pool and circuit breaker.
+#[derive(Clone, Debug)]
+pub struct Client {
+    /// The base URL of the server, without a trailing slash.
+    base:    String,
+    /// The bearer token to authenticate with, if any.
+    token:   Option<String>,
+    /// How long to wait for every individual attempt at a request, if limited.
+    timeout: Option<Duration>,
+    /// When to retry idempotent requests.
+    retry:   RetryPolicy,
+    /// The circuit breaker guarding the server, if any.
+    circuit: Option<Arc<Circuit>>,
+    /// The client used to send requests.
+    client:  reqwest::Client,
+}
+
+// Constructors
+impl Client {
+    /// Constructor for a Client that talks to the server at the given address.
+    ///
+    /// # Arguments
+    /// - `base`: The base URL of the server (e.g., `http://localhost:3030`).
+    ///
+    /// # Returns
+    /// A new Client that sends requests without authentication, gives every attempt 30 seconds,
+    /// retries according to the default [`RetryPolicy`] and has no circuit breaker.
+    #[inline]
+    pub fn new(base: impl Into<String>) -> Self {
+        let mut base: String = base.into();
+        while base.ends_with('/') {
+            base.pop();
+        }
+        Self {
+            base,
+            token: None,
+            timeout: Some(Duration::from_secs(30)),
+            retry: RetryPolicy::default(),
+            circuit: None,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Makes the client authenticate with a bearer token.
+    ///
+    /// # Arguments
+    /// - `token`: The token to send in the `Authorization`-header of every request.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Changes how long to wait for every individual attempt at a request.
+    ///
+    /// # Arguments
+    /// - `timeout`: The time after which to give up on an attempt, or [`None`] to wait forever.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    /// Changes when idempotent requests are retried.
+    ///
+    /// # Arguments
+    /// - `retry`: The [`RetryPolicy`] to follow. Use [`RetryPolicy::none()`] to never retry.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Makes the client stop sending requests to a server that keeps failing.
+    ///
+    /// # Arguments
+    /// - `breaker`: The [`CircuitBreaker`] configuring when to stop, and for how long.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.circuit = Some(Arc::new(Circuit::new(breaker)));
+        self
+    }
+}
+
+// Requests
+impl Client {
+    /// Prepares a request to the given endpoint.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The [`EndpointPath`] to send the request to.
+    /// - `args`: The arguments to instantiate the endpoint's path with.
+    ///
+    /// # Returns
+    /// A [`RequestBuilder`] that has the URL, method, timeout and authentication set.
+    fn request<'a>(&self, endpoint: &EndpointPath, args: impl IntoIterator<Item = &'a str>) -> RequestBuilder {
+        let mut req: RequestBuilder = self.client.request(endpoint.method.clone(), format!("{}{}", self.base, endpoint.instantiated_path(args)));
+        if let Some(timeout) = self.timeout {
+            req = req.timeout(timeout);
+        }
+        if let Some(token) = &self.token {
+            req = req.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        req
+    }
+
+    /// Sends a prepared request and checks that it succeeded, retrying and tripping the circuit
+    /// breaker as configured.
+    ///
+    /// # Arguments
+    /// - `req`: The [`RequestBuilder`] to send.
+    ///
+    /// # Returns
+    /// The successful [`Response`].
+    ///
+    /// # Errors
+    /// This function errors if we failed to send the request, or if the server replied with a
+    /// non-2xx status code, after all retries (if any) have been exhausted.
+    async fn send(&self, req: RequestBuilder) -> Result<Response, Error> {
+        let mut req: Request = req.build().map_err(|err| Error::Build { err })?;
+        let url: String = req.url().to_string();
+        let retries: u32 = if req.method().is_idempotent() { self.retry.max_retries } else { 0 };
+
+        let mut attempt: u32 = 0;
+        loop {
+            if let Some(circuit) = &self.circuit {
+                if !circuit.allow() {
+                    return Err(Error::CircuitOpen { url });
+                }
+            }
+
+            // NOTE: Cloning only fails for streamed bodies, which we never send
+            let next: Option<Request> = if attempt < retries { req.try_clone() } else { None };
+            debug!("Sending {} request to {url:?} (attempt {})", req.method(), attempt + 1);
+            let res: Result<Response, Error> = self.send_once(req, &url).await;
+            if let Some(circuit) = &self.circuit {
+                circuit.record(matches!(&res, Err(err) if err.kind() != ErrorKind::Client));
+            }
+
+            // Retry if we can and should (but report the actual problem if we just tripped the breaker)
+            match (res, next) {
+                (Err(err), Some(next)) if err.is_retryable() && !self.circuit.as_ref().map(|circuit| circuit.is_open()).unwrap_or(false) => {
+                    let delay: Duration = self.retry.backoff(attempt);
+                    debug!("Retrying request to {url:?} in {}ms: {err}", delay.as_millis());
+                    #[cfg(not(target_arch = "wasm32"))]
+                    tokio::time::sleep(delay).await;
+                    #[cfg(target_arch = "wasm32")]
+                    gloo_timers::future::sleep(delay).await;
+                    attempt += 1;
+                    req = next;
+                },
+                (res, _) => return res,
+            }
+        }
+    }
+
+    /// Sends a request once and checks that it succeeded.
+    ///
+    /// # Arguments
+    /// - `req`: The [`Request`] to send.
+    /// - `url`: The URL it is sent to (for errors).
+    ///
+    /// # Returns
+    /// The successful [`Response`].
+    ///
+    /// # Errors
+    /// This function errors if we failed to send the request, or if the server replied with a
+    /// non-2xx status code.
+    async fn send_once(&self, req: Request, url: &str) -> Result<Response, Error> {
+        let res: Response = self.client.execute(req).await.map_err(|err| Error::Request { url: url.into(), err })?;
+        let status: StatusCode = res.status();
+        // NOTE: 304 only occurs for conditional requests, whose callers handle it
+        if status.is_success() || status == StatusCode::NOT_MODIFIED {
+            return Ok(res);
+        }
+
+        // Attempt to get the code, message and request ID out of the error
+        let (code, message, request_id): (ApiErrorCode, String, Option<String>) = match res.json::<ErrorResponse>().await {
+            Ok(ErrorResponse { code: ApiErrorCode::Unknown, detail, request_id, .. }) => (ApiErrorCode::from_status(status), detail, request_id),
+            Ok(err) => (err.code, err.detail, err.request_id),
+            Err(_) => (ApiErrorCode::from_status(status), String::new(), None),
+        };
+        Err(Error::Status { url: url.into(), status, code, message, request_id })
+    }
+
+    /// Sends a prepared request and deserializes its response.
+    ///
+    /// # Arguments
+    /// - `req`: The [`RequestBuilder`] to send.
+    ///
+    /// # Returns
+    /// The deserialized body of the response.
+    ///
+    /// # Errors
+    /// This function errors if [`Client::send()`] fails or the body could not be deserialized as `R`.
+    async fn send_json<R: DeserializeOwned>(&self, req: RequestBuilder) -> Result<R, Error> {
+        let res: Response = self.send(req).await?;
+        let url: String = res.url().to_string();
+        res.json().await.map_err(|err| Error::Response { url, err })
+    }
+
+
+
+    /// Adds a new policy version.
+    ///
+    /// # Arguments
+    /// - `metadata`: The [`AttachedMetadata`] to attach to the policy.
+    /// - `contents`: The contents of the policy.
+    ///
+    /// # Returns
+    /// The version number assigned to the new policy.
+    ///
+    /// # Errors
+    /// This function errors if the request failed.
+    pub async fn add_version<C: Serialize>(&self, metadata: AttachedMetadata, contents: C) -> Result<u64, Error> {
+        let res: AddVersionResponse = self.send_json(self.request(&ADD_VERSION_PATH, []).json(&AddVersionRequest { metadata, contents })).await?;
+        Ok(res.version)
+    }
+
+    /// Imports a policy version copied over from another store.
+    ///
+    /// # Arguments
+    /// - `store`: The name of the store the version is copied from.
+    /// - `metadata`: The [`Metadata`] of the version in that store.
+    /// - `contents`: The contents of the version, as they were in that store.
+    ///
+    /// # Returns
+    /// The version number assigned to the imported copy.
+    ///
+    /// # Errors
+    /// This function errors if the request failed, including when the contents don't match the
+    /// hash in the `metadata`.
+    #[inline]
+    pub async fn import_version<C: Serialize>(&self, store: impl Into<String>, metadata: Metadata, contents: C) -> Result<u64, Error> {
+        self.import_signed_version(store, metadata, contents, None).await
+    }
+
+    /// Imports a policy version copied over from another store, together with a signature over
+    /// its contents.
+    ///
+    /// Servers configured to verify signatures record the result in the copy's
+    /// [verification](Metadata::verification); others ignore the signature.
+    ///
+    /// # Arguments
+    /// - `store`: The name of the store the version is copied from.
+    /// - `metadata`: The [`Metadata`] of the version in that store.
+    /// - `contents`: The contents of the version, as they were in that store.
+    /// - `signature`: The [`ContentSignature`] over the `contents`, if any.
+    ///
+    /// # Returns
+    /// The version number assigned to the imported copy.
+    ///
+    /// # Errors
+    /// This function errors if the request failed, including when the contents don't match the
+    /// hash in the `metadata` or when the server requires a valid signature and didn't get one.
+    pub async fn import_signed_version<C: Serialize>(
+        &self,
+        store: impl Into<String>,
+        metadata: Metadata,
+        contents: C,
+        signature: Option<ContentSignature>,
+    ) -> Result<u64, Error> {
+        let req = ImportVersionRequest { store: store.into(), metadata, contents, signature };
+        let res: AddVersionResponse = self.send_json(self.request(&IMPORT_VERSION_PATH, []).json(&req)).await?;
+        Ok(res.version)
+    }
+
+    /// Checks whether a policy version copied over from another store would import, without
+    /// importing it.
+    ///
+    /// # Arguments
+    /// - `store`: The name of the store the version is copied from.
+    /// - `metadata`: The [`Metadata`] of the version in that store.
+    /// - `contents`: The contents of the version, as they were in that store.
+    ///
+    /// # Returns
+    /// An [`ImportDryRunResponse`] describing what importing the version would change.
+    ///
+    /// # Errors
+    /// This function errors if the request failed, including when the import would be refused.
+    pub async fn preview_import<C: Serialize>(
+        &self,
+        store: impl Into<String>,
+        metadata: Metadata,
+        contents: C,
+    ) -> Result<ImportDryRunResponse, Error> {
+        let req = ImportVersionRequest { store: store.into(), metadata, contents, signature: None };
+        self.send_json(self.request(&IMPORT_VERSION_PATH, []).query(&ImportVersionQuery { dry_run: true }).json(&req)).await
+    }
+
+    /// Activates a policy version.
+    ///
+    /// # Arguments
+    /// - `version`: The version to activate.
+    ///
+    /// # Errors
+    /// This function errors if the request failed.
+    pub async fn activate(&self, version: u64) -> Result<(), Error> {
+        self.send(self.request(&ACTIVATE_PATH, []).json(&ActivateRequest { version })).await?;
+        Ok(())
+    }
+
+    /// Deactivates the active policy version, if any.
+    ///
+    /// # Errors
+    /// This function errors if the request failed.
+    pub async fn deactivate(&self) -> Result<(), Error> {
+        self.send(self.request(&DEACTIVATE_PATH, [])).await?;
+        Ok(())
+    }
+
+    /// Activates the built-in policy version that denies all requests.
+    ///
+    /// # Returns
+    /// The version of the deny-all policy, which is now active.
+    ///
+    /// # Errors
+    /// This function errors if the request failed.
+    pub async fn deny_all(&self) -> Result<u64, Error> {
+        let res: DenyAllResponse = self.send_json(self.request(&DENY_ALL_PATH, [])).await?;
+        Ok(res.version)
+    }
+
+    /// Freezes a policy version, such that it cannot be activated or promoted until it is
+    /// unfrozen.
+    ///
+    /// Note that this does not deactivate the version if it is active.
+    ///
+    /// # Arguments
+    /// - `version`: The version to freeze.
+    /// - `reason`: Why the version is frozen.
+    ///
+    /// # Errors
+    /// This function errors if the request failed, including when the version does not exist.
+    pub async fn freeze_version(&self, version: u64, reason: impl Into<String>) -> Result<(), Error> {
+        let req = FreezeVersionRequest { reason: reason.into() };
+        self.send(self.request(&FREEZE_VERSION_PATH, [version.to_string().as_str()]).json(&req)).await?;
+        Ok(())
+    }
+
+    /// Unfreezes a frozen policy version.
+    ///
+    /// # Arguments
+    /// - `version`: The version to unfreeze.
+    ///
+    /// # Errors
+    /// This function errors if the request failed, including when the version does not exist.
+    pub async fn unfreeze_version(&self, version: u64) -> Result<(), Error> {
+        self.send(self.request(&UNFREEZE_VERSION_PATH, [version.to_string().as_str()])).await?;
+        Ok(())
+    }
+
+    /// Retrieves the metadata of all policy versions.
+    ///
+    /// # Returns
+    /// A map of version numbers to their [`Metadata`].
+    ///
+    /// # Errors
+    /// This function errors if the request failed.
+    pub async fn get_versions(&self) -> Result<HashMap<u64, Metadata>, Error> {
+        let res: GetVersionsResponse = self.send_json(self.request(&GET_VERSIONS_PATH, [])).await?;
+        Ok(res.versions)
+    }
+
+    /// Retrieves the active policy version.
+    ///
+    /// # Returns
+    /// The active version, or [`None`] if no version is active.
+    ///
+    /// # Errors
+    /// This function errors if the request failed.
+    pub async fn get_active_version(&self) -> Result<Option<u64>, Error> {
+        let res: GetActiveVersionResponse = self.send_json(self.request(&GET_ACTIVE_VERSION_PATH, [])).await?;
+        Ok(res.version)
+    }
+
+    /// Retrieves the active policy version, unless it is still the one with the given entity tag.
+    ///
+    /// # Arguments
+    /// - `etag`: The entity tag from an earlier call, if any.
+    ///
+    /// # Returns
+    /// [`Revalidated::NotModified`] if the active version is unchanged, or else the active version
+    /// (or [`None`] if no version is active) and its new entity tag.
+    ///
+    /// # Errors
+    /// This function errors if the request failed.
+    pub async fn revalidate_active_version(&self, etag: Option<&str>) -> Result<Revalidated<Option<u64>>, Error> {
+        let mut req: RequestBuilder = self.request(&GET_ACTIVE_VERSION_PATH, []);
+        if let Some(etag) = etag {
+            req = req.header(IF_NONE_MATCH, etag);
+        }
+        let res: Response = self.send(req).await?;
+        if res.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Revalidated::NotModified);
+        }
+        let etag: Option<String> = res.headers().get(ETAG).and_then(|etag| etag.to_str().ok()).map(String::from);
+        let url: String = res.url().to_string();
+        let res: GetActiveVersionResponse = res.json().await.map_err(|err| Error::Response { url, err })?;
+        Ok(Revalidated::Modified { value: res.version, etag })
+    }
+
+    /// Retrieves the metadata of a policy version.
+    ///
+    /// # Arguments
+    /// - `version`: The version to retrieve the metadata of.
+    ///
+    /// # Returns
+    /// The [`Metadata`] of the version.
+    ///
+    /// # Errors
+    /// This function errors if the request failed, including when the version does not exist.
+    pub async fn get_version_metadata(&self, version: u64) -> Result<Metadata, Error> {
+        let res: GetVersionMetadataResponse = self.send_json(self.request(&GET_VERSION_METADATA_PATH, [version.to_string().as_str()])).await?;
+        Ok(res.metadata)
+    }
+
+    /// Retrieves the contents of a policy version.
+    ///
+    /// # Arguments
+    /// - `version`: The version to retrieve the contents of.
+    ///
+    /// # Returns
+    /// The contents of the version.
+    ///
+    /// # Errors
+    /// This function errors if the request failed, including when the version does not exist.
+    pub async fn get_version_content<C: DeserializeOwned>(&self, version: u64) -> Result<C, Error> {
+        let res: GetVersionContentResponse<C> = self.send_json(self.request(&GET_VERSION_CONTENT_PATH, [version.to_string().as_str()])).await?;
+        Ok(res.content)
+    }
+
+    /// Retrieves the provenance of a policy version.
+    ///
+    /// # Arguments
+    /// - `version`: The version to retrieve the provenance of.
+    ///
+    /// # Returns
+    /// The in-toto [`Provenance`] statement of the version.
+    ///
+    /// # Errors
+    /// This function errors if the request failed, including when the version does not exist.
+    pub async fn get_version_provenance(&self, version: u64) -> Result<Provenance, Error> {
+        let res: GetVersionProvenanceResponse = self.send_json(self.request(&GET_VERSION_PROVENANCE_PATH, [version.to_string().as_str()])).await?;
+        Ok(res.provenance)
+    }
+
+    /// Retrieves the contents of the active policy version.
+    ///
+    /// Note that this takes two requests, so the active version may change in between. The
+    /// returned version always matches the returned contents.
+    ///
+    /// # Returns
+    /// The active version and its contents, or [`None`] if no version is active.
+    ///
+    /// # Errors
+    /// This function errors if either request failed.
+    pub async fn get_active_content<C: DeserializeOwned>(&self) -> Result<Option<(u64, C)>, Error> {
+        let Some(version) = self.get_active_version().await? else { return Ok(None) };
+        Ok(Some((version, self.get_version_content(version).await?)))
+    }
+
+    /// Retrieves the changes made to the store after some cursor.
+    ///
+    /// # Arguments
+    /// - `since`: The cursor returned by a previous call, or `0` to start at the beginning.
+    /// - `limit`: The maximum number of changes to return, or [`None`] for the server's default.
+    ///
+    /// # Returns
+    /// A [`GetChangesResponse`] with the changes (ordered by sequence number) and the cursor to
+    /// pass as `since` next time.
+    ///
+    /// # Errors
+    /// This function errors if the request failed.
+    pub async fn get_changes<C: DeserializeOwned>(&self, since: u64, limit: Option<u64>) -> Result<GetChangesResponse<C>, Error> {
+        self.send_json(self.request(&GET_CHANGES_PATH, []).query(&GetChangesQuery { since, limit })).await
+    }
+
+    /// Promotes a standby server to read-write, such that it stops replicating from its primary.
+    ///
+    /// # Returns
+    /// The sequence number of the last change the server replicated from its primary.
+    ///
+    /// # Errors
+    /// This function errors if the request failed, including when the server is not a standby.
+    pub async fn promote_standby(&self) -> Result<u64, Error> {
+        let res: PromoteStandbyResponse = self.send_json(self.request(&PROMOTE_STANDBY_PATH, [])).await?;
+        Ok(res.cursor)
+    }
+
+    /// Copies a policy version from this client's server to another, e.g., to promote it from a
+    /// staging store to production.
+    ///
+    /// The copy keeps the contents, content hash and origin of the version, but is created by
+    /// whoever `target` authenticates as. It is not activated.
+    ///
+    /// # Arguments
+    /// - `version`: The version to copy.
+    /// - `store`: The name to record this client's server under in the copy's origin.
+    /// - `target`: A [`Client`] for the server to copy the version to.
+    ///
+    /// # Returns
+    /// The version number of the copy in the `target` server.
+    ///
+    /// # Errors
+    /// This function errors if any of the requests failed.
+    pub async fn promote_to<C: DeserializeOwned + Serialize>(&self, version: u64, store: &str, target: &Client) -> Result<u64, Error> {
+        let metadata: Metadata = self.get_version_metadata(version).await?;
+        let contents: C = self.get_version_content(version).await?;
+        debug!("Copying policy {version} to {:?}...", target.base);
+        target.import_version(store, metadata, contents).await
+    }
+}
@@ -0,0 +1,30 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:57:13
+//  Last edited:
+//    17 Oct 2026, 01:48:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a typed client for the HTTP API of the `axum-server`
+//!   using `reqwest`.
+//
+
+// Modules
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+mod cache;
+mod client;
+pub mod oci;
+mod policy;
+mod reconcile;
+// Re-exports
+pub use axum_server_spec as spec;
+// Use local parts
+pub use cache::{ActivePolicy, ActivePolicyCache};
+pub use client::*;
+pub use policy::{CircuitBreaker, RetryPolicy};
+pub use reconcile::{PolicyDocument, Reconciled};
@@ -0,0 +1,724 @@
+//  OCI.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:36:04
+//  Last edited:
+//    16 Oct 2026, 17:42:16
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements pushing policy versions to and pulling them from OCI
+//!   registries, as artifacts in the style of ORAS.
+//!
+//!   A version is stored as an image manifest with
+//!   [`POLICY_ARTIFACT_TYPE`] as its artifact type. Its config is the
+//!   version's [`Metadata`] and its single layer is its content in
+//!   [canonical form](specifications::canonical), such that the layer's
+//!   digest is the version's content hash. Because it is an ordinary
+//!   manifest, it can be signed (e.g., with cosign) like any image.
+//!   Signatures are looked up the way cosign stores them, i.e., as the
+//!   tag `sha256-<HEX>.sig` in the same repository.
+//
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::sync::{Arc, Mutex};
+
+use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, HeaderValue, LOCATION, WWW_AUTHENTICATE};
+use reqwest::{Request, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specifications::canonical::{content_hash, to_canonical_string};
+use specifications::metadata::Metadata;
+use thiserror::Error;
+use tracing::{debug, info};
+
+use crate::client::{Client, Error};
+use crate::spec::ContentSignature;
+
+
+/***** CONSTANTS *****/
+/// The artifact type of manifests holding a policy version.
+pub const POLICY_ARTIFACT_TYPE: &str = "application/vnd.policy-store.policy.v1";
+/// The media type of the config blob, i.e., the [`Metadata`] of a policy version.
+pub const POLICY_METADATA_MEDIA_TYPE: &str = "application/vnd.policy-store.metadata.v1+json";
+/// The media type of the layer blob, i.e., the content of a policy version.
+pub const POLICY_CONTENT_MEDIA_TYPE: &str = "application/vnd.policy-store.content.v1+json";
+/// The media type of OCI image manifests.
+const MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+/// The media type of Docker image manifests, which cosign may use for its signatures.
+const DOCKER_MANIFEST_MEDIA_TYPE: &str = "application/vnd.docker.distribution.manifest.v2+json";
+/// The media type of the layers holding cosign's signed payloads.
+const COSIGN_PAYLOAD_MEDIA_TYPE: &str = "application/vnd.dev.cosign.simplesigning.v1+json";
+/// The annotation in which cosign puts the signature over a payload.
+const COSIGN_SIGNATURE_ANNOTATION: &str = "dev.cosignproject.cosign/signature";
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors when talking to an OCI registry.
+#[derive(Debug, Error)]
+pub enum OciError {
+    /// Failed to build a request, e.g., because the registry address is invalid.
+    #[error("Failed to build request")]
+    Build {
+        #[source]
+        err: reqwest::Error,
+    },
+    /// A blob did not have the digest it was referred to by.
+    #[error("Blob {url:?} has digest {actual:?}, but was referred to as {expected:?}")]
+    DigestMismatch { url: String, expected: String, actual: String },
+    /// The registry asked us to authenticate in a way we don't understand.
+    #[error("Registry {url:?} asked for unsupported authentication {challenge:?}")]
+    IllegalChallenge { url: String, challenge: String },
+    /// The given string is not a reference to an artifact.
+    #[error("Invalid OCI reference {raw:?} (must be 'REGISTRY/REPOSITORY[:TAG|@DIGEST]')")]
+    IllegalReference { raw: String },
+    /// The registry did not say where to upload a blob.
+    #[error("Registry did not return where to upload to for {url:?}")]
+    MissingLocation { url: String },
+    /// The artifact is not a policy version.
+    #[error("Artifact {reference} is not a policy version (artifact type {artifact_type:?}, {layers} layer(s))")]
+    NotAPolicy { reference: OciReference, artifact_type: Option<String>, layers: usize },
+    /// Failed to send a request or to receive its response.
+    #[error("Failed to send request to {url:?}")]
+    Request {
+        url: String,
+        #[source]
+        err: reqwest::Error,
+    },
+    /// Failed to deserialize a response or blob.
+    #[error("Failed to deserialize response of {url:?}")]
+    Response {
+        url: String,
+        #[source]
+        err: serde_json::Error,
+    },
+    /// Failed to serialize a blob or manifest.
+    #[error("Failed to serialize artifact")]
+    Serialize {
+        #[source]
+        err: serde_json::Error,
+    },
+    /// The registry replied with a non-2xx status code.
+    #[error("Request to {url:?} failed with status {status}")]
+    Status { url: String, status: StatusCode },
+    /// Failed to read from or write to the store.
+    #[error("Failed to transfer policy version from or to the store")]
+    Store {
+        #[source]
+        err: Error,
+    },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Parses the parameters of an authentication challenge, e.g., `realm="...",service="..."`.
+///
+/// # Arguments
+/// - `params`: The parameters to parse, i.e., the challenge without its scheme.
+///
+/// # Returns
+/// The parameters as (lowercase) key/value pairs. Anything unparseable is skipped.
+fn parse_challenge(params: &str) -> BTreeMap<String, String> {
+    let mut res: BTreeMap<String, String> = BTreeMap::new();
+    let mut rem: &str = params.trim();
+    while let Some((key, rest)) = rem.split_once('=') {
+        let key: String = key.trim().trim_start_matches(',').trim().to_lowercase();
+        let rest: &str = rest.trim_start();
+        // NOTE: Values may be quoted, in which case they may contain commas (e.g., scopes)
+        let (value, rest): (&str, &str) = match rest.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, rest)) => (value, rest),
+                None => (quoted, ""),
+            },
+            None => rest.split_once(',').map(|(value, rest)| (value.trim(), rest)).unwrap_or((rest.trim(), "")),
+        };
+        res.insert(key, value.into());
+        rem = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+    }
+    res
+}
+
+/// Computes the digest of a blob.
+///
+/// # Arguments
+/// - `blob`: The blob to compute the digest of.
+///
+/// # Returns
+/// Its digest, as `sha256:<hex>`.
+#[inline]
+fn digest(blob: &str) -> String { format!("sha256:{}", content_hash(blob)) }
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Refers to an artifact in an OCI registry, e.g., `registry.example.com/policies/prod:v3`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OciReference {
+    /// The address of the registry, e.g., `registry.example.com` or `localhost:5000`.
+    pub registry:   String,
+    /// The repository in the registry, e.g., `policies/prod`.
+    pub repository: String,
+    /// The tag (e.g., `v3`) or digest (e.g., `sha256:...`) of the artifact.
+    pub reference:  String,
+}
+impl OciReference {
+    /// Parses a reference from its textual form.
+    ///
+    /// The registry must always be given. If neither a tag nor a digest is given, the tag
+    /// `latest` is assumed.
+    ///
+    /// # Arguments
+    /// - `raw`: The reference to parse, e.g., `registry.example.com/policies/prod:v3` or
+    ///   `localhost:5000/policies@sha256:...`.
+    ///
+    /// # Returns
+    /// A new OciReference.
+    ///
+    /// # Errors
+    /// This function errors if `raw` is not a reference.
+    pub fn parse(raw: &str) -> Result<Self, OciError> {
+        let illegal = || OciError::IllegalReference { raw: raw.into() };
+        let (registry, rest): (&str, &str) = raw.split_once('/').ok_or_else(illegal)?;
+        let (repository, reference): (&str, &str) = match rest.split_once('@') {
+            Some((repository, digest)) => (repository, digest),
+            None => match rest.rsplit_once(':') {
+                Some((repository, tag)) if !tag.contains('/') => (repository, tag),
+                _ => (rest, "latest"),
+            },
+        };
+        if registry.is_empty() || repository.is_empty() || reference.is_empty() {
+            return Err(illegal());
+        }
+        Ok(Self { registry: registry.into(), repository: repository.into(), reference: reference.into() })
+    }
+
+    /// Returns whether this reference refers to an artifact by digest rather than by tag.
+    ///
+    /// # Returns
+    /// True if the [reference](OciReference::reference) is a digest, or false otherwise.
+    #[inline]
+    pub fn is_digest(&self) -> bool { self.reference.contains(':') }
+}
+impl Display for OciReference {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        write!(f, "{}/{}{}{}", self.registry, self.repository, if self.is_digest() { '@' } else { ':' }, self.reference)
+    }
+}
+
+
+
+/// Describes a blob referred to by a [`Manifest`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Descriptor {
+    /// The media type of the blob.
+    pub media_type: String,
+    /// The digest of the blob.
+    pub digest: String,
+    /// The size of the blob, in bytes.
+    pub size: u64,
+    /// Any annotations of the blob.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// An OCI image manifest, as far as we need it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    /// The version of the manifest format. Always 2.
+    pub schema_version: u32,
+    /// The media type of the manifest.
+    #[serde(default)]
+    pub media_type: Option<String>,
+    /// The type of artifact described by the manifest, if it isn't an image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact_type: Option<String>,
+    /// The config blob.
+    pub config: Descriptor,
+    /// The layer blobs.
+    pub layers: Vec<Descriptor>,
+    /// Any annotations of the manifest.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A client for the [distribution API](https://github.com/opencontainers/distribution-spec) of
+/// OCI registries.
+///
+/// Registries that ask for a bearer token get one from the token service they point to, using the
+/// basic credentials given (if any). Clones share the same token.
+///
+/// Note that a single token is kept at a time, so a client is best used for one repository.
+#[derive(Clone, Debug)]
+pub struct OciClient {
+    /// The username and password to authenticate with, if any.
+    credentials: Option<(String, String)>,
+    /// Whether to talk plain HTTP instead of HTTPS, e.g., for a registry on localhost.
+    insecure: bool,
+    /// The `Authorization`-header to send, once a registry asked us to authenticate.
+    auth: Arc<Mutex<Option<HeaderValue>>>,
+    /// The client used to send requests.
+    client: reqwest::Client,
+}
+impl Default for OciClient {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+// Constructors
+impl OciClient {
+    /// Constructor for an OciClient that talks to registries anonymously over HTTPS.
+    ///
+    /// # Returns
+    /// A new OciClient.
+    #[inline]
+    pub fn new() -> Self { Self { credentials: None, insecure: false, auth: Arc::new(Mutex::new(None)), client: reqwest::Client::new() } }
+
+    /// Makes the client authenticate with a username and password.
+    ///
+    /// # Arguments
+    /// - `username`: The username to authenticate as.
+    /// - `password`: The password (or access token) to authenticate with.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Makes the client talk plain HTTP instead of HTTPS.
+    ///
+    /// Only meant for registries that are not reachable by anyone else, e.g., in tests.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn insecure(mut self) -> Self {
+        self.insecure = true;
+        self
+    }
+}
+
+// Requests
+impl OciClient {
+    /// Builds the URL of something in a repository.
+    ///
+    /// # Arguments
+    /// - `reference`: The [`OciReference`] to the repository.
+    /// - `path`: The path within the repository, e.g., `manifests/v3`.
+    ///
+    /// # Returns
+    /// The URL, as a string.
+    #[inline]
+    fn url(&self, reference: &OciReference, path: &str) -> String {
+        format!("{}://{}/v2/{}/{path}", if self.insecure { "http" } else { "https" }, reference.registry, reference.repository)
+    }
+
+    /// Answers an authentication challenge of a registry.
+    ///
+    /// Bearer challenges are answered by fetching a token from the token service the registry
+    /// points to, using the basic credentials (if any). Basic challenges are answered with the
+    /// credentials directly.
+    ///
+    /// # Arguments
+    /// - `url`: The URL of the request that was challenged (for errors).
+    /// - `challenge`: The `WWW-Authenticate`-header the registry sent.
+    ///
+    /// # Errors
+    /// This function errors if the challenge is not understood, if it asks for credentials we
+    /// don't have, or if the token service failed to hand out a token.
+    async fn authenticate(&self, url: &str, challenge: &str) -> Result<(), OciError> {
+        let illegal = || OciError::IllegalChallenge { url: url.into(), challenge: challenge.into() };
+        let (scheme, params): (&str, &str) = challenge.split_once(' ').unwrap_or((challenge, ""));
+
+        // Basic challenges are answered with the credentials as-is
+        if scheme.eq_ignore_ascii_case("basic") {
+            let Some((username, password)) = &self.credentials else { return Err(illegal()) };
+            // NOTE: Building a request is the easiest way to encode the credentials
+            let req: Request = self.client.get(url).basic_auth(username, Some(password)).build().map_err(|err| OciError::Build { err })?;
+            *self.auth.lock().unwrap() = req.headers().get(AUTHORIZATION).cloned();
+            return Ok(());
+        } else if !scheme.eq_ignore_ascii_case("bearer") {
+            return Err(illegal());
+        }
+
+        // Bearer challenges are answered by asking the token service for a token
+        let params: BTreeMap<String, String> = parse_challenge(params);
+        let realm: &str = params.get("realm").ok_or_else(illegal)?;
+        debug!("Fetching token from {realm:?}...");
+        let query: Vec<(&str, &str)> = ["service", "scope"].into_iter().filter_map(|key| Some((key, params.get(key)?.as_str()))).collect();
+        let mut req: RequestBuilder = self.client.get(realm).query(&query);
+        if let Some((username, password)) = &self.credentials {
+            req = req.basic_auth(username, Some(password));
+        }
+        let res: Response = req.send().await.map_err(|err| OciError::Request { url: realm.into(), err })?;
+        if !res.status().is_success() {
+            return Err(OciError::Status { url: realm.into(), status: res.status() });
+        }
+        let body: String = res.text().await.map_err(|err| OciError::Request { url: realm.into(), err })?;
+        let body: Value = serde_json::from_str(&body).map_err(|err| OciError::Response { url: realm.into(), err })?;
+        // NOTE: Token services may use either field, per the Docker token spec
+        let token: &str = body.get("token").or_else(|| body.get("access_token")).and_then(Value::as_str).ok_or_else(illegal)?;
+        *self.auth.lock().unwrap() = Some(HeaderValue::from_str(&format!("Bearer {token}")).map_err(|_| illegal())?);
+        Ok(())
+    }
+
+    /// Sends a request and checks that it succeeded, authenticating first if the registry asks
+    /// us to.
+    ///
+    /// # Arguments
+    /// - `req`: The [`RequestBuilder`] to send.
+    ///
+    /// # Returns
+    /// The successful [`Response`].
+    ///
+    /// # Errors
+    /// This function errors if we failed to send the request or to authenticate, or if the
+    /// registry replied with a non-2xx status code.
+    async fn send(&self, req: RequestBuilder) -> Result<Response, OciError> {
+        let mut req: Request = req.build().map_err(|err| OciError::Build { err })?;
+        let url: String = req.url().to_string();
+
+        let mut challenged: bool = false;
+        loop {
+            if let Some(auth) = self.auth.lock().unwrap().clone() {
+                req.headers_mut().insert(AUTHORIZATION, auth);
+            }
+
+            // Answer the first challenge, if any, and then try again
+            // NOTE: Cloning only fails for streamed bodies, which we never send
+            let retry: Option<Request> = if challenged { None } else { req.try_clone() };
+            let res: Response = self.client.execute(req).await.map_err(|err| OciError::Request { url: url.clone(), err })?;
+            if let (StatusCode::UNAUTHORIZED, Some(retry)) = (res.status(), retry) {
+                if let Some(challenge) = res.headers().get(WWW_AUTHENTICATE).and_then(|challenge| challenge.to_str().ok()) {
+                    self.authenticate(&url, challenge).await?;
+                    challenged = true;
+                    req = retry;
+                    continue;
+                }
+            }
+
+            if !res.status().is_success() {
+                return Err(OciError::Status { url, status: res.status() });
+            }
+            return Ok(res);
+        }
+    }
+
+    /// Uploads a blob, unless the registry already has it.
+    ///
+    /// # Arguments
+    /// - `reference`: The [`OciReference`] to the repository to upload to.
+    /// - `media_type`: The media type of the blob.
+    /// - `blob`: The blob itself.
+    ///
+    /// # Returns
+    /// A [`Descriptor`] for the uploaded blob.
+    ///
+    /// # Errors
+    /// This function errors if the upload failed.
+    async fn push_blob(&self, reference: &OciReference, media_type: &str, blob: String) -> Result<Descriptor, OciError> {
+        let desc = Descriptor { media_type: media_type.into(), digest: digest(&blob), size: blob.len() as u64, annotations: BTreeMap::new() };
+
+        // Don't upload what's already there
+        match self.send(self.client.head(self.url(reference, &format!("blobs/{}", desc.digest)))).await {
+            Ok(_) => {
+                debug!("Registry already has blob {}", desc.digest);
+                return Ok(desc);
+            },
+            Err(OciError::Status { status: StatusCode::NOT_FOUND, .. }) => {},
+            Err(err) => return Err(err),
+        }
+
+        // Start an upload, then send everything in one go
+        let url: String = self.url(reference, "blobs/uploads/");
+        let res: Response = self.send(self.client.post(&url)).await?;
+        let location: &str =
+            res.headers().get(LOCATION).and_then(|location| location.to_str().ok()).ok_or(OciError::MissingLocation { url: url.clone() })?;
+        // NOTE: The location may be relative, and may already have a query
+        let mut location: Url = Url::parse(&url).and_then(|url| url.join(location)).map_err(|_| OciError::MissingLocation { url })?;
+        location.query_pairs_mut().append_pair("digest", &desc.digest);
+        debug!("Uploading blob {} ({} bytes)...", desc.digest, desc.size);
+        self.send(self.client.put(location).header(CONTENT_TYPE, "application/octet-stream").body(blob)).await?;
+        Ok(desc)
+    }
+
+    /// Downloads a blob and checks its digest.
+    ///
+    /// # Arguments
+    /// - `reference`: The [`OciReference`] to the repository to download from.
+    /// - `desc`: The [`Descriptor`] of the blob to download.
+    ///
+    /// # Returns
+    /// The blob.
+    ///
+    /// # Errors
+    /// This function errors if the download failed, or if the blob did not match its digest.
+    async fn pull_blob(&self, reference: &OciReference, desc: &Descriptor) -> Result<String, OciError> {
+        let url: String = self.url(reference, &format!("blobs/{}", desc.digest));
+        let res: Response = self.send(self.client.get(&url)).await?;
+        let blob: String = res.text().await.map_err(|err| OciError::Request { url: url.clone(), err })?;
+        let actual: String = digest(&blob);
+        if actual != desc.digest {
+            return Err(OciError::DigestMismatch { url, expected: desc.digest.clone(), actual });
+        }
+        Ok(blob)
+    }
+
+
+
+    /// Pushes a policy version to a registry.
+    ///
+    /// # Arguments
+    /// - `reference`: The [`OciReference`] to push to. Usually refers to a tag.
+    /// - `metadata`: The [`Metadata`] of the version.
+    /// - `contents`: The contents of the version.
+    ///
+    /// # Returns
+    /// The digest of the pushed manifest, e.g., to sign it.
+    ///
+    /// # Errors
+    /// This function errors if the contents could not be serialized, or if any request failed.
+    pub async fn push<C: Serialize>(&self, reference: &OciReference, metadata: &Metadata, contents: &C) -> Result<String, OciError> {
+        let config: String = serde_json::to_string(metadata).map_err(|err| OciError::Serialize { err })?;
+        let content: String = to_canonical_string(contents).map_err(|err| OciError::Serialize { err })?;
+
+        // Upload the blobs first, then the manifest that refers to them
+        let config: Descriptor = self.push_blob(reference, POLICY_METADATA_MEDIA_TYPE, config).await?;
+        let mut layer: Descriptor = self.push_blob(reference, POLICY_CONTENT_MEDIA_TYPE, content).await?;
+        layer.annotations.insert("org.opencontainers.image.title".into(), format!("{}-{}.json", metadata.attached.name, metadata.version));
+        let manifest = Manifest {
+            schema_version: 2,
+            media_type: Some(MANIFEST_MEDIA_TYPE.into()),
+            artifact_type: Some(POLICY_ARTIFACT_TYPE.into()),
+            config,
+            layers: vec![layer],
+            annotations: BTreeMap::from([
+                ("org.opencontainers.image.created".into(), metadata.created.to_rfc3339()),
+                ("org.opencontainers.image.description".into(), metadata.attached.description.clone()),
+                ("org.opencontainers.image.title".into(), metadata.attached.name.clone()),
+                ("org.opencontainers.image.version".into(), metadata.version.to_string()),
+            ]),
+        };
+        let manifest: String = serde_json::to_string(&manifest).map_err(|err| OciError::Serialize { err })?;
+        let manifest_digest: String = digest(&manifest);
+        self.send(
+            self.client
+                .put(self.url(reference, &format!("manifests/{}", reference.reference)))
+                .header(CONTENT_TYPE, MANIFEST_MEDIA_TYPE)
+                .body(manifest),
+        )
+        .await?;
+        info!("Pushed policy version {} to {reference} ({manifest_digest})", metadata.version);
+        Ok(manifest_digest)
+    }
+
+    /// Pulls a policy version from a registry.
+    ///
+    /// # Arguments
+    /// - `reference`: The [`OciReference`] to pull.
+    ///
+    /// # Returns
+    /// The [`Metadata`] of the version as it was pushed, and its contents.
+    ///
+    /// # Errors
+    /// This function errors if any request failed, if the artifact is not a policy version, or if
+    /// it does not match its digests.
+    #[inline]
+    pub async fn pull<C: DeserializeOwned>(&self, reference: &OciReference) -> Result<(Metadata, C), OciError> {
+        let (metadata, contents, _): (Metadata, C, String) = self.pull_manifest(reference).await?;
+        Ok((metadata, contents))
+    }
+
+    /// Pulls a policy version from a registry, together with its cosign signature (if any).
+    ///
+    /// Note that the signature is not checked here; that's up to whoever trusts its signer (e.g.,
+    /// the server the version is [imported](Client::import_signed_version()) into).
+    ///
+    /// # Arguments
+    /// - `reference`: The [`OciReference`] to pull.
+    ///
+    /// # Returns
+    /// The [`Metadata`] of the version as it was pushed, its contents, and a
+    /// [`ContentSignature::Manifest`] if the artifact was signed.
+    ///
+    /// # Errors
+    /// This function errors if any request failed, if the artifact is not a policy version, or if
+    /// it (or its signature) does not match its digests.
+    pub async fn pull_signed<C: DeserializeOwned>(&self, reference: &OciReference) -> Result<(Metadata, C, Option<ContentSignature>), OciError> {
+        let (metadata, contents, manifest): (Metadata, C, String) = self.pull_manifest(reference).await?;
+
+        // Find the signature next to it
+        let sig_ref = OciReference {
+            registry:   reference.registry.clone(),
+            repository: reference.repository.clone(),
+            reference:  format!("{}.sig", digest(&manifest).replace(':', "-")),
+        };
+        let url: String = self.url(&sig_ref, &format!("manifests/{}", sig_ref.reference));
+        let accept: String = format!("{MANIFEST_MEDIA_TYPE}, {DOCKER_MANIFEST_MEDIA_TYPE}");
+        let res: Response = match self.send(self.client.get(&url).header(ACCEPT, accept)).await {
+            Ok(res) => res,
+            Err(OciError::Status { status: StatusCode::NOT_FOUND, .. }) => {
+                debug!("Policy version at {reference} is not signed");
+                return Ok((metadata, contents, None));
+            },
+            Err(err) => return Err(err),
+        };
+        let sig_manifest: Manifest = res.json().await.map_err(|err| OciError::Request { url: url.clone(), err })?;
+        let Some((layer, signature)) = sig_manifest
+            .layers
+            .iter()
+            .filter(|layer| layer.media_type == COSIGN_PAYLOAD_MEDIA_TYPE)
+            .find_map(|layer| layer.annotations.get(COSIGN_SIGNATURE_ANNOTATION).map(|sig| (layer, sig)))
+        else {
+            debug!("Signature manifest of {reference} holds no cosign signatures");
+            return Ok((metadata, contents, None));
+        };
+        let payload: String = self.pull_blob(&sig_ref, layer).await?;
+        Ok((metadata, contents, Some(ContentSignature::Manifest { manifest, payload, signature: signature.clone() })))
+    }
+
+    /// Pulls a policy version from a registry, keeping the manifest it was pulled by.
+    ///
+    /// # Arguments
+    /// - `reference`: The [`OciReference`] to pull.
+    ///
+    /// # Returns
+    /// The [`Metadata`] of the version as it was pushed, its contents and the raw manifest.
+    ///
+    /// # Errors
+    /// This function errors if any request failed, if the artifact is not a policy version, or if
+    /// it does not match its digests.
+    async fn pull_manifest<C: DeserializeOwned>(&self, reference: &OciReference) -> Result<(Metadata, C, String), OciError> {
+        let url: String = self.url(reference, &format!("manifests/{}", reference.reference));
+        let res: Response = self.send(self.client.get(&url).header(ACCEPT, MANIFEST_MEDIA_TYPE)).await?;
+        let manifest: String = res.text().await.map_err(|err| OciError::Request { url: url.clone(), err })?;
+        if reference.is_digest() && digest(&manifest) != reference.reference {
+            return Err(OciError::DigestMismatch { url, expected: reference.reference.clone(), actual: digest(&manifest) });
+        }
+        let raw: String = manifest;
+        let manifest: Manifest = serde_json::from_str(&raw).map_err(|err| OciError::Response { url, err })?;
+        let [layer] = manifest.layers.as_slice() else {
+            return Err(OciError::NotAPolicy { reference: reference.clone(), artifact_type: manifest.artifact_type, layers: manifest.layers.len() });
+        };
+        if manifest.artifact_type.as_deref() != Some(POLICY_ARTIFACT_TYPE) || layer.media_type != POLICY_CONTENT_MEDIA_TYPE {
+            return Err(OciError::NotAPolicy { reference: reference.clone(), artifact_type: manifest.artifact_type, layers: 1 });
+        }
+
+        // Download the blobs
+        let config: String = self.pull_blob(reference, &manifest.config).await?;
+        let metadata: Metadata = serde_json::from_str(&config)
+            .map_err(|err| OciError::Response { url: self.url(reference, &format!("blobs/{}", manifest.config.digest)), err })?;
+        let content: String = self.pull_blob(reference, layer).await?;
+        let contents: C =
+            serde_json::from_str(&content).map_err(|err| OciError::Response { url: self.url(reference, &format!("blobs/{}", layer.digest)), err })?;
+        info!("Pulled policy version {} from {reference}", metadata.version);
+        Ok((metadata, contents, raw))
+    }
+}
+
+// Transfers
+impl Client {
+    /// Pushes a policy version from this client's server to an OCI registry.
+    ///
+    /// # Arguments
+    /// - `version`: The version to push.
+    /// - `registry`: The [`OciClient`] to push with.
+    /// - `reference`: The [`OciReference`] to push to.
+    ///
+    /// # Returns
+    /// The digest of the pushed manifest, e.g., to sign it.
+    ///
+    /// # Errors
+    /// This function errors if any of the requests failed.
+    pub async fn push_to_registry(&self, version: u64, registry: &OciClient, reference: &OciReference) -> Result<String, OciError> {
+        let metadata: Metadata = self.get_version_metadata(version).await.map_err(|err| OciError::Store { err })?;
+        let contents: Value = self.get_version_content(version).await.map_err(|err| OciError::Store { err })?;
+        registry.push(reference, &metadata, &contents).await
+    }
+
+    /// Pulls a policy version from an OCI registry into this client's server.
+    ///
+    /// The version is [imported](Client::import_signed_version()) like a promoted one, recording
+    /// the `reference` as the store it came from (unless it was imported before). Its contents are
+    /// checked against the hash they were pushed with. Its cosign signature, if any, is passed
+    /// along for the server to verify. It is not activated.
+    ///
+    /// # Arguments
+    /// - `registry`: The [`OciClient`] to pull with.
+    /// - `reference`: The [`OciReference`] to pull.
+    ///
+    /// # Returns
+    /// The version number of the copy in this client's server.
+    ///
+    /// # Errors
+    /// This function errors if any of the requests failed, including when the contents don't
+    /// match their hash.
+    pub async fn pull_from_registry(&self, registry: &OciClient, reference: &OciReference) -> Result<u64, OciError> {
+        let (metadata, contents, signature): (Metadata, Value, Option<ContentSignature>) = registry.pull_signed(reference).await?;
+        self.import_signed_version(reference.to_string(), metadata, contents, signature).await.map_err(|err| OciError::Store { err })
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reference() {
+        let reference = OciReference::parse("registry.example.com/policies/prod:v3").unwrap();
+        assert_eq!(
+            (reference.registry.as_str(), reference.repository.as_str(), reference.reference.as_str()),
+            ("registry.example.com", "policies/prod", "v3")
+        );
+        assert_eq!(reference.to_string(), "registry.example.com/policies/prod:v3");
+
+        let reference = OciReference::parse("localhost:5000/policies").unwrap();
+        assert_eq!(
+            (reference.registry.as_str(), reference.repository.as_str(), reference.reference.as_str()),
+            ("localhost:5000", "policies", "latest")
+        );
+
+        let reference = OciReference::parse("localhost:5000/policies@sha256:abcd").unwrap();
+        assert!(reference.is_digest());
+        assert_eq!(reference.to_string(), "localhost:5000/policies@sha256:abcd");
+
+        assert!(OciReference::parse("policies").is_err());
+        assert!(OciReference::parse("registry.example.com/").is_err());
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        let params =
+            parse_challenge(r#"realm="https://auth.example.com/token",service="registry.example.com",scope="repository:policies:pull,push""#);
+        assert_eq!(params.get("realm").map(String::as_str), Some("https://auth.example.com/token"));
+        assert_eq!(params.get("service").map(String::as_str), Some("registry.example.com"));
+        assert_eq!(params.get("scope").map(String::as_str), Some("repository:policies:pull,push"));
+    }
+}
@@ -0,0 +1,206 @@
+//  POLICY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:02:28
+//  Last edited:
+//    17 Oct 2026, 01:48:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines how the [`Client`](crate::Client) copes with failing
+//!   requests, i.e., when it retries and when it stops trying altogether.
+//
+
+use std::hash::{BuildHasher as _, Hasher as _, RandomState};
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+
+/***** HELPER FUNCTIONS *****/
+/// Picks a random duration between zero and the given maximum.
+///
+/// # Arguments
+/// - `max`: The maximum duration to return.
+///
+/// # Returns
+/// A [`Duration`] in `[0, max]`.
+fn jitter(max: Duration) -> Duration {
+    // NOTE: Every `RandomState` is seeded differently, which is plenty random to spread out retries
+    let rand: u64 = RandomState::new().build_hasher().finish();
+    max.mul_f64(rand as f64 / u64::MAX as f64)
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Configures how often and how fast idempotent requests are retried.
+///
+/// Only transport failures and server replies that suggest trying again later (429, 502, 503 and
+/// 504) are retried. Delays grow exponentially and are fully jittered, such that clients that
+/// failed together don't retry together.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// The maximum number of retries after the first attempt.
+    pub max_retries: u32,
+    /// The maximum delay before the first retry. Doubles for every next retry.
+    pub base_delay:  Duration,
+    /// The maximum delay before any retry.
+    pub max_delay:   Duration,
+}
+impl Default for RetryPolicy {
+    /// Retries three times, waiting up to 100ms, 200ms and 400ms.
+    #[inline]
+    fn default() -> Self { Self { max_retries: 3, base_delay: Duration::from_millis(100), max_delay: Duration::from_secs(10) } }
+}
+impl RetryPolicy {
+    /// Constructor for a RetryPolicy that never retries.
+    ///
+    /// # Returns
+    /// A new RetryPolicy with zero retries.
+    #[inline]
+    pub const fn none() -> Self { Self { max_retries: 0, base_delay: Duration::ZERO, max_delay: Duration::ZERO } }
+
+    /// Computes how long to wait before a retry.
+    ///
+    /// # Arguments
+    /// - `attempt`: The number of retries done so far.
+    ///
+    /// # Returns
+    /// A random [`Duration`] up to the (capped) exponential delay for this attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let max: Duration = self.base_delay.saturating_mul(2u32.saturating_pow(attempt)).min(self.max_delay);
+        jitter(max)
+    }
+}
+
+
+
+/// Configures when the client stops sending requests to a failing server.
+///
+/// After `threshold` consecutive transport failures or 5xx replies, the circuit opens and every
+/// request fails immediately for `cooldown`. After that, requests are let through again, but the
+/// first failure reopens the circuit.
+#[derive(Clone, Copy, Debug)]
+pub struct CircuitBreaker {
+    /// The number of consecutive failures after which to open the circuit.
+    pub threshold: u32,
+    /// How long the circuit stays open.
+    pub cooldown:  Duration,
+}
+
+
+
+/// Keeps track of the state of a [`CircuitBreaker`].
+#[derive(Debug)]
+pub(crate) struct Circuit {
+    /// The configuration of the breaker.
+    breaker: CircuitBreaker,
+    /// The number of consecutive failures and, if open, until when.
+    state:   Mutex<(u32, Option<Instant>)>,
+}
+impl Circuit {
+    /// Constructor for a closed Circuit.
+    ///
+    /// # Arguments
+    /// - `breaker`: The [`CircuitBreaker`] configuring it.
+    ///
+    /// # Returns
+    /// A new Circuit that lets requests through.
+    #[inline]
+    pub(crate) fn new(breaker: CircuitBreaker) -> Self { Self { breaker, state: Mutex::new((0, None)) } }
+
+    /// Checks whether a request may be sent.
+    ///
+    /// # Returns
+    /// True if it may, or false if the circuit is open.
+    pub(crate) fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        match state.1 {
+            Some(until) if Instant::now() < until => false,
+            Some(_) => {
+                // Half-open: let this one through, but open again on the first failure
+                *state = (self.breaker.threshold.saturating_sub(1), None);
+                true
+            },
+            None => true,
+        }
+    }
+
+    /// Checks whether the circuit is open, without letting a request through if it's half-open.
+    ///
+    /// # Returns
+    /// True if requests are currently refused.
+    pub(crate) fn is_open(&self) -> bool {
+        let state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        state.1.map(|until| Instant::now() < until).unwrap_or(false)
+    }
+
+    /// Records the outcome of a request.
+    ///
+    /// # Arguments
+    /// - `failed`: Whether the request failed in a way that counts against the server.
+    pub(crate) fn record(&self, failed: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        if !failed {
+            *state = (0, None);
+            return;
+        }
+        state.0 = state.0.saturating_add(1);
+        if state.0 >= self.breaker.threshold {
+            state.1 = Some(Instant::now() + self.breaker.cooldown);
+        }
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = RetryPolicy { max_retries: 10, base_delay: Duration::from_millis(100), max_delay: Duration::from_secs(1) };
+        for attempt in 0..10 {
+            let max: Duration = Duration::from_millis(100 * 2u64.pow(attempt)).min(Duration::from_secs(1));
+            assert!(policy.backoff(attempt) <= max);
+        }
+        assert_eq!(RetryPolicy::none().backoff(3), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_circuit_opens_and_half_opens() {
+        let circuit = Circuit::new(CircuitBreaker { threshold: 2, cooldown: Duration::from_millis(50) });
+        circuit.record(true);
+        assert!(!circuit.is_open());
+        assert!(circuit.allow());
+        circuit.record(true);
+        assert!(circuit.is_open());
+        assert!(!circuit.allow());
+
+        // After the cooldown, requests are let through, but a single failure opens it again
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(circuit.allow());
+        circuit.record(true);
+        assert!(!circuit.allow());
+
+        // Whereas a success closes it
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(circuit.allow());
+        circuit.record(false);
+        circuit.record(true);
+        assert!(circuit.allow());
+    }
+}
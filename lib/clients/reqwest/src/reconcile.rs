@@ -0,0 +1,102 @@
+//  RECONCILE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:12:22
+//  Last edited:
+//    17 Oct 2026, 02:36:26
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements reconciling a declared policy into a store, such that
+//!   the active policy can be managed declaratively (e.g., with GitOps).
+//
+
+use serde::{Deserialize, Serialize};
+use specifications::canonical::{content_hash, to_canonical_string};
+use specifications::metadata::{AttachedMetadata, Metadata};
+use tracing::{debug, info};
+
+use crate::client::{Client, Error};
+
+
+/***** LIBRARY *****/
+/// A policy as declared outside of the store, e.g., in a file mounted from a Kubernetes ConfigMap.
+///
+/// Its serialized form is the same as the body of a request to add a version.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PolicyDocument<C> {
+    /// The metadata to attach to the policy.
+    pub metadata: AttachedMetadata,
+    /// The contents of the policy itself.
+    pub contents: C,
+}
+
+/// Describes what [`Client::reconcile()`] did to make a [`PolicyDocument`] the active policy.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Reconciled {
+    /// The document already was the active policy.
+    UpToDate { version: u64 },
+    /// The document already was in the store, and has been activated.
+    Activated { version: u64 },
+    /// The document has been added to the store and activated.
+    Added { version: u64 },
+}
+impl Reconciled {
+    /// Returns the version that is now active.
+    ///
+    /// # Returns
+    /// The version number of the document in the store.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        match self {
+            Self::UpToDate { version } | Self::Activated { version } | Self::Added { version } => *version,
+        }
+    }
+}
+
+// Reconciliation
+impl Client {
+    /// Makes the given document the active policy of the server, adding it if needed.
+    ///
+    /// Documents are recognised by the hash of their contents and their branch, so re-applying
+    /// the same document does not add a new version. If a document is already in the store, its
+    /// latest version is activated again.
+    ///
+    /// Note that documents whose contents are changed by the server when added (e.g., by a
+    /// [`ContentTransformer`](specifications::ContentTransformer)) are never recognised, and so
+    /// are added again every time.
+    ///
+    /// # Arguments
+    /// - `document`: The [`PolicyDocument`] to make active.
+    ///
+    /// # Returns
+    /// What was done to make the `document` active, as [`Reconciled`].
+    ///
+    /// # Errors
+    /// This function errors if the contents could not be serialized or if any request failed.
+    pub async fn reconcile<C: Serialize>(&self, document: &PolicyDocument<C>) -> Result<Reconciled, Error> {
+        let hash: String = content_hash(&to_canonical_string(&document.contents).map_err(|err| Error::Serialize { err })?);
+        let matches =
+            |metadata: &Metadata| -> bool { metadata.hash.as_deref() == Some(hash.as_str()) && metadata.attached.branch == document.metadata.branch };
+
+        // Nothing to do if it's already active
+        if let Some(active) = self.get_active_version().await? {
+            if matches(&self.get_version_metadata(active).await?) {
+                debug!("Policy {:?} is already active as version {active}", document.metadata.name);
+                return Ok(Reconciled::UpToDate { version: active });
+            }
+        }
+
+        // Otherwise, activate it, adding it first if it's not there yet
+        let existing: Option<u64> = self.get_versions().await?.into_values().filter(matches).map(|metadata| metadata.version).max();
+        let res: Reconciled = match existing {
+            Some(version) => Reconciled::Activated { version },
+            None => Reconciled::Added { version: self.add_version(document.metadata.clone(), &document.contents).await? },
+        };
+        self.activate(res.version()).await?;
+        info!("Reconciled policy {:?} as version {}", document.metadata.name, res.version());
+        Ok(res)
+    }
+}
@@ -0,0 +1,2464 @@
+//  CONNECTION.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:06:42
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the [`DatabaseConnection`] shared by the diesel-based
+//!   backends.
+//!
+//!   Diesel's queries are typed by the backend they run on, so this is a
+//!   macro that every backend expands against its own `schema` and `dialect`
+//!   modules. The latter provides whatever the SQL dialects disagree on
+//!   (e.g., how to upsert or to lock the database for writing).
+//
+
+
+/***** LIBRARY *****/
+/// Defines the `DieselConnection` (and the functions around it) in the module it is expanded in.
+///
+/// The crate expanding it must define, at its root, a `schema` module with the tables of the
+/// backend, a `models` module expanded from [`models!`](crate::models!) and a `dialect` module
+/// with:
+/// - `RawConnection`: The diesel connection type of the backend;
+/// - `Backend`: The diesel backend type of the backend;
+/// - `exclusive_transaction()` and `snapshot_transaction()`: Runs work in a transaction that
+///   excludes other writers or that sees a single snapshot, respectively; and
+/// - `upsert_current_active()`, `upsert_template()`, `insert_revoked_token()`,
+///   `upsert_revoked_user()` and `upsert_event_cursor()`: Writes the given model without failing
+///   on a duplicate key.
+///
+/// Besides that, the crate expanding it must depend on the crates the connection uses (`chrono`,
+/// `deadpool`, `deadpool-diesel`, `diesel`, `serde`, `serde_json`, `specifications`, `tokio` and
+/// `tracing`).
+// NOTE: `crate` deliberately refers to the backend expanding it, which owns the schema
+#[allow(clippy::crate_in_macro_def)]
+#[macro_export]
+macro_rules! connection {
+    () => {
+        use std::collections::{HashMap, HashSet};
+        use std::future::Future;
+        use std::marker::PhantomData;
+        use std::net::IpAddr;
+        use std::time::Instant;
+
+        use chrono::{DateTime, NaiveDateTime, Utc};
+        use deadpool::managed::Object;
+        use deadpool_diesel::Manager;
+        use diesel::connection::LoadConnection;
+        use diesel::{ExpressionMethods as _, OptionalExtension as _, QueryDsl as _, RunQueryDsl as _, SelectableHelper as _};
+        use serde::Serialize;
+        use serde::de::DeserializeOwned;
+        use specifications::audit::{AuditAction, AuditRecord, Change, verify_chain};
+        use specifications::canonical::{content_hash, to_canonical_string};
+        use specifications::consistency::Finding;
+        use specifications::databaseconn::{ClientError, DatabaseConnection};
+        use specifications::decision::Decision;
+        use specifications::key::{AttachedKey, Key, KeyKind};
+        use specifications::metadata::{Activation, AttachedMetadata, Branch, Freeze, MAX_VERSION, Metadata, Origin, User, Verification};
+        use specifications::provenance::Provenance;
+        use specifications::revocation::{AttachedRevocation, Revocation};
+        use specifications::schema::{AttachedSchema, Schema};
+        use specifications::template::{AttachedTemplate, Template, TemplateParameter};
+        use tracing::{Level, debug, info, span, warn};
+        use $crate::ConnectionError;
+
+        use crate::dialect::{
+            Backend, RawConnection, exclusive_transaction, insert_revoked_token, snapshot_transaction, upsert_current_active, upsert_event_cursor,
+            upsert_revoked_user, upsert_template,
+        };
+        use crate::models::{
+            DbActiveVersion, DbAuditRecord, DbBranch, DbCurrentActive, DbDecision, DbEventCursor, DbKey, DbPolicy, DbPolicyMetadata, DbProvenance,
+            DbRevokedToken, DbRevokedUser, DbSchema, DbTemplate,
+        };
+
+
+
+
+
+        /***** HELPER FUNCTIONS *****/
+        /// Converts a version number to the signed integer it is stored as in the database.
+        ///
+        /// # Arguments
+        /// - `version`: The version number to convert.
+        ///
+        /// # Returns
+        /// The version as stored, or [`None`] if it is higher than [`MAX_VERSION`] (and can thus never
+        /// exist).
+        #[inline]
+        fn version_to_sql(version: u64) -> Option<i64> { i64::try_from(version).ok() }
+
+        /// Converts a version number as stored in the database back to a version number.
+        ///
+        /// # Arguments
+        /// - `version`: The stored version number to convert.
+        ///
+        /// # Returns
+        /// The version number.
+        ///
+        /// # Errors
+        /// This function errors if the stored version is negative, which it can only be if the database
+        /// was tampered with.
+        #[inline]
+        fn version_from_sql(version: i64) -> Result<u64, ConnectionError> {
+            u64::try_from(version).map_err(|_| ConnectionError::InvalidVersion { version })
+        }
+
+        /// Converts a [`DbPolicyMetadata`] as stored in the database to a [`Metadata`].
+        ///
+        /// # Arguments
+        /// - `model`: The [`DbPolicyMetadata`] to convert.
+        ///
+        /// # Returns
+        /// An equivalent [`Metadata`].
+        ///
+        /// # Errors
+        /// This function errors if any of the stored versions was not a valid version number.
+        fn metadata_from_model(model: DbPolicyMetadata) -> Result<Metadata, ConnectionError> {
+            Ok(Metadata {
+                attached: AttachedMetadata {
+                    name: model.name,
+                    description: model.description,
+                    language: model.language,
+                    branch: model.branch,
+                    schema: model.schema,
+                    confidential: model.confidential,
+                },
+                created: model.created_at.and_utc(),
+                creator: User { id: model.creator, name: "John Smith".into(), issuer: model.creator_issuer, roles: Vec::new() },
+                version: version_from_sql(model.version)?,
+                parent: model.parent.map(version_from_sql).transpose()?,
+                promoted_from: model.promoted_from.map(version_from_sql).transpose()?,
+                hash: model.content_hash,
+                origin: match (model.origin_store, model.origin_version, model.origin_creator, model.origin_created_at) {
+                    (Some(store), Some(version), Some(creator), Some(created)) => {
+                        Some(Origin { store, version: version_from_sql(version)?, creator, created: created.and_utc() })
+                    },
+                    _ => None,
+                },
+                verification: match (model.verified_identity, model.verified_digest, model.verified_at) {
+                    (Some(identity), Some(digest), Some(verified)) => Some(Verification { identity, digest, verified: verified.and_utc() }),
+                    _ => None,
+                },
+                frozen: match (model.frozen_reason, model.frozen_by, model.frozen_at) {
+                    (Some(reason), Some(id), Some(frozen)) => Some(Freeze {
+                        reason,
+                        frozen: frozen.and_utc(),
+                        freezer: User { id, name: "John Smith".into(), issuer: model.frozen_by_issuer, roles: Vec::new() },
+                    }),
+                    _ => None,
+                },
+            })
+        }
+
+        /// Converts a [`DbActiveVersion`] as stored in the database to an [`Activation`].
+        ///
+        /// # Arguments
+        /// - `model`: The [`DbActiveVersion`] to convert.
+        ///
+        /// # Returns
+        /// An equivalent [`Activation`].
+        ///
+        /// # Errors
+        /// This function errors if the stored version was not a valid version number.
+        fn activation_from_model(model: DbActiveVersion) -> Result<Activation, ConnectionError> {
+            Ok(Activation {
+                version:     version_from_sql(model.version)?,
+                activated:   model.activated_on.and_utc(),
+                activator:   User { id: model.activated_by, name: "John Smith".into(), issuer: None, roles: Vec::new() },
+                client:      model.client.and_then(|client| client.parse().ok()),
+                deactivated: model.deactivated_on.map(|on| on.and_utc()),
+                deactivator: model.deactivated_by.map(|id| User { id, name: "John Smith".into(), issuer: None, roles: Vec::new() }),
+            })
+        }
+
+        /// Converts a [`DbAuditRecord`] as stored in the database to an [`AuditRecord`].
+        ///
+        /// # Arguments
+        /// - `model`: The [`DbAuditRecord`] to convert.
+        ///
+        /// # Returns
+        /// An equivalent [`AuditRecord`].
+        ///
+        /// # Errors
+        /// This function errors if the action of the record was not valid JSON.
+        fn audit_from_model(model: DbAuditRecord) -> Result<AuditRecord, ConnectionError> {
+            let action: AuditAction =
+                serde_json::from_str(&model.action).map_err(|err| ConnectionError::AuditDeserialize { seq: model.seq as u64, err })?;
+            Ok(AuditRecord {
+                seq: model.seq as u64,
+                timestamp: model.timestamp.and_utc(),
+                user: model.user_id,
+                // NOTE: An unparsable address can only result from tampering, which verification will catch
+                client: model.client.and_then(|client| client.parse().ok()),
+                action,
+                prev_hash: model.prev_hash,
+                hash: model.hash,
+            })
+        }
+
+        /// Converts a [`DbDecision`] as stored in the database to a [`Decision`].
+        ///
+        /// # Arguments
+        /// - `model`: The [`DbDecision`] to convert.
+        ///
+        /// # Returns
+        /// An equivalent [`Decision`].
+        ///
+        /// # Errors
+        /// This function errors if the stored version was not a valid version number.
+        fn decision_from_model(model: DbDecision) -> Result<Decision, ConnectionError> {
+            Ok(Decision {
+                id: model.id,
+                version: version_from_sql(model.version)?,
+                recorded: model.recorded_at.and_utc(),
+                reporter: User { id: model.reporter, name: "John Smith".into(), issuer: None, roles: Vec::new() },
+            })
+        }
+
+        /// Converts a [`KeyKind`] to its representation in the database.
+        ///
+        /// # Arguments
+        /// - `kind`: The [`KeyKind`] to convert.
+        ///
+        /// # Returns
+        /// The string stored in the database for it.
+        fn key_kind_to_sql(kind: KeyKind) -> &'static str {
+            match kind {
+                KeyKind::Oct => "oct",
+                KeyKind::Rsa => "rsa",
+                KeyKind::Ec => "ec",
+                KeyKind::Ed => "ed",
+            }
+        }
+
+        /// Converts a [`DbKey`] as stored in the database to a [`Key`].
+        ///
+        /// # Arguments
+        /// - `model`: The [`DbKey`] to convert.
+        ///
+        /// # Returns
+        /// An equivalent [`Key`].
+        ///
+        /// # Errors
+        /// This function errors if the kind of the key is unknown.
+        fn key_from_model(model: DbKey) -> Result<Key, ConnectionError> {
+            let kind: KeyKind = match model.kind.as_str() {
+                "oct" => KeyKind::Oct,
+                "rsa" => KeyKind::Rsa,
+                "ec" => KeyKind::Ec,
+                "ed" => KeyKind::Ed,
+                _ => return Err(ConnectionError::InvalidKeyKind { id: model.id, kind: model.kind }),
+            };
+            Ok(Key {
+                attached: AttachedKey { id: model.id, kind, material: model.material },
+                created:  model.created_at.and_utc(),
+                creator:  User { id: model.creator, name: "John Smith".into(), issuer: None, roles: Vec::new() },
+                revoked:  model.revoked_at.map(|revoked| revoked.and_utc()),
+            })
+        }
+
+        /// Converts a [`DbSchema`] as stored in the database to a [`Schema`].
+        ///
+        /// # Arguments
+        /// - `model`: The [`DbSchema`] to convert.
+        ///
+        /// # Returns
+        /// An equivalent [`Schema`].
+        ///
+        /// # Errors
+        /// This function errors if the schema was not valid JSON.
+        fn schema_from_model(model: DbSchema) -> Result<Schema, ConnectionError> {
+            let schema: serde_json::Value =
+                serde_json::from_str(&model.schema).map_err(|err| ConnectionError::SchemaDeserialize { name: model.name.clone(), err })?;
+            Ok(Schema {
+                attached: AttachedSchema { name: model.name, description: model.description, schema },
+                created:  model.created_at.and_utc(),
+                creator:  User { id: model.creator, name: "John Smith".into(), issuer: None, roles: Vec::new() },
+            })
+        }
+
+        /// Converts a [`DbTemplate`] as stored in the database to a [`Template`].
+        ///
+        /// # Arguments
+        /// - `model`: The [`DbTemplate`] to convert.
+        ///
+        /// # Returns
+        /// An equivalent [`Template`].
+        ///
+        /// # Errors
+        /// This function errors if the parameters or the skeleton of the template were not valid JSON.
+        fn template_from_model(model: DbTemplate) -> Result<Template, ConnectionError> {
+            let parameters: Vec<TemplateParameter> = serde_json::from_str(&model.parameters).map_err(|err| ConnectionError::TemplateDeserialize {
+                name: model.name.clone(),
+                what: "parameters",
+                err,
+            })?;
+            let skeleton: serde_json::Value = serde_json::from_str(&model.skeleton).map_err(|err| ConnectionError::TemplateDeserialize {
+                name: model.name.clone(),
+                what: "skeleton",
+                err,
+            })?;
+            Ok(Template {
+                attached: AttachedTemplate { name: model.name, description: model.description, language: model.language, parameters, skeleton },
+                created:  model.created_at.and_utc(),
+                creator:  User { id: model.creator, name: "John Smith".into(), issuer: None, roles: Vec::new() },
+            })
+        }
+
+        /// Runs the given statements one after another.
+        ///
+        /// Used for the preflight queries of warming up as well as for maintenance.
+        ///
+        /// # Arguments
+        /// - `conn`: The [`RawConnection`] to run the statements on.
+        /// - `statements`: The statements to run.
+        ///
+        /// # Errors
+        /// This function errors with the failing statement (and why) if any of them fails. Any
+        /// statements after it are not run.
+        pub(crate) fn execute_all(conn: &mut RawConnection, statements: &[&'static str]) -> Result<(), (&'static str, diesel::result::Error)> {
+            for what in statements {
+                debug!("Running {what:?}...");
+                if let Err(err) = diesel::sql_query(*what).execute(conn) {
+                    return Err((what, err));
+                }
+            }
+            Ok(())
+        }
+
+        /// Repairs the issues found by [`verify()`] that can be repaired safely.
+        ///
+        /// These are:
+        /// - [`Finding::OrphanedActivation`]s, which are removed from the activation history; and
+        /// - [`Finding::MissingHash`]es (e.g., of versions added before hashes were recorded), which are
+        ///   recomputed from the content.
+        ///
+        /// Any other findings are skipped. All repairs are done in a single transaction, so either all of
+        /// them happen or none do.
+        ///
+        /// # Arguments
+        /// - `conn`: The [`RawConnection`] to write the repairs with.
+        /// - `findings`: The findings to repair.
+        ///
+        /// # Returns
+        /// The findings that were actually repaired.
+        ///
+        /// # Errors
+        /// This function errors if we failed to write the repairs.
+        pub(crate) fn repair(conn: &mut RawConnection, findings: Vec<Finding>) -> Result<Vec<Finding>, diesel::result::Error> {
+            use crate::schema::active_version::dsl as av;
+            use crate::schema::policies::dsl as policy;
+
+            exclusive_transaction(conn, |conn| -> Result<Vec<Finding>, diesel::result::Error> {
+                let mut repaired: Vec<Finding> = Vec::with_capacity(findings.len());
+                for finding in findings {
+                    match &finding {
+                        Finding::OrphanedActivation { version, activated } => {
+                            let Some(sql_version) = version_to_sql(*version) else { continue };
+                            debug!("Removing orphaned activation of policy version {version} at {activated}...");
+                            if diesel::delete(av::active_version.find((sql_version, activated.naive_utc()))).execute(conn)? == 0 {
+                                continue;
+                            }
+                        },
+                        Finding::MissingHash { version } => {
+                            let Some(sql_version) = version_to_sql(*version) else { continue };
+                            let Some(content) = policy::policies.find(sql_version).select(policy::content).first::<String>(conn).optional()? else {
+                                continue;
+                            };
+                            // NOTE: Hashed the same way as when adding, which can't fail for values
+                            let value: serde_json::Value = match serde_json::from_str(&content) {
+                                Ok(value) => value,
+                                Err(err) => {
+                                    warn!("Not hashing policy version {version}, as its content is not valid JSON: {err}");
+                                    continue;
+                                },
+                            };
+                            debug!("Recomputing hash of policy version {version}...");
+                            let hash: String = content_hash(&to_canonical_string(&value).unwrap_or_default());
+                            if diesel::update(policy::policies.find(sql_version).filter(policy::content_hash.is_null()))
+                                .set(policy::content_hash.eq(hash))
+                                .execute(conn)?
+                                == 0
+                            {
+                                continue;
+                            }
+                        },
+                        _ => continue,
+                    }
+                    repaired.push(finding);
+                }
+                Ok(repaired)
+            })
+        }
+
+        /// Checks that the active version and every activation refer to existing versions, that every
+        /// version is numbered higher than the versions it derives from, and that the content of every
+        /// version is valid and matches its hash.
+        ///
+        /// # Generic arguments
+        /// - `C`: The type of content that every version must deserialize to.
+        ///
+        /// # Arguments
+        /// - `url`: The (redacted) URL or path of the backend database. Only given for debugging purposes.
+        /// - `conn`: The [`RawConnection`] to read the database with.
+        ///
+        /// # Returns
+        /// Everything that is wrong with the database. Empty if nothing is.
+        ///
+        /// # Errors
+        /// This function errors if we failed to read the database.
+        pub(crate) fn verify<C: DeserializeOwned>(url: &str, conn: &mut RawConnection) -> Result<Vec<Finding>, ConnectionError> {
+            use crate::schema::active_version::dsl as av;
+            use crate::schema::current_active::dsl as ca;
+            use crate::schema::policies::dsl as policy;
+
+            // Read everything in one go, such that we check a single snapshot
+            type Row = (i64, Option<i64>, Option<i64>, String, Option<String>);
+            let (versions, active, activations): (Vec<Row>, Option<i64>, Vec<(i64, NaiveDateTime)>) = snapshot_transaction(conn, |conn| {
+                debug!("Reading versions...");
+                let versions: Vec<Row> = policy::policies
+                    .select((policy::version, policy::parent, policy::promoted_from, policy::content, policy::content_hash))
+                    .order_by(policy::version.asc())
+                    .load(conn)
+                    .map_err(|err| ConnectionError::GetVersions { url: url.into(), err })?;
+                debug!("Reading activations...");
+                let active: Option<i64> = ca::current_active
+                    .find(CURRENT_ACTIVE_ID)
+                    .select(ca::version)
+                    .first(conn)
+                    .optional()
+                    .map_err(|err| ConnectionError::GetActiveVersion { url: url.into(), err })?;
+                let activations: Vec<(i64, NaiveDateTime)> = av::active_version
+                    .select((av::version, av::activated_on))
+                    .order_by(av::activated_on.asc())
+                    .load(conn)
+                    .map_err(|err| ConnectionError::GetActiveVersion { url: url.into(), err })?;
+                Ok::<_, ConnectionError>((versions, active, activations))
+            })?;
+
+            // Check the versions themselves
+            let mut findings: Vec<Finding> = Vec::new();
+            let mut present: HashSet<u64> = HashSet::with_capacity(versions.len());
+            for (version, parent, promoted_from, content, hash) in versions {
+                let version: u64 = version_from_sql(version)?;
+                present.insert(version);
+                for parent in parent.into_iter().chain(promoted_from) {
+                    let parent: u64 = version_from_sql(parent)?;
+                    if parent >= version {
+                        findings.push(Finding::NonMonotoneVersion { version, parent });
+                    }
+                }
+
+                let value: serde_json::Value = match serde_json::from_str(&content) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        findings.push(Finding::InvalidContent { version, reason: err.to_string() });
+                        continue;
+                    },
+                };
+                // NOTE: Hashed the same way as when adding, which can't fail for values
+                let actual: String = content_hash(&to_canonical_string(&value).unwrap_or_default());
+                match hash {
+                    Some(expected) if expected != actual => findings.push(Finding::HashMismatch { version, expected, actual }),
+                    Some(_) => {},
+                    None => findings.push(Finding::MissingHash { version }),
+                }
+                if let Err(err) = serde_json::from_value::<C>(value) {
+                    findings.push(Finding::InvalidContent { version, reason: err.to_string() });
+                }
+            }
+
+            // Then check whatever refers to them
+            if let Some(active) = active {
+                let active: u64 = version_from_sql(active)?;
+                if !present.contains(&active) {
+                    findings.push(Finding::DanglingActive { version: active });
+                }
+            }
+            for (version, activated) in activations {
+                let version: u64 = version_from_sql(version)?;
+                if !present.contains(&version) {
+                    findings.push(Finding::OrphanedActivation { version, activated: activated.and_utc() });
+                }
+            }
+            Ok(findings)
+        }
+
+
+
+
+
+        /***** CONSTANTS *****/
+        /// The queries run on every connection when warming up the pool.
+        ///
+        /// These touch the actual tables, such that they also catch a database that is reachable but
+        /// not (or no longer) the one we migrated.
+        pub(crate) const PREFLIGHT_QUERIES: [&str; 2] = ["SELECT version FROM policies LIMIT 1", "SELECT name FROM branches LIMIT 1"];
+
+        /// The identifier of the only row in the `current_active` table.
+        pub(crate) const CURRENT_ACTIVE_ID: i32 = 1;
+
+
+
+
+
+        /***** LIBRARY *****/
+        /// Represents the connection created by the [`DatabaseConnector`] of a diesel-based backend.
+        pub struct DieselConnection<'a, C> {
+            /// The (redacted) URL or path of the database we represent. Only retained during runtime for
+            /// debugging.
+            pub(crate) url: &'a str,
+            /// The connection we wrap.
+            pub(crate) conn: Object<Manager<RawConnection>>,
+            /// The user that is doing everything in this connection.
+            pub(crate) user: &'a User,
+            /// The address of the client from which the user connects, if known.
+            pub(crate) client: Option<IpAddr>,
+            /// The ID of the request in which the user connects, if any.
+            pub(crate) request_id: Option<String>,
+            /// The moment after which no new work should be started, if any.
+            pub(crate) deadline: Option<Instant>,
+            /// Remembers the type of content chosen for this connection.
+            pub(crate) _content: PhantomData<C>,
+        }
+        impl<C> DieselConnection<'_, C> {
+            /// Runs some work on the connection, unless the deadline of the access has passed by the time
+            /// it gets to run.
+            ///
+            /// Work that has started always completes, as interrupting a transaction halfway would only
+            /// waste what has been done so far. In fact, it runs on a blocking thread that completes
+            /// even if the returned future is dropped; and since every method does all its work in a
+            /// single call to this function (and within a single transaction), this is what makes them
+            /// all cancellation-safe.
+            ///
+            /// # Arguments
+            /// - `work`: A closure doing the actual work on the [`RawConnection`].
+            ///
+            /// # Returns
+            /// Whatever the `work` returns.
+            ///
+            /// # Errors
+            /// This function errors if the `work` does, or if the deadline has passed.
+            async fn interact<R>(
+                &self,
+                work: impl 'static + Send + FnOnce(&mut RawConnection) -> Result<R, ConnectionError>,
+            ) -> Result<R, ConnectionError>
+            where
+                R: 'static + Send,
+            {
+                let url: String = self.url.into();
+                let deadline: Option<Instant> = self.deadline;
+                self.conn
+                    .interact(move |conn| {
+                        // NOTE: Checked only now to also catch work that expired while waiting for a thread
+                        if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+                            debug!("Skipping work on backend database {url:?} past its deadline");
+                            return Err(ConnectionError::DeadlineExceeded { url });
+                        }
+                        work(conn)
+                    })
+                    .await
+                    .expect("database transaction should not panic")
+            }
+
+            /// Helper function for doing the non-async active version retrieval.
+            ///
+            /// This is a single primary-key read of the `current_active` pointer, regardless of how long
+            /// the activation history has grown.
+            ///
+            /// # Arguments
+            /// - `url`: The (redacted) URL or path of the backend database. Only given for debugging purposes.
+            /// - `conn`: Some [`LoadConnection`] that we use to talk to the database.
+            ///
+            /// # Returns
+            /// An activate version if there was one (else, [`None`]).
+            ///
+            /// # Errors
+            /// This function errors if we failed to get the active version.
+            fn _get_active_version<C2>(url: &str, conn: &mut C2) -> Result<Option<u64>, ConnectionError>
+            where
+                C2: LoadConnection<Backend = Backend>,
+            {
+                use crate::schema::current_active::dsl as ca;
+
+                debug!("Fetching active version...");
+                match ca::current_active.find(CURRENT_ACTIVE_ID).select(ca::version).first::<i64>(conn).optional() {
+                    Ok(Some(version)) => Ok(Some(version_from_sql(version)?)),
+                    Ok(None) => Ok(None),
+                    Err(err) => Err(ConnectionError::GetActiveVersion { url: url.into(), err }),
+                }
+            }
+
+            /// Helper function for retrieving the activation currently in effect.
+            ///
+            /// # Arguments
+            /// - `url`: The (redacted) URL or path of the backend database. Only given for debugging purposes.
+            /// - `conn`: Some [`LoadConnection`] that we use to talk to the database.
+            ///
+            /// # Returns
+            /// The [`DbActiveVersion`] in the history that the `current_active` pointer points to, or
+            /// [`None`] if no version is active.
+            ///
+            /// # Errors
+            /// This function errors if we failed to read either table.
+            fn _get_current_activation<C2>(url: &str, conn: &mut C2) -> Result<Option<DbActiveVersion>, ConnectionError>
+            where
+                C2: LoadConnection<Backend = Backend>,
+            {
+                use crate::schema::active_version::dsl as av;
+                use crate::schema::current_active::dsl as ca;
+
+                debug!("Fetching current activation...");
+                let pointer: DbCurrentActive =
+                    match ca::current_active.find(CURRENT_ACTIVE_ID).select(DbCurrentActive::as_select()).first(conn).optional() {
+                        Ok(Some(pointer)) => pointer,
+                        Ok(None) => return Ok(None),
+                        Err(err) => return Err(ConnectionError::GetActiveVersion { url: url.into(), err }),
+                    };
+                match av::active_version.find((pointer.version, pointer.activated_on)).select(DbActiveVersion::as_select()).first(conn).optional() {
+                    Ok(model) => Ok(model),
+                    Err(err) => Err(ConnectionError::GetActiveVersion { url: url.into(), err }),
+                }
+            }
+
+            /// Helper function for checking whether a version exists.
+            ///
+            /// # Arguments
+            /// - `url`: The (redacted) URL or path of the backend database. Only given for debugging purposes.
+            /// - `conn`: Some [`RawConnection`] that we use to talk to the database.
+            /// - `version`: The version to look for.
+            ///
+            /// # Returns
+            /// Whether the version exists.
+            ///
+            /// # Errors
+            /// This function errors if we failed to look for the version.
+            fn _version_exists(url: &str, conn: &mut RawConnection, version: u64) -> Result<bool, ConnectionError> {
+                use crate::schema::policies::dsl as policy;
+
+                let Some(version_sql) = version_to_sql(version) else { return Ok(false) };
+                match policy::policies.filter(policy::version.eq(version_sql)).count().get_result::<i64>(conn) {
+                    Ok(count) => Ok(count > 0),
+                    Err(err) => Err(ConnectionError::GetVersion { url: url.into(), version, err }),
+                }
+            }
+
+            /// Helper function for determining the number of the next version to add.
+            ///
+            /// # Arguments
+            /// - `url`: The (redacted) URL or path of the backend database. Only given for debugging purposes.
+            /// - `conn`: Some [`RawConnection`] that we use to talk to the database.
+            ///
+            /// # Returns
+            /// The next version number, as stored in the database.
+            ///
+            /// # Errors
+            /// This function errors if we failed to get the latest version, or if [`MAX_VERSION`] has been
+            /// reached.
+            fn _next_version(url: &str, conn: &mut RawConnection) -> Result<i64, ConnectionError> {
+                use crate::schema::policies::dsl as policy;
+
+                // NOTE: We don't look at the creation time, as the clock may have been turned back since
+                debug!("Retrieving latest policy version...");
+                let latest: i64 = policy::policies
+                    .select(diesel::dsl::max(policy::version))
+                    .first::<Option<i64>>(conn)
+                    .map_err(|err| ConnectionError::GetLatestVersion { url: url.into(), err })?
+                    .unwrap_or(0);
+                // NOTE: Also rejects negative versions, from which we'd otherwise start counting again
+                version_from_sql(latest)?;
+                latest.checked_add(1).ok_or_else(|| ConnectionError::VersionsExhausted { url: url.into() })
+            }
+
+            /// Helper function for finding the head of the branch to append a version to.
+            ///
+            /// Creates the branch if it doesn't exist yet.
+            ///
+            /// # Arguments
+            /// - `url`: The (redacted) URL or path of the backend database. Only given for debugging purposes.
+            /// - `conn`: Some [`RawConnection`] that we use to talk to the database.
+            /// - `branch`: The name of the branch to append to.
+            /// - `user_id`: The ID of the user to record as the branch's creator, if it's created.
+            ///
+            /// # Returns
+            /// The latest version on the branch, as stored in the database, or [`None`] if it has none.
+            ///
+            /// # Errors
+            /// This function errors if we failed to get or create the branch.
+            fn _branch_head(url: &str, conn: &mut RawConnection, branch: &str, user_id: &str) -> Result<Option<i64>, ConnectionError> {
+                use crate::schema::branches::dsl as branches;
+
+                debug!("Retrieving head of branch {branch:?}...");
+                match branches::branches.filter(branches::name.eq(branch)).select(branches::head).first::<Option<i64>>(conn).optional() {
+                    Ok(Some(head)) => Ok(head),
+                    Ok(None) => {
+                        debug!("Creating new branch {branch:?}...");
+                        let model =
+                            DbBranch { name: branch.into(), head: None, base: None, creator: user_id.into(), created_at: Utc::now().naive_utc() };
+                        match diesel::insert_into(branches::branches).values(&model).execute(conn) {
+                            Ok(_) => Ok(None),
+                            Err(err) => Err(ConnectionError::CreateBranch { url: url.into(), name: branch.into(), err }),
+                        }
+                    },
+                    Err(err) => Err(ConnectionError::GetBranch { url: url.into(), name: branch.into(), err }),
+                }
+            }
+
+            /// Helper function for replacing a user with a pseudonym in all tables but the audit log.
+            ///
+            /// # Arguments
+            /// - `conn`: Some [`RawConnection`] that we use to talk to the database.
+            /// - `id`: The ID of the user to replace.
+            /// - `pseudonym`: The ID to replace it with.
+            ///
+            /// # Returns
+            /// The number of rows changed.
+            ///
+            /// # Errors
+            /// This function errors if any of the updates failed.
+            fn _pseudonymize_tables(conn: &mut RawConnection, id: &str, pseudonym: &str) -> Result<u64, diesel::result::Error> {
+                use crate::schema::active_version::dsl as av;
+                use crate::schema::branches::dsl as branches;
+                use crate::schema::decisions::dsl as decisions;
+                use crate::schema::keys::dsl as keys;
+                use crate::schema::policies::dsl as policy;
+                use crate::schema::provenance::dsl as provenance;
+                use crate::schema::revoked_tokens::dsl as rt;
+                use crate::schema::revoked_users::dsl as ru;
+                use crate::schema::schemas::dsl as schemas;
+                use crate::schema::templates::dsl as templates;
+
+                let mut count: usize = 0;
+                // Where they wrote their versions from is as telling as their ID, so forget it (before we lose track of their versions)
+                let versions = policy::policies.filter(policy::creator.eq(id)).select(policy::version);
+                count += diesel::update(provenance::provenance.filter(provenance::version.eq_any(versions)).filter(provenance::client.is_not_null()))
+                    .set(provenance::client.eq(None::<String>))
+                    .execute(conn)?;
+                count += diesel::update(policy::policies.filter(policy::creator.eq(id))).set(policy::creator.eq(pseudonym)).execute(conn)?;
+                count +=
+                    diesel::update(policy::policies.filter(policy::origin_creator.eq(id))).set(policy::origin_creator.eq(pseudonym)).execute(conn)?;
+                count += diesel::update(policy::policies.filter(policy::frozen_by.eq(id))).set(policy::frozen_by.eq(pseudonym)).execute(conn)?;
+                count +=
+                    diesel::update(templates::templates.filter(templates::creator.eq(id))).set(templates::creator.eq(pseudonym)).execute(conn)?;
+                count += diesel::update(branches::branches.filter(branches::creator.eq(id))).set(branches::creator.eq(pseudonym)).execute(conn)?;
+                count += diesel::update(schemas::schemas.filter(schemas::creator.eq(id))).set(schemas::creator.eq(pseudonym)).execute(conn)?;
+                count += diesel::update(av::active_version.filter(av::activated_by.eq(id)).filter(av::client.is_not_null()))
+                    .set(av::client.eq(None::<String>))
+                    .execute(conn)?;
+                count += diesel::update(av::active_version.filter(av::activated_by.eq(id))).set(av::activated_by.eq(pseudonym)).execute(conn)?;
+                count += diesel::update(av::active_version.filter(av::deactivated_by.eq(id))).set(av::deactivated_by.eq(pseudonym)).execute(conn)?;
+                count +=
+                    diesel::update(decisions::decisions.filter(decisions::reporter.eq(id))).set(decisions::reporter.eq(pseudonym)).execute(conn)?;
+                count += diesel::update(keys::keys.filter(keys::creator.eq(id))).set(keys::creator.eq(pseudonym)).execute(conn)?;
+                count += diesel::update(rt::revoked_tokens.filter(rt::creator.eq(id))).set(rt::creator.eq(pseudonym)).execute(conn)?;
+                count += diesel::update(ru::revoked_users.filter(ru::creator.eq(id))).set(ru::creator.eq(pseudonym)).execute(conn)?;
+                Ok(count as u64)
+            }
+
+            /// Helper function for appending a record to the audit log.
+            ///
+            /// Should be called in the same transaction as the change it records.
+            ///
+            /// # Arguments
+            /// - `url`: The (redacted) URL or path of the backend database. Only given for debugging purposes.
+            /// - `conn`: Some [`RawConnection`] that we use to talk to the database.
+            /// - `user`: The ID of the user who made the change.
+            /// - `client`: The address of the client from which the change was made, if known.
+            /// - `action`: The [`AuditAction`] describing the change.
+            ///
+            /// # Errors
+            /// This function errors if we failed to find the previous record or to append the new one.
+            fn _append_audit(
+                url: &str,
+                conn: &mut RawConnection,
+                user: &str,
+                client: Option<IpAddr>,
+                action: AuditAction,
+            ) -> Result<(), ConnectionError> {
+                use crate::schema::audit_log::dsl as audit;
+
+                // Find the record to follow up on
+                let prev: Option<AuditRecord> =
+                    match audit::audit_log.order_by(audit::seq.desc()).select(DbAuditRecord::as_select()).first(conn).optional() {
+                        Ok(Some(model)) => Some(audit_from_model(model)?),
+                        Ok(None) => None,
+                        Err(err) => return Err(ConnectionError::GetAuditLog { url: url.into(), err }),
+                    };
+
+                // Append the new one
+                let record = AuditRecord::new(prev.as_ref(), user, client, action);
+                debug!("Appending audit record {}...", record.seq);
+                let model = DbAuditRecord {
+                    seq: record.seq as i64,
+                    timestamp: record.timestamp.naive_utc(),
+                    user_id: record.user,
+                    client: record.client.map(|client| client.to_string()),
+                    action: serde_json::to_string(&record.action).map_err(|err| ConnectionError::AuditSerialize { seq: record.seq, err })?,
+                    prev_hash: record.prev_hash,
+                    hash: record.hash,
+                };
+                match diesel::insert_into(audit::audit_log).values(&model).execute(conn) {
+                    Ok(_) => Ok(()),
+                    Err(err) => Err(ConnectionError::AppendAudit { url: url.into(), err }),
+                }
+            }
+
+            /// Helper function for recording what is only known while a version is being written, such
+            /// that its [`Provenance`] can be generated later.
+            ///
+            /// Should be called in the same transaction as the one writing the version.
+            ///
+            /// # Arguments
+            /// - `url`: The (redacted) URL or path of the backend database. Only given for debugging purposes.
+            /// - `conn`: Some [`RawConnection`] that we use to talk to the database.
+            /// - `version`: The version being written.
+            /// - `client`: The address of the client from which it is written, if known.
+            /// - `request_id`: The ID of the request in which it is written, if known.
+            ///
+            /// # Errors
+            /// This function errors if we failed to record anything.
+            fn _record_provenance(
+                url: &str,
+                conn: &mut RawConnection,
+                version: i64,
+                client: Option<IpAddr>,
+                request_id: Option<String>,
+            ) -> Result<(), ConnectionError> {
+                use crate::schema::provenance::dsl as provenance;
+
+                let model = DbProvenance { version, client: client.map(|client| client.to_string()), request_id };
+                match diesel::insert_into(provenance::provenance).values(&model).execute(conn) {
+                    Ok(_) => Ok(()),
+                    Err(err) => Err(ConnectionError::AddProvenance { url: url.into(), version: version as u64, err }),
+                }
+            }
+        }
+        impl<C: Send + Sync + DeserializeOwned + Serialize + 'static> DatabaseConnection for DieselConnection<'_, C> {
+            type Content = C;
+            type Error = ConnectionError;
+
+
+            // Mutable
+            fn add_version(&mut self, metadata: AttachedMetadata, content: Self::Content) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+                use crate::schema::branches::dsl as branches;
+                use crate::schema::policies::dsl::policies;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::add_version", policy = metadata.name,);
+
+                    debug!("Starting transaction...");
+                    let user_id = self.user.id.clone();
+                    let user_issuer = self.user.issuer.clone();
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let request_id = self.request_id.clone();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<u64, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            let next_version: i64 = Self::_next_version(&url, conn)?;
+                            let version: u64 = version_from_sql(next_version)?;
+
+                            // Find the branch to append to, creating it if it doesn't exist yet
+                            let parent: Option<i64> = Self::_branch_head(&url, conn, &metadata.branch, &user_id)?;
+
+                            // Construct the policy itself
+                            debug!("Adding new policy {next_version}...");
+                            let content: String = match to_canonical_string(&content) {
+                                Ok(content) => content,
+                                Err(err) => return Err(ConnectionError::ContentSerialize { name: metadata.name, err }),
+                            };
+                            let hash: String = content_hash(&content);
+                            let model = DbPolicy {
+                                name: metadata.name,
+                                description: metadata.description,
+                                language: metadata.language,
+                                version: next_version,
+                                creator: user_id.clone(),
+                                created_at: Utc::now().naive_utc(),
+                                content,
+                                branch: metadata.branch.clone(),
+                                parent,
+                                promoted_from: None,
+                                content_hash: Some(hash),
+                                schema: metadata.schema,
+                                origin_store: None,
+                                origin_version: None,
+                                origin_creator: None,
+                                origin_created_at: None,
+                                verified_identity: None,
+                                verified_digest: None,
+                                verified_at: None,
+                                creator_issuer: user_issuer,
+                                frozen_reason: None,
+                                frozen_by: None,
+                                frozen_by_issuer: None,
+                                frozen_at: None,
+                                confidential: metadata.confidential,
+                            };
+
+                            // Submit it
+                            if let Err(err) = diesel::insert_into(policies).values(&model).execute(conn) {
+                                return Err(ConnectionError::AddVersion { url, err });
+                            }
+
+                            // Move the branch along
+                            if let Err(err) = diesel::update(branches::branches)
+                                .filter(branches::name.eq(&metadata.branch))
+                                .set(branches::head.eq(next_version))
+                                .execute(conn)
+                            {
+                                return Err(ConnectionError::SetBranchHead { url, name: metadata.branch, version, err });
+                            }
+
+                            // Log it
+                            Self::_record_provenance(&url, conn, next_version, client, request_id)?;
+                            let action = AuditAction::AddVersion { version, name: model.name, branch: metadata.branch };
+                            Self::_append_audit(&url, conn, &user_id, client, action)?;
+                            Ok(version)
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn activate(&mut self, version: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+                use crate::schema::active_version::dsl::{active_version, deactivated_by, deactivated_on};
+                use crate::schema::policies::dsl as policy;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::activate", version = version);
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let user_id = self.user.id.clone();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<(), Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Frozen versions may not be activated
+                            if let Some(version_sql) = version_to_sql(version) {
+                                match policy::policies
+                                    .filter(policy::version.eq(version_sql))
+                                    .select(policy::frozen_at)
+                                    .first::<Option<NaiveDateTime>>(conn)
+                                    .optional()
+                                {
+                                    Ok(Some(Some(_))) => return Err(ConnectionError::VersionFrozen { url: url.clone(), version }),
+                                    Ok(_) => {},
+                                    Err(err) => return Err(ConnectionError::GetVersion { url: url.clone(), version, err }),
+                                }
+                            }
+
+                            // Get the information about what to activate
+                            let current: Option<DbActiveVersion> = Self::_get_current_activation(&url, conn)?;
+
+                            // They may already be the same, ez
+                            let version_sql: i64 = version_to_sql(version).ok_or(ConnectionError::VersionOutOfRange { version })?;
+                            if current.as_ref().is_some_and(|current| current.version == version_sql) {
+                                info!("Activated already-active version {version}");
+                                return Ok(());
+                            }
+
+                            // Otherwise, close the activation it supersedes (if any) in the history...
+                            let model = DbActiveVersion::new(version_sql, user_id.clone(), client.map(|client| client.to_string()));
+                            if let Some(current) = current {
+                                debug!("Closing activation of superseded policy {}...", current.version);
+                                if let Err(err) = diesel::update(active_version.find((current.version, current.activated_on)))
+                                    .set((deactivated_on.eq(model.activated_on), deactivated_by.eq(&user_id)))
+                                    .execute(conn)
+                                {
+                                    return Err(ConnectionError::SetActive { url: url.clone(), version, err });
+                                }
+                            }
+
+                            // ...and then submit the new one
+                            debug!("Activating policy {version}...");
+                            if let Err(err) = diesel::insert_into(active_version).values(&model).execute(conn) {
+                                return Err(ConnectionError::SetActive { url: url.clone(), version, err });
+                            }
+                            let pointer = DbCurrentActive { id: CURRENT_ACTIVE_ID, version: version_sql, activated_on: model.activated_on };
+                            if let Err(err) = upsert_current_active(conn, &pointer) {
+                                return Err(ConnectionError::SetActive { url: url.clone(), version, err });
+                            }
+                            Self::_append_audit(&url, conn, &user_id, client, AuditAction::Activate { version })
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> {
+                use crate::schema::active_version::dsl::{active_version, deactivated_by, deactivated_on};
+                use crate::schema::current_active::dsl::current_active;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::deactivate");
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let user_id = self.user.id.clone();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<(), Self::Error> {
+                            // Get the current activation, if any
+                            let model: DbActiveVersion = match Self::_get_current_activation(&url, conn)? {
+                                Some(model) => model,
+                                None => {
+                                    info!("Deactivated a policy whilst none were active");
+                                    return Ok(());
+                                },
+                            };
+                            let av: u64 = version_from_sql(model.version)?;
+
+                            // If we found one, then close it in the history and clear the pointer
+                            debug!("Deactivating active policy {av}...");
+                            if let Err(err) = diesel::update(active_version.find((model.version, model.activated_on)))
+                                .set((deactivated_on.eq(Utc::now().naive_utc()), deactivated_by.eq(&user_id)))
+                                .execute(conn)
+                            {
+                                return Err(ConnectionError::DeactivateVersion { url: url.clone(), version: av, err });
+                            }
+                            if let Err(err) = diesel::delete(current_active.find(CURRENT_ACTIVE_ID)).execute(conn) {
+                                return Err(ConnectionError::DeactivateVersion { url: url.clone(), version: av, err });
+                            }
+                            Self::_append_audit(&url, conn, &user_id, client, AuditAction::Deactivate { version: av })
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn freeze_version(&mut self, version: u64, reason: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+                use crate::schema::policies::dsl as policy;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::freeze_version", version = version);
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let user_id = self.user.id.clone();
+                    let user_issuer = self.user.issuer.clone();
+                    let reason: String = reason.into();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<(), ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Freeze the version, which also tells us whether it exists
+                            debug!("Freezing policy {version}...");
+                            let Some(version_sql) = version_to_sql(version) else { return Ok(Err(ClientError::UnknownVersion { version })) };
+                            let rows: usize = match diesel::update(policy::policies.filter(policy::version.eq(version_sql)))
+                                .set((
+                                    policy::frozen_reason.eq(&reason),
+                                    policy::frozen_by.eq(&user_id),
+                                    policy::frozen_by_issuer.eq(&user_issuer),
+                                    policy::frozen_at.eq(Utc::now().naive_utc()),
+                                ))
+                                .execute(conn)
+                            {
+                                Ok(rows) => rows,
+                                Err(err) => return Err(ConnectionError::FreezeVersion { url, version, err }),
+                            };
+                            if rows == 0 {
+                                return Ok(Err(ClientError::UnknownVersion { version }));
+                            }
+                            Self::_append_audit(&url, conn, &user_id, client, AuditAction::FreezeVersion { version, reason })?;
+                            Ok(Ok(()))
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn unfreeze_version(&mut self, version: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+                use crate::schema::policies::dsl as policy;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::unfreeze_version", version = version);
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let user_id = self.user.id.clone();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<(), ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Ensure the version exists
+                            let Some(version_sql) = version_to_sql(version) else { return Ok(Err(ClientError::UnknownVersion { version })) };
+                            match policy::policies.filter(policy::version.eq(version_sql)).count().get_result::<i64>(conn) {
+                                Ok(0) => return Ok(Err(ClientError::UnknownVersion { version })),
+                                Ok(_) => {},
+                                Err(err) => return Err(ConnectionError::GetVersion { url, version, err }),
+                            }
+
+                            // Unfreeze it, unless it isn't frozen
+                            debug!("Unfreezing policy {version}...");
+                            let rows: usize = match diesel::update(
+                                policy::policies.filter(policy::version.eq(version_sql)).filter(policy::frozen_at.is_not_null()),
+                            )
+                            .set((
+                                policy::frozen_reason.eq(None::<String>),
+                                policy::frozen_by.eq(None::<String>),
+                                policy::frozen_by_issuer.eq(None::<String>),
+                                policy::frozen_at.eq(None::<NaiveDateTime>),
+                            ))
+                            .execute(conn)
+                            {
+                                Ok(rows) => rows,
+                                Err(err) => return Err(ConnectionError::UnfreezeVersion { url, version, err }),
+                            };
+                            if rows > 0 {
+                                Self::_append_audit(&url, conn, &user_id, client, AuditAction::UnfreezeVersion { version })?;
+                            }
+                            Ok(Ok(()))
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn add_template(&mut self, template: AttachedTemplate) -> impl Send + Future<Output = Result<(), Self::Error>> {
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::add_template", template = template.name);
+
+                    // Serialize the JSON parts of the template
+                    let parameters: String = serde_json::to_string(&template.parameters).map_err(|err| ConnectionError::TemplateSerialize {
+                        name: template.name.clone(),
+                        what: "parameters",
+                        err,
+                    })?;
+                    let skeleton: String = serde_json::to_string(&template.skeleton).map_err(|err| ConnectionError::TemplateSerialize {
+                        name: template.name.clone(),
+                        what: "skeleton",
+                        err,
+                    })?;
+                    let model = DbTemplate {
+                        name: template.name,
+                        description: template.description,
+                        language: template.language,
+                        parameters,
+                        skeleton,
+                        creator: self.user.id.clone(),
+                        created_at: Utc::now().naive_utc(),
+                    };
+
+                    // Submit it, replacing any existing one
+                    debug!("Adding template {:?}...", model.name);
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<(), Self::Error> {
+                            if let Err(err) = upsert_template(conn, &model) {
+                                return Err(ConnectionError::AddTemplate { url, name: model.name, err });
+                            }
+                            Self::_append_audit(&url, conn, &model.creator, client, AuditAction::AddTemplate { name: model.name })
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn create_branch(&mut self, name: &str, from: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+                use crate::schema::branches::dsl as branches;
+                use crate::schema::policies::dsl as policy;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::create_branch", branch = name, from = from);
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let user_id = self.user.id.clone();
+                    let name: String = name.into();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<(), ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Ensure the branch doesn't exist yet
+                            match branches::branches.filter(branches::name.eq(&name)).count().get_result::<i64>(conn) {
+                                Ok(0) => {},
+                                Ok(_) => return Ok(Err(ClientError::BranchExists { name })),
+                                Err(err) => return Err(ConnectionError::GetBranch { url, name, err }),
+                            }
+                            // Ensure the version to fork from does
+                            let Some(from_sql) = version_to_sql(from) else { return Ok(Err(ClientError::UnknownVersion { version: from })) };
+                            match policy::policies.filter(policy::version.eq(from_sql)).count().get_result::<i64>(conn) {
+                                Ok(0) => return Ok(Err(ClientError::UnknownVersion { version: from })),
+                                Ok(_) => {},
+                                Err(err) => return Err(ConnectionError::GetVersion { url, version: from, err }),
+                            }
+
+                            // Create it
+                            debug!("Creating branch {name:?} from version {from}...");
+                            let model =
+                                DbBranch { name, head: Some(from_sql), base: Some(from_sql), creator: user_id, created_at: Utc::now().naive_utc() };
+                            if let Err(err) = diesel::insert_into(branches::branches).values(&model).execute(conn) {
+                                return Err(ConnectionError::CreateBranch { url, name: model.name, err });
+                            }
+                            Self::_append_audit(&url, conn, &model.creator, client, AuditAction::CreateBranch { name: model.name, from })?;
+                            Ok(Ok(()))
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn add_schema(&mut self, schema: AttachedSchema) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+                use crate::schema::schemas::dsl as schemas;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::add_schema", schema = schema.name);
+
+                    // Serialize the schema itself
+                    let model = DbSchema {
+                        schema: serde_json::to_string(&schema.schema)
+                            .map_err(|err| ConnectionError::SchemaSerialize { name: schema.name.clone(), err })?,
+                        name: schema.name,
+                        description: schema.description,
+                        creator: self.user.id.clone(),
+                        created_at: Utc::now().naive_utc(),
+                    };
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<(), ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Ensure the schema doesn't exist yet
+                            match schemas::schemas.filter(schemas::name.eq(&model.name)).count().get_result::<i64>(conn) {
+                                Ok(0) => {},
+                                Ok(_) => return Ok(Err(ClientError::SchemaExists { name: model.name })),
+                                Err(err) => return Err(ConnectionError::GetSchema { url, name: model.name, err }),
+                            }
+
+                            // Submit it
+                            debug!("Adding schema {:?}...", model.name);
+                            if let Err(err) = diesel::insert_into(schemas::schemas).values(&model).execute(conn) {
+                                return Err(ConnectionError::AddSchema { url, name: model.name, err });
+                            }
+                            Self::_append_audit(&url, conn, &model.creator, client, AuditAction::AddSchema { name: model.name })?;
+                            Ok(Ok(()))
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn pseudonymize_user(&mut self, id: &str, pseudonym: &str) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+                use crate::schema::audit_log::dsl as audit;
+
+                async move {
+                    // NOTE: Deliberately not logging the ID itself, as that's what we're trying to get rid of
+                    let span = span!(Level::INFO, "DieselConnection::pseudonymize_user", pseudonym = pseudonym);
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let user_id = self.user.id.clone();
+                    let id: String = id.into();
+                    let pseudonym: String = pseudonym.into();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<u64, ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // First, the regular tables
+                            debug!("Pseudonymizing user in tables...");
+                            let mut count: u64 = match Self::_pseudonymize_tables(conn, &id, &pseudonym) {
+                                Ok(count) => count,
+                                Err(err) => return Err(ConnectionError::Pseudonymize { url, err }),
+                            };
+
+                            // Then the audit log. We don't want to launder any tampering by re-hashing, so verify it first.
+                            let records: Vec<AuditRecord> =
+                                match audit::audit_log.order_by(audit::seq.asc()).select(DbAuditRecord::as_select()).load(conn) {
+                                    Ok(r) => r.into_iter().map(audit_from_model).collect::<Result<_, _>>()?,
+                                    Err(err) => return Err(ConnectionError::GetAuditLog { url, err }),
+                                };
+                            if let Err(err) = verify_chain(&records) {
+                                return Err(ConnectionError::AuditChain { url, err });
+                            }
+                            debug!("Pseudonymizing user in audit log...");
+                            let mut prev_hash: Option<String> = None;
+                            let mut rehashed_from: Option<u64> = None;
+                            for mut record in records {
+                                if record.user == id {
+                                    record.user = pseudonym.clone();
+                                    record.client = None;
+                                    rehashed_from = rehashed_from.or(Some(record.seq));
+                                    count += 1;
+                                }
+                                if record.action.pseudonymize(&id, &pseudonym) > 0 {
+                                    rehashed_from = rehashed_from.or(Some(record.seq));
+                                    count += 1;
+                                }
+                                if rehashed_from.is_some() {
+                                    record.prev_hash = prev_hash;
+                                    record.hash = record.compute_hash();
+                                    let action: String = serde_json::to_string(&record.action)
+                                        .map_err(|err| ConnectionError::AuditSerialize { seq: record.seq, err })?;
+                                    if let Err(err) = diesel::update(audit::audit_log.filter(audit::seq.eq(record.seq as i64)))
+                                        .set((
+                                            audit::user_id.eq(&record.user),
+                                            audit::action.eq(action),
+                                            audit::client.eq(record.client.map(|client| client.to_string())),
+                                            audit::prev_hash.eq(&record.prev_hash),
+                                            audit::hash.eq(&record.hash),
+                                        ))
+                                        .execute(conn)
+                                    {
+                                        return Err(ConnectionError::RewriteAudit { url, seq: record.seq, err });
+                                    }
+                                }
+                                prev_hash = Some(record.hash);
+                            }
+                            if count == 0 {
+                                return Ok(Err(ClientError::UnknownUser { id }));
+                            }
+
+                            // Finally, log the pseudonymization itself
+                            Self::_append_audit(&url, conn, &user_id, client, AuditAction::Pseudonymize { pseudonym, rehashed_from })?;
+                            Ok(Ok(count))
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn add_decision(&mut self, version: u64, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+                use crate::schema::decisions::dsl as decisions;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::add_decision", version = version, decision = id);
+
+                    let Some(version_sql) = version_to_sql(version) else { return Ok(Err(ClientError::UnknownVersion { version })) };
+                    let model =
+                        DbDecision { id: id.into(), version: version_sql, reporter: self.user.id.clone(), recorded_at: Utc::now().naive_utc() };
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<(), ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Ensure the version exists and the decision doesn't yet
+                            if !Self::_version_exists(&url, conn, version)? {
+                                return Ok(Err(ClientError::UnknownVersion { version }));
+                            }
+                            match decisions::decisions.filter(decisions::id.eq(&model.id)).count().get_result::<i64>(conn) {
+                                Ok(0) => {},
+                                Ok(_) => return Ok(Err(ClientError::DecisionExists { id: model.id })),
+                                Err(err) => return Err(ConnectionError::GetDecision { url, id: model.id, err }),
+                            }
+
+                            // Submit it
+                            debug!("Adding decision {:?}...", model.id);
+                            if let Err(err) = diesel::insert_into(decisions::decisions).values(&model).execute(conn) {
+                                return Err(ConnectionError::AddDecision { url, id: model.id, err });
+                            }
+                            Self::_append_audit(&url, conn, &model.reporter, client, AuditAction::AddDecision { version, id: model.id })?;
+                            Ok(Ok(()))
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn prune_activations(&mut self, before: DateTime<Utc>) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+                use crate::schema::active_version::dsl as av;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::prune_activations", before = before.to_rfc3339());
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let user_id = self.user.id.clone();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<u64, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // NOTE: Activations are closed when deactivated or superseded, so only the current
+                            // one lacks a deactivation time (and never matches)
+                            debug!("Removing activations that ended before {}...", before.to_rfc3339());
+                            let rows: u64 = match diesel::delete(av::active_version.filter(av::deactivated_on.lt(before.naive_utc()))).execute(conn) {
+                                Ok(rows) => rows as u64,
+                                Err(err) => return Err(ConnectionError::PruneActivations { url, err }),
+                            };
+                            if rows > 0 {
+                                Self::_append_audit(&url, conn, &user_id, client, AuditAction::PruneActivations { before, rows })?;
+                            }
+                            Ok(rows)
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn add_key(&mut self, key: AttachedKey) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+                use crate::schema::keys::dsl as keys;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::add_key", key = key.id);
+
+                    let model = DbKey {
+                        id: key.id,
+                        kind: key_kind_to_sql(key.kind).into(),
+                        material: key.material,
+                        creator: self.user.id.clone(),
+                        created_at: Utc::now().naive_utc(),
+                        revoked_at: None,
+                    };
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<(), ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Ensure the key doesn't exist yet (not even revoked)
+                            match keys::keys.filter(keys::id.eq(&model.id)).count().get_result::<i64>(conn) {
+                                Ok(0) => {},
+                                Ok(_) => return Ok(Err(ClientError::KeyExists { id: model.id })),
+                                Err(err) => return Err(ConnectionError::GetKey { url, id: model.id, err }),
+                            }
+
+                            // Submit it
+                            debug!("Adding key {:?}...", model.id);
+                            if let Err(err) = diesel::insert_into(keys::keys).values(&model).execute(conn) {
+                                return Err(ConnectionError::AddKey { url, id: model.id, err });
+                            }
+                            Self::_append_audit(&url, conn, &model.creator, client, AuditAction::AddKey { id: model.id })?;
+                            Ok(Ok(()))
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn revoke_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+                use crate::schema::keys::dsl as keys;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::revoke_key", key = id);
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let user_id = self.user.id.clone();
+                    let id: String = id.into();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<(), ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Ensure the key exists
+                            match keys::keys.filter(keys::id.eq(&id)).count().get_result::<i64>(conn) {
+                                Ok(0) => return Ok(Err(ClientError::UnknownKey { id })),
+                                Ok(_) => {},
+                                Err(err) => return Err(ConnectionError::GetKey { url, id, err }),
+                            }
+
+                            // Revoke it, unless it already is
+                            debug!("Revoking key {id:?}...");
+                            let rows: usize = match diesel::update(keys::keys.filter(keys::id.eq(&id)).filter(keys::revoked_at.is_null()))
+                                .set(keys::revoked_at.eq(Utc::now().naive_utc()))
+                                .execute(conn)
+                            {
+                                Ok(rows) => rows,
+                                Err(err) => return Err(ConnectionError::RevokeKey { url, id, err }),
+                            };
+                            if rows > 0 {
+                                Self::_append_audit(&url, conn, &user_id, client, AuditAction::RevokeKey { id })?;
+                            }
+                            Ok(Ok(()))
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn revoke_tokens(&mut self, revocation: AttachedRevocation) -> impl Send + Future<Output = Result<(), Self::Error>> {
+                use crate::schema::revoked_users::dsl as ru;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::revoke_tokens");
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let user_id = self.user.id.clone();
+                    let now = Utc::now().naive_utc();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<(), Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            match revocation {
+                                AttachedRevocation::Token { jti } => {
+                                    // Revoke it, unless it already is
+                                    debug!("Revoking token {jti:?}...");
+                                    let model = DbRevokedToken { jti, creator: user_id, created_at: now };
+                                    let rows: usize = match insert_revoked_token(conn, &model) {
+                                        Ok(rows) => rows,
+                                        Err(err) => return Err(ConnectionError::RevokeTokens { url, err }),
+                                    };
+                                    if rows > 0 {
+                                        Self::_append_audit(&url, conn, &model.creator, client, AuditAction::RevokeToken { jti: model.jti })?;
+                                    }
+                                },
+                                AttachedRevocation::User { user, before } => {
+                                    // Only keep the latest revocation per user
+                                    let prev: Option<NaiveDateTime> =
+                                        match ru::revoked_users.filter(ru::user_id.eq(&user)).select(ru::revoked_before).first(conn).optional() {
+                                            Ok(prev) => prev,
+                                            Err(err) => return Err(ConnectionError::GetRevocations { url, err }),
+                                        };
+                                    let before: NaiveDateTime = before.naive_utc();
+                                    if prev.is_some_and(|prev| prev >= before) {
+                                        debug!("Tokens of user {user:?} were already revoked before {before}");
+                                        return Ok(());
+                                    }
+
+                                    debug!("Revoking tokens of user {user:?} issued before {before}...");
+                                    let model = DbRevokedUser { user_id: user, revoked_before: before, creator: user_id, created_at: now };
+                                    if let Err(err) = upsert_revoked_user(conn, &model) {
+                                        return Err(ConnectionError::RevokeTokens { url, err });
+                                    }
+                                    Self::_append_audit(&url, conn, &model.creator, client, AuditAction::RevokeUserTokens {
+                                        user:   model.user_id,
+                                        before: model.revoked_before.and_utc(),
+                                    })?;
+                                },
+                            }
+                            Ok(())
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn promote(
+                &mut self,
+                version: u64,
+                target: &str,
+                force: bool,
+            ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+                use crate::schema::branches::dsl as branches;
+                use crate::schema::policies::dsl as policy;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::promote", version = version, target = target);
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let request_id = self.request_id.clone();
+                    let user_id = self.user.id.clone();
+                    let user_issuer = self.user.issuer.clone();
+                    let target: String = target.into();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<u64, ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Get the version to promote
+                            debug!("Retrieving policy {version}...");
+                            let Some(version_sql) = version_to_sql(version) else { return Ok(Err(ClientError::UnknownVersion { version })) };
+                            let original: DbPolicy =
+                                match policy::policies.filter(policy::version.eq(version_sql)).select(DbPolicy::as_select()).first(conn).optional() {
+                                    Ok(Some(original)) => original,
+                                    Ok(None) => return Ok(Err(ClientError::UnknownVersion { version })),
+                                    Err(err) => return Err(ConnectionError::GetVersion { url, version, err }),
+                                };
+                            if original.frozen_at.is_some() {
+                                return Ok(Err(ClientError::VersionFrozen { version }));
+                            }
+                            if original.branch == target {
+                                return Ok(Err(ClientError::PromoteSameBranch { version, branch: target }));
+                            }
+
+                            // Get both branches
+                            let mut get_branch = |name: &str| -> Result<Option<DbBranch>, ConnectionError> {
+                                branches::branches
+                                    .filter(branches::name.eq(name))
+                                    .select(DbBranch::as_select())
+                                    .first(conn)
+                                    .optional()
+                                    .map_err(|err| ConnectionError::GetBranch { url: url.clone(), name: name.into(), err })
+                            };
+                            let source_branch: Option<DbBranch> = get_branch(&original.branch)?;
+                            let target_branch: DbBranch = match get_branch(&target)? {
+                                Some(branch) => branch,
+                                None => return Ok(Err(ClientError::UnknownBranch { name: target })),
+                            };
+
+                            // Find where the branches last met; i.e., the latest promotion from the source into the target, or else the fork point
+                            debug!("Checking for conflicts between branch {:?} and {target:?}...", original.branch);
+                            let source_versions: Vec<i64> =
+                                match policy::policies.filter(policy::branch.eq(&original.branch)).select(policy::version).load(conn) {
+                                    Ok(versions) => versions,
+                                    Err(err) => return Err(ConnectionError::GetVersions { url, err }),
+                                };
+                            let last_promotion: Option<i64> = match policy::policies
+                                .filter(policy::branch.eq(&target))
+                                .filter(policy::promoted_from.eq_any(source_versions))
+                                .select(diesel::dsl::max(policy::version))
+                                .first::<Option<i64>>(conn)
+                            {
+                                Ok(promotion) => promotion,
+                                Err(err) => return Err(ConnectionError::GetVersions { url, err }),
+                            };
+                            let base: Option<i64> = last_promotion.or(source_branch.and_then(|b| b.base));
+                            if let Some(head) = target_branch.head {
+                                if Some(head) != base {
+                                    if force {
+                                        info!("Forcing promotion of version {version} into branch {target:?} despite it having advanced to {head}");
+                                    } else {
+                                        return Ok(Err(ClientError::PromoteConflict {
+                                            version,
+                                            source_branch: original.branch,
+                                            target,
+                                            head: version_from_sql(head)?,
+                                            base: base.map(version_from_sql).transpose()?,
+                                        }));
+                                    }
+                                }
+                            }
+
+                            // Get the next version number
+                            let next_version: i64 = Self::_next_version(&url, conn)?;
+                            let promoted: u64 = version_from_sql(next_version)?;
+
+                            // Append the copy to the target
+                            debug!("Promoting policy {version} into branch {target:?} as {next_version}...");
+                            let model = DbPolicy {
+                                name: original.name,
+                                description: original.description,
+                                language: original.language,
+                                version: next_version,
+                                creator: user_id,
+                                created_at: Utc::now().naive_utc(),
+                                content: original.content,
+                                branch: target.clone(),
+                                parent: target_branch.head,
+                                promoted_from: Some(version_sql),
+                                content_hash: original.content_hash,
+                                schema: original.schema,
+                                origin_store: original.origin_store,
+                                origin_version: original.origin_version,
+                                origin_creator: original.origin_creator,
+                                origin_created_at: original.origin_created_at,
+                                verified_identity: original.verified_identity,
+                                verified_digest: original.verified_digest,
+                                verified_at: original.verified_at,
+                                creator_issuer: user_issuer,
+                                frozen_reason: None,
+                                frozen_by: None,
+                                frozen_by_issuer: None,
+                                frozen_at: None,
+                                confidential: original.confidential,
+                            };
+                            if let Err(err) = diesel::insert_into(policy::policies).values(&model).execute(conn) {
+                                return Err(ConnectionError::AddVersion { url, err });
+                            }
+                            if let Err(err) = diesel::update(branches::branches)
+                                .filter(branches::name.eq(&target))
+                                .set(branches::head.eq(next_version))
+                                .execute(conn)
+                            {
+                                return Err(ConnectionError::SetBranchHead { url, name: target, version: promoted, err });
+                            }
+                            Self::_record_provenance(&url, conn, next_version, client, request_id)?;
+                            Self::_append_audit(&url, conn, &model.creator, client, AuditAction::Promote { version, target, promoted })?;
+                            Ok(Ok(promoted))
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn import_version(
+                &mut self,
+                store: &str,
+                metadata: Metadata,
+                content: Self::Content,
+            ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+                use crate::schema::branches::dsl as branches;
+                use crate::schema::policies::dsl::policies;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::import_version", store = store, version = metadata.version);
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let client = self.client;
+                    let request_id = self.request_id.clone();
+                    let user_id = self.user.id.clone();
+                    let user_issuer = self.user.issuer.clone();
+                    let store: String = store.into();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<Result<u64, ClientError>, Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Make sure the content survived the trip
+                            let content: String = match to_canonical_string(&content) {
+                                Ok(content) => content,
+                                Err(err) => return Err(ConnectionError::ContentSerialize { name: metadata.attached.name, err }),
+                            };
+                            let hash: String = content_hash(&content);
+                            if let Some(expected) = metadata.hash {
+                                if expected != hash {
+                                    return Ok(Err(ClientError::HashMismatch { version: metadata.version, expected, actual: hash }));
+                                }
+                            }
+
+                            let (verified_identity, verified_digest, verified_at) = match metadata.verification {
+                                Some(verification) => {
+                                    (Some(verification.identity), Some(verification.digest), Some(verification.verified.naive_utc()))
+                                },
+                                None => (None, None, None),
+                            };
+
+                            // Keep the original origin if this version was imported before
+                            let origin: Origin = metadata.origin.unwrap_or(Origin {
+                                store:   store.clone(),
+                                version: metadata.version,
+                                creator: metadata.creator.id,
+                                created: metadata.created,
+                            });
+                            // NOTE: Other stores are bound by the same maximum, so they can't have such a version either
+                            let Some(origin_version) = version_to_sql(origin.version) else {
+                                return Ok(Err(ClientError::UnknownVersion { version: origin.version }));
+                            };
+
+                            // Append the copy to the same branch as in the other store
+                            let next_version: i64 = Self::_next_version(&url, conn)?;
+                            let version: u64 = version_from_sql(next_version)?;
+                            let branch: String = metadata.attached.branch;
+                            let parent: Option<i64> = Self::_branch_head(&url, conn, &branch, &user_id)?;
+                            debug!("Importing policy {} from store {store:?} as {next_version}...", metadata.version);
+                            let model = DbPolicy {
+                                name: metadata.attached.name,
+                                description: metadata.attached.description,
+                                language: metadata.attached.language,
+                                version: next_version,
+                                creator: user_id,
+                                created_at: Utc::now().naive_utc(),
+                                content,
+                                branch: branch.clone(),
+                                parent,
+                                promoted_from: None,
+                                content_hash: Some(hash),
+                                schema: metadata.attached.schema,
+                                origin_store: Some(origin.store),
+                                origin_version: Some(origin_version),
+                                origin_creator: Some(origin.creator),
+                                origin_created_at: Some(origin.created.naive_utc()),
+                                verified_identity,
+                                verified_digest,
+                                verified_at,
+                                creator_issuer: user_issuer,
+                                frozen_reason: None,
+                                frozen_by: None,
+                                frozen_by_issuer: None,
+                                frozen_at: None,
+                                confidential: metadata.attached.confidential,
+                            };
+                            if let Err(err) = diesel::insert_into(policies).values(&model).execute(conn) {
+                                return Err(ConnectionError::AddVersion { url, err });
+                            }
+                            if let Err(err) = diesel::update(branches::branches)
+                                .filter(branches::name.eq(&branch))
+                                .set(branches::head.eq(next_version))
+                                .execute(conn)
+                            {
+                                return Err(ConnectionError::SetBranchHead { url, name: branch, version, err });
+                            }
+
+                            // Log it
+                            Self::_record_provenance(&url, conn, next_version, client, request_id)?;
+                            let action = AuditAction::Import { version, name: model.name, branch, store, source: metadata.version };
+                            Self::_append_audit(&url, conn, &model.creator, client, action)?;
+                            Ok(Ok(version))
+                        })
+                    })
+                    .await
+                }
+            }
+
+
+            fn ack_events(&mut self, sink: &str, seq: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+                use crate::schema::event_cursors::dsl as ec;
+
+                async move {
+                    let span = span!(Level::INFO, "DieselConnection::ack_events", sink = sink, seq = seq);
+
+                    debug!("Starting transaction...");
+                    let url = self.url.to_owned();
+                    let sink: String = sink.into();
+                    let now = Utc::now().naive_utc();
+                    self.interact(move |conn| {
+                        exclusive_transaction(conn, |conn| -> Result<(), Self::Error> {
+                            // Trick the compiler into moving the span too
+                            let _span = span;
+
+                            // Only ever move the cursor forward
+                            // NOTE: Sequence numbers are stored signed, but can never exceed it in practice
+                            let new: i64 = i64::try_from(seq).unwrap_or(i64::MAX);
+                            let prev: Option<i64> = match ec::event_cursors.filter(ec::sink.eq(&sink)).select(ec::seq).first(conn).optional() {
+                                Ok(prev) => prev,
+                                Err(err) => return Err(ConnectionError::GetEventCursor { url, sink, err }),
+                            };
+                            if let Some(prev) = prev.filter(|prev| *prev >= new) {
+                                debug!("Sink {sink:?} already acknowledged events up to {prev}");
+                                return Ok(());
+                            }
+
+                            debug!("Acknowledging events up to {seq} for sink {sink:?}...");
+                            let model = DbEventCursor { sink, seq: new, updated_at: now };
+                            if let Err(err) = upsert_event_cursor(conn, &model) {
+                                return Err(ConnectionError::AckEvents { url, sink: model.sink, seq, err });
+                            }
+                            Ok(())
+                        })
+                    })
+                    .await
+                }
+            }
+
+            // Immutable
+            fn get_versions(&mut self) -> impl Send + Future<Output = Result<HashMap<u64, Metadata>, Self::Error>> {
+                use crate::schema::policies::dsl as policy;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_versions");
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Retrieving all policy versions...");
+                        match policy::policies
+                            .order_by(crate::schema::policies::dsl::created_at.desc())
+                            .select(DbPolicyMetadata::as_select())
+                            .load(conn)
+                        {
+                            Ok(r) => r.into_iter().map(|model| metadata_from_model(model).map(|metadata| (metadata.version, metadata))).collect(),
+                            Err(err) => Err(ConnectionError::GetVersions { url, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_active_version(&mut self) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_active");
+
+                    // Do a call to get the active, if any
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| Self::_get_active_version(&url, conn)).await
+                }
+            }
+
+            fn get_active_version_at(&mut self, at: DateTime<Utc>) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+                use crate::schema::active_version::dsl as av;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_active_version_at", at = at.to_rfc3339());
+
+                    // Find the last activation before the given time, and see if it was still active then
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Fetching version active at {at}...");
+                        let at = at.naive_utc();
+                        match av::active_version
+                            .filter(av::activated_on.le(at))
+                            .order_by(av::activated_on.desc())
+                            .select(DbActiveVersion::as_select())
+                            .first(conn)
+                            .optional()
+                        {
+                            Ok(Some(row)) => {
+                                if row.deactivated_on.is_some_and(|on| on <= at) {
+                                    Ok(None)
+                                } else {
+                                    Ok(Some(version_from_sql(row.version)?))
+                                }
+                            },
+                            Ok(None) => Ok(None),
+                            Err(err) => Err(ConnectionError::GetActiveVersion { url, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>> {
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_active");
+
+                    // Do a call to get the active, if any
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        Ok(Self::_get_current_activation(&url, conn)?.map(|av| User {
+                            id:     av.activated_by,
+                            name:   "John Smith".into(),
+                            issuer: None,
+                            roles:  Vec::new(),
+                        }))
+                    })
+                    .await
+                }
+            }
+
+            fn get_activation(&mut self) -> impl Send + Future<Output = Result<Option<Activation>, Self::Error>> {
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_activation");
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| Self::_get_current_activation(&url, conn)?.map(activation_from_model).transpose()).await
+                }
+            }
+
+            fn get_activations(&mut self) -> impl Send + Future<Output = Result<Vec<Activation>, Self::Error>> {
+                use crate::schema::active_version::dsl as av;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_activations");
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Retrieving activation history...");
+                        match av::active_version.order_by(av::activated_on.asc()).select(DbActiveVersion::as_select()).load(conn) {
+                            Ok(r) => r.into_iter().map(activation_from_model).collect(),
+                            Err(err) => Err(ConnectionError::GetActiveVersion { url, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_version_metadata(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Metadata>, Self::Error>> {
+                use crate::schema::policies::dsl as policy;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_version_metadata", version = version);
+
+                    debug!("Retrieving metadata for version {version}...");
+                    let Some(version_sql) = version_to_sql(version) else { return Ok(None) };
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        match policy::policies
+                            .limit(1)
+                            .filter(crate::schema::policies::dsl::version.eq(version_sql))
+                            .select(DbPolicyMetadata::as_select())
+                            .load(conn)
+                        {
+                            Ok(mut r) => {
+                                // Extract the version itself
+                                if r.is_empty() {
+                                    return Ok(None);
+                                }
+
+                                // Done, return the thing
+                                Ok(Some(metadata_from_model(r.remove(0))?))
+                            },
+                            Err(err) => match err {
+                                diesel::result::Error::NotFound => Ok(None),
+                                err => Err(ConnectionError::GetVersion { url: url.clone(), version, err }),
+                            },
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>> {
+                use crate::schema::policies::dsl as policy;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_version_content", version = version);
+
+                    let Some(version_sql) = version_to_sql(version) else { return Ok(None) };
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Retrieving content for version {version}...");
+                        match policy::policies
+                            .limit(1)
+                            .filter(crate::schema::policies::dsl::version.eq(version_sql))
+                            .select((policy::name, policy::content))
+                            .load::<(String, String)>(conn)
+                        {
+                            Ok(mut r) => {
+                                // Extract the version itself
+                                if r.is_empty() {
+                                    return Ok(None);
+                                }
+                                let (name, content) = r.remove(0);
+
+                                // Deserialize the content
+                                match serde_json::from_str(&content) {
+                                    Ok(content) => Ok(Some(content)),
+                                    Err(err) => Err(ConnectionError::ContentDeserialize { name, version, err }),
+                                }
+                            },
+                            Err(err) => match err {
+                                diesel::result::Error::NotFound => Ok(None),
+                                err => Err(ConnectionError::GetVersion { url: url.clone(), version, err }),
+                            },
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_version_provenance(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Provenance>, Self::Error>> {
+                use crate::schema::policies::dsl as policy;
+                use crate::schema::provenance::dsl as provenance;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_version_provenance", version = version);
+
+                    debug!("Retrieving provenance for version {version}...");
+                    let Some(version_sql) = version_to_sql(version) else { return Ok(None) };
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        let model: DbPolicyMetadata = match policy::policies
+                            .filter(policy::version.eq(version_sql))
+                            .select(DbPolicyMetadata::as_select())
+                            .first(conn)
+                            .optional()
+                        {
+                            Ok(Some(model)) => model,
+                            Ok(None) => return Ok(None),
+                            Err(err) => return Err(ConnectionError::GetVersion { url, version, err }),
+                        };
+                        // NOTE: Versions written before provenance was recorded simply have no row
+                        let recorded: Option<DbProvenance> =
+                            match provenance::provenance.find(version_sql).select(DbProvenance::as_select()).first(conn).optional() {
+                                Ok(recorded) => recorded,
+                                Err(err) => return Err(ConnectionError::GetProvenance { url, version, err }),
+                            };
+
+                        let metadata: Metadata = metadata_from_model(model)?;
+                        let (client, request_id): (Option<IpAddr>, Option<String>) = match recorded {
+                            Some(recorded) => (recorded.client.and_then(|client| client.parse().ok()), recorded.request_id),
+                            None => (None, None),
+                        };
+                        Ok(Some(Provenance::new(&metadata, client, request_id)))
+                    })
+                    .await
+                }
+            }
+
+            fn get_templates(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Template>, Self::Error>> {
+                use crate::schema::templates::dsl::templates;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_templates");
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Retrieving all templates...");
+                        match templates.select(DbTemplate::as_select()).load(conn) {
+                            Ok(r) => r.into_iter().map(|model| Ok((model.name.clone(), template_from_model(model)?))).collect(),
+                            Err(err) => Err(ConnectionError::GetTemplates { url, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_template(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Template>, Self::Error>> {
+                use crate::schema::templates::dsl as template;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_template", template = name);
+
+                    debug!("Retrieving template {name:?}...");
+                    let url = self.url.to_owned();
+                    let name: String = name.into();
+                    self.interact(move |conn| {
+                        match template::templates.filter(template::name.eq(&name)).select(DbTemplate::as_select()).first(conn).optional() {
+                            Ok(Some(model)) => template_from_model(model).map(Some),
+                            Ok(None) => Ok(None),
+                            Err(err) => Err(ConnectionError::GetTemplate { url, name, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_branches(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Branch>, Self::Error>> {
+                use crate::schema::branches::dsl::branches;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_branches");
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Retrieving all branches...");
+                        match branches.select(DbBranch::as_select()).load(conn) {
+                            Ok(r) => r
+                                .into_iter()
+                                .map(|branch| {
+                                    Ok((branch.name.clone(), Branch {
+                                        name:    branch.name,
+                                        head:    branch.head.map(version_from_sql).transpose()?,
+                                        base:    branch.base.map(version_from_sql).transpose()?,
+                                        created: branch.created_at.and_utc(),
+                                        creator: User { id: branch.creator, name: "John Smith".into(), issuer: None, roles: Vec::new() },
+                                    }))
+                                })
+                                .collect(),
+                            Err(err) => Err(ConnectionError::GetBranches { url, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_schemas(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Schema>, Self::Error>> {
+                use crate::schema::schemas::dsl::schemas;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_schemas");
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Retrieving all schemas...");
+                        match schemas.select(DbSchema::as_select()).load(conn) {
+                            Ok(r) => r.into_iter().map(|model| Ok((model.name.clone(), schema_from_model(model)?))).collect(),
+                            Err(err) => Err(ConnectionError::GetSchemas { url, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_schema(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Schema>, Self::Error>> {
+                use crate::schema::schemas::dsl as schema;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_schema", schema = name);
+
+                    debug!("Retrieving schema {name:?}...");
+                    let url = self.url.to_owned();
+                    let name: String = name.into();
+                    self.interact(move |conn| {
+                        match schema::schemas.filter(schema::name.eq(&name)).select(DbSchema::as_select()).first(conn).optional() {
+                            Ok(Some(model)) => schema_from_model(model).map(Some),
+                            Ok(None) => Ok(None),
+                            Err(err) => Err(ConnectionError::GetSchema { url, name, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Vec<Decision>>, Self::Error>> {
+                use crate::schema::decisions::dsl as decisions;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_decisions", version = version);
+
+                    let Some(version_sql) = version_to_sql(version) else { return Ok(None) };
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        if !Self::_version_exists(&url, conn, version)? {
+                            return Ok(None);
+                        }
+                        debug!("Retrieving decisions taken under version {version}...");
+                        match decisions::decisions
+                            .filter(decisions::version.eq(version_sql))
+                            .order_by(decisions::recorded_at.asc())
+                            .select(DbDecision::as_select())
+                            .load(conn)
+                        {
+                            Ok(r) => Ok(Some(r.into_iter().map(decision_from_model).collect::<Result<_, _>>()?)),
+                            Err(err) => Err(ConnectionError::GetDecisions { url, version, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn count_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+                use crate::schema::decisions::dsl as decisions;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::count_decisions", version = version);
+
+                    let Some(version_sql) = version_to_sql(version) else { return Ok(None) };
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        if !Self::_version_exists(&url, conn, version)? {
+                            return Ok(None);
+                        }
+                        debug!("Counting decisions taken under version {version}...");
+                        match decisions::decisions.filter(decisions::version.eq(version_sql)).count().get_result::<i64>(conn) {
+                            Ok(count) => Ok(Some(count as u64)),
+                            Err(err) => Err(ConnectionError::GetDecisions { url, version, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_decision(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Decision>, Self::Error>> {
+                use crate::schema::decisions::dsl as decisions;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_decision", decision = id);
+
+                    let url = self.url.to_owned();
+                    let id: String = id.into();
+                    self.interact(move |conn| {
+                        debug!("Retrieving decision {id:?}...");
+                        match decisions::decisions.filter(decisions::id.eq(&id)).select(DbDecision::as_select()).first(conn).optional() {
+                            Ok(model) => model.map(decision_from_model).transpose(),
+                            Err(err) => Err(ConnectionError::GetDecision { url, id, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_keys(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Key>, Self::Error>> {
+                use crate::schema::keys::dsl::keys;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_keys");
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Retrieving all keys...");
+                        match keys.select(DbKey::as_select()).load(conn) {
+                            Ok(r) => r.into_iter().map(|model| Ok((model.id.clone(), key_from_model(model)?))).collect(),
+                            Err(err) => Err(ConnectionError::GetKeys { url, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Key>, Self::Error>> {
+                use crate::schema::keys::dsl as keys;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_key", key = id);
+
+                    debug!("Retrieving key {id:?}...");
+                    let url = self.url.to_owned();
+                    let id: String = id.into();
+                    self.interact(move |conn| match keys::keys.filter(keys::id.eq(&id)).select(DbKey::as_select()).first(conn).optional() {
+                        Ok(model) => model.map(key_from_model).transpose(),
+                        Err(err) => Err(ConnectionError::GetKey { url, id, err }),
+                    })
+                    .await
+                }
+            }
+
+            fn get_revocations(&mut self) -> impl Send + Future<Output = Result<Vec<Revocation>, Self::Error>> {
+                use crate::schema::revoked_tokens::dsl as rt;
+                use crate::schema::revoked_users::dsl as ru;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_revocations");
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Retrieving all token revocations...");
+                        let tokens: Vec<DbRevokedToken> = match rt::revoked_tokens.select(DbRevokedToken::as_select()).load(conn) {
+                            Ok(r) => r,
+                            Err(err) => return Err(ConnectionError::GetRevocations { url, err }),
+                        };
+                        let users: Vec<DbRevokedUser> = match ru::revoked_users.select(DbRevokedUser::as_select()).load(conn) {
+                            Ok(r) => r,
+                            Err(err) => return Err(ConnectionError::GetRevocations { url, err }),
+                        };
+
+                        // Merge them into one list
+                        let mut revocations: Vec<Revocation> = tokens
+                            .into_iter()
+                            .map(|model| Revocation {
+                                attached: AttachedRevocation::Token { jti: model.jti },
+                                created:  model.created_at.and_utc(),
+                                creator:  User { id: model.creator, name: "John Smith".into(), issuer: None, roles: Vec::new() },
+                            })
+                            .chain(users.into_iter().map(|model| Revocation {
+                                attached: AttachedRevocation::User { user: model.user_id, before: model.revoked_before.and_utc() },
+                                created:  model.created_at.and_utc(),
+                                creator:  User { id: model.creator, name: "John Smith".into(), issuer: None, roles: Vec::new() },
+                            }))
+                            .collect();
+                        revocations.sort_by_key(|revocation| revocation.created);
+                        Ok(revocations)
+                    })
+                    .await
+                }
+            }
+
+            fn is_revoked(
+                &mut self,
+                jti: Option<&str>,
+                user: &str,
+                issued: Option<DateTime<Utc>>,
+            ) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+                use crate::schema::revoked_tokens::dsl as rt;
+                use crate::schema::revoked_users::dsl as ru;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::is_revoked", user = user);
+
+                    debug!("Checking whether token {jti:?} of user {user:?} is revoked...");
+                    let url = self.url.to_owned();
+                    let jti: Option<String> = jti.map(String::from);
+                    let user: String = user.into();
+                    self.interact(move |conn| {
+                        // First, by identifier
+                        if let Some(jti) = jti {
+                            match rt::revoked_tokens.filter(rt::jti.eq(&jti)).count().get_result::<i64>(conn) {
+                                Ok(0) => {},
+                                Ok(_) => return Ok(true),
+                                Err(err) => return Err(ConnectionError::GetRevocations { url, err }),
+                            }
+                        }
+
+                        // Then by user
+                        let before: Option<NaiveDateTime> =
+                            match ru::revoked_users.filter(ru::user_id.eq(&user)).select(ru::revoked_before).first(conn).optional() {
+                                Ok(before) => before,
+                                Err(err) => return Err(ConnectionError::GetRevocations { url, err }),
+                            };
+                        Ok(match (before, issued) {
+                            (Some(before), Some(issued)) => issued.naive_utc() < before,
+                            (Some(_), None) => true,
+                            (None, _) => false,
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn get_audit_log(&mut self, since: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<Vec<AuditRecord>, Self::Error>> {
+                use crate::schema::audit_log::dsl as audit;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_audit_log");
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        debug!("Retrieving audit log...");
+                        let mut query = audit::audit_log.order_by(audit::seq.asc()).select(DbAuditRecord::as_select()).into_boxed();
+                        if let Some(since) = since {
+                            query = query.filter(audit::timestamp.ge(since.naive_utc()));
+                        }
+                        match query.load(conn) {
+                            Ok(r) => r.into_iter().map(audit_from_model).collect(),
+                            Err(err) => Err(ConnectionError::GetAuditLog { url, err }),
+                        }
+                    })
+                    .await
+                }
+            }
+
+            fn get_changes(&mut self, since: u64, limit: u64) -> impl Send + Future<Output = Result<Vec<Change<Self::Content>>, Self::Error>> {
+                use crate::schema::audit_log::dsl as audit;
+                use crate::schema::policies::dsl as policy;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_changes", since = since, limit = limit);
+
+                    let url = self.url.to_owned();
+                    self.interact(move |conn| {
+                        // NOTE: Use a transaction to get a consistent view of the log and the versions it refers to
+                        snapshot_transaction(conn, |conn| -> Result<Vec<Change<C>>, Self::Error> {
+                            debug!("Retrieving changes since {since}...");
+                            let records: Vec<DbAuditRecord> = match audit::audit_log
+                                .filter(audit::seq.gt(i64::try_from(since).unwrap_or(i64::MAX)))
+                                .order_by(audit::seq.asc())
+                                .limit(limit.min(i64::MAX as u64) as i64)
+                                .select(DbAuditRecord::as_select())
+                                .load(conn)
+                            {
+                                Ok(records) => records,
+                                Err(err) => return Err(ConnectionError::GetAuditLog { url, err }),
+                            };
+
+                            // Attach any versions created
+                            let mut changes: Vec<Change<C>> = Vec::with_capacity(records.len());
+                            for record in records {
+                                let record: AuditRecord = audit_from_model(record)?;
+                                let (metadata, content): (Option<Metadata>, Option<C>) = match record.action.created_version() {
+                                    Some(version) => match policy::policies
+                                        .filter(policy::version.eq(version_to_sql(version).ok_or(ConnectionError::VersionOutOfRange { version })?))
+                                        .select((DbPolicyMetadata::as_select(), policy::content))
+                                        .first::<(DbPolicyMetadata, String)>(conn)
+                                        .optional()
+                                    {
+                                        Ok(Some((model, content))) => {
+                                            let content: C = serde_json::from_str(&content).map_err(|err| ConnectionError::ContentDeserialize {
+                                                name: model.name.clone(),
+                                                version,
+                                                err,
+                                            })?;
+                                            (Some(metadata_from_model(model)?), Some(content))
+                                        },
+                                        Ok(None) => (None, None),
+                                        Err(err) => return Err(ConnectionError::GetVersion { url, version, err }),
+                                    },
+                                    None => (None, None),
+                                };
+                                changes.push(Change { record, metadata, content, added: None });
+                            }
+                            Ok(changes)
+                        })
+                    })
+                    .await
+                }
+            }
+
+            fn get_event_cursor(&mut self, sink: &str) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+                use crate::schema::event_cursors::dsl as ec;
+
+                async move {
+                    let _span = span!(Level::INFO, "DieselConnection::get_event_cursor", sink = sink);
+
+                    debug!("Retrieving event cursor of sink {sink:?}...");
+                    let url = self.url.to_owned();
+                    let sink: String = sink.into();
+                    self.interact(move |conn| match ec::event_cursors.filter(ec::sink.eq(&sink)).select(ec::seq).first::<i64>(conn).optional() {
+                        // NOTE: Cursors are only ever written from a `u64`, so they're never negative
+                        Ok(seq) => Ok(seq.map(|seq| seq.max(0) as u64).unwrap_or(0)),
+                        Err(err) => Err(ConnectionError::GetEventCursor { url, sink, err }),
+                    })
+                    .await
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,409 @@
+//  ERROR.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:06:42
+//  Last edited:
+//    16 Oct 2026, 18:18:27
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the errors of the connections of the diesel-based backends.
+//
+
+use specifications::audit::ChainError;
+use specifications::metadata::MAX_VERSION;
+use thiserror::Error;
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the connection of a diesel-based backend.
+#[derive(Debug, Error)]
+pub enum ConnectionError {
+    /// Failed to acknowledge events for a sink.
+    #[error("Failed to acknowledge events up to {seq} for sink {sink:?} in backend database {url:?}")]
+    AckEvents {
+        url:  String,
+        sink: String,
+        seq:  u64,
+        #[source]
+        err:  diesel::result::Error,
+    },
+    /// Failed to add a new version to the backend database.
+    #[error("Failed to add a new version to backend database {url:?}")]
+    AddVersion {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to record the provenance of a new version in the backend database.
+    #[error("Failed to record the provenance of version {version} in backend database {url:?}")]
+    AddProvenance {
+        url:     String,
+        version: u64,
+        #[source]
+        err:     diesel::result::Error,
+    },
+    /// Failed to register a decision in the backend database.
+    #[error("Failed to add decision {id:?} to backend database {url:?}")]
+    AddDecision {
+        url: String,
+        id:  String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to add a new template to the backend database.
+    #[error("Failed to add template {name:?} to backend database {url:?}")]
+    AddTemplate {
+        url:  String,
+        name: String,
+        #[source]
+        err:  diesel::result::Error,
+    },
+    /// Failed to append a record to the audit log.
+    #[error("Failed to append a record to the audit log in backend database {url:?}")]
+    AppendAudit {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// The audit log failed verification, so it cannot safely be rewritten.
+    #[error("Audit log in backend database {url:?} failed verification")]
+    AuditChain {
+        url: String,
+        #[source]
+        err: ChainError,
+    },
+    /// Failed to deserialize the action of a stored audit record from JSON.
+    #[error("Failed to deserialize the action of audit record {seq} from JSON")]
+    AuditDeserialize {
+        seq: u64,
+        #[source]
+        err: serde_json::Error,
+    },
+    /// Failed to serialize the action of an audit record as JSON.
+    #[error("Failed to serialize the action of audit record {seq} as JSON")]
+    AuditSerialize {
+        seq: u64,
+        #[source]
+        err: serde_json::Error,
+    },
+    /// Failed to add a new verification key to the backend database.
+    #[error("Failed to add key {id:?} to backend database {url:?}")]
+    AddKey {
+        url: String,
+        id:  String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to add a new schema to the backend database.
+    #[error("Failed to add schema {name:?} to backend database {url:?}")]
+    AddSchema {
+        url:  String,
+        name: String,
+        #[source]
+        err:  diesel::result::Error,
+    },
+    /// Failed to create a new branch in the backend database.
+    #[error("Failed to create branch {name:?} in backend database {url:?}")]
+    CreateBranch {
+        url:  String,
+        name: String,
+        #[source]
+        err:  diesel::result::Error,
+    },
+    /// Failed to deserialize the given content from JSON.
+    #[error("Failed to deserialize the given content of policy {name:?} ({version}) from JSON")]
+    ContentDeserialize {
+        name:    String,
+        version: u64,
+        #[source]
+        err:     serde_json::Error,
+    },
+    /// Failed to serialize the given content as JSON.
+    #[error("Failed to serialize the content of policy {name:?} as JSON")]
+    ContentSerialize {
+        name: String,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to deactivate an active version.
+    #[error("Failed to deactivate active policy version {version} in backend database {url:?}")]
+    DeactivateVersion {
+        url:     String,
+        version: u64,
+        #[source]
+        err:     diesel::result::Error,
+    },
+    /// The deadline of the access passed before work on the backend database could start.
+    #[error("Deadline passed before work on backend database {url:?} could start")]
+    DeadlineExceeded { url: String },
+    /// Failed to freeze a version.
+    #[error("Failed to freeze version {version} in backend database {url:?}")]
+    FreezeVersion {
+        url:     String,
+        version: u64,
+        #[source]
+        err:     diesel::result::Error,
+    },
+    /// Failed to fetch the active version.
+    #[error("Failed to get active version from backend database {url:?}")]
+    GetActiveVersion {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to get a specific branch.
+    #[error("Failed to get branch {name:?} from backend database {url:?}")]
+    GetBranch {
+        url:  String,
+        name: String,
+        #[source]
+        err:  diesel::result::Error,
+    },
+    /// Failed to get the list of branches.
+    #[error("Failed to get the list of branches from backend database {url:?}")]
+    GetBranches {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to fetch the latest version.
+    #[error("Failed to get latest version from backend database {url:?}")]
+    GetLatestVersion {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to get a specific decision.
+    #[error("Failed to get decision {id:?} from backend database {url:?}")]
+    GetDecision {
+        url: String,
+        id:  String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to get the decisions taken under a version.
+    #[error("Failed to get the decisions taken under version {version} from backend database {url:?}")]
+    GetDecisions {
+        url:     String,
+        version: u64,
+        #[source]
+        err:     diesel::result::Error,
+    },
+    /// Failed to get the recorded provenance of a version.
+    #[error("Failed to get the provenance of version {version} from backend database {url:?}")]
+    GetProvenance {
+        url:     String,
+        version: u64,
+        #[source]
+        err:     diesel::result::Error,
+    },
+    /// Failed to get a specific version.
+    #[error("Failed to get version {version} from backend database {url:?}")]
+    GetVersion {
+        url:     String,
+        version: u64,
+        #[source]
+        err:     diesel::result::Error,
+    },
+    /// Failed to get the list of versions.
+    #[error("Failed to get the list of versions from backend database {url:?}")]
+    GetVersions {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to get the audit log.
+    #[error("Failed to get the audit log from backend database {url:?}")]
+    GetAuditLog {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to get the event cursor of a sink.
+    #[error("Failed to get the event cursor of sink {sink:?} from backend database {url:?}")]
+    GetEventCursor {
+        url:  String,
+        sink: String,
+        #[source]
+        err:  diesel::result::Error,
+    },
+    /// Failed to get a specific verification key.
+    #[error("Failed to get key {id:?} from backend database {url:?}")]
+    GetKey {
+        url: String,
+        id:  String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to get the list of verification keys.
+    #[error("Failed to get the list of keys from backend database {url:?}")]
+    GetKeys {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to get the list of token revocations.
+    #[error("Failed to get the list of token revocations from backend database {url:?}")]
+    GetRevocations {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to get a specific schema.
+    #[error("Failed to get schema {name:?} from backend database {url:?}")]
+    GetSchema {
+        url:  String,
+        name: String,
+        #[source]
+        err:  diesel::result::Error,
+    },
+    /// Failed to get the list of schemas.
+    #[error("Failed to get the list of schemas from backend database {url:?}")]
+    GetSchemas {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to get a specific template.
+    #[error("Failed to get template {name:?} from backend database {url:?}")]
+    GetTemplate {
+        url:  String,
+        name: String,
+        #[source]
+        err:  diesel::result::Error,
+    },
+    /// Failed to get the list of templates.
+    #[error("Failed to get the list of templates from backend database {url:?}")]
+    GetTemplates {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// A version stored in the database is not a valid version number.
+    #[error("Stored version {version} is not a valid version number")]
+    InvalidVersion { version: i64 },
+    /// A key stored in the database has a kind we don't know.
+    #[error("Stored key {id:?} has unknown kind {kind:?}")]
+    InvalidKeyKind { id: String, kind: String },
+    /// Failed to remove historical activation records.
+    #[error("Failed to prune activation records in backend database {url:?}")]
+    PruneActivations {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to replace a user with their pseudonym.
+    #[error("Failed to pseudonymize user in backend database {url:?}")]
+    Pseudonymize {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to revoke a verification key.
+    #[error("Failed to revoke key {id:?} in backend database {url:?}")]
+    RevokeKey {
+        url: String,
+        id:  String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to revoke tokens.
+    #[error("Failed to revoke tokens in backend database {url:?}")]
+    RevokeTokens {
+        url: String,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to rewrite a record in the audit log.
+    #[error("Failed to rewrite audit record {seq} in backend database {url:?}")]
+    RewriteAudit {
+        url: String,
+        seq: u64,
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to deserialize a stored schema from JSON.
+    #[error("Failed to deserialize schema {name:?} from JSON")]
+    SchemaDeserialize {
+        name: String,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to serialize a schema as JSON.
+    #[error("Failed to serialize schema {name:?} as JSON")]
+    SchemaSerialize {
+        name: String,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to update the head of a branch.
+    #[error("Failed to set version {version} as the head of branch {name:?} in backend database {url:?}")]
+    SetBranchHead {
+        url:     String,
+        name:    String,
+        version: u64,
+        #[source]
+        err:     diesel::result::Error,
+    },
+    /// Failed to set the currently active policy.
+    #[error("Failed to set version {version} as the active policy in backend database {url:?}")]
+    SetActive {
+        url:     String,
+        version: u64,
+        #[source]
+        err:     diesel::result::Error,
+    },
+    /// Failed to spawn a background blocking task.
+    #[error("Failed to spawn a blocking task")]
+    SpawnBlocking {
+        #[source]
+        err: tokio::task::JoinError,
+    },
+    /// Failed to deserialize a part of a stored template from JSON.
+    #[error("Failed to deserialize the {what} of template {name:?} from JSON")]
+    TemplateDeserialize {
+        name: String,
+        what: &'static str,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to serialize a part of a template as JSON.
+    #[error("Failed to serialize the {what} of template {name:?} as JSON")]
+    TemplateSerialize {
+        name: String,
+        what: &'static str,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to start a transaction with the database.
+    #[error("Failed to start a transaction with the backend database")]
+    Transaction {
+        #[source]
+        err: diesel::result::Error,
+    },
+    /// Failed to unfreeze a version.
+    #[error("Failed to unfreeze version {version} in backend database {url:?}")]
+    UnfreezeVersion {
+        url:     String,
+        version: u64,
+        #[source]
+        err:     diesel::result::Error,
+    },
+    /// Attempted to activate a version that is frozen.
+    #[error("Cannot activate version {version} in backend database {url:?}, because it is frozen")]
+    VersionFrozen { url: String, version: u64 },
+    /// A version was given that is higher than [`MAX_VERSION`].
+    #[error("Version {version} is out of range (must be at most {MAX_VERSION})")]
+    VersionOutOfRange { version: u64 },
+    /// No more versions can be allocated.
+    #[error("Cannot allocate a new version in backend database {url:?}, because version {MAX_VERSION} has been reached")]
+    VersionsExhausted { url: String },
+}
+// Note: implemented to always error for transaction
+impl From<diesel::result::Error> for ConnectionError {
+    #[inline]
+    fn from(value: diesel::result::Error) -> Self { Self::Transaction { err: value } }
+}
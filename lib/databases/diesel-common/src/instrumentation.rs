@@ -0,0 +1,70 @@
+//  INSTRUMENTATION.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:06:42
+//  Last edited:
+//    16 Oct 2026, 18:06:42
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements logging of the queries run by the diesel-based
+//!   connectors, including how long they took.
+//
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use diesel::connection::{Instrumentation, InstrumentationEvent};
+use tracing::{trace, warn};
+
+
+/***** LIBRARY *****/
+/// A diesel [`Instrumentation`] that logs every query with its duration.
+///
+/// All queries are logged at TRACE-level. Queries that take at least the configured threshold are
+/// additionally logged at WARN-level.
+#[derive(Debug)]
+pub struct QueryLogger {
+    /// The threshold (in microseconds) above which queries are considered slow. [`u64::MAX`]
+    /// disables warning about slow queries.
+    ///
+    /// Shared with the database connector, such that it can be changed after connections have
+    /// been created.
+    threshold: Arc<AtomicU64>,
+    /// When the current query started, if any.
+    start:     Option<Instant>,
+}
+impl QueryLogger {
+    /// Constructor for the QueryLogger.
+    ///
+    /// # Arguments
+    /// - `threshold`: The shared threshold (in microseconds) above which queries are considered
+    ///   slow.
+    ///
+    /// # Returns
+    /// A new QueryLogger.
+    #[inline]
+    pub const fn new(threshold: Arc<AtomicU64>) -> Self { Self { threshold, start: None } }
+}
+impl Instrumentation for QueryLogger {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        match event {
+            InstrumentationEvent::StartQuery { .. } => self.start = Some(Instant::now()),
+            InstrumentationEvent::FinishQuery { query, error, .. } => {
+                let Some(start) = self.start.take() else { return };
+                let took: Duration = start.elapsed();
+                let threshold: u64 = self.threshold.load(Ordering::Relaxed);
+                let failed: &str = if error.is_some() { " (failed)" } else { "" };
+                if threshold != u64::MAX && took >= Duration::from_micros(threshold) {
+                    warn!("Slow query took {took:?}{failed}: {query}");
+                } else {
+                    trace!("Query took {took:?}{failed}: {query}");
+                }
+            },
+            _ => {},
+        }
+    }
+}
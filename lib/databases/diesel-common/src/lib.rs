@@ -0,0 +1,27 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:06:42
+//  Last edited:
+//    16 Oct 2026, 18:31:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the parts of the `DatabaseConnector` shared by the
+//!   diesel-based backends (i.e., SQLite, PostgreSQL and MySQL).
+//!
+//!   The connection itself and the rows it reads and writes are provided
+//!   as macros, which every backend expands against its own schema.
+//
+
+// Declare modules
+mod connection;
+mod error;
+mod instrumentation;
+mod models;
+
+// Import some of it
+pub use error::*;
+pub use instrumentation::*;
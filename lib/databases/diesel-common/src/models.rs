@@ -0,0 +1,221 @@
+//  MODELS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:06:42
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the rows of the tables used by the diesel-based backends.
+//!
+//!   Diesel ties the rows to the tables of a backend, so this is a macro
+//!   that every backend expands against its own `schema` module.
+//
+
+
+/***** LIBRARY *****/
+/// Defines the rows of the tables in the module it is expanded in.
+///
+/// The crate expanding it must define, at its root, a `schema` module with the tables of the
+/// backend, and must depend on `chrono` and `diesel`.
+// NOTE: `crate` deliberately refers to the backend expanding it, which owns the schema
+#[allow(clippy::crate_in_macro_def)]
+#[macro_export]
+macro_rules! models {
+    () => {
+        use chrono::{NaiveDateTime, Utc};
+        use diesel::prelude::*;
+
+        use crate::schema::{
+            active_version, audit_log, branches, current_active, decisions, event_cursors, keys, policies, provenance, revoked_tokens, revoked_users,
+            schemas, templates,
+        };
+
+        #[derive(Queryable, Insertable, Selectable)]
+        #[diesel(table_name = policies)]
+        pub struct DbPolicy {
+            pub description: String,
+            pub name: String,
+            pub language: String,
+            pub version: i64,
+            pub creator: String,
+            pub created_at: NaiveDateTime,
+            pub content: String,
+            pub branch: String,
+            pub parent: Option<i64>,
+            pub promoted_from: Option<i64>,
+            pub content_hash: Option<String>,
+            pub schema: Option<String>,
+            pub origin_store: Option<String>,
+            pub origin_version: Option<i64>,
+            pub origin_creator: Option<String>,
+            pub origin_created_at: Option<NaiveDateTime>,
+            pub verified_identity: Option<String>,
+            pub verified_digest: Option<String>,
+            pub verified_at: Option<NaiveDateTime>,
+            pub creator_issuer: Option<String>,
+            pub frozen_reason: Option<String>,
+            pub frozen_by: Option<String>,
+            pub frozen_by_issuer: Option<String>,
+            pub frozen_at: Option<NaiveDateTime>,
+            pub confidential: bool,
+        }
+
+        #[derive(Queryable, Selectable)]
+        #[diesel(table_name = policies)]
+        pub struct DbPolicyMetadata {
+            pub description: String,
+            pub name: String,
+            pub language: String,
+            pub version: i64,
+            pub creator: String,
+            pub created_at: NaiveDateTime,
+            pub branch: String,
+            pub parent: Option<i64>,
+            pub promoted_from: Option<i64>,
+            pub content_hash: Option<String>,
+            pub schema: Option<String>,
+            pub origin_store: Option<String>,
+            pub origin_version: Option<i64>,
+            pub origin_creator: Option<String>,
+            pub origin_created_at: Option<NaiveDateTime>,
+            pub verified_identity: Option<String>,
+            pub verified_digest: Option<String>,
+            pub verified_at: Option<NaiveDateTime>,
+            pub creator_issuer: Option<String>,
+            pub frozen_reason: Option<String>,
+            pub frozen_by: Option<String>,
+            pub frozen_by_issuer: Option<String>,
+            pub frozen_at: Option<NaiveDateTime>,
+            pub confidential: bool,
+        }
+
+        #[derive(Queryable, Insertable, Selectable)]
+        #[diesel(table_name = active_version)]
+        pub struct DbActiveVersion {
+            pub version: i64,
+            pub activated_on: NaiveDateTime,
+            pub activated_by: String,
+            pub deactivated_on: Option<NaiveDateTime>,
+            pub deactivated_by: Option<String>,
+            pub client: Option<String>,
+        }
+
+        impl DbActiveVersion {
+            pub fn new(version: i64, activated_by: String, client: Option<String>) -> Self {
+                Self { version, activated_by, activated_on: Utc::now().naive_utc(), deactivated_by: None, deactivated_on: None, client }
+            }
+        }
+
+        #[derive(Queryable, Insertable, Selectable, AsChangeset)]
+        #[diesel(table_name = current_active)]
+        pub struct DbCurrentActive {
+            pub id: i32,
+            pub version: i64,
+            pub activated_on: NaiveDateTime,
+        }
+
+        #[derive(Queryable, Insertable, Selectable, AsChangeset)]
+        #[diesel(table_name = templates)]
+        #[diesel(primary_key(name))]
+        pub struct DbTemplate {
+            pub name: String,
+            pub description: String,
+            pub language: String,
+            pub parameters: String,
+            pub skeleton: String,
+            pub creator: String,
+            pub created_at: NaiveDateTime,
+        }
+
+        #[derive(Queryable, Insertable, Selectable)]
+        #[diesel(table_name = branches)]
+        pub struct DbBranch {
+            pub name: String,
+            pub head: Option<i64>,
+            pub base: Option<i64>,
+            pub creator: String,
+            pub created_at: NaiveDateTime,
+        }
+
+        #[derive(Queryable, Insertable, Selectable)]
+        #[diesel(table_name = decisions)]
+        pub struct DbDecision {
+            pub id: String,
+            pub version: i64,
+            pub reporter: String,
+            pub recorded_at: NaiveDateTime,
+        }
+
+        #[derive(Queryable, Insertable, Selectable)]
+        #[diesel(table_name = provenance)]
+        pub struct DbProvenance {
+            pub version:    i64,
+            pub client:     Option<String>,
+            pub request_id: Option<String>,
+        }
+
+        #[derive(Queryable, Insertable, Selectable)]
+        #[diesel(table_name = keys)]
+        pub struct DbKey {
+            pub id: String,
+            pub kind: String,
+            pub material: String,
+            pub creator: String,
+            pub created_at: NaiveDateTime,
+            pub revoked_at: Option<NaiveDateTime>,
+        }
+
+        #[derive(Queryable, Insertable, Selectable, AsChangeset)]
+        #[diesel(table_name = event_cursors)]
+        #[diesel(primary_key(sink))]
+        pub struct DbEventCursor {
+            pub sink: String,
+            pub seq: i64,
+            pub updated_at: NaiveDateTime,
+        }
+
+        #[derive(Queryable, Insertable, Selectable)]
+        #[diesel(table_name = revoked_tokens)]
+        pub struct DbRevokedToken {
+            pub jti: String,
+            pub creator: String,
+            pub created_at: NaiveDateTime,
+        }
+
+        #[derive(Queryable, Insertable, Selectable, AsChangeset)]
+        #[diesel(table_name = revoked_users)]
+        #[diesel(primary_key(user_id))]
+        pub struct DbRevokedUser {
+            pub user_id: String,
+            pub revoked_before: NaiveDateTime,
+            pub creator: String,
+            pub created_at: NaiveDateTime,
+        }
+
+        #[derive(Queryable, Insertable, Selectable)]
+        #[diesel(table_name = schemas)]
+        pub struct DbSchema {
+            pub name: String,
+            pub description: String,
+            pub schema: String,
+            pub creator: String,
+            pub created_at: NaiveDateTime,
+        }
+
+        #[derive(Queryable, Insertable, Selectable)]
+        #[diesel(table_name = audit_log)]
+        pub struct DbAuditRecord {
+            pub seq: i64,
+            pub timestamp: NaiveDateTime,
+            pub user_id: String,
+            pub action: String,
+            pub prev_hash: Option<String>,
+            pub hash: String,
+            pub client: Option<String>,
+        }
+    };
+}
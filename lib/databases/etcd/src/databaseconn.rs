@@ -0,0 +1,1913 @@
+//  DATABASECONN.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 19:05:36
+//  Last edited:
+//    17 Oct 2026, 02:16:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the actual [`DatabaseConnector`].
+//
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+pub use etcd_client::ConnectOptions;
+use etcd_client::{
+    Client, Compare, CompareOp, Error as EtcdError, GetOptions, GetResponse, SortOrder, SortTarget, Txn, TxnOp, TxnOpResponse, TxnResponse,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specifications::DatabaseConnector;
+use specifications::audit::{AuditAction, AuditRecord, ChainError, Change, verify_chain};
+use specifications::canonical::{content_hash, to_canonical_string};
+use specifications::consistency::Finding;
+use specifications::databaseconn::{ClientError, DatabaseConnection};
+use specifications::decision::Decision;
+use specifications::key::{AttachedKey, Key};
+use specifications::metadata::{AccessContext, Activation, AttachedMetadata, Branch, Freeze, MAX_VERSION, Metadata, Origin, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::schema::{AttachedSchema, Schema};
+use specifications::template::{AttachedTemplate, Template};
+use specifications::userid::{UserIdError, UserIdFormat};
+use thiserror::Error;
+use tracing::{Level, debug, info, span};
+
+
+/***** CONSTANTS *****/
+/// The prefix of all keys used by an [`EtcdDatabase`] unless configured otherwise.
+pub const DEFAULT_PREFIX: &str = "policy-store";
+
+/// The key bumped by every change, such that changes based on outdated reads are refused.
+const GUARD_KEY: &str = "guard";
+/// The key of the counter holding the number of the latest version allocated.
+const NEXT_VERSION_KEY: &str = "next_version";
+/// The directory with the metadata of every version, by number.
+const VERSIONS_KEY: &str = "versions";
+/// The directory with the content of every version, by number.
+const CONTENTS_KEY: &str = "contents";
+/// The key holding the number of the active version. Absent if no version is active.
+const ACTIVE_KEY: &str = "active";
+/// The directory with the history of activations, by the sequence number of their audit record.
+const ACTIVATIONS_KEY: &str = "activations";
+/// The directory with the branches, by name.
+const BRANCHES_KEY: &str = "branches";
+/// The directory with the templates, by name.
+const TEMPLATES_KEY: &str = "templates";
+/// The directory with the schemas, by name.
+const SCHEMAS_KEY: &str = "schemas";
+/// The directory with the decisions, by identifier.
+const DECISIONS_KEY: &str = "decisions";
+/// The directory with the verification keys, by identifier.
+const KEYS_KEY: &str = "keys";
+/// The directory with the token revocations, by token or user (see [`revocation_field()`]).
+const REVOCATIONS_KEY: &str = "revocations";
+/// The directory with the event cursors, by sink name.
+const EVENT_CURSORS_KEY: &str = "event_cursors";
+/// The directory with the audit log, by sequence number.
+const AUDIT_LOG_KEY: &str = "audit_log";
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the [`EtcdDatabase`].
+#[derive(Debug, Error)]
+pub enum DatabaseError {
+    /// Failed to connect to the etcd cluster.
+    #[error("Failed to connect to backend etcd cluster at {endpoints:?}")]
+    Connect {
+        endpoints: Vec<String>,
+        #[source]
+        err: EtcdError,
+    },
+    /// The ID of the connecting user does not adhere to the configured [`UserIdFormat`].
+    #[error("Refusing to connect to backend etcd cluster at {endpoints:?} with illegal user ID")]
+    IllegalUserId {
+        endpoints: Vec<String>,
+        #[source]
+        err: UserIdError,
+    },
+    /// Failed to read the etcd cluster while checking its consistency.
+    #[error("Failed to verify the consistency of backend etcd cluster at {endpoints:?}")]
+    Verify {
+        endpoints: Vec<String>,
+        #[source]
+        err: ConnectionError,
+    },
+}
+
+/// Defines errors originating from the [`EtcdConnection`].
+#[derive(Debug, Error)]
+pub enum ConnectionError {
+    /// The active key does not contain a version number.
+    #[error("etcd key {key:?} does not contain a version number (got {raw:?})")]
+    ActiveParse { key: String, raw: String },
+    /// The audit log failed verification, so it cannot safely be rewritten.
+    #[error("Audit log under etcd key {key:?} failed verification")]
+    AuditChain {
+        key: String,
+        #[source]
+        err: ChainError,
+    },
+    /// Failed to commit a transaction.
+    #[error("Failed to commit transaction to etcd")]
+    Commit {
+        #[source]
+        err: EtcdError,
+    },
+    /// Failed to serialize the given content as JSON.
+    #[error("Failed to serialize the content of policy {name:?} as JSON")]
+    ContentSerialize {
+        name: String,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to read a particular key (or the keys under it).
+    #[error("Failed to read etcd key {key:?}")]
+    Get {
+        key: String,
+        #[source]
+        err: EtcdError,
+    },
+    /// Failed to parse a stored value as JSON.
+    #[error("Failed to parse value under etcd key {key:?} as JSON")]
+    Parse {
+        key: String,
+        #[source]
+        err: serde_json::Error,
+    },
+    /// Failed to serialize a value to store as JSON.
+    #[error("Failed to serialize value for etcd key {key:?} as JSON")]
+    Serialize {
+        key: String,
+        #[source]
+        err: serde_json::Error,
+    },
+    /// Attempted to activate a version that does not exist.
+    #[error("Cannot activate version {version} in etcd, because it does not exist")]
+    UnknownVersion { version: u64 },
+    /// Attempted to activate a version that is frozen.
+    #[error("Cannot activate version {version} in etcd, because it is frozen")]
+    VersionFrozen { version: u64 },
+    /// No more versions can be allocated.
+    #[error("Cannot allocate a new version in etcd, because version {MAX_VERSION} has been reached")]
+    VersionsExhausted,
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Strips a [`User`] down to what is recorded about them.
+///
+/// The roles of a user are granted per access, so they are not stored with whatever the user
+/// did; just like in any other backend.
+///
+/// # Arguments
+/// - `user`: The [`User`] to record.
+///
+/// # Returns
+/// A copy of the `user` without any roles.
+#[inline]
+fn recorded(user: &User) -> User { User { id: user.id.clone(), name: user.name.clone(), issuer: user.issuer.clone(), roles: Vec::new() } }
+
+/// Hashes the content of a policy the same way every other backend does.
+///
+/// # Arguments
+/// - `name`: The name of the policy. Only used for debugging.
+/// - `content`: The content to hash.
+///
+/// # Returns
+/// The hash of the canonical JSON of the `content`.
+///
+/// # Errors
+/// This function errors if the `content` could not be serialized as JSON.
+#[inline]
+fn hash_content<C: Serialize>(name: &str, content: &C) -> Result<String, ConnectionError> {
+    match to_canonical_string(content) {
+        Ok(canonical) => Ok(content_hash(&canonical)),
+        Err(err) => Err(ConnectionError::ContentSerialize { name: name.into(), err }),
+    }
+}
+
+/// Replaces a user ID with a pseudonym, if it is the one to replace.
+///
+/// # Arguments
+/// - `field`: The stored ID to (maybe) replace.
+/// - `id`: The ID of the user to replace.
+/// - `pseudonym`: The ID to replace it with.
+///
+/// # Returns
+/// `1` if the `field` was replaced, or `0` otherwise, such that it can be counted.
+#[inline]
+fn pseudonymize(field: &mut String, id: &str, pseudonym: &str) -> u64 {
+    if field == id {
+        *field = pseudonym.into();
+        1
+    } else {
+        0
+    }
+}
+
+/// Parses a value read from etcd as JSON.
+///
+/// # Arguments
+/// - `key`: The key the value was read from. Only used for debugging.
+/// - `raw`: The value to parse.
+///
+/// # Returns
+/// The parsed value.
+///
+/// # Errors
+/// This function errors if the `raw` value is not valid JSON for `T`.
+#[inline]
+fn from_json<T: DeserializeOwned>(key: &str, raw: &[u8]) -> Result<T, ConnectionError> {
+    serde_json::from_slice(raw).map_err(|err| ConnectionError::Parse { key: key.into(), err })
+}
+
+/// Serializes a value to write to etcd as JSON.
+///
+/// # Arguments
+/// - `key`: The key the value will be written to. Only used for debugging.
+/// - `value`: The value to serialize.
+///
+/// # Returns
+/// The serialized value.
+///
+/// # Errors
+/// This function errors if the `value` could not be serialized.
+#[inline]
+fn to_json<T: Serialize>(key: &str, value: &T) -> Result<String, ConnectionError> {
+    serde_json::to_string(value).map_err(|err| ConnectionError::Serialize { key: key.into(), err })
+}
+
+/// Parses the value of the active key.
+///
+/// # Arguments
+/// - `key`: The full name of the active key. Only used for debugging.
+/// - `raw`: The value to parse.
+///
+/// # Returns
+/// The number of the active version.
+///
+/// # Errors
+/// This function errors if the `raw` value is not a number.
+#[inline]
+fn parse_active(key: String, raw: &[u8]) -> Result<u64, ConnectionError> {
+    let raw: String = String::from_utf8_lossy(raw).into_owned();
+    raw.parse::<u64>().map_err(|_| ConnectionError::ActiveParse { key, raw })
+}
+
+/// Formats a number as a field in a directory.
+///
+/// Numbers are padded to a fixed width, such that etcd (which sorts keys as bytes) lists them in
+/// numerical order.
+///
+/// # Arguments
+/// - `n`: The number to format.
+///
+/// # Returns
+/// The number as a field.
+#[inline]
+fn numbered(n: u64) -> String { format!("{n:020}") }
+
+/// Determines under which field a token revocation is stored.
+///
+/// Revocations of tokens and of users are stored in separate subdirectories, such that there is
+/// at most one per token or user.
+///
+/// # Arguments
+/// - `revocation`: The [`AttachedRevocation`] to store.
+///
+/// # Returns
+/// The field in the [`REVOCATIONS_KEY`] directory.
+#[inline]
+fn revocation_field(revocation: &AttachedRevocation) -> String {
+    match revocation {
+        AttachedRevocation::Token { jti } => format!("token/{jti}"),
+        AttachedRevocation::User { user, .. } => format!("user/{user}"),
+    }
+}
+
+/// Returns the options to read the last key in a directory with.
+///
+/// # Returns
+/// [`GetOptions`] for the key sorted last under the requested prefix.
+#[inline]
+fn last_options() -> GetOptions { GetOptions::new().with_prefix().with_sort(SortTarget::Key, SortOrder::Descend).with_limit(1) }
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Everything but the content of a version as it is stored in an [`EtcdDatabase`].
+#[derive(Deserialize, Serialize)]
+struct StoredMetadata {
+    /// The metadata of the version.
+    metadata:   Metadata,
+    /// The address of the client from which the version was written, if known.
+    #[serde(default)]
+    client:     Option<IpAddr>,
+    /// The ID of the request in which the version was written, if known.
+    #[serde(default)]
+    request_id: Option<String>,
+}
+
+/// Knows under which keys everything is stored in an [`EtcdDatabase`], and how to read and write
+/// them.
+///
+/// Its functions do not coordinate with other stores; that is left to the [`EtcdConnection`].
+#[derive(Clone)]
+struct Layout {
+    /// The prefix of every key.
+    prefix: String,
+}
+impl Layout {
+    /// Returns the full name of one of the keys.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the key without prefix (e.g., [`ACTIVE_KEY`]).
+    ///
+    /// # Returns
+    /// The name of the key in etcd.
+    #[inline]
+    fn key(&self, name: &str) -> String { format!("{}/{name}", self.prefix) }
+
+    /// Returns the full name of a field in one of the directories.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the directory without prefix (e.g., [`VERSIONS_KEY`]).
+    /// - `field`: The field in the directory.
+    ///
+    /// # Returns
+    /// The name of the key in etcd.
+    #[inline]
+    fn field(&self, name: &str, field: impl Display) -> String { format!("{}/{name}/{field}", self.prefix) }
+
+    /// Reads a key as-is.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    /// - `key`: The full name of the key.
+    ///
+    /// # Returns
+    /// The value of the key, or [`None`] if it does not exist.
+    ///
+    /// # Errors
+    /// This function errors if the key could not be read.
+    async fn get_raw(&self, etcd: &mut Client, key: String) -> Result<Option<Vec<u8>>, ConnectionError> {
+        let res: GetResponse = etcd.get(key.as_str(), None).await.map_err(|err| ConnectionError::Get { key, err })?;
+        Ok(res.kvs().first().map(|kv| kv.value().to_vec()))
+    }
+
+    /// Reads and parses a key.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    /// - `key`: The full name of the key (see [`Layout::key()`] and [`Layout::field()`]).
+    ///
+    /// # Returns
+    /// The parsed value of the key, or [`None`] if it does not exist.
+    ///
+    /// # Errors
+    /// This function errors if the key could not be read or parsed.
+    async fn get<T: DeserializeOwned>(&self, etcd: &mut Client, key: String) -> Result<Option<T>, ConnectionError> {
+        let raw: Option<Vec<u8>> = self.get_raw(etcd, key.clone()).await?;
+        raw.map(|raw| from_json(&key, &raw)).transpose()
+    }
+
+    /// Checks whether a key exists.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    /// - `key`: The full name of the key.
+    ///
+    /// # Returns
+    /// True if the key exists, or false otherwise.
+    ///
+    /// # Errors
+    /// This function errors if the key could not be read.
+    async fn exists(&self, etcd: &mut Client, key: String) -> Result<bool, ConnectionError> {
+        let res: GetResponse =
+            etcd.get(key.as_str(), Some(GetOptions::new().with_count_only())).await.map_err(|err| ConnectionError::Get { key, err })?;
+        Ok(res.count() > 0)
+    }
+
+    /// Reads (part of) a directory as-is.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    /// - `name`: The name of the directory without prefix.
+    /// - `from`: If given, the [number](numbered()) of the first field to read.
+    /// - `limit`: If given, the maximum number of fields to read.
+    ///
+    /// # Returns
+    /// The fields and their values, ordered by field.
+    ///
+    /// # Errors
+    /// This function errors if the directory could not be read.
+    async fn list_raw(
+        &self,
+        etcd: &mut Client,
+        name: &str,
+        from: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<(String, Vec<u8>)>, ConnectionError> {
+        // NOTE: The directory ends right before `<prefix>/<name>0`, as `0` is the byte after `/`
+        let dir: String = self.field(name, "");
+        let mut options: GetOptions = GetOptions::new().with_range(format!("{}/{name}0", self.prefix));
+        match limit {
+            // NOTE: etcd reads everything for a limit of zero
+            Some(0) => return Ok(Vec::new()),
+            Some(limit) => options = options.with_limit(i64::try_from(limit).unwrap_or(i64::MAX)),
+            None => {},
+        }
+        let start: String = match from {
+            Some(from) => self.field(name, numbered(from)),
+            None => dir.clone(),
+        };
+
+        let res: GetResponse = etcd.get(start, Some(options)).await.map_err(|err| ConnectionError::Get { key: dir.clone(), err })?;
+        Ok(res.kvs().iter().map(|kv| (String::from_utf8_lossy(&kv.key()[dir.len()..]).into_owned(), kv.value().to_vec())).collect())
+    }
+
+    /// Reads and parses (part of) a directory.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    /// - `name`: The name of the directory without prefix.
+    /// - `from`: If given, the [number](numbered()) of the first field to read.
+    /// - `limit`: If given, the maximum number of fields to read.
+    ///
+    /// # Returns
+    /// The fields and their parsed values, ordered by field.
+    ///
+    /// # Errors
+    /// This function errors if the directory could not be read or any of its values could not be
+    /// parsed.
+    async fn list<T: DeserializeOwned>(
+        &self,
+        etcd: &mut Client,
+        name: &str,
+        from: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<(String, T)>, ConnectionError> {
+        let key: String = self.key(name);
+        let raw: Vec<(String, Vec<u8>)> = self.list_raw(etcd, name, from, limit).await?;
+        raw.into_iter().map(|(field, raw)| Ok((field, from_json(&key, &raw)?))).collect()
+    }
+
+    /// Reads and parses the last field of a directory.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    /// - `name`: The name of the directory without prefix.
+    ///
+    /// # Returns
+    /// The field and its parsed value, or [`None`] if the directory is empty.
+    ///
+    /// # Errors
+    /// This function errors if the directory could not be read or its value could not be parsed.
+    async fn last<T: DeserializeOwned>(&self, etcd: &mut Client, name: &str) -> Result<Option<(String, T)>, ConnectionError> {
+        let dir: String = self.field(name, "");
+        let res: GetResponse = etcd.get(dir.as_str(), Some(last_options())).await.map_err(|err| ConnectionError::Get { key: dir.clone(), err })?;
+        res.kvs().first().map(|kv| Ok((String::from_utf8_lossy(&kv.key()[dir.len()..]).into_owned(), from_json(&dir, kv.value())?))).transpose()
+    }
+
+    /// Reads the last record in the audit log.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    ///
+    /// # Returns
+    /// The last [`AuditRecord`], or [`None`] if nothing was audited yet.
+    ///
+    /// # Errors
+    /// This function errors if the audit log could not be read.
+    #[inline]
+    async fn last_record(&self, etcd: &mut Client) -> Result<Option<AuditRecord>, ConnectionError> {
+        Ok(self.last(etcd, AUDIT_LOG_KEY).await?.map(|(_, record)| record))
+    }
+
+    /// Reads the active version.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    ///
+    /// # Returns
+    /// The number of the active version, or [`None`] if no version is active.
+    ///
+    /// # Errors
+    /// This function errors if the key could not be read or does not contain a number.
+    async fn active(&self, etcd: &mut Client) -> Result<Option<u64>, ConnectionError> {
+        let key: String = self.key(ACTIVE_KEY);
+        match self.get_raw(etcd, key.clone()).await? {
+            Some(raw) => Ok(Some(parse_active(key, &raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieves the activation currently in effect.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    ///
+    /// # Returns
+    /// The [`Activation`] of the active version, or [`None`] if no version is active (or if the
+    /// active key was changed without recording an activation).
+    ///
+    /// # Errors
+    /// This function errors if the active version or the activations could not be read.
+    async fn current_activation(&self, etcd: &mut Client) -> Result<Option<Activation>, ConnectionError> {
+        // Read both in one transaction, such that they can't be changed in between
+        let (active, activations): (String, String) = (self.key(ACTIVE_KEY), self.field(ACTIVATIONS_KEY, ""));
+        let txn: Txn = Txn::new().and_then([TxnOp::get(active.as_str(), None), TxnOp::get(activations.as_str(), Some(last_options()))]);
+        let res: TxnResponse = etcd.txn(txn).await.map_err(|err| ConnectionError::Get { key: active.clone(), err })?;
+        let mut values = res.op_responses().into_iter().map(|res| match res {
+            TxnOpResponse::Get(res) => res.kvs().first().map(|kv| kv.value().to_vec()),
+            _ => None,
+        });
+        let (version, last): (Option<Vec<u8>>, Option<Vec<u8>>) = (values.next().flatten(), values.next().flatten());
+        let Some(version) = version else { return Ok(None) };
+        let version: u64 = parse_active(active, &version)?;
+        let last: Option<Activation> = last.map(|last| from_json(&activations, &last)).transpose()?;
+        Ok(last.filter(|activation| activation.version == version))
+    }
+
+    /// Reads the revision of the [`GUARD_KEY`] before reading anything a change depends on.
+    ///
+    /// The change should then be [committed](Layout::commit()) with this revision, such that it
+    /// is refused if another change was committed in between.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    ///
+    /// # Returns
+    /// The revision at which the guard was last changed, or `0` if it was never changed.
+    ///
+    /// # Errors
+    /// This function errors if the key could not be read.
+    async fn guard(&self, etcd: &mut Client) -> Result<i64, ConnectionError> {
+        let key: String = self.key(GUARD_KEY);
+        let res: GetResponse = etcd.get(key.as_str(), None).await.map_err(|err| ConnectionError::Get { key, err })?;
+        Ok(res.kvs().first().map(|kv| kv.mod_revision()).unwrap_or(0))
+    }
+
+    /// Determines the number of the next version to add.
+    ///
+    /// Should be called after reading the [guard](Layout::guard()), as the counter is only
+    /// bumped when the version is [added](Layout::add()).
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    ///
+    /// # Returns
+    /// The next version number.
+    ///
+    /// # Errors
+    /// This function errors if the counter could not be read or if [`MAX_VERSION`] has been
+    /// reached.
+    async fn next_version(&self, etcd: &mut Client) -> Result<u64, ConnectionError> {
+        let latest: u64 = self.get(etcd, self.key(NEXT_VERSION_KEY)).await?.unwrap_or(0);
+        if latest >= MAX_VERSION {
+            return Err(ConnectionError::VersionsExhausted);
+        }
+        Ok(latest + 1)
+    }
+
+    /// Queues writing a key.
+    ///
+    /// # Arguments
+    /// - `ops`: The transaction to queue the write in.
+    /// - `key`: The full name of the key.
+    /// - `value`: The new value of the key.
+    ///
+    /// # Errors
+    /// This function errors if the `value` could not be serialized.
+    fn put<T: Serialize>(&self, ops: &mut Vec<TxnOp>, key: String, value: &T) -> Result<(), ConnectionError> {
+        let raw: String = to_json(&key, value)?;
+        ops.push(TxnOp::put(key, raw, None));
+        Ok(())
+    }
+
+    /// Queues appending a version to the branch in its metadata.
+    ///
+    /// Creates the branch if it doesn't exist yet, sets the version's parent to the previous head
+    /// of the branch and bumps the version counter. Should be called after reading the
+    /// [guard](Layout::guard()).
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read the branch with.
+    /// - `ops`: The transaction to queue the writes in.
+    /// - `stored`: The [`StoredMetadata`] of the version to add. Its version number should come
+    ///   from [`Layout::next_version()`].
+    /// - `content`: The content of the version, already serialized as JSON.
+    ///
+    /// # Errors
+    /// This function errors if the branch could not be read or the version could not be
+    /// serialized.
+    async fn add(&self, etcd: &mut Client, ops: &mut Vec<TxnOp>, mut stored: StoredMetadata, content: Vec<u8>) -> Result<(), ConnectionError> {
+        let name: String = stored.metadata.attached.branch.clone();
+        let mut branch: Branch = match self.get(etcd, self.field(BRANCHES_KEY, &name)).await? {
+            Some(branch) => branch,
+            None => {
+                debug!("Creating new branch {name:?}...");
+                Branch {
+                    name:    name.clone(),
+                    head:    None,
+                    base:    None,
+                    created: stored.metadata.created,
+                    creator: stored.metadata.creator.clone(),
+                }
+            },
+        };
+        let version: u64 = stored.metadata.version;
+        stored.metadata.parent = branch.head.replace(version);
+
+        ops.push(TxnOp::put(self.key(NEXT_VERSION_KEY), version.to_string(), None));
+        self.put(ops, self.field(VERSIONS_KEY, numbered(version)), &stored)?;
+        ops.push(TxnOp::put(self.field(CONTENTS_KEY, numbered(version)), content, None));
+        self.put(ops, self.field(BRANCHES_KEY, &name), &branch)
+    }
+
+    /// Queues appending a record to the audit log.
+    ///
+    /// Should be called in the same transaction as the change it records.
+    ///
+    /// # Arguments
+    /// - `ops`: The transaction to queue the write in.
+    /// - `prev`: The last record currently in the log, if any.
+    /// - `user`: The ID of the user who made the change.
+    /// - `client`: The address of the client from which the change was made, if known.
+    /// - `action`: The [`AuditAction`] describing the change.
+    ///
+    /// # Returns
+    /// The sequence number of the new record.
+    ///
+    /// # Errors
+    /// This function errors if the record could not be serialized.
+    fn append_audit(
+        &self,
+        ops: &mut Vec<TxnOp>,
+        prev: Option<&AuditRecord>,
+        user: &str,
+        client: Option<IpAddr>,
+        action: AuditAction,
+    ) -> Result<u64, ConnectionError> {
+        let record = AuditRecord::new(prev, user, client, action);
+        debug!("Appending audit record {}...", record.seq);
+        self.put(ops, self.field(AUDIT_LOG_KEY, numbered(record.seq)), &record)?;
+        Ok(record.seq)
+    }
+
+    /// Commits a transaction, unless another change was committed since reading the
+    /// [guard](Layout::guard()).
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to commit with.
+    /// - `guard`: The revision of the guard as read before the transaction.
+    /// - `ops`: The writes of the transaction.
+    ///
+    /// # Returns
+    /// True if the transaction was committed, or false if it has to be retried.
+    ///
+    /// # Errors
+    /// This function errors if the transaction could not be sent.
+    async fn commit(&self, etcd: &mut Client, guard: i64, mut ops: Vec<TxnOp>) -> Result<bool, ConnectionError> {
+        let key: String = self.key(GUARD_KEY);
+        ops.push(TxnOp::put(key.as_str(), "", None));
+        let txn: Txn = Txn::new().when([Compare::mod_revision(key, CompareOp::Equal, guard)]).and_then(ops);
+        let res: TxnResponse = etcd.txn(txn).await.map_err(|err| ConnectionError::Commit { err })?;
+        if !res.succeeded() {
+            debug!("Transaction conflicted with a concurrent change; retrying...");
+        }
+        Ok(res.succeeded())
+    }
+
+    /// Checks the invariants of the stored policies.
+    ///
+    /// # Arguments
+    /// - `etcd`: The client to read with.
+    ///
+    /// # Returns
+    /// A list of [`Finding`]s describing every violated invariant.
+    ///
+    /// # Errors
+    /// This function errors if anything could not be read.
+    async fn verify<C: DeserializeOwned>(&self, etcd: &mut Client) -> Result<Vec<Finding>, ConnectionError> {
+        // Check the versions themselves
+        let versions: Vec<(String, StoredMetadata)> = self.list(etcd, VERSIONS_KEY, None, None).await?;
+        let contents: HashMap<String, Vec<u8>> = self.list_raw(etcd, CONTENTS_KEY, None, None).await?.into_iter().collect();
+        let mut findings: Vec<Finding> = Vec::new();
+        let mut present: HashSet<u64> = HashSet::new();
+        for (field, stored) in versions {
+            let Ok(version) = field.parse::<u64>() else { continue };
+            present.insert(version);
+            for parent in stored.metadata.parent.into_iter().chain(stored.metadata.promoted_from) {
+                if parent >= version {
+                    findings.push(Finding::NonMonotoneVersion { version, parent });
+                }
+            }
+
+            // Values that aren't even JSON are found rather than failed on, as they are likely edited by hand
+            let content: Value = match contents.get(&field).map(|raw| serde_json::from_slice(raw)) {
+                Some(Ok(content)) => content,
+                Some(Err(err)) => {
+                    findings.push(Finding::InvalidContent { version, reason: err.to_string() });
+                    continue;
+                },
+                None => {
+                    findings.push(Finding::InvalidContent { version, reason: "Content is missing".into() });
+                    continue;
+                },
+            };
+
+            // NOTE: Hashed the same way as when adding, which can't fail for values
+            let actual: String = content_hash(&to_canonical_string(&content).unwrap_or_default());
+            match stored.metadata.hash {
+                Some(expected) if expected != actual => findings.push(Finding::HashMismatch { version, expected, actual }),
+                Some(_) => {},
+                None => findings.push(Finding::MissingHash { version }),
+            }
+            if let Err(err) = serde_json::from_value::<C>(content) {
+                findings.push(Finding::InvalidContent { version, reason: err.to_string() });
+            }
+        }
+
+        // Then check whatever refers to them
+        if let Some(active) = self.active(etcd).await? {
+            if !present.contains(&active) {
+                findings.push(Finding::DanglingActive { version: active });
+            }
+        }
+        let activations: Vec<(String, Activation)> = self.list(etcd, ACTIVATIONS_KEY, None, None).await?;
+        for (_, activation) in activations {
+            if !present.contains(&activation.version) {
+                findings.push(Finding::OrphanedActivation { version: activation.version, activated: activation.activated });
+            }
+        }
+        Ok(findings)
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A [`DatabaseConnector`] that stores everything in an etcd cluster.
+///
+/// Every version is stored as two keys (one for its metadata and one for its content) under a
+/// directory keyed by its number, and the number of the active version in a key of its own.
+/// Everything else (e.g., branches or the audit log) is stored in its own directory next to them.
+/// Version numbers are allocated from a counter key. All keys share a
+/// [prefix](EtcdDatabase::with_prefix()), such that the store can share a cluster with others
+/// (like the one of a Kubernetes deployment).
+///
+/// Reads are served straight from the cluster, without any coordination. Changes are written in
+/// transactions that only succeed if no other change was written since reading what they depend
+/// on, and are retried otherwise, such that any number of stores can share the same keys. Note
+/// that etcd limits the size of a transaction (to 128 operations by default), which may be
+/// exceeded when pseudonymizing a user who did a lot.
+pub struct EtcdDatabase<C> {
+    /// The endpoints of the cluster we connect to.
+    endpoints: Vec<String>,
+    /// The (shared) client used for everything.
+    etcd:      Client,
+    /// Where everything is stored.
+    layout:    Layout,
+    /// The format that the IDs of connecting users must adhere to.
+    user_ids:  Arc<dyn Send + Sync + UserIdFormat>,
+    /// Remembers the type of content stored.
+    _content:  PhantomData<C>,
+}
+impl<C> Clone for EtcdDatabase<C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            endpoints: self.endpoints.clone(),
+            etcd:      self.etcd.clone(),
+            layout:    self.layout.clone(),
+            user_ids:  self.user_ids.clone(),
+            _content:  PhantomData,
+        }
+    }
+}
+impl<C> EtcdDatabase<C> {
+    /// Constructor for the EtcdDatabase.
+    ///
+    /// # Arguments
+    /// - `endpoints`: The endpoints of the etcd cluster to connect to (e.g., `localhost:2379`).
+    /// - `options`: Any [`ConnectOptions`] (e.g., credentials) to connect with.
+    ///
+    /// # Returns
+    /// A new EtcdDatabase struct that stores everything under the [`DEFAULT_PREFIX`].
+    ///
+    /// # Errors
+    /// This function errors if any of the endpoints is invalid or if we failed to connect to the
+    /// cluster.
+    pub async fn new(endpoints: impl IntoIterator<Item = impl Into<String>>, options: Option<ConnectOptions>) -> Result<Self, DatabaseError> {
+        let endpoints: Vec<String> = endpoints.into_iter().map(Into::into).collect();
+        debug!("Connecting to etcd cluster at {endpoints:?}...");
+        let etcd: Client = match Client::connect(&endpoints, options).await {
+            Ok(etcd) => etcd,
+            Err(err) => return Err(DatabaseError::Connect { endpoints, err }),
+        };
+        Ok(Self { endpoints, etcd, layout: Layout { prefix: DEFAULT_PREFIX.into() }, user_ids: Arc::new(()), _content: PhantomData })
+    }
+
+    /// Stores everything under keys with the given prefix instead of the [`DEFAULT_PREFIX`].
+    ///
+    /// Stores sharing a cluster but not their policies should use different prefixes.
+    ///
+    /// # Arguments
+    /// - `prefix`: The prefix of every key (joined to the rest with a slash).
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.layout.prefix = prefix.into();
+        self
+    }
+
+    /// Refuses connections for users whose ID does not adhere to the given format.
+    ///
+    /// IDs are [validated](UserIdFormat::validate()), not normalized; see
+    /// `SQLiteDatabase::with_user_id_format()` for why. By default, any ID is accepted.
+    ///
+    /// # Arguments
+    /// - `format`: The [`UserIdFormat`] to enforce.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_user_id_format(mut self, format: impl 'static + Send + Sync + UserIdFormat) -> Self {
+        self.user_ids = Arc::new(format);
+        self
+    }
+
+    /// Returns the prefix of the keys under which everything is stored.
+    ///
+    /// # Returns
+    /// The prefix, without the slash joining it to the rest of a key.
+    #[inline]
+    pub fn prefix(&self) -> &str { &self.layout.prefix }
+}
+impl<C: Send + Sync + Serialize + DeserializeOwned + 'static> DatabaseConnector for EtcdDatabase<C> {
+    type Connection<'s>
+        = EtcdConnection<'s, C>
+    where
+        Self: 's;
+    type Content = C;
+    type Error = DatabaseError;
+
+    #[inline]
+    fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        self.connect_from(user, None)
+    }
+
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            if let Err(err) = self.user_ids.validate(&user.id) {
+                return Err(DatabaseError::IllegalUserId { endpoints: self.endpoints.clone(), err });
+            }
+            debug!("Creating new connection to etcd cluster at {:?}...", self.endpoints);
+            Ok(EtcdConnection { etcd: self.etcd.clone(), layout: &self.layout, user, client, request_id: None, _content: PhantomData })
+        }
+    }
+
+    /// Also records the request ID of the access in the provenance of the versions written.
+    ///
+    /// Deadlines are ignored, as connecting never waits (the client is shared).
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            let mut conn: EtcdConnection<'s, C> = self.connect_from(&context.user, context.client).await?;
+            conn.request_id = context.request_id.clone();
+            Ok(conn)
+        }
+    }
+
+    /// Checks that the active version and every activation refer to existing versions, that every
+    /// version is numbered higher than the versions it derives from, and that the content of
+    /// every version is valid and matches its hash.
+    ///
+    /// Note that the directories are read one after another, so findings may be reported
+    /// spuriously if other stores change them while verifying.
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdDatabase::verify", prefix = self.layout.prefix);
+
+            let mut etcd: Client = self.etcd.clone();
+            self.layout.verify::<C>(&mut etcd).await.map_err(|err| DatabaseError::Verify { endpoints: self.endpoints.clone(), err })
+        }
+    }
+}
+
+
+
+/// Represents the connection created by [`EtcdDatabase::connect()`].
+///
+/// Every change is written as a single transaction (including its audit record), such that it is
+/// never observed halfway.
+pub struct EtcdConnection<'a, C> {
+    /// The client used for everything.
+    etcd: Client,
+    /// Where everything is stored.
+    layout: &'a Layout,
+    /// The user that is doing everything in this connection.
+    user: &'a User,
+    /// The address of the client from which the user connects, if known.
+    client: Option<IpAddr>,
+    /// The ID of the request in which the user connects, if any.
+    request_id: Option<String>,
+    /// Remembers the type of content stored.
+    _content: PhantomData<C>,
+}
+impl<C: Send + Sync + Serialize + DeserializeOwned + 'static> DatabaseConnection for EtcdConnection<'_, C> {
+    type Content = C;
+    type Error = ConnectionError;
+
+
+    // Mutable
+    fn add_version(&mut self, metadata: AttachedMetadata, content: Self::Content) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::add_version", policy = metadata.name);
+
+            let hash: String = hash_content(&metadata.name, &content)?;
+            let content: Vec<u8> = to_json(&self.layout.key(CONTENTS_KEY), &content)?.into_bytes();
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let version: u64 = self.layout.next_version(&mut self.etcd).await?;
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+
+                debug!("Adding new policy {version}...");
+                let action = AuditAction::AddVersion { version, name: metadata.name.clone(), branch: metadata.branch.clone() };
+                let stored = StoredMetadata {
+                    metadata:   Metadata {
+                        attached: metadata.clone(),
+                        created: Utc::now(),
+                        creator: recorded(self.user),
+                        version,
+                        parent: None,
+                        promoted_from: None,
+                        hash: Some(hash.clone()),
+                        origin: None,
+                        verification: None,
+                        frozen: None,
+                    },
+                    client:     self.client,
+                    request_id: self.request_id.clone(),
+                };
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.add(&mut self.etcd, &mut ops, stored, content.clone()).await?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(version);
+                }
+            }
+        }
+    }
+
+    fn activate(&mut self, version: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::activate", version = version);
+
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                match self.layout.get::<StoredMetadata>(&mut self.etcd, self.layout.field(VERSIONS_KEY, numbered(version))).await? {
+                    Some(stored) if stored.metadata.frozen.is_some() => return Err(ConnectionError::VersionFrozen { version }),
+                    Some(_) => {},
+                    None => return Err(ConnectionError::UnknownVersion { version }),
+                }
+
+                // They may already be the same, ez
+                let active: Option<u64> = self.layout.active(&mut self.etcd).await?;
+                if active == Some(version) {
+                    info!("Activated already-active version {version}");
+                    return Ok(());
+                }
+
+                debug!("Activating policy {version}...");
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let activation = Activation {
+                    version,
+                    activated: Utc::now(),
+                    activator: recorded(self.user),
+                    client: self.client,
+                    deactivated: None,
+                    deactivator: None,
+                };
+                let mut ops: Vec<TxnOp> = Vec::new();
+
+                // Close the activation it supersedes (if any) before adding the new one
+                if let Some((field, mut current)) =
+                    self.layout.last::<Activation>(&mut self.etcd, ACTIVATIONS_KEY).await?.filter(|(_, current)| Some(current.version) == active)
+                {
+                    current.deactivated = Some(activation.activated);
+                    current.deactivator = Some(recorded(self.user));
+                    self.layout.put(&mut ops, self.layout.field(ACTIVATIONS_KEY, field), &current)?;
+                }
+                let seq: u64 = self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, AuditAction::Activate { version })?;
+                // NOTE: Numbered after the audit record, as those are never reused (unlike the positions of pruned activations)
+                self.layout.put(&mut ops, self.layout.field(ACTIVATIONS_KEY, numbered(seq)), &activation)?;
+                ops.push(TxnOp::put(self.layout.key(ACTIVE_KEY), version.to_string(), None));
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::deactivate");
+
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let Some(version) = self.layout.active(&mut self.etcd).await? else {
+                    info!("Deactivated a policy whilst none were active");
+                    return Ok(());
+                };
+
+                debug!("Deactivating active policy {version}...");
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let mut ops: Vec<TxnOp> = Vec::new();
+                if let Some((field, mut activation)) =
+                    self.layout.last::<Activation>(&mut self.etcd, ACTIVATIONS_KEY).await?.filter(|(_, activation)| activation.version == version)
+                {
+                    activation.deactivated = Some(Utc::now());
+                    activation.deactivator = Some(recorded(self.user));
+                    self.layout.put(&mut ops, self.layout.field(ACTIVATIONS_KEY, field), &activation)?;
+                }
+                ops.push(TxnOp::delete(self.layout.key(ACTIVE_KEY), None));
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, AuditAction::Deactivate { version })?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn freeze_version(&mut self, version: u64, reason: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::freeze_version", version = version);
+
+            let key: String = self.layout.field(VERSIONS_KEY, numbered(version));
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let Some(mut stored) = self.layout.get::<StoredMetadata>(&mut self.etcd, key.clone()).await? else {
+                    return Ok(Err(ClientError::UnknownVersion { version }));
+                };
+
+                debug!("Freezing policy {version}...");
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                stored.metadata.frozen = Some(Freeze { reason: reason.into(), frozen: Utc::now(), freezer: recorded(self.user) });
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, key.clone(), &stored)?;
+                let action = AuditAction::FreezeVersion { version, reason: reason.into() };
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn unfreeze_version(&mut self, version: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::unfreeze_version", version = version);
+
+            let key: String = self.layout.field(VERSIONS_KEY, numbered(version));
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let Some(mut stored) = self.layout.get::<StoredMetadata>(&mut self.etcd, key.clone()).await? else {
+                    return Ok(Err(ClientError::UnknownVersion { version }));
+                };
+
+                // Unfreeze it, unless it isn't frozen
+                debug!("Unfreezing policy {version}...");
+                if stored.metadata.frozen.take().is_none() {
+                    return Ok(Ok(()));
+                }
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, key.clone(), &stored)?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, AuditAction::UnfreezeVersion { version })?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn add_template(&mut self, template: AttachedTemplate) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::add_template", template = template.name);
+
+            // Submit it, replacing any existing one
+            debug!("Adding template {:?}...", template.name);
+            let name: String = template.name.clone();
+            let template = Template { attached: template, created: Utc::now(), creator: recorded(self.user) };
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, self.layout.field(TEMPLATES_KEY, &name), &template)?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, AuditAction::AddTemplate { name: name.clone() })?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn create_branch(&mut self, name: &str, from: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::create_branch", branch = name, from = from);
+
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                if self.layout.exists(&mut self.etcd, self.layout.field(BRANCHES_KEY, name)).await? {
+                    return Ok(Err(ClientError::BranchExists { name: name.into() }));
+                }
+                if !self.layout.exists(&mut self.etcd, self.layout.field(VERSIONS_KEY, numbered(from))).await? {
+                    return Ok(Err(ClientError::UnknownVersion { version: from }));
+                }
+
+                debug!("Creating branch {name:?} from version {from}...");
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let branch =
+                    Branch { name: name.into(), head: Some(from), base: Some(from), created: Utc::now(), creator: recorded(self.user) };
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, self.layout.field(BRANCHES_KEY, name), &branch)?;
+                let action = AuditAction::CreateBranch { name: name.into(), from };
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn promote(&mut self, version: u64, target: &str, force: bool) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::promote", version = version, target = target);
+
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let Some(original) = self.layout.get::<StoredMetadata>(&mut self.etcd, self.layout.field(VERSIONS_KEY, numbered(version))).await?
+                else {
+                    return Ok(Err(ClientError::UnknownVersion { version }));
+                };
+                if original.metadata.frozen.is_some() {
+                    return Ok(Err(ClientError::VersionFrozen { version }));
+                }
+                let source: &str = &original.metadata.attached.branch;
+                if source == target {
+                    return Ok(Err(ClientError::PromoteSameBranch { version, branch: target.into() }));
+                }
+                let branches: HashMap<String, Branch> = self.layout.list(&mut self.etcd, BRANCHES_KEY, None, None).await?.into_iter().collect();
+                let Some(target_branch) = branches.get(target) else { return Ok(Err(ClientError::UnknownBranch { name: target.into() })) };
+
+                // Find where the branches last met; i.e., the latest promotion from the source into the target, or else the fork point
+                debug!("Checking for conflicts between branch {source:?} and {target:?}...");
+                let versions: HashMap<u64, StoredMetadata> = self
+                    .layout
+                    .list::<StoredMetadata>(&mut self.etcd, VERSIONS_KEY, None, None)
+                    .await?
+                    .into_iter()
+                    .map(|(_, stored)| (stored.metadata.version, stored))
+                    .collect();
+                let last_promotion: Option<u64> = versions
+                    .values()
+                    .filter(|stored| stored.metadata.attached.branch == target)
+                    .filter(|stored| {
+                        stored.metadata.promoted_from.and_then(|from| versions.get(&from)).is_some_and(|from| from.metadata.attached.branch == source)
+                    })
+                    .map(|stored| stored.metadata.version)
+                    .max();
+                let base: Option<u64> = last_promotion.or(branches.get(source).and_then(|branch| branch.base));
+                if let Some(head) = target_branch.head {
+                    if Some(head) != base {
+                        if force {
+                            info!("Forcing promotion of version {version} into branch {target:?} despite it having advanced to {head}");
+                        } else {
+                            return Ok(Err(ClientError::PromoteConflict {
+                                version,
+                                source_branch: source.into(),
+                                target: target.into(),
+                                head,
+                                base,
+                            }));
+                        }
+                    }
+                }
+
+                // Append the copy to the target, copying the content as-is
+                let content: Option<Vec<u8>> = self.layout.get_raw(&mut self.etcd, self.layout.field(CONTENTS_KEY, numbered(version))).await?;
+                let Some(content) = content else { return Ok(Err(ClientError::UnknownVersion { version })) };
+                let promoted: u64 = self.layout.next_version(&mut self.etcd).await?;
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                debug!("Promoting policy {version} into branch {target:?} as {promoted}...");
+                let stored = StoredMetadata {
+                    metadata:   Metadata {
+                        attached: AttachedMetadata { branch: target.into(), ..original.metadata.attached.clone() },
+                        created: Utc::now(),
+                        creator: recorded(self.user),
+                        version: promoted,
+                        parent: None,
+                        promoted_from: Some(version),
+                        hash: original.metadata.hash.clone(),
+                        origin: original.metadata.origin.clone(),
+                        verification: original.metadata.verification.clone(),
+                        frozen: None,
+                    },
+                    client:     self.client,
+                    request_id: self.request_id.clone(),
+                };
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.add(&mut self.etcd, &mut ops, stored, content).await?;
+                let action = AuditAction::Promote { version, target: target.into(), promoted };
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(promoted));
+                }
+            }
+        }
+    }
+
+    fn import_version(
+        &mut self,
+        store: &str,
+        metadata: Metadata,
+        content: Self::Content,
+    ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::import_version", store = store, version = metadata.version);
+
+            // Make sure the content survived the trip
+            let hash: String = hash_content(&metadata.attached.name, &content)?;
+            if let Some(expected) = metadata.hash {
+                if expected != hash {
+                    return Ok(Err(ClientError::HashMismatch { version: metadata.version, expected, actual: hash }));
+                }
+            }
+            let content: Vec<u8> = to_json(&self.layout.key(CONTENTS_KEY), &content)?.into_bytes();
+
+            // Keep the original origin if this version was imported before
+            let source: u64 = metadata.version;
+            let origin: Origin =
+                metadata.origin.unwrap_or(Origin { store: store.into(), version: source, creator: metadata.creator.id, created: metadata.created });
+            // NOTE: Other stores are bound by the same maximum, so they can't have such a version either
+            if origin.version > MAX_VERSION {
+                return Ok(Err(ClientError::UnknownVersion { version: origin.version }));
+            }
+
+            // Append the copy to the same branch as in the other store
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let version: u64 = self.layout.next_version(&mut self.etcd).await?;
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                debug!("Importing policy {source} from store {store:?} as {version}...");
+                let action = AuditAction::Import {
+                    version,
+                    name: metadata.attached.name.clone(),
+                    branch: metadata.attached.branch.clone(),
+                    store: store.into(),
+                    source,
+                };
+                let stored = StoredMetadata {
+                    metadata:   Metadata {
+                        attached: metadata.attached.clone(),
+                        created: Utc::now(),
+                        creator: recorded(self.user),
+                        version,
+                        parent: None,
+                        promoted_from: None,
+                        hash: Some(hash.clone()),
+                        origin: Some(origin.clone()),
+                        verification: metadata.verification.clone(),
+                        frozen: None,
+                    },
+                    client:     self.client,
+                    request_id: self.request_id.clone(),
+                };
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.add(&mut self.etcd, &mut ops, stored, content.clone()).await?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(version));
+                }
+            }
+        }
+    }
+
+    fn add_schema(&mut self, schema: AttachedSchema) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::add_schema", schema = schema.name);
+
+            let name: String = schema.name.clone();
+            let schema = Schema { attached: schema, created: Utc::now(), creator: recorded(self.user) };
+            let key: String = self.layout.field(SCHEMAS_KEY, &name);
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                if self.layout.exists(&mut self.etcd, key.clone()).await? {
+                    return Ok(Err(ClientError::SchemaExists { name }));
+                }
+
+                debug!("Adding schema {name:?}...");
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, key.clone(), &schema)?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, AuditAction::AddSchema { name: name.clone() })?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    /// Note that everything is rewritten in a single transaction, which fails if it exceeds the
+    /// maximum number of operations configured for the cluster (`--max-txn-ops`).
+    fn pseudonymize_user(&mut self, id: &str, pseudonym: &str) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            // NOTE: Deliberately not logging the ID itself, as that's what we're trying to get rid of
+            let _span = span!(Level::INFO, "EtcdConnection::pseudonymize_user", pseudonym = pseudonym);
+
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+
+                // We don't want to launder any tampering by re-hashing, so verify the audit log first
+                let mut log: Vec<AuditRecord> =
+                    self.layout.list(&mut self.etcd, AUDIT_LOG_KEY, None, None).await?.into_iter().map(|(_, record)| record).collect();
+                if let Err(err) = verify_chain(&log) {
+                    return Err(ConnectionError::AuditChain { key: self.layout.key(AUDIT_LOG_KEY), err });
+                }
+
+                // First, everything but the audit log; only rewriting keys that actually change
+                debug!("Pseudonymizing user in keys...");
+                let mut ops: Vec<TxnOp> = Vec::new();
+                let mut count: u64 = 0;
+                for (field, mut stored) in self.layout.list::<StoredMetadata>(&mut self.etcd, VERSIONS_KEY, None, None).await? {
+                    // Where they wrote it from is as telling as their ID, so forget it
+                    let mut changed: u64 = u64::from(stored.metadata.creator.id == id && stored.client.take().is_some());
+                    changed += pseudonymize(&mut stored.metadata.creator.id, id, pseudonym);
+                    if let Some(origin) = &mut stored.metadata.origin {
+                        changed += pseudonymize(&mut origin.creator, id, pseudonym);
+                    }
+                    if let Some(frozen) = &mut stored.metadata.frozen {
+                        changed += pseudonymize(&mut frozen.freezer.id, id, pseudonym);
+                    }
+                    if changed > 0 {
+                        self.layout.put(&mut ops, self.layout.field(VERSIONS_KEY, field), &stored)?;
+                        count += changed;
+                    }
+                }
+                for (field, mut template) in self.layout.list::<Template>(&mut self.etcd, TEMPLATES_KEY, None, None).await? {
+                    if pseudonymize(&mut template.creator.id, id, pseudonym) > 0 {
+                        self.layout.put(&mut ops, self.layout.field(TEMPLATES_KEY, field), &template)?;
+                        count += 1;
+                    }
+                }
+                for (field, mut branch) in self.layout.list::<Branch>(&mut self.etcd, BRANCHES_KEY, None, None).await? {
+                    if pseudonymize(&mut branch.creator.id, id, pseudonym) > 0 {
+                        self.layout.put(&mut ops, self.layout.field(BRANCHES_KEY, field), &branch)?;
+                        count += 1;
+                    }
+                }
+                for (field, mut schema) in self.layout.list::<Schema>(&mut self.etcd, SCHEMAS_KEY, None, None).await? {
+                    if pseudonymize(&mut schema.creator.id, id, pseudonym) > 0 {
+                        self.layout.put(&mut ops, self.layout.field(SCHEMAS_KEY, field), &schema)?;
+                        count += 1;
+                    }
+                }
+                for (field, mut activation) in self.layout.list::<Activation>(&mut self.etcd, ACTIVATIONS_KEY, None, None).await? {
+                    let changed: u64 = u64::from(activation.activator.id == id && activation.client.take().is_some())
+                        + pseudonymize(&mut activation.activator.id, id, pseudonym)
+                        + activation.deactivator.as_mut().map(|deactivator| pseudonymize(&mut deactivator.id, id, pseudonym)).unwrap_or(0);
+                    if changed > 0 {
+                        self.layout.put(&mut ops, self.layout.field(ACTIVATIONS_KEY, field), &activation)?;
+                        count += changed;
+                    }
+                }
+                for (field, mut decision) in self.layout.list::<Decision>(&mut self.etcd, DECISIONS_KEY, None, None).await? {
+                    if pseudonymize(&mut decision.reporter.id, id, pseudonym) > 0 {
+                        self.layout.put(&mut ops, self.layout.field(DECISIONS_KEY, field), &decision)?;
+                        count += 1;
+                    }
+                }
+                for (field, mut key) in self.layout.list::<Key>(&mut self.etcd, KEYS_KEY, None, None).await? {
+                    if pseudonymize(&mut key.creator.id, id, pseudonym) > 0 {
+                        self.layout.put(&mut ops, self.layout.field(KEYS_KEY, field), &key)?;
+                        count += 1;
+                    }
+                }
+                for (field, mut revocation) in self.layout.list::<Revocation>(&mut self.etcd, REVOCATIONS_KEY, None, None).await? {
+                    let changed: u64 = pseudonymize(&mut revocation.creator.id, id, pseudonym);
+                    if changed > 0 {
+                        self.layout.put(&mut ops, self.layout.field(REVOCATIONS_KEY, field), &revocation)?;
+                        count += changed;
+                    }
+                }
+
+                // Then the audit log, which is re-hashed from the first affected record onwards
+                debug!("Pseudonymizing user in audit log...");
+                let mut prev_hash: Option<String> = None;
+                let mut rehashed_from: Option<u64> = None;
+                for record in &mut log {
+                    if record.user == id {
+                        record.user = pseudonym.into();
+                        record.client = None;
+                        rehashed_from = rehashed_from.or(Some(record.seq));
+                        count += 1;
+                    }
+                    if record.action.pseudonymize(id, pseudonym) > 0 {
+                        rehashed_from = rehashed_from.or(Some(record.seq));
+                        count += 1;
+                    }
+                    if rehashed_from.is_some() {
+                        record.prev_hash = prev_hash;
+                        record.hash = record.compute_hash();
+                        self.layout.put(&mut ops, self.layout.field(AUDIT_LOG_KEY, numbered(record.seq)), &*record)?;
+                    }
+                    prev_hash = Some(record.hash.clone());
+                }
+                if count == 0 {
+                    return Ok(Err(ClientError::UnknownUser { id: id.into() }));
+                }
+
+                // Finally, log the pseudonymization itself
+                let action = AuditAction::Pseudonymize { pseudonym: pseudonym.into(), rehashed_from };
+                self.layout.append_audit(&mut ops, log.last(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(count));
+                }
+            }
+        }
+    }
+
+    fn add_decision(&mut self, version: u64, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::add_decision", version = version, decision = id);
+
+            // Ensure the version exists and the decision doesn't yet
+            let key: String = self.layout.field(DECISIONS_KEY, id);
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                if !self.layout.exists(&mut self.etcd, self.layout.field(VERSIONS_KEY, numbered(version))).await? {
+                    return Ok(Err(ClientError::UnknownVersion { version }));
+                }
+                if self.layout.exists(&mut self.etcd, key.clone()).await? {
+                    return Ok(Err(ClientError::DecisionExists { id: id.into() }));
+                }
+
+                debug!("Adding decision {id:?}...");
+                let decision = Decision { id: id.into(), version, recorded: Utc::now(), reporter: recorded(self.user) };
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, key.clone(), &decision)?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, AuditAction::AddDecision { version, id: id.into() })?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn prune_activations(&mut self, before: DateTime<Utc>) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::prune_activations", before = before.to_rfc3339());
+
+            // NOTE: Activations are closed when deactivated or superseded, so only the active one
+            // lacks a deactivation time (and never matches)
+            debug!("Removing activations that ended before {}...", before.to_rfc3339());
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let mut ops: Vec<TxnOp> = Vec::new();
+                for (field, activation) in self.layout.list::<Activation>(&mut self.etcd, ACTIVATIONS_KEY, None, None).await? {
+                    if activation.deactivated.is_some_and(|deactivated| deactivated < before) {
+                        ops.push(TxnOp::delete(self.layout.field(ACTIVATIONS_KEY, field), None));
+                    }
+                }
+                let rows: u64 = ops.len() as u64;
+                if rows == 0 {
+                    return Ok(0);
+                }
+
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, AuditAction::PruneActivations { before, rows })?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(rows);
+                }
+            }
+        }
+    }
+
+    fn add_key(&mut self, key: AttachedKey) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::add_key", key = key.id);
+
+            let id: String = key.id.clone();
+            let key = Key { attached: key, created: Utc::now(), creator: recorded(self.user), revoked: None };
+            let field: String = self.layout.field(KEYS_KEY, &id);
+            loop {
+                // Ensure the key doesn't exist yet (not even revoked)
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                if self.layout.exists(&mut self.etcd, field.clone()).await? {
+                    return Ok(Err(ClientError::KeyExists { id }));
+                }
+
+                debug!("Adding key {id:?}...");
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, field.clone(), &key)?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, AuditAction::AddKey { id: id.clone() })?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn revoke_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::revoke_key", key = id);
+
+            let field: String = self.layout.field(KEYS_KEY, id);
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let Some(mut key) = self.layout.get::<Key>(&mut self.etcd, field.clone()).await? else {
+                    return Ok(Err(ClientError::UnknownKey { id: id.into() }));
+                };
+
+                // Revoke it, unless it already is
+                debug!("Revoking key {id:?}...");
+                if key.revoked.is_some() {
+                    return Ok(Ok(()));
+                }
+                key.revoked = Some(Utc::now());
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, field.clone(), &key)?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, AuditAction::RevokeKey { id: id.into() })?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn revoke_tokens(&mut self, revocation: AttachedRevocation) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::revoke_tokens");
+
+            // NOTE: There is at most one revocation per token or user, which is simply replaced
+            let key: String = self.layout.field(REVOCATIONS_KEY, revocation_field(&revocation));
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                let prev: Option<Revocation> = self.layout.get(&mut self.etcd, key.clone()).await?;
+                let action: AuditAction = match &revocation {
+                    AttachedRevocation::Token { jti } => {
+                        // Revoke it, unless it already is
+                        if prev.is_some() {
+                            debug!("Token {jti:?} was already revoked");
+                            return Ok(());
+                        }
+                        debug!("Revoking token {jti:?}...");
+                        AuditAction::RevokeToken { jti: jti.clone() }
+                    },
+                    AttachedRevocation::User { user, before } => {
+                        // Only keep the latest revocation per user
+                        if prev.is_some_and(|prev| matches!(prev.attached, AttachedRevocation::User { before: revoked, .. } if revoked >= *before)) {
+                            debug!("Tokens of user {user:?} were already revoked before {before}");
+                            return Ok(());
+                        }
+                        debug!("Revoking tokens of user {user:?} issued before {before}...");
+                        AuditAction::RevokeUserTokens { user: user.clone(), before: *before }
+                    },
+                };
+                let stored = Revocation { attached: revocation.clone(), created: Utc::now(), creator: recorded(self.user) };
+
+                let prev: Option<AuditRecord> = self.layout.last_record(&mut self.etcd).await?;
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, key.clone(), &stored)?;
+                self.layout.append_audit(&mut ops, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn ack_events(&mut self, sink: &str, seq: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::ack_events", sink = sink, seq = seq);
+
+            // Only ever move the cursor forward
+            let key: String = self.layout.field(EVENT_CURSORS_KEY, sink);
+            loop {
+                let guard: i64 = self.layout.guard(&mut self.etcd).await?;
+                if let Some(prev) = self.layout.get::<u64>(&mut self.etcd, key.clone()).await?.filter(|prev| *prev >= seq) {
+                    debug!("Sink {sink:?} already acknowledged events up to {prev}");
+                    return Ok(());
+                }
+
+                debug!("Acknowledging events up to {seq} for sink {sink:?}...");
+                let mut ops: Vec<TxnOp> = Vec::new();
+                self.layout.put(&mut ops, key.clone(), &seq)?;
+                if self.layout.commit(&mut self.etcd, guard, ops).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    // Immutable
+    fn get_versions(&mut self) -> impl Send + Future<Output = Result<HashMap<u64, Metadata>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_versions");
+
+            debug!("Retrieving all policy versions...");
+            let versions: Vec<(String, StoredMetadata)> = self.layout.list(&mut self.etcd, VERSIONS_KEY, None, None).await?;
+            Ok(versions.into_iter().map(|(_, stored)| (stored.metadata.version, stored.metadata)).collect())
+        }
+    }
+
+    fn get_active_version(&mut self) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_active");
+
+            debug!("Fetching active version...");
+            self.layout.active(&mut self.etcd).await
+        }
+    }
+
+    fn get_active_version_at(&mut self, at: DateTime<Utc>) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_active_version_at", at = at.to_rfc3339());
+
+            // Find the last activation before the given time, and see if it was still active then
+            debug!("Fetching version active at {at}...");
+            let activations: Vec<(String, Activation)> = self.layout.list(&mut self.etcd, ACTIVATIONS_KEY, None, None).await?;
+            Ok(activations
+                .into_iter()
+                .map(|(_, activation)| activation)
+                .filter(|activation| activation.activated <= at)
+                .max_by_key(|activation| activation.activated)
+                .filter(|activation| activation.deactivated.is_none_or(|deactivated| deactivated > at))
+                .map(|activation| activation.version))
+        }
+    }
+
+    fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_active");
+
+            Ok(self.layout.current_activation(&mut self.etcd).await?.map(|activation| activation.activator))
+        }
+    }
+
+    fn get_activation(&mut self) -> impl Send + Future<Output = Result<Option<Activation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_activation");
+
+            self.layout.current_activation(&mut self.etcd).await
+        }
+    }
+
+    fn get_activations(&mut self) -> impl Send + Future<Output = Result<Vec<Activation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_activations");
+
+            debug!("Retrieving activation history...");
+            let activations: Vec<(String, Activation)> = self.layout.list(&mut self.etcd, ACTIVATIONS_KEY, None, None).await?;
+            let mut activations: Vec<Activation> = activations.into_iter().map(|(_, activation)| activation).collect();
+            activations.sort_by_key(|activation| activation.activated);
+            Ok(activations)
+        }
+    }
+
+    fn get_version_metadata(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Metadata>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_version_metadata", version = version);
+
+            debug!("Retrieving metadata for version {version}...");
+            let stored: Option<StoredMetadata> = self.layout.get(&mut self.etcd, self.layout.field(VERSIONS_KEY, numbered(version))).await?;
+            Ok(stored.map(|stored| stored.metadata))
+        }
+    }
+
+    fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_version_content", version = version);
+
+            debug!("Retrieving content for version {version}...");
+            self.layout.get(&mut self.etcd, self.layout.field(CONTENTS_KEY, numbered(version))).await
+        }
+    }
+
+    fn get_version_provenance(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Provenance>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_version_provenance", version = version);
+
+            debug!("Retrieving provenance for version {version}...");
+            let stored: Option<StoredMetadata> = self.layout.get(&mut self.etcd, self.layout.field(VERSIONS_KEY, numbered(version))).await?;
+            Ok(stored.map(|stored| Provenance::new(&stored.metadata, stored.client, stored.request_id)))
+        }
+    }
+
+    fn get_templates(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Template>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_templates");
+
+            debug!("Retrieving all templates...");
+            Ok(self.layout.list(&mut self.etcd, TEMPLATES_KEY, None, None).await?.into_iter().collect())
+        }
+    }
+
+    fn get_template(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Template>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_template", template = name);
+
+            debug!("Retrieving template {name:?}...");
+            self.layout.get(&mut self.etcd, self.layout.field(TEMPLATES_KEY, name)).await
+        }
+    }
+
+    fn get_branches(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Branch>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_branches");
+
+            debug!("Retrieving all branches...");
+            Ok(self.layout.list(&mut self.etcd, BRANCHES_KEY, None, None).await?.into_iter().collect())
+        }
+    }
+
+    fn get_schemas(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Schema>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_schemas");
+
+            debug!("Retrieving all schemas...");
+            Ok(self.layout.list(&mut self.etcd, SCHEMAS_KEY, None, None).await?.into_iter().collect())
+        }
+    }
+
+    fn get_schema(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Schema>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_schema", schema = name);
+
+            debug!("Retrieving schema {name:?}...");
+            self.layout.get(&mut self.etcd, self.layout.field(SCHEMAS_KEY, name)).await
+        }
+    }
+
+    fn get_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Vec<Decision>>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_decisions", version = version);
+
+            if !self.layout.exists(&mut self.etcd, self.layout.field(VERSIONS_KEY, numbered(version))).await? {
+                return Ok(None);
+            }
+            debug!("Retrieving decisions taken under version {version}...");
+            let decisions: Vec<(String, Decision)> = self.layout.list(&mut self.etcd, DECISIONS_KEY, None, None).await?;
+            let mut decisions: Vec<Decision> =
+                decisions.into_iter().map(|(_, decision)| decision).filter(|decision| decision.version == version).collect();
+            decisions.sort_by_key(|decision| decision.recorded);
+            Ok(Some(decisions))
+        }
+    }
+
+    fn count_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::count_decisions", version = version);
+
+            if !self.layout.exists(&mut self.etcd, self.layout.field(VERSIONS_KEY, numbered(version))).await? {
+                return Ok(None);
+            }
+            debug!("Counting decisions taken under version {version}...");
+            let decisions: Vec<(String, Decision)> = self.layout.list(&mut self.etcd, DECISIONS_KEY, None, None).await?;
+            Ok(Some(decisions.iter().filter(|(_, decision)| decision.version == version).count() as u64))
+        }
+    }
+
+    fn get_decision(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Decision>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_decision", decision = id);
+
+            debug!("Retrieving decision {id:?}...");
+            self.layout.get(&mut self.etcd, self.layout.field(DECISIONS_KEY, id)).await
+        }
+    }
+
+    fn get_keys(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Key>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_keys");
+
+            debug!("Retrieving all keys...");
+            Ok(self.layout.list(&mut self.etcd, KEYS_KEY, None, None).await?.into_iter().collect())
+        }
+    }
+
+    fn get_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Key>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_key", key = id);
+
+            debug!("Retrieving key {id:?}...");
+            self.layout.get(&mut self.etcd, self.layout.field(KEYS_KEY, id)).await
+        }
+    }
+
+    fn get_revocations(&mut self) -> impl Send + Future<Output = Result<Vec<Revocation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_revocations");
+
+            debug!("Retrieving all token revocations...");
+            let revocations: Vec<(String, Revocation)> = self.layout.list(&mut self.etcd, REVOCATIONS_KEY, None, None).await?;
+            let mut revocations: Vec<Revocation> = revocations.into_iter().map(|(_, revocation)| revocation).collect();
+            revocations.sort_by_key(|revocation| revocation.created);
+            Ok(revocations)
+        }
+    }
+
+    fn is_revoked(&mut self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::is_revoked", user = user);
+
+            // NOTE: Revocations are stored per token and user (see `revocation_field()`), so we can look them up directly
+            debug!("Checking whether token {jti:?} of user {user:?} is revoked...");
+            if let Some(jti) = jti {
+                if self.layout.exists(&mut self.etcd, self.layout.field(REVOCATIONS_KEY, format!("token/{jti}"))).await? {
+                    return Ok(true);
+                }
+            }
+            let field: String = format!("user/{user}");
+            let revocation: Option<Revocation> = self.layout.get(&mut self.etcd, self.layout.field(REVOCATIONS_KEY, field)).await?;
+            Ok(revocation.is_some_and(|revocation| match revocation.attached {
+                AttachedRevocation::User { before, .. } => issued.is_none_or(|issued| issued < before),
+                AttachedRevocation::Token { .. } => false,
+            }))
+        }
+    }
+
+    fn get_audit_log(&mut self, since: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<Vec<AuditRecord>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_audit_log");
+
+            debug!("Retrieving audit log...");
+            let log: Vec<(String, AuditRecord)> = self.layout.list(&mut self.etcd, AUDIT_LOG_KEY, None, None).await?;
+            Ok(log.into_iter().map(|(_, record)| record).filter(|record| since.is_none_or(|since| record.timestamp >= since)).collect())
+        }
+    }
+
+    fn get_changes(&mut self, since: u64, limit: u64) -> impl Send + Future<Output = Result<Vec<Change<Self::Content>>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_changes", since = since, limit = limit);
+
+            // NOTE: Records are stored by sequence number, so we can start reading right after `since`
+            debug!("Retrieving changes since {since}...");
+            let log: Vec<(String, AuditRecord)> = self.layout.list(&mut self.etcd, AUDIT_LOG_KEY, Some(since.saturating_add(1)), Some(limit)).await?;
+
+            // Attach any versions created
+            let mut changes: Vec<Change<C>> = Vec::new();
+            for (_, record) in log {
+                let (metadata, content): (Option<Metadata>, Option<C>) = match record.action.created_version() {
+                    Some(version) => {
+                        let stored: Option<StoredMetadata> =
+                            self.layout.get(&mut self.etcd, self.layout.field(VERSIONS_KEY, numbered(version))).await?;
+                        (
+                            stored.map(|stored| stored.metadata),
+                            self.layout.get(&mut self.etcd, self.layout.field(CONTENTS_KEY, numbered(version))).await?,
+                        )
+                    },
+                    None => (None, None),
+                };
+                changes.push(Change { record, metadata, content, added: None });
+            }
+            Ok(changes)
+        }
+    }
+
+    fn get_event_cursor(&mut self, sink: &str) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "EtcdConnection::get_event_cursor", sink = sink);
+
+            debug!("Retrieving event cursor of sink {sink:?}...");
+            Ok(self.layout.get(&mut self.etcd, self.layout.field(EVENT_CURSORS_KEY, sink)).await?.unwrap_or(0))
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 19:05:36
+//  Last edited:
+//    16 Oct 2026, 19:05:36
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the `DatabaseConnector` for an etcd backend. Policies are
+//!   kept under a key prefix in an (existing) etcd cluster, such that a
+//!   store can piggy-back on the cluster of a Kubernetes deployment.
+//
+
+// Declare modules
+mod databaseconn;
+
+// Import some of it
+pub use databaseconn::*;
@@ -0,0 +1,321 @@
+//  DATABASECONN.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:21:54
+//  Last edited:
+//    16 Oct 2026, 18:18:27
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the [`DatabaseConnector`] that injects faults into
+//!   another.
+//
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use specifications::DatabaseConnector;
+use specifications::audit::{AuditRecord, Change};
+use specifications::consistency::Finding;
+use specifications::databaseconn::{ClientError, DatabaseConnection};
+use specifications::decision::Decision;
+use specifications::key::{AttachedKey, Key};
+use specifications::metadata::{AccessContext, Activation, AttachedMetadata, Branch, Metadata, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::schema::{AttachedSchema, Schema};
+use specifications::template::{AttachedTemplate, Template};
+use thiserror::Error;
+
+use crate::faults::Faults;
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the [`FaultyDatabase`] and its [`FaultyConnection`]s.
+#[derive(Debug, Error)]
+pub enum FaultError<E> {
+    /// The wrapped backend failed.
+    #[error("Backend database failed")]
+    Backend {
+        #[source]
+        err: E,
+    },
+    /// A failure was injected instead of calling the backend.
+    #[error("Injected failure into {what}")]
+    Injected { what: &'static str },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Injects faults around a call to the backend.
+///
+/// # Arguments
+/// - `faults`: The [`Faults`] deciding what to inject.
+/// - `what`: The name of the call, for debugging purposes.
+/// - `call`: The call to the backend. It is only polled if it isn't failed.
+///
+/// # Returns
+/// Whatever the `call` returns.
+///
+/// # Errors
+/// This function errors if the `call` does, or if a failure was injected instead.
+async fn inject<T, E>(faults: &Faults, what: &'static str, call: impl Future<Output = Result<T, E>>) -> Result<T, FaultError<E>> {
+    if !faults.before(what).await {
+        return Err(FaultError::Injected { what });
+    }
+    let res: T = call.await.map_err(|err| FaultError::Backend { err })?;
+    faults.after().await;
+    Ok(res)
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Wraps another [`DatabaseConnector`] to inject faults (failures and latency) into every call,
+/// including connecting.
+///
+/// This is meant for testing how servers (and their clients) behave when the backend misbehaves,
+/// e.g., whether giving up halfway through a call leaves the store consistent. Never use it in
+/// production.
+#[derive(Clone, Debug)]
+pub struct FaultyDatabase<D> {
+    /// The connector to inject faults into.
+    inner:  D,
+    /// Decides which faults to inject.
+    faults: Arc<Faults>,
+}
+impl<D> FaultyDatabase<D> {
+    /// Constructor for the FaultyDatabase.
+    ///
+    /// # Arguments
+    /// - `inner`: The [`DatabaseConnector`] to inject faults into.
+    ///
+    /// # Returns
+    /// A new FaultyDatabase that doesn't inject anything until told to through its
+    /// [`faults()`](FaultyDatabase::faults()).
+    #[inline]
+    pub fn new(inner: D) -> Self { Self { inner, faults: Arc::new(Faults::new()) } }
+
+    /// Returns the [`Faults`] that decide what to inject.
+    ///
+    /// These can be changed at any time, also while calls are in flight. Clone the [`Arc`] to
+    /// keep changing them after the FaultyDatabase has been handed to a server.
+    ///
+    /// # Returns
+    /// A shared reference to the [`Faults`].
+    #[inline]
+    pub fn faults(&self) -> &Arc<Faults> { &self.faults }
+
+    /// Returns the wrapped connector, e.g., to check what ended up in it without faults.
+    ///
+    /// # Returns
+    /// A reference to the inner [`DatabaseConnector`].
+    #[inline]
+    pub fn inner(&self) -> &D { &self.inner }
+}
+impl<D> DatabaseConnector for FaultyDatabase<D>
+where
+    D: Sync + DatabaseConnector,
+    D::Content: Send,
+    D::Error: 'static,
+    for<'s> D::Connection<'s>: Send,
+    for<'s> <D::Connection<'s> as DatabaseConnection>::Error: 'static,
+{
+    type Connection<'s>
+        = FaultyConnection<'s, D::Connection<'s>>
+    where
+        Self: 's;
+    type Content = D::Content;
+    type Error = FaultError<D::Error>;
+
+    #[inline]
+    fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        inject(&self.faults, "connect", async move { self.inner.connect(user).await.map(|inner| FaultyConnection { inner, faults: &self.faults }) })
+    }
+
+    #[inline]
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        inject(&self.faults, "connect_from", async move {
+            self.inner.connect_from(user, client).await.map(|inner| FaultyConnection { inner, faults: &self.faults })
+        })
+    }
+
+    #[inline]
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        inject(&self.faults, "connect_with", async move {
+            self.inner.connect_with(context).await.map(|inner| FaultyConnection { inner, faults: &self.faults })
+        })
+    }
+
+    #[inline]
+    fn maintain(&self) -> impl Send + Future<Output = Result<(), Self::Error>> { inject(&self.faults, "maintain", self.inner.maintain()) }
+
+    #[inline]
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> { inject(&self.faults, "verify", self.inner.verify()) }
+}
+
+
+
+/// A connection to the backend of a [`FaultyDatabase`], which injects faults into every call.
+#[derive(Debug)]
+pub struct FaultyConnection<'f, C> {
+    /// The connection to inject faults into.
+    inner:  C,
+    /// Decides which faults to inject.
+    faults: &'f Faults,
+}
+impl<C> FaultyConnection<'_, C> {
+    /// Returns the wrapped connection, e.g., to check what ended up in it without faults.
+    ///
+    /// # Returns
+    /// A mutable reference to the inner [`DatabaseConnection`].
+    #[inline]
+    pub fn inner(&mut self) -> &mut C { &mut self.inner }
+}
+impl<C> DatabaseConnection for FaultyConnection<'_, C>
+where
+    C: Send + DatabaseConnection,
+    C::Content: Send,
+    C::Error: 'static,
+{
+    type Content = C::Content;
+    type Error = FaultError<C::Error>;
+
+    fn add_version(&mut self, metadata: AttachedMetadata, content: Self::Content) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        inject(self.faults, "add_version", self.inner.add_version(metadata, content))
+    }
+    fn activate(&mut self, version: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        inject(self.faults, "activate", self.inner.activate(version))
+    }
+    fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> { inject(self.faults, "deactivate", self.inner.deactivate()) }
+    fn freeze_version(&mut self, version: u64, reason: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        inject(self.faults, "freeze_version", self.inner.freeze_version(version, reason))
+    }
+    fn unfreeze_version(&mut self, version: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        inject(self.faults, "unfreeze_version", self.inner.unfreeze_version(version))
+    }
+    fn add_template(&mut self, template: AttachedTemplate) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        inject(self.faults, "add_template", self.inner.add_template(template))
+    }
+    fn create_branch(&mut self, name: &str, from: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        inject(self.faults, "create_branch", self.inner.create_branch(name, from))
+    }
+    fn promote(&mut self, version: u64, target: &str, force: bool) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        inject(self.faults, "promote", self.inner.promote(version, target, force))
+    }
+    fn import_version(
+        &mut self,
+        store: &str,
+        metadata: Metadata,
+        content: Self::Content,
+    ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        inject(self.faults, "import_version", self.inner.import_version(store, metadata, content))
+    }
+    fn add_schema(&mut self, schema: AttachedSchema) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        inject(self.faults, "add_schema", self.inner.add_schema(schema))
+    }
+    fn pseudonymize_user(&mut self, id: &str, pseudonym: &str) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        inject(self.faults, "pseudonymize_user", self.inner.pseudonymize_user(id, pseudonym))
+    }
+    fn add_decision(&mut self, version: u64, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        inject(self.faults, "add_decision", self.inner.add_decision(version, id))
+    }
+    fn prune_activations(&mut self, before: DateTime<Utc>) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        inject(self.faults, "prune_activations", self.inner.prune_activations(before))
+    }
+    fn add_key(&mut self, key: AttachedKey) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        inject(self.faults, "add_key", self.inner.add_key(key))
+    }
+    fn revoke_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        inject(self.faults, "revoke_key", self.inner.revoke_key(id))
+    }
+    fn revoke_tokens(&mut self, revocation: AttachedRevocation) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        inject(self.faults, "revoke_tokens", self.inner.revoke_tokens(revocation))
+    }
+    fn ack_events(&mut self, sink: &str, seq: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        inject(self.faults, "ack_events", self.inner.ack_events(sink, seq))
+    }
+
+    fn get_versions(&mut self) -> impl Send + Future<Output = Result<HashMap<u64, Metadata>, Self::Error>> {
+        inject(self.faults, "get_versions", self.inner.get_versions())
+    }
+    fn get_active_version(&mut self) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        inject(self.faults, "get_active_version", self.inner.get_active_version())
+    }
+    fn get_active_version_at(&mut self, at: DateTime<Utc>) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        inject(self.faults, "get_active_version_at", self.inner.get_active_version_at(at))
+    }
+    fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>> {
+        inject(self.faults, "get_activator", self.inner.get_activator())
+    }
+    fn get_activation(&mut self) -> impl Send + Future<Output = Result<Option<Activation>, Self::Error>> {
+        inject(self.faults, "get_activation", self.inner.get_activation())
+    }
+    fn get_activations(&mut self) -> impl Send + Future<Output = Result<Vec<Activation>, Self::Error>> {
+        inject(self.faults, "get_activations", self.inner.get_activations())
+    }
+    fn get_version_metadata(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Metadata>, Self::Error>> {
+        inject(self.faults, "get_version_metadata", self.inner.get_version_metadata(version))
+    }
+    fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>> {
+        inject(self.faults, "get_version_content", self.inner.get_version_content(version))
+    }
+    fn get_version_provenance(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Provenance>, Self::Error>> {
+        inject(self.faults, "get_version_provenance", self.inner.get_version_provenance(version))
+    }
+    fn get_templates(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Template>, Self::Error>> {
+        inject(self.faults, "get_templates", self.inner.get_templates())
+    }
+    fn get_template(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Template>, Self::Error>> {
+        inject(self.faults, "get_template", self.inner.get_template(name))
+    }
+    fn get_branches(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Branch>, Self::Error>> {
+        inject(self.faults, "get_branches", self.inner.get_branches())
+    }
+    fn get_schemas(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Schema>, Self::Error>> {
+        inject(self.faults, "get_schemas", self.inner.get_schemas())
+    }
+    fn get_schema(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Schema>, Self::Error>> {
+        inject(self.faults, "get_schema", self.inner.get_schema(name))
+    }
+    fn get_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Vec<Decision>>, Self::Error>> {
+        inject(self.faults, "get_decisions", self.inner.get_decisions(version))
+    }
+    fn count_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        inject(self.faults, "count_decisions", self.inner.count_decisions(version))
+    }
+    fn get_decision(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Decision>, Self::Error>> {
+        inject(self.faults, "get_decision", self.inner.get_decision(id))
+    }
+    fn get_keys(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Key>, Self::Error>> {
+        inject(self.faults, "get_keys", self.inner.get_keys())
+    }
+    fn get_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Key>, Self::Error>> {
+        inject(self.faults, "get_key", self.inner.get_key(id))
+    }
+    fn get_revocations(&mut self) -> impl Send + Future<Output = Result<Vec<Revocation>, Self::Error>> {
+        inject(self.faults, "get_revocations", self.inner.get_revocations())
+    }
+    fn is_revoked(&mut self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        inject(self.faults, "is_revoked", self.inner.is_revoked(jti, user, issued))
+    }
+    fn get_audit_log(&mut self, since: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<Vec<AuditRecord>, Self::Error>> {
+        inject(self.faults, "get_audit_log", self.inner.get_audit_log(since))
+    }
+    fn get_changes(&mut self, since: u64, limit: u64) -> impl Send + Future<Output = Result<Vec<Change<Self::Content>>, Self::Error>> {
+        inject(self.faults, "get_changes", self.inner.get_changes(since, limit))
+    }
+    fn get_event_cursor(&mut self, sink: &str) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        inject(self.faults, "get_event_cursor", self.inner.get_event_cursor(sink))
+    }
+}
@@ -0,0 +1,166 @@
+//  FAULTS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:21:54
+//  Last edited:
+//    16 Oct 2026, 17:21:54
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines which faults to inject, and when.
+//
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
+
+use tracing::debug;
+
+
+/***** CONSTANTS *****/
+/// The number of parts a failure rate is divided into.
+const PARTS: u64 = 1_000_000;
+
+
+
+
+
+/***** LIBRARY *****/
+/// Decides which faults to inject into calls to the backend.
+///
+/// Every setting can be changed at any time, also while calls are in flight, such that tests (or
+/// chaos experiments) can script failures as they go. By default, nothing is injected at all.
+///
+/// Which calls fail at a particular failure rate is decided by a simple generator seeded with a
+/// constant, so a single-threaded workload sees the same failures every run.
+#[derive(Debug)]
+pub struct Faults {
+    /// The chance (in parts per [`PARTS`]) that a call fails.
+    failure_rate: AtomicU32,
+    /// The number of upcoming calls that fail regardless of the failure rate.
+    failures: AtomicU64,
+    /// How long (in microseconds) calls wait before reaching the backend.
+    latency: AtomicU64,
+    /// How long (in microseconds) calls wait after the backend is done, before reporting back.
+    trailing_latency: AtomicU64,
+    /// The state of the generator deciding which calls fail.
+    state: AtomicU64,
+}
+impl Default for Faults {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl Faults {
+    /// Constructor for Faults that don't inject anything (yet).
+    ///
+    /// # Returns
+    /// A new Faults.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            failure_rate: AtomicU32::new(0),
+            failures: AtomicU64::new(0),
+            latency: AtomicU64::new(0),
+            trailing_latency: AtomicU64::new(0),
+            state: AtomicU64::new(0x2545_F491_4F6C_DD1D),
+        }
+    }
+
+    /// Makes calls fail at random.
+    ///
+    /// # Arguments
+    /// - `rate`: The chance that any call fails, between 0 (never) and 1 (always). Clamped to
+    ///   that range.
+    #[inline]
+    pub fn set_failure_rate(&self, rate: f64) { self.failure_rate.store((rate.clamp(0.0, 1.0) * PARTS as f64) as u32, Ordering::Relaxed); }
+
+    /// Makes the next calls fail, regardless of the [failure rate](Faults::set_failure_rate()).
+    ///
+    /// # Arguments
+    /// - `calls`: The number of upcoming calls to fail.
+    #[inline]
+    pub fn fail_next(&self, calls: u64) { self.failures.store(calls, Ordering::Relaxed); }
+
+    /// Delays calls before they reach the backend, e.g., to simulate a slow network or an
+    /// exhausted connection pool.
+    ///
+    /// # Arguments
+    /// - `latency`: How long to wait before every call.
+    #[inline]
+    pub fn set_latency(&self, latency: Duration) { self.latency.store(latency.as_micros() as u64, Ordering::Relaxed); }
+
+    /// Delays reporting back after the backend is done with a call, e.g., to simulate a slow
+    /// network.
+    ///
+    /// This is the most interesting moment for callers to give up, as the backend has already
+    /// done whatever it was asked to do.
+    ///
+    /// # Arguments
+    /// - `latency`: How long to wait after every successful call.
+    #[inline]
+    pub fn set_trailing_latency(&self, latency: Duration) { self.trailing_latency.store(latency.as_micros() as u64, Ordering::Relaxed); }
+
+    /// Stops injecting any faults.
+    pub fn clear(&self) {
+        self.failure_rate.store(0, Ordering::Relaxed);
+        self.failures.store(0, Ordering::Relaxed);
+        self.latency.store(0, Ordering::Relaxed);
+        self.trailing_latency.store(0, Ordering::Relaxed);
+    }
+
+
+
+    /// Decides whether the current call should fail.
+    ///
+    /// # Returns
+    /// True if it should, or false otherwise.
+    fn should_fail(&self) -> bool {
+        // Failures asked for explicitly go first
+        if self.failures.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1)).is_ok() {
+            return true;
+        }
+
+        // Otherwise, roll the dice (with xorshift64*)
+        let rate: u64 = self.failure_rate.load(Ordering::Relaxed) as u64;
+        if rate == 0 {
+            return false;
+        }
+        let mut state: u64 = 0;
+        let _ = self.state.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |mut x| {
+            x ^= x >> 12;
+            x ^= x << 25;
+            x ^= x >> 27;
+            state = x;
+            Some(x)
+        });
+        state.wrapping_mul(0x2545_F491_4F6C_DD1D) % PARTS < rate
+    }
+
+    /// Injects the faults due before a call reaches the backend.
+    ///
+    /// # Arguments
+    /// - `what`: The name of the call, for debugging purposes.
+    ///
+    /// # Returns
+    /// False if the call should fail instead of reaching the backend, or true otherwise.
+    pub(crate) async fn before(&self, what: &'static str) -> bool {
+        let latency: u64 = self.latency.load(Ordering::Relaxed);
+        if latency > 0 {
+            tokio::time::sleep(Duration::from_micros(latency)).await;
+        }
+        if self.should_fail() {
+            debug!("Injecting failure into {what}");
+            return false;
+        }
+        true
+    }
+
+    /// Injects the faults due after the backend is done with a call.
+    pub(crate) async fn after(&self) {
+        let latency: u64 = self.trailing_latency.load(Ordering::Relaxed);
+        if latency > 0 {
+            tokio::time::sleep(Duration::from_micros(latency)).await;
+        }
+    }
+}
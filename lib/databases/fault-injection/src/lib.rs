@@ -0,0 +1,23 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:21:54
+//  Last edited:
+//    16 Oct 2026, 17:21:54
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a [`DatabaseConnector`](specifications::DatabaseConnector)
+//!   that injects failures and latency into another, for testing how the
+//!   store behaves when its backend misbehaves.
+//
+
+// Declare modules
+mod databaseconn;
+mod faults;
+
+// Import some of it
+pub use databaseconn::*;
+pub use faults::Faults;
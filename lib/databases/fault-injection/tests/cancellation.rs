@@ -0,0 +1,139 @@
+//  CANCELLATION.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:21:54
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests that giving up on calls halfway (e.g., because the client
+//!   disconnected) never leaves the SQLite connector in a broken state.
+//
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use fault_injection_database::{FaultError, FaultyDatabase};
+use specifications::DatabaseConnector as _;
+use specifications::audit::{AuditAction, verify_chain};
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::User;
+use specifications::testing::metadata;
+use sqlite_database::{SQLiteDatabase, testing};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates a fresh database file for a test, wrapped to inject faults.
+///
+/// # Arguments
+/// - `name`: A name for the test, used to make the file unique.
+///
+/// # Returns
+/// A connector to the new database, and the path to its file.
+async fn fresh_database(name: &str) -> (FaultyDatabase<SQLiteDatabase<bool>>, PathBuf) {
+    let (db, path): (SQLiteDatabase<bool>, PathBuf) = testing::fresh_database(name).await;
+    (FaultyDatabase::new(db), path)
+}
+
+/// Asserts that every version in the store was added completely, i.e., that it is recorded in the
+/// audit log and that the branch it was added to has moved along.
+///
+/// # Arguments
+/// - `db`: The [`SQLiteDatabase`] to check.
+/// - `user`: Some [`User`] to check as.
+///
+/// # Returns
+/// The number of versions in the store.
+async fn assert_consistent(db: &SQLiteDatabase<bool>, user: &User) -> usize {
+    let mut conn = db.connect(user).await.expect("connection should still be usable");
+    let versions: Vec<u64> = conn.get_versions().await.unwrap().into_keys().collect();
+    let log = conn.get_audit_log(None).await.unwrap();
+    verify_chain(&log).expect("audit log should be intact");
+
+    let mut added: Vec<u64> =
+        log.iter().filter_map(|record| if let AuditAction::AddVersion { version, .. } = &record.action { Some(*version) } else { None }).collect();
+    added.sort_unstable();
+    let mut sorted: Vec<u64> = versions.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, added, "every version should have been logged, and every logged version should exist");
+    assert_eq!(conn.get_branches().await.unwrap().get("main").and_then(|branch| branch.head), sorted.last().copied());
+    versions.len()
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_cancel_after_backend_is_done() {
+    let (db, path) = fresh_database("cancel-after").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    // The caller gives up while the result is on its way back, so the change must be there
+    db.faults().set_trailing_latency(Duration::from_secs(60));
+    assert!(tokio::time::timeout(Duration::from_millis(50), conn.add_version(metadata(), true)).await.is_err());
+    db.faults().clear();
+    assert_eq!(assert_consistent(db.inner(), &user).await, 1);
+
+    // And the connection that was used can simply be used again
+    assert_eq!(conn.get_versions().await.unwrap().len(), 1);
+    assert!(conn.inner().add_version(metadata(), false).await.is_ok());
+    assert_eq!(assert_consistent(db.inner(), &user).await, 2);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_cancel_mid_operation() {
+    let (db, path) = fresh_database("cancel-mid").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+
+    // Give up at all kinds of moments, including while the backend is working
+    db.faults().set_latency(Duration::from_millis(1));
+    for i in 0..20u64 {
+        let mut conn = db.connect(&user).await.unwrap();
+        let _ = tokio::time::timeout(Duration::from_micros(250 * i), conn.add_version(metadata(), true)).await;
+    }
+    db.faults().clear();
+
+    // Whatever made it must have made it completely, and nothing may be stuck
+    // NOTE: Work that was given up on may still be finishing in the background, so give it a moment
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let count: usize = assert_consistent(db.inner(), &user).await;
+    let mut conn = db.connect(&user).await.unwrap();
+    conn.add_version(metadata(), true).await.unwrap();
+    assert_eq!(assert_consistent(db.inner(), &user).await, count + 1);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_injected_failures() {
+    let (db, path) = fresh_database("injected-failures").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+
+    // Injected failures never reach the backend
+    db.faults().fail_next(1);
+    assert!(matches!(db.connect(&user).await, Err(FaultError::Injected { what: "connect" })));
+    let mut conn = db.connect(&user).await.unwrap();
+    db.faults().fail_next(1);
+    assert!(matches!(conn.add_version(metadata(), true).await, Err(FaultError::Injected { what: "add_version" })));
+    assert_eq!(assert_consistent(db.inner(), &user).await, 0);
+
+    // Once they're over, all works as usual
+    conn.add_version(metadata(), true).await.unwrap();
+    assert_eq!(assert_consistent(db.inner(), &user).await, 1);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,1668 @@
+//  DATABASECONN.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:40:00
+//  Last edited:
+//    17 Oct 2026, 02:16:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the actual [`DatabaseConnector`].
+//
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::{ErrorKind, Write as _};
+use std::marker::PhantomData;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specifications::DatabaseConnector;
+use specifications::audit::{AuditAction, AuditRecord, ChainError, Change, verify_chain};
+use specifications::canonical::{content_hash, to_canonical_string};
+use specifications::consistency::Finding;
+use specifications::databaseconn::{ClientError, DatabaseConnection};
+use specifications::decision::Decision;
+use specifications::key::{AttachedKey, Key};
+use specifications::metadata::{AccessContext, Activation, AttachedMetadata, Branch, Freeze, MAX_VERSION, Metadata, Origin, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::schema::{AttachedSchema, Schema};
+use specifications::template::{AttachedTemplate, Template};
+use specifications::userid::{UserIdError, UserIdFormat};
+use thiserror::Error;
+use tracing::{Level, debug, info, span};
+
+
+/***** CONSTANTS *****/
+/// The directory (in the root) that contains a file per version, named after its number.
+const VERSIONS_DIR: &str = "versions";
+/// The file (in the root) that contains the number of the active version. Absent if no version
+/// is active.
+const ACTIVE_FILE: &str = "active";
+/// The file (in the root) that contains the history of activations.
+const ACTIVATIONS_FILE: &str = "activations.json";
+/// The file (in the root) that contains the branches, by name.
+const BRANCHES_FILE: &str = "branches.json";
+/// The file (in the root) that contains the templates, by name.
+const TEMPLATES_FILE: &str = "templates.json";
+/// The file (in the root) that contains the schemas, by name.
+const SCHEMAS_FILE: &str = "schemas.json";
+/// The file (in the root) that contains the decisions, by identifier.
+const DECISIONS_FILE: &str = "decisions.json";
+/// The file (in the root) that contains the verification keys, by identifier.
+const KEYS_FILE: &str = "keys.json";
+/// The file (in the root) that contains the token revocations.
+const REVOCATIONS_FILE: &str = "revocations.json";
+/// The file (in the root) that contains the event cursors, by sink name.
+const EVENT_CURSORS_FILE: &str = "event_cursors.json";
+/// The file (in the root) that contains the audit log, one JSON record per line.
+const AUDIT_LOG_FILE: &str = "audit_log.ndjson";
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the [`FsDatabase`].
+#[derive(Debug, Error)]
+pub enum DatabaseError {
+    /// Failed to create the directory to store the policies in.
+    #[error("Failed to create policy directory {}", path.display())]
+    DirCreate {
+        path: PathBuf,
+        #[source]
+        err:  std::io::Error,
+    },
+    /// The ID of the connecting user does not adhere to the configured [`UserIdFormat`].
+    #[error("Refusing to connect to policy directory {} with illegal user ID", path.display())]
+    IllegalUserId {
+        path: PathBuf,
+        #[source]
+        err:  UserIdError,
+    },
+    /// Failed to read the policy directory while checking its consistency.
+    #[error("Failed to verify the consistency of policy directory {}", path.display())]
+    Verify {
+        path: PathBuf,
+        #[source]
+        err:  ConnectionError,
+    },
+}
+
+/// Defines errors originating from the [`FsConnection`].
+#[derive(Debug, Error)]
+pub enum ConnectionError {
+    /// The active marker does not contain a version number.
+    #[error("Active marker {} does not contain a version number (got {raw:?})", path.display())]
+    ActiveParse { path: PathBuf, raw: String },
+    /// The audit log failed verification, so it cannot safely be rewritten.
+    #[error("Audit log {} failed verification", path.display())]
+    AuditChain {
+        path: PathBuf,
+        #[source]
+        err:  ChainError,
+    },
+    /// Failed to serialize the given content as JSON.
+    #[error("Failed to serialize the content of policy {name:?} as JSON")]
+    ContentSerialize {
+        name: String,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to list the versions in the versions directory.
+    #[error("Failed to read directory {}", path.display())]
+    DirRead {
+        path: PathBuf,
+        #[source]
+        err:  std::io::Error,
+    },
+    /// Failed to parse the contents of a file as JSON.
+    #[error("Failed to parse file {} as JSON", path.display())]
+    FileParse {
+        path: PathBuf,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to read a file.
+    #[error("Failed to read file {}", path.display())]
+    FileRead {
+        path: PathBuf,
+        #[source]
+        err:  std::io::Error,
+    },
+    /// Failed to serialize something to write to a file as JSON.
+    #[error("Failed to serialize contents of file {} as JSON", path.display())]
+    FileSerialize {
+        path: PathBuf,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to write a file.
+    #[error("Failed to write file {}", path.display())]
+    FileWrite {
+        path: PathBuf,
+        #[source]
+        err:  std::io::Error,
+    },
+    /// Another connection panicked while holding the directory, which may have left it in an
+    /// inconsistent state.
+    #[error("Policy directory was poisoned by a panicking connection")]
+    Poisoned,
+    /// Attempted to activate a version that does not exist.
+    #[error("Cannot activate version {version} in policy directory, because it does not exist")]
+    UnknownVersion { version: u64 },
+    /// Attempted to activate a version that is frozen.
+    #[error("Cannot activate version {version} in policy directory, because it is frozen")]
+    VersionFrozen { version: u64 },
+    /// No more versions can be allocated.
+    #[error("Cannot allocate a new version in policy directory, because version {MAX_VERSION} has been reached")]
+    VersionsExhausted,
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Strips a [`User`] down to what is recorded about them.
+///
+/// The roles of a user are granted per access, so they are not stored with whatever the user
+/// did; just like in any other backend.
+///
+/// # Arguments
+/// - `user`: The [`User`] to record.
+///
+/// # Returns
+/// A copy of the `user` without any roles.
+#[inline]
+fn recorded(user: &User) -> User { User { id: user.id.clone(), name: user.name.clone(), issuer: user.issuer.clone(), roles: Vec::new() } }
+
+/// Hashes the content of a policy the same way every other backend does.
+///
+/// # Arguments
+/// - `name`: The name of the policy. Only used for debugging.
+/// - `content`: The content to hash.
+///
+/// # Returns
+/// The hash of the canonical JSON of the `content`.
+///
+/// # Errors
+/// This function errors if the `content` could not be serialized as JSON.
+#[inline]
+fn hash_content<C: Serialize>(name: &str, content: &C) -> Result<String, ConnectionError> {
+    match to_canonical_string(content) {
+        Ok(canonical) => Ok(content_hash(&canonical)),
+        Err(err) => Err(ConnectionError::ContentSerialize { name: name.into(), err }),
+    }
+}
+
+/// Replaces a user ID with a pseudonym, if it is the one to replace.
+///
+/// # Arguments
+/// - `field`: The stored ID to (maybe) replace.
+/// - `id`: The ID of the user to replace.
+/// - `pseudonym`: The ID to replace it with.
+///
+/// # Returns
+/// `1` if the `field` was replaced, or `0` otherwise, such that it can be counted.
+#[inline]
+fn pseudonymize(field: &mut String, id: &str, pseudonym: &str) -> u64 {
+    if field == id {
+        *field = pseudonym.into();
+        1
+    } else {
+        0
+    }
+}
+
+/// Reads a JSON file.
+///
+/// # Arguments
+/// - `path`: The path of the file to read.
+///
+/// # Returns
+/// The parsed contents of the file, or [`None`] if it does not exist.
+///
+/// # Errors
+/// This function errors if the file exists but could not be read or parsed.
+fn read_file<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, ConnectionError> {
+    let raw: String = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(ConnectionError::FileRead { path: path.into(), err }),
+    };
+    serde_json::from_str(&raw).map(Some).map_err(|err| ConnectionError::FileParse { path: path.into(), err })
+}
+
+/// Replaces a file with new contents.
+///
+/// The contents are written to a temporary file first, which is then moved over the original.
+/// Hence, readers (including those outside of this process) never observe half a file.
+///
+/// # Arguments
+/// - `path`: The path of the file to write.
+/// - `raw`: The new contents of the file.
+///
+/// # Errors
+/// This function errors if the file could not be written.
+fn write_file(path: &Path, raw: &[u8]) -> Result<(), ConnectionError> {
+    let mut tmp: OsString = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp: PathBuf = tmp.into();
+
+    let mut handle: File = File::create(&tmp).map_err(|err| ConnectionError::FileWrite { path: tmp.clone(), err })?;
+    handle.write_all(raw).map_err(|err| ConnectionError::FileWrite { path: tmp.clone(), err })?;
+    handle.sync_all().map_err(|err| ConnectionError::FileWrite { path: tmp.clone(), err })?;
+    fs::rename(&tmp, path).map_err(|err| ConnectionError::FileWrite { path: path.into(), err })
+}
+
+/// Replaces a file with new contents, serialized as (pretty) JSON.
+///
+/// # Arguments
+/// - `path`: The path of the file to write.
+/// - `value`: The new contents of the file.
+///
+/// # Errors
+/// This function errors if the `value` could not be serialized or if the file could not be
+/// written.
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), ConnectionError> {
+    let mut raw: String = serde_json::to_string_pretty(value).map_err(|err| ConnectionError::FileSerialize { path: path.into(), err })?;
+    raw.push('\n');
+    write_file(path, raw.as_bytes())
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// A version as it is stored in its file in an [`FsDatabase`].
+#[derive(Deserialize, Serialize)]
+struct StoredVersion<C> {
+    /// The metadata of the version.
+    metadata:   Metadata,
+    /// The content of the version.
+    content:    C,
+    /// The address of the client from which the version was written, if known.
+    #[serde(default)]
+    client:     Option<IpAddr>,
+    /// The ID of the request in which the version was written, if known.
+    #[serde(default)]
+    request_id: Option<String>,
+}
+
+/// Everything but the content of a version as it is stored in its file, for when the content
+/// isn't needed.
+#[derive(Deserialize)]
+struct StoredMetadata {
+    /// The metadata of the version.
+    metadata:   Metadata,
+    /// The address of the client from which the version was written, if known.
+    #[serde(default)]
+    client:     Option<IpAddr>,
+    /// The ID of the request in which the version was written, if known.
+    #[serde(default)]
+    request_id: Option<String>,
+}
+
+/// Knows where everything is stored in the directory of an [`FsDatabase`].
+///
+/// Its functions do not lock anything; that is left to the [`FsConnection`].
+struct Layout {
+    /// The directory in which everything is stored.
+    root: PathBuf,
+}
+impl Layout {
+    /// Returns the path of the file storing a particular version.
+    ///
+    /// # Arguments
+    /// - `version`: The number of the version.
+    ///
+    /// # Returns
+    /// The path of its file, whether it exists or not.
+    #[inline]
+    fn version_path(&self, version: u64) -> PathBuf { self.root.join(VERSIONS_DIR).join(format!("{version}.json")) }
+
+    /// Checks whether a particular version exists.
+    ///
+    /// # Arguments
+    /// - `version`: The number of the version.
+    ///
+    /// # Returns
+    /// True if its file exists, or false otherwise.
+    #[inline]
+    fn has_version(&self, version: u64) -> bool { self.version_path(version).is_file() }
+
+    /// Lists the numbers of all versions stored.
+    ///
+    /// Files in the versions directory that are not named after a version number are ignored.
+    ///
+    /// # Returns
+    /// The version numbers, in no particular order.
+    ///
+    /// # Errors
+    /// This function errors if the versions directory could not be read.
+    fn versions(&self) -> Result<Vec<u64>, ConnectionError> {
+        let dir: PathBuf = self.root.join(VERSIONS_DIR);
+        let entries = fs::read_dir(&dir).map_err(|err| ConnectionError::DirRead { path: dir.clone(), err })?;
+        let mut versions: Vec<u64> = Vec::new();
+        for entry in entries {
+            let path: PathBuf = entry.map_err(|err| ConnectionError::DirRead { path: dir.clone(), err })?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            if let Some(version) = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse::<u64>().ok()) {
+                versions.push(version);
+            }
+        }
+        Ok(versions)
+    }
+
+    /// Determines the number of the next version to add.
+    ///
+    /// # Returns
+    /// The next version number.
+    ///
+    /// # Errors
+    /// This function errors if the versions directory could not be read or if [`MAX_VERSION`]
+    /// has been reached.
+    fn next_version(&self) -> Result<u64, ConnectionError> {
+        let latest: u64 = self.versions()?.into_iter().max().unwrap_or(0);
+        if latest >= MAX_VERSION {
+            return Err(ConnectionError::VersionsExhausted);
+        }
+        Ok(latest + 1)
+    }
+
+    /// Reads the file of a particular version.
+    ///
+    /// # Arguments
+    /// - `version`: The number of the version.
+    ///
+    /// # Returns
+    /// The parsed file (e.g., a [`StoredVersion`] or [`StoredMetadata`]), or [`None`] if the
+    /// version does not exist.
+    ///
+    /// # Errors
+    /// This function errors if the file could not be read or parsed.
+    #[inline]
+    fn read_version<T: DeserializeOwned>(&self, version: u64) -> Result<Option<T>, ConnectionError> { read_file(&self.version_path(version)) }
+
+    /// Reads the files of all versions.
+    ///
+    /// # Returns
+    /// The parsed files (e.g., [`StoredVersion`]s or [`StoredMetadata`]), by version number.
+    ///
+    /// # Errors
+    /// This function errors if any of the files could not be read or parsed.
+    fn read_versions<T: DeserializeOwned>(&self) -> Result<HashMap<u64, T>, ConnectionError> {
+        let mut res: HashMap<u64, T> = HashMap::new();
+        for version in self.versions()? {
+            // NOTE: Versions are never removed, but someone may have done so by hand in the meantime
+            if let Some(stored) = self.read_version(version)? {
+                res.insert(version, stored);
+            }
+        }
+        Ok(res)
+    }
+
+    /// Writes the file of a version, replacing it if it exists.
+    ///
+    /// # Arguments
+    /// - `stored`: The [`StoredVersion`] to write. Its number is taken from its metadata.
+    ///
+    /// # Errors
+    /// This function errors if the file could not be written.
+    #[inline]
+    fn write_version<C: Serialize>(&self, stored: &StoredVersion<C>) -> Result<(), ConnectionError> {
+        write_json(&self.version_path(stored.metadata.version), stored)
+    }
+
+    /// Appends a version to the branch in its metadata.
+    ///
+    /// Creates the branch if it doesn't exist yet, and sets the version's parent to the previous
+    /// head of the branch.
+    ///
+    /// # Arguments
+    /// - `stored`: The [`StoredVersion`] to add. Its version number should come from
+    ///   [`Layout::next_version()`].
+    ///
+    /// # Errors
+    /// This function errors if the version or the branches could not be written.
+    fn add<C: Serialize>(&self, mut stored: StoredVersion<C>) -> Result<(), ConnectionError> {
+        let mut branches: BTreeMap<String, Branch> = self.read(BRANCHES_FILE)?;
+        let branch: &mut Branch = branches.entry(stored.metadata.attached.branch.clone()).or_insert_with(|| {
+            debug!("Creating new branch {:?}...", stored.metadata.attached.branch);
+            Branch {
+                name:    stored.metadata.attached.branch.clone(),
+                head:    None,
+                base:    None,
+                created: stored.metadata.created,
+                creator: stored.metadata.creator.clone(),
+            }
+        });
+        stored.metadata.parent = branch.head.replace(stored.metadata.version);
+
+        // NOTE: Write the version first, such that a branch never points to a missing version
+        self.write_version(&stored)?;
+        self.write(BRANCHES_FILE, &branches)
+    }
+
+    /// Reads one of the files that aren't versions.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the file in the root.
+    ///
+    /// # Returns
+    /// The parsed file, or its default (i.e., empty) value if it does not exist.
+    ///
+    /// # Errors
+    /// This function errors if the file could not be read or parsed.
+    #[inline]
+    fn read<T: Default + DeserializeOwned>(&self, name: &str) -> Result<T, ConnectionError> {
+        Ok(read_file(&self.root.join(name))?.unwrap_or_default())
+    }
+
+    /// Writes one of the files that aren't versions.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the file in the root.
+    /// - `value`: The new contents of the file.
+    ///
+    /// # Errors
+    /// This function errors if the file could not be written.
+    #[inline]
+    fn write<T: Serialize>(&self, name: &str, value: &T) -> Result<(), ConnectionError> { write_json(&self.root.join(name), value) }
+
+    /// Reads the active marker.
+    ///
+    /// # Returns
+    /// The number of the active version, or [`None`] if no version is active.
+    ///
+    /// # Errors
+    /// This function errors if the marker could not be read or does not contain a number.
+    fn active(&self) -> Result<Option<u64>, ConnectionError> {
+        let path: PathBuf = self.root.join(ACTIVE_FILE);
+        let raw: String = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(ConnectionError::FileRead { path, err }),
+        };
+        match raw.trim().parse::<u64>() {
+            Ok(version) => Ok(Some(version)),
+            Err(_) => Err(ConnectionError::ActiveParse { path, raw }),
+        }
+    }
+
+    /// Writes (or removes) the active marker.
+    ///
+    /// # Arguments
+    /// - `version`: The number of the active version, or [`None`] if no version is active.
+    ///
+    /// # Errors
+    /// This function errors if the marker could not be written or removed.
+    fn set_active(&self, version: Option<u64>) -> Result<(), ConnectionError> {
+        let path: PathBuf = self.root.join(ACTIVE_FILE);
+        match version {
+            Some(version) => write_file(&path, format!("{version}\n").as_bytes()),
+            None => match fs::remove_file(&path) {
+                Ok(()) => Ok(()),
+                Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+                Err(err) => Err(ConnectionError::FileWrite { path, err }),
+            },
+        }
+    }
+
+    /// Retrieves the activation currently in effect.
+    ///
+    /// # Returns
+    /// The [`Activation`] of the active version, or [`None`] if no version is active (or if the
+    /// active marker was changed without recording an activation).
+    ///
+    /// # Errors
+    /// This function errors if the active marker or the activations could not be read.
+    fn current_activation(&self) -> Result<Option<Activation>, ConnectionError> {
+        let Some(version) = self.active()? else { return Ok(None) };
+        let mut activations: Vec<Activation> = self.read(ACTIVATIONS_FILE)?;
+        Ok(activations.pop().filter(|activation| activation.version == version))
+    }
+
+    /// Reads the audit log.
+    ///
+    /// # Returns
+    /// The [`AuditRecord`]s in the log, ordered by sequence number.
+    ///
+    /// # Errors
+    /// This function errors if the log could not be read or any of its lines could not be
+    /// parsed.
+    fn read_audit_log(&self) -> Result<Vec<AuditRecord>, ConnectionError> {
+        let path: PathBuf = self.root.join(AUDIT_LOG_FILE);
+        let raw: String = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(ConnectionError::FileRead { path, err }),
+        };
+        raw.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(|err| ConnectionError::FileParse { path: path.clone(), err }))
+            .collect()
+    }
+
+    /// Replaces the audit log, e.g., after rewriting some of its records.
+    ///
+    /// # Arguments
+    /// - `log`: The [`AuditRecord`]s to write.
+    ///
+    /// # Errors
+    /// This function errors if the log could not be written.
+    fn write_audit_log(&self, log: &[AuditRecord]) -> Result<(), ConnectionError> {
+        let path: PathBuf = self.root.join(AUDIT_LOG_FILE);
+        let mut raw: String = String::new();
+        for record in log {
+            raw.push_str(&serde_json::to_string(record).map_err(|err| ConnectionError::FileSerialize { path: path.clone(), err })?);
+            raw.push('\n');
+        }
+        write_file(&path, raw.as_bytes())
+    }
+
+    /// Appends a record to the audit log.
+    ///
+    /// Should be called after the change it records has been written, while still holding the
+    /// same lock.
+    ///
+    /// # Arguments
+    /// - `user`: The ID of the user who made the change.
+    /// - `client`: The address of the client from which the change was made, if known.
+    /// - `action`: The [`AuditAction`] describing the change.
+    ///
+    /// # Errors
+    /// This function errors if the log could not be read or appended to.
+    fn append_audit(&self, user: &str, client: Option<IpAddr>, action: AuditAction) -> Result<(), ConnectionError> {
+        let path: PathBuf = self.root.join(AUDIT_LOG_FILE);
+        let record = AuditRecord::new(self.read_audit_log()?.last(), user, client, action);
+        debug!("Appending audit record {}...", record.seq);
+        let mut line: String = serde_json::to_string(&record).map_err(|err| ConnectionError::FileSerialize { path: path.clone(), err })?;
+        line.push('\n');
+
+        let mut handle: File =
+            OpenOptions::new().create(true).append(true).open(&path).map_err(|err| ConnectionError::FileWrite { path: path.clone(), err })?;
+        handle.write_all(line.as_bytes()).map_err(|err| ConnectionError::FileWrite { path: path.clone(), err })?;
+        handle.sync_all().map_err(|err| ConnectionError::FileWrite { path, err })
+    }
+
+    /// Checks the invariants of the directory.
+    ///
+    /// # Returns
+    /// A list of [`Finding`]s describing every violated invariant.
+    ///
+    /// # Errors
+    /// This function errors if the directory could not be read.
+    fn verify<C: DeserializeOwned>(&self) -> Result<Vec<Finding>, ConnectionError> {
+        // Check the versions themselves
+        let mut findings: Vec<Finding> = Vec::new();
+        let mut present: HashSet<u64> = HashSet::new();
+        let mut versions: Vec<u64> = self.versions()?;
+        versions.sort_unstable();
+        for version in versions {
+            // Files that aren't even JSON are found rather than failed on, as they are likely edited by hand
+            let stored: StoredVersion<Value> = match self.read_version(version) {
+                Ok(Some(stored)) => stored,
+                Ok(None) => continue,
+                Err(ConnectionError::FileParse { err, .. }) => {
+                    present.insert(version);
+                    findings.push(Finding::InvalidContent { version, reason: err.to_string() });
+                    continue;
+                },
+                Err(err) => return Err(err),
+            };
+            present.insert(version);
+            for parent in stored.metadata.parent.into_iter().chain(stored.metadata.promoted_from) {
+                if parent >= version {
+                    findings.push(Finding::NonMonotoneVersion { version, parent });
+                }
+            }
+
+            // NOTE: Hashed the same way as when adding, which can't fail for values
+            let actual: String = content_hash(&to_canonical_string(&stored.content).unwrap_or_default());
+            match stored.metadata.hash {
+                Some(expected) if expected != actual => findings.push(Finding::HashMismatch { version, expected, actual }),
+                Some(_) => {},
+                None => findings.push(Finding::MissingHash { version }),
+            }
+            if let Err(err) = serde_json::from_value::<C>(stored.content) {
+                findings.push(Finding::InvalidContent { version, reason: err.to_string() });
+            }
+        }
+
+        // Then check whatever refers to them
+        if let Some(active) = self.active()? {
+            if !present.contains(&active) {
+                findings.push(Finding::DanglingActive { version: active });
+            }
+        }
+        let activations: Vec<Activation> = self.read(ACTIVATIONS_FILE)?;
+        for activation in activations {
+            if !present.contains(&activation.version) {
+                findings.push(Finding::OrphanedActivation { version: activation.version, activated: activation.activated });
+            }
+        }
+        Ok(findings)
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A [`DatabaseConnector`] that stores everything as files in a directory.
+///
+/// Every version is stored as a JSON file named after its number in the `versions` directory,
+/// and the number of the active version in the `active` file. Everything else (e.g., branches
+/// or the audit log) is stored in its own file next to them. This makes the directory easy to
+/// inspect, back up or keep in version control.
+///
+/// Files are always replaced as a whole, such that other processes (e.g., a reasoner reading the
+/// `active` file) never observe half a file. However, only a single store should write to a
+/// directory at a time; clones of the same FsDatabase coordinate, but separate processes don't.
+pub struct FsDatabase<C> {
+    /// Where everything is stored.
+    layout:   Arc<Layout>,
+    /// Serializes changes to the directory. The lock is only ever held for the duration of a
+    /// single operation, which never awaits anything.
+    lock:     Arc<RwLock<()>>,
+    /// The format that the IDs of connecting users must adhere to.
+    user_ids: Arc<dyn Send + Sync + UserIdFormat>,
+    /// Remembers the type of content stored.
+    _content: PhantomData<C>,
+}
+impl<C> Clone for FsDatabase<C> {
+    #[inline]
+    fn clone(&self) -> Self { Self { layout: self.layout.clone(), lock: self.lock.clone(), user_ids: self.user_ids.clone(), _content: PhantomData } }
+}
+impl<C> FsDatabase<C> {
+    /// Constructor for the FsDatabase.
+    ///
+    /// # Arguments
+    /// - `path`: The directory to store everything in. It (and the directory for versions in
+    ///   it) is created if it does not exist yet.
+    ///
+    /// # Returns
+    /// A new FsDatabase struct that stores everything in the given directory.
+    ///
+    /// # Errors
+    /// This function errors if the directory could not be created.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, DatabaseError> {
+        let root: PathBuf = path.into();
+        debug!("Opening policy directory {}...", root.display());
+        let versions: PathBuf = root.join(VERSIONS_DIR);
+        if let Err(err) = fs::create_dir_all(&versions) {
+            return Err(DatabaseError::DirCreate { path: versions, err });
+        }
+        Ok(Self { layout: Arc::new(Layout { root }), lock: Arc::new(RwLock::new(())), user_ids: Arc::new(()), _content: PhantomData })
+    }
+
+    /// Refuses connections for users whose ID does not adhere to the given format.
+    ///
+    /// IDs are [validated](UserIdFormat::validate()), not normalized; see
+    /// `SQLiteDatabase::with_user_id_format()` for why. By default, any ID is accepted.
+    ///
+    /// # Arguments
+    /// - `format`: The [`UserIdFormat`] to enforce.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_user_id_format(mut self, format: impl 'static + Send + Sync + UserIdFormat) -> Self {
+        self.user_ids = Arc::new(format);
+        self
+    }
+
+    /// Returns the directory in which everything is stored.
+    ///
+    /// # Returns
+    /// The path given when this database was created.
+    #[inline]
+    pub fn path(&self) -> &Path { &self.layout.root }
+}
+impl<C: Send + Sync + Serialize + DeserializeOwned + 'static> DatabaseConnector for FsDatabase<C> {
+    type Connection<'s>
+        = FsConnection<'s, C>
+    where
+        Self: 's;
+    type Content = C;
+    type Error = DatabaseError;
+
+    #[inline]
+    fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        self.connect_from(user, None)
+    }
+
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            if let Err(err) = self.user_ids.validate(&user.id) {
+                return Err(DatabaseError::IllegalUserId { path: self.layout.root.clone(), err });
+            }
+            debug!("Creating new connection to policy directory {}...", self.layout.root.display());
+            Ok(FsConnection { layout: &self.layout, lock: &self.lock, user, client, request_id: None, _content: PhantomData })
+        }
+    }
+
+    /// Also records the request ID of the access in the provenance of the versions written.
+    ///
+    /// Deadlines are ignored, as connecting never waits.
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            let mut conn: FsConnection<'s, C> = self.connect_from(&context.user, context.client).await?;
+            conn.request_id = context.request_id.clone();
+            Ok(conn)
+        }
+    }
+
+    /// Checks that the active version and every activation refer to existing versions, that every
+    /// version is numbered higher than the versions it derives from, and that the content of
+    /// every version is valid and matches its hash.
+    ///
+    /// This is especially useful after the directory was edited by hand.
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsDatabase::verify", path = self.layout.root.display().to_string());
+
+            let res: Result<Vec<Finding>, ConnectionError> = match self.lock.read() {
+                Ok(_guard) => self.layout.verify::<C>(),
+                Err(_) => Err(ConnectionError::Poisoned),
+            };
+            res.map_err(|err| DatabaseError::Verify { path: self.layout.root.clone(), err })
+        }
+    }
+}
+
+
+
+/// Represents the connection created by [`FsDatabase::connect()`].
+///
+/// Every method does all its work while holding the directory's lock, without awaiting anything
+/// in between. Hence, changes are never observed halfway by other connections to the same
+/// [`FsDatabase`]. They may be by other processes, however, as changes spanning multiple files
+/// are not written atomically.
+pub struct FsConnection<'a, C> {
+    /// Where everything is stored.
+    layout: &'a Layout,
+    /// Serializes changes to the directory.
+    lock: &'a RwLock<()>,
+    /// The user that is doing everything in this connection.
+    user: &'a User,
+    /// The address of the client from which the user connects, if known.
+    client: Option<IpAddr>,
+    /// The ID of the request in which the user connects, if any.
+    request_id: Option<String>,
+    /// Remembers the type of content stored.
+    _content: PhantomData<C>,
+}
+impl<C> FsConnection<'_, C> {
+    /// Locks the directory for reading.
+    ///
+    /// # Returns
+    /// A guard that keeps others from changing the directory while it lives.
+    ///
+    /// # Errors
+    /// This function errors if another connection panicked while holding the lock.
+    #[inline]
+    fn read(&self) -> Result<RwLockReadGuard<'_, ()>, ConnectionError> { self.lock.read().map_err(|_| ConnectionError::Poisoned) }
+
+    /// Locks the directory for writing.
+    ///
+    /// # Returns
+    /// A guard that keeps others from reading or changing the directory while it lives.
+    ///
+    /// # Errors
+    /// This function errors if another connection panicked while holding the lock.
+    #[inline]
+    fn write(&self) -> Result<RwLockWriteGuard<'_, ()>, ConnectionError> { self.lock.write().map_err(|_| ConnectionError::Poisoned) }
+}
+impl<C: Send + Sync + Serialize + DeserializeOwned + 'static> DatabaseConnection for FsConnection<'_, C> {
+    type Content = C;
+    type Error = ConnectionError;
+
+
+    // Mutable
+    fn add_version(&mut self, metadata: AttachedMetadata, content: Self::Content) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::add_version", policy = metadata.name);
+
+            let hash: String = hash_content(&metadata.name, &content)?;
+            let _guard = self.write()?;
+            let version: u64 = self.layout.next_version()?;
+
+            debug!("Adding new policy {version}...");
+            let action = AuditAction::AddVersion { version, name: metadata.name.clone(), branch: metadata.branch.clone() };
+            let metadata = Metadata {
+                attached: metadata,
+                created: Utc::now(),
+                creator: recorded(self.user),
+                version,
+                parent: None,
+                promoted_from: None,
+                hash: Some(hash),
+                origin: None,
+                verification: None,
+                frozen: None,
+            };
+            self.layout.add(StoredVersion { metadata, content, client: self.client, request_id: self.request_id.clone() })?;
+            self.layout.append_audit(&self.user.id, self.client, action)?;
+            Ok(version)
+        }
+    }
+
+    fn activate(&mut self, version: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::activate", version = version);
+
+            let _guard = self.write()?;
+            match self.layout.read_version::<StoredMetadata>(version)? {
+                Some(stored) if stored.metadata.frozen.is_some() => return Err(ConnectionError::VersionFrozen { version }),
+                Some(_) => {},
+                None => return Err(ConnectionError::UnknownVersion { version }),
+            }
+
+            // They may already be the same, ez
+            let active: Option<u64> = self.layout.active()?;
+            if active == Some(version) {
+                info!("Activated already-active version {version}");
+                return Ok(());
+            }
+
+            // Close the activation it supersedes (if any) before adding the new one
+            debug!("Activating policy {version}...");
+            let activated: DateTime<Utc> = Utc::now();
+            let mut activations: Vec<Activation> = self.layout.read(ACTIVATIONS_FILE)?;
+            if let Some(current) = activations.last_mut().filter(|activation| Some(activation.version) == active) {
+                current.deactivated = Some(activated);
+                current.deactivator = Some(recorded(self.user));
+            }
+            activations.push(Activation {
+                version,
+                activated,
+                activator: recorded(self.user),
+                client: self.client,
+                deactivated: None,
+                deactivator: None,
+            });
+            self.layout.write(ACTIVATIONS_FILE, &activations)?;
+            self.layout.set_active(Some(version))?;
+            self.layout.append_audit(&self.user.id, self.client, AuditAction::Activate { version })
+        }
+    }
+
+    fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::deactivate");
+
+            let _guard = self.write()?;
+            let Some(version) = self.layout.active()? else {
+                info!("Deactivated a policy whilst none were active");
+                return Ok(());
+            };
+
+            debug!("Deactivating active policy {version}...");
+            let mut activations: Vec<Activation> = self.layout.read(ACTIVATIONS_FILE)?;
+            if let Some(activation) = activations.last_mut().filter(|activation| activation.version == version) {
+                activation.deactivated = Some(Utc::now());
+                activation.deactivator = Some(recorded(self.user));
+                self.layout.write(ACTIVATIONS_FILE, &activations)?;
+            }
+            self.layout.set_active(None)?;
+            self.layout.append_audit(&self.user.id, self.client, AuditAction::Deactivate { version })
+        }
+    }
+
+    fn freeze_version(&mut self, version: u64, reason: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::freeze_version", version = version);
+
+            // NOTE: The content is kept as-is, as there's no need to parse it
+            let _guard = self.write()?;
+            let Some(mut stored) = self.layout.read_version::<StoredVersion<Value>>(version)? else {
+                return Ok(Err(ClientError::UnknownVersion { version }));
+            };
+
+            debug!("Freezing policy {version}...");
+            stored.metadata.frozen = Some(Freeze { reason: reason.into(), frozen: Utc::now(), freezer: recorded(self.user) });
+            self.layout.write_version(&stored)?;
+            self.layout.append_audit(&self.user.id, self.client, AuditAction::FreezeVersion { version, reason: reason.into() })?;
+            Ok(Ok(()))
+        }
+    }
+
+    fn unfreeze_version(&mut self, version: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::unfreeze_version", version = version);
+
+            let _guard = self.write()?;
+            let Some(mut stored) = self.layout.read_version::<StoredVersion<Value>>(version)? else {
+                return Ok(Err(ClientError::UnknownVersion { version }));
+            };
+
+            // Unfreeze it, unless it isn't frozen
+            debug!("Unfreezing policy {version}...");
+            if stored.metadata.frozen.take().is_some() {
+                self.layout.write_version(&stored)?;
+                self.layout.append_audit(&self.user.id, self.client, AuditAction::UnfreezeVersion { version })?;
+            }
+            Ok(Ok(()))
+        }
+    }
+
+    fn add_template(&mut self, template: AttachedTemplate) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::add_template", template = template.name);
+
+            // Submit it, replacing any existing one
+            debug!("Adding template {:?}...", template.name);
+            let name: String = template.name.clone();
+            let _guard = self.write()?;
+            let mut templates: BTreeMap<String, Template> = self.layout.read(TEMPLATES_FILE)?;
+            templates.insert(name.clone(), Template { attached: template, created: Utc::now(), creator: recorded(self.user) });
+            self.layout.write(TEMPLATES_FILE, &templates)?;
+            self.layout.append_audit(&self.user.id, self.client, AuditAction::AddTemplate { name })
+        }
+    }
+
+    fn create_branch(&mut self, name: &str, from: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::create_branch", branch = name, from = from);
+
+            let _guard = self.write()?;
+            let mut branches: BTreeMap<String, Branch> = self.layout.read(BRANCHES_FILE)?;
+            if branches.contains_key(name) {
+                return Ok(Err(ClientError::BranchExists { name: name.into() }));
+            }
+            if !self.layout.has_version(from) {
+                return Ok(Err(ClientError::UnknownVersion { version: from }));
+            }
+
+            debug!("Creating branch {name:?} from version {from}...");
+            let branch = Branch { name: name.into(), head: Some(from), base: Some(from), created: Utc::now(), creator: recorded(self.user) };
+            branches.insert(name.into(), branch);
+            self.layout.write(BRANCHES_FILE, &branches)?;
+            self.layout.append_audit(&self.user.id, self.client, AuditAction::CreateBranch { name: name.into(), from })?;
+            Ok(Ok(()))
+        }
+    }
+
+    fn promote(&mut self, version: u64, target: &str, force: bool) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::promote", version = version, target = target);
+
+            let _guard = self.write()?;
+            let Some(original) = self.layout.read_version::<StoredVersion<Value>>(version)? else {
+                return Ok(Err(ClientError::UnknownVersion { version }));
+            };
+            if original.metadata.frozen.is_some() {
+                return Ok(Err(ClientError::VersionFrozen { version }));
+            }
+            let source: &str = &original.metadata.attached.branch;
+            if source == target {
+                return Ok(Err(ClientError::PromoteSameBranch { version, branch: target.into() }));
+            }
+            let branches: BTreeMap<String, Branch> = self.layout.read(BRANCHES_FILE)?;
+            let Some(target_branch) = branches.get(target) else { return Ok(Err(ClientError::UnknownBranch { name: target.into() })) };
+
+            // Find where the branches last met; i.e., the latest promotion from the source into the target, or else the fork point
+            debug!("Checking for conflicts between branch {source:?} and {target:?}...");
+            let versions: HashMap<u64, StoredMetadata> = self.layout.read_versions()?;
+            let last_promotion: Option<u64> = versions
+                .values()
+                .filter(|stored| stored.metadata.attached.branch == target)
+                .filter(|stored| {
+                    stored.metadata.promoted_from.and_then(|from| versions.get(&from)).is_some_and(|from| from.metadata.attached.branch == source)
+                })
+                .map(|stored| stored.metadata.version)
+                .max();
+            let base: Option<u64> = last_promotion.or(branches.get(source).and_then(|branch| branch.base));
+            if let Some(head) = target_branch.head {
+                if Some(head) != base {
+                    if force {
+                        info!("Forcing promotion of version {version} into branch {target:?} despite it having advanced to {head}");
+                    } else {
+                        return Ok(Err(ClientError::PromoteConflict { version, source_branch: source.into(), target: target.into(), head, base }));
+                    }
+                }
+            }
+
+            // Append the copy to the target
+            let promoted: u64 = self.layout.next_version()?;
+            debug!("Promoting policy {version} into branch {target:?} as {promoted}...");
+            let metadata = Metadata {
+                attached: AttachedMetadata { branch: target.into(), ..original.metadata.attached.clone() },
+                created: Utc::now(),
+                creator: recorded(self.user),
+                version: promoted,
+                parent: None,
+                promoted_from: Some(version),
+                hash: original.metadata.hash.clone(),
+                origin: original.metadata.origin.clone(),
+                verification: original.metadata.verification.clone(),
+                frozen: None,
+            };
+            self.layout.add(StoredVersion { metadata, content: original.content, client: self.client, request_id: self.request_id.clone() })?;
+            self.layout.append_audit(&self.user.id, self.client, AuditAction::Promote { version, target: target.into(), promoted })?;
+            Ok(Ok(promoted))
+        }
+    }
+
+    fn import_version(
+        &mut self,
+        store: &str,
+        metadata: Metadata,
+        content: Self::Content,
+    ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::import_version", store = store, version = metadata.version);
+
+            // Make sure the content survived the trip
+            let hash: String = hash_content(&metadata.attached.name, &content)?;
+            if let Some(expected) = metadata.hash {
+                if expected != hash {
+                    return Ok(Err(ClientError::HashMismatch { version: metadata.version, expected, actual: hash }));
+                }
+            }
+
+            // Keep the original origin if this version was imported before
+            let source: u64 = metadata.version;
+            let origin: Origin =
+                metadata.origin.unwrap_or(Origin { store: store.into(), version: source, creator: metadata.creator.id, created: metadata.created });
+            // NOTE: Other stores are bound by the same maximum, so they can't have such a version either
+            if origin.version > MAX_VERSION {
+                return Ok(Err(ClientError::UnknownVersion { version: origin.version }));
+            }
+
+            // Append the copy to the same branch as in the other store
+            let _guard = self.write()?;
+            let version: u64 = self.layout.next_version()?;
+            debug!("Importing policy {source} from store {store:?} as {version}...");
+            let action = AuditAction::Import {
+                version,
+                name: metadata.attached.name.clone(),
+                branch: metadata.attached.branch.clone(),
+                store: store.into(),
+                source,
+            };
+            let metadata = Metadata {
+                attached: metadata.attached,
+                created: Utc::now(),
+                creator: recorded(self.user),
+                version,
+                parent: None,
+                promoted_from: None,
+                hash: Some(hash),
+                origin: Some(origin),
+                verification: metadata.verification,
+                frozen: None,
+            };
+            self.layout.add(StoredVersion { metadata, content, client: self.client, request_id: self.request_id.clone() })?;
+            self.layout.append_audit(&self.user.id, self.client, action)?;
+            Ok(Ok(version))
+        }
+    }
+
+    fn add_schema(&mut self, schema: AttachedSchema) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::add_schema", schema = schema.name);
+
+            let _guard = self.write()?;
+            let mut schemas: BTreeMap<String, Schema> = self.layout.read(SCHEMAS_FILE)?;
+            if schemas.contains_key(&schema.name) {
+                return Ok(Err(ClientError::SchemaExists { name: schema.name }));
+            }
+
+            debug!("Adding schema {:?}...", schema.name);
+            let name: String = schema.name.clone();
+            schemas.insert(name.clone(), Schema { attached: schema, created: Utc::now(), creator: recorded(self.user) });
+            self.layout.write(SCHEMAS_FILE, &schemas)?;
+            self.layout.append_audit(&self.user.id, self.client, AuditAction::AddSchema { name })?;
+            Ok(Ok(()))
+        }
+    }
+
+    fn pseudonymize_user(&mut self, id: &str, pseudonym: &str) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            // NOTE: Deliberately not logging the ID itself, as that's what we're trying to get rid of
+            let _span = span!(Level::INFO, "FsConnection::pseudonymize_user", pseudonym = pseudonym);
+
+            // We don't want to launder any tampering by re-hashing, so verify the audit log first
+            let _guard = self.write()?;
+            let mut log: Vec<AuditRecord> = self.layout.read_audit_log()?;
+            if let Err(err) = verify_chain(&log) {
+                return Err(ConnectionError::AuditChain { path: self.layout.root.join(AUDIT_LOG_FILE), err });
+            }
+
+            // First, everything but the audit log; only rewriting files that actually change
+            debug!("Pseudonymizing user in files...");
+            let mut count: u64 = 0;
+            for stored in self.layout.read_versions::<StoredVersion<Value>>()?.values_mut() {
+                // Where they wrote it from is as telling as their ID, so forget it
+                let mut changed: u64 = u64::from(stored.metadata.creator.id == id && stored.client.take().is_some());
+                changed += pseudonymize(&mut stored.metadata.creator.id, id, pseudonym);
+                if let Some(origin) = &mut stored.metadata.origin {
+                    changed += pseudonymize(&mut origin.creator, id, pseudonym);
+                }
+                if let Some(frozen) = &mut stored.metadata.frozen {
+                    changed += pseudonymize(&mut frozen.freezer.id, id, pseudonym);
+                }
+                if changed > 0 {
+                    self.layout.write_version(stored)?;
+                    count += changed;
+                }
+            }
+            let mut templates: BTreeMap<String, Template> = self.layout.read(TEMPLATES_FILE)?;
+            let changed: u64 = templates.values_mut().map(|template| pseudonymize(&mut template.creator.id, id, pseudonym)).sum();
+            if changed > 0 {
+                self.layout.write(TEMPLATES_FILE, &templates)?;
+                count += changed;
+            }
+            let mut branches: BTreeMap<String, Branch> = self.layout.read(BRANCHES_FILE)?;
+            let changed: u64 = branches.values_mut().map(|branch| pseudonymize(&mut branch.creator.id, id, pseudonym)).sum();
+            if changed > 0 {
+                self.layout.write(BRANCHES_FILE, &branches)?;
+                count += changed;
+            }
+            let mut schemas: BTreeMap<String, Schema> = self.layout.read(SCHEMAS_FILE)?;
+            let changed: u64 = schemas.values_mut().map(|schema| pseudonymize(&mut schema.creator.id, id, pseudonym)).sum();
+            if changed > 0 {
+                self.layout.write(SCHEMAS_FILE, &schemas)?;
+                count += changed;
+            }
+            let mut activations: Vec<Activation> = self.layout.read(ACTIVATIONS_FILE)?;
+            let changed: u64 = activations
+                .iter_mut()
+                .map(|activation| {
+                    u64::from(activation.activator.id == id && activation.client.take().is_some())
+                        + pseudonymize(&mut activation.activator.id, id, pseudonym)
+                        + activation.deactivator.as_mut().map(|deactivator| pseudonymize(&mut deactivator.id, id, pseudonym)).unwrap_or(0)
+                })
+                .sum();
+            if changed > 0 {
+                self.layout.write(ACTIVATIONS_FILE, &activations)?;
+                count += changed;
+            }
+            let mut decisions: BTreeMap<String, Decision> = self.layout.read(DECISIONS_FILE)?;
+            let changed: u64 = decisions.values_mut().map(|decision| pseudonymize(&mut decision.reporter.id, id, pseudonym)).sum();
+            if changed > 0 {
+                self.layout.write(DECISIONS_FILE, &decisions)?;
+                count += changed;
+            }
+            let mut keys: BTreeMap<String, Key> = self.layout.read(KEYS_FILE)?;
+            let changed: u64 = keys.values_mut().map(|key| pseudonymize(&mut key.creator.id, id, pseudonym)).sum();
+            if changed > 0 {
+                self.layout.write(KEYS_FILE, &keys)?;
+                count += changed;
+            }
+            let mut revocations: Vec<Revocation> = self.layout.read(REVOCATIONS_FILE)?;
+            let changed: u64 = revocations.iter_mut().map(|revocation| pseudonymize(&mut revocation.creator.id, id, pseudonym)).sum();
+            if changed > 0 {
+                self.layout.write(REVOCATIONS_FILE, &revocations)?;
+                count += changed;
+            }
+
+            // Then the audit log, which is re-hashed from the first affected record onwards
+            debug!("Pseudonymizing user in audit log...");
+            let mut prev_hash: Option<String> = None;
+            let mut rehashed_from: Option<u64> = None;
+            for record in &mut log {
+                if record.user == id {
+                    record.user = pseudonym.into();
+                    record.client = None;
+                    rehashed_from = rehashed_from.or(Some(record.seq));
+                    count += 1;
+                }
+                if record.action.pseudonymize(id, pseudonym) > 0 {
+                    rehashed_from = rehashed_from.or(Some(record.seq));
+                    count += 1;
+                }
+                if rehashed_from.is_some() {
+                    record.prev_hash = prev_hash;
+                    record.hash = record.compute_hash();
+                }
+                prev_hash = Some(record.hash.clone());
+            }
+            if count == 0 {
+                return Ok(Err(ClientError::UnknownUser { id: id.into() }));
+            }
+
+            // Finally, log the pseudonymization itself
+            let record =
+                AuditRecord::new(log.last(), &self.user.id, self.client, AuditAction::Pseudonymize { pseudonym: pseudonym.into(), rehashed_from });
+            log.push(record);
+            self.layout.write_audit_log(&log)?;
+            Ok(Ok(count))
+        }
+    }
+
+    fn add_decision(&mut self, version: u64, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::add_decision", version = version, decision = id);
+
+            // Ensure the version exists and the decision doesn't yet
+            let _guard = self.write()?;
+            if !self.layout.has_version(version) {
+                return Ok(Err(ClientError::UnknownVersion { version }));
+            }
+            let mut decisions: BTreeMap<String, Decision> = self.layout.read(DECISIONS_FILE)?;
+            if decisions.contains_key(id) {
+                return Ok(Err(ClientError::DecisionExists { id: id.into() }));
+            }
+
+            debug!("Adding decision {id:?}...");
+            decisions.insert(id.into(), Decision { id: id.into(), version, recorded: Utc::now(), reporter: recorded(self.user) });
+            self.layout.write(DECISIONS_FILE, &decisions)?;
+            self.layout.append_audit(&self.user.id, self.client, AuditAction::AddDecision { version, id: id.into() })?;
+            Ok(Ok(()))
+        }
+    }
+
+    fn prune_activations(&mut self, before: DateTime<Utc>) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::prune_activations", before = before.to_rfc3339());
+
+            // NOTE: Activations are closed when deactivated or superseded, so only the active one
+            // lacks a deactivation time (and never matches)
+            debug!("Removing activations that ended before {}...", before.to_rfc3339());
+            let _guard = self.write()?;
+            let mut activations: Vec<Activation> = self.layout.read(ACTIVATIONS_FILE)?;
+            let len: usize = activations.len();
+            activations.retain(|activation| activation.deactivated.is_none_or(|deactivated| deactivated >= before));
+            let rows: u64 = (len - activations.len()) as u64;
+            if rows > 0 {
+                self.layout.write(ACTIVATIONS_FILE, &activations)?;
+                self.layout.append_audit(&self.user.id, self.client, AuditAction::PruneActivations { before, rows })?;
+            }
+            Ok(rows)
+        }
+    }
+
+    fn add_key(&mut self, key: AttachedKey) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::add_key", key = key.id);
+
+            // Ensure the key doesn't exist yet (not even revoked)
+            let _guard = self.write()?;
+            let mut keys: BTreeMap<String, Key> = self.layout.read(KEYS_FILE)?;
+            if keys.contains_key(&key.id) {
+                return Ok(Err(ClientError::KeyExists { id: key.id }));
+            }
+
+            debug!("Adding key {:?}...", key.id);
+            let id: String = key.id.clone();
+            keys.insert(id.clone(), Key { attached: key, created: Utc::now(), creator: recorded(self.user), revoked: None });
+            self.layout.write(KEYS_FILE, &keys)?;
+            self.layout.append_audit(&self.user.id, self.client, AuditAction::AddKey { id })?;
+            Ok(Ok(()))
+        }
+    }
+
+    fn revoke_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::revoke_key", key = id);
+
+            let _guard = self.write()?;
+            let mut keys: BTreeMap<String, Key> = self.layout.read(KEYS_FILE)?;
+            let Some(key) = keys.get_mut(id) else { return Ok(Err(ClientError::UnknownKey { id: id.into() })) };
+
+            // Revoke it, unless it already is
+            debug!("Revoking key {id:?}...");
+            if key.revoked.is_none() {
+                key.revoked = Some(Utc::now());
+                self.layout.write(KEYS_FILE, &keys)?;
+                self.layout.append_audit(&self.user.id, self.client, AuditAction::RevokeKey { id: id.into() })?;
+            }
+            Ok(Ok(()))
+        }
+    }
+
+    fn revoke_tokens(&mut self, revocation: AttachedRevocation) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::revoke_tokens");
+
+            let _guard = self.write()?;
+            let mut revocations: Vec<Revocation> = self.layout.read(REVOCATIONS_FILE)?;
+            let action: AuditAction = match &revocation {
+                AttachedRevocation::Token { jti } => {
+                    // Revoke it, unless it already is
+                    if revocations.iter().any(|prev| matches!(&prev.attached, AttachedRevocation::Token { jti: revoked } if revoked == jti)) {
+                        debug!("Token {jti:?} was already revoked");
+                        return Ok(());
+                    }
+                    debug!("Revoking token {jti:?}...");
+                    AuditAction::RevokeToken { jti: jti.clone() }
+                },
+                AttachedRevocation::User { user, before } => {
+                    // Only keep the latest revocation per user
+                    let prev: Option<usize> = revocations
+                        .iter()
+                        .position(|prev| matches!(&prev.attached, AttachedRevocation::User { user: revoked, .. } if revoked == user));
+                    if let Some(prev) = prev {
+                        if matches!(revocations[prev].attached, AttachedRevocation::User { before: revoked, .. } if revoked >= *before) {
+                            debug!("Tokens of user {user:?} were already revoked before {before}");
+                            return Ok(());
+                        }
+                        revocations.remove(prev);
+                    }
+                    debug!("Revoking tokens of user {user:?} issued before {before}...");
+                    AuditAction::RevokeUserTokens { user: user.clone(), before: *before }
+                },
+            };
+            revocations.push(Revocation { attached: revocation, created: Utc::now(), creator: recorded(self.user) });
+            self.layout.write(REVOCATIONS_FILE, &revocations)?;
+            self.layout.append_audit(&self.user.id, self.client, action)
+        }
+    }
+
+    fn ack_events(&mut self, sink: &str, seq: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::ack_events", sink = sink, seq = seq);
+
+            // Only ever move the cursor forward
+            let _guard = self.write()?;
+            let mut cursors: BTreeMap<String, u64> = self.layout.read(EVENT_CURSORS_FILE)?;
+            if let Some(prev) = cursors.get(sink).filter(|prev| **prev >= seq) {
+                debug!("Sink {sink:?} already acknowledged events up to {prev}");
+                return Ok(());
+            }
+
+            debug!("Acknowledging events up to {seq} for sink {sink:?}...");
+            cursors.insert(sink.into(), seq);
+            self.layout.write(EVENT_CURSORS_FILE, &cursors)
+        }
+    }
+
+    // Immutable
+    fn get_versions(&mut self) -> impl Send + Future<Output = Result<HashMap<u64, Metadata>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_versions");
+
+            debug!("Retrieving all policy versions...");
+            let _guard = self.read()?;
+            Ok(self.layout.read_versions::<StoredMetadata>()?.into_iter().map(|(version, stored)| (version, stored.metadata)).collect())
+        }
+    }
+
+    fn get_active_version(&mut self) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_active");
+
+            debug!("Fetching active version...");
+            let _guard = self.read()?;
+            self.layout.active()
+        }
+    }
+
+    fn get_active_version_at(&mut self, at: DateTime<Utc>) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_active_version_at", at = at.to_rfc3339());
+
+            // Find the last activation before the given time, and see if it was still active then
+            debug!("Fetching version active at {at}...");
+            let _guard = self.read()?;
+            let activations: Vec<Activation> = self.layout.read(ACTIVATIONS_FILE)?;
+            Ok(activations
+                .into_iter()
+                .filter(|activation| activation.activated <= at)
+                .max_by_key(|activation| activation.activated)
+                .filter(|activation| activation.deactivated.is_none_or(|deactivated| deactivated > at))
+                .map(|activation| activation.version))
+        }
+    }
+
+    fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_active");
+
+            let _guard = self.read()?;
+            Ok(self.layout.current_activation()?.map(|activation| activation.activator))
+        }
+    }
+
+    fn get_activation(&mut self) -> impl Send + Future<Output = Result<Option<Activation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_activation");
+
+            let _guard = self.read()?;
+            self.layout.current_activation()
+        }
+    }
+
+    fn get_activations(&mut self) -> impl Send + Future<Output = Result<Vec<Activation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_activations");
+
+            debug!("Retrieving activation history...");
+            let _guard = self.read()?;
+            let mut activations: Vec<Activation> = self.layout.read(ACTIVATIONS_FILE)?;
+            activations.sort_by_key(|activation| activation.activated);
+            Ok(activations)
+        }
+    }
+
+    fn get_version_metadata(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Metadata>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_version_metadata", version = version);
+
+            debug!("Retrieving metadata for version {version}...");
+            let _guard = self.read()?;
+            Ok(self.layout.read_version::<StoredMetadata>(version)?.map(|stored| stored.metadata))
+        }
+    }
+
+    fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_version_content", version = version);
+
+            debug!("Retrieving content for version {version}...");
+            let _guard = self.read()?;
+            Ok(self.layout.read_version::<StoredVersion<C>>(version)?.map(|stored| stored.content))
+        }
+    }
+
+    fn get_version_provenance(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Provenance>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_version_provenance", version = version);
+
+            debug!("Retrieving provenance for version {version}...");
+            let _guard = self.read()?;
+            Ok(self.layout.read_version::<StoredMetadata>(version)?.map(|stored| Provenance::new(&stored.metadata, stored.client, stored.request_id)))
+        }
+    }
+
+    fn get_templates(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Template>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_templates");
+
+            debug!("Retrieving all templates...");
+            let _guard = self.read()?;
+            Ok(self.layout.read::<BTreeMap<String, Template>>(TEMPLATES_FILE)?.into_iter().collect())
+        }
+    }
+
+    fn get_template(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Template>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_template", template = name);
+
+            debug!("Retrieving template {name:?}...");
+            let _guard = self.read()?;
+            Ok(self.layout.read::<BTreeMap<String, Template>>(TEMPLATES_FILE)?.remove(name))
+        }
+    }
+
+    fn get_branches(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Branch>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_branches");
+
+            debug!("Retrieving all branches...");
+            let _guard = self.read()?;
+            Ok(self.layout.read::<BTreeMap<String, Branch>>(BRANCHES_FILE)?.into_iter().collect())
+        }
+    }
+
+    fn get_schemas(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Schema>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_schemas");
+
+            debug!("Retrieving all schemas...");
+            let _guard = self.read()?;
+            Ok(self.layout.read::<BTreeMap<String, Schema>>(SCHEMAS_FILE)?.into_iter().collect())
+        }
+    }
+
+    fn get_schema(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Schema>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_schema", schema = name);
+
+            debug!("Retrieving schema {name:?}...");
+            let _guard = self.read()?;
+            Ok(self.layout.read::<BTreeMap<String, Schema>>(SCHEMAS_FILE)?.remove(name))
+        }
+    }
+
+    fn get_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Vec<Decision>>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_decisions", version = version);
+
+            let _guard = self.read()?;
+            if !self.layout.has_version(version) {
+                return Ok(None);
+            }
+            debug!("Retrieving decisions taken under version {version}...");
+            let decisions: BTreeMap<String, Decision> = self.layout.read(DECISIONS_FILE)?;
+            let mut decisions: Vec<Decision> = decisions.into_values().filter(|decision| decision.version == version).collect();
+            decisions.sort_by_key(|decision| decision.recorded);
+            Ok(Some(decisions))
+        }
+    }
+
+    fn count_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::count_decisions", version = version);
+
+            let _guard = self.read()?;
+            if !self.layout.has_version(version) {
+                return Ok(None);
+            }
+            debug!("Counting decisions taken under version {version}...");
+            let decisions: BTreeMap<String, Decision> = self.layout.read(DECISIONS_FILE)?;
+            Ok(Some(decisions.values().filter(|decision| decision.version == version).count() as u64))
+        }
+    }
+
+    fn get_decision(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Decision>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_decision", decision = id);
+
+            debug!("Retrieving decision {id:?}...");
+            let _guard = self.read()?;
+            Ok(self.layout.read::<BTreeMap<String, Decision>>(DECISIONS_FILE)?.remove(id))
+        }
+    }
+
+    fn get_keys(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Key>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_keys");
+
+            debug!("Retrieving all keys...");
+            let _guard = self.read()?;
+            Ok(self.layout.read::<BTreeMap<String, Key>>(KEYS_FILE)?.into_iter().collect())
+        }
+    }
+
+    fn get_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Key>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_key", key = id);
+
+            debug!("Retrieving key {id:?}...");
+            let _guard = self.read()?;
+            Ok(self.layout.read::<BTreeMap<String, Key>>(KEYS_FILE)?.remove(id))
+        }
+    }
+
+    fn get_revocations(&mut self) -> impl Send + Future<Output = Result<Vec<Revocation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_revocations");
+
+            debug!("Retrieving all token revocations...");
+            let _guard = self.read()?;
+            self.layout.read(REVOCATIONS_FILE)
+        }
+    }
+
+    fn is_revoked(&mut self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::is_revoked", user = user);
+
+            debug!("Checking whether token {jti:?} of user {user:?} is revoked...");
+            let _guard = self.read()?;
+            let revocations: Vec<Revocation> = self.layout.read(REVOCATIONS_FILE)?;
+            Ok(revocations.iter().any(|revocation| match &revocation.attached {
+                AttachedRevocation::Token { jti: revoked } => jti == Some(revoked.as_str()),
+                AttachedRevocation::User { user: revoked, before } => revoked == user && issued.is_none_or(|issued| issued < *before),
+            }))
+        }
+    }
+
+    fn get_audit_log(&mut self, since: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<Vec<AuditRecord>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_audit_log");
+
+            debug!("Retrieving audit log...");
+            let _guard = self.read()?;
+            let mut log: Vec<AuditRecord> = self.layout.read_audit_log()?;
+            log.retain(|record| since.is_none_or(|since| record.timestamp >= since));
+            Ok(log)
+        }
+    }
+
+    fn get_changes(&mut self, since: u64, limit: u64) -> impl Send + Future<Output = Result<Vec<Change<Self::Content>>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_changes", since = since, limit = limit);
+
+            // Attach any versions created
+            debug!("Retrieving changes since {since}...");
+            let _guard = self.read()?;
+            let mut changes: Vec<Change<C>> = Vec::new();
+            for record in
+                self.layout.read_audit_log()?.into_iter().filter(|record| record.seq > since).take(usize::try_from(limit).unwrap_or(usize::MAX))
+            {
+                let stored: Option<StoredVersion<C>> = match record.action.created_version() {
+                    Some(version) => self.layout.read_version(version)?,
+                    None => None,
+                };
+                let (metadata, content): (Option<Metadata>, Option<C>) = match stored {
+                    Some(stored) => (Some(stored.metadata), Some(stored.content)),
+                    None => (None, None),
+                };
+                changes.push(Change { record, metadata, content, added: None });
+            }
+            Ok(changes)
+        }
+    }
+
+    fn get_event_cursor(&mut self, sink: &str) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "FsConnection::get_event_cursor", sink = sink);
+
+            debug!("Retrieving event cursor of sink {sink:?}...");
+            let _guard = self.read()?;
+            let cursors: BTreeMap<String, u64> = self.layout.read(EVENT_CURSORS_FILE)?;
+            Ok(cursors.get(sink).copied().unwrap_or(0))
+        }
+    }
+}
@@ -0,0 +1,22 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:40:00
+//  Last edited:
+//    16 Oct 2026, 18:40:00
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the `DatabaseConnector` for a backend that stores every
+//!   policy version as a JSON file in a directory tree. Useful for
+//!   air-gapped or GitOps-style deployments where policies live on a
+//!   mounted volume.
+//
+
+// Declare modules
+mod databaseconn;
+
+// Import some of it
+pub use databaseconn::*;
@@ -0,0 +1,168 @@
+//  VERSIONS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:40:00
+//  Last edited:
+//    16 Oct 2026, 19:34:36
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the filesystem connector stores, activates and promotes
+//!   policy versions, and what it leaves on disk.
+//
+
+use std::path::PathBuf;
+
+use chrono::{Duration, Utc};
+use fs_database::{ConnectionError, FsDatabase};
+use specifications::DatabaseConnector as _;
+use specifications::audit::{AuditRecord, verify_chain};
+use specifications::consistency::Finding;
+use specifications::databaseconn::{ClientError, DatabaseConnection as _};
+use specifications::metadata::User;
+use specifications::testing::{metadata, metadata_on};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates an empty directory to store policies in.
+///
+/// # Arguments
+/// - `name`: A name for the directory that is unique among the tests.
+///
+/// # Returns
+/// The path to the (not yet existing) directory.
+fn tempdir(name: &str) -> PathBuf {
+    let path: PathBuf = std::env::temp_dir().join(format!("policy-store-fs-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    path
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_version_lifecycle() {
+    let path: PathBuf = tempdir("lifecycle");
+    let db: FsDatabase<String> = FsDatabase::new(&path).unwrap();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    // Versions are numbered in order, and chained on their branch
+    let first: u64 = conn.add_version(metadata(), "allow".into()).await.unwrap();
+    let second: u64 = conn.add_version(metadata(), "deny".into()).await.unwrap();
+    assert_eq!((first, second), (1, 2));
+    assert_eq!(conn.get_version_metadata(second).await.unwrap().unwrap().parent, Some(first));
+    assert_eq!(conn.get_version_content(first).await.unwrap(), Some("allow".into()));
+    assert_eq!(conn.get_branches().await.unwrap()["main"].head, Some(second));
+
+    // Only existing versions can be activated
+    assert!(matches!(conn.activate(42).await, Err(ConnectionError::UnknownVersion { version: 42 })));
+    conn.activate(first).await.unwrap();
+    conn.activate(second).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(second));
+    conn.deactivate().await.unwrap();
+    conn.deactivate().await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), None);
+    assert_eq!(conn.get_activations().await.unwrap().iter().map(|activation| activation.version).collect::<Vec<u64>>(), vec![first, second]);
+
+    // Everything but the no-op deactivation is audited
+    let log: Vec<AuditRecord> = conn.get_audit_log(None).await.unwrap();
+    let kinds: Vec<&str> = log.iter().map(|record| record.action.kind()).collect();
+    assert_eq!(kinds, vec!["add_version", "add_version", "activate", "activate", "deactivate"]);
+    assert_eq!(verify_chain(&log).unwrap(), 5);
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn test_prune_superseded() {
+    let path: PathBuf = tempdir("prune_superseded");
+    let db: FsDatabase<String> = FsDatabase::new(&path).unwrap();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let first: u64 = conn.add_version(metadata(), "allow".into()).await.unwrap();
+    let second: u64 = conn.add_version(metadata(), "deny".into()).await.unwrap();
+
+    // Activating another version closes the activation it supersedes, such that it can be pruned
+    conn.activate(first).await.unwrap();
+    conn.activate(second).await.unwrap();
+    let activations = conn.get_activations().await.unwrap();
+    assert_eq!(activations[0].deactivated, Some(activations[1].activated));
+    assert_eq!(activations[1].deactivated, None);
+    assert_eq!(conn.prune_activations(Utc::now() + Duration::seconds(1)).await.unwrap(), 1);
+    assert_eq!(conn.get_activations().await.unwrap().iter().map(|activation| activation.version).collect::<Vec<u64>>(), vec![second]);
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(second));
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn test_layout_on_disk() {
+    let path: PathBuf = tempdir("layout");
+    let db: FsDatabase<String> = FsDatabase::new(&path).unwrap();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let version: u64 = db.connect(&user).await.unwrap().add_version(metadata(), "allow".into()).await.unwrap();
+    db.connect(&user).await.unwrap().activate(version).await.unwrap();
+
+    // Every version has its own file, and the active one is marked
+    let raw: String = std::fs::read_to_string(path.join("versions").join(format!("{version}.json"))).unwrap();
+    let stored: serde_json::Value = serde_json::from_str(&raw).unwrap();
+    assert_eq!(stored["content"], "allow");
+    assert_eq!(std::fs::read_to_string(path.join("active")).unwrap().trim(), version.to_string());
+
+    // Which survives reopening the directory
+    drop(db);
+    let db: FsDatabase<String> = FsDatabase::new(&path).unwrap();
+    let mut conn = db.connect(&user).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(version));
+    assert_eq!(conn.get_activator().await.unwrap().map(|user| user.id), Some("tester".into()));
+    assert_eq!(conn.add_version(metadata(), "deny".into()).await.unwrap(), version + 1);
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn test_promote_conflict() {
+    let path: PathBuf = tempdir("promote");
+    let db: FsDatabase<String> = FsDatabase::new(&path).unwrap();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let base: u64 = conn.add_version(metadata(), "allow".into()).await.unwrap();
+    conn.create_branch("staging", base).await.unwrap().unwrap();
+    let staged: u64 = conn.add_version(metadata_on("staging"), "deny".into()).await.unwrap();
+
+    // Promoting into an untouched branch works, and copies the content
+    let promoted: u64 = conn.promote(staged, "main", false).await.unwrap().unwrap();
+    assert_eq!(conn.get_version_metadata(promoted).await.unwrap().unwrap().promoted_from, Some(staged));
+    assert_eq!(conn.get_version_content(promoted).await.unwrap(), Some("deny".into()));
+
+    // But not once the target has advanced since, unless forced
+    let hotfix: u64 = conn.add_version(metadata(), "allow".into()).await.unwrap();
+    let staged: u64 = conn.add_version(metadata_on("staging"), "deny".into()).await.unwrap();
+    match conn.promote(staged, "main", false).await.unwrap() {
+        Err(ClientError::PromoteConflict { head, base, .. }) => assert_eq!((head, base), (hotfix, Some(promoted))),
+        res => panic!("expected a conflict, got {res:?}"),
+    }
+    conn.promote(staged, "main", true).await.unwrap().unwrap();
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn test_verify_finds_edits() {
+    let path: PathBuf = tempdir("verify");
+    let db: FsDatabase<String> = FsDatabase::new(&path).unwrap();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let version: u64 = db.connect(&user).await.unwrap().add_version(metadata(), "allow".into()).await.unwrap();
+    assert!(db.verify().await.unwrap().is_empty());
+
+    // Editing the content by hand breaks its hash, and pointing the marker elsewhere dangles
+    let file: PathBuf = path.join("versions").join(format!("{version}.json"));
+    let mut stored: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+    stored["content"] = "deny".into();
+    std::fs::write(&file, serde_json::to_string(&stored).unwrap()).unwrap();
+    std::fs::write(path.join("active"), "42\n").unwrap();
+    let findings: Vec<Finding> = db.verify().await.unwrap();
+    assert!(matches!(findings.as_slice(), [Finding::HashMismatch { version: 1, .. }, Finding::DanglingActive { version: 42 }]));
+    let _ = std::fs::remove_dir_all(&path);
+}
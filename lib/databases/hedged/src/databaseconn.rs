@@ -0,0 +1,447 @@
+//  DATABASECONN.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:25:22
+//  Last edited:
+//    16 Oct 2026, 19:26:32
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the [`DatabaseConnector`] that hedges reads from a
+//!   primary to a secondary backend.
+//
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use specifications::DatabaseConnector;
+use specifications::audit::{AuditRecord, Change};
+use specifications::consistency::Finding;
+use specifications::databaseconn::{ClientError, DatabaseConnection};
+use specifications::decision::Decision;
+use specifications::key::{AttachedKey, Key};
+use specifications::metadata::{AccessContext, Activation, AttachedMetadata, Branch, Metadata, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::schema::{AttachedSchema, Schema};
+use specifications::template::{AttachedTemplate, Template};
+use thiserror::Error;
+use tracing::debug;
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the [`HedgedConnection`].
+#[derive(Debug, Error)]
+pub enum HedgeError<E, F> {
+    /// The primary backend failed on a call that is never hedged.
+    #[error("Primary backend failed")]
+    Primary {
+        #[source]
+        err: E,
+    },
+    /// Both the primary and the secondary backend failed on a hedged call.
+    #[error("Both the primary and the secondary backend failed (secondary: {secondary})")]
+    Both {
+        #[source]
+        err: E,
+        secondary: F,
+    },
+}
+
+/// Defines errors originating from the secondary backend of a [`HedgedConnection`].
+#[derive(Debug, Error)]
+pub enum SecondaryError<C, E> {
+    /// Failed to connect to the secondary backend.
+    #[error("Failed to connect to secondary backend")]
+    Connect {
+        #[source]
+        err: C,
+    },
+    /// The secondary backend failed.
+    #[error("Secondary backend failed")]
+    Backend {
+        #[source]
+        err: E,
+    },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Forwards a call that is never hedged to the primary backend.
+///
+/// # Arguments
+/// - `call`: The call to the primary backend.
+///
+/// # Returns
+/// Whatever the `call` returns.
+///
+/// # Errors
+/// This function errors if the `call` does.
+async fn primary<T, E, F>(call: impl Future<Output = Result<T, E>>) -> Result<T, HedgeError<E, F>> {
+    call.await.map_err(|err| HedgeError::Primary { err })
+}
+
+/// Hedges a call to the primary backend to the secondary one.
+///
+/// The `primary` gets a head start of `delay`. If it hasn't answered by then, the `secondary` is
+/// started as well, and whichever answers successfully first wins; the other is dropped. If the
+/// `primary` fails during its head start, the `secondary` is tried straight away.
+///
+/// # Arguments
+/// - `delay`: How long to give the `primary` before also trying the `secondary`.
+/// - `primary`: The call to the primary backend.
+/// - `secondary`: The same call to the secondary backend. It is only polled if needed.
+///
+/// # Returns
+/// Whatever the first successful call returns.
+///
+/// # Errors
+/// This function errors if both calls do.
+async fn hedge<T, E, F>(
+    delay: Duration,
+    primary: impl Future<Output = Result<T, E>>,
+    secondary: impl Future<Output = Result<T, F>>,
+) -> Result<T, HedgeError<E, F>> {
+    tokio::pin!(primary);
+    tokio::pin!(secondary);
+
+    // Give the primary its head start
+    match tokio::time::timeout(delay, &mut primary).await {
+        Ok(Ok(res)) => return Ok(res),
+        Ok(Err(err)) => {
+            debug!("Primary backend failed; falling back to secondary");
+            return secondary.await.map_err(|secondary| HedgeError::Both { err, secondary });
+        },
+        Err(_) => debug!("Primary backend did not answer within {delay:?}; hedging to secondary"),
+    }
+
+    // Then race them
+    // NOTE: Dropping the loser halfway is fine, as connections must be cancellation-safe
+    tokio::select! {
+        res = &mut primary => match res {
+            Ok(res) => Ok(res),
+            Err(err) => secondary.await.map_err(|secondary| HedgeError::Both { err, secondary }),
+        },
+        res = &mut secondary => match res {
+            Ok(res) => Ok(res),
+            Err(secondary) => primary.await.map_err(|err| HedgeError::Both { err, secondary }),
+        },
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Remembers on whose behalf a [`HedgedConnection`] was made, such that it can connect to the
+/// secondary backend the same way when it needs to.
+#[derive(Clone, Copy, Debug)]
+enum Access<'s> {
+    /// Connected through [`DatabaseConnector::connect()`] or
+    /// [`DatabaseConnector::connect_from()`].
+    User { user: &'s User, client: Option<IpAddr> },
+    /// Connected through [`DatabaseConnector::connect_with()`].
+    Context(&'s AccessContext),
+}
+
+/// The secondary side of a [`HedgedConnection`], which only connects once first needed.
+struct Secondary<'s, S: 's + DatabaseConnector> {
+    /// The connector to connect with.
+    connector: &'s S,
+    /// On whose behalf to connect.
+    access:    Access<'s>,
+    /// The connection, once made.
+    conn:      Option<S::Connection<'s>>,
+}
+impl<'s, S: 's + DatabaseConnector> Secondary<'s, S> {
+    /// Returns the connection to the secondary backend, connecting first if needed.
+    ///
+    /// # Returns
+    /// The [`DatabaseConnector::Connection`] to the secondary backend.
+    ///
+    /// # Errors
+    /// This function errors if we failed to connect.
+    async fn connection(&mut self) -> Result<&mut S::Connection<'s>, SecondaryError<S::Error, <S::Connection<'s> as DatabaseConnection>::Error>> {
+        if self.conn.is_none() {
+            debug!("Connecting to secondary backend...");
+            let conn: S::Connection<'s> = match self.access {
+                Access::User { user, client } => self.connector.connect_from(user, client).await,
+                Access::Context(context) => self.connector.connect_with(context).await,
+            }
+            .map_err(|err| SecondaryError::Connect { err })?;
+            self.conn = Some(conn);
+        }
+        Ok(self.conn.as_mut().unwrap())
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Combines a primary and a secondary [`DatabaseConnector`], hedging the hot reads of the reasoner
+/// to the secondary when the primary is slow to answer.
+///
+/// Specifically, [`get_active_version()`](DatabaseConnection::get_active_version()),
+/// [`get_version_metadata()`](DatabaseConnection::get_version_metadata()) and
+/// [`get_version_content()`](DatabaseConnection::get_version_content()) are sent to the secondary
+/// if the primary hasn't answered within a configured delay (or has failed), and whichever answers
+/// first is used. This cuts off the tail latency of the primary at the cost of some extra load on
+/// the secondary. Everything else, including all writes, only ever goes to the primary.
+///
+/// Note that the secondary is expected to be a replica of the primary, e.g., kept in sync through
+/// the changes feed. As such, hedged reads may return slightly stale answers while it catches up.
+#[derive(Clone, Debug)]
+pub struct HedgedDatabase<P, S> {
+    /// The connector that is authoritative.
+    primary:   P,
+    /// The connector to hedge reads to.
+    secondary: S,
+    /// How long to wait for the primary before hedging.
+    delay:     Duration,
+}
+impl<P, S> HedgedDatabase<P, S> {
+    /// Constructor for the HedgedDatabase.
+    ///
+    /// # Arguments
+    /// - `primary`: The [`DatabaseConnector`] that is authoritative.
+    /// - `secondary`: A [`DatabaseConnector`] to a replica of the `primary`.
+    /// - `delay`: How long to wait for the `primary` before hedging a read to the `secondary`.
+    ///   Typically somewhere around the 95th percentile of the `primary`'s latency.
+    ///
+    /// # Returns
+    /// A new HedgedDatabase.
+    #[inline]
+    pub fn new(primary: P, secondary: S, delay: Duration) -> Self { Self { primary, secondary, delay } }
+
+    /// Returns the primary connector.
+    ///
+    /// # Returns
+    /// A reference to the primary [`DatabaseConnector`].
+    #[inline]
+    pub fn primary(&self) -> &P { &self.primary }
+
+    /// Returns the secondary connector.
+    ///
+    /// # Returns
+    /// A reference to the secondary [`DatabaseConnector`].
+    #[inline]
+    pub fn secondary(&self) -> &S { &self.secondary }
+}
+impl<P, S> DatabaseConnector for HedgedDatabase<P, S>
+where
+    P: Sync + DatabaseConnector,
+    P::Content: Send,
+    S: Sync + DatabaseConnector<Content = P::Content>,
+    S::Error: 'static + Send,
+    for<'s> P::Connection<'s>: Send,
+    for<'s> <P::Connection<'s> as DatabaseConnection>::Error: 'static + Send,
+    for<'s> S::Connection<'s>: Send,
+    for<'s> <S::Connection<'s> as DatabaseConnection>::Error: 'static + Send,
+{
+    type Connection<'s>
+        = HedgedConnection<'s, P::Connection<'s>, S>
+    where
+        Self: 's;
+    type Content = P::Content;
+    type Error = P::Error;
+
+    #[inline]
+    fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        self.connect_from(user, None)
+    }
+
+    /// Only connects to the primary; the secondary is connected to once a read is first hedged.
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            let primary: P::Connection<'s> = self.primary.connect_from(user, client).await?;
+            Ok(HedgedConnection {
+                primary,
+                secondary: Secondary { connector: &self.secondary, access: Access::User { user, client }, conn: None },
+                delay: self.delay,
+            })
+        }
+    }
+
+    /// Only connects to the primary; the secondary is connected to once a read is first hedged.
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            let primary: P::Connection<'s> = self.primary.connect_with(context).await?;
+            Ok(HedgedConnection {
+                primary,
+                secondary: Secondary { connector: &self.secondary, access: Access::Context(context), conn: None },
+                delay: self.delay,
+            })
+        }
+    }
+
+    /// Only maintains the primary, as the secondary is assumed to be maintained by whoever keeps
+    /// it in sync.
+    #[inline]
+    fn maintain(&self) -> impl Send + Future<Output = Result<(), Self::Error>> { self.primary.maintain() }
+
+    /// Only verifies the primary, as that is the one that is authoritative.
+    #[inline]
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> { self.primary.verify() }
+}
+
+
+
+/// A connection to the backends of a [`HedgedDatabase`].
+pub struct HedgedConnection<'s, C, S: 's + DatabaseConnector> {
+    /// The connection to the primary backend.
+    primary:   C,
+    /// The (lazy) connection to the secondary backend.
+    secondary: Secondary<'s, S>,
+    /// How long to wait for the primary before hedging.
+    delay:     Duration,
+}
+impl<'s, C, S> DatabaseConnection for HedgedConnection<'s, C, S>
+where
+    C: Send + DatabaseConnection,
+    C::Content: Send,
+    C::Error: 'static + Send,
+    S: Sync + DatabaseConnector<Content = C::Content>,
+    S::Error: 'static + Send,
+    for<'a> S::Connection<'a>: Send,
+    for<'a> <S::Connection<'a> as DatabaseConnection>::Error: 'static + Send,
+{
+    type Content = C::Content;
+    type Error = HedgeError<C::Error, SecondaryError<S::Error, <S::Connection<'s> as DatabaseConnection>::Error>>;
+
+    fn add_version(&mut self, metadata: AttachedMetadata, content: Self::Content) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        primary(self.primary.add_version(metadata, content))
+    }
+    fn activate(&mut self, version: u64) -> impl Send + Future<Output = Result<(), Self::Error>> { primary(self.primary.activate(version)) }
+    fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> { primary(self.primary.deactivate()) }
+    fn freeze_version(&mut self, version: u64, reason: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        primary(self.primary.freeze_version(version, reason))
+    }
+    fn unfreeze_version(&mut self, version: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        primary(self.primary.unfreeze_version(version))
+    }
+    fn add_template(&mut self, template: AttachedTemplate) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        primary(self.primary.add_template(template))
+    }
+    fn create_branch(&mut self, name: &str, from: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        primary(self.primary.create_branch(name, from))
+    }
+    fn promote(&mut self, version: u64, target: &str, force: bool) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        primary(self.primary.promote(version, target, force))
+    }
+    fn import_version(
+        &mut self,
+        store: &str,
+        metadata: Metadata,
+        content: Self::Content,
+    ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        primary(self.primary.import_version(store, metadata, content))
+    }
+    fn add_schema(&mut self, schema: AttachedSchema) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        primary(self.primary.add_schema(schema))
+    }
+    fn pseudonymize_user(&mut self, id: &str, pseudonym: &str) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        primary(self.primary.pseudonymize_user(id, pseudonym))
+    }
+    fn add_decision(&mut self, version: u64, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        primary(self.primary.add_decision(version, id))
+    }
+    fn prune_activations(&mut self, before: DateTime<Utc>) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        primary(self.primary.prune_activations(before))
+    }
+    fn add_key(&mut self, key: AttachedKey) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        primary(self.primary.add_key(key))
+    }
+    fn revoke_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        primary(self.primary.revoke_key(id))
+    }
+    fn revoke_tokens(&mut self, revocation: AttachedRevocation) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        primary(self.primary.revoke_tokens(revocation))
+    }
+    fn ack_events(&mut self, sink: &str, seq: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        primary(self.primary.ack_events(sink, seq))
+    }
+
+    fn get_versions(&mut self) -> impl Send + Future<Output = Result<HashMap<u64, Metadata>, Self::Error>> { primary(self.primary.get_versions()) }
+    fn get_active_version(&mut self) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        let secondary: &mut Secondary<'s, S> = &mut self.secondary;
+        hedge(self.delay, self.primary.get_active_version(), async move {
+            match secondary.connection().await {
+                Ok(conn) => conn.get_active_version().await.map_err(|err| SecondaryError::Backend { err }),
+                Err(err) => Err(err),
+            }
+        })
+    }
+    fn get_active_version_at(&mut self, at: DateTime<Utc>) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        primary(self.primary.get_active_version_at(at))
+    }
+    fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>> { primary(self.primary.get_activator()) }
+    fn get_activation(&mut self) -> impl Send + Future<Output = Result<Option<Activation>, Self::Error>> { primary(self.primary.get_activation()) }
+    fn get_activations(&mut self) -> impl Send + Future<Output = Result<Vec<Activation>, Self::Error>> { primary(self.primary.get_activations()) }
+    fn get_version_metadata(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Metadata>, Self::Error>> {
+        let secondary: &mut Secondary<'s, S> = &mut self.secondary;
+        hedge(self.delay, self.primary.get_version_metadata(version), async move {
+            match secondary.connection().await {
+                Ok(conn) => conn.get_version_metadata(version).await.map_err(|err| SecondaryError::Backend { err }),
+                Err(err) => Err(err),
+            }
+        })
+    }
+    fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>> {
+        let secondary: &mut Secondary<'s, S> = &mut self.secondary;
+        hedge(self.delay, self.primary.get_version_content(version), async move {
+            match secondary.connection().await {
+                Ok(conn) => conn.get_version_content(version).await.map_err(|err| SecondaryError::Backend { err }),
+                Err(err) => Err(err),
+            }
+        })
+    }
+    fn get_version_provenance(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Provenance>, Self::Error>> {
+        primary(self.primary.get_version_provenance(version))
+    }
+    fn get_templates(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Template>, Self::Error>> {
+        primary(self.primary.get_templates())
+    }
+    fn get_template(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Template>, Self::Error>> {
+        primary(self.primary.get_template(name))
+    }
+    fn get_branches(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Branch>, Self::Error>> { primary(self.primary.get_branches()) }
+    fn get_schemas(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Schema>, Self::Error>> { primary(self.primary.get_schemas()) }
+    fn get_schema(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Schema>, Self::Error>> {
+        primary(self.primary.get_schema(name))
+    }
+    fn get_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Vec<Decision>>, Self::Error>> {
+        primary(self.primary.get_decisions(version))
+    }
+    fn count_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        primary(self.primary.count_decisions(version))
+    }
+    fn get_decision(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Decision>, Self::Error>> {
+        primary(self.primary.get_decision(id))
+    }
+    fn get_keys(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Key>, Self::Error>> { primary(self.primary.get_keys()) }
+    fn get_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Key>, Self::Error>> { primary(self.primary.get_key(id)) }
+    fn get_revocations(&mut self) -> impl Send + Future<Output = Result<Vec<Revocation>, Self::Error>> { primary(self.primary.get_revocations()) }
+    fn is_revoked(&mut self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        primary(self.primary.is_revoked(jti, user, issued))
+    }
+    fn get_audit_log(&mut self, since: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<Vec<AuditRecord>, Self::Error>> {
+        primary(self.primary.get_audit_log(since))
+    }
+    fn get_changes(&mut self, since: u64, limit: u64) -> impl Send + Future<Output = Result<Vec<Change<Self::Content>>, Self::Error>> {
+        primary(self.primary.get_changes(since, limit))
+    }
+    fn get_event_cursor(&mut self, sink: &str) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        primary(self.primary.get_event_cursor(sink))
+    }
+}
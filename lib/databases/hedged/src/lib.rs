@@ -0,0 +1,22 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:25:22
+//  Last edited:
+//    16 Oct 2026, 17:25:22
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a [`DatabaseConnector`](specifications::DatabaseConnector)
+//!   that sends writes to a primary backend, but hedges the hot reads of
+//!   the reasoner to a secondary (replica) backend if the primary is slow
+//!   to answer.
+//
+
+// Declare modules
+mod databaseconn;
+
+// Import some of it
+pub use databaseconn::*;
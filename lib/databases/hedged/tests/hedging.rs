@@ -0,0 +1,92 @@
+//  HEDGING.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:25:22
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests that hot reads are hedged to the secondary backend when the
+//!   primary is slow or failing, and that writes never are.
+//
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use fault_injection_database::FaultyDatabase;
+use hedged_database::HedgedDatabase;
+use specifications::DatabaseConnector as _;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::User;
+use specifications::testing::metadata;
+use sqlite_database::{SQLiteDatabase, testing};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates a fresh database file for a test, with some versions in it of which the last is active.
+///
+/// # Arguments
+/// - `name`: A name for the database, used to make the file unique.
+/// - `versions`: The number of versions to add.
+///
+/// # Returns
+/// A connector to the new database, and the path to its file.
+async fn fresh_database(name: &str, versions: usize) -> (SQLiteDatabase<bool>, PathBuf) {
+    let (db, path): (SQLiteDatabase<bool>, PathBuf) = testing::fresh_database(name).await;
+
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    for _ in 0..versions {
+        let version: u64 = conn.add_version(metadata(), true).await.unwrap();
+        conn.activate(version).await.unwrap();
+    }
+    drop(conn);
+    (db, path)
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_hedged_reads() {
+    // NOTE: The secondary is "ahead" of the primary, so we can tell who answered
+    let (primary, primary_path) = fresh_database("hedging-primary", 1).await;
+    let (secondary, secondary_path) = fresh_database("hedging-secondary", 2).await;
+    let db = HedgedDatabase::new(FaultyDatabase::new(primary), secondary, Duration::from_millis(20));
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    // A healthy primary answers itself
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(1));
+
+    // A slow primary is overtaken by the secondary
+    db.primary().faults().set_latency(Duration::from_secs(60));
+    let active = tokio::time::timeout(Duration::from_secs(5), conn.get_active_version()).await.expect("read should have been hedged");
+    assert_eq!(active.unwrap(), Some(2));
+    assert!(tokio::time::timeout(Duration::from_secs(5), conn.get_version_content(2)).await.unwrap().unwrap().is_some());
+
+    // A failing primary is replaced by the secondary straight away
+    db.primary().faults().clear();
+    db.primary().faults().fail_next(1);
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(2));
+
+    // Writes (and other reads) never go to the secondary
+    db.primary().faults().fail_next(1);
+    assert!(conn.add_version(metadata(), true).await.is_err());
+    assert_eq!(conn.add_version(metadata(), true).await.unwrap(), 2);
+    assert_eq!(conn.get_versions().await.unwrap().len(), 2);
+    let mut secondary = db.secondary().connect(&user).await.unwrap();
+    assert_eq!(secondary.get_versions().await.unwrap().len(), 2);
+    assert_eq!(secondary.get_active_version().await.unwrap(), Some(2));
+
+    drop(secondary);
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&primary_path);
+    let _ = std::fs::remove_file(&secondary_path);
+}
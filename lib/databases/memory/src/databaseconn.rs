@@ -0,0 +1,1148 @@
+//  DATABASECONN.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:23:16
+//  Last edited:
+//    17 Oct 2026, 02:16:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the actual [`DatabaseConnector`].
+//
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use specifications::DatabaseConnector;
+use specifications::audit::{AuditAction, AuditRecord, ChainError, Change, verify_chain};
+use specifications::canonical::{content_hash, to_canonical_string};
+use specifications::databaseconn::{ClientError, DatabaseConnection};
+use specifications::decision::Decision;
+use specifications::key::{AttachedKey, Key};
+use specifications::metadata::{AccessContext, Activation, AttachedMetadata, Branch, Freeze, MAX_VERSION, Metadata, Origin, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::schema::{AttachedSchema, Schema};
+use specifications::template::{AttachedTemplate, Template};
+use specifications::userid::{UserIdError, UserIdFormat};
+use thiserror::Error;
+use tracing::{Level, debug, info, span};
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the [`MemoryDatabase`].
+#[derive(Debug, Error)]
+pub enum DatabaseError {
+    /// The ID of the connecting user does not adhere to the configured [`UserIdFormat`].
+    #[error("Refusing to connect to in-memory database with illegal user ID")]
+    IllegalUserId {
+        #[source]
+        err: UserIdError,
+    },
+}
+
+/// Defines errors originating from the [`MemoryConnection`].
+#[derive(Debug, Error)]
+pub enum ConnectionError {
+    /// The audit log failed verification, so it cannot safely be rewritten.
+    #[error("Audit log in in-memory database failed verification")]
+    AuditChain {
+        #[source]
+        err: ChainError,
+    },
+    /// Failed to serialize the given content as JSON.
+    #[error("Failed to serialize the content of policy {name:?} as JSON")]
+    ContentSerialize {
+        name: String,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Another connection panicked while holding the database, which may have left it in an
+    /// inconsistent state.
+    #[error("In-memory database was poisoned by a panicking connection")]
+    Poisoned,
+    /// Attempted to activate a version that does not exist.
+    #[error("Cannot activate version {version} in in-memory database, because it does not exist")]
+    UnknownVersion { version: u64 },
+    /// Attempted to activate a version that is frozen.
+    #[error("Cannot activate version {version} in in-memory database, because it is frozen")]
+    VersionFrozen { version: u64 },
+    /// No more versions can be allocated.
+    #[error("Cannot allocate a new version in in-memory database, because version {MAX_VERSION} has been reached")]
+    VersionsExhausted,
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Strips a [`User`] down to what is recorded about them.
+///
+/// The roles of a user are granted per access, so they are not stored with whatever the user
+/// did; just like in any other backend.
+///
+/// # Arguments
+/// - `user`: The [`User`] to record.
+///
+/// # Returns
+/// A copy of the `user` without any roles.
+#[inline]
+fn recorded(user: &User) -> User { User { id: user.id.clone(), name: user.name.clone(), issuer: user.issuer.clone(), roles: Vec::new() } }
+
+/// Hashes the content of a policy the same way every other backend does.
+///
+/// # Arguments
+/// - `name`: The name of the policy. Only used for debugging.
+/// - `content`: The content to hash.
+///
+/// # Returns
+/// The hash of the canonical JSON of the `content`.
+///
+/// # Errors
+/// This function errors if the `content` could not be serialized as JSON.
+#[inline]
+fn hash_content<C: Serialize>(name: &str, content: &C) -> Result<String, ConnectionError> {
+    match to_canonical_string(content) {
+        Ok(canonical) => Ok(content_hash(&canonical)),
+        Err(err) => Err(ConnectionError::ContentSerialize { name: name.into(), err }),
+    }
+}
+
+/// Replaces a user ID with a pseudonym, if it is the one to replace.
+///
+/// # Arguments
+/// - `field`: The stored ID to (maybe) replace.
+/// - `id`: The ID of the user to replace.
+/// - `pseudonym`: The ID to replace it with.
+///
+/// # Returns
+/// `1` if the `field` was replaced, or `0` otherwise, such that it can be counted.
+#[inline]
+fn pseudonymize(field: &mut String, id: &str, pseudonym: &str) -> u64 {
+    if field == id {
+        *field = pseudonym.into();
+        1
+    } else {
+        0
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// A version as it is stored in a [`MemoryDatabase`].
+struct StoredVersion<C> {
+    /// The metadata of the version.
+    metadata:   Metadata,
+    /// The content of the version.
+    content:    C,
+    /// The address of the client from which the version was written, if known.
+    client:     Option<IpAddr>,
+    /// The ID of the request in which the version was written, if known.
+    request_id: Option<String>,
+}
+
+/// Everything stored in a [`MemoryDatabase`].
+struct State<C> {
+    /// The versions stored, by version number.
+    versions: HashMap<u64, StoredVersion<C>>,
+    /// The highest version number allocated so far (or `0` if none were).
+    latest: u64,
+    /// The history of which versions were active when, ordered by the time they were activated.
+    activations: Vec<Activation>,
+    /// Whether the last of the `activations` is still in effect, i.e., is that of the active
+    /// version.
+    active: bool,
+    /// The templates stored, by name.
+    templates: HashMap<String, Template>,
+    /// The branches stored, by name.
+    branches: HashMap<String, Branch>,
+    /// The schemas stored, by name.
+    schemas: HashMap<String, Schema>,
+    /// The decisions registered, by identifier.
+    decisions: HashMap<String, Decision>,
+    /// The verification keys stored (including revoked ones), by identifier.
+    keys: HashMap<String, Key>,
+    /// The token revocations, in the order they were made.
+    revocations: Vec<Revocation>,
+    /// The audit log, ordered by sequence number.
+    audit_log: Vec<AuditRecord>,
+    /// Up to where each event sink has acknowledged receiving events, by sink name.
+    event_cursors: HashMap<String, u64>,
+}
+impl<C> Default for State<C> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            versions: HashMap::new(),
+            latest: 0,
+            activations: Vec::new(),
+            active: false,
+            templates: HashMap::new(),
+            branches: HashMap::new(),
+            schemas: HashMap::new(),
+            decisions: HashMap::new(),
+            keys: HashMap::new(),
+            revocations: Vec::new(),
+            audit_log: Vec::new(),
+            event_cursors: HashMap::new(),
+        }
+    }
+}
+impl<C> State<C> {
+    /// Retrieves the activation currently in effect.
+    ///
+    /// # Returns
+    /// The [`Activation`] of the active version, or [`None`] if no version is active.
+    #[inline]
+    fn current_activation(&self) -> Option<&Activation> { if self.active { self.activations.last() } else { None } }
+
+    /// Determines the number of the next version to add.
+    ///
+    /// # Returns
+    /// The next version number.
+    ///
+    /// # Errors
+    /// This function errors if [`MAX_VERSION`] has been reached.
+    #[inline]
+    fn next_version(&self) -> Result<u64, ConnectionError> {
+        if self.latest >= MAX_VERSION {
+            return Err(ConnectionError::VersionsExhausted);
+        }
+        Ok(self.latest + 1)
+    }
+
+    /// Appends a version to the branch in its metadata.
+    ///
+    /// Creates the branch if it doesn't exist yet, and sets the version's parent to the previous
+    /// head of the branch.
+    ///
+    /// # Arguments
+    /// - `metadata`: The [`Metadata`] of the version to add. Its version number should come from
+    ///   [`State::next_version()`].
+    /// - `content`: The content of the version.
+    /// - `client`: The address of the client from which it is written, if known.
+    /// - `request_id`: The ID of the request in which it is written, if known.
+    fn add(&mut self, mut metadata: Metadata, content: C, client: Option<IpAddr>, request_id: Option<String>) {
+        let version: u64 = metadata.version;
+        let branch: &mut Branch = self.branches.entry(metadata.attached.branch.clone()).or_insert_with(|| {
+            debug!("Creating new branch {:?}...", metadata.attached.branch);
+            Branch {
+                name:    metadata.attached.branch.clone(),
+                head:    None,
+                base:    None,
+                created: metadata.created,
+                creator: metadata.creator.clone(),
+            }
+        });
+        metadata.parent = branch.head.replace(version);
+        self.latest = self.latest.max(version);
+        self.versions.insert(version, StoredVersion { metadata, content, client, request_id });
+    }
+
+    /// Appends a record to the audit log.
+    ///
+    /// Should be called together with the change it records, i.e., while still holding the same
+    /// lock.
+    ///
+    /// # Arguments
+    /// - `user`: The ID of the user who made the change.
+    /// - `client`: The address of the client from which the change was made, if known.
+    /// - `action`: The [`AuditAction`] describing the change.
+    fn append_audit(&mut self, user: &str, client: Option<IpAddr>, action: AuditAction) {
+        let record = AuditRecord::new(self.audit_log.last(), user, client, action);
+        debug!("Appending audit record {}...", record.seq);
+        self.audit_log.push(record);
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A [`DatabaseConnector`] that keeps everything in memory.
+///
+/// Clones share the same database, which is dropped together with the last of them. Nothing is
+/// ever written to disk, so everything is lost once the process exits. Useful for tests and
+/// demos.
+#[derive(Clone)]
+pub struct MemoryDatabase<C> {
+    /// Everything stored. The lock is only ever held for the duration of a single operation,
+    /// which never awaits anything.
+    state:    Arc<RwLock<State<C>>>,
+    /// The format that the IDs of connecting users must adhere to.
+    user_ids: Arc<dyn Send + Sync + UserIdFormat>,
+}
+impl<C> Default for MemoryDatabase<C> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<C> MemoryDatabase<C> {
+    /// Constructor for the MemoryDatabase.
+    ///
+    /// # Returns
+    /// A new MemoryDatabase struct with nothing in it.
+    #[inline]
+    pub fn new() -> Self {
+        debug!("Creating new in-memory database...");
+        Self { state: Arc::new(RwLock::new(State::default())), user_ids: Arc::new(()) }
+    }
+
+    /// Refuses connections for users whose ID does not adhere to the given format.
+    ///
+    /// IDs are [validated](UserIdFormat::validate()), not normalized; see
+    /// `SQLiteDatabase::with_user_id_format()` for why. By default, any ID is accepted.
+    ///
+    /// # Arguments
+    /// - `format`: The [`UserIdFormat`] to enforce.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_user_id_format(mut self, format: impl 'static + Send + Sync + UserIdFormat) -> Self {
+        self.user_ids = Arc::new(format);
+        self
+    }
+}
+impl<C: Clone + Send + Sync + Serialize + 'static> DatabaseConnector for MemoryDatabase<C> {
+    type Connection<'s>
+        = MemoryConnection<'s, C>
+    where
+        Self: 's;
+    type Content = C;
+    type Error = DatabaseError;
+
+    #[inline]
+    fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        self.connect_from(user, None)
+    }
+
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            if let Err(err) = self.user_ids.validate(&user.id) {
+                return Err(DatabaseError::IllegalUserId { err });
+            }
+            debug!("Creating new connection to in-memory database...");
+            Ok(MemoryConnection { state: &self.state, user, client, request_id: None })
+        }
+    }
+
+    /// Also records the request ID of the access in the provenance of the versions written.
+    ///
+    /// Deadlines are ignored, as connecting never waits.
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            let mut conn: MemoryConnection<'s, C> = self.connect_from(&context.user, context.client).await?;
+            conn.request_id = context.request_id.clone();
+            Ok(conn)
+        }
+    }
+}
+
+
+
+/// Represents the connection created by [`MemoryDatabase::connect()`].
+///
+/// Every method does all its work while holding the database's lock, without awaiting anything
+/// in between. Hence, changes are always made completely or not at all, and are never observed
+/// halfway by other connections.
+pub struct MemoryConnection<'a, C> {
+    /// Everything stored in the database we connect to.
+    state: &'a RwLock<State<C>>,
+    /// The user that is doing everything in this connection.
+    user: &'a User,
+    /// The address of the client from which the user connects, if known.
+    client: Option<IpAddr>,
+    /// The ID of the request in which the user connects, if any.
+    request_id: Option<String>,
+}
+impl<C> MemoryConnection<'_, C> {
+    /// Locks the database for reading.
+    ///
+    /// # Returns
+    /// A guard through which the database can be read.
+    ///
+    /// # Errors
+    /// This function errors if another connection panicked while holding the lock.
+    #[inline]
+    fn read(&self) -> Result<RwLockReadGuard<'_, State<C>>, ConnectionError> { self.state.read().map_err(|_| ConnectionError::Poisoned) }
+
+    /// Locks the database for writing.
+    ///
+    /// # Returns
+    /// A guard through which the database can be changed.
+    ///
+    /// # Errors
+    /// This function errors if another connection panicked while holding the lock.
+    #[inline]
+    fn write(&self) -> Result<RwLockWriteGuard<'_, State<C>>, ConnectionError> { self.state.write().map_err(|_| ConnectionError::Poisoned) }
+}
+impl<C: Clone + Send + Sync + Serialize + 'static> DatabaseConnection for MemoryConnection<'_, C> {
+    type Content = C;
+    type Error = ConnectionError;
+
+
+    // Mutable
+    fn add_version(&mut self, metadata: AttachedMetadata, content: Self::Content) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::add_version", policy = metadata.name);
+
+            let hash: String = hash_content(&metadata.name, &content)?;
+            let mut state = self.write()?;
+            let version: u64 = state.next_version()?;
+
+            debug!("Adding new policy {version}...");
+            let action = AuditAction::AddVersion { version, name: metadata.name.clone(), branch: metadata.branch.clone() };
+            let metadata = Metadata {
+                attached: metadata,
+                created: Utc::now(),
+                creator: recorded(self.user),
+                version,
+                parent: None,
+                promoted_from: None,
+                hash: Some(hash),
+                origin: None,
+                verification: None,
+                frozen: None,
+            };
+            state.add(metadata, content, self.client, self.request_id.clone());
+            state.append_audit(&self.user.id, self.client, action);
+            Ok(version)
+        }
+    }
+
+    fn activate(&mut self, version: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::activate", version = version);
+
+            let mut state = self.write()?;
+            match state.versions.get(&version) {
+                Some(stored) if stored.metadata.frozen.is_some() => return Err(ConnectionError::VersionFrozen { version }),
+                Some(_) => {},
+                None => return Err(ConnectionError::UnknownVersion { version }),
+            }
+
+            // They may already be the same, ez
+            if state.current_activation().is_some_and(|activation| activation.version == version) {
+                info!("Activated already-active version {version}");
+                return Ok(());
+            }
+
+            // Close the activation it supersedes (if any) before adding the new one
+            debug!("Activating policy {version}...");
+            let activated: DateTime<Utc> = Utc::now();
+            let state: &mut State<C> = &mut state;
+            if let Some(current) = state.activations.last_mut().filter(|_| state.active) {
+                current.deactivated = Some(activated);
+                current.deactivator = Some(recorded(self.user));
+            }
+            state.activations.push(Activation {
+                version,
+                activated,
+                activator: recorded(self.user),
+                client: self.client,
+                deactivated: None,
+                deactivator: None,
+            });
+            state.active = true;
+            state.append_audit(&self.user.id, self.client, AuditAction::Activate { version });
+            Ok(())
+        }
+    }
+
+    fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::deactivate");
+
+            let mut state = self.write()?;
+            let state: &mut State<C> = &mut state;
+            let Some(activation) = state.activations.last_mut().filter(|_| state.active) else {
+                info!("Deactivated a policy whilst none were active");
+                return Ok(());
+            };
+            let version: u64 = activation.version;
+
+            debug!("Deactivating active policy {version}...");
+            activation.deactivated = Some(Utc::now());
+            activation.deactivator = Some(recorded(self.user));
+            state.active = false;
+            state.append_audit(&self.user.id, self.client, AuditAction::Deactivate { version });
+            Ok(())
+        }
+    }
+
+    fn freeze_version(&mut self, version: u64, reason: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::freeze_version", version = version);
+
+            let mut state = self.write()?;
+            let Some(stored) = state.versions.get_mut(&version) else { return Ok(Err(ClientError::UnknownVersion { version })) };
+
+            debug!("Freezing policy {version}...");
+            stored.metadata.frozen = Some(Freeze { reason: reason.into(), frozen: Utc::now(), freezer: recorded(self.user) });
+            state.append_audit(&self.user.id, self.client, AuditAction::FreezeVersion { version, reason: reason.into() });
+            Ok(Ok(()))
+        }
+    }
+
+    fn unfreeze_version(&mut self, version: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::unfreeze_version", version = version);
+
+            let mut state = self.write()?;
+            let Some(stored) = state.versions.get_mut(&version) else { return Ok(Err(ClientError::UnknownVersion { version })) };
+
+            // Unfreeze it, unless it isn't frozen
+            debug!("Unfreezing policy {version}...");
+            if stored.metadata.frozen.take().is_some() {
+                state.append_audit(&self.user.id, self.client, AuditAction::UnfreezeVersion { version });
+            }
+            Ok(Ok(()))
+        }
+    }
+
+    fn add_template(&mut self, template: AttachedTemplate) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::add_template", template = template.name);
+
+            // Submit it, replacing any existing one
+            debug!("Adding template {:?}...", template.name);
+            let name: String = template.name.clone();
+            let mut state = self.write()?;
+            state.templates.insert(name.clone(), Template { attached: template, created: Utc::now(), creator: recorded(self.user) });
+            state.append_audit(&self.user.id, self.client, AuditAction::AddTemplate { name });
+            Ok(())
+        }
+    }
+
+    fn create_branch(&mut self, name: &str, from: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::create_branch", branch = name, from = from);
+
+            let mut state = self.write()?;
+            if state.branches.contains_key(name) {
+                return Ok(Err(ClientError::BranchExists { name: name.into() }));
+            }
+            if !state.versions.contains_key(&from) {
+                return Ok(Err(ClientError::UnknownVersion { version: from }));
+            }
+
+            debug!("Creating branch {name:?} from version {from}...");
+            let branch = Branch { name: name.into(), head: Some(from), base: Some(from), created: Utc::now(), creator: recorded(self.user) };
+            state.branches.insert(name.into(), branch);
+            state.append_audit(&self.user.id, self.client, AuditAction::CreateBranch { name: name.into(), from });
+            Ok(Ok(()))
+        }
+    }
+
+    fn promote(&mut self, version: u64, target: &str, force: bool) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::promote", version = version, target = target);
+
+            let mut state = self.write()?;
+            let Some(original) = state.versions.get(&version) else { return Ok(Err(ClientError::UnknownVersion { version })) };
+            if original.metadata.frozen.is_some() {
+                return Ok(Err(ClientError::VersionFrozen { version }));
+            }
+            let source: &str = &original.metadata.attached.branch;
+            if source == target {
+                return Ok(Err(ClientError::PromoteSameBranch { version, branch: target.into() }));
+            }
+            let Some(target_branch) = state.branches.get(target) else { return Ok(Err(ClientError::UnknownBranch { name: target.into() })) };
+
+            // Find where the branches last met; i.e., the latest promotion from the source into the target, or else the fork point
+            debug!("Checking for conflicts between branch {source:?} and {target:?}...");
+            let last_promotion: Option<u64> = state
+                .versions
+                .values()
+                .filter(|stored| stored.metadata.attached.branch == target)
+                .filter(|stored| {
+                    stored
+                        .metadata
+                        .promoted_from
+                        .and_then(|from| state.versions.get(&from))
+                        .is_some_and(|from| from.metadata.attached.branch == source)
+                })
+                .map(|stored| stored.metadata.version)
+                .max();
+            let base: Option<u64> = last_promotion.or(state.branches.get(source).and_then(|branch| branch.base));
+            if let Some(head) = target_branch.head {
+                if Some(head) != base {
+                    if force {
+                        info!("Forcing promotion of version {version} into branch {target:?} despite it having advanced to {head}");
+                    } else {
+                        return Ok(Err(ClientError::PromoteConflict { version, source_branch: source.into(), target: target.into(), head, base }));
+                    }
+                }
+            }
+
+            // Append the copy to the target
+            let promoted: u64 = state.next_version()?;
+            debug!("Promoting policy {version} into branch {target:?} as {promoted}...");
+            let metadata = Metadata {
+                attached: AttachedMetadata { branch: target.into(), ..original.metadata.attached.clone() },
+                created: Utc::now(),
+                creator: recorded(self.user),
+                version: promoted,
+                parent: None,
+                promoted_from: Some(version),
+                hash: original.metadata.hash.clone(),
+                origin: original.metadata.origin.clone(),
+                verification: original.metadata.verification.clone(),
+                frozen: None,
+            };
+            let content: C = original.content.clone();
+            state.add(metadata, content, self.client, self.request_id.clone());
+            state.append_audit(&self.user.id, self.client, AuditAction::Promote { version, target: target.into(), promoted });
+            Ok(Ok(promoted))
+        }
+    }
+
+    fn import_version(
+        &mut self,
+        store: &str,
+        metadata: Metadata,
+        content: Self::Content,
+    ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::import_version", store = store, version = metadata.version);
+
+            // Make sure the content survived the trip
+            let hash: String = hash_content(&metadata.attached.name, &content)?;
+            if let Some(expected) = metadata.hash {
+                if expected != hash {
+                    return Ok(Err(ClientError::HashMismatch { version: metadata.version, expected, actual: hash }));
+                }
+            }
+
+            // Keep the original origin if this version was imported before
+            let source: u64 = metadata.version;
+            let origin: Origin =
+                metadata.origin.unwrap_or(Origin { store: store.into(), version: source, creator: metadata.creator.id, created: metadata.created });
+            // NOTE: Other stores are bound by the same maximum, so they can't have such a version either
+            if origin.version > MAX_VERSION {
+                return Ok(Err(ClientError::UnknownVersion { version: origin.version }));
+            }
+
+            // Append the copy to the same branch as in the other store
+            let mut state = self.write()?;
+            let version: u64 = state.next_version()?;
+            debug!("Importing policy {source} from store {store:?} as {version}...");
+            let action = AuditAction::Import {
+                version,
+                name: metadata.attached.name.clone(),
+                branch: metadata.attached.branch.clone(),
+                store: store.into(),
+                source,
+            };
+            let metadata = Metadata {
+                attached: metadata.attached,
+                created: Utc::now(),
+                creator: recorded(self.user),
+                version,
+                parent: None,
+                promoted_from: None,
+                hash: Some(hash),
+                origin: Some(origin),
+                verification: metadata.verification,
+                frozen: None,
+            };
+            state.add(metadata, content, self.client, self.request_id.clone());
+            state.append_audit(&self.user.id, self.client, action);
+            Ok(Ok(version))
+        }
+    }
+
+    fn add_schema(&mut self, schema: AttachedSchema) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::add_schema", schema = schema.name);
+
+            let mut state = self.write()?;
+            if state.schemas.contains_key(&schema.name) {
+                return Ok(Err(ClientError::SchemaExists { name: schema.name }));
+            }
+
+            debug!("Adding schema {:?}...", schema.name);
+            let name: String = schema.name.clone();
+            state.schemas.insert(name.clone(), Schema { attached: schema, created: Utc::now(), creator: recorded(self.user) });
+            state.append_audit(&self.user.id, self.client, AuditAction::AddSchema { name });
+            Ok(Ok(()))
+        }
+    }
+
+    fn pseudonymize_user(&mut self, id: &str, pseudonym: &str) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            // NOTE: Deliberately not logging the ID itself, as that's what we're trying to get rid of
+            let _span = span!(Level::INFO, "MemoryConnection::pseudonymize_user", pseudonym = pseudonym);
+
+            // We don't want to launder any tampering by re-hashing, so verify the audit log first
+            let mut state = self.write()?;
+            if let Err(err) = verify_chain(&state.audit_log) {
+                return Err(ConnectionError::AuditChain { err });
+            }
+
+            // First, everything but the audit log
+            debug!("Pseudonymizing user in tables...");
+            let state: &mut State<C> = &mut state;
+            let mut count: u64 = 0;
+            for stored in state.versions.values_mut() {
+                // Where they wrote it from is as telling as their ID, so forget it
+                count += u64::from(stored.metadata.creator.id == id && stored.client.take().is_some());
+                count += pseudonymize(&mut stored.metadata.creator.id, id, pseudonym);
+                if let Some(origin) = &mut stored.metadata.origin {
+                    count += pseudonymize(&mut origin.creator, id, pseudonym);
+                }
+                if let Some(frozen) = &mut stored.metadata.frozen {
+                    count += pseudonymize(&mut frozen.freezer.id, id, pseudonym);
+                }
+            }
+            for template in state.templates.values_mut() {
+                count += pseudonymize(&mut template.creator.id, id, pseudonym);
+            }
+            for branch in state.branches.values_mut() {
+                count += pseudonymize(&mut branch.creator.id, id, pseudonym);
+            }
+            for schema in state.schemas.values_mut() {
+                count += pseudonymize(&mut schema.creator.id, id, pseudonym);
+            }
+            for activation in &mut state.activations {
+                count += u64::from(activation.activator.id == id && activation.client.take().is_some());
+                count += pseudonymize(&mut activation.activator.id, id, pseudonym);
+                if let Some(deactivator) = &mut activation.deactivator {
+                    count += pseudonymize(&mut deactivator.id, id, pseudonym);
+                }
+            }
+            for decision in state.decisions.values_mut() {
+                count += pseudonymize(&mut decision.reporter.id, id, pseudonym);
+            }
+            for key in state.keys.values_mut() {
+                count += pseudonymize(&mut key.creator.id, id, pseudonym);
+            }
+            for revocation in &mut state.revocations {
+                count += pseudonymize(&mut revocation.creator.id, id, pseudonym);
+            }
+
+            // Then the audit log, which is re-hashed from the first affected record onwards
+            debug!("Pseudonymizing user in audit log...");
+            let mut prev_hash: Option<String> = None;
+            let mut rehashed_from: Option<u64> = None;
+            for record in &mut state.audit_log {
+                if record.user == id {
+                    record.user = pseudonym.into();
+                    record.client = None;
+                    rehashed_from = rehashed_from.or(Some(record.seq));
+                    count += 1;
+                }
+                if record.action.pseudonymize(id, pseudonym) > 0 {
+                    rehashed_from = rehashed_from.or(Some(record.seq));
+                    count += 1;
+                }
+                if rehashed_from.is_some() {
+                    record.prev_hash = prev_hash;
+                    record.hash = record.compute_hash();
+                }
+                prev_hash = Some(record.hash.clone());
+            }
+            if count == 0 {
+                return Ok(Err(ClientError::UnknownUser { id: id.into() }));
+            }
+
+            // Finally, log the pseudonymization itself
+            state.append_audit(&self.user.id, self.client, AuditAction::Pseudonymize { pseudonym: pseudonym.into(), rehashed_from });
+            Ok(Ok(count))
+        }
+    }
+
+    fn add_decision(&mut self, version: u64, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::add_decision", version = version, decision = id);
+
+            // Ensure the version exists and the decision doesn't yet
+            let mut state = self.write()?;
+            if !state.versions.contains_key(&version) {
+                return Ok(Err(ClientError::UnknownVersion { version }));
+            }
+            if state.decisions.contains_key(id) {
+                return Ok(Err(ClientError::DecisionExists { id: id.into() }));
+            }
+
+            debug!("Adding decision {id:?}...");
+            state.decisions.insert(id.into(), Decision { id: id.into(), version, recorded: Utc::now(), reporter: recorded(self.user) });
+            state.append_audit(&self.user.id, self.client, AuditAction::AddDecision { version, id: id.into() });
+            Ok(Ok(()))
+        }
+    }
+
+    fn prune_activations(&mut self, before: DateTime<Utc>) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::prune_activations", before = before.to_rfc3339());
+
+            // NOTE: Activations are closed when deactivated or superseded, so only the active one
+            // lacks a deactivation time (and never matches)
+            debug!("Removing activations that ended before {}...", before.to_rfc3339());
+            let mut state = self.write()?;
+            let len: usize = state.activations.len();
+            state.activations.retain(|activation| activation.deactivated.is_none_or(|deactivated| deactivated >= before));
+            let rows: u64 = (len - state.activations.len()) as u64;
+            if rows > 0 {
+                state.append_audit(&self.user.id, self.client, AuditAction::PruneActivations { before, rows });
+            }
+            Ok(rows)
+        }
+    }
+
+    fn add_key(&mut self, key: AttachedKey) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::add_key", key = key.id);
+
+            // Ensure the key doesn't exist yet (not even revoked)
+            let mut state = self.write()?;
+            if state.keys.contains_key(&key.id) {
+                return Ok(Err(ClientError::KeyExists { id: key.id }));
+            }
+
+            debug!("Adding key {:?}...", key.id);
+            let id: String = key.id.clone();
+            state.keys.insert(id.clone(), Key { attached: key, created: Utc::now(), creator: recorded(self.user), revoked: None });
+            state.append_audit(&self.user.id, self.client, AuditAction::AddKey { id });
+            Ok(Ok(()))
+        }
+    }
+
+    fn revoke_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::revoke_key", key = id);
+
+            let mut state = self.write()?;
+            let Some(key) = state.keys.get_mut(id) else { return Ok(Err(ClientError::UnknownKey { id: id.into() })) };
+
+            // Revoke it, unless it already is
+            debug!("Revoking key {id:?}...");
+            if key.revoked.is_none() {
+                key.revoked = Some(Utc::now());
+                state.append_audit(&self.user.id, self.client, AuditAction::RevokeKey { id: id.into() });
+            }
+            Ok(Ok(()))
+        }
+    }
+
+    fn revoke_tokens(&mut self, revocation: AttachedRevocation) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::revoke_tokens");
+
+            let mut state = self.write()?;
+            let action: AuditAction = match &revocation {
+                AttachedRevocation::Token { jti } => {
+                    // Revoke it, unless it already is
+                    if state.revocations.iter().any(|prev| matches!(&prev.attached, AttachedRevocation::Token { jti: revoked } if revoked == jti)) {
+                        debug!("Token {jti:?} was already revoked");
+                        return Ok(());
+                    }
+                    debug!("Revoking token {jti:?}...");
+                    AuditAction::RevokeToken { jti: jti.clone() }
+                },
+                AttachedRevocation::User { user, before } => {
+                    // Only keep the latest revocation per user
+                    let prev: Option<usize> = state
+                        .revocations
+                        .iter()
+                        .position(|prev| matches!(&prev.attached, AttachedRevocation::User { user: revoked, .. } if revoked == user));
+                    if let Some(prev) = prev {
+                        if matches!(state.revocations[prev].attached, AttachedRevocation::User { before: revoked, .. } if revoked >= *before) {
+                            debug!("Tokens of user {user:?} were already revoked before {before}");
+                            return Ok(());
+                        }
+                        state.revocations.remove(prev);
+                    }
+                    debug!("Revoking tokens of user {user:?} issued before {before}...");
+                    AuditAction::RevokeUserTokens { user: user.clone(), before: *before }
+                },
+            };
+            state.revocations.push(Revocation { attached: revocation, created: Utc::now(), creator: recorded(self.user) });
+            state.append_audit(&self.user.id, self.client, action);
+            Ok(())
+        }
+    }
+
+    fn ack_events(&mut self, sink: &str, seq: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::ack_events", sink = sink, seq = seq);
+
+            // Only ever move the cursor forward
+            let mut state = self.write()?;
+            if let Some(prev) = state.event_cursors.get(sink).filter(|prev| **prev >= seq) {
+                debug!("Sink {sink:?} already acknowledged events up to {prev}");
+                return Ok(());
+            }
+
+            debug!("Acknowledging events up to {seq} for sink {sink:?}...");
+            state.event_cursors.insert(sink.into(), seq);
+            Ok(())
+        }
+    }
+
+    // Immutable
+    fn get_versions(&mut self) -> impl Send + Future<Output = Result<HashMap<u64, Metadata>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_versions");
+
+            debug!("Retrieving all policy versions...");
+            Ok(self.read()?.versions.iter().map(|(version, stored)| (*version, stored.metadata.clone())).collect())
+        }
+    }
+
+    fn get_active_version(&mut self) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_active");
+
+            debug!("Fetching active version...");
+            Ok(self.read()?.current_activation().map(|activation| activation.version))
+        }
+    }
+
+    fn get_active_version_at(&mut self, at: DateTime<Utc>) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_active_version_at", at = at.to_rfc3339());
+
+            // Find the last activation before the given time, and see if it was still active then
+            debug!("Fetching version active at {at}...");
+            let state = self.read()?;
+            Ok(state
+                .activations
+                .iter()
+                .filter(|activation| activation.activated <= at)
+                .max_by_key(|activation| activation.activated)
+                .filter(|activation| activation.deactivated.is_none_or(|deactivated| deactivated > at))
+                .map(|activation| activation.version))
+        }
+    }
+
+    fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_active");
+
+            Ok(self.read()?.current_activation().map(|activation| activation.activator.clone()))
+        }
+    }
+
+    fn get_activation(&mut self) -> impl Send + Future<Output = Result<Option<Activation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_activation");
+
+            Ok(self.read()?.current_activation().cloned())
+        }
+    }
+
+    fn get_activations(&mut self) -> impl Send + Future<Output = Result<Vec<Activation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_activations");
+
+            debug!("Retrieving activation history...");
+            let mut activations: Vec<Activation> = self.read()?.activations.clone();
+            activations.sort_by_key(|activation| activation.activated);
+            Ok(activations)
+        }
+    }
+
+    fn get_version_metadata(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Metadata>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_version_metadata", version = version);
+
+            debug!("Retrieving metadata for version {version}...");
+            Ok(self.read()?.versions.get(&version).map(|stored| stored.metadata.clone()))
+        }
+    }
+
+    fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_version_content", version = version);
+
+            debug!("Retrieving content for version {version}...");
+            Ok(self.read()?.versions.get(&version).map(|stored| stored.content.clone()))
+        }
+    }
+
+    fn get_version_provenance(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Provenance>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_version_provenance", version = version);
+
+            debug!("Retrieving provenance for version {version}...");
+            Ok(self.read()?.versions.get(&version).map(|stored| Provenance::new(&stored.metadata, stored.client, stored.request_id.clone())))
+        }
+    }
+
+    fn get_templates(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Template>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_templates");
+
+            debug!("Retrieving all templates...");
+            Ok(self.read()?.templates.clone())
+        }
+    }
+
+    fn get_template(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Template>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_template", template = name);
+
+            debug!("Retrieving template {name:?}...");
+            Ok(self.read()?.templates.get(name).cloned())
+        }
+    }
+
+    fn get_branches(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Branch>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_branches");
+
+            debug!("Retrieving all branches...");
+            Ok(self.read()?.branches.clone())
+        }
+    }
+
+    fn get_schemas(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Schema>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_schemas");
+
+            debug!("Retrieving all schemas...");
+            Ok(self.read()?.schemas.clone())
+        }
+    }
+
+    fn get_schema(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Schema>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_schema", schema = name);
+
+            debug!("Retrieving schema {name:?}...");
+            Ok(self.read()?.schemas.get(name).cloned())
+        }
+    }
+
+    fn get_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Vec<Decision>>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_decisions", version = version);
+
+            let state = self.read()?;
+            if !state.versions.contains_key(&version) {
+                return Ok(None);
+            }
+            debug!("Retrieving decisions taken under version {version}...");
+            let mut decisions: Vec<Decision> = state.decisions.values().filter(|decision| decision.version == version).cloned().collect();
+            decisions.sort_by_key(|decision| decision.recorded);
+            Ok(Some(decisions))
+        }
+    }
+
+    fn count_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::count_decisions", version = version);
+
+            let state = self.read()?;
+            if !state.versions.contains_key(&version) {
+                return Ok(None);
+            }
+            debug!("Counting decisions taken under version {version}...");
+            Ok(Some(state.decisions.values().filter(|decision| decision.version == version).count() as u64))
+        }
+    }
+
+    fn get_decision(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Decision>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_decision", decision = id);
+
+            debug!("Retrieving decision {id:?}...");
+            Ok(self.read()?.decisions.get(id).cloned())
+        }
+    }
+
+    fn get_keys(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Key>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_keys");
+
+            debug!("Retrieving all keys...");
+            Ok(self.read()?.keys.clone())
+        }
+    }
+
+    fn get_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Key>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_key", key = id);
+
+            debug!("Retrieving key {id:?}...");
+            Ok(self.read()?.keys.get(id).cloned())
+        }
+    }
+
+    fn get_revocations(&mut self) -> impl Send + Future<Output = Result<Vec<Revocation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_revocations");
+
+            debug!("Retrieving all token revocations...");
+            Ok(self.read()?.revocations.clone())
+        }
+    }
+
+    fn is_revoked(&mut self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::is_revoked", user = user);
+
+            debug!("Checking whether token {jti:?} of user {user:?} is revoked...");
+            let state = self.read()?;
+            Ok(state.revocations.iter().any(|revocation| match &revocation.attached {
+                AttachedRevocation::Token { jti: revoked } => jti == Some(revoked.as_str()),
+                AttachedRevocation::User { user: revoked, before } => revoked == user && issued.is_none_or(|issued| issued < *before),
+            }))
+        }
+    }
+
+    fn get_audit_log(&mut self, since: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<Vec<AuditRecord>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_audit_log");
+
+            debug!("Retrieving audit log...");
+            Ok(self.read()?.audit_log.iter().filter(|record| since.is_none_or(|since| record.timestamp >= since)).cloned().collect())
+        }
+    }
+
+    fn get_changes(&mut self, since: u64, limit: u64) -> impl Send + Future<Output = Result<Vec<Change<Self::Content>>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_changes", since = since, limit = limit);
+
+            // Attach any versions created
+            debug!("Retrieving changes since {since}...");
+            let state = self.read()?;
+            Ok(state
+                .audit_log
+                .iter()
+                .filter(|record| record.seq > since)
+                .take(usize::try_from(limit).unwrap_or(usize::MAX))
+                .map(|record| {
+                    let stored: Option<&StoredVersion<C>> = record.action.created_version().and_then(|version| state.versions.get(&version));
+                    Change {
+                        record:   record.clone(),
+                        metadata: stored.map(|stored| stored.metadata.clone()),
+                        content:  stored.map(|stored| stored.content.clone()),
+                        added:    None,
+                    }
+                })
+                .collect())
+        }
+    }
+
+    fn get_event_cursor(&mut self, sink: &str) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "MemoryConnection::get_event_cursor", sink = sink);
+
+            debug!("Retrieving event cursor of sink {sink:?}...");
+            Ok(self.read()?.event_cursors.get(sink).copied().unwrap_or(0))
+        }
+    }
+}
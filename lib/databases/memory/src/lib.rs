@@ -0,0 +1,21 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:23:16
+//  Last edited:
+//    16 Oct 2026, 18:23:16
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the `DatabaseConnector` for a backend that lives in
+//!   memory only. Useful for tests and demos, as it needs neither a disk
+//!   nor a database engine.
+//
+
+// Declare modules
+mod databaseconn;
+
+// Import some of it
+pub use databaseconn::*;
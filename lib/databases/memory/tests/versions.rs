@@ -0,0 +1,159 @@
+//  VERSIONS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:23:16
+//  Last edited:
+//    17 Oct 2026, 02:16:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the in-memory connector stores, activates and promotes
+//!   policy versions.
+//
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use chrono::{DateTime, Duration, Utc};
+use memory_database::{ConnectionError, MemoryDatabase};
+use specifications::DatabaseConnector as _;
+use specifications::audit::{AuditAction, AuditRecord, verify_chain};
+use specifications::databaseconn::{ClientError, DatabaseConnection as _};
+use specifications::metadata::{AccessContext, Metadata, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::testing::{metadata, metadata_on};
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_version_lifecycle() {
+    let db: MemoryDatabase<String> = MemoryDatabase::new();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    // Versions are numbered in order, and chained on their branch
+    let first: u64 = conn.add_version(metadata(), "allow".into()).await.unwrap();
+    let second: u64 = conn.add_version(metadata(), "deny".into()).await.unwrap();
+    assert_eq!((first, second), (1, 2));
+    assert_eq!(conn.get_version_metadata(second).await.unwrap().unwrap().parent, Some(first));
+    assert_eq!(conn.get_version_content(first).await.unwrap(), Some("allow".into()));
+    assert_eq!(conn.get_branches().await.unwrap()["main"].head, Some(second));
+
+    // Only existing versions can be activated
+    assert!(matches!(conn.activate(42).await, Err(ConnectionError::UnknownVersion { version: 42 })));
+    let access = AccessContext::new(user.clone()).with_client(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+    db.connect_with(&access).await.unwrap().activate(first).await.unwrap();
+    conn.activate(second).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(second));
+    conn.deactivate().await.unwrap();
+    conn.deactivate().await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), None);
+
+    // The history remembers where each activation came from
+    let activations = conn.get_activations().await.unwrap();
+    assert_eq!(activations.iter().map(|activation| activation.version).collect::<Vec<u64>>(), vec![first, second]);
+    assert_eq!(activations.iter().map(|activation| activation.client).collect::<Vec<Option<IpAddr>>>(), vec![access.client, None]);
+
+    // Everything but the no-op deactivation is audited
+    let log: Vec<AuditRecord> = conn.get_audit_log(None).await.unwrap();
+    let kinds: Vec<&str> = log.iter().map(|record| record.action.kind()).collect();
+    assert_eq!(kinds, vec!["add_version", "add_version", "activate", "activate", "deactivate"]);
+    assert_eq!(verify_chain(&log).unwrap(), 5);
+}
+
+#[tokio::test]
+async fn test_prune_superseded() {
+    let db: MemoryDatabase<String> = MemoryDatabase::new();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let first: u64 = conn.add_version(metadata(), "allow".into()).await.unwrap();
+    let second: u64 = conn.add_version(metadata(), "deny".into()).await.unwrap();
+
+    // Activating another version closes the activation it supersedes, such that it can be pruned
+    conn.activate(first).await.unwrap();
+    conn.activate(second).await.unwrap();
+    let activations = conn.get_activations().await.unwrap();
+    assert_eq!(activations[0].deactivated, Some(activations[1].activated));
+    assert_eq!(activations[1].deactivated, None);
+    assert_eq!(conn.prune_activations(Utc::now() + Duration::seconds(1)).await.unwrap(), 1);
+    assert_eq!(conn.get_activations().await.unwrap().iter().map(|activation| activation.version).collect::<Vec<u64>>(), vec![second]);
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(second));
+}
+
+#[tokio::test]
+async fn test_clones_share_database() {
+    let db: MemoryDatabase<String> = MemoryDatabase::new();
+    let other: MemoryDatabase<String> = db.clone();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+
+    // What is written through one clone is visible through the other...
+    let version: u64 = db.connect(&user).await.unwrap().add_version(metadata(), "allow".into()).await.unwrap();
+    other.connect(&user).await.unwrap().activate(version).await.unwrap();
+    assert_eq!(db.connect(&user).await.unwrap().get_active_version().await.unwrap(), Some(version));
+
+    // ...but not through a new database
+    let fresh: MemoryDatabase<String> = MemoryDatabase::new();
+    assert!(fresh.connect(&user).await.unwrap().get_versions().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_promote_conflict() {
+    let db: MemoryDatabase<String> = MemoryDatabase::new();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let base: u64 = conn.add_version(metadata(), "allow".into()).await.unwrap();
+    conn.create_branch("staging", base).await.unwrap().unwrap();
+    let staged: u64 = conn.add_version(metadata_on("staging"), "deny".into()).await.unwrap();
+
+    // Promoting into an untouched branch works, and copies the content
+    let promoted: u64 = conn.promote(staged, "main", false).await.unwrap().unwrap();
+    assert_eq!(conn.get_version_metadata(promoted).await.unwrap().unwrap().promoted_from, Some(staged));
+    assert_eq!(conn.get_version_content(promoted).await.unwrap(), Some("deny".into()));
+
+    // But not once the target has advanced since, unless forced
+    let hotfix: u64 = conn.add_version(metadata(), "allow".into()).await.unwrap();
+    let staged: u64 = conn.add_version(metadata_on("staging"), "deny".into()).await.unwrap();
+    match conn.promote(staged, "main", false).await.unwrap() {
+        Err(ClientError::PromoteConflict { head, base, .. }) => assert_eq!((head, base), (hotfix, Some(promoted))),
+        res => panic!("expected a conflict, got {res:?}"),
+    }
+    conn.promote(staged, "main", true).await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_pseudonymize_user() {
+    let db: MemoryDatabase<String> = MemoryDatabase::new();
+    let amy = User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() };
+    let admin = User { id: "admin".into(), name: "Admin".into(), issuer: None, roles: Vec::new() };
+    let access = AccessContext::new(amy.clone()).with_client(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+    let version: u64 = db.connect_with(&access).await.unwrap().add_version(metadata(), "allow".into()).await.unwrap();
+    let mut conn = db.connect(&admin).await.unwrap();
+    conn.activate(version).await.unwrap();
+    db.connect(&amy).await.unwrap().freeze_version(version, "suspected to be malicious").await.unwrap().unwrap();
+    let before: DateTime<Utc> = Utc::now();
+    conn.revoke_tokens(AttachedRevocation::User { user: "amy".into(), before }).await.unwrap();
+
+    // The version (and where she wrote it from), its freeze, its branch and three audit records are Amy's
+    assert_eq!(conn.pseudonymize_user("amy", "user-1").await.unwrap().unwrap(), 7);
+    let pseudonymized: Metadata = conn.get_version_metadata(version).await.unwrap().unwrap();
+    assert_eq!(pseudonymized.creator.id, "user-1");
+    assert_eq!(pseudonymized.frozen.expect("version should still be frozen").freezer.id, "user-1");
+    let provenance: Provenance = conn.get_version_provenance(version).await.unwrap().unwrap();
+    assert_eq!(provenance.predicate.build_definition.internal_parameters.client, None);
+
+    // Her tokens stay revoked, which they can only be by her actual ID
+    let revocations: Vec<Revocation> = conn.get_revocations().await.unwrap();
+    assert!(matches!(&revocations[..], [Revocation { attached: AttachedRevocation::User { user, .. }, .. }] if user == "amy"));
+    assert!(conn.is_revoked(None, "amy", Some(before - Duration::seconds(1))).await.unwrap());
+    assert!(!conn.is_revoked(None, "user-1", Some(before - Duration::seconds(1))).await.unwrap());
+    assert!(matches!(conn.pseudonymize_user("amy", "user-2").await.unwrap(), Err(ClientError::UnknownUser { .. })));
+
+    // The rewritten audit log still verifies
+    let log: Vec<AuditRecord> = conn.get_audit_log(None).await.unwrap();
+    assert!(log.iter().all(|record| record.user != "amy"));
+    assert!(log.iter().any(|record| record.action == AuditAction::RevokeUserTokens { user: "user-1".into(), before }));
+    assert_eq!(verify_chain(&log).unwrap(), 5);
+    assert_eq!(log.last().map(|record| &record.action), Some(&AuditAction::Pseudonymize { pseudonym: "user-1".into(), rehashed_from: Some(1) }));
+}
@@ -0,0 +1,15 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE `provenance`;
+DROP TABLE `event_cursors`;
+DROP TABLE `revoked_users`;
+DROP TABLE `revoked_tokens`;
+DROP TABLE `keys`;
+DROP TABLE `decisions`;
+DROP TABLE `audit_log`;
+DROP TABLE `schemas`;
+DROP TABLE `branches`;
+DROP TABLE `templates`;
+DROP TABLE `write_lock`;
+DROP TABLE `current_active`;
+DROP TABLE `active_version`;
+DROP TABLE `policies`;
@@ -0,0 +1,139 @@
+-- Your SQL goes here
+-- NOTE: All tables use a binary collation, such that names and IDs compare case-sensitively like
+-- they do in the other backends.
+CREATE TABLE `policies`(
+	`version` BIGINT NOT NULL PRIMARY KEY,
+	`name` TEXT NOT NULL,
+	`description` TEXT NOT NULL,
+	`creator` VARCHAR(255) NOT NULL,
+	`created_at` DATETIME(6) NOT NULL,
+	`content` LONGTEXT NOT NULL,
+	`language` VARCHAR(255) NOT NULL DEFAULT 'UNKNOWN',
+	`branch` VARCHAR(255) NOT NULL DEFAULT 'main',
+	`parent` BIGINT,
+	`promoted_from` BIGINT,
+	`content_hash` TEXT,
+	`schema` VARCHAR(255),
+	`origin_store` TEXT,
+	`origin_version` BIGINT,
+	`origin_creator` TEXT,
+	`origin_created_at` DATETIME(6),
+	`verified_identity` TEXT,
+	`verified_digest` TEXT,
+	`verified_at` DATETIME(6),
+	`creator_issuer` TEXT,
+	`frozen_reason` TEXT,
+	`frozen_by` TEXT,
+	`frozen_by_issuer` TEXT,
+	`frozen_at` DATETIME(6)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE INDEX `policies_created_at` ON `policies`(`created_at`);
+CREATE INDEX `policies_creator` ON `policies`(`creator`);
+
+CREATE TABLE `active_version`(
+	`version` BIGINT NOT NULL,
+	`activated_on` DATETIME(6) NOT NULL,
+	`activated_by` VARCHAR(255) NOT NULL,
+	`deactivated_on` DATETIME(6),
+	`deactivated_by` VARCHAR(255),
+	PRIMARY KEY(`version`, `activated_on`)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE INDEX `active_version_activated_on` ON `active_version`(`activated_on`);
+
+-- Points to the activation in `active_version` that is currently in effect, if any. Only ever has
+-- (at most) one row, such that finding the active version is a primary-key read.
+CREATE TABLE `current_active`(
+	`id` INTEGER NOT NULL PRIMARY KEY CHECK (`id` = 1),
+	`version` BIGINT NOT NULL,
+	`activated_on` DATETIME(6) NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- Has exactly one row, which every writing transaction locks (`SELECT ... FOR UPDATE`) first. This
+-- serializes writes across all stores sharing the database.
+CREATE TABLE `write_lock`(
+	`id` INTEGER NOT NULL PRIMARY KEY CHECK (`id` = 1)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+INSERT INTO `write_lock`(`id`) VALUES (1);
+
+CREATE TABLE `templates`(
+	`name` VARCHAR(255) NOT NULL PRIMARY KEY,
+	`description` TEXT NOT NULL,
+	`language` VARCHAR(255) NOT NULL,
+	`parameters` LONGTEXT NOT NULL,
+	`skeleton` LONGTEXT NOT NULL,
+	`creator` VARCHAR(255) NOT NULL,
+	`created_at` DATETIME(6) NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE `branches`(
+	`name` VARCHAR(255) NOT NULL PRIMARY KEY,
+	`head` BIGINT,
+	`base` BIGINT,
+	`creator` VARCHAR(255) NOT NULL,
+	`created_at` DATETIME(6) NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE `schemas`(
+	`name` VARCHAR(255) NOT NULL PRIMARY KEY,
+	`description` TEXT NOT NULL,
+	`schema` LONGTEXT NOT NULL,
+	`creator` VARCHAR(255) NOT NULL,
+	`created_at` DATETIME(6) NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE `audit_log`(
+	`seq` BIGINT NOT NULL PRIMARY KEY,
+	`timestamp` DATETIME(6) NOT NULL,
+	`user_id` VARCHAR(255) NOT NULL,
+	`action` LONGTEXT NOT NULL,
+	`prev_hash` TEXT,
+	`hash` TEXT NOT NULL,
+	`client` TEXT
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE `decisions`(
+	`id` VARCHAR(255) NOT NULL PRIMARY KEY,
+	`version` BIGINT NOT NULL,
+	`reporter` VARCHAR(255) NOT NULL,
+	`recorded_at` DATETIME(6) NOT NULL,
+	FOREIGN KEY(`version`) REFERENCES `policies`(`version`)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE INDEX `decisions_version` ON `decisions`(`version`);
+
+CREATE TABLE `keys`(
+	`id` VARCHAR(255) NOT NULL PRIMARY KEY,
+	`kind` VARCHAR(255) NOT NULL,
+	`material` LONGTEXT NOT NULL,
+	`creator` VARCHAR(255) NOT NULL,
+	`created_at` DATETIME(6) NOT NULL,
+	`revoked_at` DATETIME(6)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE `revoked_tokens`(
+	`jti` VARCHAR(255) NOT NULL PRIMARY KEY,
+	`creator` VARCHAR(255) NOT NULL,
+	`created_at` DATETIME(6) NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE `revoked_users`(
+	`user_id` VARCHAR(255) NOT NULL PRIMARY KEY,
+	`revoked_before` DATETIME(6) NOT NULL,
+	`creator` VARCHAR(255) NOT NULL,
+	`created_at` DATETIME(6) NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE `event_cursors`(
+	`sink` VARCHAR(255) NOT NULL PRIMARY KEY,
+	`seq` BIGINT NOT NULL,
+	`updated_at` DATETIME(6) NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE `provenance`(
+	`version` BIGINT NOT NULL PRIMARY KEY,
+	`client` TEXT,
+	`request_id` TEXT,
+	FOREIGN KEY(`version`) REFERENCES `policies`(`version`)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
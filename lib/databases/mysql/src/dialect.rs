@@ -0,0 +1,126 @@
+//  DIALECT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:31:56
+//  Last edited:
+//    16 Oct 2026, 18:31:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the parts of the shared diesel connection that are
+//!   specific to MySQL.
+//
+
+use diesel::mysql::Mysql;
+use diesel::{Connection as _, MysqlConnection, QueryDsl as _, QueryResult, RunQueryDsl as _};
+
+use crate::models::{DbCurrentActive, DbEventCursor, DbRevokedToken, DbRevokedUser, DbTemplate};
+
+
+/***** CONSTANTS *****/
+/// The identifier of the only row in the `write_lock` table.
+const WRITE_LOCK_ID: i32 = 1;
+
+
+
+
+
+/***** LIBRARY *****/
+/// The connection type of this backend.
+pub(crate) type RawConnection = MysqlConnection;
+
+/// The diesel backend of this backend.
+pub(crate) type Backend = Mysql;
+
+
+
+/// Runs the given work in a transaction that holds the lock on the only row of the `write_lock`
+/// table.
+///
+/// This is the equivalent of SQLite's exclusive transactions, but then for every store sharing
+/// the database. The row lock is released when the transaction commits or rolls back.
+///
+/// # Arguments
+/// - `conn`: The [`MysqlConnection`] to run the transaction on.
+/// - `work`: A closure doing the actual work.
+///
+/// # Returns
+/// Whatever the `work` returns.
+///
+/// # Errors
+/// This function errors if the `work` does, or if we failed to start the transaction or to take
+/// the lock.
+pub(crate) fn exclusive_transaction<R, E>(conn: &mut MysqlConnection, work: impl FnOnce(&mut MysqlConnection) -> Result<R, E>) -> Result<R, E>
+where
+    E: From<diesel::result::Error>,
+{
+    use crate::schema::write_lock::dsl as wl;
+
+    conn.transaction(|conn| {
+        wl::write_lock.find(WRITE_LOCK_ID).select(wl::id).for_update().first::<i32>(conn)?;
+        work(conn)
+    })
+}
+
+/// Runs the given work in a transaction that sees a single snapshot of the database.
+///
+/// Any transaction does, as InnoDB's default isolation level is `REPEATABLE READ`.
+///
+/// # Arguments
+/// - `conn`: The [`MysqlConnection`] to run the transaction on.
+/// - `work`: A closure doing the actual work.
+///
+/// # Returns
+/// Whatever the `work` returns.
+///
+/// # Errors
+/// This function errors if the `work` does, or if we failed to start the transaction.
+#[inline]
+pub(crate) fn snapshot_transaction<R, E>(conn: &mut MysqlConnection, work: impl FnOnce(&mut MysqlConnection) -> Result<R, E>) -> Result<R, E>
+where
+    E: From<diesel::result::Error>,
+{
+    conn.transaction(work)
+}
+
+
+
+/// Points the `current_active` pointer to the version in the given model.
+#[inline]
+pub(crate) fn upsert_current_active(conn: &mut MysqlConnection, model: &DbCurrentActive) -> QueryResult<usize> {
+    use crate::schema::current_active::dsl::current_active;
+    diesel::replace_into(current_active).values(model).execute(conn)
+}
+
+/// Adds the given template, replacing any template with the same name.
+#[inline]
+pub(crate) fn upsert_template(conn: &mut MysqlConnection, model: &DbTemplate) -> QueryResult<usize> {
+    use crate::schema::templates::dsl::templates;
+    diesel::replace_into(templates).values(model).execute(conn)
+}
+
+/// Adds the given revoked token, unless it already is.
+///
+/// # Returns
+/// The number of tokens revoked, i.e., `0` if it already was.
+#[inline]
+pub(crate) fn insert_revoked_token(conn: &mut MysqlConnection, model: &DbRevokedToken) -> QueryResult<usize> {
+    use crate::schema::revoked_tokens::dsl::revoked_tokens;
+    diesel::insert_or_ignore_into(revoked_tokens).values(model).execute(conn)
+}
+
+/// Revokes the tokens of the user in the given model, replacing any earlier revocation of theirs.
+#[inline]
+pub(crate) fn upsert_revoked_user(conn: &mut MysqlConnection, model: &DbRevokedUser) -> QueryResult<usize> {
+    use crate::schema::revoked_users::dsl::revoked_users;
+    diesel::replace_into(revoked_users).values(model).execute(conn)
+}
+
+/// Moves the cursor of the sink in the given model, creating it if it doesn't exist yet.
+#[inline]
+pub(crate) fn upsert_event_cursor(conn: &mut MysqlConnection, model: &DbEventCursor) -> QueryResult<usize> {
+    use crate::schema::event_cursors::dsl::event_cursors;
+    diesel::replace_into(event_cursors).values(model).execute(conn)
+}
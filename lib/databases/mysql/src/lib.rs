@@ -0,0 +1,34 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:31:56
+//  Last edited:
+//    16 Oct 2026, 18:31:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the `DatabaseConnector` for a MySQL (or MariaDB) backend.
+//
+
+// Declare modules
+mod connection {
+    diesel_common::connection!();
+}
+mod databaseconn;
+mod dialect;
+mod models {
+    diesel_common::models!();
+}
+mod schema;
+
+// Import some of it
+pub use connection::DieselConnection as MySQLConnection;
+pub use databaseconn::*;
+pub use diesel_common::ConnectionError;
+
+
+// Optionally import the migrations
+#[cfg(feature = "embedded-migrations")]
+pub const MIGRATIONS: diesel_migrations::EmbeddedMigrations = diesel_migrations::embed_migrations!("./migrations");
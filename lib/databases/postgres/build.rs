@@ -0,0 +1,22 @@
+//  BUILD.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:06:42
+//  Last edited:
+//    16 Oct 2026, 18:06:42
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Embeds the migration in Rust code such that it can be imported
+//!   through the `migrations` module.
+//
+
+
+/***** ENTRYPOINT *****/
+fn main() {
+    // Emit that rebuilding on migrations is necessary
+    // See https://docs.rs/migrations_macros/2.2.0/migrations_macros/macro.embed_migrations.html#automatic-rebuilds
+    println!("cargo:rerun-if-changed=./migrations");
+}
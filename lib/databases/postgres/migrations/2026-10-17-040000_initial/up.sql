@@ -0,0 +1,124 @@
+-- Your SQL goes here
+CREATE TABLE "policies"(
+	"version" BIGINT NOT NULL PRIMARY KEY,
+	"name" TEXT NOT NULL,
+	"description" TEXT NOT NULL,
+	"creator" TEXT NOT NULL,
+	"created_at" TIMESTAMP NOT NULL,
+	"content" TEXT NOT NULL,
+	"language" TEXT NOT NULL DEFAULT 'UNKNOWN',
+	"branch" TEXT NOT NULL DEFAULT 'main',
+	"parent" BIGINT,
+	"promoted_from" BIGINT,
+	"content_hash" TEXT,
+	"schema" TEXT,
+	"origin_store" TEXT,
+	"origin_version" BIGINT,
+	"origin_creator" TEXT,
+	"origin_created_at" TIMESTAMP,
+	"verified_identity" TEXT,
+	"verified_digest" TEXT,
+	"verified_at" TIMESTAMP,
+	"creator_issuer" TEXT
+);
+
+CREATE INDEX "policies_created_at" ON "policies"("created_at");
+CREATE INDEX "policies_creator" ON "policies"("creator");
+
+CREATE TABLE "active_version"(
+	"version" BIGINT NOT NULL,
+	"activated_on" TIMESTAMP NOT NULL,
+	"activated_by" TEXT NOT NULL,
+	"deactivated_on" TIMESTAMP,
+	"deactivated_by" TEXT,
+	PRIMARY KEY("version", "activated_on")
+);
+
+CREATE INDEX "active_version_activated_on" ON "active_version"("activated_on");
+
+-- Points to the activation in `active_version` that is currently in effect, if any. Only ever has
+-- (at most) one row, such that finding the active version is a primary-key read.
+CREATE TABLE "current_active"(
+	"id" INTEGER NOT NULL PRIMARY KEY CHECK ("id" = 1),
+	"version" BIGINT NOT NULL,
+	"activated_on" TIMESTAMP NOT NULL
+);
+
+CREATE TABLE "templates"(
+	"name" TEXT NOT NULL PRIMARY KEY,
+	"description" TEXT NOT NULL,
+	"language" TEXT NOT NULL,
+	"parameters" TEXT NOT NULL,
+	"skeleton" TEXT NOT NULL,
+	"creator" TEXT NOT NULL,
+	"created_at" TIMESTAMP NOT NULL
+);
+
+CREATE TABLE "branches"(
+	"name" TEXT NOT NULL PRIMARY KEY,
+	"head" BIGINT,
+	"base" BIGINT,
+	"creator" TEXT NOT NULL,
+	"created_at" TIMESTAMP NOT NULL
+);
+
+CREATE TABLE "schemas"(
+	"name" TEXT NOT NULL PRIMARY KEY,
+	"description" TEXT NOT NULL,
+	"schema" TEXT NOT NULL,
+	"creator" TEXT NOT NULL,
+	"created_at" TIMESTAMP NOT NULL
+);
+
+CREATE TABLE "audit_log"(
+	"seq" BIGINT NOT NULL PRIMARY KEY,
+	"timestamp" TIMESTAMP NOT NULL,
+	"user_id" TEXT NOT NULL,
+	"action" TEXT NOT NULL,
+	"prev_hash" TEXT,
+	"hash" TEXT NOT NULL,
+	"client" TEXT
+);
+
+CREATE TABLE "decisions"(
+	"id" TEXT NOT NULL PRIMARY KEY,
+	"version" BIGINT NOT NULL REFERENCES "policies"("version"),
+	"reporter" TEXT NOT NULL,
+	"recorded_at" TIMESTAMP NOT NULL
+);
+
+CREATE INDEX "decisions_version" ON "decisions"("version");
+
+CREATE TABLE "keys"(
+	"id" TEXT NOT NULL PRIMARY KEY,
+	"kind" TEXT NOT NULL,
+	"material" TEXT NOT NULL,
+	"creator" TEXT NOT NULL,
+	"created_at" TIMESTAMP NOT NULL,
+	"revoked_at" TIMESTAMP
+);
+
+CREATE TABLE "revoked_tokens"(
+	"jti" TEXT NOT NULL PRIMARY KEY,
+	"creator" TEXT NOT NULL,
+	"created_at" TIMESTAMP NOT NULL
+);
+
+CREATE TABLE "revoked_users"(
+	"user_id" TEXT NOT NULL PRIMARY KEY,
+	"revoked_before" TIMESTAMP NOT NULL,
+	"creator" TEXT NOT NULL,
+	"created_at" TIMESTAMP NOT NULL
+);
+
+CREATE TABLE "event_cursors"(
+	"sink" TEXT NOT NULL PRIMARY KEY,
+	"seq" BIGINT NOT NULL,
+	"updated_at" TIMESTAMP NOT NULL
+);
+
+CREATE TABLE "provenance"(
+	"version" BIGINT NOT NULL PRIMARY KEY REFERENCES "policies"("version"),
+	"client" TEXT,
+	"request_id" TEXT
+);
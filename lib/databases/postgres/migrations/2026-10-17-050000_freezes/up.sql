@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE "policies" ADD COLUMN "frozen_reason" TEXT;
+ALTER TABLE "policies" ADD COLUMN "frozen_by" TEXT;
+ALTER TABLE "policies" ADD COLUMN "frozen_by_issuer" TEXT;
+ALTER TABLE "policies" ADD COLUMN "frozen_at" TIMESTAMP;
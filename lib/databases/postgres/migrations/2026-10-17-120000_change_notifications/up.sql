@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE FUNCTION "notify_change"() RETURNS TRIGGER AS $$
+BEGIN
+	PERFORM pg_notify('policy_store_changes', NEW."seq"::TEXT);
+	RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER "audit_log_notify_change" AFTER INSERT ON "audit_log"
+	FOR EACH ROW EXECUTE FUNCTION "notify_change"();
@@ -0,0 +1,122 @@
+//  DIALECT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:06:42
+//  Last edited:
+//    16 Oct 2026, 18:06:42
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the parts of the shared diesel connection that are
+//!   specific to PostgreSQL.
+//
+
+use diesel::pg::Pg;
+use diesel::sql_types::BigInt;
+use diesel::{Connection as _, PgConnection, QueryResult, RunQueryDsl as _};
+
+use crate::models::{DbCurrentActive, DbEventCursor, DbRevokedToken, DbRevokedUser, DbTemplate};
+
+
+/***** CONSTANTS *****/
+/// The key of the advisory lock held by every transaction that writes to the database.
+const WRITE_LOCK: i64 = 0x706F_6C69_6379_0002;
+
+
+
+
+
+/***** LIBRARY *****/
+/// The connection type of this backend.
+pub(crate) type RawConnection = PgConnection;
+
+/// The diesel backend of this backend.
+pub(crate) type Backend = Pg;
+
+
+
+/// Runs the given work in a transaction that holds the [`WRITE_LOCK`].
+///
+/// This is the equivalent of SQLite's exclusive transactions, but then for every store sharing
+/// the database.
+///
+/// # Arguments
+/// - `conn`: The [`PgConnection`] to run the transaction on.
+/// - `work`: A closure doing the actual work.
+///
+/// # Returns
+/// Whatever the `work` returns.
+///
+/// # Errors
+/// This function errors if the `work` does, or if we failed to start the transaction or to take
+/// the lock.
+pub(crate) fn exclusive_transaction<R, E>(conn: &mut PgConnection, work: impl FnOnce(&mut PgConnection) -> Result<R, E>) -> Result<R, E>
+where
+    E: From<diesel::result::Error>,
+{
+    conn.transaction(|conn| {
+        diesel::sql_query("SELECT pg_advisory_xact_lock($1)").bind::<BigInt, _>(WRITE_LOCK).execute(conn)?;
+        work(conn)
+    })
+}
+
+/// Runs the given work in a read-only transaction that sees a single snapshot of the database.
+///
+/// # Arguments
+/// - `conn`: The [`PgConnection`] to run the transaction on.
+/// - `work`: A closure doing the actual work.
+///
+/// # Returns
+/// Whatever the `work` returns.
+///
+/// # Errors
+/// This function errors if the `work` does, or if we failed to start the transaction.
+#[inline]
+pub(crate) fn snapshot_transaction<R, E>(conn: &mut PgConnection, work: impl FnOnce(&mut PgConnection) -> Result<R, E>) -> Result<R, E>
+where
+    E: From<diesel::result::Error>,
+{
+    conn.build_transaction().repeatable_read().read_only().run(work)
+}
+
+
+
+/// Points the `current_active` pointer to the version in the given model.
+#[inline]
+pub(crate) fn upsert_current_active(conn: &mut PgConnection, model: &DbCurrentActive) -> QueryResult<usize> {
+    use crate::schema::current_active::dsl::{current_active, id};
+    diesel::insert_into(current_active).values(model).on_conflict(id).do_update().set(model).execute(conn)
+}
+
+/// Adds the given template, replacing any template with the same name.
+#[inline]
+pub(crate) fn upsert_template(conn: &mut PgConnection, model: &DbTemplate) -> QueryResult<usize> {
+    use crate::schema::templates::dsl::{name, templates};
+    diesel::insert_into(templates).values(model).on_conflict(name).do_update().set(model).execute(conn)
+}
+
+/// Adds the given revoked token, unless it already is.
+///
+/// # Returns
+/// The number of tokens revoked, i.e., `0` if it already was.
+#[inline]
+pub(crate) fn insert_revoked_token(conn: &mut PgConnection, model: &DbRevokedToken) -> QueryResult<usize> {
+    use crate::schema::revoked_tokens::dsl::revoked_tokens;
+    diesel::insert_into(revoked_tokens).values(model).on_conflict_do_nothing().execute(conn)
+}
+
+/// Revokes the tokens of the user in the given model, replacing any earlier revocation of theirs.
+#[inline]
+pub(crate) fn upsert_revoked_user(conn: &mut PgConnection, model: &DbRevokedUser) -> QueryResult<usize> {
+    use crate::schema::revoked_users::dsl::{revoked_users, user_id};
+    diesel::insert_into(revoked_users).values(model).on_conflict(user_id).do_update().set(model).execute(conn)
+}
+
+/// Moves the cursor of the sink in the given model, creating it if it doesn't exist yet.
+#[inline]
+pub(crate) fn upsert_event_cursor(conn: &mut PgConnection, model: &DbEventCursor) -> QueryResult<usize> {
+    use crate::schema::event_cursors::dsl::{event_cursors, sink};
+    diesel::insert_into(event_cursors).values(model).on_conflict(sink).do_update().set(model).execute(conn)
+}
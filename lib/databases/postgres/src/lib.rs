@@ -0,0 +1,36 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:06:42
+//  Last edited:
+//    17 Oct 2026, 01:39:06
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the `DatabaseConnector` for a PostgreSQL backend.
+//
+
+// Declare modules
+mod connection {
+    diesel_common::connection!();
+}
+mod databaseconn;
+mod dialect;
+mod listener;
+mod models {
+    diesel_common::models!();
+}
+mod schema;
+
+// Import some of it
+pub use connection::DieselConnection as PostgresConnection;
+pub use databaseconn::*;
+pub use diesel_common::ConnectionError;
+pub use listener::*;
+
+
+// Optionally import the migrations
+#[cfg(feature = "embedded-migrations")]
+pub const MIGRATIONS: diesel_migrations::EmbeddedMigrations = diesel_migrations::embed_migrations!("./migrations");
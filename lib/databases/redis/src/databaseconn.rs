@@ -0,0 +1,1914 @@
+//  DATABASECONN.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:49:25
+//  Last edited:
+//    17 Oct 2026, 02:16:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the actual [`DatabaseConnector`].
+//
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use redis::aio::{ConnectionLike, ConnectionManager, MultiplexedConnection};
+use redis::{AsyncCommands as _, Client, Pipeline, RedisError, ToRedisArgs};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specifications::DatabaseConnector;
+use specifications::audit::{AuditAction, AuditRecord, ChainError, Change, verify_chain};
+use specifications::canonical::{content_hash, to_canonical_string};
+use specifications::consistency::Finding;
+use specifications::databaseconn::{ClientError, DatabaseConnection};
+use specifications::decision::Decision;
+use specifications::key::{AttachedKey, Key};
+use specifications::metadata::{AccessContext, Activation, AttachedMetadata, Branch, Freeze, MAX_VERSION, Metadata, Origin, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::schema::{AttachedSchema, Schema};
+use specifications::template::{AttachedTemplate, Template};
+use specifications::userid::{UserIdError, UserIdFormat};
+use thiserror::Error;
+use tracing::{Level, debug, info, span};
+
+
+/***** CONSTANTS *****/
+/// The prefix of all keys used by a [`RedisDatabase`] unless configured otherwise.
+pub const DEFAULT_PREFIX: &str = "policy-store";
+
+/// The key of the counter holding the number of the latest version allocated.
+const NEXT_VERSION_KEY: &str = "next_version";
+/// The key of the hash with the metadata of every version, by number.
+const VERSIONS_KEY: &str = "versions";
+/// The key of the hash with the content of every version, by number.
+const CONTENTS_KEY: &str = "contents";
+/// The key holding the number of the active version. Absent if no version is active.
+const ACTIVE_KEY: &str = "active";
+/// The key of the list with the history of activations, oldest first.
+const ACTIVATIONS_KEY: &str = "activations";
+/// The key of the hash with the branches, by name.
+const BRANCHES_KEY: &str = "branches";
+/// The key of the hash with the templates, by name.
+const TEMPLATES_KEY: &str = "templates";
+/// The key of the hash with the schemas, by name.
+const SCHEMAS_KEY: &str = "schemas";
+/// The key of the hash with the decisions, by identifier.
+const DECISIONS_KEY: &str = "decisions";
+/// The key of the hash with the verification keys, by identifier.
+const KEYS_KEY: &str = "keys";
+/// The key of the list with the token revocations.
+const REVOCATIONS_KEY: &str = "revocations";
+/// The key of the hash with the event cursors, by sink name.
+const EVENT_CURSORS_KEY: &str = "event_cursors";
+/// The key of the list with the audit log, ordered by sequence number.
+const AUDIT_LOG_KEY: &str = "audit_log";
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the [`RedisDatabase`].
+#[derive(Debug, Error)]
+pub enum DatabaseError {
+    /// The given URL is not a valid Redis URL.
+    #[error("Invalid Redis URL {url:?}")]
+    Client {
+        url: String,
+        #[source]
+        err: RedisError,
+    },
+    /// Failed to connect to the Redis server.
+    #[error("Failed to connect to backend Redis server {url:?}")]
+    Connect {
+        url: String,
+        #[source]
+        err: RedisError,
+    },
+    /// The ID of the connecting user does not adhere to the configured [`UserIdFormat`].
+    #[error("Refusing to connect to backend Redis server {url:?} with illegal user ID")]
+    IllegalUserId {
+        url: String,
+        #[source]
+        err: UserIdError,
+    },
+    /// Failed to read the Redis server while checking its consistency.
+    #[error("Failed to verify the consistency of backend Redis server {url:?}")]
+    Verify {
+        url: String,
+        #[source]
+        err: ConnectionError,
+    },
+}
+
+/// Defines errors originating from the [`RedisConnection`].
+#[derive(Debug, Error)]
+pub enum ConnectionError {
+    /// The active key does not contain a version number.
+    #[error("Redis key {key:?} does not contain a version number (got {raw:?})")]
+    ActiveParse { key: String, raw: String },
+    /// The audit log failed verification, so it cannot safely be rewritten.
+    #[error("Audit log in Redis key {key:?} failed verification")]
+    AuditChain {
+        key: String,
+        #[source]
+        err: ChainError,
+    },
+    /// Failed to run a command on a particular key.
+    #[error("Failed to run command on Redis key {key:?}")]
+    Command {
+        key: String,
+        #[source]
+        err: RedisError,
+    },
+    /// Failed to commit a transaction.
+    #[error("Failed to commit transaction to Redis")]
+    Commit {
+        #[source]
+        err: RedisError,
+    },
+    /// Failed to open a dedicated connection to run a transaction on.
+    #[error("Failed to open connection to Redis for transaction")]
+    Connect {
+        #[source]
+        err: RedisError,
+    },
+    /// Failed to serialize the given content as JSON.
+    #[error("Failed to serialize the content of policy {name:?} as JSON")]
+    ContentSerialize {
+        name: String,
+        #[source]
+        err:  serde_json::Error,
+    },
+    /// Failed to parse a stored value as JSON.
+    #[error("Failed to parse value in Redis key {key:?} as JSON")]
+    Parse {
+        key: String,
+        #[source]
+        err: serde_json::Error,
+    },
+    /// Failed to serialize a value to store as JSON.
+    #[error("Failed to serialize value for Redis key {key:?} as JSON")]
+    Serialize {
+        key: String,
+        #[source]
+        err: serde_json::Error,
+    },
+    /// Attempted to activate a version that does not exist.
+    #[error("Cannot activate version {version} in Redis, because it does not exist")]
+    UnknownVersion { version: u64 },
+    /// Attempted to activate a version that is frozen.
+    #[error("Cannot activate version {version} in Redis, because it is frozen")]
+    VersionFrozen { version: u64 },
+    /// No more versions can be allocated.
+    #[error("Cannot allocate a new version in Redis, because version {MAX_VERSION} has been reached")]
+    VersionsExhausted,
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Strips a [`User`] down to what is recorded about them.
+///
+/// The roles of a user are granted per access, so they are not stored with whatever the user
+/// did; just like in any other backend.
+///
+/// # Arguments
+/// - `user`: The [`User`] to record.
+///
+/// # Returns
+/// A copy of the `user` without any roles.
+#[inline]
+fn recorded(user: &User) -> User { User { id: user.id.clone(), name: user.name.clone(), issuer: user.issuer.clone(), roles: Vec::new() } }
+
+/// Hashes the content of a policy the same way every other backend does.
+///
+/// # Arguments
+/// - `name`: The name of the policy. Only used for debugging.
+/// - `content`: The content to hash.
+///
+/// # Returns
+/// The hash of the canonical JSON of the `content`.
+///
+/// # Errors
+/// This function errors if the `content` could not be serialized as JSON.
+#[inline]
+fn hash_content<C: Serialize>(name: &str, content: &C) -> Result<String, ConnectionError> {
+    match to_canonical_string(content) {
+        Ok(canonical) => Ok(content_hash(&canonical)),
+        Err(err) => Err(ConnectionError::ContentSerialize { name: name.into(), err }),
+    }
+}
+
+/// Replaces a user ID with a pseudonym, if it is the one to replace.
+///
+/// # Arguments
+/// - `field`: The stored ID to (maybe) replace.
+/// - `id`: The ID of the user to replace.
+/// - `pseudonym`: The ID to replace it with.
+///
+/// # Returns
+/// `1` if the `field` was replaced, or `0` otherwise, such that it can be counted.
+#[inline]
+fn pseudonymize(field: &mut String, id: &str, pseudonym: &str) -> u64 {
+    if field == id {
+        *field = pseudonym.into();
+        1
+    } else {
+        0
+    }
+}
+
+/// Parses a value read from Redis as JSON.
+///
+/// # Arguments
+/// - `key`: The key the value was read from. Only used for debugging.
+/// - `raw`: The value to parse.
+///
+/// # Returns
+/// The parsed value.
+///
+/// # Errors
+/// This function errors if the `raw` value is not valid JSON for `T`.
+#[inline]
+fn from_json<T: DeserializeOwned>(key: &str, raw: &str) -> Result<T, ConnectionError> {
+    serde_json::from_str(raw).map_err(|err| ConnectionError::Parse { key: key.into(), err })
+}
+
+/// Serializes a value to write to Redis as JSON.
+///
+/// # Arguments
+/// - `key`: The key the value will be written to. Only used for debugging.
+/// - `value`: The value to serialize.
+///
+/// # Returns
+/// The serialized value.
+///
+/// # Errors
+/// This function errors if the `value` could not be serialized.
+#[inline]
+fn to_json<T: Serialize>(key: &str, value: &T) -> Result<String, ConnectionError> {
+    serde_json::to_string(value).map_err(|err| ConnectionError::Serialize { key: key.into(), err })
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Everything but the content of a version as it is stored in a [`RedisDatabase`].
+#[derive(Deserialize, Serialize)]
+struct StoredMetadata {
+    /// The metadata of the version.
+    metadata:   Metadata,
+    /// The address of the client from which the version was written, if known.
+    #[serde(default)]
+    client:     Option<IpAddr>,
+    /// The ID of the request in which the version was written, if known.
+    #[serde(default)]
+    request_id: Option<String>,
+}
+
+/// Knows under which keys everything is stored in a [`RedisDatabase`], and how to read and write
+/// them.
+///
+/// Its functions do not coordinate with other stores; that is left to the [`RedisConnection`].
+#[derive(Clone)]
+struct Layout {
+    /// The prefix of every key.
+    prefix: String,
+}
+impl Layout {
+    /// Returns the full name of one of the keys.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the key without prefix (e.g., [`VERSIONS_KEY`]).
+    ///
+    /// # Returns
+    /// The name of the key in Redis.
+    #[inline]
+    fn key(&self, name: &str) -> String { format!("{}:{name}", self.prefix) }
+
+    /// Reads and parses a field of a hash.
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read with.
+    /// - `name`: The name of the hash without prefix.
+    /// - `field`: The field to read.
+    ///
+    /// # Returns
+    /// The parsed value of the field, or [`None`] if it does not exist.
+    ///
+    /// # Errors
+    /// This function errors if the field could not be read or parsed.
+    async fn hget<T: DeserializeOwned>(
+        &self,
+        conn: &mut (impl Send + ConnectionLike),
+        name: &str,
+        field: impl Send + Sync + ToRedisArgs,
+    ) -> Result<Option<T>, ConnectionError> {
+        let key: String = self.key(name);
+        let raw: Option<String> = conn.hget(&key, field).await.map_err(|err| ConnectionError::Command { key: key.clone(), err })?;
+        raw.map(|raw| from_json(&key, &raw)).transpose()
+    }
+
+    /// Reads and parses all fields of a hash.
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read with.
+    /// - `name`: The name of the hash without prefix.
+    ///
+    /// # Returns
+    /// The parsed values, by field.
+    ///
+    /// # Errors
+    /// This function errors if the hash could not be read or any of its values could not be
+    /// parsed.
+    async fn hgetall<T: DeserializeOwned>(&self, conn: &mut (impl Send + ConnectionLike), name: &str) -> Result<HashMap<String, T>, ConnectionError> {
+        let key: String = self.key(name);
+        let raw: HashMap<String, String> = conn.hgetall(&key).await.map_err(|err| ConnectionError::Command { key: key.clone(), err })?;
+        raw.into_iter().map(|(field, raw)| Ok((field, from_json(&key, &raw)?))).collect()
+    }
+
+    /// Checks whether a hash has a particular field.
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read with.
+    /// - `name`: The name of the hash without prefix.
+    /// - `field`: The field to look for.
+    ///
+    /// # Returns
+    /// True if the field exists, or false otherwise.
+    ///
+    /// # Errors
+    /// This function errors if the hash could not be read.
+    async fn hexists(
+        &self,
+        conn: &mut (impl Send + ConnectionLike),
+        name: &str,
+        field: impl Send + Sync + ToRedisArgs,
+    ) -> Result<bool, ConnectionError> {
+        let key: String = self.key(name);
+        conn.hexists(&key, field).await.map_err(|err| ConnectionError::Command { key, err })
+    }
+
+    /// Reads and parses (the tail of) a list.
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read with.
+    /// - `name`: The name of the list without prefix.
+    /// - `start`: The index of the first element to read.
+    ///
+    /// # Returns
+    /// The parsed elements, in order.
+    ///
+    /// # Errors
+    /// This function errors if the list could not be read or any of its elements could not be
+    /// parsed.
+    async fn lrange<T: DeserializeOwned>(
+        &self,
+        conn: &mut (impl Send + ConnectionLike),
+        name: &str,
+        start: isize,
+    ) -> Result<Vec<T>, ConnectionError> {
+        let key: String = self.key(name);
+        let raw: Vec<String> = conn.lrange(&key, start, -1).await.map_err(|err| ConnectionError::Command { key: key.clone(), err })?;
+        raw.iter().map(|raw| from_json(&key, raw)).collect()
+    }
+
+    /// Reads and parses the last element of a list.
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read with.
+    /// - `name`: The name of the list without prefix.
+    ///
+    /// # Returns
+    /// The parsed element, or [`None`] if the list is empty.
+    ///
+    /// # Errors
+    /// This function errors if the list could not be read or its element could not be parsed.
+    async fn last<T: DeserializeOwned>(&self, conn: &mut (impl Send + ConnectionLike), name: &str) -> Result<Option<T>, ConnectionError> {
+        let key: String = self.key(name);
+        let raw: Option<String> = conn.lindex(&key, -1).await.map_err(|err| ConnectionError::Command { key: key.clone(), err })?;
+        raw.map(|raw| from_json(&key, &raw)).transpose()
+    }
+
+    /// Reads the active version.
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read with.
+    ///
+    /// # Returns
+    /// The number of the active version, or [`None`] if no version is active.
+    ///
+    /// # Errors
+    /// This function errors if the key could not be read or does not contain a number.
+    async fn active(&self, conn: &mut (impl Send + ConnectionLike)) -> Result<Option<u64>, ConnectionError> {
+        let key: String = self.key(ACTIVE_KEY);
+        let raw: Option<String> = conn.get(&key).await.map_err(|err| ConnectionError::Command { key: key.clone(), err })?;
+        match raw {
+            Some(raw) => match raw.parse::<u64>() {
+                Ok(version) => Ok(Some(version)),
+                Err(_) => Err(ConnectionError::ActiveParse { key, raw }),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieves the activation currently in effect.
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read with.
+    ///
+    /// # Returns
+    /// The [`Activation`] of the active version, or [`None`] if no version is active (or if the
+    /// active key was changed without recording an activation).
+    ///
+    /// # Errors
+    /// This function errors if the active version or the activations could not be read.
+    async fn current_activation(&self, conn: &mut (impl Send + ConnectionLike)) -> Result<Option<Activation>, ConnectionError> {
+        // Read both at once, such that they can't be changed in between
+        let (active, activations): (String, String) = (self.key(ACTIVE_KEY), self.key(ACTIVATIONS_KEY));
+        let (version, last): (Option<String>, Option<String>) = redis::pipe()
+            .atomic()
+            .get(&active)
+            .lindex(&activations, -1)
+            .query_async(conn)
+            .await
+            .map_err(|err| ConnectionError::Command { key: active.clone(), err })?;
+        let Some(version) = version else { return Ok(None) };
+        let version: u64 = version.parse().map_err(|_| ConnectionError::ActiveParse { key: active, raw: version })?;
+        let last: Option<Activation> = last.map(|last| from_json(&activations, &last)).transpose()?;
+        Ok(last.filter(|activation| activation.version == version))
+    }
+
+    /// Determines the number of the next version to add.
+    ///
+    /// Should be called while [watching](Layout::watch()) the [`NEXT_VERSION_KEY`], as the
+    /// counter is only bumped when the version is [added](Layout::add()).
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read with.
+    ///
+    /// # Returns
+    /// The next version number.
+    ///
+    /// # Errors
+    /// This function errors if the counter could not be read or if [`MAX_VERSION`] has been
+    /// reached.
+    async fn next_version(&self, conn: &mut (impl Send + ConnectionLike)) -> Result<u64, ConnectionError> {
+        let key: String = self.key(NEXT_VERSION_KEY);
+        let latest: Option<u64> = conn.get(&key).await.map_err(|err| ConnectionError::Command { key, err })?;
+        let latest: u64 = latest.unwrap_or(0);
+        if latest >= MAX_VERSION {
+            return Err(ConnectionError::VersionsExhausted);
+        }
+        Ok(latest + 1)
+    }
+
+    /// Queues writing a field of a hash.
+    ///
+    /// # Arguments
+    /// - `pipe`: The transaction to queue the write in.
+    /// - `name`: The name of the hash without prefix.
+    /// - `field`: The field to write.
+    /// - `value`: The new value of the field.
+    ///
+    /// # Errors
+    /// This function errors if the `value` could not be serialized.
+    fn hset<T: Serialize>(&self, pipe: &mut Pipeline, name: &str, field: impl ToRedisArgs, value: &T) -> Result<(), ConnectionError> {
+        let key: String = self.key(name);
+        let raw: String = to_json(&key, value)?;
+        pipe.hset(key, field, raw).ignore();
+        Ok(())
+    }
+
+    /// Queues replacing a list as a whole, e.g., after removing or changing some elements.
+    ///
+    /// # Arguments
+    /// - `pipe`: The transaction to queue the write in.
+    /// - `name`: The name of the list without prefix.
+    /// - `elems`: The new elements of the list.
+    ///
+    /// # Errors
+    /// This function errors if any of the `elems` could not be serialized.
+    fn replace_list<T: Serialize>(&self, pipe: &mut Pipeline, name: &str, elems: &[T]) -> Result<(), ConnectionError> {
+        let key: String = self.key(name);
+        let raw: Vec<String> = elems.iter().map(|elem| to_json(&key, elem)).collect::<Result<_, _>>()?;
+        pipe.del(&key).ignore();
+        // NOTE: Redis refuses to push nothing
+        if !raw.is_empty() {
+            pipe.rpush(key, raw).ignore();
+        }
+        Ok(())
+    }
+
+    /// Queues appending an element to a list.
+    ///
+    /// # Arguments
+    /// - `pipe`: The transaction to queue the write in.
+    /// - `name`: The name of the list without prefix.
+    /// - `elem`: The element to append.
+    ///
+    /// # Errors
+    /// This function errors if the `elem` could not be serialized.
+    fn rpush<T: Serialize>(&self, pipe: &mut Pipeline, name: &str, elem: &T) -> Result<(), ConnectionError> {
+        let key: String = self.key(name);
+        let raw: String = to_json(&key, elem)?;
+        pipe.rpush(key, raw).ignore();
+        Ok(())
+    }
+
+    /// Queues appending a version to the branch in its metadata.
+    ///
+    /// Creates the branch if it doesn't exist yet, sets the version's parent to the previous head
+    /// of the branch and bumps the version counter. Should be called while
+    /// [watching](Layout::watch()) the [`NEXT_VERSION_KEY`] and the [`BRANCHES_KEY`].
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read the branch with.
+    /// - `pipe`: The transaction to queue the writes in.
+    /// - `stored`: The [`StoredMetadata`] of the version to add. Its version number should come
+    ///   from [`Layout::next_version()`].
+    /// - `content`: The content of the version, already serialized as JSON.
+    ///
+    /// # Errors
+    /// This function errors if the branch could not be read or the version could not be
+    /// serialized.
+    async fn add(
+        &self,
+        conn: &mut (impl Send + ConnectionLike),
+        pipe: &mut Pipeline,
+        mut stored: StoredMetadata,
+        content: String,
+    ) -> Result<(), ConnectionError> {
+        let name: String = stored.metadata.attached.branch.clone();
+        let mut branch: Branch = match self.hget(conn, BRANCHES_KEY, &name).await? {
+            Some(branch) => branch,
+            None => {
+                debug!("Creating new branch {name:?}...");
+                Branch {
+                    name:    name.clone(),
+                    head:    None,
+                    base:    None,
+                    created: stored.metadata.created,
+                    creator: stored.metadata.creator.clone(),
+                }
+            },
+        };
+        let version: u64 = stored.metadata.version;
+        stored.metadata.parent = branch.head.replace(version);
+
+        pipe.set(self.key(NEXT_VERSION_KEY), version).ignore();
+        self.hset(pipe, VERSIONS_KEY, version, &stored)?;
+        pipe.hset(self.key(CONTENTS_KEY), version, content).ignore();
+        self.hset(pipe, BRANCHES_KEY, &name, &branch)
+    }
+
+    /// Queues appending a record to the audit log.
+    ///
+    /// Should be called while [watching](Layout::watch()) the [`AUDIT_LOG_KEY`], in the same
+    /// transaction as the change it records.
+    ///
+    /// # Arguments
+    /// - `pipe`: The transaction to queue the write in.
+    /// - `prev`: The last record currently in the log, if any.
+    /// - `user`: The ID of the user who made the change.
+    /// - `client`: The address of the client from which the change was made, if known.
+    /// - `action`: The [`AuditAction`] describing the change.
+    ///
+    /// # Errors
+    /// This function errors if the record could not be serialized.
+    fn append_audit(
+        &self,
+        pipe: &mut Pipeline,
+        prev: Option<&AuditRecord>,
+        user: &str,
+        client: Option<IpAddr>,
+        action: AuditAction,
+    ) -> Result<(), ConnectionError> {
+        let record = AuditRecord::new(prev, user, client, action);
+        debug!("Appending audit record {}...", record.seq);
+        self.rpush(pipe, AUDIT_LOG_KEY, &record)
+    }
+
+    /// Watches keys for changes by others, such that a transaction that reads them is only
+    /// committed if they are left alone until then.
+    ///
+    /// # Arguments
+    /// - `conn`: The dedicated connection to run the transaction on.
+    /// - `names`: The names of the keys to watch, without prefix.
+    ///
+    /// # Errors
+    /// This function errors if the keys could not be watched.
+    async fn watch(&self, conn: &mut MultiplexedConnection, names: &[&str]) -> Result<(), ConnectionError> {
+        let keys: Vec<String> = names.iter().map(|name| self.key(name)).collect();
+        redis::cmd("WATCH").arg(&keys).query_async(conn).await.map_err(|err| ConnectionError::Command { key: keys.join(" "), err })
+    }
+
+    /// Commits a transaction, unless any of the [watched](Layout::watch()) keys changed.
+    ///
+    /// # Arguments
+    /// - `conn`: The dedicated connection to run the transaction on.
+    /// - `pipe`: The (atomic) transaction to commit.
+    ///
+    /// # Returns
+    /// True if the transaction was committed, or false if it has to be retried.
+    ///
+    /// # Errors
+    /// This function errors if the transaction could not be sent.
+    async fn commit(&self, conn: &mut MultiplexedConnection, pipe: &Pipeline) -> Result<bool, ConnectionError> {
+        let res: Option<()> = pipe.query_async(conn).await.map_err(|err| ConnectionError::Commit { err })?;
+        if res.is_none() {
+            debug!("Transaction conflicted with a concurrent change; retrying...");
+        }
+        Ok(res.is_some())
+    }
+
+    /// Checks the invariants of the stored policies.
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to read with.
+    ///
+    /// # Returns
+    /// A list of [`Finding`]s describing every violated invariant.
+    ///
+    /// # Errors
+    /// This function errors if anything could not be read.
+    async fn verify<C: DeserializeOwned>(&self, conn: &mut (impl Send + ConnectionLike)) -> Result<Vec<Finding>, ConnectionError> {
+        // Check the versions themselves
+        let versions: HashMap<String, StoredMetadata> = self.hgetall(conn, VERSIONS_KEY).await?;
+        let key: String = self.key(CONTENTS_KEY);
+        let contents: HashMap<String, String> = conn.hgetall(&key).await.map_err(|err| ConnectionError::Command { key, err })?;
+        let mut versions: Vec<(u64, StoredMetadata)> =
+            versions.into_iter().filter_map(|(version, stored)| version.parse::<u64>().ok().map(|version| (version, stored))).collect();
+        versions.sort_unstable_by_key(|(version, _)| *version);
+        let mut findings: Vec<Finding> = Vec::new();
+        let mut present: HashSet<u64> = HashSet::new();
+        for (version, stored) in versions {
+            present.insert(version);
+            for parent in stored.metadata.parent.into_iter().chain(stored.metadata.promoted_from) {
+                if parent >= version {
+                    findings.push(Finding::NonMonotoneVersion { version, parent });
+                }
+            }
+
+            // Values that aren't even JSON are found rather than failed on, as they are likely edited by hand
+            let content: Value = match contents.get(&version.to_string()).map(|raw| serde_json::from_str(raw)) {
+                Some(Ok(content)) => content,
+                Some(Err(err)) => {
+                    findings.push(Finding::InvalidContent { version, reason: err.to_string() });
+                    continue;
+                },
+                None => {
+                    findings.push(Finding::InvalidContent { version, reason: "Content is missing".into() });
+                    continue;
+                },
+            };
+
+            // NOTE: Hashed the same way as when adding, which can't fail for values
+            let actual: String = content_hash(&to_canonical_string(&content).unwrap_or_default());
+            match stored.metadata.hash {
+                Some(expected) if expected != actual => findings.push(Finding::HashMismatch { version, expected, actual }),
+                Some(_) => {},
+                None => findings.push(Finding::MissingHash { version }),
+            }
+            if let Err(err) = serde_json::from_value::<C>(content) {
+                findings.push(Finding::InvalidContent { version, reason: err.to_string() });
+            }
+        }
+
+        // Then check whatever refers to them
+        if let Some(active) = self.active(conn).await? {
+            if !present.contains(&active) {
+                findings.push(Finding::DanglingActive { version: active });
+            }
+        }
+        let activations: Vec<Activation> = self.lrange(conn, ACTIVATIONS_KEY, 0).await?;
+        for activation in activations {
+            if !present.contains(&activation.version) {
+                findings.push(Finding::OrphanedActivation { version: activation.version, activated: activation.activated });
+            }
+        }
+        Ok(findings)
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A [`DatabaseConnector`] that stores everything in a Redis (or Valkey) server.
+///
+/// Versions are stored in two hashes (one for their metadata and one for their content), keyed by
+/// their number, and the number of the active version in a key of its own. Everything else
+/// (e.g., branches or the audit log) is stored in its own hash or list next to them. Version
+/// numbers are allocated from a counter key. All keys share a [prefix](RedisDatabase::with_prefix()),
+/// such that multiple stores can share a server.
+///
+/// Reads are served straight from the server, without any coordination. Changes are written in
+/// transactions that are retried when another store changed what they depend on in the meantime,
+/// such that any number of stores can share the same keys.
+pub struct RedisDatabase<C> {
+    /// The URL of the server we connect to.
+    url:      String,
+    /// The client used to open dedicated connections for transactions.
+    client:   Client,
+    /// The (shared, self-reconnecting) connection used for everything else.
+    conn:     ConnectionManager,
+    /// Where everything is stored.
+    layout:   Layout,
+    /// The format that the IDs of connecting users must adhere to.
+    user_ids: Arc<dyn Send + Sync + UserIdFormat>,
+    /// Remembers the type of content stored.
+    _content: PhantomData<C>,
+}
+impl<C> Clone for RedisDatabase<C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            url:      self.url.clone(),
+            client:   self.client.clone(),
+            conn:     self.conn.clone(),
+            layout:   self.layout.clone(),
+            user_ids: self.user_ids.clone(),
+            _content: PhantomData,
+        }
+    }
+}
+impl<C> RedisDatabase<C> {
+    /// Constructor for the RedisDatabase.
+    ///
+    /// # Arguments
+    /// - `url`: The URL of the Redis server to connect to (e.g., `redis://localhost:6379/0`).
+    ///
+    /// # Returns
+    /// A new RedisDatabase struct that stores everything under the [`DEFAULT_PREFIX`].
+    ///
+    /// # Errors
+    /// This function errors if the URL is invalid or if we failed to connect to the server.
+    pub async fn new(url: impl Into<String>) -> Result<Self, DatabaseError> {
+        let url: String = url.into();
+        debug!("Connecting to Redis server {url:?}...");
+        let client: Client = match Client::open(url.as_str()) {
+            Ok(client) => client,
+            Err(err) => return Err(DatabaseError::Client { url, err }),
+        };
+        let conn: ConnectionManager = match ConnectionManager::new(client.clone()).await {
+            Ok(conn) => conn,
+            Err(err) => return Err(DatabaseError::Connect { url, err }),
+        };
+        Ok(Self { url, client, conn, layout: Layout { prefix: DEFAULT_PREFIX.into() }, user_ids: Arc::new(()), _content: PhantomData })
+    }
+
+    /// Stores everything under keys with the given prefix instead of the [`DEFAULT_PREFIX`].
+    ///
+    /// Stores sharing a server but not their policies should use different prefixes.
+    ///
+    /// # Arguments
+    /// - `prefix`: The prefix of every key (joined to the rest with a colon).
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.layout.prefix = prefix.into();
+        self
+    }
+
+    /// Refuses connections for users whose ID does not adhere to the given format.
+    ///
+    /// IDs are [validated](UserIdFormat::validate()), not normalized; see
+    /// `SQLiteDatabase::with_user_id_format()` for why. By default, any ID is accepted.
+    ///
+    /// # Arguments
+    /// - `format`: The [`UserIdFormat`] to enforce.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_user_id_format(mut self, format: impl 'static + Send + Sync + UserIdFormat) -> Self {
+        self.user_ids = Arc::new(format);
+        self
+    }
+
+    /// Returns the prefix of the keys under which everything is stored.
+    ///
+    /// # Returns
+    /// The prefix, without the colon joining it to the rest of a key.
+    #[inline]
+    pub fn prefix(&self) -> &str { &self.layout.prefix }
+}
+impl<C: Send + Sync + Serialize + DeserializeOwned + 'static> DatabaseConnector for RedisDatabase<C> {
+    type Connection<'s>
+        = RedisConnection<'s, C>
+    where
+        Self: 's;
+    type Content = C;
+    type Error = DatabaseError;
+
+    #[inline]
+    fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        self.connect_from(user, None)
+    }
+
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            if let Err(err) = self.user_ids.validate(&user.id) {
+                return Err(DatabaseError::IllegalUserId { url: self.url.clone(), err });
+            }
+            debug!("Creating new connection to Redis server {:?}...", self.url);
+            Ok(RedisConnection {
+                redis: &self.client,
+                conn: self.conn.clone(),
+                layout: &self.layout,
+                user,
+                client,
+                request_id: None,
+                _content: PhantomData,
+            })
+        }
+    }
+
+    /// Also records the request ID of the access in the provenance of the versions written.
+    ///
+    /// Deadlines are ignored, as connecting never waits (the connection is shared).
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        async move {
+            let mut conn: RedisConnection<'s, C> = self.connect_from(&context.user, context.client).await?;
+            conn.request_id = context.request_id.clone();
+            Ok(conn)
+        }
+    }
+
+    /// Checks that the active version and every activation refer to existing versions, that every
+    /// version is numbered higher than the versions it derives from, and that the content of
+    /// every version is valid and matches its hash.
+    ///
+    /// Note that the keys are read one after another, so findings may be reported spuriously if
+    /// other stores change them while verifying.
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisDatabase::verify", prefix = self.layout.prefix);
+
+            let mut conn: ConnectionManager = self.conn.clone();
+            self.layout.verify::<C>(&mut conn).await.map_err(|err| DatabaseError::Verify { url: self.url.clone(), err })
+        }
+    }
+}
+
+
+
+/// Represents the connection created by [`RedisDatabase::connect()`].
+///
+/// Every change is written as a single transaction (including its audit record), such that it is
+/// never observed halfway. Transactions are run on a dedicated connection, opened on demand.
+pub struct RedisConnection<'a, C> {
+    /// The client used to open dedicated connections for transactions.
+    redis: &'a Client,
+    /// The shared connection used for reading.
+    conn: ConnectionManager,
+    /// Where everything is stored.
+    layout: &'a Layout,
+    /// The user that is doing everything in this connection.
+    user: &'a User,
+    /// The address of the client from which the user connects, if known.
+    client: Option<IpAddr>,
+    /// The ID of the request in which the user connects, if any.
+    request_id: Option<String>,
+    /// Remembers the type of content stored.
+    _content: PhantomData<C>,
+}
+impl<C> RedisConnection<'_, C> {
+    /// Opens a dedicated connection to run a transaction on.
+    ///
+    /// This is necessary because `WATCH`es apply to a whole connection, and the shared one is
+    /// used by others at the same time.
+    ///
+    /// # Returns
+    /// A new [`MultiplexedConnection`] that is not shared with anyone.
+    ///
+    /// # Errors
+    /// This function errors if we failed to connect.
+    #[inline]
+    async fn transaction(&self) -> Result<MultiplexedConnection, ConnectionError> {
+        self.redis.get_multiplexed_async_connection().await.map_err(|err| ConnectionError::Connect { err })
+    }
+}
+impl<C: Send + Sync + Serialize + DeserializeOwned + 'static> DatabaseConnection for RedisConnection<'_, C> {
+    type Content = C;
+    type Error = ConnectionError;
+
+
+    // Mutable
+    fn add_version(&mut self, metadata: AttachedMetadata, content: Self::Content) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::add_version", policy = metadata.name);
+
+            let hash: String = hash_content(&metadata.name, &content)?;
+            let content: String = to_json(&self.layout.key(CONTENTS_KEY), &content)?;
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[NEXT_VERSION_KEY, BRANCHES_KEY, AUDIT_LOG_KEY]).await?;
+                let version: u64 = self.layout.next_version(&mut conn).await?;
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+
+                debug!("Adding new policy {version}...");
+                let action = AuditAction::AddVersion { version, name: metadata.name.clone(), branch: metadata.branch.clone() };
+                let stored = StoredMetadata {
+                    metadata:   Metadata {
+                        attached: metadata.clone(),
+                        created: Utc::now(),
+                        creator: recorded(self.user),
+                        version,
+                        parent: None,
+                        promoted_from: None,
+                        hash: Some(hash.clone()),
+                        origin: None,
+                        verification: None,
+                        frozen: None,
+                    },
+                    client:     self.client,
+                    request_id: self.request_id.clone(),
+                };
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.add(&mut conn, &mut pipe, stored, content.clone()).await?;
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(version);
+                }
+            }
+        }
+    }
+
+    fn activate(&mut self, version: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::activate", version = version);
+
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[VERSIONS_KEY, ACTIVE_KEY, ACTIVATIONS_KEY, AUDIT_LOG_KEY]).await?;
+                match self.layout.hget::<StoredMetadata>(&mut conn, VERSIONS_KEY, version).await? {
+                    Some(stored) if stored.metadata.frozen.is_some() => return Err(ConnectionError::VersionFrozen { version }),
+                    Some(_) => {},
+                    None => return Err(ConnectionError::UnknownVersion { version }),
+                }
+
+                // They may already be the same, ez
+                let active: Option<u64> = self.layout.active(&mut conn).await?;
+                if active == Some(version) {
+                    info!("Activated already-active version {version}");
+                    return Ok(());
+                }
+
+                debug!("Activating policy {version}...");
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let activation = Activation {
+                    version,
+                    activated: Utc::now(),
+                    activator: recorded(self.user),
+                    client: self.client,
+                    deactivated: None,
+                    deactivator: None,
+                };
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+
+                // Close the activation it supersedes (if any) before adding the new one
+                if let Some(mut current) =
+                    self.layout.last::<Activation>(&mut conn, ACTIVATIONS_KEY).await?.filter(|current| Some(current.version) == active)
+                {
+                    current.deactivated = Some(activation.activated);
+                    current.deactivator = Some(recorded(self.user));
+                    let key: String = self.layout.key(ACTIVATIONS_KEY);
+                    let raw: String = to_json(&key, &current)?;
+                    pipe.lset(key, -1, raw).ignore();
+                }
+                self.layout.rpush(&mut pipe, ACTIVATIONS_KEY, &activation)?;
+                pipe.set(self.layout.key(ACTIVE_KEY), version).ignore();
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, AuditAction::Activate { version })?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::deactivate");
+
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[ACTIVE_KEY, ACTIVATIONS_KEY, AUDIT_LOG_KEY]).await?;
+                let Some(version) = self.layout.active(&mut conn).await? else {
+                    info!("Deactivated a policy whilst none were active");
+                    return Ok(());
+                };
+
+                debug!("Deactivating active policy {version}...");
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                if let Some(mut activation) =
+                    self.layout.last::<Activation>(&mut conn, ACTIVATIONS_KEY).await?.filter(|activation| activation.version == version)
+                {
+                    activation.deactivated = Some(Utc::now());
+                    activation.deactivator = Some(recorded(self.user));
+                    let key: String = self.layout.key(ACTIVATIONS_KEY);
+                    let raw: String = to_json(&key, &activation)?;
+                    pipe.lset(key, -1, raw).ignore();
+                }
+                pipe.del(self.layout.key(ACTIVE_KEY)).ignore();
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, AuditAction::Deactivate { version })?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn freeze_version(&mut self, version: u64, reason: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::freeze_version", version = version);
+
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[VERSIONS_KEY, AUDIT_LOG_KEY]).await?;
+                let Some(mut stored) = self.layout.hget::<StoredMetadata>(&mut conn, VERSIONS_KEY, version).await? else {
+                    return Ok(Err(ClientError::UnknownVersion { version }));
+                };
+
+                debug!("Freezing policy {version}...");
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                stored.metadata.frozen = Some(Freeze { reason: reason.into(), frozen: Utc::now(), freezer: recorded(self.user) });
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.hset(&mut pipe, VERSIONS_KEY, version, &stored)?;
+                let action = AuditAction::FreezeVersion { version, reason: reason.into() };
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn unfreeze_version(&mut self, version: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::unfreeze_version", version = version);
+
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[VERSIONS_KEY, AUDIT_LOG_KEY]).await?;
+                let Some(mut stored) = self.layout.hget::<StoredMetadata>(&mut conn, VERSIONS_KEY, version).await? else {
+                    return Ok(Err(ClientError::UnknownVersion { version }));
+                };
+
+                // Unfreeze it, unless it isn't frozen
+                debug!("Unfreezing policy {version}...");
+                if stored.metadata.frozen.take().is_none() {
+                    return Ok(Ok(()));
+                }
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.hset(&mut pipe, VERSIONS_KEY, version, &stored)?;
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, AuditAction::UnfreezeVersion { version })?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn add_template(&mut self, template: AttachedTemplate) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::add_template", template = template.name);
+
+            // Submit it, replacing any existing one
+            debug!("Adding template {:?}...", template.name);
+            let name: String = template.name.clone();
+            let template = Template { attached: template, created: Utc::now(), creator: recorded(self.user) };
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[AUDIT_LOG_KEY]).await?;
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.hset(&mut pipe, TEMPLATES_KEY, &name, &template)?;
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, AuditAction::AddTemplate { name: name.clone() })?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn create_branch(&mut self, name: &str, from: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::create_branch", branch = name, from = from);
+
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[BRANCHES_KEY, VERSIONS_KEY, AUDIT_LOG_KEY]).await?;
+                if self.layout.hexists(&mut conn, BRANCHES_KEY, name).await? {
+                    return Ok(Err(ClientError::BranchExists { name: name.into() }));
+                }
+                if !self.layout.hexists(&mut conn, VERSIONS_KEY, from).await? {
+                    return Ok(Err(ClientError::UnknownVersion { version: from }));
+                }
+
+                debug!("Creating branch {name:?} from version {from}...");
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let branch =
+                    Branch { name: name.into(), head: Some(from), base: Some(from), created: Utc::now(), creator: recorded(self.user) };
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.hset(&mut pipe, BRANCHES_KEY, name, &branch)?;
+                let action = AuditAction::CreateBranch { name: name.into(), from };
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn promote(&mut self, version: u64, target: &str, force: bool) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::promote", version = version, target = target);
+
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[NEXT_VERSION_KEY, VERSIONS_KEY, BRANCHES_KEY, AUDIT_LOG_KEY]).await?;
+                let Some(original) = self.layout.hget::<StoredMetadata>(&mut conn, VERSIONS_KEY, version).await? else {
+                    return Ok(Err(ClientError::UnknownVersion { version }));
+                };
+                if original.metadata.frozen.is_some() {
+                    return Ok(Err(ClientError::VersionFrozen { version }));
+                }
+                let source: &str = &original.metadata.attached.branch;
+                if source == target {
+                    return Ok(Err(ClientError::PromoteSameBranch { version, branch: target.into() }));
+                }
+                let branches: HashMap<String, Branch> = self.layout.hgetall(&mut conn, BRANCHES_KEY).await?;
+                let Some(target_branch) = branches.get(target) else { return Ok(Err(ClientError::UnknownBranch { name: target.into() })) };
+
+                // Find where the branches last met; i.e., the latest promotion from the source into the target, or else the fork point
+                debug!("Checking for conflicts between branch {source:?} and {target:?}...");
+                let versions: HashMap<u64, StoredMetadata> = self
+                    .layout
+                    .hgetall::<StoredMetadata>(&mut conn, VERSIONS_KEY)
+                    .await?
+                    .into_values()
+                    .map(|stored| (stored.metadata.version, stored))
+                    .collect();
+                let last_promotion: Option<u64> = versions
+                    .values()
+                    .filter(|stored| stored.metadata.attached.branch == target)
+                    .filter(|stored| {
+                        stored.metadata.promoted_from.and_then(|from| versions.get(&from)).is_some_and(|from| from.metadata.attached.branch == source)
+                    })
+                    .map(|stored| stored.metadata.version)
+                    .max();
+                let base: Option<u64> = last_promotion.or(branches.get(source).and_then(|branch| branch.base));
+                if let Some(head) = target_branch.head {
+                    if Some(head) != base {
+                        if force {
+                            info!("Forcing promotion of version {version} into branch {target:?} despite it having advanced to {head}");
+                        } else {
+                            return Ok(Err(ClientError::PromoteConflict {
+                                version,
+                                source_branch: source.into(),
+                                target: target.into(),
+                                head,
+                                base,
+                            }));
+                        }
+                    }
+                }
+
+                // Append the copy to the target, copying the content as-is
+                let key: String = self.layout.key(CONTENTS_KEY);
+                let content: Option<String> = conn.hget(&key, version).await.map_err(|err| ConnectionError::Command { key, err })?;
+                let Some(content) = content else { return Ok(Err(ClientError::UnknownVersion { version })) };
+                let promoted: u64 = self.layout.next_version(&mut conn).await?;
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                debug!("Promoting policy {version} into branch {target:?} as {promoted}...");
+                let stored = StoredMetadata {
+                    metadata:   Metadata {
+                        attached: AttachedMetadata { branch: target.into(), ..original.metadata.attached.clone() },
+                        created: Utc::now(),
+                        creator: recorded(self.user),
+                        version: promoted,
+                        parent: None,
+                        promoted_from: Some(version),
+                        hash: original.metadata.hash.clone(),
+                        origin: original.metadata.origin.clone(),
+                        verification: original.metadata.verification.clone(),
+                        frozen: None,
+                    },
+                    client:     self.client,
+                    request_id: self.request_id.clone(),
+                };
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.add(&mut conn, &mut pipe, stored, content).await?;
+                let action = AuditAction::Promote { version, target: target.into(), promoted };
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(promoted));
+                }
+            }
+        }
+    }
+
+    fn import_version(
+        &mut self,
+        store: &str,
+        metadata: Metadata,
+        content: Self::Content,
+    ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::import_version", store = store, version = metadata.version);
+
+            // Make sure the content survived the trip
+            let hash: String = hash_content(&metadata.attached.name, &content)?;
+            if let Some(expected) = metadata.hash {
+                if expected != hash {
+                    return Ok(Err(ClientError::HashMismatch { version: metadata.version, expected, actual: hash }));
+                }
+            }
+            let content: String = to_json(&self.layout.key(CONTENTS_KEY), &content)?;
+
+            // Keep the original origin if this version was imported before
+            let source: u64 = metadata.version;
+            let origin: Origin =
+                metadata.origin.unwrap_or(Origin { store: store.into(), version: source, creator: metadata.creator.id, created: metadata.created });
+            // NOTE: Other stores are bound by the same maximum, so they can't have such a version either
+            if origin.version > MAX_VERSION {
+                return Ok(Err(ClientError::UnknownVersion { version: origin.version }));
+            }
+
+            // Append the copy to the same branch as in the other store
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[NEXT_VERSION_KEY, BRANCHES_KEY, AUDIT_LOG_KEY]).await?;
+                let version: u64 = self.layout.next_version(&mut conn).await?;
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                debug!("Importing policy {source} from store {store:?} as {version}...");
+                let action = AuditAction::Import {
+                    version,
+                    name: metadata.attached.name.clone(),
+                    branch: metadata.attached.branch.clone(),
+                    store: store.into(),
+                    source,
+                };
+                let stored = StoredMetadata {
+                    metadata:   Metadata {
+                        attached: metadata.attached.clone(),
+                        created: Utc::now(),
+                        creator: recorded(self.user),
+                        version,
+                        parent: None,
+                        promoted_from: None,
+                        hash: Some(hash.clone()),
+                        origin: Some(origin.clone()),
+                        verification: metadata.verification.clone(),
+                        frozen: None,
+                    },
+                    client:     self.client,
+                    request_id: self.request_id.clone(),
+                };
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.add(&mut conn, &mut pipe, stored, content.clone()).await?;
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(version));
+                }
+            }
+        }
+    }
+
+    fn add_schema(&mut self, schema: AttachedSchema) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::add_schema", schema = schema.name);
+
+            let name: String = schema.name.clone();
+            let schema = Schema { attached: schema, created: Utc::now(), creator: recorded(self.user) };
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[SCHEMAS_KEY, AUDIT_LOG_KEY]).await?;
+                if self.layout.hexists(&mut conn, SCHEMAS_KEY, &name).await? {
+                    return Ok(Err(ClientError::SchemaExists { name }));
+                }
+
+                debug!("Adding schema {name:?}...");
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.hset(&mut pipe, SCHEMAS_KEY, &name, &schema)?;
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, AuditAction::AddSchema { name: name.clone() })?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn pseudonymize_user(&mut self, id: &str, pseudonym: &str) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        async move {
+            // NOTE: Deliberately not logging the ID itself, as that's what we're trying to get rid of
+            let _span = span!(Level::INFO, "RedisConnection::pseudonymize_user", pseudonym = pseudonym);
+
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout
+                    .watch(&mut conn, &[
+                        VERSIONS_KEY,
+                        TEMPLATES_KEY,
+                        BRANCHES_KEY,
+                        SCHEMAS_KEY,
+                        ACTIVATIONS_KEY,
+                        DECISIONS_KEY,
+                        KEYS_KEY,
+                        REVOCATIONS_KEY,
+                        AUDIT_LOG_KEY,
+                    ])
+                    .await?;
+
+                // We don't want to launder any tampering by re-hashing, so verify the audit log first
+                let mut log: Vec<AuditRecord> = self.layout.lrange(&mut conn, AUDIT_LOG_KEY, 0).await?;
+                if let Err(err) = verify_chain(&log) {
+                    return Err(ConnectionError::AuditChain { key: self.layout.key(AUDIT_LOG_KEY), err });
+                }
+
+                // First, everything but the audit log; only rewriting fields that actually change
+                debug!("Pseudonymizing user in keys...");
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                let mut count: u64 = 0;
+                for (version, mut stored) in self.layout.hgetall::<StoredMetadata>(&mut conn, VERSIONS_KEY).await? {
+                    // Where they wrote it from is as telling as their ID, so forget it
+                    let mut changed: u64 = u64::from(stored.metadata.creator.id == id && stored.client.take().is_some());
+                    changed += pseudonymize(&mut stored.metadata.creator.id, id, pseudonym);
+                    if let Some(origin) = &mut stored.metadata.origin {
+                        changed += pseudonymize(&mut origin.creator, id, pseudonym);
+                    }
+                    if let Some(frozen) = &mut stored.metadata.frozen {
+                        changed += pseudonymize(&mut frozen.freezer.id, id, pseudonym);
+                    }
+                    if changed > 0 {
+                        self.layout.hset(&mut pipe, VERSIONS_KEY, version, &stored)?;
+                        count += changed;
+                    }
+                }
+                for (name, mut template) in self.layout.hgetall::<Template>(&mut conn, TEMPLATES_KEY).await? {
+                    if pseudonymize(&mut template.creator.id, id, pseudonym) > 0 {
+                        self.layout.hset(&mut pipe, TEMPLATES_KEY, name, &template)?;
+                        count += 1;
+                    }
+                }
+                for (name, mut branch) in self.layout.hgetall::<Branch>(&mut conn, BRANCHES_KEY).await? {
+                    if pseudonymize(&mut branch.creator.id, id, pseudonym) > 0 {
+                        self.layout.hset(&mut pipe, BRANCHES_KEY, name, &branch)?;
+                        count += 1;
+                    }
+                }
+                for (name, mut schema) in self.layout.hgetall::<Schema>(&mut conn, SCHEMAS_KEY).await? {
+                    if pseudonymize(&mut schema.creator.id, id, pseudonym) > 0 {
+                        self.layout.hset(&mut pipe, SCHEMAS_KEY, name, &schema)?;
+                        count += 1;
+                    }
+                }
+                let mut activations: Vec<Activation> = self.layout.lrange(&mut conn, ACTIVATIONS_KEY, 0).await?;
+                let changed: u64 = activations
+                    .iter_mut()
+                    .map(|activation| {
+                        u64::from(activation.activator.id == id && activation.client.take().is_some())
+                            + pseudonymize(&mut activation.activator.id, id, pseudonym)
+                            + activation.deactivator.as_mut().map(|deactivator| pseudonymize(&mut deactivator.id, id, pseudonym)).unwrap_or(0)
+                    })
+                    .sum();
+                if changed > 0 {
+                    self.layout.replace_list(&mut pipe, ACTIVATIONS_KEY, &activations)?;
+                    count += changed;
+                }
+                for (decision_id, mut decision) in self.layout.hgetall::<Decision>(&mut conn, DECISIONS_KEY).await? {
+                    if pseudonymize(&mut decision.reporter.id, id, pseudonym) > 0 {
+                        self.layout.hset(&mut pipe, DECISIONS_KEY, decision_id, &decision)?;
+                        count += 1;
+                    }
+                }
+                for (key_id, mut key) in self.layout.hgetall::<Key>(&mut conn, KEYS_KEY).await? {
+                    if pseudonymize(&mut key.creator.id, id, pseudonym) > 0 {
+                        self.layout.hset(&mut pipe, KEYS_KEY, key_id, &key)?;
+                        count += 1;
+                    }
+                }
+                let mut revocations: Vec<Revocation> = self.layout.lrange(&mut conn, REVOCATIONS_KEY, 0).await?;
+                let changed: u64 = revocations.iter_mut().map(|revocation| pseudonymize(&mut revocation.creator.id, id, pseudonym)).sum();
+                if changed > 0 {
+                    self.layout.replace_list(&mut pipe, REVOCATIONS_KEY, &revocations)?;
+                    count += changed;
+                }
+
+                // Then the audit log, which is re-hashed from the first affected record onwards
+                debug!("Pseudonymizing user in audit log...");
+                let mut prev_hash: Option<String> = None;
+                let mut rehashed_from: Option<u64> = None;
+                for record in &mut log {
+                    if record.user == id {
+                        record.user = pseudonym.into();
+                        record.client = None;
+                        rehashed_from = rehashed_from.or(Some(record.seq));
+                        count += 1;
+                    }
+                    if record.action.pseudonymize(id, pseudonym) > 0 {
+                        rehashed_from = rehashed_from.or(Some(record.seq));
+                        count += 1;
+                    }
+                    if rehashed_from.is_some() {
+                        record.prev_hash = prev_hash;
+                        record.hash = record.compute_hash();
+                    }
+                    prev_hash = Some(record.hash.clone());
+                }
+                if count == 0 {
+                    return Ok(Err(ClientError::UnknownUser { id: id.into() }));
+                }
+
+                // Finally, log the pseudonymization itself
+                let record = AuditRecord::new(log.last(), &self.user.id, self.client, AuditAction::Pseudonymize {
+                    pseudonym: pseudonym.into(),
+                    rehashed_from,
+                });
+                log.push(record);
+                self.layout.replace_list(&mut pipe, AUDIT_LOG_KEY, &log)?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(count));
+                }
+            }
+        }
+    }
+
+    fn add_decision(&mut self, version: u64, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::add_decision", version = version, decision = id);
+
+            // Ensure the version exists and the decision doesn't yet
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[VERSIONS_KEY, DECISIONS_KEY, AUDIT_LOG_KEY]).await?;
+                if !self.layout.hexists(&mut conn, VERSIONS_KEY, version).await? {
+                    return Ok(Err(ClientError::UnknownVersion { version }));
+                }
+                if self.layout.hexists(&mut conn, DECISIONS_KEY, id).await? {
+                    return Ok(Err(ClientError::DecisionExists { id: id.into() }));
+                }
+
+                debug!("Adding decision {id:?}...");
+                let decision = Decision { id: id.into(), version, recorded: Utc::now(), reporter: recorded(self.user) };
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.hset(&mut pipe, DECISIONS_KEY, id, &decision)?;
+                self.layout
+                    .append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, AuditAction::AddDecision { version, id: id.into() })?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn prune_activations(&mut self, before: DateTime<Utc>) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::prune_activations", before = before.to_rfc3339());
+
+            // NOTE: Activations are closed when deactivated or superseded, so only the active one
+            // lacks a deactivation time (and never matches)
+            debug!("Removing activations that ended before {}...", before.to_rfc3339());
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[ACTIVATIONS_KEY, AUDIT_LOG_KEY]).await?;
+                let mut activations: Vec<Activation> = self.layout.lrange(&mut conn, ACTIVATIONS_KEY, 0).await?;
+                let len: usize = activations.len();
+                activations.retain(|activation| activation.deactivated.is_none_or(|deactivated| deactivated >= before));
+                let rows: u64 = (len - activations.len()) as u64;
+                if rows == 0 {
+                    return Ok(0);
+                }
+
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.replace_list(&mut pipe, ACTIVATIONS_KEY, &activations)?;
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, AuditAction::PruneActivations { before, rows })?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(rows);
+                }
+            }
+        }
+    }
+
+    fn add_key(&mut self, key: AttachedKey) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::add_key", key = key.id);
+
+            let id: String = key.id.clone();
+            let key = Key { attached: key, created: Utc::now(), creator: recorded(self.user), revoked: None };
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                // Ensure the key doesn't exist yet (not even revoked)
+                self.layout.watch(&mut conn, &[KEYS_KEY, AUDIT_LOG_KEY]).await?;
+                if self.layout.hexists(&mut conn, KEYS_KEY, &id).await? {
+                    return Ok(Err(ClientError::KeyExists { id }));
+                }
+
+                debug!("Adding key {id:?}...");
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.hset(&mut pipe, KEYS_KEY, &id, &key)?;
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, AuditAction::AddKey { id: id.clone() })?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn revoke_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::revoke_key", key = id);
+
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[KEYS_KEY, AUDIT_LOG_KEY]).await?;
+                let Some(mut key) = self.layout.hget::<Key>(&mut conn, KEYS_KEY, id).await? else {
+                    return Ok(Err(ClientError::UnknownKey { id: id.into() }));
+                };
+
+                // Revoke it, unless it already is
+                debug!("Revoking key {id:?}...");
+                if key.revoked.is_some() {
+                    return Ok(Ok(()));
+                }
+                key.revoked = Some(Utc::now());
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.hset(&mut pipe, KEYS_KEY, id, &key)?;
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, AuditAction::RevokeKey { id: id.into() })?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(Ok(()));
+                }
+            }
+        }
+    }
+
+    fn revoke_tokens(&mut self, revocation: AttachedRevocation) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::revoke_tokens");
+
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[REVOCATIONS_KEY, AUDIT_LOG_KEY]).await?;
+                let mut revocations: Vec<Revocation> = self.layout.lrange(&mut conn, REVOCATIONS_KEY, 0).await?;
+                let action: AuditAction = match &revocation {
+                    AttachedRevocation::Token { jti } => {
+                        // Revoke it, unless it already is
+                        if revocations.iter().any(|prev| matches!(&prev.attached, AttachedRevocation::Token { jti: revoked } if revoked == jti)) {
+                            debug!("Token {jti:?} was already revoked");
+                            return Ok(());
+                        }
+                        debug!("Revoking token {jti:?}...");
+                        AuditAction::RevokeToken { jti: jti.clone() }
+                    },
+                    AttachedRevocation::User { user, before } => {
+                        // Only keep the latest revocation per user
+                        let prev: Option<usize> = revocations
+                            .iter()
+                            .position(|prev| matches!(&prev.attached, AttachedRevocation::User { user: revoked, .. } if revoked == user));
+                        if let Some(prev) = prev {
+                            if matches!(revocations[prev].attached, AttachedRevocation::User { before: revoked, .. } if revoked >= *before) {
+                                debug!("Tokens of user {user:?} were already revoked before {before}");
+                                return Ok(());
+                            }
+                            revocations.remove(prev);
+                        }
+                        debug!("Revoking tokens of user {user:?} issued before {before}...");
+                        AuditAction::RevokeUserTokens { user: user.clone(), before: *before }
+                    },
+                };
+                revocations.push(Revocation { attached: revocation.clone(), created: Utc::now(), creator: recorded(self.user) });
+
+                let prev: Option<AuditRecord> = self.layout.last(&mut conn, AUDIT_LOG_KEY).await?;
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.replace_list(&mut pipe, REVOCATIONS_KEY, &revocations)?;
+                self.layout.append_audit(&mut pipe, prev.as_ref(), &self.user.id, self.client, action)?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn ack_events(&mut self, sink: &str, seq: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::ack_events", sink = sink, seq = seq);
+
+            // Only ever move the cursor forward
+            let mut conn: MultiplexedConnection = self.transaction().await?;
+            loop {
+                self.layout.watch(&mut conn, &[EVENT_CURSORS_KEY]).await?;
+                if let Some(prev) = self.layout.hget::<u64>(&mut conn, EVENT_CURSORS_KEY, sink).await?.filter(|prev| *prev >= seq) {
+                    debug!("Sink {sink:?} already acknowledged events up to {prev}");
+                    return Ok(());
+                }
+
+                debug!("Acknowledging events up to {seq} for sink {sink:?}...");
+                let mut pipe: Pipeline = redis::pipe();
+                pipe.atomic();
+                self.layout.hset(&mut pipe, EVENT_CURSORS_KEY, sink, &seq)?;
+                if self.layout.commit(&mut conn, &pipe).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    // Immutable
+    fn get_versions(&mut self) -> impl Send + Future<Output = Result<HashMap<u64, Metadata>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_versions");
+
+            debug!("Retrieving all policy versions...");
+            let versions: HashMap<String, StoredMetadata> = self.layout.hgetall(&mut self.conn, VERSIONS_KEY).await?;
+            Ok(versions.into_values().map(|stored| (stored.metadata.version, stored.metadata)).collect())
+        }
+    }
+
+    fn get_active_version(&mut self) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_active");
+
+            debug!("Fetching active version...");
+            self.layout.active(&mut self.conn).await
+        }
+    }
+
+    fn get_active_version_at(&mut self, at: DateTime<Utc>) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_active_version_at", at = at.to_rfc3339());
+
+            // Find the last activation before the given time, and see if it was still active then
+            debug!("Fetching version active at {at}...");
+            let activations: Vec<Activation> = self.layout.lrange(&mut self.conn, ACTIVATIONS_KEY, 0).await?;
+            Ok(activations
+                .into_iter()
+                .filter(|activation| activation.activated <= at)
+                .max_by_key(|activation| activation.activated)
+                .filter(|activation| activation.deactivated.is_none_or(|deactivated| deactivated > at))
+                .map(|activation| activation.version))
+        }
+    }
+
+    fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_active");
+
+            Ok(self.layout.current_activation(&mut self.conn).await?.map(|activation| activation.activator))
+        }
+    }
+
+    fn get_activation(&mut self) -> impl Send + Future<Output = Result<Option<Activation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_activation");
+
+            self.layout.current_activation(&mut self.conn).await
+        }
+    }
+
+    fn get_activations(&mut self) -> impl Send + Future<Output = Result<Vec<Activation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_activations");
+
+            debug!("Retrieving activation history...");
+            let mut activations: Vec<Activation> = self.layout.lrange(&mut self.conn, ACTIVATIONS_KEY, 0).await?;
+            activations.sort_by_key(|activation| activation.activated);
+            Ok(activations)
+        }
+    }
+
+    fn get_version_metadata(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Metadata>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_version_metadata", version = version);
+
+            debug!("Retrieving metadata for version {version}...");
+            Ok(self.layout.hget::<StoredMetadata>(&mut self.conn, VERSIONS_KEY, version).await?.map(|stored| stored.metadata))
+        }
+    }
+
+    fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_version_content", version = version);
+
+            debug!("Retrieving content for version {version}...");
+            self.layout.hget(&mut self.conn, CONTENTS_KEY, version).await
+        }
+    }
+
+    fn get_version_provenance(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Provenance>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_version_provenance", version = version);
+
+            debug!("Retrieving provenance for version {version}...");
+            let stored: Option<StoredMetadata> = self.layout.hget(&mut self.conn, VERSIONS_KEY, version).await?;
+            Ok(stored.map(|stored| Provenance::new(&stored.metadata, stored.client, stored.request_id)))
+        }
+    }
+
+    fn get_templates(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Template>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_templates");
+
+            debug!("Retrieving all templates...");
+            self.layout.hgetall(&mut self.conn, TEMPLATES_KEY).await
+        }
+    }
+
+    fn get_template(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Template>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_template", template = name);
+
+            debug!("Retrieving template {name:?}...");
+            self.layout.hget(&mut self.conn, TEMPLATES_KEY, name).await
+        }
+    }
+
+    fn get_branches(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Branch>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_branches");
+
+            debug!("Retrieving all branches...");
+            self.layout.hgetall(&mut self.conn, BRANCHES_KEY).await
+        }
+    }
+
+    fn get_schemas(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Schema>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_schemas");
+
+            debug!("Retrieving all schemas...");
+            self.layout.hgetall(&mut self.conn, SCHEMAS_KEY).await
+        }
+    }
+
+    fn get_schema(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Schema>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_schema", schema = name);
+
+            debug!("Retrieving schema {name:?}...");
+            self.layout.hget(&mut self.conn, SCHEMAS_KEY, name).await
+        }
+    }
+
+    fn get_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Vec<Decision>>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_decisions", version = version);
+
+            if !self.layout.hexists(&mut self.conn, VERSIONS_KEY, version).await? {
+                return Ok(None);
+            }
+            debug!("Retrieving decisions taken under version {version}...");
+            let decisions: HashMap<String, Decision> = self.layout.hgetall(&mut self.conn, DECISIONS_KEY).await?;
+            let mut decisions: Vec<Decision> = decisions.into_values().filter(|decision| decision.version == version).collect();
+            decisions.sort_by_key(|decision| decision.recorded);
+            Ok(Some(decisions))
+        }
+    }
+
+    fn count_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::count_decisions", version = version);
+
+            if !self.layout.hexists(&mut self.conn, VERSIONS_KEY, version).await? {
+                return Ok(None);
+            }
+            debug!("Counting decisions taken under version {version}...");
+            let decisions: HashMap<String, Decision> = self.layout.hgetall(&mut self.conn, DECISIONS_KEY).await?;
+            Ok(Some(decisions.values().filter(|decision| decision.version == version).count() as u64))
+        }
+    }
+
+    fn get_decision(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Decision>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_decision", decision = id);
+
+            debug!("Retrieving decision {id:?}...");
+            self.layout.hget(&mut self.conn, DECISIONS_KEY, id).await
+        }
+    }
+
+    fn get_keys(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Key>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_keys");
+
+            debug!("Retrieving all keys...");
+            self.layout.hgetall(&mut self.conn, KEYS_KEY).await
+        }
+    }
+
+    fn get_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Key>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_key", key = id);
+
+            debug!("Retrieving key {id:?}...");
+            self.layout.hget(&mut self.conn, KEYS_KEY, id).await
+        }
+    }
+
+    fn get_revocations(&mut self) -> impl Send + Future<Output = Result<Vec<Revocation>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_revocations");
+
+            debug!("Retrieving all token revocations...");
+            self.layout.lrange(&mut self.conn, REVOCATIONS_KEY, 0).await
+        }
+    }
+
+    fn is_revoked(&mut self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::is_revoked", user = user);
+
+            debug!("Checking whether token {jti:?} of user {user:?} is revoked...");
+            let revocations: Vec<Revocation> = self.layout.lrange(&mut self.conn, REVOCATIONS_KEY, 0).await?;
+            Ok(revocations.iter().any(|revocation| match &revocation.attached {
+                AttachedRevocation::Token { jti: revoked } => jti == Some(revoked.as_str()),
+                AttachedRevocation::User { user: revoked, before } => revoked == user && issued.is_none_or(|issued| issued < *before),
+            }))
+        }
+    }
+
+    fn get_audit_log(&mut self, since: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<Vec<AuditRecord>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_audit_log");
+
+            debug!("Retrieving audit log...");
+            let mut log: Vec<AuditRecord> = self.layout.lrange(&mut self.conn, AUDIT_LOG_KEY, 0).await?;
+            log.retain(|record| since.is_none_or(|since| record.timestamp >= since));
+            Ok(log)
+        }
+    }
+
+    fn get_changes(&mut self, since: u64, limit: u64) -> impl Send + Future<Output = Result<Vec<Change<Self::Content>>, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_changes", since = since, limit = limit);
+
+            // NOTE: Records are numbered from 1 and never removed, so record `since + 1` is at index `since`
+            debug!("Retrieving changes since {since}...");
+            let log: Vec<AuditRecord> = self.layout.lrange(&mut self.conn, AUDIT_LOG_KEY, isize::try_from(since).unwrap_or(isize::MAX)).await?;
+
+            // Attach any versions created
+            let mut changes: Vec<Change<C>> = Vec::new();
+            for record in log.into_iter().filter(|record| record.seq > since).take(usize::try_from(limit).unwrap_or(usize::MAX)) {
+                let (metadata, content): (Option<Metadata>, Option<C>) = match record.action.created_version() {
+                    Some(version) => {
+                        let stored: Option<StoredMetadata> = self.layout.hget(&mut self.conn, VERSIONS_KEY, version).await?;
+                        (stored.map(|stored| stored.metadata), self.layout.hget(&mut self.conn, CONTENTS_KEY, version).await?)
+                    },
+                    None => (None, None),
+                };
+                changes.push(Change { record, metadata, content, added: None });
+            }
+            Ok(changes)
+        }
+    }
+
+    fn get_event_cursor(&mut self, sink: &str) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        async move {
+            let _span = span!(Level::INFO, "RedisConnection::get_event_cursor", sink = sink);
+
+            debug!("Retrieving event cursor of sink {sink:?}...");
+            Ok(self.layout.hget(&mut self.conn, EVENT_CURSORS_KEY, sink).await?.unwrap_or(0))
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:49:25
+//  Last edited:
+//    16 Oct 2026, 18:49:25
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the `DatabaseConnector` for a Redis (or Valkey) backend.
+//!   Policies are kept in hashes on the server, such that reads are
+//!   cheap and any number of stores can share them.
+//
+
+// Declare modules
+mod databaseconn;
+
+// Import some of it
+pub use databaseconn::*;
@@ -0,0 +1,167 @@
+//  VERSIONS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:49:25
+//  Last edited:
+//    16 Oct 2026, 19:35:23
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the Redis connector stores and activates policy versions,
+//!   also when multiple stores share the same server.
+//!
+//!   These tests need a running Redis (or Valkey) server, and are
+//!   skipped unless `REDIS_TEST_URL` points to it (e.g.,
+//!   `redis://localhost:6379/0`). Every test uses its own key prefix.
+//
+
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{Duration, Utc};
+use redis_database::{ConnectionError, RedisDatabase};
+use specifications::DatabaseConnector as _;
+use specifications::audit::{AuditRecord, verify_chain};
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::User;
+use specifications::testing::{metadata, test_url};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Determines where a test stores its policies.
+///
+/// # Arguments
+/// - `name`: A name for the test, used to make the prefix unique.
+///
+/// # Returns
+/// The URL of the server and a fresh prefix for the keys of the test, or [`None`] if
+/// `REDIS_TEST_URL` is not set.
+fn fresh_prefix(name: &str) -> Option<(String, String)> {
+    let url: String = test_url("REDIS_TEST_URL")?;
+    let nanos: u128 = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    Some((url, format!("policy-store-test:{name}:{}:{nanos}", std::process::id())))
+}
+
+/// Creates a connector to the given server, as one replica of the store.
+///
+/// # Arguments
+/// - `url`: The URL of the server to connect to.
+/// - `prefix`: The prefix of the keys to use.
+///
+/// # Returns
+/// A new connector.
+async fn replica(url: &str, prefix: &str) -> RedisDatabase<bool> {
+    RedisDatabase::new(url).await.expect("failed to connect to test server").with_prefix(prefix)
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_version_lifecycle() {
+    let Some((url, prefix)) = fresh_prefix("lifecycle") else { return };
+    let db: RedisDatabase<bool> = replica(&url, &prefix).await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    // Versions are numbered in order, and chained on their branch
+    let first: u64 = conn.add_version(metadata(), true).await.unwrap();
+    let second: u64 = conn.add_version(metadata(), false).await.unwrap();
+    assert_eq!((first, second), (1, 2));
+    assert_eq!(conn.get_version_metadata(second).await.unwrap().unwrap().parent, Some(first));
+    assert_eq!(conn.get_version_content(first).await.unwrap(), Some(true));
+    assert_eq!(conn.get_branches().await.unwrap()["main"].head, Some(second));
+
+    // Only existing versions can be activated
+    assert!(matches!(conn.activate(42).await, Err(ConnectionError::UnknownVersion { version: 42 })));
+    conn.activate(first).await.unwrap();
+    conn.activate(second).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(second));
+    assert_eq!(conn.get_activator().await.unwrap().map(|user| user.id), Some("tester".into()));
+    conn.deactivate().await.unwrap();
+    conn.deactivate().await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), None);
+    let activations = conn.get_activations().await.unwrap();
+    assert_eq!(activations.iter().map(|activation| activation.version).collect::<Vec<u64>>(), vec![first, second]);
+    assert!(activations.iter().all(|activation| activation.deactivated.is_some()));
+
+    // Everything but the no-op deactivation is audited
+    let log: Vec<AuditRecord> = conn.get_audit_log(None).await.unwrap();
+    let kinds: Vec<&str> = log.iter().map(|record| record.action.kind()).collect();
+    assert_eq!(kinds, vec!["add_version", "add_version", "activate", "activate", "deactivate"]);
+    assert_eq!(verify_chain(&log).unwrap(), 5);
+    assert!(db.verify().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_prune_superseded() {
+    let Some((url, prefix)) = fresh_prefix("prune_superseded") else { return };
+    let db: RedisDatabase<bool> = replica(&url, &prefix).await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let first: u64 = conn.add_version(metadata(), true).await.unwrap();
+    let second: u64 = conn.add_version(metadata(), false).await.unwrap();
+
+    // Activating another version closes the activation it supersedes, such that it can be pruned
+    conn.activate(first).await.unwrap();
+    conn.activate(second).await.unwrap();
+    let activations = conn.get_activations().await.unwrap();
+    assert_eq!(activations[0].deactivated, Some(activations[1].activated));
+    assert_eq!(activations[1].deactivated, None);
+    assert_eq!(conn.prune_activations(Utc::now() + Duration::seconds(1)).await.unwrap(), 1);
+    assert_eq!(conn.get_activations().await.unwrap().iter().map(|activation| activation.version).collect::<Vec<u64>>(), vec![second]);
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(second));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replicas_allocate_unique_versions() {
+    let Some((url, prefix)) = fresh_prefix("unique_versions") else { return };
+    let (a, b) = tokio::join!(replica(&url, &prefix), replica(&url, &prefix));
+
+    // Add versions through both replicas at the same time
+    let mut handles = Vec::new();
+    for db in [a, b] {
+        handles.push(tokio::spawn(async move {
+            let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+            let mut conn = db.connect(&user).await.unwrap();
+            let mut versions: Vec<u64> = Vec::new();
+            for _ in 0..10 {
+                versions.push(conn.add_version(metadata(), true).await.unwrap());
+            }
+            versions
+        }));
+    }
+    let mut versions: HashSet<u64> = HashSet::new();
+    for handle in handles {
+        versions.extend(handle.await.unwrap());
+    }
+
+    // Every version must be handed out exactly once, and the audit log must still be a chain
+    assert_eq!(versions, (1..=20).collect());
+    let db: RedisDatabase<bool> = replica(&url, &prefix).await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let log: Vec<AuditRecord> = db.connect(&user).await.unwrap().get_audit_log(None).await.unwrap();
+    assert_eq!(verify_chain(&log).unwrap(), 20);
+}
+
+#[tokio::test]
+async fn test_replicas_share_active_version() {
+    let Some((url, prefix)) = fresh_prefix("share_active") else { return };
+    let a: RedisDatabase<bool> = replica(&url, &prefix).await;
+    let b: RedisDatabase<bool> = replica(&url, &prefix).await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+
+    // Activating through one replica is seen by the other
+    let mut conn_a = a.connect(&user).await.unwrap();
+    let version: u64 = conn_a.add_version(metadata(), true).await.unwrap();
+    conn_a.activate(version).await.unwrap();
+    let mut conn_b = b.connect(&user).await.unwrap();
+    assert_eq!(conn_b.get_active_version().await.unwrap(), Some(version));
+
+    conn_b.deactivate().await.unwrap();
+    assert_eq!(conn_a.get_active_version().await.unwrap(), None);
+}
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE `templates`(
+	`name` TEXT NOT NULL PRIMARY KEY,
+	`description` TEXT NOT NULL,
+	`language` TEXT NOT NULL,
+	`parameters` TEXT NOT NULL,
+	`skeleton` TEXT NOT NULL,
+	`creator` TEXT NOT NULL,
+	`created_at` TIMESTAMP NOT NULL
+);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS `branches`;
+
+ALTER TABLE `policies` DROP COLUMN `parent`;
+ALTER TABLE `policies` DROP COLUMN `branch`;
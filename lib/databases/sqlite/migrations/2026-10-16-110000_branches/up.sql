@@ -0,0 +1,19 @@
+-- Your SQL goes here
+ALTER TABLE `policies` ADD COLUMN `branch` TEXT NOT NULL DEFAULT 'main';
+ALTER TABLE `policies` ADD COLUMN `parent` BIGINT;
+
+-- Existing versions form a single lineage on the main branch
+UPDATE `policies` SET `parent` = (SELECT MAX(`p`.`version`) FROM `policies` AS `p` WHERE `p`.`version` < `policies`.`version`);
+
+CREATE TABLE `branches`(
+	`name` TEXT NOT NULL PRIMARY KEY,
+	`head` BIGINT,
+	`base` BIGINT,
+	`creator` TEXT NOT NULL,
+	`created_at` TIMESTAMP NOT NULL
+);
+
+INSERT INTO `branches` (`name`, `head`, `base`, `creator`, `created_at`)
+	SELECT 'main', MAX(`version`), NULL, (SELECT `creator` FROM `policies` ORDER BY `version` LIMIT 1), MIN(`created_at`)
+	FROM `policies`
+	HAVING COUNT(*) > 0;
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE `policies` ADD COLUMN `content_hash` TEXT;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE `policies` DROP COLUMN `schema`;
+
+DROP TABLE IF EXISTS `schemas`;
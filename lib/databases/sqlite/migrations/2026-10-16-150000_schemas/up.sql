@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE `schemas`(
+	`name` TEXT NOT NULL PRIMARY KEY,
+	`description` TEXT NOT NULL,
+	`schema` TEXT NOT NULL,
+	`creator` TEXT NOT NULL,
+	`created_at` TIMESTAMP NOT NULL
+);
+
+ALTER TABLE `policies` ADD COLUMN `schema` TEXT;
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE `audit_log`(
+	`seq` BIGINT NOT NULL PRIMARY KEY,
+	`timestamp` TIMESTAMP NOT NULL,
+	`user_id` TEXT NOT NULL,
+	`action` TEXT NOT NULL,
+	`prev_hash` TEXT,
+	`hash` TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE `audit_log` ADD COLUMN `client` TEXT;
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE `decisions`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`version` BIGINT NOT NULL REFERENCES `policies`(`version`),
+	`reporter` TEXT NOT NULL,
+	`recorded_at` TIMESTAMP NOT NULL
+);
+
+CREATE INDEX `decisions_version` ON `decisions`(`version`);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS `active_version_activated_on`;
+DROP INDEX IF EXISTS `policies_creator`;
+DROP INDEX IF EXISTS `policies_created_at`;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+CREATE INDEX `policies_created_at` ON `policies`(`created_at`);
+CREATE INDEX `policies_creator` ON `policies`(`creator`);
+CREATE INDEX `active_version_activated_on` ON `active_version`(`activated_on`);
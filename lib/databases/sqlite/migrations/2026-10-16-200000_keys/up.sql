@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE `keys`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`kind` TEXT NOT NULL,
+	`material` TEXT NOT NULL,
+	`creator` TEXT NOT NULL,
+	`created_at` TIMESTAMP NOT NULL,
+	`revoked_at` TIMESTAMP
+);
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE `revoked_tokens`(
+	`jti` TEXT NOT NULL PRIMARY KEY,
+	`creator` TEXT NOT NULL,
+	`created_at` TIMESTAMP NOT NULL
+);
+
+CREATE TABLE `revoked_users`(
+	`user_id` TEXT NOT NULL PRIMARY KEY,
+	`revoked_before` TIMESTAMP NOT NULL,
+	`creator` TEXT NOT NULL,
+	`created_at` TIMESTAMP NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+CREATE TABLE `event_cursors`(
+	`sink` TEXT NOT NULL PRIMARY KEY,
+	`seq` BIGINT NOT NULL,
+	`updated_at` TIMESTAMP NOT NULL
+);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE `policies` DROP COLUMN `origin_created_at`;
+ALTER TABLE `policies` DROP COLUMN `origin_creator`;
+ALTER TABLE `policies` DROP COLUMN `origin_version`;
+ALTER TABLE `policies` DROP COLUMN `origin_store`;
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE `policies` ADD COLUMN `origin_store` TEXT;
+ALTER TABLE `policies` ADD COLUMN `origin_version` BIGINT;
+ALTER TABLE `policies` ADD COLUMN `origin_creator` TEXT;
+ALTER TABLE `policies` ADD COLUMN `origin_created_at` TIMESTAMP;
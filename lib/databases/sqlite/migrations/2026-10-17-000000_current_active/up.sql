@@ -0,0 +1,14 @@
+-- Your SQL goes here
+-- Points to the activation in `active_version` that is currently in effect, if any. Only ever has
+-- (at most) one row, such that finding the active version is a primary-key read.
+CREATE TABLE `current_active`(
+	`id` INTEGER NOT NULL PRIMARY KEY CHECK (`id` = 1),
+	`version` BIGINT NOT NULL,
+	`activated_on` TIMESTAMP NOT NULL
+);
+
+-- The latest activation used to be the one in effect, unless it was deactivated
+INSERT INTO `current_active`(`id`, `version`, `activated_on`)
+SELECT 1, `version`, `activated_on` FROM (
+	SELECT * FROM `active_version` ORDER BY `activated_on` DESC LIMIT 1
+) WHERE `deactivated_on` IS NULL;
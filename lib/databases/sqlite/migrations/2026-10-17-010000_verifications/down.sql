@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE `policies` DROP COLUMN `verified_at`;
+ALTER TABLE `policies` DROP COLUMN `verified_digest`;
+ALTER TABLE `policies` DROP COLUMN `verified_identity`;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE `policies` ADD COLUMN `verified_identity` TEXT;
+ALTER TABLE `policies` ADD COLUMN `verified_digest` TEXT;
+ALTER TABLE `policies` ADD COLUMN `verified_at` TIMESTAMP;
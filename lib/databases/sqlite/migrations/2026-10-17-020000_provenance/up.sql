@@ -0,0 +1,6 @@
+-- Your SQL goes here
+CREATE TABLE `provenance`(
+	`version` BIGINT NOT NULL PRIMARY KEY REFERENCES `policies`(`version`),
+	`client` TEXT,
+	`request_id` TEXT
+);
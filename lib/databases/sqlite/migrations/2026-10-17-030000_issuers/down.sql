@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE `policies` DROP COLUMN `creator_issuer`;
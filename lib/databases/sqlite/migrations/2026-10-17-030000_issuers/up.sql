@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE `policies` ADD COLUMN `creator_issuer` TEXT;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE `policies` DROP COLUMN `frozen_at`;
+ALTER TABLE `policies` DROP COLUMN `frozen_by_issuer`;
+ALTER TABLE `policies` DROP COLUMN `frozen_by`;
+ALTER TABLE `policies` DROP COLUMN `frozen_reason`;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+-- NOTE: Superseded activations are closed either way; there is no telling which ones this closed
+SELECT 1;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- Activations used to stay open when another version was activated after them. Close them at the
+-- time they were superseded, by whoever superseded them.
+UPDATE `active_version` SET
+	`deactivated_on` = (
+		SELECT `next`.`activated_on` FROM `active_version` AS `next`
+		WHERE `next`.`activated_on` > `active_version`.`activated_on`
+		ORDER BY `next`.`activated_on` ASC LIMIT 1
+	),
+	`deactivated_by` = (
+		SELECT `next`.`activated_by` FROM `active_version` AS `next`
+		WHERE `next`.`activated_on` > `active_version`.`activated_on`
+		ORDER BY `next`.`activated_on` ASC LIMIT 1
+	)
+WHERE `deactivated_on` IS NULL AND EXISTS (
+	SELECT 1 FROM `active_version` AS `next` WHERE `next`.`activated_on` > `active_version`.`activated_on`
+);
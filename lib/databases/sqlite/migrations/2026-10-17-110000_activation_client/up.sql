@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE `active_version` ADD COLUMN `client` TEXT;
@@ -4,7 +4,7 @@
 //  Created:
 //    22 Oct 2024, 14:37:56
 //  Last edited:
-//    07 Feb 2025, 16:53:45
+//    16 Oct 2026, 19:26:32
 //  Auto updated?
 //    Yes
 //
@@ -12,29 +12,32 @@
 //!   Implements the actual [`DatabaseConnector`].
 //
 
-use std::collections::HashMap;
 use std::future::Future;
 use std::marker::PhantomData;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use chrono::{NaiveDateTime, Utc};
-use deadpool::managed::Object;
+use deadpool::managed::{Hook, Object};
 use deadpool_diesel::{Manager, Pool, PoolError};
-use diesel::connection::LoadConnection;
 use diesel::migration::MigrationSource;
 use diesel::sqlite::Sqlite;
-use diesel::{Connection as _, ExpressionMethods as _, QueryDsl as _, RunQueryDsl as _, SelectableHelper as _, SqliteConnection};
+use diesel::{Connection as _, SqliteConnection};
+use diesel_common::{ConnectionError, QueryLogger};
 use diesel_migrations::{FileBasedMigrations, MigrationHarness as _};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use specifications::DatabaseConnector;
-use specifications::databaseconn::DatabaseConnection;
-use specifications::metadata::{AttachedMetadata, Metadata, User};
+use specifications::consistency::Finding;
+use specifications::metadata::AccessContext;
+use specifications::userid::{UserIdError, UserIdFormat};
 use thiserror::Error;
 use tokio::fs;
-use tracing::{Level, debug, info, span};
+use tracing::{Level, debug, info, span, warn};
 
-use crate::models::{SqliteActiveVersion, SqlitePolicy};
+use crate::connection::{self, DieselConnection as SQLiteConnection, PREFLIGHT_QUERIES, execute_all};
 
 
 /***** ERRORS *****/
@@ -69,12 +72,30 @@ pub enum DatabaseError {
         #[source]
         err:  std::io::Error,
     },
+    /// The deadline of the access passed while waiting for a connection.
+    #[error("Deadline passed while waiting for a connection to backend database {:?}", path.display())]
+    DeadlineExceeded { path: PathBuf },
+    /// The ID of the connecting user does not adhere to the configured [`UserIdFormat`].
+    #[error("Refusing to connect to backend database {:?} with illegal user ID", path.display())]
+    IllegalUserId {
+        path: PathBuf,
+        #[source]
+        err:  UserIdError,
+    },
+    /// Failed to perform maintenance on the database.
+    #[error("Failed to run {what:?} on backend database {:?}", path.display())]
+    Maintain {
+        path: PathBuf,
+        what: &'static str,
+        #[source]
+        err:  diesel::result::Error,
+    },
     /// Failed to apply the migrations in a particular folder to a particular database.
-    #[error("Failed to apply migrations to new database {:?}", path.display())]
+    #[error("Failed to apply migrations to database {:?}", path.display())]
     MigrationsApply {
         path: PathBuf,
         #[source]
-        err:  Box<dyn 'static + std::error::Error>,
+        err:  Box<dyn 'static + Send + Sync + std::error::Error>,
     },
     /// Failed to find the migrations for a database in the given folder.
     #[error("Failed to find migrations in migrations folder {:?}", migrations_dir.display())]
@@ -83,103 +104,36 @@ pub enum DatabaseError {
         #[source]
         err: diesel_migrations::MigrationError,
     },
-    /// Failed to create a new connection pool.
-    #[error("Failed to create a connection pool to backend database {:?}", path.display())]
-    PoolCreate {
+    /// Failed to read the database while checking its consistency.
+    #[error("Failed to verify the consistency of backend database {:?}", path.display())]
+    Verify {
         path: PathBuf,
         #[source]
-        err:  deadpool::managed::BuildError,
-    },
-}
-
-/// Defines errors originating from the [`SQLiteConnection`].
-#[derive(Debug, Error)]
-pub enum ConnectionError {
-    /// Failed to add a new version to the backend database.
-    #[error("Failed to add a new version to backend database {:?}", path.display())]
-    AddVersion {
-        path: PathBuf,
-        #[source]
-        err:  diesel::result::Error,
-    },
-    /// Failed to deserialize the given content from JSON.
-    #[error("Failed to deserialize the given content of policy {name:?} ({version}) from JSON")]
-    ContentDeserialize {
-        name:    String,
-        version: u64,
-        #[source]
-        err:     serde_json::Error,
-    },
-    /// Failed to serialize the given content as JSON.
-    #[error("Failed to serialize the content of policy {name:?} as JSON")]
-    ContentSerialize {
-        name: String,
-        #[source]
-        err:  serde_json::Error,
-    },
-    /// Failed to deactivate an active version.
-    #[error("Failed to deactivate active policy version {version} in backend database {:?}", path.display())]
-    DeactivateVersion {
-        path:    PathBuf,
-        version: u64,
-        #[source]
-        err:     diesel::result::Error,
+        err:  ConnectionError,
     },
-    /// Failed to fetch the active version.
-    #[error("Failed to get active version from backend database {:?}", path.display())]
-    GetActiveVersion {
+    /// Failed to repair the database after checking its consistency.
+    #[error("Failed to repair backend database {:?}", path.display())]
+    Repair {
         path: PathBuf,
         #[source]
         err:  diesel::result::Error,
     },
-    /// Failed to fetch the latest version.
-    #[error("Failed to get latest version from backend database {:?}", path.display())]
-    GetLatestVersion {
+    /// A preflight query failed while warming up the connection pool.
+    #[error("Preflight query {what:?} failed on backend database {:?}", path.display())]
+    Preflight {
         path: PathBuf,
+        what: &'static str,
         #[source]
         err:  diesel::result::Error,
     },
-    /// Failed to get a specific version.
-    #[error("Failed to get version {version} from backend database {:?}", path.display())]
-    GetVersion {
-        path:    PathBuf,
-        version: u64,
-        #[source]
-        err:     diesel::result::Error,
-    },
-    /// Failed to get the list of versions.
-    #[error("Failed to get the list of versions from backend database {:?}", path.display())]
-    GetVersions {
+    /// Failed to create a new connection pool.
+    #[error("Failed to create a connection pool to backend database {:?}", path.display())]
+    PoolCreate {
         path: PathBuf,
         #[source]
-        err:  diesel::result::Error,
-    },
-    /// Failed to set the currently active policy.
-    #[error("Failed to set version {version} as the active policy in backend database {:?}", path.display())]
-    SetActive {
-        path:    PathBuf,
-        version: u64,
-        #[source]
-        err:     diesel::result::Error,
-    },
-    /// Failed to spawn a background blocking task.
-    #[error("Failed to spawn a blocking task")]
-    SpawnBlocking {
-        #[source]
-        err: tokio::task::JoinError,
-    },
-    /// Failed to start a transaction with the database.
-    #[error("Failed to start a transaction with the backend database")]
-    Transaction {
-        #[source]
-        err: diesel::result::Error,
+        err:  deadpool::managed::BuildError,
     },
 }
-// Note: implemented to always error for transaction
-impl From<diesel::result::Error> for ConnectionError {
-    #[inline]
-    fn from(value: diesel::result::Error) -> Self { Self::Transaction { err: value } }
-}
 
 
 
@@ -190,9 +144,19 @@ impl From<diesel::result::Error> for ConnectionError {
 #[derive(Clone)]
 pub struct SQLiteDatabase<C> {
     /// The path to the file that we represent. Only retained during runtime for debugging.
-    path:     PathBuf,
+    path: PathBuf,
+    /// The `path` as shown in the errors of our connections.
+    display: String,
     /// The pool of connections.
-    pool:     Pool<deadpool_diesel::Manager<SqliteConnection>>,
+    pool: Pool<deadpool_diesel::Manager<SqliteConnection>>,
+    /// The duration (in microseconds) above which queries are logged as slow. Shared with every
+    /// connection in the `pool`.
+    slow_query_threshold: Arc<AtomicU64>,
+    /// The format that the IDs of connecting users must adhere to.
+    user_ids: Arc<dyn Send + Sync + UserIdFormat>,
+    /// For in-memory databases, a connection that is never used but keeps the database alive
+    /// while the pool's connections come and go.
+    _keeper: Option<Arc<Mutex<SqliteConnection>>>,
     /// Remembers the type of content used.
     _content: PhantomData<C>,
 }
@@ -228,29 +192,88 @@ impl<C> SQLiteDatabase<C> {
             if let Err(err) = fs::File::create(&path).await {
                 return Err(DatabaseError::DatabaseCreate { path, err });
             }
-
-            // Apply them by connecting to the database
-            let mut conn: SqliteConnection = match SqliteConnection::establish(&path.display().to_string()) {
-                Ok(conn) => conn,
-                Err(err) => return Err(DatabaseError::ConnectDatabase { path, err }),
-            };
-            if let Err(err) = conn.run_pending_migrations(migrations) {
-                return Err(DatabaseError::MigrationsApply { path, err });
-            }
         } else {
             debug!("Database {:?} already exists", path.display());
         }
 
+        // Delegate the rest
+        Self::open(path, migrations, false)
+    }
+
+    /// Constructor for an SQLiteDatabase that lives in memory only.
+    ///
+    /// Every call creates a new, empty database, which is dropped together with the last clone of
+    /// the returned connector. Useful for tests.
+    ///
+    /// # Arguments
+    /// - `migrations`: A [`MigrationSource`] with migrations to apply to the new database.
+    ///
+    /// # Returns
+    /// A new SQLiteDatabase struct that can be used to connect to the in-memory database.
+    ///
+    /// # Errors
+    /// This function may fail if we failed to setup a connection pool, or if we failed to apply
+    /// the migrations.
+    pub async fn in_memory_async(migrations: impl MigrationSource<Sqlite>) -> Result<Self, DatabaseError> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        // NOTE: Named and shared such that all connections in the pool see the same database
+        let path: PathBuf =
+            PathBuf::from(format!("file:policy-store-{}-{}?mode=memory&cache=shared", std::process::id(), NEXT_ID.fetch_add(1, Ordering::Relaxed)));
+        debug!("Creating new in-memory SQLite connector {:?}...", path.display());
+        Self::open(path, migrations, true)
+    }
+
+    /// Connects to an existing database and applies any pending migrations.
+    ///
+    /// # Arguments
+    /// - `path`: The path (or URI) of the database to connect to.
+    /// - `migrations`: A [`MigrationSource`] with migrations to apply.
+    /// - `in_memory`: Whether the database lives in memory, in which case it is kept alive by an
+    ///   extra connection and accessed by one connection at a time.
+    ///
+    /// # Returns
+    /// A new SQLiteDatabase struct that can be used to connect to the database.
+    ///
+    /// # Errors
+    /// This function may fail if we failed to connect, to apply the migrations or to setup a
+    /// connection pool.
+    fn open(path: PathBuf, migrations: impl MigrationSource<Sqlite>, in_memory: bool) -> Result<Self, DatabaseError> {
+        // Apply any pending migrations by connecting to the database. This also brings databases
+        // created by older versions of the store up-to-date.
+        let mut conn: SqliteConnection = match SqliteConnection::establish(&path.display().to_string()) {
+            Ok(conn) => conn,
+            Err(err) => return Err(DatabaseError::ConnectDatabase { path, err }),
+        };
+        if let Err(err) = conn.run_pending_migrations(migrations) {
+            return Err(DatabaseError::MigrationsApply { path, err });
+        }
+
         // Create the pool
         debug!("Connecting to database {:?}...", path.display());
         let manager = Manager::new(path.display().to_string(), deadpool::Runtime::Tokio1);
-        let pool = match Pool::builder(manager).build() {
+        let slow_query_threshold: Arc<AtomicU64> = Arc::new(AtomicU64::new(u64::MAX));
+        let threshold: Arc<AtomicU64> = slow_query_threshold.clone();
+        let mut builder = Pool::builder(manager).post_create(Hook::sync_fn(move |conn: &mut deadpool_diesel::Connection<SqliteConnection>, _| {
+            // NOTE: The connection was only just created, so it's never locked or poisoned
+            if let Ok(mut conn) = conn.lock() {
+                conn.set_instrumentation(QueryLogger::new(threshold.clone()));
+            }
+            Ok(())
+        }));
+        if in_memory {
+            // NOTE: Shared-cache databases fail (instead of wait) on concurrent writes
+            builder = builder.max_size(1);
+        }
+        let pool = match builder.build() {
             Ok(pool) => pool,
             Err(err) => return Err(DatabaseError::PoolCreate { path, err }),
         };
 
         // OK, now create self
-        Ok(Self { path, pool, _content: PhantomData })
+        let _keeper: Option<Arc<Mutex<SqliteConnection>>> = if in_memory { Some(Arc::new(Mutex::new(conn))) } else { None };
+        let display: String = path.display().to_string();
+        Ok(Self { path, display, pool, slow_query_threshold, user_ids: Arc::new(()), _keeper, _content: PhantomData })
     }
 
     /// Constructor for the SQLiteDatabase that reads migrations from the given file.
@@ -276,379 +299,207 @@ impl<C> SQLiteDatabase<C> {
         // Delegate to the normal one
         Self::new_async(path, migrations).await
     }
-}
-impl<C: Send + Sync + DeserializeOwned + Serialize + 'static> DatabaseConnector for SQLiteDatabase<C> {
-    type Connection<'s>
-        = SQLiteConnection<'s, C>
-    where
-        Self: 's;
-    type Content = C;
-    type Error = DatabaseError;
 
+    /// Logs queries that take at least the given duration as slow.
+    ///
+    /// All queries are always logged (with their duration) at TRACE-level; slow ones are
+    /// additionally logged at WARN-level. This can help to identify missing indices.
+    ///
+    /// # Arguments
+    /// - `threshold`: The duration above which queries are considered slow.
+    ///
+    /// # Returns
+    /// Self for chaining.
     #[inline]
-    fn connect<'s>(&'s self, user: &'s specifications::metadata::User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
-        async move {
-            // Attempt to get a connection from the pool
-            debug!("Creating new connection to SQLite database {:?}...", self.path.display());
-            match self.pool.get().await {
-                Ok(conn) => Ok(SQLiteConnection { path: &self.path, conn, user, _content: PhantomData }),
-                Err(err) => Err(DatabaseError::Connect { path: self.path.clone(), err }),
-            }
-        }
+    pub fn with_slow_query_threshold(self, threshold: Duration) -> Self {
+        self.slow_query_threshold.store(threshold.as_micros().min(u64::MAX as u128 - 1) as u64, Ordering::Relaxed);
+        self
     }
-}
-
-
 
-/// Represents the connection created by [`SQLiteDatabase::connect()`].
-pub struct SQLiteConnection<'a, C> {
-    /// The path to the file that we represent. Only retained during runtime for debugging.
-    path:     &'a Path,
-    /// The connection we wrap.
-    conn:     Object<Manager<SqliteConnection>>,
-    /// The user that is doing everything in this connection.
-    user:     &'a User,
-    /// Remembers the type of content chosen for this connection.
-    _content: PhantomData<C>,
-}
-impl<C> SQLiteConnection<'_, C> {
-    /// Helper function for doing the non-async active version retrieval.
+    /// Refuses connections for users whose ID does not adhere to the given format.
+    ///
+    /// IDs are [validated](UserIdFormat::validate()), not normalized; servers are expected to
+    /// normalize the IDs they authorize before connecting. This makes sure that the same user is
+    /// never stored under two spellings. Note that this applies to every connection, including
+    /// those made by background tasks on behalf of the store itself. By default, any ID is
+    /// accepted.
     ///
     /// # Arguments
-    /// - `path`: The path where the backend SQLite database lives. Only given for debugging purposes.
-    /// - `conn`: Some [`LoadConnection`] that we use to talk to the file.
+    /// - `format`: The [`UserIdFormat`] to enforce.
     ///
     /// # Returns
-    /// An activate version if there was one (else, [`None`]).
+    /// Self for chaining.
+    #[inline]
+    pub fn with_user_id_format(mut self, format: impl 'static + Send + Sync + UserIdFormat) -> Self {
+        self.user_ids = Arc::new(format);
+        self
+    }
+
+    /// Pre-creates connections in the pool and runs a preflight query on each of them.
+    ///
+    /// Meant to be called at startup, such that problems with the database (e.g., it being
+    /// unreachable or missing tables) surface right away instead of on the first user request.
+    /// The connections are returned to the pool afterwards, so the first requests don't have to
+    /// wait for them to be created either.
+    ///
+    /// # Arguments
+    /// - `connections`: The number of connections to create. Capped at the maximum size of the
+    ///   pool.
     ///
     /// # Errors
-    /// This function errors if we failed to get the active version.
-    fn _get_active_version<C2>(path: &Path, conn: &mut C2) -> Result<Option<u64>, ConnectionError>
-    where
-        C2: LoadConnection<Backend = Sqlite>,
-    {
-        use crate::schema::active_version::dsl::active_version;
-
-        debug!("Fetching active version...");
-        match active_version
-            .limit(1)
-            .order_by(crate::schema::active_version::dsl::activated_on.desc())
-            .select(SqliteActiveVersion::as_select())
-            .load(conn)
-        {
-            Ok(mut r) => match r.pop() {
-                Some(av) => {
-                    if av.deactivated_on.is_some() {
-                        Ok(None)
-                    } else {
-                        Ok(Some(av.version as u64))
-                    }
-                },
-                None => Ok(None),
-            },
-            Err(err) => Err(ConnectionError::GetActiveVersion { path: path.into(), err }),
+    /// This function errors if a connection could not be created or if a preflight query failed
+    /// on it.
+    pub async fn warm_up(&self, connections: usize) -> Result<(), DatabaseError> {
+        let _span = span!(Level::INFO, "SQLiteDatabase::warm_up", path = self.path.display().to_string());
+
+        let max_size: usize = self.pool.status().max_size;
+        if connections > max_size {
+            warn!("Asked to warm up {connections} connections, but the pool holds at most {max_size}; warming up {max_size}");
         }
-    }
-}
-impl<C: Send + Sync + DeserializeOwned + Serialize + 'static> DatabaseConnection for SQLiteConnection<'_, C> {
-    type Content = C;
-    type Error = ConnectionError;
-
-
-    // Mutable
-    fn add_version(&mut self, metadata: AttachedMetadata, content: Self::Content) -> impl Send + Future<Output = Result<u64, Self::Error>> {
-        use crate::schema::policies::dsl::policies;
+        let connections: usize = connections.min(max_size);
+        debug!("Warming up {connections} connection(s) to SQLite database {:?}...", self.path.display());
 
-        async move {
-            let span = span!(Level::INFO, "SQLiteConnection::add_version", policy = metadata.name,);
-
-            debug!("Starting transaction...");
-            let user_id = self.user.id.clone();
-            let path = self.path.to_owned();
-            self.conn
-                .interact(move |conn| {
-                    conn.exclusive_transaction(|conn| -> Result<u64, Self::Error> {
-                        // Trick the compiler into moving the span too
-                        let _span = span;
-
-                        debug!("Retrieving latest policy version...");
-                        let latest: i64 = policies::select(policies, crate::schema::policies::dsl::version)
-                            .order_by(crate::schema::policies::dsl::created_at.desc())
-                            .limit(1)
-                            .load(conn)
-                            .map_err(|err| ConnectionError::GetLatestVersion { path: path.clone(), err })?
-                            .pop()
-                            .unwrap_or(0);
-
-                        // up to next version
-                        let next_version: i64 = latest + 1;
-
-                        // Construct the policy itself
-                        debug!("Adding new policy {next_version}...");
-                        let content = match serde_json::to_string(&content) {
-                            Ok(content) => content,
-                            Err(err) => return Err(ConnectionError::ContentSerialize { name: metadata.name, err }),
-                        };
-                        let model = SqlitePolicy {
-                            name: metadata.name,
-                            description: metadata.description,
-                            language: metadata.language,
-                            version: next_version,
-                            creator: user_id,
-                            created_at: Utc::now().naive_utc(),
-                            content,
-                        };
-
-                        // Submit it
-                        match diesel::insert_into(policies).values(&model).execute(conn) {
-                            Ok(_) => Ok(next_version as u64),
-                            Err(err) => Err(ConnectionError::AddVersion { path, err }),
-                        }
-                    })
-                })
+        // NOTE: Holding on to them all, as the pool would otherwise hand out the same one each time
+        let mut conns: Vec<Object<Manager<SqliteConnection>>> = Vec::with_capacity(connections);
+        for _ in 0..connections {
+            let conn: Object<Manager<SqliteConnection>> = match self.pool.get().await {
+                Ok(conn) => conn,
+                Err(err) => return Err(DatabaseError::Connect { path: self.path.clone(), err }),
+            };
+            conn.interact(|conn| execute_all(conn, &PREFLIGHT_QUERIES))
                 .await
-                .expect("database transaction should not panic")
+                .expect("preflight queries should not panic")
+                .map_err(|(what, err)| DatabaseError::Preflight { path: self.path.clone(), what, err })?;
+            conns.push(conn);
         }
+        info!("Warmed up {connections} connection(s) to SQLite database {:?}", self.path.display());
+        Ok(())
     }
 
-    fn activate(&mut self, version: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
-        use crate::schema::active_version::dsl::active_version;
-
-        async move {
-            let span = span!(Level::INFO, "SQLiteConnection::activate", version = version);
-
-            debug!("Starting transaction...");
-            let path = self.path.to_owned();
-            let user_id = self.user.id.clone();
-            self.conn
-                .interact(move |conn| {
-                    conn.exclusive_transaction(|conn| -> Result<(), Self::Error> {
-                        // Trick the compiler into moving the span too
-                        let _span = span;
-
-                        // Get the information about what to activate
-                        let av = Self::_get_active_version(&path, conn)?;
-
-                        // They may already be the same, ez
-                        if av.is_some_and(|v| v == version) {
-                            info!("Activated already-active version {version}");
-                            return Ok(());
-                        }
-
-                        // Otherwise, build the model and submit it
-                        debug!("Activating policy {version}...");
-                        let model = SqliteActiveVersion::new(version as i64, user_id.clone());
-                        if let Err(err) = diesel::insert_into(active_version).values(&model).execute(conn) {
-                            return Err(ConnectionError::SetActive { path: path.clone(), version, err });
-                        }
-                        Ok(())
-                    })
-                })
-                .await
-                .expect("database transaction should not panic")
+    /// Repairs the issues found by [`DatabaseConnector::verify()`] that can be repaired safely.
+    ///
+    /// These are:
+    /// - [`Finding::OrphanedActivation`]s, which are removed from the activation history; and
+    /// - [`Finding::MissingHash`]es (e.g., of versions added before hashes were recorded), which
+    ///   are recomputed from the content.
+    ///
+    /// Any other findings are left alone, as they need a human to decide what was meant. All
+    /// repairs are done in a single transaction, so either all of them happen or none do.
+    ///
+    /// # Arguments
+    /// - `findings`: The findings to repair.
+    ///
+    /// # Returns
+    /// The findings that were actually repaired.
+    ///
+    /// # Errors
+    /// This function errors if we failed to connect to the database or to write the repairs.
+    pub async fn repair(&self, findings: &[Finding]) -> Result<Vec<Finding>, DatabaseError> {
+        let _span = span!(Level::INFO, "SQLiteDatabase::repair", path = self.path.display().to_string());
+
+        let findings: Vec<Finding> =
+            findings.iter().filter(|finding| matches!(finding, Finding::OrphanedActivation { .. } | Finding::MissingHash { .. })).cloned().collect();
+        if findings.is_empty() {
+            debug!("Nothing to repair in SQLite database {:?}", self.path.display());
+            return Ok(findings);
         }
-    }
 
-    fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> {
-        use crate::schema::active_version::dsl::{active_version, deactivated_by, deactivated_on, version};
-
-        async move {
-            let _span = span!(Level::INFO, "SQLiteConnection::deactivate");
-
-            debug!("Starting transaction...");
-            let path = self.path.to_owned();
-            let user_id = self.user.id.clone();
-            self.conn
-                .interact(move |conn| {
-                    conn.exclusive_transaction(|conn| -> Result<(), Self::Error> {
-                        // Get the current active version, if any
-                        let av = match Self::_get_active_version(&path, conn)? {
-                            Some(av) => av,
-                            None => {
-                                info!("Deactivated a policy whilst none were active");
-                                return Ok(());
-                            },
-                        };
-
-                        // If we found one, then update it
-                        debug!("Deactivating active policy {av}...");
-                        if let Err(err) = diesel::update(active_version)
-                            .filter(version.eq(av as i64))
-                            .set((deactivated_on.eq(Utc::now().naive_local()), deactivated_by.eq(&user_id)))
-                            .execute(conn)
-                        {
-                            return Err(ConnectionError::DeactivateVersion { path: path.clone(), version: av, err });
-                        }
-                        Ok(())
-                    })
-                })
-                .await
-                .expect("database transaction should not panic")
-        }
+        let conn: Object<Manager<SqliteConnection>> = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(err) => return Err(DatabaseError::Connect { path: self.path.clone(), err }),
+        };
+        let repaired: Vec<Finding> = conn
+            .interact(move |conn| connection::repair(conn, findings))
+            .await
+            .expect("repairs should not panic")
+            .map_err(|err| DatabaseError::Repair { path: self.path.clone(), err })?;
+        info!("Repaired {} issue(s) in SQLite database {:?}", repaired.len(), self.path.display());
+        Ok(repaired)
     }
+}
+impl<C: Send + Sync + DeserializeOwned + Serialize + 'static> DatabaseConnector for SQLiteDatabase<C> {
+    type Connection<'s>
+        = SQLiteConnection<'s, C>
+    where
+        Self: 's;
+    type Content = C;
+    type Error = DatabaseError;
 
-
-    // Immutable
-    fn get_versions(&mut self) -> impl Send + Future<Output = Result<HashMap<u64, Metadata>, Self::Error>> {
-        use crate::schema::policies::dsl as policy;
-
-        async move {
-            let _span = span!(Level::INFO, "SQLiteConnection::get_versions");
-
-            let path = self.path.to_owned();
-            self.conn
-                .interact(move |conn| {
-                    debug!("Retrieving all policy versions...");
-                    match policy::policies
-                        .order_by(crate::schema::policies::dsl::created_at.desc())
-                        .select((policy::description, policy::name, policy::language, policy::version, policy::creator, policy::created_at))
-                        .load::<(String, String, String, i64, String, NaiveDateTime)>(conn)
-                    {
-                        Ok(r) => Ok(r
-                            .into_iter()
-                            .map(|(description, name, language, version, creator, created_at)| {
-                                (version as u64, Metadata {
-                                    attached: AttachedMetadata { name, description, language },
-                                    version:  version as u64,
-                                    creator:  User { id: creator, name: "John Smith".into() },
-                                    created:  created_at.and_utc(),
-                                })
-                            })
-                            .collect()),
-                        Err(err) => Err(ConnectionError::GetVersions { path, err }),
-                    }
-                })
-                .await
-                .expect("database transaction should not panic")
-        }
+    #[inline]
+    fn connect<'s>(&'s self, user: &'s specifications::metadata::User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        self.connect_from(user, None)
     }
 
-    fn get_active_version(&mut self) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+    fn connect_from<'s>(
+        &'s self,
+        user: &'s specifications::metadata::User,
+        client: Option<IpAddr>,
+    ) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
         async move {
-            let _span = span!(Level::INFO, "SQLiteConnection::get_active");
+            if let Err(err) = self.user_ids.validate(&user.id) {
+                return Err(DatabaseError::IllegalUserId { path: self.path.clone(), err });
+            }
 
-            // Do a call to get the active, if any
-            let path = self.path.to_owned();
-            self.conn.interact(move |conn| Self::_get_active_version(&path, conn)).await.expect("database transaction should not panic")
+            // Attempt to get a connection from the pool
+            debug!("Creating new connection to SQLite database {:?}...", self.path.display());
+            match self.pool.get().await {
+                Ok(conn) => Ok(SQLiteConnection { url: &self.display, conn, user, client, request_id: None, deadline: None, _content: PhantomData }),
+                Err(err) => Err(DatabaseError::Connect { path: self.path.clone(), err }),
+            }
         }
     }
 
-    fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>> {
-        use crate::schema::active_version::dsl::active_version;
-
+    /// Does not wait for a connection past the deadline of the access, and the returned connection
+    /// does not start any work past it either.
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
         async move {
-            let _span = span!(Level::INFO, "SQLiteConnection::get_active");
-
-            // Do a call to get the active, if any
-            debug!("Fetching active version...");
-            let path = self.path.to_owned();
-            self.conn
-                .interact(move |conn| {
-                    match active_version
-                        .limit(1)
-                        .order_by(crate::schema::active_version::dsl::activated_on.desc())
-                        .select(SqliteActiveVersion::as_select())
-                        .load(conn)
-                    {
-                        Ok(mut r) => match r.pop() {
-                            Some(av) => {
-                                if av.deactivated_on.is_some() {
-                                    Ok(None)
-                                } else {
-                                    Ok(Some(User { id: av.activated_by, name: "John Smith".into() }))
-                                }
-                            },
-                            None => Ok(None),
-                        },
-                        Err(err) => Err(ConnectionError::GetActiveVersion { path: path.clone(), err }),
-                    }
-                })
-                .await
-                .expect("database transaction should not panic")
+            let mut conn: SQLiteConnection<'s, C> = match context.deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline.into(), self.connect_from(&context.user, context.client)).await {
+                    Ok(res) => res?,
+                    Err(_) => return Err(DatabaseError::DeadlineExceeded { path: self.path.clone() }),
+                },
+                None => self.connect_from(&context.user, context.client).await?,
+            };
+            conn.request_id = context.request_id.clone();
+            conn.deadline = context.deadline;
+            Ok(conn)
         }
     }
 
-    fn get_version_metadata(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Metadata>, Self::Error>> {
-        use crate::schema::policies::dsl as policy;
-
+    /// Checkpoints the write-ahead log and then `VACUUM`s the database file.
+    ///
+    /// Note that vacuuming rewrites the entire file, blocking writers while it runs.
+    fn maintain(&self) -> impl Send + Future<Output = Result<(), Self::Error>> {
         async move {
-            let _span = span!(Level::INFO, "SQLiteConnection::get_version_metadata", version = version);
-
-            debug!("Retrieving metadata for version {version}...");
-            let path = self.path.to_owned();
-            self.conn
-                .interact(move |conn| {
-                    match policy::policies
-                        .limit(1)
-                        .filter(crate::schema::policies::dsl::version.eq(version as i64))
-                        .order_by(crate::schema::policies::dsl::created_at.desc())
-                        .select((policy::description, policy::name, policy::language, policy::version, policy::creator, policy::created_at))
-                        .load::<(String, String, String, i64, String, NaiveDateTime)>(conn)
-                    {
-                        Ok(mut r) => {
-                            // Extract the version itself
-                            if r.is_empty() {
-                                return Ok(None);
-                            }
-                            let (description, name, language, version, creator, created_at) = r.remove(0);
-
-                            // Done, return the thing
-                            Ok(Some(Metadata {
-                                attached: AttachedMetadata { name, description, language },
-                                created:  created_at.and_utc(),
-                                creator:  User { id: creator, name: "John Smith".into() },
-                                version:  version as u64,
-                            }))
-                        },
-                        Err(err) => match err {
-                            diesel::result::Error::NotFound => Ok(None),
-                            err => Err(ConnectionError::GetVersion { path: path.clone(), version, err }),
-                        },
-                    }
-                })
+            let _span = span!(Level::INFO, "SQLiteDatabase::maintain", path = self.path.display().to_string());
+
+            let conn: Object<Manager<SqliteConnection>> = match self.pool.get().await {
+                Ok(conn) => conn,
+                Err(err) => return Err(DatabaseError::Connect { path: self.path.clone(), err }),
+            };
+            conn.interact(|conn| execute_all(conn, &["PRAGMA wal_checkpoint(TRUNCATE)", "VACUUM"]))
                 .await
-                .expect("database transaction should not panic")
+                .expect("database maintenance should not panic")
+                .map_err(|(what, err)| DatabaseError::Maintain { path: self.path.clone(), what, err })
         }
     }
 
-    fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>> {
-        use crate::schema::policies::dsl as policy;
-
+    /// Checks that the active version and every activation refer to existing versions, that every
+    /// version is numbered higher than the versions it derives from, and that the content of
+    /// every version is valid and matches its hash.
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> {
         async move {
-            let _span = span!(Level::INFO, "SQLiteConnection::get_version_content", version = version);
-
-            let path = self.path.to_owned();
-            self.conn
-                .interact(move |conn| {
-                    debug!("Retrieving content for version {version}...");
-                    match policy::policies
-                        .limit(1)
-                        .filter(crate::schema::policies::dsl::version.eq(version as i64))
-                        .order_by(crate::schema::policies::dsl::created_at.desc())
-                        .select((policy::name, policy::content))
-                        .load::<(String, String)>(conn)
-                    {
-                        Ok(mut r) => {
-                            // Extract the version itself
-                            if r.is_empty() {
-                                return Ok(None);
-                            }
-                            let (name, content) = r.remove(0);
-
-                            // Deserialize the content
-                            match serde_json::from_str(&content) {
-                                Ok(content) => Ok(Some(content)),
-                                Err(err) => Err(ConnectionError::ContentDeserialize { name, version, err }),
-                            }
-                        },
-                        Err(err) => match err {
-                            diesel::result::Error::NotFound => Ok(None),
-                            err => Err(ConnectionError::GetVersion { path: path.clone(), version, err }),
-                        },
-                    }
-                })
+            let _span = span!(Level::INFO, "SQLiteDatabase::verify", path = self.path.display().to_string());
+
+            let conn: Object<Manager<SqliteConnection>> = match self.pool.get().await {
+                Ok(conn) => conn,
+                Err(err) => return Err(DatabaseError::Connect { path: self.path.clone(), err }),
+            };
+            let url: String = self.display.clone();
+            conn.interact(move |conn| connection::verify::<C>(&url, conn))
                 .await
-                .expect("database transaction should not panic")
+                .expect("consistency checks should not panic")
+                .map_err(|err| DatabaseError::Verify { path: self.path.clone(), err })
         }
     }
 }
@@ -0,0 +1,110 @@
+//  DIALECT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:06:42
+//  Last edited:
+//    16 Oct 2026, 18:06:42
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the parts of the shared diesel connection that are
+//!   specific to SQLite.
+//
+
+use diesel::sqlite::Sqlite;
+use diesel::{Connection as _, QueryResult, RunQueryDsl as _, SqliteConnection};
+
+use crate::models::{DbCurrentActive, DbEventCursor, DbRevokedToken, DbRevokedUser, DbTemplate};
+
+
+/***** LIBRARY *****/
+/// The connection type of this backend.
+pub(crate) type RawConnection = SqliteConnection;
+
+/// The diesel backend of this backend.
+pub(crate) type Backend = Sqlite;
+
+
+
+/// Runs the given work in an exclusive transaction, such that no other connection writes to the
+/// file while it runs.
+///
+/// # Arguments
+/// - `conn`: The [`SqliteConnection`] to run the transaction on.
+/// - `work`: A closure doing the actual work.
+///
+/// # Returns
+/// Whatever the `work` returns.
+///
+/// # Errors
+/// This function errors if the `work` does, or if we failed to start the transaction.
+#[inline]
+pub(crate) fn exclusive_transaction<R, E>(conn: &mut SqliteConnection, work: impl FnOnce(&mut SqliteConnection) -> Result<R, E>) -> Result<R, E>
+where
+    E: From<diesel::result::Error>,
+{
+    conn.exclusive_transaction(work)
+}
+
+/// Runs the given work in a transaction that sees a single snapshot of the database.
+///
+/// Any transaction does, as SQLite's transactions are serializable.
+///
+/// # Arguments
+/// - `conn`: The [`SqliteConnection`] to run the transaction on.
+/// - `work`: A closure doing the actual work.
+///
+/// # Returns
+/// Whatever the `work` returns.
+///
+/// # Errors
+/// This function errors if the `work` does, or if we failed to start the transaction.
+#[inline]
+pub(crate) fn snapshot_transaction<R, E>(conn: &mut SqliteConnection, work: impl FnOnce(&mut SqliteConnection) -> Result<R, E>) -> Result<R, E>
+where
+    E: From<diesel::result::Error>,
+{
+    conn.transaction(work)
+}
+
+
+
+/// Points the `current_active` pointer to the version in the given model.
+#[inline]
+pub(crate) fn upsert_current_active(conn: &mut SqliteConnection, model: &DbCurrentActive) -> QueryResult<usize> {
+    use crate::schema::current_active::dsl::current_active;
+    diesel::replace_into(current_active).values(model).execute(conn)
+}
+
+/// Adds the given template, replacing any template with the same name.
+#[inline]
+pub(crate) fn upsert_template(conn: &mut SqliteConnection, model: &DbTemplate) -> QueryResult<usize> {
+    use crate::schema::templates::dsl::templates;
+    diesel::replace_into(templates).values(model).execute(conn)
+}
+
+/// Adds the given revoked token, unless it already is.
+///
+/// # Returns
+/// The number of tokens revoked, i.e., `0` if it already was.
+#[inline]
+pub(crate) fn insert_revoked_token(conn: &mut SqliteConnection, model: &DbRevokedToken) -> QueryResult<usize> {
+    use crate::schema::revoked_tokens::dsl::revoked_tokens;
+    diesel::insert_or_ignore_into(revoked_tokens).values(model).execute(conn)
+}
+
+/// Revokes the tokens of the user in the given model, replacing any earlier revocation of theirs.
+#[inline]
+pub(crate) fn upsert_revoked_user(conn: &mut SqliteConnection, model: &DbRevokedUser) -> QueryResult<usize> {
+    use crate::schema::revoked_users::dsl::revoked_users;
+    diesel::replace_into(revoked_users).values(model).execute(conn)
+}
+
+/// Moves the cursor of the sink in the given model, creating it if it doesn't exist yet.
+#[inline]
+pub(crate) fn upsert_event_cursor(conn: &mut SqliteConnection, model: &DbEventCursor) -> QueryResult<usize> {
+    use crate::schema::event_cursors::dsl::event_cursors;
+    diesel::replace_into(event_cursors).values(model).execute(conn)
+}
@@ -0,0 +1,320 @@
+//  LEGACY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:33:31
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements reading the checker database of the (v1) policy reasoner,
+//!   and importing its contents into a store.
+//!
+//!   That database is an SQLite file with the following two tables:
+//!   - `policies(version, description, version_description, creator,
+//!     created_at, content)`, where `content` is the policy as JSON; and
+//!   - `active_version(version, activated_on, activated_by,
+//!     deactivated_on, deactivated_by)`.
+//
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::{Connection as _, ExpressionMethods as _, OptionalExtension as _, QueryDsl as _, RunQueryDsl as _, SqliteConnection};
+use serde::de::DeserializeOwned;
+use specifications::databaseconn::{ClientError, DatabaseConnection};
+use specifications::metadata::{AttachedMetadata, Metadata, User};
+use thiserror::Error;
+use tracing::{Level, debug, info, span, warn};
+
+
+/***** ERRORS *****/
+/// Defines errors when reading a legacy database.
+#[derive(Debug, Error)]
+pub enum LegacyError {
+    /// Failed to open the legacy database.
+    #[error("Failed to open legacy database {:?}", path.display())]
+    Connect {
+        path: PathBuf,
+        #[source]
+        err:  diesel::ConnectionError,
+    },
+    /// A version in the legacy database has a number we can't represent.
+    #[error("Legacy database {:?} contains policy version {version}, which is not a valid version number", path.display())]
+    InvalidVersion { path: PathBuf, version: i64 },
+    /// The legacy database does not exist.
+    #[error("Legacy database {:?} does not exist", path.display())]
+    NotFound { path: PathBuf },
+    /// Failed to read one of the tables of the legacy database.
+    #[error("Failed to read table {what:?} of legacy database {:?}", path.display())]
+    Read {
+        path: PathBuf,
+        what: &'static str,
+        #[source]
+        err:  diesel::result::Error,
+    },
+}
+
+/// Defines errors when importing a legacy database into a store.
+#[derive(Debug, Error)]
+pub enum ImportError<E> {
+    /// Failed to activate the version that was active in the legacy database.
+    #[error("Failed to activate imported policy version {version} (legacy version {legacy})")]
+    Activate {
+        legacy:  u64,
+        version: u64,
+        #[source]
+        err:     E,
+    },
+    /// The content of a legacy version could not be parsed.
+    #[error("Failed to parse the content of legacy policy version {version}")]
+    Content {
+        version: u64,
+        #[source]
+        err:     serde_json::Error,
+    },
+    /// Failed to import a legacy version into the store.
+    #[error("Failed to import legacy policy version {version}")]
+    Import {
+        version: u64,
+        #[source]
+        err:     E,
+    },
+    /// The store refused to import a legacy version.
+    #[error("Store refused to import legacy policy version {version}")]
+    Rejected {
+        version: u64,
+        #[source]
+        err:     ClientError,
+    },
+}
+
+
+
+
+
+/***** SCHEMA *****/
+mod schema {
+    diesel::table! {
+        policies (version) {
+            version -> BigInt,
+            description -> Text,
+            version_description -> Text,
+            creator -> Text,
+            created_at -> Timestamp,
+            content -> Text,
+        }
+    }
+
+    diesel::table! {
+        active_version (version, activated_on) {
+            version -> BigInt,
+            activated_on -> Timestamp,
+            activated_by -> Text,
+            deactivated_on -> Nullable<Timestamp>,
+            deactivated_by -> Nullable<Text>,
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A policy version as it was stored by the legacy policy reasoner.
+#[derive(Clone, Debug)]
+pub struct LegacyVersion {
+    /// The version number of the policy.
+    pub version: u64,
+    /// The description of the policy as a whole. Becomes the [name](AttachedMetadata::name).
+    pub description: String,
+    /// The description of this version in particular. Becomes the
+    /// [description](AttachedMetadata::description).
+    pub version_description: String,
+    /// The identifier of the user who wrote the version.
+    pub creator: String,
+    /// The time the version was written.
+    pub created: DateTime<Utc>,
+    /// The policy itself, as (unparsed) JSON.
+    pub content: String,
+}
+
+/// Everything read from the checker database of the legacy policy reasoner.
+#[derive(Clone, Debug)]
+pub struct LegacyDatabase {
+    /// The versions in the database, ordered by version number.
+    pub versions: Vec<LegacyVersion>,
+    /// The version that is active, if any.
+    pub active:   Option<u64>,
+}
+impl LegacyDatabase {
+    /// Reads a legacy database.
+    ///
+    /// Note that this blocks while reading; async callers may want to wrap it in
+    /// [`tokio::task::spawn_blocking()`].
+    ///
+    /// # Arguments
+    /// - `path`: The path to the legacy database file.
+    ///
+    /// # Returns
+    /// A new LegacyDatabase with everything in it.
+    ///
+    /// # Errors
+    /// This function errors if the file does not exist, could not be read or does not look like a
+    /// legacy database.
+    pub fn read(path: impl Into<PathBuf>) -> Result<Self, LegacyError> {
+        use self::schema::active_version::dsl as av;
+        use self::schema::policies::dsl as policy;
+
+        let path: PathBuf = path.into();
+        let _span = span!(Level::INFO, "LegacyDatabase::read", path = path.display().to_string());
+
+        // NOTE: SQLite would happily create an empty database instead
+        if !path.exists() {
+            return Err(LegacyError::NotFound { path });
+        }
+        debug!("Opening legacy database {:?}...", path.display());
+        let mut conn: SqliteConnection = match SqliteConnection::establish(&path.display().to_string()) {
+            Ok(conn) => conn,
+            Err(err) => return Err(LegacyError::Connect { path, err }),
+        };
+
+        debug!("Reading versions...");
+        type Row = (i64, String, String, String, NaiveDateTime, String);
+        let rows: Vec<Row> = match policy::policies
+            .select((policy::version, policy::description, policy::version_description, policy::creator, policy::created_at, policy::content))
+            .order_by(policy::version.asc())
+            .load(&mut conn)
+        {
+            Ok(rows) => rows,
+            Err(err) => return Err(LegacyError::Read { path, what: "policies", err }),
+        };
+        let mut versions: Vec<LegacyVersion> = Vec::with_capacity(rows.len());
+        for (version, description, version_description, creator, created_at, content) in rows {
+            let Ok(version) = u64::try_from(version) else {
+                return Err(LegacyError::InvalidVersion { path, version });
+            };
+            versions.push(LegacyVersion { version, description, version_description, creator, created: created_at.and_utc(), content });
+        }
+
+        debug!("Reading active version...");
+        let active: Option<i64> = match av::active_version
+            .filter(av::deactivated_on.is_null())
+            .order_by(av::activated_on.desc())
+            .select(av::version)
+            .first(&mut conn)
+            .optional()
+        {
+            Ok(active) => active,
+            Err(err) => return Err(LegacyError::Read { path, what: "active_version", err }),
+        };
+        let active: Option<u64> = match active.map(u64::try_from) {
+            Some(Ok(active)) => Some(active),
+            Some(Err(_)) => return Err(LegacyError::InvalidVersion { path, version: active.unwrap_or_default() }),
+            None => None,
+        };
+
+        info!("Read {} version(s) from legacy database {:?}", versions.len(), path.display());
+        Ok(Self { versions, active })
+    }
+}
+
+
+
+/// Reports what was imported by [`import()`].
+#[derive(Clone, Debug, Default)]
+pub struct Imported {
+    /// Maps the version numbers in the legacy database to the version numbers of their copies.
+    pub versions: BTreeMap<u64, u64>,
+    /// The version of the copy that was activated, if any.
+    pub active:   Option<u64>,
+}
+
+/// Imports the contents of a legacy database into a store.
+///
+/// Every version is [imported](DatabaseConnection::import_version()) in order on the default
+/// branch, recording its original version number, creator and creation time as its
+/// [origin](Metadata::origin). The version that was active in the legacy database is then
+/// activated in the store. As usual for imports, the connecting user becomes the creator of the
+/// copies (and their activator).
+///
+/// Note that versions are imported one by one, so if this fails halfway, the versions imported
+/// up to then remain in the store.
+///
+/// # Arguments
+/// - `conn`: A [`DatabaseConnection`] to the store to import into.
+/// - `store`: The name to record the legacy database under in the origins of the copies.
+/// - `language`: The language to record the copies as written in, as the legacy database did not
+///   record any.
+/// - `legacy`: The [`LegacyDatabase`] to import.
+///
+/// # Returns
+/// An [`Imported`] describing which versions were imported as what.
+///
+/// # Errors
+/// This function errors if the content of a version does not parse as the store's content, or if
+/// the store failed to import or activate a version.
+pub async fn import<D: DatabaseConnection>(
+    conn: &mut D,
+    store: &str,
+    language: &str,
+    legacy: LegacyDatabase,
+) -> Result<Imported, ImportError<D::Error>>
+where
+    D::Content: DeserializeOwned,
+{
+    let mut imported = Imported::default();
+    for version in legacy.versions {
+        let content: D::Content = match serde_json::from_str(&version.content) {
+            Ok(content) => content,
+            Err(err) => return Err(ImportError::Content { version: version.version, err }),
+        };
+        let metadata = Metadata {
+            attached: AttachedMetadata {
+                name: version.description,
+                description: version.version_description,
+                language: language.into(),
+                branch: specifications::metadata::default_branch(),
+                schema: None,
+                confidential: false,
+            },
+            created: version.created,
+            creator: User { id: version.creator.clone(), name: version.creator, issuer: None, roles: Vec::new() },
+            version: version.version,
+            parent: None,
+            promoted_from: None,
+            hash: None,
+            origin: None,
+            verification: None,
+            frozen: None,
+        };
+
+        debug!("Importing legacy policy version {}...", version.version);
+        match conn.import_version(store, metadata, content).await {
+            Ok(Ok(copy)) => {
+                imported.versions.insert(version.version, copy);
+            },
+            Ok(Err(err)) => return Err(ImportError::Rejected { version: version.version, err }),
+            Err(err) => return Err(ImportError::Import { version: version.version, err }),
+        }
+    }
+
+    // Restore which one was active
+    if let Some(legacy) = legacy.active {
+        if let Some(&version) = imported.versions.get(&legacy) {
+            debug!("Activating imported policy version {version} (legacy version {legacy})...");
+            if let Err(err) = conn.activate(version).await {
+                return Err(ImportError::Activate { legacy, version, err });
+            }
+            imported.active = Some(version);
+        } else {
+            warn!("Legacy database marks version {legacy} as active, but it does not exist; not activating anything");
+        }
+    }
+    info!("Imported {} legacy policy version(s)", imported.versions.len());
+    Ok(imported)
+}
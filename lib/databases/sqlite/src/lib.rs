@@ -4,7 +4,7 @@
 //  Created:
 //    22 Oct 2024, 14:37:34
 //  Last edited:
-//    06 Nov 2024, 14:05:26
+//    16 Oct 2026, 18:06:42
 //  Auto updated?
 //    Yes
 //
@@ -13,14 +13,25 @@
 //
 
 // Declare modules
+mod connection {
+    diesel_common::connection!();
+}
 mod databaseconn;
+mod dialect;
+pub mod legacy;
 // #[cfg(feature = "embedded-migrations")]
 // pub mod migrations;
-mod models;
+mod models {
+    diesel_common::models!();
+}
 mod schema;
+#[cfg(feature = "test-utils")]
+pub mod testing;
 
 // Import some of it
+pub use connection::DieselConnection as SQLiteConnection;
 pub use databaseconn::*;
+pub use diesel_common::ConnectionError;
 
 
 // Optionally import the migrations
@@ -7,6 +7,65 @@ diesel::table! {
         activated_by -> Text,
         deactivated_on -> Nullable<Timestamp>,
         deactivated_by -> Nullable<Text>,
+        client -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    audit_log (seq) {
+        seq -> BigInt,
+        timestamp -> Timestamp,
+        user_id -> Text,
+        action -> Text,
+        prev_hash -> Nullable<Text>,
+        hash -> Text,
+        client -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    branches (name) {
+        name -> Text,
+        head -> Nullable<BigInt>,
+        base -> Nullable<BigInt>,
+        creator -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    current_active (id) {
+        id -> Integer,
+        version -> BigInt,
+        activated_on -> Timestamp,
+    }
+}
+
+diesel::table! {
+    decisions (id) {
+        id -> Text,
+        version -> BigInt,
+        reporter -> Text,
+        recorded_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    event_cursors (sink) {
+        sink -> Text,
+        seq -> BigInt,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    keys (id) {
+        id -> Text,
+        kind -> Text,
+        material -> Text,
+        creator -> Text,
+        created_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
     }
 }
 
@@ -19,7 +78,89 @@ diesel::table! {
         created_at -> Timestamp,
         content -> Text,
         language -> Text,
+        branch -> Text,
+        parent -> Nullable<BigInt>,
+        promoted_from -> Nullable<BigInt>,
+        content_hash -> Nullable<Text>,
+        schema -> Nullable<Text>,
+        origin_store -> Nullable<Text>,
+        origin_version -> Nullable<BigInt>,
+        origin_creator -> Nullable<Text>,
+        origin_created_at -> Nullable<Timestamp>,
+        verified_identity -> Nullable<Text>,
+        verified_digest -> Nullable<Text>,
+        verified_at -> Nullable<Timestamp>,
+        creator_issuer -> Nullable<Text>,
+        frozen_reason -> Nullable<Text>,
+        frozen_by -> Nullable<Text>,
+        frozen_by_issuer -> Nullable<Text>,
+        frozen_at -> Nullable<Timestamp>,
+        confidential -> Bool,
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(active_version, policies,);
+diesel::table! {
+    provenance (version) {
+        version -> BigInt,
+        client -> Nullable<Text>,
+        request_id -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    revoked_tokens (jti) {
+        jti -> Text,
+        creator -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    revoked_users (user_id) {
+        user_id -> Text,
+        revoked_before -> Timestamp,
+        creator -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    schemas (name) {
+        name -> Text,
+        description -> Text,
+        schema -> Text,
+        creator -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    templates (name) {
+        name -> Text,
+        description -> Text,
+        language -> Text,
+        parameters -> Text,
+        skeleton -> Text,
+        creator -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::joinable!(decisions -> policies (version));
+diesel::joinable!(provenance -> policies (version));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    active_version,
+    audit_log,
+    branches,
+    current_active,
+    decisions,
+    event_cursors,
+    keys,
+    policies,
+    provenance,
+    revoked_tokens,
+    revoked_users,
+    schemas,
+    templates,
+);
@@ -0,0 +1,42 @@
+//  TESTING.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:44:37
+//  Last edited:
+//    16 Oct 2026, 16:44:37
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Provides helpers for testing against an [`SQLiteDatabase`], both in
+//!   this crate and in the ones wrapping it.
+//
+
+use std::path::PathBuf;
+
+use crate::SQLiteDatabase;
+
+
+/***** LIBRARY *****/
+/// Creates a fresh database file for a test.
+///
+/// The database is created with the migrations of this crate, and stores booleans as policy
+/// content to keep tests short.
+///
+/// # Arguments
+/// - `name`: A name for the test, used to make the file unique.
+///
+/// # Returns
+/// A connector to the new database, and the path to its file.
+///
+/// # Panics
+/// This function panics if the database could not be created.
+pub async fn fresh_database(name: &str) -> (SQLiteDatabase<bool>, PathBuf) {
+    let path: PathBuf = std::env::temp_dir().join(format!("policy-store-{name}-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let db = SQLiteDatabase::with_migrations_from_dir_async(&path, concat!(env!("CARGO_MANIFEST_DIR"), "/migrations"))
+        .await
+        .expect("failed to create test database");
+    (db, path)
+}
@@ -0,0 +1,160 @@
+//  ACTIVATIONS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:27:07
+//  Last edited:
+//    17 Oct 2026, 00:28:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the SQLite connector keeps track of the active version.
+//
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Utc};
+use diesel::{Connection as _, RunQueryDsl as _, SqliteConnection};
+use specifications::DatabaseConnector as _;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::{AccessContext, User};
+use specifications::testing::metadata;
+use sqlite_database::SQLiteDatabase;
+use sqlite_database::testing::fresh_database;
+
+
+/***** CONSTANTS *****/
+/// The migration introducing the `current_active` pointer.
+const POINTER_MIGRATION: &str = "2026-10-17-000000_current_active";
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Copies all migrations from before the `current_active` pointer to a new directory.
+///
+/// # Arguments
+/// - `dir`: The directory to create a `migrations` folder in.
+fn copy_old_migrations(dir: &Path) {
+    let src: PathBuf = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations"));
+    for entry in std::fs::read_dir(&src).unwrap() {
+        let entry = entry.unwrap();
+        // NOTE: Skips the `.keep`-file as well
+        if !entry.file_type().unwrap().is_dir() || entry.file_name().to_string_lossy().as_ref() >= POINTER_MIGRATION {
+            continue;
+        }
+        let target: PathBuf = dir.join("migrations").join(entry.file_name());
+        std::fs::create_dir_all(&target).unwrap();
+        for file in ["up.sql", "down.sql"] {
+            std::fs::copy(entry.path().join(file), target.join(file)).unwrap();
+        }
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_active_pointer() {
+    let (db, path) = fresh_database("active-pointer").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    for _ in 0..2 {
+        conn.add_version(metadata(), true).await.unwrap();
+    }
+
+    // Activating moves the pointer, deactivating clears it
+    assert_eq!(conn.get_active_version().await.unwrap(), None);
+    let access = AccessContext::new(user.clone()).with_client(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+    db.connect_with(&access).await.unwrap().activate(1).await.unwrap();
+    conn.activate(2).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(2));
+    assert_eq!(conn.get_activator().await.unwrap().map(|user| user.id), Some("tester".into()));
+    conn.deactivate().await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), None);
+    assert!(conn.get_activation().await.unwrap().is_none());
+
+    // The history is kept regardless, including where each activation came from
+    let activations = conn.get_activations().await.unwrap();
+    assert_eq!(activations.iter().map(|a| a.version).collect::<Vec<u64>>(), vec![1, 2]);
+    assert_eq!(activations.iter().map(|a| a.client).collect::<Vec<Option<IpAddr>>>(), vec![access.client, None]);
+    assert!(activations[1].deactivated.is_some());
+
+    // Even if the clock turns back, the last activation is what counts
+    let mut raw = SqliteConnection::establish(&path.display().to_string()).unwrap();
+    diesel::sql_query("UPDATE active_version SET activated_on = '2999-01-01 00:00:00' WHERE version = 2").execute(&mut raw).unwrap();
+    drop(raw);
+    conn.activate(1).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(1));
+    assert_eq!(conn.get_activation().await.unwrap().map(|a| a.version), Some(1));
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_prune_superseded() {
+    let (db, path) = fresh_database("prune-superseded").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    for _ in 0..2 {
+        conn.add_version(metadata(), true).await.unwrap();
+    }
+
+    // Activating another version closes the activation it supersedes...
+    conn.activate(1).await.unwrap();
+    conn.activate(2).await.unwrap();
+    let activations = conn.get_activations().await.unwrap();
+    assert_eq!(activations[0].deactivated, Some(activations[1].activated));
+    assert_eq!(activations[1].deactivated, None);
+
+    // ...such that it can be pruned, unlike the active one
+    assert_eq!(conn.prune_activations(Utc::now() + Duration::seconds(1)).await.unwrap(), 1);
+    let activations = conn.get_activations().await.unwrap();
+    assert_eq!(activations.iter().map(|a| a.version).collect::<Vec<u64>>(), vec![2]);
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(2));
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_active_pointer_migration() {
+    let dir: PathBuf = std::env::temp_dir().join(format!("policy-store-pointer-migration-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    copy_old_migrations(&dir);
+    let path: PathBuf = dir.join("policies.db");
+
+    // Create a database as older releases did, with some activations in it
+    drop(SQLiteDatabase::<bool>::with_migrations_from_dir_async(&path, dir.join("migrations")).await.unwrap());
+    let mut raw = SqliteConnection::establish(&path.display().to_string()).unwrap();
+    diesel::sql_query(
+        "INSERT INTO active_version (version, activated_on, activated_by) VALUES (1, '2026-01-01 00:00:00', 'amy'), (2, '2026-02-01 00:00:00', \
+         'bob')",
+    )
+    .execute(&mut raw)
+    .unwrap();
+    drop(raw);
+
+    // Upgrading points to the latest activation, and closes the one it superseded
+    let db = SQLiteDatabase::<bool>::with_migrations_from_dir_async(&path, concat!(env!("CARGO_MANIFEST_DIR"), "/migrations")).await.unwrap();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(2));
+    assert_eq!(conn.get_activator().await.unwrap().map(|user| user.id), Some("bob".into()));
+    let activations = conn.get_activations().await.unwrap();
+    assert_eq!(activations[0].deactivated, Some(activations[1].activated));
+    assert_eq!(activations[0].deactivator.as_ref().map(|user| user.id.as_str()), Some("bob"));
+    assert_eq!(activations[1].deactivated, None);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,101 @@
+//  CONSISTENCY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:30:22
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests checking (and repairing) the consistency of an SQLite database.
+//
+
+use diesel::{Connection as _, RunQueryDsl as _, SqliteConnection};
+use specifications::DatabaseConnector as _;
+use specifications::consistency::Finding;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::User;
+use specifications::testing::metadata;
+use sqlite_database::testing::fresh_database;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_verify() {
+    let (db, path) = fresh_database("verify").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    for _ in 0..5 {
+        conn.add_version(metadata(), true).await.unwrap();
+    }
+    conn.activate(5).await.unwrap();
+    drop(conn);
+
+    // A database that was only touched through the connector is fine
+    assert_eq!(db.verify().await.unwrap(), Vec::new());
+
+    // Break it in every way we can think of
+    let mut raw = SqliteConnection::establish(&path.display().to_string()).unwrap();
+    for query in [
+        "UPDATE policies SET content = 'not json' WHERE version = 1",
+        "UPDATE policies SET content = '\"not a boolean\"', content_hash = NULL WHERE version = 2",
+        "UPDATE policies SET content_hash = 'abc' WHERE version = 3",
+        "UPDATE policies SET parent = 4 WHERE version = 4",
+        "DELETE FROM policies WHERE version = 5",
+    ] {
+        diesel::sql_query(query).execute(&mut raw).unwrap();
+    }
+    drop(raw);
+
+    // Everything is found, without failing
+    let findings: Vec<Finding> = db.verify().await.unwrap();
+    assert!(matches!(findings[0], Finding::InvalidContent { version: 1, .. }), "{findings:?}");
+    assert_eq!(findings[1], Finding::MissingHash { version: 2 });
+    assert!(matches!(findings[2], Finding::InvalidContent { version: 2, .. }), "{findings:?}");
+    assert!(matches!(findings[3], Finding::HashMismatch { version: 3, .. }), "{findings:?}");
+    assert_eq!(findings[4], Finding::NonMonotoneVersion { version: 4, parent: 4 });
+    assert_eq!(findings[5], Finding::DanglingActive { version: 5 });
+    assert!(matches!(findings[6], Finding::OrphanedActivation { version: 5, .. }), "{findings:?}");
+    assert_eq!(findings.len(), 7);
+
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_repair() {
+    let (db, path) = fresh_database("repair").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    for _ in 0..3 {
+        conn.add_version(metadata(), true).await.unwrap();
+    }
+    conn.activate(2).await.unwrap();
+    conn.activate(3).await.unwrap();
+    drop(conn);
+
+    // Simulate an old database that didn't record hashes, and a botched manual cleanup
+    let mut raw = SqliteConnection::establish(&path.display().to_string()).unwrap();
+    for query in ["UPDATE policies SET content_hash = NULL WHERE version IN (1, 3)", "DELETE FROM policies WHERE version = 2"] {
+        diesel::sql_query(query).execute(&mut raw).unwrap();
+    }
+    drop(raw);
+    let findings: Vec<Finding> = db.verify().await.unwrap();
+    assert_eq!(findings.len(), 3, "{findings:?}");
+
+    // Repairing fixes all of those
+    let repaired: Vec<Finding> = db.repair(&findings).await.unwrap();
+    assert_eq!(repaired, findings);
+    assert_eq!(db.verify().await.unwrap(), Vec::new());
+    let mut conn = db.connect(&user).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(3));
+
+    // Repairing again is harmless
+    assert_eq!(db.repair(&findings).await.unwrap(), Vec::new());
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
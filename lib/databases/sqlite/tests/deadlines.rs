@@ -0,0 +1,53 @@
+//  DEADLINES.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:17:06
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests that the SQLite connector doesn't start work past the deadline
+//!   of an access.
+//
+
+use std::time::{Duration, Instant};
+
+use specifications::DatabaseConnector as _;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::{AccessContext, User};
+use specifications::testing::metadata;
+use sqlite_database::ConnectionError;
+use sqlite_database::testing::fresh_database;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_no_work_past_deadline() {
+    let (db, path) = fresh_database("deadlines").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+
+    // Work before the deadline is done as usual
+    let context = AccessContext::new(user.clone()).with_deadline(Instant::now() + Duration::from_secs(60));
+    let mut conn = db.connect_with(&context).await.unwrap();
+    let version: u64 = conn.add_version(metadata(), true).await.unwrap();
+    drop(conn);
+
+    // Work after it is not started at all
+    let context = AccessContext::new(user.clone()).with_deadline(Instant::now() + Duration::from_millis(100));
+    let mut conn = db.connect_with(&context).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(context.expired());
+    assert!(matches!(conn.add_version(metadata(), true).await, Err(ConnectionError::DeadlineExceeded { .. })));
+    drop(conn);
+
+    // Without a deadline, all is fine again
+    let mut conn = db.connect(&user).await.unwrap();
+    assert_eq!(conn.get_versions().await.unwrap().into_keys().collect::<Vec<u64>>(), vec![version]);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
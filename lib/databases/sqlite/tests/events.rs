@@ -0,0 +1,119 @@
+//  EVENTS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:04:06
+//  Last edited:
+//    16 Oct 2026, 19:38:21
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests delivering events from the SQLite connector's audit log.
+//
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::event::{EventDelivery, EventRecord, EventSink, PolicyEvent};
+use specifications::metadata::User;
+use specifications::testing::metadata;
+use specifications::{DatabaseConnector as _, Worker as _};
+use sqlite_database::SQLiteDatabase;
+use sqlite_database::testing::fresh_database;
+use thiserror::Error;
+
+
+/***** HELPER FUNCTIONS *****/
+/// The error emitted by the [`TestSink`] when told to fail.
+#[derive(Debug, Error)]
+#[error("Test sink is down")]
+struct SinkDown;
+
+/// Remembers every event delivered to it.
+#[derive(Default)]
+struct TestSink {
+    /// Whether to fail deliveries.
+    down:   AtomicBool,
+    /// The events delivered so far.
+    events: Mutex<Vec<EventRecord>>,
+}
+impl EventSink for TestSink {
+    type Error = SinkDown;
+
+    fn name(&self) -> &str { "test" }
+
+    fn deliver(&self, events: &[EventRecord]) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        let res = if self.down.load(Ordering::SeqCst) {
+            Err(SinkDown)
+        } else {
+            self.events.lock().unwrap().extend_from_slice(events);
+            Ok(())
+        };
+        async move { res }
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_ack_events_only_moves_forward() {
+    let (db, path) = fresh_database("ack-events").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    assert_eq!(conn.get_event_cursor("test").await.unwrap(), 0);
+    conn.ack_events("test", 5).await.unwrap();
+    conn.ack_events("test", 3).await.unwrap();
+    assert_eq!(conn.get_event_cursor("test").await.unwrap(), 5);
+    assert_eq!(conn.get_event_cursor("other").await.unwrap(), 0);
+
+    // Acknowledging is not a change to the store
+    assert!(conn.get_audit_log(None).await.unwrap().is_empty());
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_event_delivery() {
+    let (db, path) = fresh_database("event-delivery").await;
+    let db: Arc<SQLiteDatabase<bool>> = Arc::new(db);
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let version: u64 = conn.add_version(metadata(), true).await.unwrap();
+    conn.activate(version).await.unwrap();
+
+    // Deliver in tiny batches to see that the cursor is kept in between
+    let mut delivery = EventDelivery::new(db.clone(), user.clone(), TestSink::default()).with_interval(Duration::from_millis(10)).with_batch_size(1);
+    assert_eq!(delivery.deliver().await.unwrap(), 2);
+    assert_eq!(delivery.deliver().await.unwrap(), 0);
+    let events: Vec<PolicyEvent> = delivery.sink().events.lock().unwrap().iter().map(|record| record.event.clone()).collect();
+    assert_eq!(events, vec![PolicyEvent::VersionAdded { version, name: "test".into(), branch: "main".into() }, PolicyEvent::Activated { version }]);
+
+    // Failures should be retried later, with backoff
+    delivery.sink().down.store(true, Ordering::SeqCst);
+    conn.deactivate().await.unwrap();
+    assert!(delivery.tick().await.is_err());
+    assert_eq!(delivery.interval(), Duration::from_millis(20));
+    delivery.sink().down.store(false, Ordering::SeqCst);
+    delivery.tick().await.unwrap();
+    assert_eq!(delivery.interval(), Duration::from_millis(10));
+    assert_eq!(delivery.sink().events.lock().unwrap().last().map(|record| &record.event), Some(&PolicyEvent::Deactivated { version }));
+
+    // Everything should be acknowledged now
+    let last: u64 = conn.get_audit_log(None).await.unwrap().last().map(|record| record.seq).unwrap();
+    assert_eq!(conn.get_event_cursor("test").await.unwrap(), last);
+
+    drop(conn);
+    drop(delivery);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
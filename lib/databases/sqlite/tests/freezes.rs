@@ -0,0 +1,104 @@
+//  FREEZES.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:18:27
+//  Last edited:
+//    16 Oct 2026, 18:18:27
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the SQLite connector freezes policy versions.
+//
+
+use specifications::DatabaseConnector as _;
+use specifications::audit::AuditAction;
+use specifications::databaseconn::{ClientError, DatabaseConnection as _};
+use specifications::metadata::{Freeze, User};
+use specifications::testing::{metadata, metadata_on};
+use sqlite_database::ConnectionError;
+use sqlite_database::testing::fresh_database;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_freeze_lifecycle() {
+    let (db, path) = fresh_database("freeze-lifecycle").await;
+    let user = User { id: "responder".into(), name: "Responder".into(), issuer: Some("https://idp.example.com".into()), roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let version: u64 = conn.add_version(metadata(), true).await.unwrap();
+
+    // Freeze it and find out by whom, and why
+    conn.freeze_version(version, "suspected to be malicious").await.unwrap().unwrap();
+    let frozen: Freeze = conn.get_version_metadata(version).await.unwrap().unwrap().frozen.expect("version should be frozen");
+    assert_eq!(frozen.reason, "suspected to be malicious");
+    assert_eq!((frozen.freezer.id.as_str(), frozen.freezer.issuer.as_deref()), ("responder", Some("https://idp.example.com")));
+
+    // It can no longer be activated
+    assert!(matches!(conn.activate(version).await, Err(ConnectionError::VersionFrozen { .. })));
+    assert_eq!(conn.get_active_version().await.unwrap(), None);
+
+    // Until it is unfrozen (twice)
+    conn.unfreeze_version(version).await.unwrap().unwrap();
+    conn.unfreeze_version(version).await.unwrap().unwrap();
+    assert!(conn.get_version_metadata(version).await.unwrap().unwrap().frozen.is_none());
+    conn.activate(version).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(version));
+
+    // Unknown versions cannot be frozen
+    assert!(matches!(conn.freeze_version(42, "typo").await.unwrap(), Err(ClientError::UnknownVersion { version: 42 })));
+    assert!(matches!(conn.unfreeze_version(42).await.unwrap(), Err(ClientError::UnknownVersion { version: 42 })));
+
+    // Both changes are audited, but the second unfreeze isn't
+    let actions: Vec<AuditAction> =
+        conn.get_audit_log(None).await.unwrap().into_iter().map(|record| record.action).filter(|action| action.kind().contains("freeze")).collect();
+    let freeze = AuditAction::FreezeVersion { version, reason: "suspected to be malicious".into() };
+    assert_eq!(actions, vec![freeze, AuditAction::UnfreezeVersion { version }]);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_freeze_keeps_active() {
+    let (db, path) = fresh_database("freeze-active").await;
+    let user = User { id: "responder".into(), name: "Responder".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let version: u64 = conn.add_version(metadata(), true).await.unwrap();
+    conn.activate(version).await.unwrap();
+
+    // Freezing the active version leaves it active; that's what deactivating is for
+    conn.freeze_version(version, "suspected to be malicious").await.unwrap().unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(version));
+    conn.deactivate().await.unwrap();
+    assert!(matches!(conn.activate(version).await, Err(ConnectionError::VersionFrozen { .. })));
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_freeze_blocks_promotion() {
+    let (db, path) = fresh_database("freeze-promote").await;
+    let user = User { id: "responder".into(), name: "Responder".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let base: u64 = conn.add_version(metadata(), true).await.unwrap();
+    conn.create_branch("staging", base).await.unwrap().unwrap();
+    let version: u64 = conn.add_version(metadata_on("staging"), true).await.unwrap();
+
+    // A frozen version cannot be promoted
+    conn.freeze_version(version, "suspected to be malicious").await.unwrap().unwrap();
+    assert!(matches!(conn.promote(version, "main", false).await.unwrap(), Err(ClientError::VersionFrozen { .. })));
+
+    // But once unfrozen, it can; and the copy isn't frozen either way
+    conn.unfreeze_version(version).await.unwrap().unwrap();
+    let promoted: u64 = conn.promote(version, "main", false).await.unwrap().unwrap();
+    assert!(conn.get_version_metadata(promoted).await.unwrap().unwrap().frozen.is_none());
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
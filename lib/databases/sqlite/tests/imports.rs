@@ -0,0 +1,70 @@
+//  IMPORTS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:11:02
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests importing versions from one store into another.
+//
+
+use specifications::DatabaseConnector as _;
+use specifications::audit::AuditAction;
+use specifications::databaseconn::{ClientError, DatabaseConnection as _};
+use specifications::metadata::{Metadata, Origin, User};
+use specifications::testing::metadata;
+use sqlite_database::testing::fresh_database;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_import_version() {
+    let (staging, staging_path) = fresh_database("import-staging").await;
+    let (production, production_path) = fresh_database("import-production").await;
+    let (mirror, mirror_path) = fresh_database("import-mirror").await;
+    let amy = User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() };
+    let bob = User { id: "bob".into(), name: "Bob".into(), issuer: None, roles: Vec::new() };
+
+    // Write a version in staging, with another one before it to have the numbers differ
+    let mut conn = staging.connect(&amy).await.unwrap();
+    conn.add_version(metadata(), false).await.unwrap();
+    let source: u64 = conn.add_version(metadata(), true).await.unwrap();
+    let original: Metadata = conn.get_version_metadata(source).await.unwrap().unwrap();
+    drop(conn);
+
+    // Import it into production
+    let mut conn = production.connect(&bob).await.unwrap();
+    let version: u64 = conn.import_version("staging", original.clone(), true).await.unwrap().unwrap();
+    let imported: Metadata = conn.get_version_metadata(version).await.unwrap().unwrap();
+    assert_eq!(imported.creator.id, "bob");
+    assert_eq!(imported.attached.branch, "main");
+    assert_eq!(imported.hash, original.hash);
+    let origin: Origin = imported.origin.clone().unwrap();
+    assert_eq!((origin.store.as_str(), origin.version, origin.creator.as_str()), ("staging", source, "amy"));
+    assert_eq!(origin.created.timestamp(), original.created.timestamp());
+    assert_eq!(conn.get_version_content(version).await.unwrap(), Some(true));
+    assert!(matches!(
+        conn.get_audit_log(None).await.unwrap().last().map(|record| &record.action),
+        Some(AuditAction::Import { store, source: s, .. }) if store == "staging" && *s == source
+    ));
+
+    // Content that doesn't match its hash should be refused
+    assert!(matches!(conn.import_version("staging", original, false).await.unwrap(), Err(ClientError::HashMismatch { .. })));
+    drop(conn);
+
+    // Importing it again should keep pointing to where it was written
+    let mut conn = mirror.connect(&bob).await.unwrap();
+    let version: u64 = conn.import_version("production", imported, true).await.unwrap().unwrap();
+    let origin: Origin = conn.get_version_metadata(version).await.unwrap().unwrap().origin.unwrap();
+    assert_eq!((origin.store.as_str(), origin.version, origin.creator.as_str()), ("staging", source, "amy"));
+
+    drop(conn);
+    drop((staging, production, mirror));
+    for path in [staging_path, production_path, mirror_path] {
+        let _ = std::fs::remove_file(&path);
+    }
+}
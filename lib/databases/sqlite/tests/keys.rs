@@ -0,0 +1,70 @@
+//  KEYS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:44:37
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the SQLite connector stores verification keys.
+//
+
+use specifications::DatabaseConnector as _;
+use specifications::audit::AuditAction;
+use specifications::databaseconn::{ClientError, DatabaseConnection as _};
+use specifications::key::{AttachedKey, KeyKind};
+use specifications::metadata::User;
+use sqlite_database::testing::fresh_database;
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates a key to register.
+///
+/// # Arguments
+/// - `id`: The identifier of the key.
+///
+/// # Returns
+/// An [`AttachedKey`] with a shared secret.
+fn key(id: &str) -> AttachedKey { AttachedKey { id: id.into(), kind: KeyKind::Oct, material: "c2VjcmV0".into() } }
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_keys_lifecycle() {
+    let (db, path) = fresh_database("keys-lifecycle").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    // Register a key and find it back
+    conn.add_key(key("key-1")).await.unwrap().unwrap();
+    let found = conn.get_key("key-1").await.unwrap().unwrap();
+    assert_eq!(found.attached.kind, KeyKind::Oct);
+    assert_eq!(found.attached.material, "c2VjcmV0");
+    assert_eq!(found.creator.id, "tester");
+    assert!(found.revoked.is_none());
+    assert!(conn.get_key("key-2").await.unwrap().is_none());
+
+    // Revoke it (twice)
+    conn.revoke_key("key-1").await.unwrap().unwrap();
+    conn.revoke_key("key-1").await.unwrap().unwrap();
+    assert!(conn.get_key("key-1").await.unwrap().unwrap().revoked.is_some());
+    assert!(matches!(conn.revoke_key("key-2").await.unwrap(), Err(ClientError::UnknownKey { .. })));
+
+    // Its identifier cannot be reused
+    assert!(matches!(conn.add_key(key("key-1")).await.unwrap(), Err(ClientError::KeyExists { .. })));
+    assert_eq!(conn.get_keys().await.unwrap().len(), 1);
+
+    // Both changes are audited, but the second revocation isn't
+    let actions: Vec<AuditAction> = conn.get_audit_log(None).await.unwrap().into_iter().map(|record| record.action).collect();
+    assert_eq!(actions, vec![AuditAction::AddKey { id: "key-1".into() }, AuditAction::RevokeKey { id: "key-1".into() }]);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,113 @@
+//  LEGACY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:33:31
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests importing the checker database of the legacy policy reasoner.
+//
+
+use std::path::PathBuf;
+
+use diesel::{Connection as _, RunQueryDsl as _, SqliteConnection};
+use serde_json::{Value, json};
+use specifications::DatabaseConnector as _;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::User;
+use sqlite_database::SQLiteDatabase;
+use sqlite_database::legacy::{LegacyDatabase, LegacyError, import};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates a legacy database for a test.
+///
+/// # Arguments
+/// - `name`: A name for the test, used to make the file unique.
+///
+/// # Returns
+/// The path to the new legacy database file.
+fn legacy_database(name: &str) -> PathBuf {
+    let path: PathBuf = std::env::temp_dir().join(format!("policy-store-{name}-legacy-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut conn = SqliteConnection::establish(&path.display().to_string()).unwrap();
+    for query in [
+        "CREATE TABLE policies (id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, description TEXT NOT NULL, version BIGINT NOT NULL, \
+         version_description TEXT NOT NULL, creator TEXT NOT NULL, created_at TIMESTAMP NOT NULL, content TEXT NOT NULL)",
+        "CREATE TABLE active_version (version BIGINT NOT NULL, activated_on TIMESTAMP NOT NULL, activated_by TEXT NOT NULL, deactivated_on \
+         TIMESTAMP, deactivated_by TEXT, PRIMARY KEY(version, activated_on))",
+        "INSERT INTO policies (description, version, version_description, creator, created_at, content) VALUES ('policy', 1, 'first', 'amy', \
+         '2023-05-01 12:00:00', '[{\"reasoner\":\"eflint\",\"content\":[]}]')",
+        "INSERT INTO policies (description, version, version_description, creator, created_at, content) VALUES ('policy', 2, 'second', 'bob', \
+         '2023-06-01 12:00:00', '[{\"reasoner\":\"eflint\",\"content\":[1]}]')",
+        "INSERT INTO policies (description, version, version_description, creator, created_at, content) VALUES ('policy', 3, 'third', 'amy', \
+         '2023-07-01 12:00:00', '[{\"reasoner\":\"eflint\",\"content\":[2]}]')",
+        "INSERT INTO active_version VALUES (3, '2023-07-01 13:00:00', 'amy', '2023-07-02 13:00:00', 'bob')",
+        "INSERT INTO active_version VALUES (2, '2023-07-02 13:00:00', 'bob', NULL, NULL)",
+    ] {
+        diesel::sql_query(query).execute(&mut conn).unwrap();
+    }
+    path
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_import_legacy() {
+    let legacy_path: PathBuf = legacy_database("import");
+    let path: PathBuf = std::env::temp_dir().join(format!("policy-store-import-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let db: SQLiteDatabase<Value> = SQLiteDatabase::with_migrations_from_dir_async(&path, concat!(env!("CARGO_MANIFEST_DIR"), "/migrations"))
+        .await
+        .expect("failed to create test database");
+
+    // Read the legacy database
+    let legacy: LegacyDatabase = LegacyDatabase::read(&legacy_path).unwrap();
+    assert_eq!(legacy.versions.iter().map(|version| version.version).collect::<Vec<u64>>(), vec![1, 2, 3]);
+    assert_eq!(legacy.active, Some(2));
+
+    // Import it into a store that already has something in it
+    let user = User { id: "operator".into(), name: "Operator".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let metadata = specifications::metadata::AttachedMetadata {
+        name: "existing".into(),
+        description: "Added before the import".into(),
+        language: "eflint-json".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+    conn.add_version(metadata, json!(null)).await.unwrap();
+    let imported = import(&mut conn, "policy-reasoner-v1", "eflint-json", legacy).await.unwrap();
+    assert_eq!(imported.versions.into_iter().collect::<Vec<(u64, u64)>>(), vec![(1, 2), (2, 3), (3, 4)]);
+    assert_eq!(imported.active, Some(3));
+
+    // The history is preserved in the origins
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(3));
+    let metadata = conn.get_version_metadata(3).await.unwrap().unwrap();
+    assert_eq!(metadata.attached.description, "second");
+    assert_eq!(metadata.creator.id, "operator");
+    let origin = metadata.origin.unwrap();
+    assert_eq!((origin.store.as_str(), origin.version, origin.creator.as_str()), ("policy-reasoner-v1", 2, "bob"));
+    assert_eq!(origin.created.to_rfc3339(), "2023-06-01T12:00:00+00:00");
+    assert_eq!(conn.get_version_content(4).await.unwrap(), Some(json!([{ "reasoner": "eflint", "content": [2] }])));
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&legacy_path);
+}
+
+#[test]
+fn test_missing_legacy() {
+    let path: PathBuf = std::env::temp_dir().join(format!("policy-store-missing-legacy-{}.db", std::process::id()));
+    assert!(matches!(LegacyDatabase::read(&path), Err(LegacyError::NotFound { .. })));
+    assert!(!path.exists());
+}
@@ -0,0 +1,87 @@
+//  PROVENANCE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:47:42
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests recording the provenance of policy versions.
+//
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use specifications::DatabaseConnector as _;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::{AccessContext, Metadata, User};
+use specifications::provenance::{Creation, Provenance, Validation};
+use specifications::testing::metadata;
+use sqlite_database::testing::fresh_database;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_added_provenance() {
+    let (db, path) = fresh_database("provenance-added").await;
+    let amy = User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() };
+    let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+    // What is only known while writing is recorded...
+    let access = AccessContext::new(amy.clone()).with_client(client).with_request_id("req-1");
+    let mut conn = db.connect_with(&access).await.unwrap();
+    let version: u64 = conn.add_version(metadata(), true).await.unwrap();
+    let provenance: Provenance = conn.get_version_provenance(version).await.unwrap().unwrap();
+    assert_eq!(provenance.subject[0].name, format!("test:{version}"));
+    let internal = &provenance.predicate.build_definition.internal_parameters;
+    assert_eq!((internal.creation, internal.uploader.as_str(), internal.client), (Creation::Added, "amy", Some(client)));
+    assert!(internal.validations.is_empty());
+    assert!(provenance.predicate.build_definition.resolved_dependencies.is_empty());
+    assert_eq!(provenance.predicate.run_details.metadata.invocation_id.as_deref(), Some("req-1"));
+    drop(conn);
+
+    // ...and simply missing when it's not known
+    let mut conn = db.connect(&amy).await.unwrap();
+    let version: u64 = conn.add_version(metadata(), false).await.unwrap();
+    let provenance: Provenance = conn.get_version_provenance(version).await.unwrap().unwrap();
+    assert_eq!(provenance.predicate.build_definition.internal_parameters.client, None);
+    assert_eq!(provenance.predicate.run_details.metadata.invocation_id, None);
+    assert!(conn.get_version_provenance(version + 1).await.unwrap().is_none());
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_imported_provenance() {
+    let (staging, staging_path) = fresh_database("provenance-staging").await;
+    let (production, production_path) = fresh_database("provenance-production").await;
+    let amy = User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() };
+    let bob = User { id: "bob".into(), name: "Bob".into(), issuer: None, roles: Vec::new() };
+
+    let mut conn = staging.connect(&amy).await.unwrap();
+    let source: u64 = conn.add_version(metadata(), true).await.unwrap();
+    let original: Metadata = conn.get_version_metadata(source).await.unwrap().unwrap();
+    drop(conn);
+
+    // The copy refers to the original, and records that its hash was checked
+    let mut conn = production.connect(&bob).await.unwrap();
+    let version: u64 = conn.import_version("staging", original.clone(), true).await.unwrap().unwrap();
+    let provenance: Provenance = conn.get_version_provenance(version).await.unwrap().unwrap();
+    let definition = &provenance.predicate.build_definition;
+    assert_eq!((definition.internal_parameters.creation, definition.internal_parameters.uploader.as_str()), (Creation::Imported, "bob"));
+    assert_eq!(definition.resolved_dependencies.len(), 1);
+    assert_eq!(definition.resolved_dependencies[0].name, format!("staging/{source}"));
+    let hash: String = original.hash.unwrap();
+    assert!(matches!(&definition.internal_parameters.validations[..], [Validation::Hash { digest }] if *digest == format!("sha256:{hash}")));
+    assert_eq!(provenance.subject[0].digest.get("sha256"), Some(&hash));
+
+    drop(conn);
+    drop((staging, production));
+    for path in [staging_path, production_path] {
+        let _ = std::fs::remove_file(&path);
+    }
+}
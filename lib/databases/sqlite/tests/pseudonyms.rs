@@ -0,0 +1,67 @@
+//  PSEUDONYMS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 23:54:44
+//  Last edited:
+//    17 Oct 2026, 02:16:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the SQLite connector pseudonymizes users.
+//
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use chrono::{DateTime, Duration, Utc};
+use specifications::DatabaseConnector as _;
+use specifications::audit::{AuditAction, AuditRecord, verify_chain};
+use specifications::databaseconn::{ClientError, DatabaseConnection as _};
+use specifications::metadata::{AccessContext, Metadata, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::testing::metadata;
+use sqlite_database::testing::fresh_database;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_pseudonymize_user() {
+    let (db, path) = fresh_database("pseudonymize-user").await;
+    let amy = User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() };
+    let admin = User { id: "admin".into(), name: "Admin".into(), issuer: None, roles: Vec::new() };
+    let access = AccessContext::new(amy.clone()).with_client(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+    let version: u64 = db.connect_with(&access).await.unwrap().add_version(metadata(), true).await.unwrap();
+    let mut conn = db.connect(&admin).await.unwrap();
+    conn.activate(version).await.unwrap();
+    db.connect(&amy).await.unwrap().freeze_version(version, "suspected to be malicious").await.unwrap().unwrap();
+    let before: DateTime<Utc> = Utc::now();
+    conn.revoke_tokens(AttachedRevocation::User { user: "amy".into(), before }).await.unwrap();
+
+    // The version (and where she wrote it from), its freeze, its branch and three audit records are Amy's
+    assert_eq!(conn.pseudonymize_user("amy", "user-1").await.unwrap().unwrap(), 7);
+    let pseudonymized: Metadata = conn.get_version_metadata(version).await.unwrap().unwrap();
+    assert_eq!(pseudonymized.creator.id, "user-1");
+    assert_eq!(pseudonymized.frozen.expect("version should still be frozen").freezer.id, "user-1");
+    let provenance: Provenance = conn.get_version_provenance(version).await.unwrap().unwrap();
+    assert_eq!(provenance.predicate.build_definition.internal_parameters.client, None);
+
+    // Her tokens stay revoked, which they can only be by her actual ID
+    let revocations: Vec<Revocation> = conn.get_revocations().await.unwrap();
+    assert!(matches!(&revocations[..], [Revocation { attached: AttachedRevocation::User { user, .. }, .. }] if user == "amy"));
+    assert!(conn.is_revoked(None, "amy", Some(before - Duration::seconds(1))).await.unwrap());
+    assert!(!conn.is_revoked(None, "user-1", Some(before - Duration::seconds(1))).await.unwrap());
+    assert!(matches!(conn.pseudonymize_user("amy", "user-2").await.unwrap(), Err(ClientError::UnknownUser { .. })));
+
+    // The rewritten audit log still verifies
+    let log: Vec<AuditRecord> = conn.get_audit_log(None).await.unwrap();
+    assert!(log.iter().all(|record| record.user != "amy"));
+    assert!(log.iter().any(|record| record.action == AuditAction::RevokeUserTokens { user: "user-1".into(), before }));
+    assert_eq!(verify_chain(&log).unwrap(), 5);
+    assert_eq!(log.last().map(|record| &record.action), Some(&AuditAction::Pseudonymize { pseudonym: "user-1".into(), rehashed_from: Some(1) }));
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,75 @@
+//  REVOCATIONS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:48:23
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the SQLite connector stores token revocations.
+//
+
+use chrono::{TimeDelta, TimeZone as _, Utc};
+use specifications::DatabaseConnector as _;
+use specifications::audit::AuditAction;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::User;
+use specifications::revocation::AttachedRevocation;
+use sqlite_database::testing::fresh_database;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_revoke_token() {
+    let (db, path) = fresh_database("revoke-token").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    assert!(!conn.is_revoked(Some("token-1"), "amy", None).await.unwrap());
+    conn.revoke_tokens(AttachedRevocation::Token { jti: "token-1".into() }).await.unwrap();
+    conn.revoke_tokens(AttachedRevocation::Token { jti: "token-1".into() }).await.unwrap();
+    assert!(conn.is_revoked(Some("token-1"), "amy", None).await.unwrap());
+    assert!(!conn.is_revoked(Some("token-2"), "amy", None).await.unwrap());
+    assert!(!conn.is_revoked(None, "amy", None).await.unwrap());
+
+    // Only the first revocation is audited
+    let actions: Vec<AuditAction> = conn.get_audit_log(None).await.unwrap().into_iter().map(|record| record.action).collect();
+    assert_eq!(actions, vec![AuditAction::RevokeToken { jti: "token-1".into() }]);
+    assert_eq!(conn.get_revocations().await.unwrap().len(), 1);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_revoke_user_tokens() {
+    let (db, path) = fresh_database("revoke-user-tokens").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    let before = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+    conn.revoke_tokens(AttachedRevocation::User { user: "amy".into(), before }).await.unwrap();
+    assert!(conn.is_revoked(None, "amy", Some(before - TimeDelta::hours(1))).await.unwrap());
+    assert!(!conn.is_revoked(None, "amy", Some(before + TimeDelta::hours(1))).await.unwrap());
+    assert!(conn.is_revoked(Some("token-1"), "amy", None).await.unwrap());
+    assert!(!conn.is_revoked(None, "bob", None).await.unwrap());
+
+    // An earlier revocation doesn't undo a later one
+    conn.revoke_tokens(AttachedRevocation::User { user: "amy".into(), before: before - TimeDelta::days(1) }).await.unwrap();
+    assert!(conn.is_revoked(None, "amy", Some(before - TimeDelta::hours(1))).await.unwrap());
+    conn.revoke_tokens(AttachedRevocation::User { user: "amy".into(), before: before + TimeDelta::days(1) }).await.unwrap();
+    assert!(conn.is_revoked(None, "amy", Some(before + TimeDelta::hours(1))).await.unwrap());
+
+    let revocations = conn.get_revocations().await.unwrap();
+    assert_eq!(revocations.len(), 1);
+    assert_eq!(revocations[0].attached, AttachedRevocation::User { user: "amy".into(), before: before + TimeDelta::days(1) });
+    assert_eq!(conn.get_audit_log(None).await.unwrap().len(), 2);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
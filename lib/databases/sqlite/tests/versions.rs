@@ -0,0 +1,72 @@
+//  VERSIONS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:17:13
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests how the SQLite connector allocates version numbers.
+//
+
+use diesel::{Connection as _, RunQueryDsl as _, SqliteConnection};
+use specifications::DatabaseConnector as _;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::User;
+use specifications::testing::metadata;
+use sqlite_database::testing::fresh_database;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_versions_unique_after_clock_rollback() {
+    let (db, path) = fresh_database("clock-rollback").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+
+    // Add some versions, then pretend the clock was turned back by moving the latest far into the past
+    let mut versions: Vec<u64> = Vec::new();
+    for _ in 0..3 {
+        versions.push(conn.add_version(metadata(), true).await.unwrap());
+    }
+    let mut raw = SqliteConnection::establish(&path.display().to_string()).unwrap();
+    diesel::sql_query("UPDATE policies SET created_at = '1970-01-01 00:00:00' WHERE version = 3").execute(&mut raw).unwrap();
+    drop(raw);
+    for _ in 0..3 {
+        versions.push(conn.add_version(metadata(), true).await.unwrap());
+    }
+
+    // Every version must be fresh
+    assert_eq!(versions, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(conn.get_versions().await.unwrap().len(), 6);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_creator_issuer() {
+    let (db, path) = fresh_database("creator-issuer").await;
+
+    // The same ID from two organizations is told apart by the issuer
+    let mut versions: Vec<u64> = Vec::new();
+    for issuer in [Some("https://idp.a.example.com"), Some("https://idp.b.example.com"), None] {
+        let user = User { id: "amy".into(), name: "Amy".into(), issuer: issuer.map(String::from), roles: Vec::new() };
+        versions.push(db.connect(&user).await.unwrap().add_version(metadata(), true).await.unwrap());
+    }
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let mut conn = db.connect(&user).await.unwrap();
+    let mut issuers: Vec<Option<String>> = Vec::new();
+    for version in versions {
+        issuers.push(conn.get_version_metadata(version).await.unwrap().unwrap().creator.issuer);
+    }
+    assert_eq!(issuers, vec![Some("https://idp.a.example.com".into()), Some("https://idp.b.example.com".into()), None]);
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
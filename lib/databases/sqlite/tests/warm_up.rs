@@ -0,0 +1,53 @@
+//  WARM UP.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:22:54
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests warming up the connection pool of the SQLite connector.
+//
+
+use specifications::DatabaseConnector as _;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::User;
+use sqlite_database::DatabaseError;
+use sqlite_database::testing::fresh_database;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_warm_up() {
+    let (db, path) = fresh_database("warm-up").await;
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+
+    // Asking for more than the pool holds is fine too
+    db.warm_up(4).await.unwrap();
+    db.warm_up(usize::MAX).await.unwrap();
+
+    // The warmed-up connections are simply used afterwards
+    let mut conn = db.connect(&user).await.unwrap();
+    assert!(conn.get_versions().await.unwrap().is_empty());
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_warm_up_fails_fast() {
+    let (db, path) = fresh_database("warm-up-fails").await;
+
+    // Pull the database away from under the connector, which will happily create a new (empty) one
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+    }
+    assert!(matches!(db.warm_up(1).await, Err(DatabaseError::Preflight { .. })));
+
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
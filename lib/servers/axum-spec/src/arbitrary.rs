@@ -0,0 +1,560 @@
+//  ARBITRARY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:30:16
+//  Last edited:
+//    17 Oct 2026, 03:32:33
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements [`Arbitrary`] for the request and response bodies of the
+//!   API, such that they can be generated in property-based tests.
+//
+
+use std::collections::HashMap;
+
+use proptest::collection::{hash_map, vec};
+use proptest::prelude::*;
+use specifications::arbitrary::{arb_datetime, arb_json};
+
+use crate::*;
+
+
+/***** CONSTANTS *****/
+/// The maximum number of elements in generated lists and maps.
+const MAX_LEN: usize = 4;
+
+
+
+
+
+/***** LIBRARY *****/
+impl Arbitrary for ApiErrorCode {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop::sample::select(vec![
+            Self::BadRequest,
+            Self::MalformedBody,
+            Self::ValidationFailed,
+            Self::Unauthorized,
+            Self::Forbidden,
+            Self::NotFound,
+            Self::MethodNotAllowed,
+            Self::VersionNotFound,
+            Self::BranchNotFound,
+            Self::UserNotFound,
+            Self::UploadNotFound,
+            Self::DecisionNotFound,
+            Self::KeyNotFound,
+            Self::Conflict,
+            Self::VersionFrozen,
+            Self::PayloadTooLarge,
+            Self::UnsupportedMediaType,
+            Self::RangeNotSatisfiable,
+            Self::Unavailable,
+            Self::DeadlineExceeded,
+            Self::Internal,
+            Self::Unknown,
+        ])
+        .boxed()
+    }
+}
+
+impl Arbitrary for BodyPosition {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), any::<u64>()).prop_map(|(line, column)| Self { line, column }).boxed()
+    }
+}
+
+impl Arbitrary for ErrorResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (
+            any::<String>(),
+            any::<String>(),
+            any::<u16>(),
+            any::<String>(),
+            any::<Option<String>>(),
+            any::<ApiErrorCode>(),
+            any::<Option<String>>(),
+            any::<Option<BodyPosition>>(),
+            // NOTE: `Some(null)` is serialized as `null`, which reads back as `None`
+            proptest::option::of(arb_json().prop_filter("null details are absent details", |details| !details.is_null())),
+        )
+            .prop_map(|(kind, title, status, detail, instance, code, request_id, position, details)| Self {
+                kind,
+                title,
+                status,
+                detail,
+                instance,
+                code,
+                request_id,
+                position,
+                details,
+            })
+            .boxed()
+    }
+}
+
+
+
+impl<C: 'static + Arbitrary> Arbitrary for AddVersionRequest<C> {
+    type Parameters = C::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        (any::<AttachedMetadata>(), any_with::<C>(args)).prop_map(|(metadata, contents)| Self { metadata, contents }).boxed()
+    }
+}
+
+impl Arbitrary for AddVersionResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<u64>().prop_map(|version| Self { version }).boxed() }
+}
+
+impl Arbitrary for ActivateRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<u64>().prop_map(|version| Self { version }).boxed() }
+}
+
+impl Arbitrary for DenyAllResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<u64>().prop_map(|version| Self { version }).boxed() }
+}
+
+impl Arbitrary for FreezeVersionRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<String>().prop_map(|reason| Self { reason }).boxed() }
+}
+
+impl Arbitrary for GetVersionsResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        hash_map(any::<u64>(), any::<Metadata>(), 0..MAX_LEN).prop_map(|versions| Self { versions }).boxed()
+    }
+}
+
+impl Arbitrary for GetActiveVersionQuery {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { proptest::option::of(arb_datetime()).prop_map(|at| Self { at }).boxed() }
+}
+
+impl Arbitrary for GetActiveVersionResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<Option<u64>>().prop_map(|version| Self { version }).boxed() }
+}
+
+impl Arbitrary for GetActivatorResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<Option<User>>(), proptest::option::of(arb_datetime())).prop_map(|(user, activated)| Self { user, activated }).boxed()
+    }
+}
+
+impl Arbitrary for GetActivationsResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        vec(any::<Activation>(), 0..MAX_LEN).prop_map(|activations| Self { activations }).boxed()
+    }
+}
+
+impl Arbitrary for GetVersionMetadataResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<Metadata>().prop_map(|metadata| Self { metadata }).boxed() }
+}
+
+impl Arbitrary for GetVersionProvenanceResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<Provenance>().prop_map(|provenance| Self { provenance }).boxed() }
+}
+
+impl<C: 'static + Arbitrary> Arbitrary for GetActiveContentResponse<C> {
+    type Parameters = C::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        (any::<Option<u64>>(), any::<bool>(), any_with::<C>(args))
+            .prop_map(|(version, fallback, content)| Self { version, fallback, content })
+            .boxed()
+    }
+}
+
+impl<C: 'static + Arbitrary> Arbitrary for GetVersionContentResponse<C> {
+    type Parameters = C::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy { any_with::<C>(args).prop_map(|content| Self { content }).boxed() }
+}
+
+impl Arbitrary for AddTemplateRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<AttachedTemplate>().prop_map(|template| Self { template }).boxed() }
+}
+
+impl Arbitrary for GetTemplatesResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        hash_map(any::<String>(), any::<Template>(), 0..MAX_LEN).prop_map(|templates| Self { templates }).boxed()
+    }
+}
+
+impl Arbitrary for InstantiateTemplateRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<String>(), any::<String>(), any::<Option<String>>(), hash_map(any::<String>(), arb_json(), 0..MAX_LEN))
+            .prop_map(|(name, description, branch, schema, values): (_, _, _, _, HashMap<String, serde_json::Value>)| Self {
+                name,
+                description,
+                branch,
+                schema,
+                values,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for AddSchemaRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<AttachedSchema>().prop_map(|schema| Self { schema }).boxed() }
+}
+
+impl Arbitrary for GetSchemasResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        hash_map(any::<String>(), any::<Schema>(), 0..MAX_LEN).prop_map(|schemas| Self { schemas }).boxed()
+    }
+}
+
+impl Arbitrary for CreateBranchRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<u64>()).prop_map(|(name, from)| Self { name, from }).boxed()
+    }
+}
+
+impl Arbitrary for GetBranchesResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        hash_map(any::<String>(), any::<Branch>(), 0..MAX_LEN).prop_map(|branches| Self { branches }).boxed()
+    }
+}
+
+impl Arbitrary for PromoteRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), any::<bool>()).prop_map(|(version, force)| Self { version, force }).boxed()
+    }
+}
+
+impl Arbitrary for ImportVersionQuery {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<bool>().prop_map(|dry_run| Self { dry_run }).boxed() }
+}
+
+impl<C: 'static + Arbitrary> Arbitrary for ImportVersionRequest<C> {
+    type Parameters = C::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<Metadata>(), any_with::<C>(args), any::<Option<ContentSignature>>())
+            .prop_map(|(store, metadata, contents, signature)| Self { store, metadata, contents, signature })
+            .boxed()
+    }
+}
+
+impl Arbitrary for ContentSignature {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            any::<String>().prop_map(|signature| Self::Blob { signature }),
+            (any::<String>(), any::<String>(), any::<String>()).prop_map(|(manifest, payload, signature)| Self::Manifest {
+                manifest,
+                payload,
+                signature
+            }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for ImportDryRunResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<Option<u64>>(), any::<String>(), vec(any::<u64>(), 0..MAX_LEN))
+            .prop_map(|(branch, head, hash, duplicates)| Self { branch, head, hash, duplicates })
+            .boxed()
+    }
+}
+
+impl Arbitrary for InitiateUploadRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<AttachedMetadata>(), any::<Option<u64>>()).prop_map(|(metadata, length)| Self { metadata, length }).boxed()
+    }
+}
+
+impl Arbitrary for InitiateUploadResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<u64>().prop_map(|id| Self { id }).boxed() }
+}
+
+impl Arbitrary for UploadStatusResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), any::<Option<u64>>()).prop_map(|(offset, length)| Self { offset, length }).boxed()
+    }
+}
+
+impl Arbitrary for GetAuditLogResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        vec(any::<AuditRecord>(), 0..MAX_LEN).prop_map(|records| Self { records }).boxed()
+    }
+}
+
+impl Arbitrary for VerifyAuditLogResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<bool>(), any::<u64>(), any::<Option<String>>(), any::<Option<String>>())
+            .prop_map(|(valid, records, head, error)| Self { valid, records, head, error })
+            .boxed()
+    }
+}
+
+impl Arbitrary for ExportFormat {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { prop_oneof![Just(Self::Ndjson), Just(Self::Cef)].boxed() }
+}
+
+impl Arbitrary for ExportAuditLogQuery {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (proptest::option::of(arb_datetime()), any::<ExportFormat>()).prop_map(|(since, format)| Self { since, format }).boxed()
+    }
+}
+
+impl Arbitrary for GetChangesQuery {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), any::<Option<u64>>()).prop_map(|(since, limit)| Self { since, limit }).boxed()
+    }
+}
+
+impl<C: 'static + Arbitrary> Arbitrary for GetChangesResponse<C> {
+    type Parameters = C::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        (vec(any_with::<Change<C>>(args), 0..MAX_LEN), any::<u64>()).prop_map(|(changes, cursor)| Self { changes, cursor }).boxed()
+    }
+}
+
+impl Arbitrary for PseudonymizeUserResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<u64>()).prop_map(|(pseudonym, rows)| Self { pseudonym, rows }).boxed()
+    }
+}
+
+impl Arbitrary for PruneActivationsRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { arb_datetime().prop_map(|before| Self { before }).boxed() }
+}
+
+impl Arbitrary for PruneActivationsResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<u64>().prop_map(|rows| Self { rows }).boxed() }
+}
+
+impl Arbitrary for AddDecisionRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<String>().prop_map(|id| Self { id }).boxed() }
+}
+
+impl Arbitrary for GetDecisionsResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        vec(any::<Decision>(), 0..MAX_LEN).prop_map(|decisions| Self { decisions }).boxed()
+    }
+}
+
+impl Arbitrary for CountDecisionsResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<u64>().prop_map(|count| Self { count }).boxed() }
+}
+
+impl Arbitrary for GetDecisionResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<Decision>().prop_map(|decision| Self { decision }).boxed() }
+}
+
+impl Arbitrary for AddKeyRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<AttachedKey>().prop_map(|key| Self { key }).boxed() }
+}
+
+impl Arbitrary for GetKeysResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        hash_map(any::<String>(), any::<Key>(), 0..MAX_LEN).prop_map(|keys| Self { keys }).boxed()
+    }
+}
+
+impl Arbitrary for RevokeTokensRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<AttachedRevocation>().prop_map(|revocation| Self { revocation }).boxed() }
+}
+
+impl Arbitrary for GetRevocationsResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        vec(any::<Revocation>(), 0..MAX_LEN).prop_map(|revocations| Self { revocations }).boxed()
+    }
+}
+
+impl Arbitrary for PingResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<String>()).prop_map(|(name, version)| Self { name, version }).boxed()
+    }
+}
+
+impl Arbitrary for DevTokenRequest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<Option<String>>(), vec(any::<String>(), 0..MAX_LEN))
+            .prop_map(|(user, name, roles)| Self { user, name, roles })
+            .boxed()
+    }
+}
+
+impl Arbitrary for DevTokenResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), arb_datetime()).prop_map(|(token, expires)| Self { token, expires }).boxed()
+    }
+}
+
+impl Arbitrary for FederatedStore {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (hash_map(any::<u64>(), any::<Metadata>(), 0..MAX_LEN), any::<Option<u64>>(), any::<Option<String>>())
+            .prop_map(|(versions, active, error)| Self { versions, active, error })
+            .boxed()
+    }
+}
+
+impl Arbitrary for GetFederatedVersionsResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        hash_map(any::<String>(), any::<FederatedStore>(), 0..MAX_LEN).prop_map(|stores| Self { stores }).boxed()
+    }
+}
+
+impl Arbitrary for PromoteStandbyResponse {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy { any::<u64>().prop_map(|cursor| Self { cursor }).boxed() }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    06 Dec 2024, 17:59:58
 //  Last edited:
-//    06 Dec 2024, 18:32:22
+//    17 Oct 2026, 03:32:33
 //  Auto updated?
 //    Yes
 //
@@ -13,6 +13,10 @@
 //!   request/response bodies for the `axum-server`.
 //
 
+// Declare modules
+#[cfg(feature = "proptest")]
+mod arbitrary;
+
 use core::str;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -24,11 +28,32 @@ use axum::handler::Handler;
 #[cfg(feature = "axum")]
 use axum::routing::MethodRouter;
 #[cfg(feature = "axum")]
-use axum::routing::method_routing::{delete, get, post, put};
-use http::Method;
+use axum::routing::method_routing::{delete, get, patch, post, put};
+use chrono::{DateTime, Utc};
+use http::{Method, StatusCode};
 use itertools::Itertools as _;
 use serde::{Deserialize, Serialize};
-use specifications::metadata::{AttachedMetadata, Metadata, User};
+use specifications::audit::{AuditRecord, Change};
+use specifications::databaseconn::ClientError;
+use specifications::decision::Decision;
+use specifications::key::{AttachedKey, Key};
+use specifications::metadata::{Activation, AttachedMetadata, Branch, Metadata, User, default_branch};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::schema::{AttachedSchema, Schema};
+use specifications::template::{AttachedTemplate, Template};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Returns the default problem type of an [`ErrorResponse`].
+///
+/// # Returns
+/// `about:blank`.
+#[inline]
+fn about_blank() -> String { "about:blank".into() }
+
+
+
 
 
 /***** AUXILLARY *****/
@@ -66,6 +91,7 @@ impl EndpointPath {
             Method::POST => post(handler),
             Method::PUT => put(handler),
             Method::DELETE => delete(handler),
+            Method::PATCH => patch(handler),
             _ => unimplemented!(),
         }
     }
@@ -112,6 +138,252 @@ impl EndpointPath {
 
 
 
+/// Identifies what went wrong in an [`ErrorResponse`], such that clients needn't match on
+/// messages.
+///
+/// Every code implies a [status code](ApiErrorCode::status_code()).
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiErrorCode {
+    /// The request was invalid in some other way.
+    BadRequest,
+    /// The request body could not be parsed.
+    MalformedBody,
+    /// The request was well-formed, but its content was rejected (e.g., by a schema).
+    ValidationFailed,
+    /// The request did not carry valid credentials.
+    Unauthorized,
+    /// The credentials were valid, but do not allow this request.
+    Forbidden,
+    /// The requested resource does not exist.
+    NotFound,
+    /// The requested resource exists, but not for the request's method.
+    MethodNotAllowed,
+    /// The requested policy version does not exist.
+    VersionNotFound,
+    /// The requested branch does not exist.
+    BranchNotFound,
+    /// The requested user does not appear in the store.
+    UserNotFound,
+    /// The requested upload does not exist (anymore).
+    UploadNotFound,
+    /// The requested decision does not exist.
+    DecisionNotFound,
+    /// The requested verification key does not exist.
+    KeyNotFound,
+    /// The request conflicts with the current state (e.g., something already exists).
+    Conflict,
+    /// The requested policy version is frozen, and cannot be used until it is unfrozen.
+    VersionFrozen,
+    /// The request body is too large.
+    PayloadTooLarge,
+    /// The request body is not in a format the server understands.
+    UnsupportedMediaType,
+    /// The requested range lies outside of the resource.
+    RangeNotSatisfiable,
+    /// The server cannot handle requests right now.
+    Unavailable,
+    /// The request's deadline (see [`REQUEST_DEADLINE_HEADER`]) passed before it was handled.
+    DeadlineExceeded,
+    /// The server failed to handle the request.
+    Internal,
+    /// A code this version of the spec doesn't know about (only produced when deserializing).
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+impl ApiErrorCode {
+    /// Returns the most specific code for a status code.
+    ///
+    /// # Arguments
+    /// - `status`: The [`StatusCode`] to find the code for.
+    ///
+    /// # Returns
+    /// A generic ApiErrorCode matching the status (e.g., [`ApiErrorCode::NotFound`] for 404).
+    pub fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::BAD_REQUEST => Self::BadRequest,
+            StatusCode::UNAUTHORIZED => Self::Unauthorized,
+            StatusCode::FORBIDDEN => Self::Forbidden,
+            StatusCode::NOT_FOUND => Self::NotFound,
+            StatusCode::METHOD_NOT_ALLOWED => Self::MethodNotAllowed,
+            StatusCode::CONFLICT => Self::Conflict,
+            StatusCode::PAYLOAD_TOO_LARGE => Self::PayloadTooLarge,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => Self::UnsupportedMediaType,
+            StatusCode::RANGE_NOT_SATISFIABLE => Self::RangeNotSatisfiable,
+            StatusCode::SERVICE_UNAVAILABLE => Self::Unavailable,
+            StatusCode::GATEWAY_TIMEOUT => Self::DeadlineExceeded,
+            status if status.is_client_error() => Self::BadRequest,
+            _ => Self::Internal,
+        }
+    }
+
+    /// Returns the status code that is sent with this code.
+    ///
+    /// # Returns
+    /// The [`StatusCode`] of responses carrying this code.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::BadRequest | Self::MalformedBody | Self::ValidationFailed => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::NotFound
+            | Self::VersionNotFound
+            | Self::BranchNotFound
+            | Self::UserNotFound
+            | Self::UploadNotFound
+            | Self::DecisionNotFound
+            | Self::KeyNotFound => StatusCode::NOT_FOUND,
+            Self::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            Self::Conflict | Self::VersionFrozen => StatusCode::CONFLICT,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::RangeNotSatisfiable => StatusCode::RANGE_NOT_SATISFIABLE,
+            Self::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            Self::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+            Self::Internal | Self::Unknown => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+impl From<&ClientError> for ApiErrorCode {
+    #[inline]
+    fn from(value: &ClientError) -> Self {
+        match value {
+            ClientError::BranchExists { .. }
+            | ClientError::DecisionExists { .. }
+            | ClientError::KeyExists { .. }
+            | ClientError::PromoteConflict { .. }
+            | ClientError::SchemaExists { .. } => Self::Conflict,
+            ClientError::HashMismatch { .. } | ClientError::PromoteSameBranch { .. } => Self::BadRequest,
+            ClientError::UnknownBranch { .. } => Self::BranchNotFound,
+            ClientError::UnknownKey { .. } => Self::KeyNotFound,
+            ClientError::UnknownUser { .. } => Self::UserNotFound,
+            ClientError::UnknownVersion { .. } => Self::VersionNotFound,
+            ClientError::VersionFrozen { .. } => Self::VersionFrozen,
+        }
+    }
+}
+
+/// The `Content-Type` of every [`ErrorResponse`], regardless of the format of other bodies.
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Name of the header that identifies a request. Clients may set it; if they don't, the server
+/// generates one. Either way, it is echoed in the response.
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Name of the header that clients may set to the moment (as an RFC 3339 timestamp) after which
+/// they no longer care about the response. The server stops working on requests past their
+/// deadline and replies with [`ApiErrorCode::DeadlineExceeded`] instead.
+pub const REQUEST_DEADLINE_HEADER: &str = "X-Request-Deadline";
+
+/// Name of the header that clients may set to how long they are willing to wait for the response,
+/// in the format used by gRPC (e.g., `500m` for 500 milliseconds). If given together with a
+/// [`REQUEST_DEADLINE_HEADER`], the earliest of the two is used.
+pub const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// Replied by any endpoint when a request fails (i.e., with a non-2xx status code).
+///
+/// This is a problem details object as defined in RFC 7807, extended with the members `code`,
+/// `request_id`, `position` and `details`. It is always encoded as JSON (see [`PROBLEM_CONTENT_TYPE`]).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ErrorResponse {
+    /// A URI identifying the type of problem. We don't document types separately, so this is
+    /// always `about:blank` (see `code` instead).
+    #[serde(rename = "type", default = "about_blank")]
+    pub kind: String,
+    /// A short summary of the type of problem, which is the reason phrase of the status code.
+    #[serde(default)]
+    pub title: String,
+    /// The status code of the response.
+    #[serde(default)]
+    pub status: u16,
+    /// A message describing what went wrong. May be empty if the code says it all.
+    // NOTE: Older servers called this `message`
+    #[serde(default, alias = "message")]
+    pub detail: String,
+    /// The path of the request that failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Identifies what went wrong.
+    #[serde(default)]
+    pub code: ApiErrorCode,
+    /// The ID of the request that failed (see [`REQUEST_ID_HEADER`]), to correlate it with the
+    /// server's logs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Where in the request body the problem was found, if it was a malformed body and the
+    /// format reports positions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<BodyPosition>,
+    /// Machine-readable details about what went wrong (e.g., the identifier that wasn't found),
+    /// if the error provides any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+impl ErrorResponse {
+    /// Constructor for an ErrorResponse.
+    ///
+    /// # Arguments
+    /// - `code`: The [`ApiErrorCode`] identifying what went wrong.
+    /// - `detail`: A message describing what went wrong.
+    ///
+    /// # Returns
+    /// A new ErrorResponse with the status code and title implied by `code`, and no `instance`,
+    /// `request_id`, `position` or `details` yet.
+    pub fn new(code: ApiErrorCode, detail: impl Into<String>) -> Self {
+        let status: StatusCode = code.status_code();
+        Self {
+            kind: about_blank(),
+            title: status.canonical_reason().unwrap_or_default().into(),
+            status: status.as_u16(),
+            detail: detail.into(),
+            instance: None,
+            code,
+            request_id: None,
+            position: None,
+            details: None,
+        }
+    }
+
+    /// Sets where in the request body the problem was found.
+    ///
+    /// # Arguments
+    /// - `position`: The [`BodyPosition`] of the problem, if known.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_position(mut self, position: Option<BodyPosition>) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Sets the machine-readable details about what went wrong.
+    ///
+    /// # Arguments
+    /// - `details`: The details, e.g., as given by [`HttpError::body()`](specifications::authresolver::HttpError::body()).
+    ///   [`Value::Null`](serde_json::Value::Null) means there are none.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = if details.is_null() { None } else { Some(details) };
+        self
+    }
+}
+
+/// Describes a position in a request body.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BodyPosition {
+    /// The line number (one-indexed).
+    pub line:   u64,
+    /// The column number (one-indexed).
+    pub column: u64,
+}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -155,6 +427,24 @@ pub const DEACTIVATE_PATH: EndpointPath = EndpointPath { method: Method::DELETE,
 
 
 
+/// Path of the endpoint to activate the built-in policy version that denies everything.
+///
+/// Unlike [deactivating](DEACTIVATE_PATH), this leaves the reasoner with a policy whose behaviour
+/// is well-defined, which makes it the preferred panic button.
+///
+/// This endpoint is only served by servers built with the `deny-all` feature, and only to
+/// administrators.
+pub const DENY_ALL_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/policies/active/deny-all" };
+
+/// Replied when [activating the deny-all policy](axum-server::server::AxumServer::deny_all()).
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct DenyAllResponse {
+    /// The version of the deny-all policy that is now active.
+    pub version: u64,
+}
+
+
+
 /// Path of the endpoint to retrieve the metadata of all submitted policy versions.
 pub const GET_VERSIONS_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/policies" };
 
@@ -170,6 +460,14 @@ pub struct GetVersionsResponse {
 /// Path of the endpoint to retrieve the currently active policy version, if any.
 pub const GET_ACTIVE_VERSION_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/policies/active" };
 
+/// The query parameters accepted when [retrieving the active policy](axum-server::server::AxumServer::get_active_version()).
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct GetActiveVersionQuery {
+    /// If given, retrieves the version that was active at this time (RFC 3339) instead of now.
+    #[serde(default)]
+    pub at: Option<DateTime<Utc>>,
+}
+
 /// Replied when [retrieving the active policy](axum-server::server::AxumServer::get_active_version()).
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 pub struct GetActiveVersionResponse {
@@ -179,6 +477,27 @@ pub struct GetActiveVersionResponse {
 
 
 
+/// Path of the endpoint to retrieve the contents of the currently active policy version.
+///
+/// What is replied when no version is active is configured per server: either 404 NOT FOUND,
+/// or a fallback policy (see [`GetActiveContentResponse::fallback`]).
+pub const GET_ACTIVE_CONTENT_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/policies/active/content" };
+
+/// Replied when [retrieving the active content](axum-server::server::AxumServer::get_active_content()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetActiveContentResponse<C> {
+    /// The version whose content is replied, or [`None`] if it is the server's built-in deny-all
+    /// policy.
+    pub version:  Option<u64>,
+    /// Whether the content is a fallback, replied because no version is active.
+    #[serde(default)]
+    pub fallback: bool,
+    /// The content of the active (or fallback) policy.
+    pub content:  C,
+}
+
+
+
 /// Path of the endpoint to retrieve the person who activated the currently active policy version, if any.
 pub const GET_ACTIVATOR_VERSION_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/policies/active/activator" };
 
@@ -186,7 +505,22 @@ pub const GET_ACTIVATOR_VERSION_PATH: EndpointPath = EndpointPath { method: Meth
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct GetActivatorResponse {
     /// The person who activated the active policy, if any.
-    pub user: Option<User>,
+    pub user:      Option<User>,
+    /// The time (RFC 3339) at which the active policy was activated, if any.
+    #[serde(default)]
+    pub activated: Option<DateTime<Utc>>,
+}
+
+
+
+/// Path of the endpoint to retrieve the history of which policy versions were active when.
+pub const GET_ACTIVATIONS_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/policies/active/history" };
+
+/// Replied when [retrieving the activation history](axum-server::server::AxumServer::get_activations()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetActivationsResponse {
+    /// Every activation, ordered by the time it happened.
+    pub activations: Vec<Activation>,
 }
 
 
@@ -212,3 +546,735 @@ pub struct GetVersionContentResponse<C> {
     /// The content of the requested policy.
     pub content: C,
 }
+
+
+
+/// Path of the endpoint to retrieve the contents of a particular policy version as raw JSON.
+///
+/// Unlike [`GET_VERSION_CONTENT_PATH`], the content is not wrapped in a response object. This
+/// endpoint honours (single) `Range` headers, replying with 206 PARTIAL CONTENT, so that large
+/// downloads can be resumed.
+pub const GET_VERSION_CONTENT_RAW_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/policies/{version}/content/raw" };
+
+
+
+/// Path of the endpoint to freeze a particular policy version, such that it cannot be activated
+/// or promoted while it is investigated.
+pub const FREEZE_VERSION_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/policies/{version}/freeze" };
+
+/// What to send in the body of a request when [freezing](axum-server::server::AxumServer::freeze_version())
+/// a version.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FreezeVersionRequest {
+    /// Why the version is frozen.
+    pub reason: String,
+}
+
+
+
+/// Path of the endpoint to unfreeze a frozen policy version.
+pub const UNFREEZE_VERSION_PATH: EndpointPath = EndpointPath { method: Method::DELETE, path: "/v2/policies/{version}/freeze" };
+
+
+
+/// Path of the endpoint to retrieve the provenance of a particular policy version.
+pub const GET_VERSION_PROVENANCE_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/policies/{version}/provenance" };
+
+/// Replied when [retrieving provenance](axum-server::server::AxumServer::get_version_provenance()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetVersionProvenanceResponse {
+    /// The in-toto provenance statement of the requested policy.
+    pub provenance: Provenance,
+}
+
+
+
+/// Path of the endpoint to register a new policy template.
+pub const ADD_TEMPLATE_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/templates" };
+
+/// What to send in the body of a request when [registering](axum-server::server::AxumServer::add_template())
+/// a template.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AddTemplateRequest {
+    /// The template to register.
+    pub template: AttachedTemplate,
+}
+
+
+
+/// Path of the endpoint to retrieve all registered policy templates.
+pub const GET_TEMPLATES_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/templates" };
+
+/// Replied when [listing](axum-server::server::AxumServer::get_templates()) all templates.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTemplatesResponse {
+    /// The templates in the store, by name.
+    pub templates: HashMap<String, Template>,
+}
+
+
+
+/// Path of the endpoint to add a new policy version by instantiating a template.
+pub const INSTANTIATE_TEMPLATE_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/policies/from-template/{name}" };
+
+/// What to send in the body of a request when [instantiating](axum-server::server::AxumServer::instantiate_template())
+/// a template.
+///
+/// The version created is replied with an [`AddVersionResponse`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct InstantiateTemplateRequest {
+    /// Some name for the new policy version.
+    pub name: String,
+    /// Some description of the new policy version.
+    pub description: String,
+    /// The branch to append the new policy version to.
+    #[serde(default = "default_branch")]
+    pub branch: String,
+    /// The content schema the new policy version should adhere to, if any.
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// The values to give to the parameters of the template.
+    #[serde(default)]
+    pub values: HashMap<String, serde_json::Value>,
+}
+
+
+
+/// Path of the endpoint to register a new content schema.
+pub const ADD_SCHEMA_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/schemas" };
+
+/// What to send in the body of a request when [adding](axum-server::server::AxumServer::add_schema())
+/// a new content schema.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AddSchemaRequest {
+    /// The schema to register.
+    pub schema: AttachedSchema,
+}
+
+
+
+/// Path of the endpoint to retrieve all registered content schemas.
+pub const GET_SCHEMAS_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/schemas" };
+
+/// Replied when [listing](axum-server::server::AxumServer::get_schemas()) all content schemas.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetSchemasResponse {
+    /// The schemas in the store, by name.
+    pub schemas: HashMap<String, Schema>,
+}
+
+
+
+/// Path of the endpoint to fork a new branch from an existing policy version.
+pub const CREATE_BRANCH_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/branches" };
+
+/// What to send in the body of a request when [creating](axum-server::server::AxumServer::create_branch())
+/// a branch.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CreateBranchRequest {
+    /// The name of the new branch.
+    pub name: String,
+    /// The version to fork the branch from.
+    pub from: u64,
+}
+
+
+
+/// Path of the endpoint to retrieve all branches and their latest versions.
+pub const GET_BRANCHES_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/branches" };
+
+/// Replied when [listing](axum-server::server::AxumServer::get_branches()) all branches.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetBranchesResponse {
+    /// The branches in the store, by name.
+    pub branches: HashMap<String, Branch>,
+}
+
+
+
+/// Path of the endpoint to promote a policy version from its branch into another branch.
+pub const PROMOTE_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/branches/{name}/promote" };
+
+/// What to send in the body of a request when [promoting](axum-server::server::AxumServer::promote())
+/// a version into a branch.
+///
+/// The version created is replied with an [`AddVersionResponse`].
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct PromoteRequest {
+    /// The version to promote into the branch.
+    pub version: u64,
+    /// Whether to promote even if the target branch has advanced since the branches last met.
+    #[serde(default)]
+    pub force:   bool,
+}
+
+
+
+/// Path of the endpoint to import a policy version copied over from another store.
+pub const IMPORT_VERSION_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/policies/import" };
+
+/// The query parameters accepted when [importing](axum-server::server::AxumServer::import_version())
+/// a version from another store.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct ImportVersionQuery {
+    /// If true, only validates the import and replies with an [`ImportDryRunResponse`] describing
+    /// what it would change, without writing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// What to send in the body of a request when [importing](axum-server::server::AxumServer::import_version())
+/// a version from another store.
+///
+/// The version created is replied with an [`AddVersionResponse`] (or an [`ImportDryRunResponse`]
+/// when doing a [dry run](ImportVersionQuery::dry_run)).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ImportVersionRequest<C> {
+    /// The name of the store the version is copied from.
+    pub store:     String,
+    /// The metadata of the version in that store.
+    pub metadata:  Metadata,
+    /// The contents of the version, as they were in that store.
+    pub contents:  C,
+    /// A signature over the contents, if they were signed. Servers that verify signatures record
+    /// the result in the copy's [verification](Metadata::verification).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ContentSignature>,
+}
+
+/// A signature over the contents of a policy version, as made by `cosign`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContentSignature {
+    /// A signature made with `cosign sign-blob` over the contents in
+    /// [canonical form](specifications::canonical).
+    Blob {
+        /// The signature, base64-encoded.
+        signature: String,
+    },
+    /// A signature made with `cosign sign` over an OCI manifest whose single layer holds the
+    /// contents in [canonical form](specifications::canonical).
+    Manifest {
+        /// The manifest, exactly as it was signed.
+        manifest:  String,
+        /// The signed (simple signing) payload, referring to the manifest by its digest.
+        payload:   String,
+        /// The signature over the payload, base64-encoded.
+        signature: String,
+    },
+}
+
+
+
+/// Replied when [importing](axum-server::server::AxumServer::import_version()) a version with
+/// [`dry_run`](ImportVersionQuery::dry_run) set, once the import validated.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ImportDryRunResponse {
+    /// The branch the copy would be appended to.
+    pub branch: String,
+    /// The current head of that branch, which the copy would follow, or [`None`] if the branch
+    /// would be created.
+    pub head: Option<u64>,
+    /// The hash of the contents, as the copy would record it.
+    pub hash: String,
+    /// The versions in this store that are already copies of the same original version.
+    pub duplicates: Vec<u64>,
+}
+
+
+
+/// Path of the endpoint to initiate a chunked upload of a new policy version.
+pub const INITIATE_UPLOAD_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/uploads" };
+
+/// Name of the header that carries the offset of a chunk in an upload.
+pub const UPLOAD_OFFSET_HEADER: &str = "Upload-Offset";
+
+/// What to send in the body of a request when [initiating](axum-server::server::AxumServer::initiate_upload())
+/// a chunked upload.
+///
+/// The upload's contents are then sent as raw chunks of (JSON) bytes, which together form what
+/// would be the `contents` of an [`AddVersionRequest`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct InitiateUploadRequest {
+    /// The metadata for the policy to upload.
+    pub metadata: AttachedMetadata,
+    /// The total length of the contents, in bytes, if known in advance.
+    #[serde(default)]
+    pub length:   Option<u64>,
+}
+
+/// Replied when [initiating](axum-server::server::AxumServer::initiate_upload()) a chunked upload.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct InitiateUploadResponse {
+    /// The identifier of the new upload.
+    pub id: u64,
+}
+
+
+
+/// Path of the endpoint to append a chunk to an upload.
+///
+/// The body of the request is the raw chunk, and the [`UPLOAD_OFFSET_HEADER`] must be set to the
+/// offset where it starts. This must equal the upload's current offset.
+pub const UPLOAD_CHUNK_PATH: EndpointPath = EndpointPath { method: Method::PATCH, path: "/v2/uploads/{id}" };
+
+/// Path of the endpoint to retrieve the status of an upload (e.g., to resume it).
+pub const GET_UPLOAD_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/uploads/{id}" };
+
+/// Replied when [uploading a chunk](axum-server::server::AxumServer::upload_chunk()) or
+/// [retrieving the status](axum-server::server::AxumServer::get_upload()) of an upload.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct UploadStatusResponse {
+    /// The number of bytes received so far, i.e., the offset of the next chunk.
+    pub offset: u64,
+    /// The total length of the contents, if given when initiating the upload.
+    pub length: Option<u64>,
+}
+
+
+
+/// Path of the endpoint to finalize an upload into a new policy version.
+///
+/// The version created is replied with an [`AddVersionResponse`].
+pub const FINALIZE_UPLOAD_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/uploads/{id}/finalize" };
+
+
+
+/// Path of the endpoint to abort an upload.
+pub const CANCEL_UPLOAD_PATH: EndpointPath = EndpointPath { method: Method::DELETE, path: "/v2/uploads/{id}" };
+
+
+
+/// Path of the endpoint to retrieve the audit log.
+pub const GET_AUDIT_LOG_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/audit" };
+
+/// Replied when [retrieving the audit log](axum-server::server::AxumServer::get_audit_log()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetAuditLogResponse {
+    /// The records in the audit log, ordered by sequence number.
+    pub records: Vec<AuditRecord>,
+}
+
+
+
+/// Path of the endpoint to verify the integrity of the audit log.
+pub const VERIFY_AUDIT_LOG_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/audit/verify" };
+
+/// Replied when [verifying the audit log](axum-server::server::AxumServer::verify_audit_log()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VerifyAuditLogResponse {
+    /// Whether the audit log is intact.
+    pub valid:   bool,
+    /// The number of records verified before the first problem (or in total, if valid).
+    pub records: u64,
+    /// The hash of the last record in the log, if any.
+    ///
+    /// This can be published elsewhere (e.g., periodically) to anchor the log, such that it
+    /// cannot be rewritten entirely either.
+    pub head:    Option<String>,
+    /// A description of what is wrong with the log, if anything.
+    pub error:   Option<String>,
+}
+
+
+
+/// Path of the endpoint to export the audit log for ingestion by a SIEM.
+///
+/// Query parameters are given as an [`ExportAuditLogQuery`]. The reply is streamed as one event
+/// per line, in the requested [`ExportFormat`].
+pub const EXPORT_AUDIT_LOG_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/audit/export" };
+
+/// The formats in which the audit log can be exported.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Every [`AuditRecord`] as a JSON object on its own line (`application/x-ndjson`).
+    #[default]
+    Ndjson,
+    /// Every [`AuditRecord`] as an ArcSight Common Event Format event on its own line.
+    Cef,
+}
+
+/// The query parameters accepted when [exporting the audit log](axum-server::server::AxumServer::export_audit_log()).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ExportAuditLogQuery {
+    /// If given, only records made at or after this time (RFC 3339) are exported.
+    #[serde(default)]
+    pub since:  Option<DateTime<Utc>>,
+    /// The format in which to export the records.
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+
+
+/// Path of the endpoint to retrieve the changes made to the store after some cursor, e.g., for
+/// incremental backups or downstream synchronization.
+///
+/// Query parameters are given as a [`GetChangesQuery`].
+pub const GET_CHANGES_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/changes" };
+
+/// The query parameters accepted when [retrieving changes](axum-server::server::AxumServer::get_changes()).
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct GetChangesQuery {
+    /// The cursor returned by a previous call. Omit to start at the beginning.
+    #[serde(default)]
+    pub since: u64,
+    /// The maximum number of changes to return. Defaults to 1000.
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+/// Replied when [retrieving changes](axum-server::server::AxumServer::get_changes()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetChangesResponse<C> {
+    /// The changes after the given cursor, ordered by sequence number.
+    pub changes: Vec<Change<C>>,
+    /// The cursor to pass as `since` to retrieve the next changes.
+    pub cursor:  u64,
+}
+
+
+
+/// Path of the endpoint to pseudonymize a user, e.g., to honour a request to erase their personal
+/// data.
+pub const PSEUDONYMIZE_USER_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/users/{id}/pseudonymize" };
+
+/// Replied when [pseudonymizing a user](axum-server::server::AxumServer::pseudonymize_user()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PseudonymizeUserResponse {
+    /// The pseudonym that replaced the user's ID everywhere.
+    pub pseudonym: String,
+    /// The number of references to the user (including in audit records) that were replaced or
+    /// forgotten. Note that a single row may reference the user more than once.
+    pub rows:      u64,
+}
+
+
+
+/// Path of the endpoint to remove historical activation records that ended before some time.
+pub const PRUNE_ACTIVATIONS_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/maintenance/prune-activations" };
+
+/// What to send in the body of a request when [pruning activations](axum-server::server::AxumServer::prune_activations()).
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct PruneActivationsRequest {
+    /// Activations that ended before this time (RFC 3339) are removed.
+    pub before: DateTime<Utc>,
+}
+
+/// Replied when [pruning activations](axum-server::server::AxumServer::prune_activations()).
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct PruneActivationsResponse {
+    /// The number of activation records removed.
+    pub rows: u64,
+}
+
+
+
+/// Path of the endpoint to run maintenance on the backend database (e.g., reclaiming unused
+/// space).
+pub const MAINTAIN_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/maintenance/run" };
+
+
+
+/// Path of the endpoint to register that a decision was taken under a particular policy version.
+pub const ADD_DECISION_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/policies/{version}/decisions" };
+
+/// What to send in the body of a request when [registering](axum-server::server::AxumServer::add_decision())
+/// a decision.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AddDecisionRequest {
+    /// The identifier of the decision, as chosen by the reasoner.
+    pub id: String,
+}
+
+
+
+/// Path of the endpoint to retrieve the decisions taken under a particular policy version.
+pub const GET_DECISIONS_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/policies/{version}/decisions" };
+
+/// Replied when [retrieving decisions](axum-server::server::AxumServer::get_decisions()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetDecisionsResponse {
+    /// The decisions taken under the version, ordered by the time they were registered.
+    pub decisions: Vec<Decision>,
+}
+
+
+
+/// Path of the endpoint to count the decisions taken under a particular policy version.
+pub const COUNT_DECISIONS_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/policies/{version}/decisions/count" };
+
+/// Replied when [counting decisions](axum-server::server::AxumServer::count_decisions()).
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct CountDecisionsResponse {
+    /// The number of decisions taken under the version.
+    pub count: u64,
+}
+
+
+
+/// Path of the endpoint to retrieve a particular decision, e.g., to find under which policy version
+/// it was taken.
+pub const GET_DECISION_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/decisions/{id}" };
+
+/// Replied when [retrieving a decision](axum-server::server::AxumServer::get_decision()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetDecisionResponse {
+    /// The requested decision.
+    pub decision: Decision,
+}
+
+
+
+/// Path of the endpoint to register a new verification key, e.g., for the database-backed key
+/// resolver of the `jwk-auth` scheme.
+pub const ADD_KEY_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/keys" };
+
+/// What to send in the body of a request when [registering](axum-server::server::AxumServer::add_key())
+/// a verification key.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AddKeyRequest {
+    /// The key to register.
+    pub key: AttachedKey,
+}
+
+
+
+/// Path of the endpoint to retrieve all verification keys, including revoked ones.
+pub const GET_KEYS_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/keys" };
+
+/// Replied when [retrieving keys](axum-server::server::AxumServer::get_keys()).
+///
+/// Note that the material of shared secrets is never sent back; it is left empty instead.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetKeysResponse {
+    /// Maps key identifiers to keys.
+    pub keys: HashMap<String, Key>,
+}
+
+
+
+/// Path of the endpoint to revoke a verification key.
+pub const REVOKE_KEY_PATH: EndpointPath = EndpointPath { method: Method::DELETE, path: "/v2/keys/{id}" };
+
+
+
+/// Path of the endpoint to revoke tokens, either by identifier or all tokens of a user.
+pub const REVOKE_TOKENS_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/revocations" };
+
+/// What to send in the body of a request when [revoking](axum-server::server::AxumServer::revoke_tokens())
+/// tokens.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RevokeTokensRequest {
+    /// Which tokens to revoke.
+    pub revocation: AttachedRevocation,
+}
+
+
+
+/// Path of the endpoint to retrieve all token revocations.
+pub const GET_REVOCATIONS_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/revocations" };
+
+/// Replied when [retrieving revocations](axum-server::server::AxumServer::get_revocations()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetRevocationsResponse {
+    /// The revocations, in the order they were made.
+    pub revocations: Vec<Revocation>,
+}
+
+
+
+/// Path of the endpoint to check whether the server is up, e.g., for container healthchecks.
+///
+/// Unlike all other endpoints, this one requires no authentication and never touches the
+/// database. It is served unless explicitly [disabled](axum-server::server::AxumServer::without_ping()).
+pub const PING_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/ping" };
+
+/// Replied when [pinging](axum-server::server::AxumServer::ping()) the server.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PingResponse {
+    /// The name of the server implementation.
+    pub name:    String,
+    /// The version of the server implementation.
+    pub version: String,
+}
+
+
+
+/// Path of the endpoint to mint a token for any user, for local development and testing.
+///
+/// This endpoint requires no authentication at all. As such, it is only served by servers built
+/// with the `dev-auth` feature that are explicitly configured to mint tokens, and must never be
+/// enabled in production.
+pub const DEV_TOKEN_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/dev/token" };
+
+/// What to send in the body of a request when [minting](axum-server::server::AxumServer::dev_token())
+/// a development token.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DevTokenRequest {
+    /// The identifier of the user to mint the token for.
+    pub user:  String,
+    /// The display name of the user. Defaults to their identifier.
+    #[serde(default)]
+    pub name:  Option<String>,
+    /// The roles to grant the user.
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+/// Replied when [minting a development token](axum-server::server::AxumServer::dev_token()).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DevTokenResponse {
+    /// The signed token, to be sent as `Authorization: Bearer <token>`.
+    pub token:   String,
+    /// The time the token expires.
+    pub expires: DateTime<Utc>,
+}
+
+
+
+
+
+/// Path of the endpoint to retrieve the policy versions of all upstream stores of a federating
+/// server.
+///
+/// This endpoint is only served by servers built with the `federation` feature that are
+/// configured with at least one upstream store.
+pub const GET_FEDERATED_VERSIONS_PATH: EndpointPath = EndpointPath { method: Method::GET, path: "/v2/federated/policies" };
+
+/// The view on a single upstream store, as part of a [`GetFederatedVersionsResponse`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FederatedStore {
+    /// The versions in the upstream store.
+    pub versions: HashMap<u64, Metadata>,
+    /// The active version in the upstream store, if any.
+    pub active:   Option<u64>,
+    /// Why the upstream store could not be reached, if it couldn't. The other fields are empty
+    /// in that case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error:    Option<String>,
+}
+
+/// Replied when [listing](axum-server::server::AxumServer::get_federated_versions()) the versions
+/// of all upstream stores.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetFederatedVersionsResponse {
+    /// The upstream stores, by their configured name.
+    pub stores: HashMap<String, FederatedStore>,
+}
+
+
+
+/// Path of the endpoint to promote a standby server to read-write, e.g., once its primary is
+/// gone for good.
+///
+/// This endpoint is only served by servers built with the `standby` feature that are configured
+/// to replicate from a primary. Promoting an already promoted server does nothing.
+pub const PROMOTE_STANDBY_PATH: EndpointPath = EndpointPath { method: Method::POST, path: "/v2/standby/promote" };
+
+/// Replied when [promoting a standby](axum-server::server::AxumServer::promote_standby()).
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct PromoteStandbyResponse {
+    /// The sequence number of the last change replicated from the primary, i.e., the point at
+    /// which the server stopped following it.
+    pub cursor: u64,
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone as _};
+    use serde_json::Value;
+
+    use super::*;
+
+
+    /// Returns a fixed time with sub-second precision to test with.
+    fn time() -> DateTime<Utc> { Utc.with_ymd_and_hms(2026, 10, 16, 13, 37, 42).unwrap() + TimeDelta::microseconds(123456) }
+
+    /// Returns an activation that has both timestamps set.
+    fn activation() -> Activation {
+        Activation {
+            version:     1,
+            activated:   time(),
+            activator:   User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() },
+            client:      None,
+            deactivated: Some(time() + TimeDelta::hours(1)),
+            deactivator: Some(User { id: "bob".into(), name: "Bob".into(), issuer: None, roles: Vec::new() }),
+        }
+    }
+
+
+    #[test]
+    fn test_timestamps_are_rfc3339_with_offset() {
+        let res: Value = serde_json::to_value(GetActivationsResponse { activations: vec![activation()] }).unwrap();
+        assert_eq!(res["activations"][0]["activated"], "2026-10-16T13:37:42.123456Z");
+        assert_eq!(res["activations"][0]["deactivated"], "2026-10-16T14:37:42.123456Z");
+
+        let metadata = Metadata {
+            attached: AttachedMetadata {
+                name: "test".into(),
+                description: "A test policy".into(),
+                language: "test".into(),
+                branch: default_branch(),
+                schema: None,
+                confidential: false,
+            },
+            created: time(),
+            creator: User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() },
+            version: 1,
+            parent: None,
+            promoted_from: None,
+            hash: None,
+            origin: None,
+            verification: None,
+            frozen: None,
+        };
+        let res: Value = serde_json::to_value(GetVersionMetadataResponse { metadata }).unwrap();
+        assert_eq!(res["metadata"]["created"], "2026-10-16T13:37:42.123456Z");
+    }
+
+    #[test]
+    fn test_timestamps_roundtrip() {
+        let res: GetActivationsResponse =
+            serde_json::from_str(&serde_json::to_string(&GetActivationsResponse { activations: vec![activation()] }).unwrap()).unwrap();
+        assert_eq!(res.activations.len(), 1);
+        assert_eq!(res.activations[0].activated, activation().activated);
+        assert_eq!(res.activations[0].deactivated, activation().deactivated);
+    }
+
+    #[test]
+    fn test_timestamps_accept_other_offsets() {
+        // Clients in other timezones may send their local time with an explicit offset
+        let res: GetActivationsResponse = serde_json::from_str(
+            r#"{"activations":[{"version":1,"activated":"2026-10-16T15:37:42.123456+02:00","activator":{"id":"amy","name":"Amy"},"deactivated":null,"deactivator":null}]}"#,
+        )
+        .unwrap();
+        assert_eq!(res.activations[0].activated, time());
+        assert_eq!(res.activations[0].deactivated, None);
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let res: Value = serde_json::to_value(ErrorResponse::new(ApiErrorCode::VersionNotFound, "Unknown policy version 42")).unwrap();
+        assert_eq!(res["type"], "about:blank");
+        assert_eq!(res["title"], "Not Found");
+        assert_eq!(res["status"], 404);
+        assert_eq!(res["detail"], "Unknown policy version 42");
+        assert_eq!(res["code"], "version_not_found");
+        assert_eq!(ApiErrorCode::VersionNotFound.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(ApiErrorCode::from_status(StatusCode::NOT_FOUND), ApiErrorCode::NotFound);
+
+        // Codes from newer servers, or no codes from older ones, should not break clients
+        let res: ErrorResponse = serde_json::from_str(r#"{"code":"something_new","message":"Oops"}"#).unwrap();
+        assert_eq!(res.code, ApiErrorCode::Unknown);
+        let res: ErrorResponse = serde_json::from_str(r#"{"message":"Oops"}"#).unwrap();
+        assert_eq!(res.code, ApiErrorCode::Unknown);
+        assert_eq!(res.detail, "Oops");
+    }
+}
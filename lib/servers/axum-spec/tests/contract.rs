@@ -0,0 +1,560 @@
+//  CONTRACT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:31:31
+//  Last edited:
+//    17 Oct 2026, 03:32:33
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Snapshots the wire contract of the API, i.e., the paths of all
+//!   endpoints and the shapes of their request and response bodies.
+//!
+//!   These tests failing means that existing clients may break. If the
+//!   change is intentional, update the snapshots below (and bump the API
+//!   version where needed).
+//
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+
+use axum_server_spec::*;
+use chrono::{DateTime, TimeZone as _, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+use specifications::audit::{Added, AuditAction, AuditRecord, Change};
+use specifications::decision::Decision;
+use specifications::key::{AttachedKey, Key, KeyKind};
+use specifications::metadata::{Activation, AttachedMetadata, Branch, Metadata, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::{AttachedRevocation, Revocation};
+use specifications::schema::{AttachedSchema, Schema};
+use specifications::template::{AttachedTemplate, Template, TemplateParameter};
+
+
+/***** HELPERS *****/
+/// Asserts that the given body serializes to the given snapshot.
+#[track_caller]
+fn assert_snapshot(body: impl Serialize, snapshot: Value) {
+    let body: Value = serde_json::to_value(body).unwrap();
+    assert_eq!(body, snapshot, "Body no longer matches its snapshot:\n{}", serde_json::to_string_pretty(&body).unwrap());
+}
+
+
+
+/// Returns a fixed time to put in bodies.
+fn time() -> DateTime<Utc> { Utc.with_ymd_and_hms(2026, 10, 16, 13, 37, 42).unwrap() }
+/// The way [`time()`] is serialized.
+const TIME: &str = "2026-10-16T13:37:42Z";
+
+/// Returns a user to put in bodies.
+fn user() -> User { User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() } }
+/// Returns the snapshot of [`user()`].
+fn user_snapshot() -> Value { json!({ "id": "amy", "name": "Amy" }) }
+
+/// Returns attached metadata to put in bodies.
+fn attached_metadata() -> AttachedMetadata {
+    AttachedMetadata {
+        name: "test".into(),
+        description: "A test policy".into(),
+        language: "eflint-json".into(),
+        branch: "main".into(),
+        schema: Some("eflint".into()),
+        confidential: false,
+    }
+}
+/// Returns the snapshot of [`attached_metadata()`].
+fn attached_metadata_snapshot() -> Value {
+    json!({ "name": "test", "description": "A test policy", "language": "eflint-json", "branch": "main", "schema": "eflint" })
+}
+
+/// Returns metadata to put in bodies.
+fn metadata() -> Metadata {
+    Metadata {
+        attached: attached_metadata(),
+        created: time(),
+        creator: user(),
+        version: 2,
+        parent: Some(1),
+        promoted_from: None,
+        hash: Some("abc".into()),
+        origin: None,
+        verification: None,
+        frozen: None,
+    }
+}
+/// Returns the snapshot of [`metadata()`].
+fn metadata_snapshot() -> Value {
+    json!({
+        "attached": attached_metadata_snapshot(),
+        "created": TIME,
+        "creator": user_snapshot(),
+        "version": 2,
+        "parent": 1,
+        "promoted_from": null,
+        "hash": "abc",
+        "origin": null,
+        "verification": null,
+        "frozen": null,
+    })
+}
+
+/// Returns an activation to put in bodies.
+fn activation() -> Activation {
+    Activation {
+        version:     2,
+        activated:   time(),
+        activator:   user(),
+        client:      Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+        deactivated: None,
+        deactivator: None,
+    }
+}
+/// Returns the snapshot of [`activation()`].
+fn activation_snapshot() -> Value {
+    json!({ "version": 2, "activated": TIME, "activator": user_snapshot(), "client": "10.0.0.1", "deactivated": null, "deactivator": null })
+}
+
+/// Returns an attached template to put in bodies.
+fn attached_template() -> AttachedTemplate {
+    AttachedTemplate {
+        name: "greeting".into(),
+        description: "Greets someone".into(),
+        language: "eflint-json".into(),
+        parameters: vec![TemplateParameter { name: "who".into(), description: "Who to greet".into(), default: Some(json!("world")) }],
+        skeleton: json!({ "greet": "{{who}}" }),
+    }
+}
+/// Returns the snapshot of [`attached_template()`].
+fn attached_template_snapshot() -> Value {
+    json!({
+        "name": "greeting",
+        "description": "Greets someone",
+        "language": "eflint-json",
+        "parameters": [{ "name": "who", "description": "Who to greet", "default": "world" }],
+        "skeleton": { "greet": "{{who}}" },
+    })
+}
+
+/// Returns an attached schema to put in bodies.
+fn attached_schema() -> AttachedSchema {
+    AttachedSchema { name: "eflint".into(), description: "eFLINT JSON".into(), schema: json!({ "type": "object" }) }
+}
+/// Returns the snapshot of [`attached_schema()`].
+fn attached_schema_snapshot() -> Value { json!({ "name": "eflint", "description": "eFLINT JSON", "schema": { "type": "object" } }) }
+
+/// Returns an audit record to put in bodies.
+fn audit_record() -> AuditRecord {
+    AuditRecord {
+        seq: 3,
+        timestamp: time(),
+        user: "amy".into(),
+        client: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        action: AuditAction::Activate { version: 2 },
+        prev_hash: Some("abc".into()),
+        hash: "def".into(),
+    }
+}
+/// Returns the snapshot of [`audit_record()`].
+fn audit_record_snapshot() -> Value {
+    json!({
+        "seq": 3,
+        "timestamp": TIME,
+        "user": "amy",
+        "client": "127.0.0.1",
+        "action": { "kind": "activate", "version": 2 },
+        "prev_hash": "abc",
+        "hash": "def",
+    })
+}
+
+/// Returns a decision to put in bodies.
+fn decision() -> Decision { Decision { id: "d1".into(), version: 2, recorded: time(), reporter: user() } }
+/// Returns the snapshot of [`decision()`].
+fn decision_snapshot() -> Value { json!({ "id": "d1", "version": 2, "recorded": TIME, "reporter": user_snapshot() }) }
+
+
+
+
+
+/***** TESTS *****/
+#[test]
+fn test_paths() {
+    let paths: &[(&EndpointPath, &str, &str)] = &[
+        (&ADD_VERSION_PATH, "POST", "/v2/policies"),
+        (&ACTIVATE_PATH, "PUT", "/v2/policies/active"),
+        (&DEACTIVATE_PATH, "DELETE", "/v2/policies/active"),
+        (&DENY_ALL_PATH, "POST", "/v2/policies/active/deny-all"),
+        (&GET_VERSIONS_PATH, "GET", "/v2/policies"),
+        (&GET_ACTIVE_VERSION_PATH, "GET", "/v2/policies/active"),
+        (&GET_ACTIVE_CONTENT_PATH, "GET", "/v2/policies/active/content"),
+        (&GET_ACTIVATOR_VERSION_PATH, "GET", "/v2/policies/active/activator"),
+        (&GET_ACTIVATIONS_PATH, "GET", "/v2/policies/active/history"),
+        (&GET_VERSION_METADATA_PATH, "GET", "/v2/policies/{version}"),
+        (&GET_VERSION_CONTENT_PATH, "GET", "/v2/policies/{version}/content"),
+        (&GET_VERSION_CONTENT_RAW_PATH, "GET", "/v2/policies/{version}/content/raw"),
+        (&GET_VERSION_PROVENANCE_PATH, "GET", "/v2/policies/{version}/provenance"),
+        (&FREEZE_VERSION_PATH, "POST", "/v2/policies/{version}/freeze"),
+        (&UNFREEZE_VERSION_PATH, "DELETE", "/v2/policies/{version}/freeze"),
+        (&ADD_TEMPLATE_PATH, "POST", "/v2/templates"),
+        (&GET_TEMPLATES_PATH, "GET", "/v2/templates"),
+        (&INSTANTIATE_TEMPLATE_PATH, "POST", "/v2/policies/from-template/{name}"),
+        (&ADD_SCHEMA_PATH, "POST", "/v2/schemas"),
+        (&GET_SCHEMAS_PATH, "GET", "/v2/schemas"),
+        (&CREATE_BRANCH_PATH, "POST", "/v2/branches"),
+        (&GET_BRANCHES_PATH, "GET", "/v2/branches"),
+        (&PROMOTE_PATH, "POST", "/v2/branches/{name}/promote"),
+        (&IMPORT_VERSION_PATH, "POST", "/v2/policies/import"),
+        (&INITIATE_UPLOAD_PATH, "POST", "/v2/uploads"),
+        (&UPLOAD_CHUNK_PATH, "PATCH", "/v2/uploads/{id}"),
+        (&GET_UPLOAD_PATH, "GET", "/v2/uploads/{id}"),
+        (&FINALIZE_UPLOAD_PATH, "POST", "/v2/uploads/{id}/finalize"),
+        (&CANCEL_UPLOAD_PATH, "DELETE", "/v2/uploads/{id}"),
+        (&GET_AUDIT_LOG_PATH, "GET", "/v2/audit"),
+        (&VERIFY_AUDIT_LOG_PATH, "GET", "/v2/audit/verify"),
+        (&EXPORT_AUDIT_LOG_PATH, "GET", "/v2/audit/export"),
+        (&GET_CHANGES_PATH, "GET", "/v2/changes"),
+        (&PSEUDONYMIZE_USER_PATH, "POST", "/v2/users/{id}/pseudonymize"),
+        (&PRUNE_ACTIVATIONS_PATH, "POST", "/v2/maintenance/prune-activations"),
+        (&MAINTAIN_PATH, "POST", "/v2/maintenance/run"),
+        (&ADD_DECISION_PATH, "POST", "/v2/policies/{version}/decisions"),
+        (&GET_DECISIONS_PATH, "GET", "/v2/policies/{version}/decisions"),
+        (&COUNT_DECISIONS_PATH, "GET", "/v2/policies/{version}/decisions/count"),
+        (&GET_DECISION_PATH, "GET", "/v2/decisions/{id}"),
+        (&ADD_KEY_PATH, "POST", "/v2/keys"),
+        (&GET_KEYS_PATH, "GET", "/v2/keys"),
+        (&REVOKE_KEY_PATH, "DELETE", "/v2/keys/{id}"),
+        (&REVOKE_TOKENS_PATH, "POST", "/v2/revocations"),
+        (&GET_REVOCATIONS_PATH, "GET", "/v2/revocations"),
+        (&PING_PATH, "GET", "/v2/ping"),
+        (&DEV_TOKEN_PATH, "POST", "/v2/dev/token"),
+        (&GET_FEDERATED_VERSIONS_PATH, "GET", "/v2/federated/policies"),
+        (&PROMOTE_STANDBY_PATH, "POST", "/v2/standby/promote"),
+    ];
+    for &(endpoint, method, path) in paths {
+        assert_eq!((endpoint.method.as_str(), endpoint.path), (method, path), "Endpoint no longer matches its snapshot");
+    }
+
+    // Not paths, but just as much part of the contract
+    assert_eq!(PROBLEM_CONTENT_TYPE, "application/problem+json");
+    assert_eq!(REQUEST_ID_HEADER, "X-Request-Id");
+    assert_eq!(UPLOAD_OFFSET_HEADER, "Upload-Offset");
+}
+
+#[test]
+fn test_error_bodies() {
+    let mut body = ErrorResponse::new(ApiErrorCode::MalformedBody, "Oops").with_position(Some(BodyPosition { line: 2, column: 3 }));
+    body.instance = Some("/v2/policies".into());
+    body.request_id = Some("r1".into());
+    assert_snapshot(
+        body,
+        json!({
+            "type": "about:blank",
+            "title": "Bad Request",
+            "status": 400,
+            "detail": "Oops",
+            "instance": "/v2/policies",
+            "code": "malformed_body",
+            "request_id": "r1",
+            "position": { "line": 2, "column": 3 },
+        }),
+    );
+    assert_snapshot(
+        ErrorResponse::new(ApiErrorCode::VersionNotFound, ""),
+        json!({ "type": "about:blank", "title": "Not Found", "status": 404, "detail": "", "code": "version_not_found" }),
+    );
+    assert_snapshot(
+        ErrorResponse::new(ApiErrorCode::VersionNotFound, "Unknown policy version 42").with_details(json!({ "version": 42 })),
+        json!({
+            "type": "about:blank",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "Unknown policy version 42",
+            "code": "version_not_found",
+            "details": { "version": 42 },
+        }),
+    );
+}
+
+#[test]
+fn test_version_bodies() {
+    assert_snapshot(
+        AddVersionRequest { metadata: attached_metadata(), contents: json!({ "facts": [] }) },
+        json!({ "metadata": attached_metadata_snapshot(), "contents": { "facts": [] } }),
+    );
+    assert_snapshot(AddVersionResponse { version: 2 }, json!({ "version": 2 }));
+    assert_snapshot(ActivateRequest { version: 2 }, json!({ "version": 2 }));
+    assert_snapshot(DenyAllResponse { version: 2 }, json!({ "version": 2 }));
+    assert_snapshot(FreezeVersionRequest { reason: "under investigation".into() }, json!({ "reason": "under investigation" }));
+    assert_snapshot(GetVersionsResponse { versions: HashMap::from([(2, metadata())]) }, json!({ "versions": { "2": metadata_snapshot() } }));
+    assert_snapshot(GetActiveVersionQuery { at: Some(time()) }, json!({ "at": TIME }));
+    assert_snapshot(GetActiveVersionResponse { version: Some(2) }, json!({ "version": 2 }));
+    assert_snapshot(
+        GetActiveContentResponse { version: None, fallback: true, content: json!(false) },
+        json!({ "version": null, "fallback": true, "content": false }),
+    );
+    assert_snapshot(GetActivatorResponse { user: Some(user()), activated: Some(time()) }, json!({ "user": user_snapshot(), "activated": TIME }));
+    assert_snapshot(GetActivationsResponse { activations: vec![activation()] }, json!({ "activations": [activation_snapshot()] }));
+    assert_snapshot(GetVersionMetadataResponse { metadata: metadata() }, json!({ "metadata": metadata_snapshot() }));
+    assert_snapshot(GetVersionContentResponse { content: json!({ "facts": [] }) }, json!({ "content": { "facts": [] } }));
+    assert_snapshot(
+        GetVersionProvenanceResponse { provenance: Provenance::new(&metadata(), Some(IpAddr::V4(Ipv4Addr::LOCALHOST)), Some("req".into())) },
+        json!({
+            "provenance": {
+                "_type": "https://in-toto.io/Statement/v1",
+                "subject": [{ "name": "test:2", "digest": { "sha256": "abc" } }],
+                "predicateType": "https://slsa.dev/provenance/v1",
+                "predicate": {
+                    "buildDefinition": {
+                        "buildType": "https://github.com/epi-project/policy-store/provenance/v1",
+                        "externalParameters": attached_metadata_snapshot(),
+                        "internalParameters": {
+                            "creation": "added",
+                            "uploader": "amy",
+                            "client": "127.0.0.1",
+                            "validations": [{ "kind": "schema", "name": "eflint" }],
+                        },
+                    },
+                    "runDetails": {
+                        "builder": { "id": "https://github.com/epi-project/policy-store" },
+                        "metadata": { "invocationId": "req", "finishedOn": TIME },
+                    },
+                },
+            },
+        }),
+    );
+    assert_snapshot(ImportVersionQuery { dry_run: true }, json!({ "dry_run": true }));
+    assert_snapshot(
+        ImportVersionRequest { store: "staging".into(), metadata: metadata(), contents: json!({ "facts": [] }), signature: None },
+        json!({ "store": "staging", "metadata": metadata_snapshot(), "contents": { "facts": [] } }),
+    );
+    assert_snapshot(
+        ImportVersionRequest {
+            store:     "staging".into(),
+            metadata:  metadata(),
+            contents:  json!({ "facts": [] }),
+            signature: Some(ContentSignature::Blob { signature: "c2ln".into() }),
+        },
+        json!({
+            "store": "staging",
+            "metadata": metadata_snapshot(),
+            "contents": { "facts": [] },
+            "signature": { "kind": "blob", "signature": "c2ln" },
+        }),
+    );
+    assert_snapshot(
+        ImportDryRunResponse { branch: "main".into(), head: Some(2), hash: "abc".into(), duplicates: vec![3] },
+        json!({ "branch": "main", "head": 2, "hash": "abc", "duplicates": [3] }),
+    );
+}
+
+#[test]
+fn test_template_and_schema_bodies() {
+    assert_snapshot(AddTemplateRequest { template: attached_template() }, json!({ "template": attached_template_snapshot() }));
+    assert_snapshot(
+        GetTemplatesResponse {
+            templates: HashMap::from([("greeting".into(), Template { attached: attached_template(), created: time(), creator: user() })]),
+        },
+        json!({ "templates": { "greeting": { "attached": attached_template_snapshot(), "created": TIME, "creator": user_snapshot() } } }),
+    );
+    assert_snapshot(
+        InstantiateTemplateRequest {
+            name: "hello".into(),
+            description: "Says hello".into(),
+            branch: "main".into(),
+            schema: None,
+            values: HashMap::from([("who".into(), json!("Bob"))]),
+        },
+        json!({ "name": "hello", "description": "Says hello", "branch": "main", "schema": null, "values": { "who": "Bob" } }),
+    );
+    assert_snapshot(AddSchemaRequest { schema: attached_schema() }, json!({ "schema": attached_schema_snapshot() }));
+    assert_snapshot(
+        GetSchemasResponse { schemas: HashMap::from([("eflint".into(), Schema { attached: attached_schema(), created: time(), creator: user() })]) },
+        json!({ "schemas": { "eflint": { "attached": attached_schema_snapshot(), "created": TIME, "creator": user_snapshot() } } }),
+    );
+}
+
+#[test]
+fn test_branch_bodies() {
+    assert_snapshot(CreateBranchRequest { name: "staging".into(), from: 2 }, json!({ "name": "staging", "from": 2 }));
+    assert_snapshot(
+        GetBranchesResponse {
+            branches: HashMap::from([("staging".into(), Branch {
+                name:    "staging".into(),
+                head:    Some(3),
+                base:    Some(2),
+                created: time(),
+                creator: user(),
+            })]),
+        },
+        json!({ "branches": { "staging": { "name": "staging", "head": 3, "base": 2, "created": TIME, "creator": user_snapshot() } } }),
+    );
+    assert_snapshot(PromoteRequest { version: 3, force: false }, json!({ "version": 3, "force": false }));
+}
+
+#[test]
+fn test_upload_bodies() {
+    assert_snapshot(
+        InitiateUploadRequest { metadata: attached_metadata(), length: Some(1024) },
+        json!({ "metadata": attached_metadata_snapshot(), "length": 1024 }),
+    );
+    assert_snapshot(InitiateUploadResponse { id: 1 }, json!({ "id": 1 }));
+    assert_snapshot(UploadStatusResponse { offset: 512, length: Some(1024) }, json!({ "offset": 512, "length": 1024 }));
+}
+
+#[test]
+fn test_audit_bodies() {
+    assert_snapshot(GetAuditLogResponse { records: vec![audit_record()] }, json!({ "records": [audit_record_snapshot()] }));
+    assert_snapshot(
+        VerifyAuditLogResponse { valid: true, records: 3, head: Some("def".into()), error: None },
+        json!({ "valid": true, "records": 3, "head": "def", "error": null }),
+    );
+    assert_snapshot(ExportAuditLogQuery { since: Some(time()), format: ExportFormat::Cef }, json!({ "since": TIME, "format": "cef" }));
+    assert_snapshot(ExportAuditLogQuery { since: None, format: ExportFormat::Ndjson }, json!({ "since": null, "format": "ndjson" }));
+    assert_snapshot(GetChangesQuery { since: 2, limit: Some(10) }, json!({ "since": 2, "limit": 10 }));
+    assert_snapshot(
+        GetChangesResponse {
+            changes: vec![
+                Change { record: audit_record(), metadata: None, content: None, added: None },
+                Change { record: audit_record(), metadata: Some(metadata()), content: Some(json!({ "facts": [] })), added: None },
+                Change { record: audit_record(), metadata: None, content: None, added: Some(Added::Schema(attached_schema())) },
+            ],
+            cursor:  3,
+        },
+        json!({
+            "changes": [
+                { "record": audit_record_snapshot() },
+                { "record": audit_record_snapshot(), "metadata": metadata_snapshot(), "content": { "facts": [] } },
+                { "record": audit_record_snapshot(), "added": { "schema": attached_schema_snapshot() } },
+            ],
+            "cursor": 3,
+        }),
+    );
+}
+
+#[test]
+fn test_maintenance_bodies() {
+    assert_snapshot(PseudonymizeUserResponse { pseudonym: "user-1".into(), rows: 4 }, json!({ "pseudonym": "user-1", "rows": 4 }));
+    assert_snapshot(PruneActivationsRequest { before: time() }, json!({ "before": TIME }));
+    assert_snapshot(PruneActivationsResponse { rows: 4 }, json!({ "rows": 4 }));
+}
+
+#[test]
+fn test_decision_bodies() {
+    assert_snapshot(AddDecisionRequest { id: "d1".into() }, json!({ "id": "d1" }));
+    assert_snapshot(GetDecisionsResponse { decisions: vec![decision()] }, json!({ "decisions": [decision_snapshot()] }));
+    assert_snapshot(CountDecisionsResponse { count: 1 }, json!({ "count": 1 }));
+    assert_snapshot(GetDecisionResponse { decision: decision() }, json!({ "decision": decision_snapshot() }));
+}
+
+#[test]
+fn test_key_bodies() {
+    let key = AttachedKey { id: "k1".into(), kind: KeyKind::Rsa, material: "-----BEGIN PUBLIC KEY-----".into() };
+    let key_snapshot = json!({ "id": "k1", "kind": "rsa", "material": "-----BEGIN PUBLIC KEY-----" });
+    assert_snapshot(AddKeyRequest { key: key.clone() }, json!({ "key": key_snapshot }));
+    assert_snapshot(
+        GetKeysResponse { keys: HashMap::from([("k1".into(), Key { attached: key, created: time(), creator: user(), revoked: Some(time()) })]) },
+        json!({ "keys": { "k1": { "attached": key_snapshot, "created": TIME, "creator": user_snapshot(), "revoked": TIME } } }),
+    );
+}
+
+#[test]
+fn test_revocation_bodies() {
+    assert_snapshot(
+        RevokeTokensRequest { revocation: AttachedRevocation::Token { jti: "t1".into() } },
+        json!({ "revocation": { "kind": "token", "jti": "t1" } }),
+    );
+    assert_snapshot(
+        GetRevocationsResponse {
+            revocations: vec![Revocation {
+                attached: AttachedRevocation::User { user: "amy".into(), before: time() },
+                created:  time(),
+                creator:  user(),
+            }],
+        },
+        json!({ "revocations": [{ "attached": { "kind": "user", "user": "amy", "before": TIME }, "created": TIME, "creator": user_snapshot() }] }),
+    );
+}
+
+#[test]
+fn test_ping_bodies() {
+    assert_snapshot(PingResponse { name: "axum-server".into(), version: "0.2.0".into() }, json!({ "name": "axum-server", "version": "0.2.0" }));
+}
+
+#[test]
+fn test_dev_token_bodies() {
+    assert_snapshot(
+        DevTokenRequest { user: "amy".into(), name: None, roles: vec!["read".into()] },
+        json!({ "user": "amy", "name": null, "roles": ["read"] }),
+    );
+    assert_snapshot(DevTokenResponse { token: "a.b.c".into(), expires: time() }, json!({ "token": "a.b.c", "expires": TIME }));
+}
+
+#[test]
+fn test_federated_bodies() {
+    assert_snapshot(
+        GetFederatedVersionsResponse {
+            stores: HashMap::from([
+                ("a".into(), FederatedStore { versions: HashMap::from([(2, metadata())]), active: Some(2), error: None }),
+                ("b".into(), FederatedStore { versions: HashMap::new(), active: None, error: Some("Connection refused".into()) }),
+            ]),
+        },
+        json!({
+            "stores": {
+                "a": { "versions": { "2": metadata_snapshot() }, "active": 2 },
+                "b": { "versions": {}, "active": null, "error": "Connection refused" },
+            }
+        }),
+    );
+}
+
+#[test]
+fn test_standby_bodies() { assert_snapshot(PromoteStandbyResponse { cursor: 42 }, json!({ "cursor": 42 })); }
+
+#[test]
+fn test_audit_actions() {
+    // The audit actions end up in exported logs, so they are part of the contract as well
+    let actions: [(AuditAction, Value); 18] = [
+        (
+            AuditAction::AddVersion { version: 2, name: "test".into(), branch: "main".into() },
+            json!({ "kind": "add_version", "version": 2, "name": "test", "branch": "main" }),
+        ),
+        (AuditAction::Activate { version: 2 }, json!({ "kind": "activate", "version": 2 })),
+        (AuditAction::Deactivate { version: 2 }, json!({ "kind": "deactivate", "version": 2 })),
+        (AuditAction::AddTemplate { name: "greeting".into() }, json!({ "kind": "add_template", "name": "greeting" })),
+        (AuditAction::AddSchema { name: "eflint".into() }, json!({ "kind": "add_schema", "name": "eflint" })),
+        (AuditAction::CreateBranch { name: "staging".into(), from: 2 }, json!({ "kind": "create_branch", "name": "staging", "from": 2 })),
+        (
+            AuditAction::Promote { version: 3, target: "main".into(), promoted: 4 },
+            json!({ "kind": "promote", "version": 3, "target": "main", "promoted": 4 }),
+        ),
+        (
+            AuditAction::Import { version: 5, name: "test".into(), branch: "main".into(), store: "staging".into(), source: 3 },
+            json!({ "kind": "import", "version": 5, "name": "test", "branch": "main", "store": "staging", "source": 3 }),
+        ),
+        (AuditAction::Pseudonymize { pseudonym: "user-1".into(), rehashed_from: None }, json!({ "kind": "pseudonymize", "pseudonym": "user-1" })),
+        (
+            AuditAction::Pseudonymize { pseudonym: "user-1".into(), rehashed_from: Some(2) },
+            json!({ "kind": "pseudonymize", "pseudonym": "user-1", "rehashed_from": 2 }),
+        ),
+        (AuditAction::PruneActivations { before: time(), rows: 4 }, json!({ "kind": "prune_activations", "before": TIME, "rows": 4 })),
+        (AuditAction::AddKey { id: "k1".into() }, json!({ "kind": "add_key", "id": "k1" })),
+        (AuditAction::RevokeKey { id: "k1".into() }, json!({ "kind": "revoke_key", "id": "k1" })),
+        (AuditAction::RevokeToken { jti: "t1".into() }, json!({ "kind": "revoke_token", "jti": "t1" })),
+        (
+            AuditAction::RevokeUserTokens { user: "amy".into(), before: time() },
+            json!({ "kind": "revoke_user_tokens", "user": "amy", "before": TIME }),
+        ),
+        (
+            AuditAction::FreezeVersion { version: 2, reason: "under investigation".into() },
+            json!({ "kind": "freeze_version", "version": 2, "reason": "under investigation" }),
+        ),
+        (AuditAction::UnfreezeVersion { version: 2 }, json!({ "kind": "unfreeze_version", "version": 2 })),
+        (AuditAction::AddDecision { version: 2, id: "d1".into() }, json!({ "kind": "add_decision", "version": 2, "id": "d1" })),
+    ];
+    for (action, snapshot) in actions {
+        assert_snapshot(action, snapshot);
+    }
+}
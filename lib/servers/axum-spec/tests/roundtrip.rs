@@ -0,0 +1,196 @@
+//  ROUNDTRIP.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:30:16
+//  Last edited:
+//    17 Oct 2026, 03:32:33
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests that all request and response bodies survive a serde
+//!   round-trip unchanged.
+//
+
+use axum_server_spec::*;
+use proptest::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+
+/***** HELPERS *****/
+/// Serializes the given value to JSON text, parses it back and asserts it serializes the same
+/// way again.
+///
+/// We compare the serialized forms instead of the values themselves, as the bodies don't
+/// implement [`PartialEq`] and because it's the wire format we want to guard anyway.
+fn assert_roundtrip<T: Serialize + DeserializeOwned>(value: &T) -> Result<(), TestCaseError> {
+    let before: serde_json::Value = serde_json::to_value(value).unwrap();
+    let parsed: T = serde_json::from_str(&serde_json::to_string(value).unwrap()).map_err(|err| TestCaseError::fail(err.to_string()))?;
+    prop_assert_eq!(before, serde_json::to_value(&parsed).unwrap());
+    Ok(())
+}
+
+
+
+
+
+/***** TESTS *****/
+proptest! {
+    #[test]
+    fn test_api_error_code_roundtrip(value: ApiErrorCode) {
+        let parsed: ApiErrorCode = serde_json::from_str(&serde_json::to_string(&value).unwrap()).unwrap();
+        prop_assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn test_error_response_roundtrip(value: ErrorResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_add_version_request_roundtrip(value: AddVersionRequest<String>) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_add_version_response_roundtrip(value: AddVersionResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_activate_request_roundtrip(value: ActivateRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_deny_all_response_roundtrip(value: DenyAllResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_freeze_version_request_roundtrip(value: FreezeVersionRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_versions_response_roundtrip(value: GetVersionsResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_active_version_query_roundtrip(value: GetActiveVersionQuery) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_active_version_response_roundtrip(value: GetActiveVersionResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_activator_response_roundtrip(value: GetActivatorResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_activations_response_roundtrip(value: GetActivationsResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_version_metadata_response_roundtrip(value: GetVersionMetadataResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_active_content_response_roundtrip(value: GetActiveContentResponse<String>) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_version_content_response_roundtrip(value: GetVersionContentResponse<String>) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_version_provenance_response_roundtrip(value: GetVersionProvenanceResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_add_template_request_roundtrip(value: AddTemplateRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_templates_response_roundtrip(value: GetTemplatesResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_instantiate_template_request_roundtrip(value: InstantiateTemplateRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_add_schema_request_roundtrip(value: AddSchemaRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_schemas_response_roundtrip(value: GetSchemasResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_create_branch_request_roundtrip(value: CreateBranchRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_branches_response_roundtrip(value: GetBranchesResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_promote_request_roundtrip(value: PromoteRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_import_version_query_roundtrip(value: ImportVersionQuery) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_import_version_request_roundtrip(value: ImportVersionRequest<String>) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_import_dry_run_response_roundtrip(value: ImportDryRunResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_initiate_upload_request_roundtrip(value: InitiateUploadRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_initiate_upload_response_roundtrip(value: InitiateUploadResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_upload_status_response_roundtrip(value: UploadStatusResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_audit_log_response_roundtrip(value: GetAuditLogResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_verify_audit_log_response_roundtrip(value: VerifyAuditLogResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_export_audit_log_query_roundtrip(value: ExportAuditLogQuery) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_changes_query_roundtrip(value: GetChangesQuery) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_changes_response_roundtrip(value: GetChangesResponse<String>) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_pseudonymize_user_response_roundtrip(value: PseudonymizeUserResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_prune_activations_request_roundtrip(value: PruneActivationsRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_prune_activations_response_roundtrip(value: PruneActivationsResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_add_decision_request_roundtrip(value: AddDecisionRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_decisions_response_roundtrip(value: GetDecisionsResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_count_decisions_response_roundtrip(value: CountDecisionsResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_decision_response_roundtrip(value: GetDecisionResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_add_key_request_roundtrip(value: AddKeyRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_keys_response_roundtrip(value: GetKeysResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_revoke_tokens_request_roundtrip(value: RevokeTokensRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_revocations_response_roundtrip(value: GetRevocationsResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_ping_response_roundtrip(value: PingResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_dev_token_request_roundtrip(value: DevTokenRequest) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_dev_token_response_roundtrip(value: DevTokenResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_get_federated_versions_response_roundtrip(value: GetFederatedVersionsResponse) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_promote_standby_response_roundtrip(value: PromoteStandbyResponse) { assert_roundtrip(&value)?; }
+}
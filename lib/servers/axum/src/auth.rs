@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 11:58:43
 //  Last edited:
-//    02 Dec 2024, 15:17:13
+//    16 Oct 2026, 19:30:16
 //  Auto updated?
 //    Yes
 //
@@ -12,21 +12,28 @@
 //!   Implements the server's authorization middleware.
 //
 
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::Instant;
 
-use axum::body::Body;
-use axum::extract::{ConnectInfo, Request, State};
-use axum::http::StatusCode;
+use axum::extract::{ConnectInfo, MatchedPath, Request, State};
+use axum::http::header::FORWARDED;
+use axum::http::{HeaderMap, StatusCode};
 use axum::middleware::Next;
 use axum::response::Response;
 use error_trace::ErrorTrace as _;
 use specifications::AuthResolver;
-use specifications::authresolver::HttpError;
+use specifications::authresolver::{AsUser, HttpError};
+use specifications::metadata::AccessContext;
 use thiserror::Error;
-use tracing::{Level, error, info, span};
+use tracing::{Level, debug, error, info, span};
 
-use crate::server::AxumServer;
+use crate::deadline::request_deadline;
+use crate::problem::RequestId;
+use crate::proxy::IpCidr;
+use crate::server::{ADMIN_PATHS, AxumServer};
+use crate::spec::ApiErrorCode;
+use crate::wire::WireFormat;
 
 
 /***** ERRORS *****/
@@ -46,6 +53,100 @@ impl<E: 'static + HttpError> HttpError for Error<E> {
             Self::AuthorizeFailed { err } => err.status_code(),
         }
     }
+
+    #[inline]
+    fn body(&self) -> serde_json::Value {
+        match self {
+            Self::AuthorizeFailed { err } => err.body(),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Extracts the addresses of the hops a request passed through from its `Forwarded` header.
+///
+/// Only the `for` parameter of every element is used (see RFC 7239). Quotes, brackets and ports
+/// are stripped, such that only the node name remains.
+///
+/// # Arguments
+/// - `headers`: The headers of the request.
+///
+/// # Returns
+/// The node names of the hops, in the order they were added (so the client comes first), or
+/// [`None`] if there was no `Forwarded` header.
+fn forwarded_hops(headers: &HeaderMap) -> Option<Vec<&str>> {
+    if !headers.contains_key(FORWARDED) {
+        return None;
+    }
+    Some(
+        headers
+            .get_all(FORWARDED)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|elem| {
+                elem.split(';')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                    .map(|(_, node)| {
+                        let node: &str = node.trim().trim_matches('"');
+                        match node.strip_prefix('[') {
+                            // IPv6, possibly with a port
+                            Some(node) => node.split(']').next().unwrap_or(node),
+                            // IPv4 or something obfuscated, possibly with a port
+                            None => node.split(':').next().unwrap_or(node),
+                        }
+                    })
+                    // NOTE: Elements without `for` still count as hops, they just can't be resolved
+                    .unwrap_or("unknown")
+            })
+            .collect(),
+    )
+}
+
+/// Resolves the address of the client that sent a request.
+///
+/// If the request came in through a trusted proxy, the hops it passed through are walked
+/// backwards to find the first address not belonging to a trusted proxy. The hops are taken from
+/// the `Forwarded` header if there is one, or else from the `X-Forwarded-For` header. If the
+/// request did not come in through a trusted proxy, these headers are ignored, as anybody could
+/// have set them.
+///
+/// # Arguments
+/// - `peer`: The address of the peer that connected to us.
+/// - `headers`: The headers of the request.
+/// - `trusted`: The ranges of proxies whose forwarding headers are trusted.
+///
+/// # Returns
+/// The address of the client.
+fn resolve_client(peer: IpAddr, headers: &HeaderMap, trusted: &[IpCidr]) -> IpAddr {
+    let is_trusted = |addr: &IpAddr| trusted.iter().any(|range| range.contains(addr));
+    if !is_trusted(&peer) {
+        return peer;
+    }
+    let hops: Vec<&str> = forwarded_hops(headers).unwrap_or_else(|| {
+        headers.get_all("x-forwarded-for").iter().filter_map(|value| value.to_str().ok()).flat_map(|value| value.split(',')).map(str::trim).collect()
+    });
+    let mut client: IpAddr = peer;
+    for hop in hops.into_iter().rev() {
+        match hop.parse::<IpAddr>() {
+            Ok(addr) => {
+                client = addr;
+                if !is_trusted(&addr) {
+                    break;
+                }
+            },
+            Err(_) => {
+                debug!("Ignoring remainder of forwarded hops after illegal address {hop:?}");
+                break;
+            },
+        }
+    }
+    client
 }
 
 
@@ -53,38 +154,112 @@ impl<E: 'static + HttpError> HttpError for Error<E> {
 
 
 /***** LIBRARY *****/
-impl<A, D> AxumServer<A, D>
+/// The address of the client that sent a request.
+///
+/// Injected as an extension by [`AxumServer::check()`], taking trusted proxies into account.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ClientAddr(pub IpAddr);
+
+
+
+impl<A, D, T, W> AxumServer<A, D, T, W>
 where
     A: AuthResolver,
-    A::Context: 'static + Send + Sync + Clone,
+    A::Context: 'static + Send + Sync + Clone + AsUser,
     A::ClientError: 'static,
     A::ServerError: 'static,
+    W: WireFormat,
 {
     pub async fn check(State(context): State<Arc<Self>>, ConnectInfo(client): ConnectInfo<SocketAddr>, mut request: Request, next: Next) -> Response {
+        let client: IpAddr = resolve_client(client.ip(), request.headers(), &context.trusted_proxies);
         let _span = span!(Level::INFO, "AxumServer::check", client = client.to_string());
 
+        // Find out how long the client is willing to wait, and don't bother if it already gave up
+        let deadline: Option<Instant> = match request_deadline(request.headers()) {
+            Ok(deadline) => deadline,
+            Err(err) => {
+                info!("{}", err.trace());
+                return context.wire.error_response(ApiErrorCode::BadRequest, err.to_string());
+            },
+        };
+        if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+            info!("Refusing request whose deadline has already passed");
+            return context.wire.error_response(ApiErrorCode::DeadlineExceeded, "Request deadline passed before it could be handled".into());
+        }
+
         // Do the auth thingy
-        let user: A::Context = match context.auth.authorize(request.headers()).await {
+        let user: A::Context = match context.auth.authorize_request(request.method(), request.uri().path(), request.headers()).await {
             Ok(Ok(user)) => user,
             Ok(Err(err)) => {
                 let err = Error::AuthorizeFailed { err };
                 info!("{}", err.trace());
-                let mut res =
-                    Response::new(Body::from(serde_json::to_string(&err.freeze()).unwrap_or_else(|err| panic!("Failed to serialize Trace: {err}"))));
-                *res.status_mut() = err.status_code();
-                return res;
+                return context.wire.http_error_response(ApiErrorCode::from_status(err.status_code()), err.trace().to_string(), &err);
             },
             Err(err) => {
                 let err = Error::AuthorizeFailed { err };
                 error!("{}", err.trace());
-                let mut res = Response::new(Body::from(err.to_string()));
-                *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
-                return res;
+                return context.wire.error_response(ApiErrorCode::Internal, err.to_string());
             },
         };
 
-        // If we found a context, then inject it in the request as an extension; then continue
+        // If we found a context, then inject it in the request as an extension (together with
+        // everything else we know about this access)
+        let mut access: AccessContext = AccessContext::new(user.as_user().clone()).with_client(client);
+        access.user.id = match context.user_ids.normalize(&access.user.id) {
+            Ok(id) => id,
+            Err(err) => {
+                info!("{}", err.trace());
+                return context.wire.error_response(ApiErrorCode::Unauthorized, err.to_string());
+            },
+        };
+        if let Some(RequestId(id)) = request.extensions().get::<RequestId>() {
+            access = access.with_request_id(id.clone());
+        }
+        if let Some(deadline) = deadline {
+            access = access.with_deadline(deadline);
+        }
         request.extensions_mut().insert(user);
-        next.run(request).await
+        request.extensions_mut().insert(ClientAddr(client));
+
+        // Check if the user may call this endpoint at all
+        if let Some(path) = request.extensions().get::<MatchedPath>() {
+            let roles: &[String] = &access.user.roles;
+            // NOTE: Matched paths include the prefix we're nested under (see `AxumServer::into_router()`)
+            for ((method, _), required) in
+                context.required_roles.iter().filter(|((method, endpoint), _)| method == request.method() && path.as_str().ends_with(endpoint))
+            {
+                if !required.iter().any(|role| roles.contains(role)) {
+                    info!("Refusing {} {} for user without any of the roles {required:?}", method, path.as_str());
+                    return context
+                        .wire
+                        .error_response(ApiErrorCode::Forbidden, format!("Calling this endpoint requires any of the roles {required:?}"));
+                }
+            }
+            if ADMIN_PATHS.iter().any(|endpoint| endpoint.method == request.method() && path.as_str().ends_with(endpoint.path))
+                && !context.admin_roles.iter().any(|role| roles.contains(role))
+            {
+                let required: &[String] = &context.admin_roles;
+                info!("Refusing {} {} for user without any of the admin roles {required:?}", request.method(), path.as_str());
+                return context
+                    .wire
+                    .error_response(ApiErrorCode::Forbidden, format!("Calling this endpoint requires any of the admin roles {required:?}"));
+            }
+        }
+
+        // OK, continue until the deadline (if any)
+        // NOTE: Dropping the handler at the deadline doesn't interrupt database work already
+        // started, but connectors don't start any new work for the request either (see
+        // `AccessContext::deadline`)
+        request.extensions_mut().insert(access);
+        match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline.into(), next.run(request)).await {
+                Ok(res) => res,
+                Err(_) => {
+                    info!("Abandoning request whose deadline passed");
+                    context.wire.error_response(ApiErrorCode::DeadlineExceeded, "Request deadline passed before it could be handled".into())
+                },
+            },
+            None => next.run(request).await,
+        }
     }
 }
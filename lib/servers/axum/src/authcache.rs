@@ -0,0 +1,207 @@
+//  AUTHCACHE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:49:24
+//  Last edited:
+//    17 Oct 2026, 02:19:51
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a cache of authorization decisions, such that clients
+//!   sending the same token on every request don't need to be
+//!   authorized from scratch every time.
+//
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, Method};
+use sha2::{Digest as _, Sha256};
+use specifications::AuthResolver;
+use tracing::{debug, trace};
+
+
+/***** AUXILLARY *****/
+/// The hash of whatever identifies a remembered authorization.
+type CacheKey = [u8; 32];
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Hashes the parts of a request that identify a remembered authorization.
+///
+/// # Arguments
+/// - `parts`: The raw parts to hash, e.g., the bytes of the `Authorization` header. Each is
+///   length-prefixed, such that they can't run into each other.
+///
+/// # Returns
+/// The SHA-256 hash of the parts.
+fn hash_key(parts: &[&[u8]]) -> CacheKey {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Wraps an [`AuthResolver`] such that successful authorizations are remembered for a while.
+///
+/// Requests are matched by (a hash of) their `Authorization` header, so only the
+/// `Authorization` header may influence the decision of the nested resolver. When authorized
+/// through [`AuthResolver::authorize_request()`], the method and path are matched too. Requests
+/// without an `Authorization` header are always passed on. Failed authorizations are never
+/// remembered.
+///
+/// Note that this means tokens are accepted for up to the TTL after they expire, or after they are
+/// revoked by other means than the server (which [invalidates](AuthResolver::invalidate()) the
+/// cache when revoking).
+pub struct CachedAuthResolver<A: AuthResolver> {
+    /// The resolver that actually authorizes requests.
+    auth:     A,
+    /// How long to remember authorizations.
+    ttl:      Duration,
+    /// The maximum number of tokens to remember.
+    capacity: usize,
+    /// Maps token hashes to when their entry expires and what they were authorized as.
+    entries:  Mutex<HashMap<CacheKey, (Instant, A::Context)>>,
+}
+impl<A: AuthResolver> CachedAuthResolver<A> {
+    /// Constructor for the CachedAuthResolver.
+    ///
+    /// # Arguments
+    /// - `auth`: The [`AuthResolver`] that actually authorizes requests.
+    /// - `ttl`: How long to remember successful authorizations.
+    /// - `capacity`: The maximum number of tokens to remember.
+    ///
+    /// # Returns
+    /// A new CachedAuthResolver with an empty cache.
+    #[inline]
+    pub fn new(auth: A, ttl: Duration, capacity: usize) -> Self { Self { auth, ttl, capacity, entries: Mutex::new(HashMap::new()) } }
+
+    /// Returns the resolver that actually authorizes requests.
+    ///
+    /// # Returns
+    /// A reference to the nested [`AuthResolver`].
+    #[inline]
+    pub const fn resolver(&self) -> &A { &self.auth }
+
+    /// Forgets all remembered authorizations, e.g., after revoking a token.
+    ///
+    /// Note that the server already does this (through [`AuthResolver::invalidate()`]) whenever it
+    /// revokes tokens or keys itself.
+    #[inline]
+    pub fn clear(&self) { self.entries.lock().unwrap_or_else(|err| err.into_inner()).clear(); }
+}
+impl<A> CachedAuthResolver<A>
+where
+    A: AuthResolver,
+    A::Context: Clone,
+{
+    /// Looks up a remembered authorization, or else authorizes and remembers it.
+    ///
+    /// # Arguments
+    /// - `key`: The key to remember the authorization by.
+    /// - `authorize`: The future that actually authorizes the request.
+    ///
+    /// # Returns
+    /// The (remembered or fresh) result of the nested resolver.
+    ///
+    /// # Errors
+    /// This function errors if the nested resolver does.
+    async fn cached(
+        &self,
+        key: CacheKey,
+        authorize: impl Future<Output = Result<Result<A::Context, A::ClientError>, A::ServerError>>,
+    ) -> Result<Result<A::Context, A::ClientError>, A::ServerError> {
+        // See if we remember it
+        {
+            let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+            match entries.get(&key) {
+                Some((until, context)) if Instant::now() < *until => {
+                    trace!("Authorization cache hit");
+                    return Ok(Ok(context.clone()));
+                },
+                Some(_) => {
+                    entries.remove(&key);
+                },
+                None => {},
+            }
+        }
+
+        // Otherwise, authorize it and remember the result if it's good
+        let context: A::Context = match authorize.await? {
+            Ok(context) => context,
+            Err(err) => return Ok(Err(err)),
+        };
+        if self.capacity > 0 {
+            let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+            if entries.len() >= self.capacity && !entries.contains_key(&key) {
+                let now = Instant::now();
+                entries.retain(|_, (until, _)| now < *until);
+                if entries.len() >= self.capacity {
+                    debug!("Authorization cache full ({} entries); clearing it", entries.len());
+                    entries.clear();
+                }
+            }
+            entries.insert(key, (Instant::now() + self.ttl, context.clone()));
+        }
+        Ok(Ok(context))
+    }
+}
+impl<A> AuthResolver for CachedAuthResolver<A>
+where
+    A: Sync + AuthResolver,
+    A::Context: Send + Clone,
+{
+    type Context = A::Context;
+    type ClientError = A::ClientError;
+    type ServerError = A::ServerError;
+
+
+    fn authorize(&self, headers: &HeaderMap) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>> {
+        async move {
+            // Only the token is remembered, and only as a hash
+            match headers.get(AUTHORIZATION) {
+                Some(value) => self.cached(hash_key(&[value.as_bytes()]), self.auth.authorize(headers)).await,
+                None => self.auth.authorize(headers).await,
+            }
+        }
+    }
+
+    fn authorize_request(
+        &self,
+        method: &Method,
+        path: &str,
+        headers: &HeaderMap,
+    ) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>> {
+        async move {
+            // NOTE: The nested resolver may decide differently per endpoint, so remember those separately
+            match headers.get(AUTHORIZATION) {
+                Some(value) => {
+                    let key: CacheKey = hash_key(&[method.as_str().as_bytes(), path.as_bytes(), value.as_bytes()]);
+                    self.cached(key, self.auth.authorize_request(method, path, headers)).await
+                },
+                None => self.auth.authorize_request(method, path, headers).await,
+            }
+        }
+    }
+
+    #[inline]
+    fn invalidate(&self) {
+        self.clear();
+        self.auth.invalidate();
+    }
+}
@@ -0,0 +1,150 @@
+//  BOOTSTRAP.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:35:01
+//  Last edited:
+//    16 Oct 2026, 18:35:01
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements bootstrapping a default policy on first start, such that
+//!   fresh deployments never run without an active policy.
+//
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use error_trace::trace;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::{AttachedMetadata, User};
+use specifications::{AuthResolver, ContentTransformer, DatabaseConnector};
+use thiserror::Error;
+use tracing::{Level, debug, info, span};
+
+use crate::server::{AxumServer, Error};
+
+
+/***** ERRORS *****/
+/// Defines errors emitted when loading a [`BootstrapPolicy`].
+#[derive(Debug, Error)]
+pub enum BootstrapError {
+    /// Failed to read the policy file.
+    #[error("Failed to read bootstrap policy file {}", path.display())]
+    FileRead {
+        path: PathBuf,
+        #[source]
+        err:  std::io::Error,
+    },
+    /// Failed to parse the policy file.
+    #[error("Failed to parse bootstrap policy file {} as JSON", path.display())]
+    FileParse {
+        path: PathBuf,
+        #[source]
+        err:  serde_json::Error,
+    },
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A policy that is added and activated when the server starts against an empty database.
+///
+/// It is written in the same shape as the body of an add-version request, i.e., as an object
+/// with `metadata` and `contents` fields, such that it can be kept in a file or inline in the
+/// configuration of the server.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BootstrapPolicy {
+    /// The metadata to add the policy with.
+    pub metadata: AttachedMetadata,
+    /// The contents of the policy. These are parsed as the content type of the database (and
+    /// transformed like any other policy) when the policy is added.
+    pub contents: serde_json::Value,
+}
+impl BootstrapPolicy {
+    /// Constructor for the BootstrapPolicy.
+    ///
+    /// # Arguments
+    /// - `metadata`: The [`AttachedMetadata`] to add the policy with.
+    /// - `contents`: The contents of the policy.
+    ///
+    /// # Returns
+    /// A new BootstrapPolicy.
+    #[inline]
+    pub fn new(metadata: AttachedMetadata, contents: impl Into<serde_json::Value>) -> Self { Self { metadata, contents: contents.into() } }
+
+    /// Loads a BootstrapPolicy from a JSON file.
+    ///
+    /// # Arguments
+    /// - `path`: The path of the file to load.
+    ///
+    /// # Returns
+    /// A new BootstrapPolicy with the contents of the file.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read the file or if it is not a valid policy.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, BootstrapError> {
+        let path: &Path = path.as_ref();
+        let raw: String = std::fs::read_to_string(path).map_err(|err| BootstrapError::FileRead { path: path.into(), err })?;
+        serde_json::from_str(&raw).map_err(|err| BootstrapError::FileParse { path: path.into(), err })
+    }
+}
+
+
+
+impl<A, D, T, W> AxumServer<A, D, T, W>
+where
+    A: 'static + Send + Sync + AuthResolver,
+    D: 'static + Send + Sync + DatabaseConnector,
+    D::Content: Send + DeserializeOwned + Serialize,
+    for<'s> D::Connection<'s>: Send,
+    T: 'static + Send + Sync + ContentTransformer<D::Content>,
+{
+    /// Adds and activates the [bootstrap policy](AxumServer::with_bootstrap_policy()) if the
+    /// database does not contain any versions yet.
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    ///
+    /// # Errors
+    /// This function errors if we failed to inspect the database, or if we failed to parse, add
+    /// or activate the policy.
+    pub(crate) async fn bootstrap(this: &Arc<Self>) -> Result<(), Error> {
+        let Some(policy) = &this.bootstrap else { return Ok(()) };
+        let _span = span!(Level::INFO, "AxumServer::bootstrap", policy = policy.metadata.name);
+
+        // Only do something if the database is empty
+        let user = User { id: "bootstrap".into(), name: "Bootstrap policy".into(), issuer: None, roles: Vec::new() };
+        let mut conn: D::Connection<'_> =
+            this.data.connect(&user).await.map_err(|err| Error::Bootstrap { err: trace!(("Failed to connect to database"), err).to_string() })?;
+        let versions = conn.get_versions().await.map_err(|err| Error::Bootstrap { err: trace!(("Failed to get versions"), err).to_string() })?;
+        if !versions.is_empty() {
+            debug!("Database already contains {} version(s); not bootstrapping", versions.len());
+            return Ok(());
+        }
+
+        // Parse it as what the database expects
+        let contents: D::Content = serde_json::from_value(policy.contents.clone())
+            .map_err(|err| Error::Bootstrap { err: trace!(("Failed to parse contents of policy {}", policy.metadata.name), err).to_string() })?;
+        let contents: D::Content = this
+            .transformer
+            .transform(&policy.metadata, contents)
+            .map_err(|err| Error::Bootstrap { err: trace!(("Failed to transform contents of policy {}", policy.metadata.name), err).to_string() })?;
+
+        // Then add and activate it
+        let version: u64 = conn
+            .add_version(policy.metadata.clone(), contents)
+            .await
+            .map_err(|err| Error::Bootstrap { err: trace!(("Failed to add policy {}", policy.metadata.name), err).to_string() })?;
+        conn.activate(version)
+            .await
+            .map_err(|err| Error::Bootstrap { err: trace!(("Failed to activate policy {}", policy.metadata.name), err).to_string() })?;
+        info!("Bootstrapped empty database with policy {} as version {version}", policy.metadata.name);
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+//  COSIGN.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:42:16
+//  Last edited:
+//    16 Oct 2026, 17:42:16
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements verifying cosign signatures over the contents of
+//!   imported policy versions.
+//!
+//!   Only key-based signatures (ECDSA P-256 with SHA-256, cosign's
+//!   default) are supported; keyless signatures need a transparency log
+//!   and certificate chain we don't check.
+//
+
+use base64ct::{Base64, Encoding as _};
+use chrono::Utc;
+use p256::ecdsa::signature::Verifier as _;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::pkcs8::DecodePublicKey as _;
+use serde_json::Value;
+use specifications::canonical::content_hash;
+use specifications::metadata::Verification;
+use thiserror::Error;
+use tracing::debug;
+
+use crate::spec::ContentSignature;
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`CosignVerifier`].
+#[derive(Debug, Error)]
+pub enum CosignError {
+    /// The signed contents are not the contents that were sent.
+    #[error("Signed contents have digest {signed:?}, but the contents sent have digest {actual:?}")]
+    ContentMismatch { signed: String, actual: String },
+    /// A signature was not valid base64.
+    #[error("Signature is not valid base64")]
+    IllegalEncoding {
+        #[source]
+        err: base64ct::Error,
+    },
+    /// A public key could not be parsed.
+    #[error("Public key of identity {name:?} is not a PEM-encoded P-256 public key")]
+    IllegalKey {
+        name: String,
+        #[source]
+        err:  p256::pkcs8::spki::Error,
+    },
+    /// A signed manifest was not a manifest of a policy version.
+    #[error("Signed manifest is not a manifest of a single policy version")]
+    IllegalManifest,
+    /// A signed payload could not be parsed.
+    #[error("Signed payload is not a cosign simple signing payload")]
+    IllegalPayload {
+        #[source]
+        err: serde_json::Error,
+    },
+    /// A signature was not a DER-encoded ECDSA signature.
+    #[error("Signature is not a DER-encoded ECDSA signature")]
+    IllegalSignature {
+        #[source]
+        err: p256::ecdsa::Error,
+    },
+    /// The signed payload refers to another manifest than the one that was sent.
+    #[error("Signed payload refers to manifest {signed:?}, but the manifest sent has digest {actual:?}")]
+    ManifestMismatch { signed: String, actual: String },
+    /// A signed payload did not refer to any manifest.
+    #[error("Signed payload does not refer to a manifest")]
+    UnreferencedManifest,
+    /// No signature was given.
+    #[error("Contents are not signed")]
+    Unsigned,
+    /// None of the trusted identities made the signature.
+    #[error("Signature was not made by any trusted identity")]
+    Untrusted,
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Computes the digest of a blob, the way cosign and OCI registries refer to it.
+///
+/// # Arguments
+/// - `blob`: The blob to compute the digest of.
+///
+/// # Returns
+/// Its digest, as `sha256:<hex>`.
+#[inline]
+fn digest(blob: &str) -> String { format!("sha256:{}", content_hash(blob)) }
+
+
+
+
+
+/***** LIBRARY *****/
+/// Verifies [`ContentSignature`]s against a set of trusted identities.
+///
+/// An identity is a name and the public key its owner signs with (i.e., what `cosign
+/// generate-key-pair` writes to `cosign.pub`). The name is what is recorded in the
+/// [`Verification`] of versions it signed.
+///
+/// By default, versions without a valid signature are still accepted, just without a
+/// verification. In [strict mode](CosignVerifier::with_strict_mode()), they are rejected.
+#[derive(Clone, Debug, Default)]
+pub struct CosignVerifier {
+    /// The trusted identities, by name.
+    identities: Vec<(String, VerifyingKey)>,
+    /// Whether to reject contents that don't verify.
+    strict:     bool,
+}
+impl CosignVerifier {
+    /// Constructor for a CosignVerifier that does not trust anyone (yet).
+    ///
+    /// # Returns
+    /// A new CosignVerifier.
+    #[inline]
+    pub fn new() -> Self { Self::default() }
+
+    /// Trusts signatures made by the given identity.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the identity, recorded in the verification of versions it signed.
+    /// - `pem`: The PEM-encoded P-256 public key of the identity.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    ///
+    /// # Errors
+    /// This function errors if the `pem` is not a P-256 public key.
+    pub fn with_identity(mut self, name: impl Into<String>, pem: &str) -> Result<Self, CosignError> {
+        let name: String = name.into();
+        let key: VerifyingKey = match VerifyingKey::from_public_key_pem(pem) {
+            Ok(key) => key,
+            Err(err) => return Err(CosignError::IllegalKey { name, err }),
+        };
+        self.identities.push((name, key));
+        Ok(self)
+    }
+
+    /// Changes whether contents without a valid signature are rejected.
+    ///
+    /// # Arguments
+    /// - `strict`: True to reject them, or false to accept them without a verification.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns whether contents without a valid signature are rejected.
+    ///
+    /// # Returns
+    /// True if they are, or false if they are accepted without a verification.
+    #[inline]
+    pub fn is_strict(&self) -> bool { self.strict }
+
+
+
+    /// Verifies a signature over the contents of a policy version.
+    ///
+    /// For [blob](ContentSignature::Blob) signatures, the signature must be over the contents
+    /// themselves. For [manifest](ContentSignature::Manifest) signatures, it must be over a
+    /// payload referring to the manifest, whose single layer must be the contents. Note that in
+    /// the latter case, only the contents are checked; the metadata in the manifest's config is
+    /// not.
+    ///
+    /// # Arguments
+    /// - `canonical`: The contents in [canonical form](specifications::canonical).
+    /// - `signature`: The [`ContentSignature`] to verify, if any.
+    ///
+    /// # Returns
+    /// A [`Verification`] recording who signed what.
+    ///
+    /// # Errors
+    /// This function errors if there is no signature, if it is malformed, if it is not over the
+    /// given contents or if it was not made by a trusted identity.
+    pub fn verify(&self, canonical: &str, signature: Option<&ContentSignature>) -> Result<Verification, CosignError> {
+        let actual: String = digest(canonical);
+        let (signed, signature, signed_digest): (&str, &str, String) = match signature {
+            Some(ContentSignature::Blob { signature }) => (canonical, signature, actual),
+            Some(ContentSignature::Manifest { manifest, payload, signature }) => {
+                // The payload must refer to the manifest...
+                let value: Value = serde_json::from_str(payload).map_err(|err| CosignError::IllegalPayload { err })?;
+                let manifest_digest: String = digest(manifest);
+                match value.pointer("/critical/image/docker-manifest-digest").and_then(Value::as_str) {
+                    Some(signed) if signed == manifest_digest => {},
+                    Some(signed) => return Err(CosignError::ManifestMismatch { signed: signed.into(), actual: manifest_digest }),
+                    None => return Err(CosignError::UnreferencedManifest),
+                }
+
+                // ...and the manifest to the contents
+                let value: Value = serde_json::from_str(manifest).map_err(|_| CosignError::IllegalManifest)?;
+                let layer: &str = match value.get("layers").and_then(Value::as_array).map(Vec::as_slice) {
+                    Some([layer]) => layer.get("digest").and_then(Value::as_str).ok_or(CosignError::IllegalManifest)?,
+                    _ => return Err(CosignError::IllegalManifest),
+                };
+                if layer != actual {
+                    return Err(CosignError::ContentMismatch { signed: layer.into(), actual });
+                }
+                (payload, signature, manifest_digest)
+            },
+            None => return Err(CosignError::Unsigned),
+        };
+
+        // Now check the signature itself
+        let signature: Vec<u8> = Base64::decode_vec(signature.trim()).map_err(|err| CosignError::IllegalEncoding { err })?;
+        let signature: Signature = Signature::from_der(&signature).map_err(|err| CosignError::IllegalSignature { err })?;
+        for (name, key) in &self.identities {
+            if key.verify(signed.as_bytes(), &signature).is_ok() {
+                debug!("Signature over {signed_digest} verified as made by {name:?}");
+                return Ok(Verification { identity: name.clone(), digest: signed_digest, verified: Utc::now() });
+            }
+        }
+        Err(CosignError::Untrusted)
+    }
+}
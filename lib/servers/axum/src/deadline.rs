@@ -0,0 +1,108 @@
+//  DEADLINE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:17:06
+//  Last edited:
+//    16 Oct 2026, 17:17:06
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements finding out how long clients are willing to wait for a
+//!   response.
+//
+
+use std::time::{Duration, Instant};
+
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::spec::{GRPC_TIMEOUT_HEADER, REQUEST_DEADLINE_HEADER};
+
+
+/***** ERRORS *****/
+/// Defines errors when finding the deadline of a request.
+#[derive(Debug, Error)]
+pub(crate) enum DeadlineError {
+    /// The deadline header was not an RFC 3339 timestamp.
+    #[error("Invalid {REQUEST_DEADLINE_HEADER} header {raw:?} (must be an RFC 3339 timestamp)")]
+    IllegalDeadline { raw: String },
+    /// The timeout header was not in the gRPC format.
+    #[error("Invalid {GRPC_TIMEOUT_HEADER} header {raw:?} (must be at most 8 digits followed by one of 'H', 'M', 'S', 'm', 'u' or 'n')")]
+    IllegalTimeout { raw: String },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Parses a timeout in the format used by gRPC.
+///
+/// # Arguments
+/// - `raw`: The timeout to parse, e.g., `500m`.
+///
+/// # Returns
+/// The parsed [`Duration`], or [`None`] if `raw` is not a valid timeout.
+fn parse_grpc_timeout(raw: &str) -> Option<Duration> {
+    let unit: char = raw.chars().last()?;
+    let digits: &str = &raw[..raw.len() - unit.len_utf8()];
+    if digits.is_empty() || digits.len() > 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let value: u64 = digits.parse().ok()?;
+    match unit {
+        'H' => Some(Duration::from_secs(value * 3600)),
+        'M' => Some(Duration::from_secs(value * 60)),
+        'S' => Some(Duration::from_secs(value)),
+        'm' => Some(Duration::from_millis(value)),
+        'u' => Some(Duration::from_micros(value)),
+        'n' => Some(Duration::from_nanos(value)),
+        _ => None,
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Finds the deadline of a request from its [`REQUEST_DEADLINE_HEADER`] and
+/// [`GRPC_TIMEOUT_HEADER`], if it has any.
+///
+/// # Arguments
+/// - `headers`: The headers of the request.
+///
+/// # Returns
+/// The earliest deadline set by either header, or [`None`] if neither is given (or both lie too
+/// far in the future to represent). Deadlines in the past are returned as now.
+///
+/// # Errors
+/// This function errors if either header is given but malformed.
+pub(crate) fn request_deadline(headers: &HeaderMap) -> Result<Option<Instant>, DeadlineError> {
+    let now: Instant = Instant::now();
+    let mut deadline: Option<Instant> = None;
+
+    if let Some(raw) = headers.get(REQUEST_DEADLINE_HEADER) {
+        let raw: String = String::from_utf8_lossy(raw.as_bytes()).into();
+        let at: DateTime<Utc> = match DateTime::parse_from_rfc3339(&raw) {
+            Ok(at) => at.with_timezone(&Utc),
+            Err(_) => return Err(DeadlineError::IllegalDeadline { raw }),
+        };
+        // NOTE: Deadlines in the past become "now", as `Instant`s cannot go back arbitrarily
+        let left: Duration = (at - Utc::now()).to_std().unwrap_or_default();
+        deadline = now.checked_add(left);
+    }
+    if let Some(raw) = headers.get(GRPC_TIMEOUT_HEADER) {
+        let raw: String = String::from_utf8_lossy(raw.as_bytes()).into();
+        let Some(timeout) = parse_grpc_timeout(&raw) else {
+            return Err(DeadlineError::IllegalTimeout { raw });
+        };
+        if let Some(at) = now.checked_add(timeout) {
+            deadline = Some(deadline.map_or(at, |deadline| deadline.min(at)));
+        }
+    }
+    Ok(deadline)
+}
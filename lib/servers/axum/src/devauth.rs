@@ -0,0 +1,123 @@
+//  DEVAUTH.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:52:29
+//  Last edited:
+//    16 Oct 2026, 16:52:29
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements minting of development tokens, such that the
+//!   JWK-protected API can be tested locally without an identity
+//!   provider.
+//
+
+use chrono::{DateTime, TimeDelta, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::spec::{DevTokenRequest, DevTokenResponse};
+
+
+/***** LIBRARY *****/
+/// Signs JWTs for arbitrary users with a local key.
+///
+/// **Never use this in production.** Anyone who can reach the server can mint a token for any
+/// user with any roles.
+pub struct DevTokenMinter {
+    /// The header to put in every token (determines the algorithm and key ID).
+    header: Header,
+    /// The key to sign tokens with.
+    key: EncodingKey,
+    /// The claim in which to put the user's identifier.
+    initiator_claim: String,
+    /// How long minted tokens are valid.
+    ttl: TimeDelta,
+}
+impl DevTokenMinter {
+    /// Constructor for the DevTokenMinter that signs tokens with a shared secret (`HS256`).
+    ///
+    /// By default, the user's identifier is put in the `sub` claim, their name in `name` and
+    /// their roles in `roles`. Tokens are valid for an hour.
+    ///
+    /// # Arguments
+    /// - `kid`: The key ID to put in the header of every token, if any.
+    /// - `secret`: The raw bytes of the shared secret.
+    ///
+    /// # Returns
+    /// A new DevTokenMinter.
+    #[inline]
+    pub fn new(kid: Option<String>, secret: &[u8]) -> Self {
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = kid;
+        Self::from_key(header, EncodingKey::from_secret(secret))
+    }
+
+    /// Constructor for the DevTokenMinter that signs tokens with an arbitrary key.
+    ///
+    /// # Arguments
+    /// - `header`: The [`Header`] to put in every token. Its algorithm must match the `key`.
+    /// - `key`: The [`EncodingKey`] to sign tokens with.
+    ///
+    /// # Returns
+    /// A new DevTokenMinter.
+    #[inline]
+    pub fn from_key(header: Header, key: EncodingKey) -> Self { Self { header, key, initiator_claim: "sub".into(), ttl: TimeDelta::hours(1) } }
+
+    /// Changes the claim in which the user's identifier is put.
+    ///
+    /// This should match the initiator claim of the resolver that checks the tokens.
+    ///
+    /// # Arguments
+    /// - `claim`: The name of the claim.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_initiator_claim(mut self, claim: impl Into<String>) -> Self {
+        self.initiator_claim = claim.into();
+        self
+    }
+
+    /// Changes how long minted tokens are valid.
+    ///
+    /// # Arguments
+    /// - `ttl`: The lifetime of a token.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_ttl(mut self, ttl: TimeDelta) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Mints a new token.
+    ///
+    /// # Arguments
+    /// - `req`: The [`DevTokenRequest`] describing who to mint the token for.
+    ///
+    /// # Returns
+    /// A [`DevTokenResponse`] with the signed token.
+    ///
+    /// # Errors
+    /// This function errors if we failed to sign the token.
+    pub fn mint(&self, req: &DevTokenRequest) -> Result<DevTokenResponse, jsonwebtoken::errors::Error> {
+        let now: DateTime<Utc> = Utc::now();
+        let expires: DateTime<Utc> = now + self.ttl;
+
+        let mut claims: Map<String, Value> = Map::new();
+        claims.insert(self.initiator_claim.clone(), Value::String(req.user.clone()));
+        claims.insert("name".into(), Value::String(req.name.clone().unwrap_or_else(|| req.user.clone())));
+        claims.insert("roles".into(), Value::Array(req.roles.iter().cloned().map(Value::String).collect()));
+        claims.insert("iat".into(), Value::from(now.timestamp()));
+        claims.insert("exp".into(), Value::from(expires.timestamp()));
+        claims.insert("jti".into(), Value::String(Uuid::new_v4().to_string()));
+
+        let token: String = jsonwebtoken::encode(&self.header, &claims, &self.key)?;
+        Ok(DevTokenResponse { token, expires })
+    }
+}
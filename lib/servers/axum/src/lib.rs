@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 10:25:43
 //  Last edited:
-//    06 Dec 2024, 18:02:38
+//    17 Oct 2026, 02:37:51
 //  Auto updated?
 //    Yes
 //
@@ -15,10 +15,50 @@
 
 // Modules
 mod auth;
+mod authcache;
+mod bootstrap;
+#[cfg(feature = "cosign")]
+mod cosign;
+mod deadline;
+#[cfg(feature = "dev-auth")]
+mod devauth;
+mod maintenance;
 mod paths;
+mod problem;
+mod proxy;
+mod pseudonym;
 mod server;
-
+#[cfg(feature = "standby")]
+mod standby;
+mod syslog;
+#[cfg(feature = "test-utils")]
+pub mod testing;
+mod uploads;
+#[cfg(feature = "webhooks")]
+mod webhook;
+mod wire;
+mod worker;
 // Re-exports
-pub use axum_server_spec as spec;
 // Use local parts
+pub use auth::ClientAddr;
+pub use authcache::CachedAuthResolver;
+pub use axum;
+pub use axum_server_spec as spec;
+pub use bootstrap::{BootstrapError, BootstrapPolicy};
+#[cfg(feature = "cosign")]
+pub use cosign::{CosignError, CosignVerifier};
+#[cfg(feature = "dev-auth")]
+pub use devauth::DevTokenMinter;
+pub use problem::RequestId;
+pub use proxy::{IpCidr, IpCidrParseError};
+pub use pseudonym::PseudonymKey;
 pub use server::*;
+#[cfg(feature = "standby")]
+pub use standby::Standby;
+pub use syslog::SyslogForwarder;
+pub use uploads::{DEFAULT_MAX_TOTAL_UPLOAD_SIZE, DEFAULT_MAX_UPLOAD_SIZE, DEFAULT_MAX_UPLOADS_PER_USER};
+#[cfg(feature = "webhooks")]
+pub use webhook::{WebhookError, WebhookSink};
+#[cfg(feature = "cbor")]
+pub use wire::{Cbor, CborError};
+pub use wire::{Json, WireFormat};
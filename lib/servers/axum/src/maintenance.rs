@@ -0,0 +1,84 @@
+//  MAINTENANCE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:08:10
+//  Last edited:
+//    16 Oct 2026, 15:40:02
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements running backend maintenance on a daily schedule, such
+//!   that it happens during quiet hours.
+//
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveTime, TimeDelta, Utc};
+use error_trace::trace;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use specifications::{AuthResolver, ContentTransformer, DatabaseConnector};
+use tracing::{Level, debug, error, info, span};
+
+use crate::server::AxumServer;
+
+
+/***** HELPER FUNCTIONS *****/
+/// Computes the first moment after `now` at which the time of day is `at`.
+///
+/// # Arguments
+/// - `now`: The current time.
+/// - `at`: The time of day (in UTC).
+///
+/// # Returns
+/// The next moment at which it is `at`.
+fn next_occurrence(now: DateTime<Utc>, at: NaiveTime) -> DateTime<Utc> {
+    let today: DateTime<Utc> = now.date_naive().and_time(at).and_utc();
+    if today > now { today } else { today + TimeDelta::days(1) }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+impl<A, D, T, W> AxumServer<A, D, T, W>
+where
+    A: 'static + Send + Sync + AuthResolver,
+    D: 'static + Send + Sync + DatabaseConnector,
+    D::Content: Send + DeserializeOwned + Serialize,
+    for<'s> D::Connection<'s>: Send,
+    T: 'static + Send + Sync + ContentTransformer<D::Content>,
+{
+    /// Runs [`DatabaseConnector::maintain()`] every day at the configured time, if any.
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    ///
+    /// # Returns
+    /// This function does not return for as long as the server runs (unless no time is
+    /// configured, in which case it returns immediately).
+    pub(crate) async fn run_maintenance(this: Arc<Self>) {
+        let Some(at) = this.maintenance else { return };
+        let _span = span!(Level::INFO, "AxumServer::run_maintenance", at = at.to_string());
+
+        info!("Scheduling daily database maintenance at {at} UTC");
+        loop {
+            // Wait until it's time
+            let now: DateTime<Utc> = Utc::now();
+            let next: DateTime<Utc> = next_occurrence(now, at);
+            debug!("Next database maintenance at {}", next.to_rfc3339());
+            tokio::time::sleep((next - now).to_std().unwrap_or(Duration::ZERO)).await;
+
+            // Run it
+            info!("Running database maintenance...");
+            match this.data.maintain().await {
+                Ok(_) => info!("Database maintenance completed"),
+                Err(err) => error!("{}", trace!(("Failed to run database maintenance"), err)),
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 11:56:03
 //  Last edited:
-//    06 Dec 2024, 14:38:58
+//    17 Oct 2026, 03:32:33
 //  Auto updated?
 //    Yes
 //
@@ -17,44 +17,116 @@ use std::future::Future;
 use std::sync::Arc;
 
 use axum::Extension;
-use axum::body::Bytes;
-use axum::extract::{Path, Request, State};
-use axum::http::StatusCode;
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::header::{ACCEPT_RANGES, CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_NONE_MATCH, RANGE};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse as _, Response};
+use chrono::{DateTime, Utc};
 use error_trace::trace;
 use futures::StreamExt;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use specifications::DatabaseConnector;
-use specifications::databaseconn::DatabaseConnection;
-use specifications::metadata::{Metadata, User};
+#[cfg(feature = "deny-all")]
+use specifications::DenyAll;
+use specifications::audit::{Added, AuditAction, AuditRecord, Change, verify_chain};
+use specifications::canonical::{content_hash, to_canonical_string};
+use specifications::databaseconn::{ClientError, DatabaseConnection};
+use specifications::decision::Decision;
+#[cfg(feature = "deny-all")]
+use specifications::denyall::DENY_ALL_BRANCH;
+use specifications::key::{Key, KeyKind};
+use specifications::metadata::{AccessContext, Activation, AttachedMetadata, Branch, Metadata, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::Revocation;
+use specifications::schema::{Schema, ValidateError};
+use specifications::template::Template;
+use specifications::{AuthResolver, ContentTransformer, DatabaseConnector};
+#[cfg(any(feature = "cosign", feature = "deny-all", feature = "dev-auth", feature = "federation"))]
+use tracing::warn;
 use tracing::{Level, error, info, span};
 
-use crate::server::AxumServer;
+use crate::server::{AxumServer, MaybeDenyAll, NoActivePolicy};
+#[cfg(feature = "deny-all")]
+use crate::spec::DenyAllResponse;
+#[cfg(feature = "standby")]
+use crate::spec::PromoteStandbyResponse;
 use crate::spec::{
-    ActivateRequest, AddVersionRequest, AddVersionResponse, GetActivatorResponse, GetActiveVersionResponse, GetVersionContentResponse,
-    GetVersionMetadataResponse, GetVersionsResponse,
+    ActivateRequest, AddDecisionRequest, AddKeyRequest, AddSchemaRequest, AddTemplateRequest, AddVersionRequest, AddVersionResponse, ApiErrorCode,
+    CountDecisionsResponse, CreateBranchRequest, ErrorResponse, ExportAuditLogQuery, ExportFormat, FreezeVersionRequest, GetActivationsResponse,
+    GetActivatorResponse, GetActiveContentResponse, GetActiveVersionQuery, GetActiveVersionResponse, GetAuditLogResponse, GetBranchesResponse,
+    GetChangesQuery, GetChangesResponse, GetDecisionResponse, GetDecisionsResponse, GetKeysResponse, GetRevocationsResponse, GetSchemasResponse,
+    GetTemplatesResponse, GetVersionContentResponse, GetVersionMetadataResponse, GetVersionProvenanceResponse, GetVersionsResponse,
+    ImportDryRunResponse, ImportVersionQuery, ImportVersionRequest, InitiateUploadRequest, InitiateUploadResponse, InstantiateTemplateRequest,
+    PingResponse, PromoteRequest, PruneActivationsRequest, PruneActivationsResponse, PseudonymizeUserResponse, RevokeTokensRequest,
+    UPLOAD_OFFSET_HEADER, UploadStatusResponse, VerifyAuditLogResponse,
 };
+#[cfg(feature = "dev-auth")]
+use crate::spec::{DevTokenRequest, DevTokenResponse};
+#[cfg(feature = "federation")]
+use crate::spec::{FederatedStore, GetFederatedVersionsResponse};
+use crate::uploads::TakenUpload;
+use crate::wire::WireFormat;
 
 
 /***** HELPER FUNCTIONS *****/
 /// Turns the given [`Request`] into a deserialized object.
 ///
 /// This is done instead of using the [`Json`](axum::extract::Json) extractor because we want to
-/// log the raw inputs upon failure.
+/// report where parsing failed (and support other [`WireFormat`]s).
 ///
 /// # Generics
 /// - `T`: The thing to deserialize to.
 ///
 /// # Arguments
-/// - `request`: The [`Request`] to download and turn into JSON.
+/// - `wire`: The [`WireFormat`] in which the body is encoded.
+/// - `log_body`: Whether to log the raw body (at TRACE level) if it fails to parse.
+/// - `request`: The [`Request`] to download and deserialize.
 ///
 /// # Returns
 /// A parsed `T`.
 ///
 /// # Errors
-/// This function errors if we failed to download the request body, or it was not valid JSON.
-async fn download_request<T: DeserializeOwned>(request: Request) -> Result<T, (StatusCode, String)> {
+/// This function errors with an [`ErrorResponse`] to send back if the request's `Content-Type`
+/// is not accepted by the `wire` format, if we failed to download the request body, or if it was
+/// not a valid encoding of a `T`.
+async fn download_request<W: WireFormat, T: DeserializeOwned>(wire: &W, log_body: bool, request: Request) -> Result<T, Box<ErrorResponse>> {
+    // Refuse anything we can't parse anyway
+    match request.headers().get(CONTENT_TYPE).map(|value| value.to_str()) {
+        Some(Ok(content_type)) if wire.accepts(content_type) => {},
+        Some(Ok(content_type)) => {
+            let msg: String = format!("Unsupported request body type {content_type:?} (expected {:?})", wire.content_type());
+            info!("{msg}");
+            return Err(Box::new(ErrorResponse::new(ApiErrorCode::UnsupportedMediaType, msg)));
+        },
+        Some(Err(_)) | None => {
+            let msg: String = format!("Missing or invalid Content-Type header (expected {:?})", wire.content_type());
+            info!("{msg}");
+            return Err(Box::new(ErrorResponse::new(ApiErrorCode::UnsupportedMediaType, msg)));
+        },
+    }
+
     // Download the entire request first
+    let req: Vec<u8> = download_body(request, None).await.map_err(|(code, message)| Box::new(ErrorResponse::new(code, message)))?;
+
+    // Deserialize the request contents
+    parse_body(wire, log_body, &req)
+}
+
+/// Downloads the raw body of the given [`Request`].
+///
+/// # Arguments
+/// - `request`: The [`Request`] to download.
+/// - `limit`: The maximum number of bytes to download, if any. Downloading stops as soon as the
+///   body grows beyond it.
+///
+/// # Returns
+/// The bytes in the body.
+///
+/// # Errors
+/// This function errors if we failed to download the request body, or with
+/// [`ApiErrorCode::PayloadTooLarge`] if it is longer than `limit`.
+async fn download_body(request: Request, limit: Option<u64>) -> Result<Vec<u8>, (ApiErrorCode, String)> {
     let mut req: Vec<u8> = Vec::new();
     let mut request = request.into_body().into_data_stream();
     while let Some(next) = request.next().await {
@@ -64,42 +136,350 @@ async fn download_request<T: DeserializeOwned>(request: Request) -> Result<T, (S
             Err(err) => {
                 let msg: &'static str = "Failed to download request body";
                 error!("{}", trace!(("{msg}"), err));
-                return Err((StatusCode::INTERNAL_SERVER_ERROR, msg.into()));
+                return Err((ApiErrorCode::Internal, msg.into()));
             },
         };
 
-        // Append it
+        // Append it, if it fits
+        if let Some(limit) = limit {
+            if (req.len() + next.len()) as u64 > limit {
+                return Err((ApiErrorCode::PayloadTooLarge, format!("Request body exceeds maximum of {limit} bytes")));
+            }
+        }
         req.extend(next);
     }
+    Ok(req)
+}
 
-    // Deserialize the request contents
-    match serde_json::from_slice(&req) {
+/// Deserializes some downloaded body.
+///
+/// # Generics
+/// - `T`: The thing to deserialize to.
+///
+/// # Arguments
+/// - `wire`: The [`WireFormat`] in which the body is encoded.
+/// - `log_body`: Whether to log the raw body (at TRACE level) if it fails to parse.
+/// - `req`: The raw bytes to deserialize.
+///
+/// # Returns
+/// A parsed `T`.
+///
+/// # Errors
+/// This function errors with an [`ErrorResponse`] to send back if the body was not a valid
+/// encoding of a `T`. This response never includes the body itself, as it may contain secrets.
+fn parse_body<W: WireFormat, T: DeserializeOwned>(wire: &W, log_body: bool, req: &[u8]) -> Result<T, Box<ErrorResponse>> {
+    match wire.deserialize(req) {
         Ok(req) => Ok(req),
         Err(err) => {
-            let error: String = format!(
-                "{}Raw body:\n{}\n{}\n{}\n",
-                trace!(("Failed to deserialize request body"), err),
-                (0..80).map(|_| '-').collect::<String>(),
-                String::from_utf8_lossy(&req),
-                (0..80).map(|_| '-').collect::<String>()
-            );
-            info!("{error}");
-            Err((StatusCode::BAD_REQUEST, error))
+            let message: String = trace!(("Failed to deserialize request body"), &err).to_string();
+            info!("{message}");
+            if log_body {
+                // NOTE: Fully qualified, as `trace!()` is the error-trace one here
+                tracing::trace!(
+                    "Raw body:\n{}\n{}\n{}\n",
+                    (0..80).map(|_| '-').collect::<String>(),
+                    String::from_utf8_lossy(req),
+                    (0..80).map(|_| '-').collect::<String>()
+                );
+            }
+            Err(Box::new(ErrorResponse::new(ApiErrorCode::MalformedBody, message).with_position(wire.error_position(&err))))
+        },
+    }
+}
+
+
+
+
+
+/// Validates content against the [`Schema`] referenced in its metadata, if any.
+///
+/// # Arguments
+/// - `conn`: The [`DatabaseConnection`] to retrieve the schema from.
+/// - `metadata`: The [`AttachedMetadata`] that may reference a schema.
+/// - `content`: The content to validate.
+///
+/// # Returns
+/// The given `content`, if it is valid.
+///
+/// # Errors
+/// This function errors with a response to send back if the schema doesn't exist, if the content
+/// does not match it, or if we failed to retrieve the schema.
+async fn validate_content<C>(conn: &mut C, metadata: &AttachedMetadata, content: C::Content) -> Result<C::Content, (ApiErrorCode, String)>
+where
+    C: DatabaseConnection,
+    C::Content: Serialize,
+{
+    // Only do something if a schema is referenced
+    let name: &str = match &metadata.schema {
+        Some(name) => name,
+        None => return Ok(content),
+    };
+    let value: serde_json::Value = match serde_json::to_value(&content) {
+        Ok(value) => value,
+        Err(err) => {
+            let msg: String = format!("Failed to serialize content of policy {}", metadata.name);
+            error!("{}", trace!(("{msg}"), err));
+            return Err((ApiErrorCode::Internal, msg));
+        },
+    };
+
+    // Get the schema
+    let schema: Schema = match conn.get_schema(name).await {
+        Ok(Some(schema)) => schema,
+        Ok(None) => return Err((ApiErrorCode::ValidationFailed, format!("Unknown schema {name:?}"))),
+        Err(err) => {
+            let msg: String = format!("Failed to get schema {name}");
+            error!("{}", trace!(("{msg}"), err));
+            return Err((ApiErrorCode::Internal, msg));
+        },
+    };
+
+    // Validate it
+    match schema.validate(&value) {
+        Ok(()) => Ok(content),
+        Err(err @ ValidateError::Mismatch { .. }) => {
+            let msg: String = err.to_string();
+            info!("{msg}");
+            Err((ApiErrorCode::ValidationFailed, msg))
+        },
+        Err(err) => {
+            let msg: String = format!("Failed to validate content of policy {}", metadata.name);
+            error!("{}", trace!(("{msg}"), err));
+            Err((ApiErrorCode::Internal, msg))
+        },
+    }
+}
+
+/// Finds the well-known deny-all policy version served by [`AxumServer::deny_all()`].
+///
+/// # Arguments
+/// - `conn`: The [`DatabaseConnection`] to search with.
+///
+/// # Returns
+/// The head of the [`DENY_ALL_BRANCH`] if its content is [`DenyAll::deny_all()`] and it isn't
+/// frozen, or [`None`] if there's no such version (yet).
+///
+/// # Errors
+/// This function errors with a message to send back if we failed to consult the backend.
+#[cfg(feature = "deny-all")]
+async fn find_deny_all<C>(conn: &mut C) -> Result<Option<u64>, String>
+where
+    C: DatabaseConnection,
+    C::Content: Serialize + DenyAll,
+{
+    // Find the head of the branch, if it exists
+    let head: u64 = match conn.get_branches().await {
+        Ok(branches) => match branches.get(DENY_ALL_BRANCH).and_then(|branch| branch.head) {
+            Some(head) => head,
+            None => return Ok(None),
+        },
+        Err(err) => {
+            let msg: String = "Failed to get branches".to_string();
+            error!("{}", trace!(("{msg}"), err));
+            return Err(msg);
+        },
+    };
+
+    // Someone may have appended to (or frozen) the branch, so check it's still what we expect
+    match conn.get_version_metadata(head).await {
+        Ok(Some(metadata)) if metadata.frozen.is_none() => {},
+        Ok(_) => return Ok(None),
+        Err(err) => {
+            let msg: String = format!("Failed to get metadata of policy {head}");
+            error!("{}", trace!(("{msg}"), err));
+            return Err(msg);
+        },
+    }
+    let content: C::Content = match conn.get_version_content(head).await {
+        Ok(Some(content)) => content,
+        Ok(None) => return Ok(None),
+        Err(err) => {
+            let msg: String = format!("Failed to get content of policy {head}");
+            error!("{}", trace!(("{msg}"), err));
+            return Err(msg);
+        },
+    };
+    match (to_canonical_string(&content), to_canonical_string(&C::Content::deny_all())) {
+        (Ok(content), Ok(expected)) => Ok(Some(head).filter(|_| content == expected)),
+        (Err(err), _) | (_, Err(err)) => {
+            let msg: String = format!("Failed to serialize content of policy {head}");
+            error!("{}", trace!(("{msg}"), err));
+            Err(msg)
+        },
+    }
+}
+
+/// Validates the import of a version without writing anything.
+///
+/// This checks the same as [`DatabaseConnection::import_version()`] does, i.e., that the content
+/// matches the hash it was recorded with.
+///
+/// # Arguments
+/// - `conn`: The [`DatabaseConnection`] to consult.
+/// - `store`: The name of the store the version is copied from.
+/// - `metadata`: The [`Metadata`] of the version in that store.
+/// - `content`: The (already validated) content of the version.
+///
+/// # Returns
+/// An [`ImportDryRunResponse`] describing what importing would change, or a [`ClientError`] if it
+/// would be refused.
+///
+/// # Errors
+/// This function errors with a message to send back if we failed to consult the backend.
+async fn preview_import<C>(
+    conn: &mut C,
+    store: &str,
+    metadata: Metadata,
+    content: C::Content,
+) -> Result<Result<ImportDryRunResponse, ClientError>, String>
+where
+    C: DatabaseConnection,
+    C::Content: Serialize,
+{
+    // Make sure the content survived the trip
+    let hash: String = match to_canonical_string(&content) {
+        Ok(canonical) => content_hash(&canonical),
+        Err(err) => {
+            let msg: String = format!("Failed to serialize content of policy {} from store {store:?}", metadata.version);
+            error!("{}", trace!(("{msg}"), err));
+            return Err(msg);
+        },
+    };
+    if let Some(expected) = metadata.hash.filter(|expected| *expected != hash) {
+        return Ok(Err(ClientError::HashMismatch { version: metadata.version, expected, actual: hash }));
+    }
+
+    // Find where the copy would end up...
+    let head: Option<u64> = match conn.get_branches().await {
+        Ok(branches) => branches.get(&metadata.attached.branch).and_then(|branch| branch.head),
+        Err(err) => {
+            let msg: String = "Failed to get branches".to_string();
+            error!("{}", trace!(("{msg}"), err));
+            return Err(msg);
+        },
+    };
+
+    // ...and whether the original was imported before
+    let (origin_store, origin_version): (&str, u64) = match &metadata.origin {
+        Some(origin) => (&origin.store, origin.version),
+        None => (store, metadata.version),
+    };
+    let mut duplicates: Vec<u64> = match conn.get_versions().await {
+        Ok(versions) => versions
+            .into_iter()
+            .filter(|(_, version)| version.origin.as_ref().is_some_and(|origin| origin.store == origin_store && origin.version == origin_version))
+            .map(|(version, _)| version)
+            .collect(),
+        Err(err) => {
+            let msg: String = "Failed to get policy versions".to_string();
+            error!("{}", trace!(("{msg}"), err));
+            return Err(msg);
+        },
+    };
+    duplicates.sort_unstable();
+
+    Ok(Ok(ImportDryRunResponse { branch: metadata.attached.branch, head, hash, duplicates }))
+}
+
+/// Checks whether a user may read the content of a version.
+///
+/// # Arguments
+/// - `metadata`: The [`Metadata`] of the version to read.
+/// - `access`: The [`AccessContext`] of the user reading it.
+/// - `roles`: The roles of which the user needs at least one if the version is confidential.
+///
+/// # Returns
+/// True if the version isn't confidential or the user has one of the `roles`, false otherwise.
+fn may_read_content<'r>(metadata: &Metadata, access: &AccessContext, mut roles: impl Iterator<Item = &'r String>) -> bool {
+    !metadata.attached.confidential || roles.any(|role| access.user.roles.contains(role))
+}
+
+/// Parses the value of a `Range` header.
+///
+/// Only single byte ranges are supported (i.e., `bytes=<start>-<end>`, `bytes=<start>-` or
+/// `bytes=-<suffix>`).
+///
+/// # Arguments
+/// - `range`: The value of the `Range` header.
+/// - `len`: The total length of the resource requested.
+///
+/// # Returns
+/// [`None`] if the header is not a single byte range (and should be ignored),
+/// [`Some(Ok((start, end)))`] with the inclusive range if it is satisfiable, or [`Some(Err(()))`]
+/// if it is not.
+fn parse_range(range: &str, len: u64) -> Option<Result<(u64, u64), ()>> {
+    let spec: &str = range.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end): (&str, &str) = spec.split_once('-')?;
+    let (start, end): (u64, u64) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            if suffix == 0 || len == 0 {
+                return Some(Err(()));
+            }
+            (len.saturating_sub(suffix), len - 1)
         },
+        (start, "") => (start.parse().ok()?, len.saturating_sub(1)),
+        (start, end) => {
+            let (start, end): (u64, u64) = (start.parse().ok()?, end.parse().ok()?);
+            if end < start {
+                return None;
+            }
+            (start, end.min(len.saturating_sub(1)))
+        },
+    };
+    if start >= len { Some(Err(())) } else { Some(Ok((start, end))) }
+}
+
+/// Computes the entity tag of the active version.
+///
+/// The [`GetActiveVersionResponse`] only depends on the version, so that is all that is needed.
+///
+/// # Arguments
+/// - `version`: The active version, if any.
+///
+/// # Returns
+/// A quoted entity tag.
+fn active_version_etag(version: Option<u64>) -> String {
+    match version {
+        Some(version) => format!("\"{version}\""),
+        None => "\"none\"".into(),
     }
 }
 
+/// Checks whether an `If-None-Match` header matches an entity tag.
+///
+/// # Arguments
+/// - `headers`: The [`HeaderMap`] of the request.
+/// - `etag`: The (quoted) entity tag of the current resource.
+///
+/// # Returns
+/// True if the client's copy is current (and a 304 should be sent), false otherwise.
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+}
+
 
 
 
 
 /***** LIBRARIES *****/
-impl<A, D> AxumServer<A, D>
+impl<A, D, T, W> AxumServer<A, D, T, W>
 where
-    A: 'static + Send + Sync,
+    A: 'static + Send + Sync + AuthResolver,
     D: 'static + Send + Sync + DatabaseConnector,
-    D::Content: Send + DeserializeOwned + Serialize,
+    D::Content: Send + DeserializeOwned + Serialize + MaybeDenyAll,
     for<'s> D::Connection<'s>: Send,
+    T: 'static + Send + Sync + ContentTransformer<D::Content>,
+    W: WireFormat,
 {
     /// Handler for `POST /v2/policies` (i.e., uploading a new policy).
     ///
@@ -108,43 +488,58 @@ where
     ///
     /// Out:
     /// - 200 OK with an [`AddVersionResponse`] detailling the version number of the new policy;
-    /// - 404 BAD REQUEST with the reason why we failed to parse the request; or
+    /// - 404 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
     /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
     pub fn add_version(
         State(this): State<Arc<Self>>,
-        Extension(auth): Extension<User>,
+        Extension(access): Extension<AccessContext>,
         request: Request,
-    ) -> impl 'static + Send + Future<Output = (StatusCode, String)> {
+    ) -> impl 'static + Send + Future<Output = Response> {
         async move {
-            let _span = span!(Level::INFO, "AxumServer::add_version", user = auth.id);
+            let _span = span!(Level::INFO, "AxumServer::add_version", user = access.user.id);
 
             // Get the request
-            let req: AddVersionRequest<D::Content> = match download_request(request).await {
+            let req: AddVersionRequest<D::Content> = match download_request(&this.wire, this.log_bodies, request).await {
                 Ok(req) => req,
-                Err(res) => return res,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Run it through the transformers
+            let contents: D::Content = match this.transformer.transform(&req.metadata, req.contents) {
+                Ok(contents) => contents,
+                Err(err) => {
+                    let msg: String = trace!(("Failed to transform contents of policy {}", req.metadata.name), err).to_string();
+                    info!("{msg}");
+                    return this.wire.error_response(ApiErrorCode::ValidationFailed, msg);
+                },
             };
 
             // Just try to send it to the DB
-            let mut conn: D::Connection<'_> = match this.data.connect(&auth).await {
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
                 Ok(conn) => conn,
                 Err(err) => {
                     let msg: String = format!("Failed to add policy {}", req.metadata.name);
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
+            let contents: D::Content = match validate_content(&mut conn, &req.metadata, contents).await {
+                Ok(contents) => contents,
+                Err((code, msg)) => return this.wire.error_response(code, msg),
+            };
             let name: String = req.metadata.name.clone();
-            let version: u64 = match conn.add_version(req.metadata, req.contents).await {
+            let version: u64 = match conn.add_version(req.metadata, contents).await {
                 Ok(res) => res,
                 Err(err) => {
                     let msg: String = format!("Failed to add policy {name}");
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
 
             // Return the version
-            (StatusCode::OK, serde_json::to_string(&AddVersionResponse { version }).unwrap())
+            this.wire.response(StatusCode::OK, &AddVersionResponse { version })
         }
     }
 
@@ -155,39 +550,56 @@ where
     ///
     /// Out:
     /// - 200 OK;
-    /// - 404 BAD REQUEST with the reason why we failed to parse the request; or
+    /// - 404 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 409 CONFLICT if the version is [frozen](AxumServer::freeze_version());
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
     /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
     pub fn activate(
         State(this): State<Arc<Self>>,
-        Extension(auth): Extension<User>,
+        Extension(access): Extension<AccessContext>,
         request: Request,
-    ) -> impl 'static + Send + Future<Output = (StatusCode, String)> {
+    ) -> impl 'static + Send + Future<Output = Response> {
         async move {
-            let _span = span!(Level::INFO, "AxumServer::activate", user = auth.id);
+            let _span = span!(Level::INFO, "AxumServer::activate", user = access.user.id);
 
             // Get the request
-            let version: ActivateRequest = match download_request(request).await {
+            let version: ActivateRequest = match download_request(&this.wire, this.log_bodies, request).await {
                 Ok(req) => req,
-                Err(res) => return res,
+                Err(err) => return this.wire.problem_response(*err),
             };
 
             // Just try to send it to the DB
-            let mut conn: D::Connection<'_> = match this.data.connect(&auth).await {
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
                 Ok(conn) => conn,
                 Err(err) => {
                     let msg: String = format!("Failed to activate policy {}", version.version);
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
+
+            // Tell the client why frozen versions can't be activated (the backend refuses them regardless)
+            match conn.get_version_metadata(version.version).await {
+                Ok(Some(metadata)) if metadata.frozen.is_some() => {
+                    let err = ClientError::VersionFrozen { version: version.version };
+                    info!("{}", trace!(("Failed to activate policy {}", version.version), err));
+                    return this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err);
+                },
+                Ok(_) => {},
+                Err(err) => {
+                    let msg: String = format!("Failed to activate policy {}", version.version);
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            }
             if let Err(err) = conn.activate(version.version).await {
                 let msg: String = format!("Failed to activate policy {}", version.version);
                 error!("{}", trace!(("{msg}"), err));
-                return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                return this.wire.error_response(ApiErrorCode::Internal, msg);
             };
 
             // Done
-            (StatusCode::OK, String::new())
+            StatusCode::OK.into_response()
         }
     }
 
@@ -196,35 +608,88 @@ where
     /// Out:
     /// - 200 OK; or
     /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
-    pub fn deactivate(
-        State(this): State<Arc<Self>>,
-        Extension(auth): Extension<User>,
-    ) -> impl 'static + Send + Future<Output = (StatusCode, String)> {
+    pub fn deactivate(State(this): State<Arc<Self>>, Extension(access): Extension<AccessContext>) -> impl 'static + Send + Future<Output = Response> {
         async move {
-            let _span = span!(Level::INFO, "AxumServer::deactivate", user = auth.id);
+            let _span = span!(Level::INFO, "AxumServer::deactivate", user = access.user.id);
 
             // Just try to send it to the DB
-            let mut conn: D::Connection<'_> = match this.data.connect(&auth).await {
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
                 Ok(conn) => conn,
                 Err(err) => {
                     let msg: String = "Failed to deactivate any active policy".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
             if let Err(err) = conn.deactivate().await {
                 let msg: String = "Failed to deactivate any active policy".to_string();
                 error!("{}", trace!(("{msg}"), err));
-                return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                return this.wire.error_response(ApiErrorCode::Internal, msg);
             };
 
             // Done
-            (StatusCode::OK, String::new())
+            StatusCode::OK.into_response()
         }
     }
 
 
 
+    /// Handler for `POST /v2/policies/active/deny-all` (i.e., activating the built-in deny-all
+    /// policy).
+    ///
+    /// The deny-all policy is the head of the [`DENY_ALL_BRANCH`], which is reused as long as its
+    /// content is still [`DenyAll::deny_all()`] and it isn't frozen. Otherwise, a new version is
+    /// added to that branch first. Either way, it does not pass through any transformers or
+    /// schemas, as the store defines it itself.
+    ///
+    /// Out:
+    /// - 200 OK with a [`DenyAllResponse`] detailling the version of the deny-all policy; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    #[cfg(feature = "deny-all")]
+    pub fn deny_all(State(this): State<Arc<Self>>, Extension(access): Extension<AccessContext>) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::deny_all", user = access.user.id);
+
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to activate deny-all policy".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Find the well-known version, if it has been added before and is still usable
+            let version: Option<u64> = match find_deny_all(&mut conn).await {
+                Ok(version) => version,
+                Err(msg) => return this.wire.error_response(ApiErrorCode::Internal, msg),
+            };
+            let version: u64 = match version {
+                Some(version) => version,
+                None => match conn.add_version(D::Content::metadata(), D::Content::deny_all()).await {
+                    Ok(version) => {
+                        info!("Added deny-all policy as version {version}");
+                        version
+                    },
+                    Err(err) => {
+                        let msg: String = "Failed to add deny-all policy".to_string();
+                        error!("{}", trace!(("{msg}"), err));
+                        return this.wire.error_response(ApiErrorCode::Internal, msg);
+                    },
+                },
+            };
+
+            // Then activate it
+            if let Err(err) = conn.activate(version).await {
+                let msg: String = format!("Failed to activate deny-all policy {version}");
+                error!("{}", trace!(("{msg}"), err));
+                return this.wire.error_response(ApiErrorCode::Internal, msg);
+            };
+            warn!("Activated deny-all policy {version}; the reasoner now denies all requests");
+            this.wire.response(StatusCode::OK, &DenyAllResponse { version })
+        }
+    }
+
     /// Handler for `GET /v2/policies` (i.e., listing all policy).
     ///
     /// Out:
@@ -233,18 +698,18 @@ where
     /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
     pub fn get_versions(
         State(this): State<Arc<Self>>,
-        Extension(auth): Extension<User>,
-    ) -> impl 'static + Send + Future<Output = (StatusCode, String)> {
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
         async move {
-            let _span = span!(Level::INFO, "AxumServer::get_versions", user = auth.id);
+            let _span = span!(Level::INFO, "AxumServer::get_versions", user = access.user.id);
 
             // Just try to send it to the DB
-            let mut conn: D::Connection<'_> = match this.data.connect(&auth).await {
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
                 Ok(conn) => conn,
                 Err(err) => {
                     let msg: String = "Failed to deactivate any active policy".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
             let versions: HashMap<u64, Metadata> = match conn.get_versions().await {
@@ -252,103 +717,223 @@ where
                 Err(err) => {
                     let msg: String = "Failed to deactivate any active policy".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
 
             // Serialize the result
-            match serde_json::to_string(&GetVersionsResponse { versions }) {
-                Ok(versions) => (StatusCode::OK, versions),
-                Err(err) => {
-                    let msg: String = "Failed to serialize result".to_string();
-                    error!("{}", trace!(("{msg}"), err));
-                    (StatusCode::INTERNAL_SERVER_ERROR, msg)
-                },
-            }
+            this.wire.response(StatusCode::OK, &GetVersionsResponse { versions })
         }
     }
 
     /// Handler for `GET /v2/policies/active` (i.e., get active policy).
     ///
+    /// In:
+    /// - An optional `at` query parameter with an RFC 3339 timestamp, to get the policy that was
+    ///   active at that time instead.
+    /// - An optional `If-None-Match` header with the `ETag` of an earlier reply.
+    ///
     /// Out:
-    /// - 200 OK with a [`GetActiveVersionResponse`] describing the version; or
+    /// - 200 OK with a [`GetActiveVersionResponse`] describing the version and an `ETag` header;
+    /// - 304 NOT MODIFIED if the `If-None-Match` header matches the current version; or
     /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
     pub fn get_active_version(
         State(this): State<Arc<Self>>,
-        Extension(auth): Extension<User>,
-    ) -> impl 'static + Send + Future<Output = (StatusCode, String)> {
+        Extension(access): Extension<AccessContext>,
+        Query(query): Query<GetActiveVersionQuery>,
+        headers: HeaderMap,
+    ) -> impl 'static + Send + Future<Output = Response> {
         async move {
-            let _span = span!(Level::INFO, "AxumServer::get_active_version", user = auth.id);
+            let _span = span!(Level::INFO, "AxumServer::get_active_version", user = access.user.id);
 
             // Just try to send it to the DB
-            let mut conn: D::Connection<'_> = match this.data.connect(&auth).await {
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
                 Ok(conn) => conn,
                 Err(err) => {
                     let msg: String = "Failed to get active policy".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
-            let version: Option<u64> = match conn.get_active_version().await {
+            let version: Result<Option<u64>, _> = match query.at {
+                Some(at) => conn.get_active_version_at(at).await,
+                None => conn.get_active_version().await,
+            };
+            let version: Option<u64> = match version {
                 Ok(version) => version,
                 Err(err) => {
                     let msg: String = "Failed to get active policy".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
 
-            // Serialize the result
-            match serde_json::to_string(&GetActiveVersionResponse { version }) {
-                Ok(res) => (StatusCode::OK, res),
+            // Serialize the result, unless the client already has it
+            let etag: String = active_version_etag(version);
+            if etag_matches(&headers, &etag) {
+                return (StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response();
+            }
+            let mut res: Response = this.wire.response(StatusCode::OK, &GetActiveVersionResponse { version });
+            // SAFETY: Quoted numbers are always valid header values
+            res.headers_mut().insert(ETAG, etag.parse().unwrap());
+            res
+        }
+    }
+
+    /// Handler for `GET /v2/policies/active/content` (i.e., get active content).
+    ///
+    /// What is returned while no policy is active depends on the server's
+    /// [`NoActivePolicy`](AxumServer::with_no_active_policy()).
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetActiveContentResponse<D::Content>`](GetActiveContentResponse)
+    ///   describing the active version and its content, or the fallback content;
+    /// - 403 FORBIDDEN if that version is [confidential](AxumServer::with_confidential_roles())
+    ///   and the user may not read it;
+    /// - 404 NOT FOUND if no policy is active and there is no fallback; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_active_content(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_active_content", user = access.user.id);
+
+            // Find out which version to return, if any
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
                 Err(err) => {
-                    let msg: String = "Failed to serialize result".to_string();
+                    let msg: String = "Failed to get active policy content".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let active: Option<u64> = match conn.get_active_version().await {
+                Ok(active) => active,
+                Err(err) => {
+                    let msg: String = "Failed to get active policy".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let (version, fallback): (u64, bool) = match (active, this.no_active) {
+                (Some(version), _) => (version, false),
+                (None, NoActivePolicy::NotFound) => return this.wire.error_response(ApiErrorCode::NotFound, "No policy is active".into()),
+                (None, NoActivePolicy::Fallback(version)) => (version, true),
+                #[cfg(feature = "deny-all")]
+                (None, NoActivePolicy::DenyAll) => {
+                    return this.wire.response(StatusCode::OK, &GetActiveContentResponse {
+                        version:  None,
+                        fallback: true,
+                        content:  D::Content::deny_all(),
+                    });
+                },
+            };
+
+            // Then get its content, if the user may see it
+            match conn.get_version_metadata(version).await {
+                Ok(Some(metadata)) if !may_read_content(&metadata, &access, this.confidential_roles.iter().chain(&this.admin_roles)) => {
+                    info!("Refusing to reveal content of confidential policy {version} to user {:?}", access.user.id);
+                    return this.wire.error_response(ApiErrorCode::Forbidden, format!("Content of policy version {version} is confidential"));
+                },
+                Ok(_) => {},
+                Err(err) => {
+                    let msg: String = format!("Failed to get metadata of policy {version}");
                     error!("{}", trace!(("{msg}"), err));
-                    (StatusCode::INTERNAL_SERVER_ERROR, msg)
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             }
+            let content: D::Content = match conn.get_version_content(version).await {
+                Ok(Some(content)) => content,
+                Ok(None) => {
+                    // Both the active and the fallback version should always exist
+                    let msg: String = format!("Policy version {version} does not exist");
+                    error!("{msg}");
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+                Err(err) => {
+                    let msg: String = "Failed to get active policy content".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetActiveContentResponse { version: Some(version), fallback, content })
         }
     }
 
     /// Handler for `GET /v2/policies/active/activator` (i.e., get activator).
     ///
     /// Out:
-    /// - 200 OK with a [`GetActivatorResponse`] describing the version; or
+    /// - 200 OK with a [`GetActivatorResponse`] describing who activated the version and when; or
     /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
     pub fn get_activator(
         State(this): State<Arc<Self>>,
-        Extension(auth): Extension<User>,
-    ) -> impl 'static + Send + Future<Output = (StatusCode, String)> {
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
         async move {
-            let _span = span!(Level::INFO, "AxumServer::get_activator", user = auth.id);
+            let _span = span!(Level::INFO, "AxumServer::get_activator", user = access.user.id);
 
             // Just try to send it to the DB
-            let mut conn: D::Connection<'_> = match this.data.connect(&auth).await {
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
                 Ok(conn) => conn,
                 Err(err) => {
                     let msg: String = "Failed to get activator".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
-            let user: Option<User> = match conn.get_activator().await {
-                Ok(user) => user,
+            let activation: Option<Activation> = match conn.get_activation().await {
+                Ok(activation) => activation,
                 Err(err) => {
                     let msg: String = "Failed to get activator".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
 
             // Serialize the result
-            match serde_json::to_string(&GetActivatorResponse { user }) {
-                Ok(versions) => (StatusCode::OK, versions),
+            let (user, activated): (Option<User>, Option<DateTime<Utc>>) = match activation {
+                Some(activation) => (Some(activation.activator), Some(activation.activated)),
+                None => (None, None),
+            };
+            this.wire.response(StatusCode::OK, &GetActivatorResponse { user, activated })
+        }
+    }
+
+    /// Handler for `GET /v2/policies/active/history` (i.e., get the activation history).
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetActivationsResponse`] describing when which version was active; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_activations(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_activations", user = access.user.id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
                 Err(err) => {
-                    let msg: String = "Failed to serialize result".to_string();
+                    let msg: String = "Failed to get activation history".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    (StatusCode::INTERNAL_SERVER_ERROR, msg)
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
-            }
+            };
+            let activations: Vec<Activation> = match conn.get_activations().await {
+                Ok(activations) => activations,
+                Err(err) => {
+                    let msg: String = "Failed to get activation history".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetActivationsResponse { activations })
         }
     }
 
@@ -360,42 +945,35 @@ where
     /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
     pub fn get_version_metadata(
         State(this): State<Arc<Self>>,
-        Extension(auth): Extension<User>,
+        Extension(access): Extension<AccessContext>,
         Path(version): Path<u64>,
-    ) -> impl 'static + Send + Future<Output = (StatusCode, String)> {
+    ) -> impl 'static + Send + Future<Output = Response> {
         async move {
-            let _span = span!(Level::INFO, "AxumServer::get_version_metadata", user = auth.id);
+            let _span = span!(Level::INFO, "AxumServer::get_version_metadata", user = access.user.id);
 
             // Just try to send it to the DB
-            let mut conn: D::Connection<'_> = match this.data.connect(&auth).await {
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
                 Ok(conn) => conn,
                 Err(err) => {
                     let msg: String = "Failed to get policy metadata".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
             let metadata: Metadata = match conn.get_version_metadata(version).await {
                 Ok(Some(metadata)) => metadata,
                 Ok(None) => {
-                    return (StatusCode::NOT_FOUND, String::new());
+                    return this.wire.error_response(ApiErrorCode::VersionNotFound, format!("Unknown policy version {version}"));
                 },
                 Err(err) => {
                     let msg: String = "Failed to get policy metadata".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
 
             // Serialize the result
-            match serde_json::to_string(&GetVersionMetadataResponse { metadata }) {
-                Ok(versions) => (StatusCode::OK, versions),
-                Err(err) => {
-                    let msg: String = "Failed to serialize result".to_string();
-                    error!("{}", trace!(("{msg}"), err));
-                    (StatusCode::INTERNAL_SERVER_ERROR, msg)
-                },
-            }
+            this.wire.response(StatusCode::OK, &GetVersionMetadataResponse { metadata })
         }
     }
 
@@ -404,46 +982,1859 @@ where
     /// Out:
     /// - 200 OK with a [`GetVersionContentResponse<D::Content>`](GetVersionContentResponse)
     ///   describing the version's content;
+    /// - 403 FORBIDDEN if the version is [confidential](AxumServer::with_confidential_roles())
+    ///   and the user may not read it;
     /// - 404 NOT FOUND if there was no policy with version `:version`; or
     /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
     pub fn get_version_content(
         State(this): State<Arc<Self>>,
-        Extension(auth): Extension<User>,
+        Extension(access): Extension<AccessContext>,
         Path(version): Path<u64>,
-    ) -> impl 'static + Send + Future<Output = (StatusCode, String)> {
+    ) -> impl 'static + Send + Future<Output = Response> {
         async move {
-            let _span = span!(Level::INFO, "AxumServer::get_version_content", user = auth.id);
+            let _span = span!(Level::INFO, "AxumServer::get_version_content", user = access.user.id);
 
             // Just try to send it to the DB
-            let mut conn: D::Connection<'_> = match this.data.connect(&auth).await {
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
                 Ok(conn) => conn,
                 Err(err) => {
                     let msg: String = "Failed to get policy content".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
+            // Make sure the user may see it
+            match conn.get_version_metadata(version).await {
+                Ok(Some(metadata)) if !may_read_content(&metadata, &access, this.confidential_roles.iter().chain(&this.admin_roles)) => {
+                    info!("Refusing to reveal content of confidential policy {version} to user {:?}", access.user.id);
+                    return this.wire.error_response(ApiErrorCode::Forbidden, format!("Content of policy version {version} is confidential"));
+                },
+                Ok(_) => {},
+                Err(err) => {
+                    let msg: String = format!("Failed to get metadata of policy {version}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            }
             let content: D::Content = match conn.get_version_content(version).await {
                 Ok(Some(content)) => content,
                 Ok(None) => {
-                    return (StatusCode::NOT_FOUND, String::new());
+                    return this.wire.error_response(ApiErrorCode::VersionNotFound, format!("Unknown policy version {version}"));
                 },
                 Err(err) => {
                     let msg: String = "Failed to get policy content".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    return (StatusCode::INTERNAL_SERVER_ERROR, msg);
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
             };
 
             // Serialize the result
-            match serde_json::to_string(&GetVersionContentResponse { content }) {
-                Ok(content) => (StatusCode::OK, content),
+            this.wire.response(StatusCode::OK, &GetVersionContentResponse { content })
+        }
+    }
+    /// Handler for `GET /v2/policy/:version/content/raw` (i.e., get version content as-is).
+    ///
+    /// In:
+    /// - An optional `Range` header with a single byte range to download.
+    ///
+    /// Out:
+    /// - 200 OK with the version's content encoded in the server's [`WireFormat`];
+    /// - 206 PARTIAL CONTENT with the requested range of the version's content, encoded likewise;
+    /// - 403 FORBIDDEN if the version is [confidential](AxumServer::with_confidential_roles())
+    ///   and the user may not read it;
+    /// - 404 NOT FOUND if there was no policy with version `:version`;
+    /// - 416 RANGE NOT SATISFIABLE if the requested range lies outside of the content; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_version_content_raw(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(version): Path<u64>,
+        headers: HeaderMap,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_version_content_raw", user = access.user.id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
                 Err(err) => {
-                    let msg: String = "Failed to serialize result".to_string();
+                    let msg: String = "Failed to get policy content".to_string();
                     error!("{}", trace!(("{msg}"), err));
-                    (StatusCode::INTERNAL_SERVER_ERROR, msg)
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
                 },
-            }
+            };
+            // Make sure the user may see it
+            match conn.get_version_metadata(version).await {
+                Ok(Some(metadata)) if !may_read_content(&metadata, &access, this.confidential_roles.iter().chain(&this.admin_roles)) => {
+                    info!("Refusing to reveal content of confidential policy {version} to user {:?}", access.user.id);
+                    return this.wire.error_response(ApiErrorCode::Forbidden, format!("Content of policy version {version} is confidential"));
+                },
+                Ok(_) => {},
+                Err(err) => {
+                    let msg: String = format!("Failed to get metadata of policy {version}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            }
+            let content: D::Content = match conn.get_version_content(version).await {
+                Ok(Some(content)) => content,
+                Ok(None) => {
+                    return this.wire.error_response(ApiErrorCode::VersionNotFound, format!("Unknown policy version {version}"));
+                },
+                Err(err) => {
+                    let msg: String = "Failed to get policy content".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            let content: Vec<u8> = match this.wire.serialize(&content) {
+                Ok(content) => content,
+                Err(err) => {
+                    let msg: String = "Failed to serialize result".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Return the (requested part of the) content
+            let len: u64 = content.len() as u64;
+            match headers.get(RANGE).and_then(|range| range.to_str().ok()).and_then(|range| parse_range(range, len)) {
+                Some(Ok((start, end))) => (
+                    StatusCode::PARTIAL_CONTENT,
+                    [
+                        (ACCEPT_RANGES, "bytes".to_string()),
+                        (CONTENT_TYPE, this.wire.content_type().into()),
+                        (CONTENT_RANGE, format!("bytes {start}-{end}/{len}")),
+                    ],
+                    content[start as usize..=end as usize].to_vec(),
+                )
+                    .into_response(),
+                Some(Err(())) => {
+                    let mut res: Response = this
+                        .wire
+                        .error_response(ApiErrorCode::RangeNotSatisfiable, format!("Requested range lies outside of the {len} bytes of content"));
+                    res.headers_mut().insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+                    // SAFETY: Formatted numbers are always valid header values
+                    res.headers_mut().insert(CONTENT_RANGE, HeaderValue::from_str(&format!("bytes */{len}")).unwrap());
+                    res
+                },
+                None => (StatusCode::OK, [(ACCEPT_RANGES, "bytes"), (CONTENT_TYPE, this.wire.content_type())], content).into_response(),
+            }
+        }
+    }
+
+    /// Handler for `GET /v2/policy/:version/provenance` (i.e., get version provenance).
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetVersionProvenanceResponse`] carrying the version's in-toto statement;
+    /// - 404 NOT FOUND if there was no policy with version `:version`; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_version_provenance(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(version): Path<u64>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_version_provenance", user = access.user.id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get policy provenance".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let provenance: Provenance = match conn.get_version_provenance(version).await {
+                Ok(Some(provenance)) => provenance,
+                Ok(None) => {
+                    return this.wire.error_response(ApiErrorCode::VersionNotFound, format!("Unknown policy version {version}"));
+                },
+                Err(err) => {
+                    let msg: String = "Failed to get policy provenance".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetVersionProvenanceResponse { provenance })
+        }
+    }
+
+
+
+    /// Handler for `POST /v2/policies/:version/freeze` (i.e., freezing a version).
+    ///
+    /// Frozen versions cannot be activated or promoted until they are
+    /// [unfrozen](AxumServer::unfreeze_version()). Note that this does not deactivate the version
+    /// if it is active.
+    ///
+    /// Only users with one of the [admin roles](AxumServer::with_admin_roles()) may call this.
+    ///
+    /// In:
+    /// - [`FreezeVersionRequest`].
+    ///
+    /// Out:
+    /// - 200 OK;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 403 FORBIDDEN if the user has none of the admin roles;
+    /// - 404 NOT FOUND if there was no policy with version `:version`;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn freeze_version(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(version): Path<u64>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::freeze_version", user = access.user.id, version = version);
+
+            // Get the request
+            let req: FreezeVersionRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to freeze policy {version}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            match conn.freeze_version(version, &req.reason).await {
+                Ok(Ok(())) => {
+                    info!("User {:?} froze policy {version} ({:?})", access.user.id, req.reason);
+                    StatusCode::OK.into_response()
+                },
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to freeze policy {version}"), err));
+                    this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err)
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to freeze policy {version}");
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+    /// Handler for `DELETE /v2/policies/:version/freeze` (i.e., unfreezing a version).
+    ///
+    /// Only users with one of the [admin roles](AxumServer::with_admin_roles()) may call this.
+    ///
+    /// Out:
+    /// - 200 OK if the version was unfrozen (or wasn't frozen);
+    /// - 403 FORBIDDEN if the user has none of the admin roles;
+    /// - 404 NOT FOUND if there was no policy with version `:version`; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn unfreeze_version(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(version): Path<u64>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::unfreeze_version", user = access.user.id, version = version);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to unfreeze policy {version}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            match conn.unfreeze_version(version).await {
+                Ok(Ok(())) => StatusCode::OK.into_response(),
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to unfreeze policy {version}"), err));
+                    this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err)
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to unfreeze policy {version}");
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+
+
+    /// Handler for `POST /v2/templates` (i.e., registering a new template).
+    ///
+    /// Only users with one of the [admin roles](AxumServer::with_admin_roles()) may call this.
+    ///
+    /// In:
+    /// - [`AddTemplateRequest`].
+    ///
+    /// Out:
+    /// - 200 OK;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 403 FORBIDDEN if the user has none of the admin roles;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn add_template(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::add_template", user = access.user.id);
+
+            // Get the request
+            let req: AddTemplateRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to add template {}", req.template.name);
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let name: String = req.template.name.clone();
+            if let Err(err) = conn.add_template(req.template).await {
+                let msg: String = format!("Failed to add template {name}");
+                error!("{}", trace!(("{msg}"), err));
+                return this.wire.error_response(ApiErrorCode::Internal, msg);
+            }
+
+            // Done
+            StatusCode::OK.into_response()
+        }
+    }
+
+    /// Handler for `GET /v2/templates` (i.e., listing all templates).
+    ///
+    /// Out:
+    /// - 200 OK with an [`GetTemplatesResponse`] mapping template names to [`Template`]s; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_templates(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_templates", user = access.user.id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get templates".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let templates: HashMap<String, Template> = match conn.get_templates().await {
+                Ok(templates) => templates,
+                Err(err) => {
+                    let msg: String = "Failed to get templates".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetTemplatesResponse { templates })
+        }
+    }
+
+    /// Handler for `POST /v2/policies/from-template/:name` (i.e., adding a new policy from a
+    /// template).
+    ///
+    /// In:
+    /// - [`InstantiateTemplateRequest`].
+    ///
+    /// Out:
+    /// - 200 OK with an [`AddVersionResponse`] detailling the version number of the new policy;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request or instantiate the
+    ///   template;
+    /// - 404 NOT FOUND if there was no template with name `:name`;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn instantiate_template(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(name): Path<String>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::instantiate_template", user = access.user.id, template = name);
+
+            // Get the request
+            let req: InstantiateTemplateRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Find the template
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to instantiate template {name}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let template: Template = match conn.get_template(&name).await {
+                Ok(Some(template)) => template,
+                Ok(None) => {
+                    return this.wire.error_response(ApiErrorCode::NotFound, format!("Unknown template {name:?}"));
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to get template {name}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Instantiate it as the content type of the store
+            let content: D::Content = match template.instantiate(&req.values) {
+                Ok(content) => match serde_json::from_value(content) {
+                    Ok(content) => content,
+                    Err(err) => {
+                        let msg: String = trace!(("Instantiated template {name} is not a valid policy"), err).to_string();
+                        info!("{msg}");
+                        return this.wire.error_response(ApiErrorCode::ValidationFailed, msg);
+                    },
+                },
+                Err(err) => {
+                    let msg: String = trace!(("Failed to instantiate template {name}"), err).to_string();
+                    info!("{msg}");
+                    return this.wire.error_response(ApiErrorCode::ValidationFailed, msg);
+                },
+            };
+
+            // Run it through the transformers
+            let metadata = AttachedMetadata {
+                name: req.name,
+                description: req.description,
+                language: template.attached.language,
+                branch: req.branch,
+                schema: req.schema,
+                confidential: false,
+            };
+            let content: D::Content = match this.transformer.transform(&metadata, content) {
+                Ok(content) => content,
+                Err(err) => {
+                    let msg: String = trace!(("Failed to transform instantiated template {name}"), err).to_string();
+                    info!("{msg}");
+                    return this.wire.error_response(ApiErrorCode::ValidationFailed, msg);
+                },
+            };
+
+            // Store it as a new version
+            let content: D::Content = match validate_content(&mut conn, &metadata, content).await {
+                Ok(content) => content,
+                Err((code, msg)) => return this.wire.error_response(code, msg),
+            };
+            let version: u64 = match conn.add_version(metadata, content).await {
+                Ok(res) => res,
+                Err(err) => {
+                    let msg: String = format!("Failed to add policy from template {name}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Return the version
+            this.wire.response(StatusCode::OK, &AddVersionResponse { version })
+        }
+    }
+
+
+
+    /// Handler for `POST /v2/schemas` (i.e., registering a new content schema).
+    ///
+    /// In:
+    /// - [`AddSchemaRequest`].
+    ///
+    /// Out:
+    /// - 200 OK;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request, or if the schema is
+    ///   not a valid JSON schema;
+    /// - 409 CONFLICT if a schema with the same name already exists;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn add_schema(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::add_schema", user = access.user.id);
+
+            // Get the request
+            let req: AddSchemaRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+            if let Err(err) = req.schema.check() {
+                let msg: String = err.to_string();
+                info!("{msg}");
+                return this.wire.error_response(ApiErrorCode::ValidationFailed, msg);
+            }
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to add schema {}", req.schema.name);
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let name: String = req.schema.name.clone();
+            match conn.add_schema(req.schema).await {
+                Ok(Ok(())) => StatusCode::OK.into_response(),
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to add schema {name}"), err));
+                    this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err)
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to add schema {name}");
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+    /// Handler for `GET /v2/schemas` (i.e., listing all content schemas).
+    ///
+    /// Out:
+    /// - 200 OK with an [`GetSchemasResponse`] mapping schema names to [`Schema`]s; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_schemas(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_schemas", user = access.user.id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get schemas".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let schemas: HashMap<String, Schema> = match conn.get_schemas().await {
+                Ok(schemas) => schemas,
+                Err(err) => {
+                    let msg: String = "Failed to get schemas".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetSchemasResponse { schemas })
+        }
+    }
+
+
+
+    /// Handler for `POST /v2/branches` (i.e., forking a new branch).
+    ///
+    /// In:
+    /// - [`CreateBranchRequest`].
+    ///
+    /// Out:
+    /// - 200 OK;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 404 NOT FOUND if the version to fork from does not exist;
+    /// - 409 CONFLICT if a branch with the given name already exists;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn create_branch(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::create_branch", user = access.user.id);
+
+            // Get the request
+            let req: CreateBranchRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to create branch {}", req.name);
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            match conn.create_branch(&req.name, req.from).await {
+                Ok(Ok(())) => StatusCode::OK.into_response(),
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to create branch {}", req.name), err));
+                    this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err)
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to create branch {}", req.name);
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+    /// Handler for `GET /v2/branches` (i.e., listing all branches).
+    ///
+    /// Out:
+    /// - 200 OK with an [`GetBranchesResponse`] mapping branch names to [`Branch`]es; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_branches(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_branches", user = access.user.id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get branches".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let branches: HashMap<String, Branch> = match conn.get_branches().await {
+                Ok(branches) => branches,
+                Err(err) => {
+                    let msg: String = "Failed to get branches".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetBranchesResponse { branches })
+        }
+    }
+
+    /// Handler for `POST /v2/branches/:name/promote` (i.e., promoting a version into a branch).
+    ///
+    /// In:
+    /// - [`PromoteRequest`].
+    ///
+    /// Out:
+    /// - 200 OK with an [`AddVersionResponse`] detailling the version number of the promoted
+    ///   copy;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request, or if the version is
+    ///   already on branch `:name`;
+    /// - 404 NOT FOUND if the version or branch `:name` does not exist;
+    /// - 409 CONFLICT if the version is [frozen](AxumServer::freeze_version()), or if both branches
+    ///   have advanced since they last met and the promotion isn't forced;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn promote(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(name): Path<String>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::promote", user = access.user.id, branch = name);
+
+            // Get the request
+            let req: PromoteRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to promote policy {} into branch {name}", req.version);
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let version: u64 = match conn.promote(req.version, &name, req.force).await {
+                Ok(Ok(version)) => version,
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to promote policy {} into branch {name}", req.version), err));
+                    return this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err);
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to promote policy {} into branch {name}", req.version);
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Return the version
+            this.wire.response(StatusCode::OK, &AddVersionResponse { version })
+        }
+    }
+
+    /// Handler for `POST /v2/policies/import` (i.e., importing a version from another store).
+    ///
+    /// Unlike when [adding](AxumServer::add_version()) a version, the contents are not run
+    /// through the [`ContentTransformer`], as they must stay exactly as they were in the other
+    /// store.
+    ///
+    /// Any [verification](Metadata::verification) in the request is ignored. Instead, if
+    /// [enabled](AxumServer::with_cosign_verification()), the signature sent along is verified
+    /// and the result recorded.
+    ///
+    /// If `?dry_run=true` is given, the version is validated as usual, but nothing is written.
+    /// Instead, the reply describes where the copy would end up and which versions in this store
+    /// already are copies of the same original.
+    ///
+    /// In:
+    /// - An optional [`ImportVersionQuery`]; and
+    /// - [`ImportVersionRequest<D::Content>`](ImportVersionRequest).
+    ///
+    /// Out:
+    /// - 200 OK with an [`AddVersionResponse`] detailling the version number of the imported
+    ///   copy, or an [`ImportDryRunResponse`] describing what importing it would change;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request, if the contents
+    ///   don't match their schema, if they don't match the hash they were recorded with, or if
+    ///   their signature did not verify in strict mode;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn import_version(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Query(query): Query<ImportVersionQuery>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::import_version", user = access.user.id, dry_run = query.dry_run);
+
+            // Get the request
+            let mut req: ImportVersionRequest<D::Content> = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+            // NOTE: Only we get to say what's verified
+            req.metadata.verification = None;
+
+            // Just try to send it to the DB
+            let (store, source): (String, u64) = (req.store, req.metadata.version);
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to import policy {source} from store {store:?}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let contents: D::Content = match validate_content(&mut conn, &req.metadata.attached, req.contents).await {
+                Ok(contents) => contents,
+                Err((code, msg)) => return this.wire.error_response(code, msg),
+            };
+            #[cfg(feature = "cosign")]
+            if let Some(verifier) = &this.cosign {
+                let canonical: String = match to_canonical_string(&contents) {
+                    Ok(canonical) => canonical,
+                    Err(err) => {
+                        let msg: String = format!("Failed to serialize content of policy {source} from store {store:?}");
+                        error!("{}", trace!(("{msg}"), err));
+                        return this.wire.error_response(ApiErrorCode::Internal, msg);
+                    },
+                };
+                match verifier.verify(&canonical, req.signature.as_ref()) {
+                    Ok(verification) => req.metadata.verification = Some(verification),
+                    Err(err) if verifier.is_strict() => {
+                        let msg: String = format!("Signature of policy {source} from store {store:?} did not verify");
+                        info!("{}", trace!(("{msg}"), err));
+                        return this.wire.error_response(ApiErrorCode::ValidationFailed, format!("{msg}: {err}"));
+                    },
+                    Err(err) => warn!("{}", trace!(("Importing policy {source} from store {store:?} without verification"), err)),
+                }
+            }
+
+            // Only report what would change, if asked to
+            if query.dry_run {
+                return match preview_import(&mut conn, &store, req.metadata, contents).await {
+                    Ok(Ok(res)) => this.wire.response(StatusCode::OK, &res),
+                    Ok(Err(err)) => {
+                        info!("{}", trace!(("Dry run of importing policy {source} from store {store:?} failed"), err));
+                        this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err)
+                    },
+                    Err(msg) => this.wire.error_response(ApiErrorCode::Internal, msg),
+                };
+            }
+            let version: u64 = match conn.import_version(&store, req.metadata, contents).await {
+                Ok(Ok(version)) => version,
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to import policy {source} from store {store:?}"), err));
+                    return this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err);
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to import policy {source} from store {store:?}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Return the version
+            this.wire.response(StatusCode::OK, &AddVersionResponse { version })
+        }
+    }
+
+
+
+    /// Handler for `POST /v2/users/:id/pseudonymize` (i.e., replacing every reference to a user
+    /// with a pseudonym).
+    ///
+    /// The pseudonym is [derived](AxumServer::with_pseudonym_key()) from the user's ID, so the
+    /// same user always gets the same one.
+    ///
+    /// Note that this rewrites the audit log, so any previously published heads of it no longer
+    /// match.
+    ///
+    /// Only users with one of the [admin roles](AxumServer::with_admin_roles()) may call this.
+    ///
+    /// Out:
+    /// - 200 OK with a [`PseudonymizeUserResponse`] detailling the pseudonym used;
+    /// - 403 FORBIDDEN if the user has none of the admin roles;
+    /// - 404 NOT FOUND if user `:id` does not appear anywhere in the store; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn pseudonymize_user(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(id): Path<String>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let pseudonym: String = this.pseudonyms.pseudonym(&id);
+            // NOTE: Deliberately not logging the ID itself, as that's what we're trying to get rid of
+            let _span = span!(Level::INFO, "AxumServer::pseudonymize_user", user = access.user.id, pseudonym = pseudonym);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to pseudonymize user".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let rows: u64 = match conn.pseudonymize_user(&id, &pseudonym).await {
+                Ok(Ok(rows)) => rows,
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to pseudonymize user"), err));
+                    return this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err);
+                },
+                Err(err) => {
+                    let msg: String = "Failed to pseudonymize user".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Return the pseudonym
+            this.wire.response(StatusCode::OK, &PseudonymizeUserResponse { pseudonym, rows })
+        }
+    }
+
+    /// Handler for `POST /v2/maintenance/run` (i.e., running maintenance on the backend database).
+    ///
+    /// Note that this may take a while, and may block other requests while it runs. Only users with
+    /// one of the [admin roles](AxumServer::with_admin_roles()) may call this.
+    ///
+    /// Out:
+    /// - 200 OK if maintenance completed;
+    /// - 403 FORBIDDEN if the user has none of the admin roles; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn maintain(State(this): State<Arc<Self>>, Extension(access): Extension<AccessContext>) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::maintain", user = access.user.id);
+
+            // Just try to send it to the DB
+            match this.data.maintain().await {
+                Ok(_) => StatusCode::OK.into_response(),
+                Err(err) => {
+                    let msg: String = "Failed to run database maintenance".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+    /// Handler for `POST /v2/maintenance/prune-activations` (i.e., removing historical activation
+    /// records).
+    ///
+    /// Only users with one of the [admin roles](AxumServer::with_admin_roles()) may call this.
+    ///
+    /// In:
+    /// - [`PruneActivationsRequest`].
+    ///
+    /// Out:
+    /// - 200 OK with a [`PruneActivationsResponse`] detailling how many records were removed;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 403 FORBIDDEN if the user has none of the admin roles;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn prune_activations(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::prune_activations", user = access.user.id);
+
+            // Get the request
+            let req: PruneActivationsRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to prune activations".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let rows: u64 = match conn.prune_activations(req.before).await {
+                Ok(rows) => rows,
+                Err(err) => {
+                    let msg: String = "Failed to prune activations".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Return the number of rows
+            this.wire.response(StatusCode::OK, &PruneActivationsResponse { rows })
+        }
+    }
+
+
+
+    /// Handler for `POST /v2/uploads` (i.e., initiating a chunked upload).
+    ///
+    /// In:
+    /// - [`InitiateUploadRequest`].
+    ///
+    /// Out:
+    /// - 200 OK with an [`InitiateUploadResponse`] detailling the ID of the new upload;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 409 CONFLICT if the user already has the [maximum](AxumServer::with_max_uploads_per_user())
+    ///   number of uploads in progress;
+    /// - 413 PAYLOAD TOO LARGE if the declared length exceeds the maximum upload size; or
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`].
+    pub fn initiate_upload(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::initiate_upload", user = access.user.id);
+
+            // Get the request
+            let req: InitiateUploadRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Register it
+            match this.uploads.initiate(access.user.id, req.metadata, req.length) {
+                Ok(id) => this.wire.response(StatusCode::OK, &InitiateUploadResponse { id }),
+                Err((code, msg)) => this.wire.error_response(code, msg),
+            }
+        }
+    }
+
+    /// Handler for `PATCH /v2/uploads/:id` (i.e., appending a chunk to an upload).
+    ///
+    /// In:
+    /// - The raw bytes of the chunk, with the [`UPLOAD_OFFSET_HEADER`] set to where it starts.
+    ///
+    /// Out:
+    /// - 200 OK with an [`UploadStatusResponse`] detailling the new offset;
+    /// - 400 BAD REQUEST if the offset header is missing or invalid;
+    /// - 404 NOT FOUND if no upload `:id` exists (for this user);
+    /// - 409 CONFLICT if the offset doesn't match that of the upload;
+    /// - 413 PAYLOAD TOO LARGE if the chunk exceeds the upload's length, the maximum upload size
+    ///   or the maximum size of all uploads in progress together; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn upload_chunk(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(id): Path<u64>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::upload_chunk", user = access.user.id, upload = id);
+
+            // Get the offset and the chunk
+            let offset: u64 = match request.headers().get(UPLOAD_OFFSET_HEADER).map(|value| value.to_str().map(str::parse::<u64>)) {
+                Some(Ok(Ok(offset))) => offset,
+                Some(_) => return this.wire.error_response(ApiErrorCode::BadRequest, format!("Invalid {UPLOAD_OFFSET_HEADER} header")),
+                None => return this.wire.error_response(ApiErrorCode::BadRequest, format!("Missing {UPLOAD_OFFSET_HEADER} header")),
+            };
+            // NOTE: Bounded, as we don't want to buffer chunks that don't fit in the upload anyway
+            let chunk: Vec<u8> = match download_body(request, Some(this.uploads.max_upload_size.saturating_sub(offset))).await {
+                Ok(chunk) => chunk,
+                Err((code, msg)) => return this.wire.error_response(code, msg),
+            };
+
+            // Append it and return the status
+            match this.uploads.append(id, &access.user.id, offset, chunk) {
+                Ok((offset, length)) => this.wire.response(StatusCode::OK, &UploadStatusResponse { offset, length }),
+                Err((code, msg)) => this.wire.error_response(code, msg),
+            }
+        }
+    }
+
+    /// Handler for `GET /v2/uploads/:id` (i.e., retrieving the status of an upload).
+    ///
+    /// Out:
+    /// - 200 OK with an [`UploadStatusResponse`] detailling where to resume the upload; or
+    /// - 404 NOT FOUND if no upload `:id` exists (for this user).
+    pub fn get_upload(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(id): Path<u64>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_upload", user = access.user.id, upload = id);
+
+            let uploads = this.uploads.lock();
+            match uploads.get(&id) {
+                Some(upload) if upload.owner == access.user.id => {
+                    this.wire.response(StatusCode::OK, &UploadStatusResponse { offset: upload.data.len() as u64, length: upload.length })
+                },
+                _ => this.wire.error_response(ApiErrorCode::UploadNotFound, format!("Unknown upload {id}")),
+            }
+        }
+    }
+
+    /// Handler for `POST /v2/uploads/:id/finalize` (i.e., turning an upload into a new version).
+    ///
+    /// If finalizing fails, the upload is left as it was, such that it can be finalized again. The
+    /// same goes if the client gives up halfway, although the new version may then have been
+    /// added already.
+    ///
+    /// Out:
+    /// - 200 OK with an [`AddVersionResponse`] detailling the version number of the new policy;
+    /// - 400 BAD REQUEST if the uploaded contents are incomplete or not a valid policy;
+    /// - 404 NOT FOUND if no upload `:id` exists (for this user); or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn finalize_upload(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(id): Path<u64>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::finalize_upload", user = access.user.id, upload = id);
+
+            // Take the upload out of the list (it's put back if we return early)
+            let upload: TakenUpload = match this.uploads.take(id, &access.user.id) {
+                Some(upload) => upload,
+                None => return this.wire.error_response(ApiErrorCode::UploadNotFound, format!("Unknown upload {id}")),
+            };
+            if let Some(length) = upload.length {
+                if upload.data.len() as u64 != length {
+                    return this.wire.error_response(
+                        ApiErrorCode::BadRequest,
+                        format!("Upload is incomplete (received {} out of {length} bytes)", upload.data.len()),
+                    );
+                }
+            }
+
+            // Parse the contents
+            let contents: D::Content = match parse_body(&this.wire, this.log_bodies, &upload.data) {
+                Ok(contents) => contents,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Run it through the transformers
+            let contents: D::Content = match this.transformer.transform(&upload.metadata, contents) {
+                Ok(contents) => contents,
+                Err(err) => {
+                    let msg: String = trace!(("Failed to transform contents of policy {}", upload.metadata.name), err).to_string();
+                    info!("{msg}");
+                    return this.wire.error_response(ApiErrorCode::ValidationFailed, msg);
+                },
+            };
+
+            // Just try to send it to the DB
+            let name: String = upload.metadata.name.clone();
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to add policy {name}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let contents: D::Content = match validate_content(&mut conn, &upload.metadata, contents).await {
+                Ok(contents) => contents,
+                Err((code, msg)) => return this.wire.error_response(code, msg),
+            };
+            let version: u64 = match conn.add_version(upload.metadata.clone(), contents).await {
+                Ok(res) => res,
+                Err(err) => {
+                    let msg: String = format!("Failed to add policy {name}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            upload.commit();
+
+            // Return the version
+            this.wire.response(StatusCode::OK, &AddVersionResponse { version })
+        }
+    }
+
+    /// Handler for `DELETE /v2/uploads/:id` (i.e., aborting an upload).
+    ///
+    /// Out:
+    /// - 200 OK; or
+    /// - 404 NOT FOUND if no upload `:id` exists (for this user).
+    pub fn cancel_upload(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(id): Path<u64>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::cancel_upload", user = access.user.id, upload = id);
+
+            let mut uploads = this.uploads.lock();
+            match uploads.get(&id) {
+                Some(upload) if upload.owner == access.user.id => {
+                    uploads.remove(&id);
+                    StatusCode::OK.into_response()
+                },
+                _ => this.wire.error_response(ApiErrorCode::UploadNotFound, format!("Unknown upload {id}")),
+            }
+        }
+    }
+
+
+
+    /// Handler for `GET /v2/audit` (i.e., retrieving the audit log).
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetAuditLogResponse`] listing all records; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_audit_log(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_audit_log", user = access.user.id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get audit log".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let records: Vec<AuditRecord> = match conn.get_audit_log(None).await {
+                Ok(records) => records,
+                Err(err) => {
+                    let msg: String = "Failed to get audit log".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetAuditLogResponse { records })
+        }
+    }
+
+    /// Handler for `GET /v2/changes` (i.e., retrieving the changes made after some cursor).
+    ///
+    /// In:
+    /// - An optional `since` query parameter with the cursor of a previous call; and
+    /// - An optional `limit` query parameter with the maximum number of changes to return.
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetChangesResponse`] detailling the changes and the next cursor; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_changes(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Query(query): Query<GetChangesQuery>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_changes", user = access.user.id, since = query.since);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get changes".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let mut changes: Vec<Change<D::Content>> = match conn.get_changes(query.since, query.limit.unwrap_or(1000)).await {
+                Ok(changes) => changes,
+                Err(err) => {
+                    let msg: String = "Failed to get changes".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Send along whatever else they added, such that standbys can replicate it
+            for change in &mut changes {
+                let added = match &change.record.action {
+                    AuditAction::AddTemplate { name } => conn.get_template(name).await.map(|template| template.map(|t| Added::Template(t.attached))),
+                    AuditAction::AddSchema { name } => conn.get_schema(name).await.map(|schema| schema.map(|s| Added::Schema(s.attached))),
+                    AuditAction::AddKey { id } => conn.get_key(id).await.map(|key| key.map(|k| Added::Key(k.attached))),
+                    _ => Ok(None),
+                };
+                match added {
+                    Ok(added) => change.added = added,
+                    Err(err) => {
+                        let msg: String = "Failed to get changes".to_string();
+                        error!("{}", trace!(("{msg}"), err));
+                        return this.wire.error_response(ApiErrorCode::Internal, msg);
+                    },
+                }
+            }
+
+            // Serialize the result
+            let cursor: u64 = changes.last().map(|change| change.record.seq).unwrap_or(query.since);
+            this.wire.response(StatusCode::OK, &GetChangesResponse { changes, cursor })
+        }
+    }
+
+    /// Handler for `GET /v2/audit/verify` (i.e., verifying the integrity of the audit log).
+    ///
+    /// Out:
+    /// - 200 OK with a [`VerifyAuditLogResponse`] describing whether the log is intact; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn verify_audit_log(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::verify_audit_log", user = access.user.id);
+
+            // Get the log
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get audit log".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let records: Vec<AuditRecord> = match conn.get_audit_log(None).await {
+                Ok(records) => records,
+                Err(err) => {
+                    let msg: String = "Failed to get audit log".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Verify it
+            let head: Option<String> = records.last().map(|record| record.hash.clone());
+            let res: VerifyAuditLogResponse = match verify_chain(&records) {
+                Ok(count) => VerifyAuditLogResponse { valid: true, records: count, head, error: None },
+                Err(err) => {
+                    error!("{}", trace!(("Audit log failed verification"), err));
+                    let count: u64 = records.iter().take_while(|record| record.seq < err.seq()).count() as u64;
+                    VerifyAuditLogResponse { valid: false, records: count, head, error: Some(err.to_string()) }
+                },
+            };
+            this.wire.response(StatusCode::OK, &res)
+        }
+    }
+
+    /// Handler for `GET /v2/audit/export` (i.e., exporting the audit log for ingestion by a SIEM).
+    ///
+    /// In:
+    /// - An optional `since` query parameter with an RFC 3339 timestamp, to only export records
+    ///   made at or after it; and
+    /// - An optional `format` query parameter with the [`ExportFormat`] to use (`ndjson` by
+    ///   default).
+    ///
+    /// Out:
+    /// - 200 OK with the records, streamed one per line; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn export_audit_log(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Query(query): Query<ExportAuditLogQuery>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::export_audit_log", user = access.user.id);
+
+            // Get the (relevant part of the) log
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get audit log".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let records: Vec<AuditRecord> = match conn.get_audit_log(query.since).await {
+                Ok(records) => records,
+                Err(err) => {
+                    let msg: String = "Failed to get audit log".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Stream them back in the requested format
+            let content_type: &'static str = match query.format {
+                ExportFormat::Ndjson => "application/x-ndjson",
+                ExportFormat::Cef => "text/plain; charset=utf-8",
+            };
+            let lines = futures::stream::iter(records).map(move |record| match query.format {
+                ExportFormat::Ndjson => serde_json::to_string(&record).map(|line| line + "\n"),
+                ExportFormat::Cef => Ok(record.to_cef(env!("CARGO_PKG_VERSION")) + "\n"),
+            });
+            (StatusCode::OK, [(CONTENT_TYPE, content_type)], Body::from_stream(lines)).into_response()
+        }
+    }
+
+    /// Handler for `POST /v2/policies/:version/decisions` (i.e., registering a decision taken
+    /// under a policy version).
+    ///
+    /// In:
+    /// - [`AddDecisionRequest`].
+    ///
+    /// Out:
+    /// - 200 OK if the decision was registered;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 404 NOT FOUND if there was no policy with version `:version`;
+    /// - 409 CONFLICT if the decision was already registered;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn add_decision(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(version): Path<u64>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::add_decision", user = access.user.id, version = version);
+
+            // Get the request
+            let req: AddDecisionRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to add decision {:?}", req.id);
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            match conn.add_decision(version, &req.id).await {
+                Ok(Ok(())) => StatusCode::OK.into_response(),
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to add decision {:?}", req.id), err));
+                    this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err)
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to add decision {:?}", req.id);
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+    /// Handler for `GET /v2/policies/:version/decisions` (i.e., listing the decisions taken under
+    /// a policy version).
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetDecisionsResponse`] listing the decisions;
+    /// - 404 NOT FOUND if there was no policy with version `:version`; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_decisions(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(version): Path<u64>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_decisions", user = access.user.id, version = version);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get decisions".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let decisions: Vec<Decision> = match conn.get_decisions(version).await {
+                Ok(Some(decisions)) => decisions,
+                Ok(None) => {
+                    return this.wire.error_response(ApiErrorCode::VersionNotFound, format!("Unknown policy version {version}"));
+                },
+                Err(err) => {
+                    let msg: String = "Failed to get decisions".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetDecisionsResponse { decisions })
+        }
+    }
+
+    /// Handler for `GET /v2/policies/:version/decisions/count` (i.e., counting the decisions taken
+    /// under a policy version).
+    ///
+    /// Out:
+    /// - 200 OK with a [`CountDecisionsResponse`] detailling the number of decisions;
+    /// - 404 NOT FOUND if there was no policy with version `:version`; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn count_decisions(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(version): Path<u64>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::count_decisions", user = access.user.id, version = version);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to count decisions".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            match conn.count_decisions(version).await {
+                Ok(Some(count)) => this.wire.response(StatusCode::OK, &CountDecisionsResponse { count }),
+                Ok(None) => this.wire.error_response(ApiErrorCode::VersionNotFound, format!("Unknown policy version {version}")),
+                Err(err) => {
+                    let msg: String = "Failed to count decisions".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+    /// Handler for `GET /v2/decisions/:id` (i.e., finding under which policy version a decision
+    /// was taken).
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetDecisionResponse`] describing the decision;
+    /// - 404 NOT FOUND if no decision `:id` was registered; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_decision(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(id): Path<String>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_decision", user = access.user.id, decision = id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to get decision {id:?}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let decision: Decision = match conn.get_decision(&id).await {
+                Ok(Some(decision)) => decision,
+                Ok(None) => {
+                    return this.wire.error_response(ApiErrorCode::DecisionNotFound, format!("Unknown decision {id:?}"));
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to get decision {id:?}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetDecisionResponse { decision })
+        }
+    }
+
+
+
+    /// Handler for `POST /v2/keys` (i.e., registering a new verification key).
+    ///
+    /// Tokens signed with the key are trusted by the `DatabaseKeyResolver` of the JWK resolver, so
+    /// only users with one of the [admin roles](AxumServer::with_admin_roles()) may call this.
+    ///
+    /// In:
+    /// - [`AddKeyRequest`].
+    ///
+    /// Out:
+    /// - 200 OK if the key was registered;
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 403 FORBIDDEN if the user has none of the admin roles;
+    /// - 409 CONFLICT if a key with the same identifier was registered before;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn add_key(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::add_key", user = access.user.id);
+
+            // Get the request
+            let req: AddKeyRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to add key {:?}", req.key.id);
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let id: String = req.key.id.clone();
+            match conn.add_key(req.key).await {
+                Ok(Ok(())) => StatusCode::OK.into_response(),
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to add key {id:?}"), err));
+                    this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err)
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to add key {id:?}");
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+    /// Handler for `GET /v2/keys` (i.e., listing all verification keys).
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetKeysResponse`] mapping key identifiers to [`Key`]s (without the
+    ///   material of shared secrets); or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_keys(State(this): State<Arc<Self>>, Extension(access): Extension<AccessContext>) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_keys", user = access.user.id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get keys".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let mut keys: HashMap<String, Key> = match conn.get_keys().await {
+                Ok(keys) => keys,
+                Err(err) => {
+                    let msg: String = "Failed to get keys".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+
+            // Anyone knowing a shared secret can forge tokens, so don't hand them out
+            for key in keys.values_mut() {
+                if key.attached.kind == KeyKind::Oct {
+                    key.attached.material.clear();
+                }
+            }
+            this.wire.response(StatusCode::OK, &GetKeysResponse { keys })
+        }
+    }
+
+    /// Handler for `DELETE /v2/keys/:id` (i.e., revoking a verification key).
+    ///
+    /// Any keys cached by the [`AuthResolver`] are [invalidated](AuthResolver::invalidate()), such
+    /// that tokens signed with the key are rejected right away.
+    ///
+    /// Only users with one of the [admin roles](AxumServer::with_admin_roles()) may call this.
+    ///
+    /// Out:
+    /// - 200 OK if the key was revoked (or already was);
+    /// - 403 FORBIDDEN if the user has none of the admin roles;
+    /// - 404 NOT FOUND if no key `:id` was registered; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn revoke_key(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        Path(id): Path<String>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::revoke_key", user = access.user.id, key = id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = format!("Failed to revoke key {id:?}");
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            match conn.revoke_key(&id).await {
+                Ok(Ok(())) => {
+                    // NOTE: Make sure the key isn't trusted from any cache anymore
+                    this.auth.invalidate();
+                    StatusCode::OK.into_response()
+                },
+                Ok(Err(err)) => {
+                    info!("{}", trace!(("Failed to revoke key {id:?}"), err));
+                    this.wire.http_error_response(ApiErrorCode::from(&err), err.to_string(), &err)
+                },
+                Err(err) => {
+                    let msg: String = format!("Failed to revoke key {id:?}");
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+    /// Handler for `POST /v2/revocations` (i.e., revoking tokens).
+    ///
+    /// Only users with one of the [admin roles](AxumServer::with_admin_roles()) may call this, as
+    /// it can lock out any user.
+    ///
+    /// In:
+    /// - [`RevokeTokensRequest`].
+    ///
+    /// Out:
+    /// - 200 OK if the tokens were revoked (or already were);
+    /// - 400 BAD REQUEST with the reason why we failed to parse the request;
+    /// - 403 FORBIDDEN if the user has none of the admin roles;
+    /// - 415 UNSUPPORTED MEDIA TYPE if the body is not encoded in the server's [`WireFormat`]; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn revoke_tokens(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+        request: Request,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::revoke_tokens", user = access.user.id);
+
+            // Get the request
+            let req: RevokeTokensRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to revoke tokens".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            match conn.revoke_tokens(req.revocation).await {
+                Ok(()) => {
+                    // NOTE: Make sure the tokens aren't accepted from any cache anymore
+                    this.auth.invalidate();
+                    StatusCode::OK.into_response()
+                },
+                Err(err) => {
+                    let msg: String = "Failed to revoke tokens".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    this.wire.error_response(ApiErrorCode::Internal, msg)
+                },
+            }
+        }
+    }
+
+    /// Handler for `GET /v2/revocations` (i.e., listing all token revocations).
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetRevocationsResponse`] listing all [`Revocation`]s; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    pub fn get_revocations(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_revocations", user = access.user.id);
+
+            // Just try to send it to the DB
+            let mut conn: D::Connection<'_> = match this.data.connect_with(&access).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    let msg: String = "Failed to get revocations".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            let revocations: Vec<Revocation> = match conn.get_revocations().await {
+                Ok(revocations) => revocations,
+                Err(err) => {
+                    let msg: String = "Failed to get revocations".to_string();
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            this.wire.response(StatusCode::OK, &GetRevocationsResponse { revocations })
+        }
+    }
+
+    /// Handler for `GET /v2/ping` (i.e., checking whether the server is up).
+    ///
+    /// Note that this endpoint is unauthenticated, does not touch the database, and is only served
+    /// if not [disabled](AxumServer::without_ping()).
+    ///
+    /// Out:
+    /// - 200 OK with a [`PingResponse`] carrying the name and version of the server.
+    pub fn ping(State(this): State<Arc<Self>>) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::ping");
+            this.wire.response(StatusCode::OK, &PingResponse { name: env!("CARGO_PKG_NAME").into(), version: env!("CARGO_PKG_VERSION").into() })
+        }
+    }
+
+    /// Handler for `POST /v2/dev/token` (i.e., minting a development token).
+    ///
+    /// Note that this endpoint is unauthenticated, and only served if
+    /// [enabled](AxumServer::with_dev_tokens()).
+    ///
+    /// In:
+    /// - [`DevTokenRequest`].
+    ///
+    /// Out:
+    /// - 200 OK with a [`DevTokenResponse`] carrying the signed token;
+    /// - 404 NOT FOUND if development tokens are not enabled; or
+    /// - 500 INTERNAL SERVER ERROR with a message what went wrong.
+    #[cfg(feature = "dev-auth")]
+    pub fn dev_token(State(this): State<Arc<Self>>, request: Request) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::dev_token");
+
+            // Get the request
+            let req: DevTokenRequest = match download_request(&this.wire, this.log_bodies, request).await {
+                Ok(req) => req,
+                Err(err) => return this.wire.problem_response(*err),
+            };
+
+            // Mint the token, if we're allowed to
+            let Some(minter) = &this.dev_tokens else {
+                return this.wire.error_response(ApiErrorCode::NotFound, "Development tokens are not enabled".into());
+            };
+            let res: DevTokenResponse = match minter.mint(&req) {
+                Ok(res) => res,
+                Err(err) => {
+                    let msg: String = format!("Failed to mint development token for user {:?}", req.user);
+                    error!("{}", trace!(("{msg}"), err));
+                    return this.wire.error_response(ApiErrorCode::Internal, msg);
+                },
+            };
+            warn!("Minted development token for user {:?} with roles {:?}", req.user, req.roles);
+            this.wire.response(StatusCode::OK, &res)
+        }
+    }
+
+    /// Handler for `GET /v2/federated/policies` (i.e., listing all policy of every upstream).
+    ///
+    /// Note that this endpoint is only served if any [upstreams](AxumServer::with_upstream()) are
+    /// configured. Upstreams that cannot be reached are reported with an error instead of failing
+    /// the whole request.
+    ///
+    /// Out:
+    /// - 200 OK with a [`GetFederatedVersionsResponse`] mapping upstream names to their versions
+    ///   and active version.
+    #[cfg(feature = "federation")]
+    pub fn get_federated_versions(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::get_federated_versions", user = access.user.id);
+
+            // Ask all upstreams at once
+            let stores: Vec<(String, FederatedStore)> = futures::future::join_all(this.upstreams.iter().map(|(name, client)| async move {
+                match futures::future::try_join(client.get_versions(), client.get_active_version()).await {
+                    Ok((versions, active)) => (name.clone(), FederatedStore { versions, active, error: None }),
+                    Err(err) => {
+                        warn!("{}", trace!(("Failed to read from upstream {name:?}"), &err));
+                        (name.clone(), FederatedStore { versions: HashMap::new(), active: None, error: Some(err.to_string()) })
+                    },
+                }
+            }))
+            .await;
+
+            // Serialize the result
+            this.wire.response(StatusCode::OK, &GetFederatedVersionsResponse { stores: stores.into_iter().collect() })
+        }
+    }
+
+    /// Handler for `POST /v2/standby/promote` (i.e., promoting a standby to read-write).
+    ///
+    /// Note that this endpoint is only served if this server is a
+    /// [standby](AxumServer::with_standby()). Promoting an already promoted standby does nothing.
+    /// Only users with one of the [admin roles](AxumServer::with_admin_roles()) may call this.
+    ///
+    /// Out:
+    /// - 200 OK with a [`PromoteStandbyResponse`] carrying the sequence number of the last change
+    ///   replicated from the primary;
+    /// - 403 FORBIDDEN if the user has none of the admin roles; or
+    /// - 404 NOT FOUND if this server is not a standby.
+    #[cfg(feature = "standby")]
+    pub fn promote_standby(
+        State(this): State<Arc<Self>>,
+        Extension(access): Extension<AccessContext>,
+    ) -> impl 'static + Send + Future<Output = Response> {
+        async move {
+            let _span = span!(Level::INFO, "AxumServer::promote_standby", user = access.user.id);
+
+            let Some(standby) = &this.standby else {
+                return this.wire.error_response(ApiErrorCode::NotFound, "This server is not a standby".into());
+            };
+            let cursor: u64 = standby.promote().await;
+            info!("User {:?} promoted standby after replicating up to change {cursor}", access.user.id);
+            this.wire.response(StatusCode::OK, &PromoteStandbyResponse { cursor })
         }
     }
 }
@@ -0,0 +1,154 @@
+//  PROBLEM.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:14:30
+//  Last edited:
+//    16 Oct 2026, 16:14:30
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Makes sure that every failing request is answered with an RFC 7807
+//!   problem, including ones that never reached our handlers.
+//
+
+use axum::body::{Body, Bytes};
+use axum::extract::Request;
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::{HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse as _, Response};
+use error_trace::trace;
+use tracing::{Instrument as _, Level, error, span};
+use uuid::Uuid;
+
+use crate::spec::{ApiErrorCode, ErrorResponse, PROBLEM_CONTENT_TYPE, REQUEST_ID_HEADER};
+
+
+/***** CONSTANTS *****/
+/// The maximum length of request IDs given by clients. Longer ones are replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// The maximum size of non-problem error bodies that are turned into a problem's `detail`.
+const MAX_DETAIL_LEN: usize = 4096;
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Finds the ID of a request, generating one if the client didn't give a (usable) one.
+///
+/// # Arguments
+/// - `request`: The [`Request`] to find the ID of.
+///
+/// # Returns
+/// The ID given in the [`REQUEST_ID_HEADER`], or a new random one.
+fn request_id(request: &Request) -> String {
+    match request.headers().get(REQUEST_ID_HEADER).and_then(|id| id.to_str().ok()) {
+        Some(id) if !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic()) => id.into(),
+        _ => Uuid::new_v4().to_string(),
+    }
+}
+
+
+
+/// Encodes a problem as response.
+///
+/// # Arguments
+/// - `problem`: The [`ErrorResponse`] to encode.
+///
+/// # Returns
+/// A [`Response`] with the status code of the `problem`, and the problem itself both as body and
+/// as extension.
+pub(crate) fn render(problem: ErrorResponse) -> Response {
+    let status: StatusCode = StatusCode::from_u16(problem.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    match serde_json::to_vec(&problem) {
+        Ok(body) => {
+            let mut res: Response = (status, [(CONTENT_TYPE, PROBLEM_CONTENT_TYPE)], body).into_response();
+            res.extensions_mut().insert(problem);
+            res
+        },
+        Err(err) => {
+            error!("{}", trace!(("Failed to serialize error response"), err));
+            (status, problem.detail).into_response()
+        },
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// The ID of a request, as given by the client or generated by the server.
+///
+/// Injected as an extension by [`normalize_errors()`], and echoed in the [`REQUEST_ID_HEADER`] of
+/// the response.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RequestId(pub String);
+
+
+
+/// Middleware that identifies every request, and turns every error response into a problem.
+///
+/// Errors from our own handlers are completed with the request's ID and path. Any other error
+/// response (e.g., from axum's extractors, or for unknown routes) is replaced by a problem with
+/// the generic [`ApiErrorCode`] of its status code, using its body (if any) as `detail`.
+///
+/// # Arguments
+/// - `request`: The [`Request`] to handle.
+/// - `next`: The rest of the service stack.
+///
+/// # Returns
+/// The [`Response`] of the rest of the stack, with the request ID added.
+pub(crate) async fn normalize_errors(mut request: Request, next: Next) -> Response {
+    let id: String = request_id(&request);
+    let path: String = request.uri().path().into();
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    // Run the request with the ID attached to every log line
+    let span = span!(Level::INFO, "request", id = id.as_str());
+    let res: Response = next.run(request).instrument(span).await;
+
+    // Complete (or create) the problem if it's an error
+    let mut res: Response = if res.status().is_client_error() || res.status().is_server_error() {
+        let (mut parts, body) = res.into_parts();
+        let mut problem: ErrorResponse = match parts.extensions.remove::<ErrorResponse>() {
+            Some(problem) => problem,
+            None => {
+                let detail: String = match axum::body::to_bytes(body, MAX_DETAIL_LEN).await {
+                    Ok(body) => String::from_utf8_lossy(&body).trim().into(),
+                    Err(_) => String::new(),
+                };
+                let mut problem = ErrorResponse::new(ApiErrorCode::from_status(parts.status), detail);
+                // NOTE: Keep the original status, which may be more specific than the code's
+                problem.status = parts.status.as_u16();
+                problem.title = parts.status.canonical_reason().unwrap_or_default().into();
+                problem
+            },
+        };
+        problem.instance = Some(path);
+        problem.request_id = Some(id.clone());
+
+        // Re-render it, keeping any other headers (e.g., `Allow` or `WWW-Authenticate`)
+        let body: Bytes = match serde_json::to_vec(&problem) {
+            Ok(body) => body.into(),
+            Err(err) => {
+                error!("{}", trace!(("Failed to serialize error response"), err));
+                problem.detail.clone().into()
+            },
+        };
+        parts.headers.remove(CONTENT_LENGTH);
+        parts.headers.insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_CONTENT_TYPE));
+        parts.extensions.insert(problem);
+        Response::from_parts(parts, Body::from(body))
+    } else {
+        res
+    };
+
+    // SAFETY: The ID is either checked to be a valid header value, or a UUID
+    res.headers_mut().insert(REQUEST_ID_HEADER, HeaderValue::from_str(&id).unwrap());
+    res
+}
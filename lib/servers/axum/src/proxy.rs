@@ -0,0 +1,121 @@
+//  PROXY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:29:21
+//  Last edited:
+//    16 Oct 2026, 15:29:21
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines how trusted proxies are configured, i.e., as ranges of
+//!   addresses in CIDR notation.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+
+/***** ERRORS *****/
+/// Defines errors when parsing an [`IpCidr`].
+#[derive(Debug, Error)]
+pub enum IpCidrParseError {
+    /// The address part was not a valid IP address.
+    #[error("Invalid address {raw:?} in CIDR")]
+    IllegalAddress {
+        raw: String,
+        #[source]
+        err: std::net::AddrParseError,
+    },
+    /// The prefix length was not a number, or too long for the address.
+    #[error("Invalid prefix length {raw:?} in CIDR (must be at most {max})")]
+    IllegalPrefix { raw: String, max: u8 },
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A range of IP addresses in CIDR notation (e.g., `10.0.0.0/8` or `fd00::/8`).
+///
+/// A single address (without prefix length) is parsed as a range containing just that address.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IpCidr {
+    /// The (first) address in the range.
+    addr:   IpAddr,
+    /// The number of leading bits that addresses must share with `addr` to be in the range.
+    prefix: u8,
+}
+impl IpCidr {
+    /// Constructor for the IpCidr.
+    ///
+    /// # Arguments
+    /// - `addr`: Some address in the range.
+    /// - `prefix`: The number of leading bits that addresses must share with `addr` to be in the
+    ///   range. Clamped to the length of the address.
+    ///
+    /// # Returns
+    /// A new IpCidr.
+    #[inline]
+    pub fn new(addr: IpAddr, prefix: u8) -> Self {
+        let max: u8 = if addr.is_ipv4() { 32 } else { 128 };
+        Self { addr, prefix: prefix.min(max) }
+    }
+
+    /// Checks whether an address is in this range.
+    ///
+    /// IPv4 addresses mapped to IPv6 (e.g., `::ffff:10.0.0.1`) are treated as the IPv4 address
+    /// they map.
+    ///
+    /// # Arguments
+    /// - `addr`: The address to check.
+    ///
+    /// # Returns
+    /// True if the address is in the range, or false otherwise (including if it is of another
+    /// family).
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        match (self.addr.to_canonical(), addr.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask: u32 = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                u32::from(net) & mask == u32::from(addr) & mask
+            },
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask: u128 = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                u128::from(net) & mask == u128::from(addr) & mask
+            },
+            _ => false,
+        }
+    }
+}
+impl Display for IpCidr {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "{}/{}", self.addr, self.prefix) }
+}
+impl From<IpAddr> for IpCidr {
+    #[inline]
+    fn from(value: IpAddr) -> Self { Self::new(value, if value.is_ipv4() { 32 } else { 128 }) }
+}
+impl FromStr for IpCidr {
+    type Err = IpCidrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix): (&str, Option<&str>) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.parse().map_err(|err| IpCidrParseError::IllegalAddress { raw: addr.into(), err })?;
+        let max: u8 = if addr.is_ipv4() { 32 } else { 128 };
+        match prefix {
+            Some(prefix) => match prefix.parse::<u8>() {
+                Ok(len) if len <= max => Ok(Self::new(addr, len)),
+                _ => Err(IpCidrParseError::IllegalPrefix { raw: prefix.into(), max }),
+            },
+            None => Ok(Self::from(addr)),
+        }
+    }
+}
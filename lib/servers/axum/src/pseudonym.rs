@@ -0,0 +1,106 @@
+//  PSEUDONYM.rs
+//    by Lut99
+//
+//  Created:
+//    17 Oct 2026, 01:54:47
+//  Last edited:
+//    17 Oct 2026, 01:54:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the keyed derivation of the pseudonyms that replace
+//!   erased users.
+//
+
+use std::fmt::{Debug, Formatter, Result as FResult, Write as _};
+
+use hmac::{Hmac, Mac as _};
+use sha2::Sha256;
+use uuid::Uuid;
+
+
+/***** LIBRARY *****/
+/// Derives the pseudonym of a user as the HMAC-SHA256 of their ID under a secret key.
+///
+/// This makes pseudonyms stable, i.e., pseudonymizing the same user twice (or on two stores
+/// sharing the key) yields the same pseudonym, while nobody without the key can tell which user it
+/// replaced.
+#[derive(Clone)]
+pub struct PseudonymKey {
+    /// The secret key.
+    key: Vec<u8>,
+}
+impl Default for PseudonymKey {
+    /// Generates a random key.
+    ///
+    /// Pseudonyms derived with it are only stable for as long as the key is kept around, i.e.,
+    /// until the server restarts.
+    #[inline]
+    fn default() -> Self {
+        let mut key: Vec<u8> = Vec::with_capacity(32);
+        key.extend_from_slice(Uuid::new_v4().as_bytes());
+        key.extend_from_slice(Uuid::new_v4().as_bytes());
+        Self { key }
+    }
+}
+impl Debug for PseudonymKey {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { f.debug_struct("PseudonymKey").finish_non_exhaustive() }
+}
+impl PseudonymKey {
+    /// Constructor for the PseudonymKey.
+    ///
+    /// # Arguments
+    /// - `key`: The raw bytes of the secret key. Keep this secret and keep it around, as anyone
+    ///   holding it can check whether a pseudonym belongs to a given user.
+    ///
+    /// # Returns
+    /// A new PseudonymKey.
+    #[inline]
+    pub fn new(key: impl Into<Vec<u8>>) -> Self { Self { key: key.into() } }
+
+    /// Derives the pseudonym of a user.
+    ///
+    /// # Arguments
+    /// - `id`: The ID of the user to derive the pseudonym of.
+    ///
+    /// # Returns
+    /// A pseudonym of the form `anonymous-<hex-encoded HMAC>`.
+    pub fn pseudonym(&self, id: &str) -> String {
+        // NOTE: HMAC accepts keys of any length
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).unwrap_or_else(|_| unreachable!());
+        mac.update(id.as_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        let mut res: String = String::with_capacity("anonymous-".len() + 2 * hash.len());
+        res.push_str("anonymous-");
+        for byte in hash {
+            // SAFETY: Writing to a string never fails
+            write!(res, "{byte:02x}").unwrap();
+        }
+        res
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudonym_is_keyed() {
+        let key = PseudonymKey::new(*b"secret");
+        assert_eq!(key.pseudonym("amy"), key.pseudonym("amy"));
+        assert_eq!(key.pseudonym("amy"), PseudonymKey::new(*b"secret").pseudonym("amy"));
+        assert_ne!(key.pseudonym("amy"), key.pseudonym("bob"));
+        assert_ne!(key.pseudonym("amy"), PseudonymKey::new(*b"other").pseudonym("amy"));
+        assert_ne!(key.pseudonym("amy"), PseudonymKey::default().pseudonym("amy"));
+        assert!(key.pseudonym("amy").starts_with("anonymous-"));
+        assert_eq!(key.pseudonym("amy").len(), "anonymous-".len() + 64);
+    }
+}
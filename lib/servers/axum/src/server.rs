@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 10:28:29
 //  Last edited:
-//    06 Dec 2024, 18:32:03
+//    17 Oct 2026, 03:20:17
 //  Auto updated?
 //    Yes
 //
@@ -12,30 +12,99 @@
 //!   Defines the API itself.
 //
 
+use std::convert::Infallible;
 use std::future::Future;
 use std::net::SocketAddr;
+use std::pin::pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::Router;
 use axum::extract::connect_info::IntoMakeServiceWithConnectInfo;
+use axum::extract::{Request as AxumRequest, State};
+use axum::http::header::{CONTENT_SECURITY_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method};
+use axum::response::{IntoResponse, Response};
+use axum::routing::Route;
+use chrono::NaiveTime;
 use error_trace::trace;
+use futures::future::{Either, join, join_all, select};
 use hyper::Request;
 use hyper::body::Incoming;
 use hyper_util::rt::{TokioExecutor, TokioIo};
 use hyper_util::server::conn::auto::Builder as HyperBuilder;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use specifications::{AuthResolver, DatabaseConnector, Server};
+#[cfg(feature = "deny-all")]
+use specifications::DenyAll;
+use specifications::authresolver::AsUser;
+use specifications::consistency::Finding;
+use specifications::{AuthResolver, ContentTransformer, DatabaseConnector, Server, ServerHooks, UserIdFormat, Worker};
 use thiserror::Error;
 use tokio::net::{TcpListener, TcpStream};
-use tower_service::Service as _;
+use tower_layer::Layer;
+use tower_service::Service;
 use tracing::field::Empty;
 use tracing::{Level, debug, error, info, span};
 
+use crate::authcache::CachedAuthResolver;
+use crate::bootstrap::BootstrapPolicy;
+#[cfg(feature = "cosign")]
+use crate::cosign::CosignVerifier;
+#[cfg(feature = "dev-auth")]
+use crate::devauth::DevTokenMinter;
+use crate::problem::normalize_errors;
+use crate::proxy::IpCidr;
+use crate::pseudonym::PseudonymKey;
+#[cfg(feature = "dev-auth")]
+use crate::spec::DEV_TOKEN_PATH;
+#[cfg(feature = "federation")]
+use crate::spec::GET_FEDERATED_VERSIONS_PATH;
 use crate::spec::{
-    ACTIVATE_PATH, ADD_VERSION_PATH, DEACTIVATE_PATH, GET_ACTIVATOR_VERSION_PATH, GET_ACTIVE_VERSION_PATH, GET_VERSION_CONTENT_PATH,
-    GET_VERSION_METADATA_PATH, GET_VERSIONS_PATH,
+    ACTIVATE_PATH, ADD_DECISION_PATH, ADD_KEY_PATH, ADD_SCHEMA_PATH, ADD_TEMPLATE_PATH, ADD_VERSION_PATH, CANCEL_UPLOAD_PATH, COUNT_DECISIONS_PATH,
+    CREATE_BRANCH_PATH, DEACTIVATE_PATH, DENY_ALL_PATH, EXPORT_AUDIT_LOG_PATH, EndpointPath, FINALIZE_UPLOAD_PATH, FREEZE_VERSION_PATH,
+    GET_ACTIVATIONS_PATH, GET_ACTIVATOR_VERSION_PATH, GET_ACTIVE_CONTENT_PATH, GET_ACTIVE_VERSION_PATH, GET_AUDIT_LOG_PATH, GET_BRANCHES_PATH,
+    GET_CHANGES_PATH, GET_DECISION_PATH, GET_DECISIONS_PATH, GET_KEYS_PATH, GET_REVOCATIONS_PATH, GET_SCHEMAS_PATH, GET_TEMPLATES_PATH,
+    GET_UPLOAD_PATH, GET_VERSION_CONTENT_PATH, GET_VERSION_CONTENT_RAW_PATH, GET_VERSION_METADATA_PATH, GET_VERSION_PROVENANCE_PATH,
+    GET_VERSIONS_PATH, IMPORT_VERSION_PATH, INITIATE_UPLOAD_PATH, INSTANTIATE_TEMPLATE_PATH, MAINTAIN_PATH, PING_PATH, PROMOTE_PATH,
+    PROMOTE_STANDBY_PATH, PRUNE_ACTIVATIONS_PATH, PSEUDONYMIZE_USER_PATH, REVOKE_KEY_PATH, REVOKE_TOKENS_PATH, UNFREEZE_VERSION_PATH,
+    UPLOAD_CHUNK_PATH, VERIFY_AUDIT_LOG_PATH,
 };
+#[cfg(feature = "standby")]
+use crate::standby::Standby;
+use crate::syslog::SyslogForwarder;
+use crate::uploads::Uploads;
+use crate::wire::{Json, WireFormat};
+use crate::worker::{WorkerTask, erase_worker, stop_signal};
+
+
+/***** CONSTANTS *****/
+/// The role that users need to call the administrative endpoints, unless other
+/// [admin roles](AxumServer::with_admin_roles()) are configured.
+pub const DEFAULT_ADMIN_ROLE: &str = "admin";
+
+/// The endpoints that only users with one of the [admin roles](AxumServer::with_admin_roles())
+/// may call.
+pub(crate) const ADMIN_PATHS: &[&EndpointPath] = &[
+    &PSEUDONYMIZE_USER_PATH,
+    &ADD_KEY_PATH,
+    &REVOKE_KEY_PATH,
+    &REVOKE_TOKENS_PATH,
+    &FREEZE_VERSION_PATH,
+    &UNFREEZE_VERSION_PATH,
+    &ADD_TEMPLATE_PATH,
+    &ADD_SCHEMA_PATH,
+    &IMPORT_VERSION_PATH,
+    &GET_AUDIT_LOG_PATH,
+    &EXPORT_AUDIT_LOG_PATH,
+    &PRUNE_ACTIVATIONS_PATH,
+    &MAINTAIN_PATH,
+    &PROMOTE_STANDBY_PATH,
+    &DENY_ALL_PATH,
+];
+
+
+
 
 
 /***** ERRORS *****/
@@ -49,6 +118,67 @@ pub enum Error {
         #[source]
         err:  std::io::Error,
     },
+    /// Failed to find out on which address the listener is bound.
+    #[error("Failed to get local address of server bound on '{addr}'")]
+    ListenerAddr {
+        addr: SocketAddr,
+        #[source]
+        err:  std::io::Error,
+    },
+    /// The backend database violates some invariants.
+    #[error("Backend database is inconsistent ({} finding(s); see the log for details)", findings.len())]
+    Inconsistent { findings: Vec<Finding> },
+    /// Failed to check the consistency of the backend database.
+    #[error("Failed to verify the consistency of the backend database: {err}")]
+    Verify { err: String },
+    /// Failed to add or activate the bootstrap policy in an empty database.
+    #[error("Failed to bootstrap the backend database: {err}")]
+    Bootstrap { err: String },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// A type-erased tower [`Layer`] that can be applied to a [`Router`].
+type RouterLayer = Box<dyn Send + Sync + Fn(Router) -> Router>;
+
+/// Type-erases a tower [`Layer`] such that it can be stored in the [`AxumServer`].
+///
+/// # Arguments
+/// - `layer`: The [`Layer`] to erase.
+///
+/// # Returns
+/// A [`RouterLayer`] that applies (a clone of) the `layer` to any given [`Router`].
+fn erase_layer<L>(layer: L) -> RouterLayer
+where
+    L: 'static + Clone + Send + Sync + Layer<Route>,
+    L::Service: 'static + Clone + Send + Sync + Service<AxumRequest>,
+    <L::Service as Service<AxumRequest>>::Response: 'static + IntoResponse,
+    <L::Service as Service<AxumRequest>>::Error: 'static + Into<Infallible>,
+    <L::Service as Service<AxumRequest>>::Future: 'static + Send,
+{
+    Box::new(move |router: Router| router.layer(layer.clone()))
+}
+
+/// Adds the default headers to a response, unless the handler already set them.
+///
+/// # Arguments
+/// - `headers`: The default headers to add.
+/// - `response`: The [`Response`] to add them to.
+///
+/// # Returns
+/// The given `response`, with the headers added.
+async fn set_default_headers(State(headers): State<Arc<HeaderMap>>, mut response: Response) -> Response {
+    for name in headers.keys() {
+        if !response.headers().contains_key(name) {
+            for value in headers.get_all(name) {
+                response.headers_mut().append(name.clone(), value.clone());
+            }
+        }
+    }
+    response
 }
 
 
@@ -56,14 +186,102 @@ pub enum Error {
 
 
 /***** LIBRARY *****/
+/// Defines what the server replies to requests for the content of the active policy while no
+/// policy is active.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NoActivePolicy {
+    /// Reply with 404 NOT FOUND.
+    #[default]
+    NotFound,
+    /// Reply with the content of the given version instead, without activating it.
+    Fallback(u64),
+    /// Reply with the [built-in deny-all policy](specifications::DenyAll::deny_all()), without
+    /// storing it.
+    #[cfg(feature = "deny-all")]
+    DenyAll,
+}
+
+/// The bound that the `deny-all` feature puts on the policy content served, i.e., [`DenyAll`].
+///
+/// Without that feature, this is implemented for any content, such that content without a
+/// deny-all policy can still be served.
+#[cfg(feature = "deny-all")]
+pub trait MaybeDenyAll: DenyAll {}
+#[cfg(feature = "deny-all")]
+impl<C: DenyAll> MaybeDenyAll for C {}
+
+/// The bound that the `deny-all` feature puts on the policy content served, i.e., nothing, as it
+/// is not compiled in.
+#[cfg(not(feature = "deny-all"))]
+pub trait MaybeDenyAll {}
+#[cfg(not(feature = "deny-all"))]
+impl<C> MaybeDenyAll for C {}
+
+
+
 /// Defines the policy store compliant [`axum`] [`Server`].
-pub struct AxumServer<A, D> {
+pub struct AxumServer<A, D, T = (), W = Json> {
     /// The address on which to bind the server.
     pub(crate) addr: SocketAddr,
     /// The auth resolver for resolving auth.
     pub(crate) auth: A,
     /// The database connector for connecting to databases.
     pub(crate) data: D,
+    /// The (chain of) content transformer(s) to run on policy content before storing it.
+    pub(crate) transformer: T,
+    /// The format in which request and response bodies are encoded.
+    pub(crate) wire: W,
+    /// The chunked uploads currently in progress.
+    pub(crate) uploads: Uploads,
+    /// Where to forward the audit log to, if anywhere.
+    pub(crate) syslog: Option<SyslogForwarder>,
+    /// The ranges of proxies whose `Forwarded` and `X-Forwarded-For` headers are trusted.
+    pub(crate) trusted_proxies: Vec<IpCidr>,
+    /// The time of day (in UTC) at which to run database maintenance, if ever.
+    pub(crate) maintenance: Option<NaiveTime>,
+    /// Whether to [verify](DatabaseConnector::verify()) the backend before serving anything.
+    pub(crate) verify: bool,
+    /// The policy to add and activate when starting against an empty database, if any.
+    pub(crate) bootstrap: Option<BootstrapPolicy>,
+    /// What to reply to requests for the active content while no policy is active.
+    pub(crate) no_active: NoActivePolicy,
+    /// Headers to add to every response (unless the handler already set them).
+    pub(crate) headers: HeaderMap,
+    /// Whether to log raw request bodies (at TRACE level) that fail to parse.
+    pub(crate) log_bodies: bool,
+    /// Whether to serve the unauthenticated [`PING_PATH`].
+    pub(crate) ping: bool,
+    /// Additional tower layers to wrap around all routes.
+    pub(crate) layers: Vec<RouterLayer>,
+    /// Additional tower layers to wrap around specific endpoints, identified by their method and path.
+    pub(crate) route_layers: Vec<((Method, &'static str), RouterLayer)>,
+    /// The roles of which users need at least one to call specific endpoints, identified by their
+    /// method and path.
+    pub(crate) required_roles: Vec<((Method, &'static str), Vec<String>)>,
+    /// The roles of which users need at least one to call the administrative endpoints (see
+    /// [`ADMIN_PATHS`]).
+    pub(crate) admin_roles: Vec<String>,
+    /// The roles of which users need at least one (or one of the admin roles) to read the content
+    /// of confidential versions.
+    pub(crate) confidential_roles: Vec<String>,
+    /// Background [`Worker`]s to run while serving.
+    pub(crate) workers: Vec<WorkerTask>,
+    /// The format to normalize the IDs of authorized users to.
+    pub(crate) user_ids: Arc<dyn Send + Sync + UserIdFormat>,
+    /// The key from which to derive the pseudonyms of erased users.
+    pub(crate) pseudonyms: PseudonymKey,
+    /// Verifies the signatures of imported versions, if enabled.
+    #[cfg(feature = "cosign")]
+    pub(crate) cosign: Option<CosignVerifier>,
+    /// Mints development tokens for anyone who asks, if enabled.
+    #[cfg(feature = "dev-auth")]
+    pub(crate) dev_tokens: Option<DevTokenMinter>,
+    /// The upstream stores to serve a federated view of, by name.
+    #[cfg(feature = "federation")]
+    pub(crate) upstreams: Vec<(String, reqwest_client::Client)>,
+    /// The primary to replicate from while serving as its standby, if any.
+    #[cfg(feature = "standby")]
+    pub(crate) standby: Option<Standby>,
 }
 impl<A, D> AxumServer<A, D> {
     /// Constructor for the AxumServer.
@@ -76,17 +294,635 @@ impl<A, D> AxumServer<A, D> {
     /// # Returns
     /// A new AxumServer, ready to serve its opponents.
     #[inline]
-    pub fn new(addr: impl Into<SocketAddr>, auth: A, data: D) -> Self { Self { addr: addr.into(), auth, data } }
+    pub fn new(addr: impl Into<SocketAddr>, auth: A, data: D) -> Self {
+        Self {
+            addr: addr.into(),
+            auth,
+            data,
+            transformer: (),
+            wire: Json,
+            uploads: Uploads::default(),
+            syslog: None,
+            trusted_proxies: Vec::new(),
+            maintenance: None,
+            verify: false,
+            bootstrap: None,
+            no_active: NoActivePolicy::NotFound,
+            headers: HeaderMap::new(),
+            log_bodies: false,
+            ping: true,
+            layers: Vec::new(),
+            route_layers: Vec::new(),
+            required_roles: Vec::new(),
+            admin_roles: vec![DEFAULT_ADMIN_ROLE.into()],
+            confidential_roles: Vec::new(),
+            workers: Vec::new(),
+            user_ids: Arc::new(()),
+            pseudonyms: PseudonymKey::default(),
+            #[cfg(feature = "cosign")]
+            cosign: None,
+            #[cfg(feature = "dev-auth")]
+            dev_tokens: None,
+            #[cfg(feature = "federation")]
+            upstreams: Vec::new(),
+            #[cfg(feature = "standby")]
+            standby: None,
+        }
+    }
+}
+impl<A, D, T, W> AxumServer<A, D, T, W> {
+    /// Adds a [`ContentTransformer`] to the end of the chain of transformers run on policy
+    /// content before it is stored.
+    ///
+    /// # Arguments
+    /// - `transformer`: The [`ContentTransformer`] to add.
+    ///
+    /// # Returns
+    /// A new AxumServer that runs the given `transformer` after the existing ones.
+    #[inline]
+    pub fn with_transformer<T2>(self, transformer: T2) -> AxumServer<A, D, (T, T2), W> {
+        AxumServer {
+            addr: self.addr,
+            auth: self.auth,
+            data: self.data,
+            transformer: (self.transformer, transformer),
+            wire: self.wire,
+            uploads: self.uploads,
+            syslog: self.syslog,
+            trusted_proxies: self.trusted_proxies,
+            maintenance: self.maintenance,
+            verify: self.verify,
+            bootstrap: self.bootstrap,
+            no_active: self.no_active,
+            headers: self.headers,
+            log_bodies: self.log_bodies,
+            ping: self.ping,
+            layers: self.layers,
+            route_layers: self.route_layers,
+            required_roles: self.required_roles,
+            admin_roles: self.admin_roles,
+            confidential_roles: self.confidential_roles,
+            workers: self.workers,
+            user_ids: self.user_ids,
+            pseudonyms: self.pseudonyms,
+            #[cfg(feature = "cosign")]
+            cosign: self.cosign,
+            #[cfg(feature = "dev-auth")]
+            dev_tokens: self.dev_tokens,
+            #[cfg(feature = "federation")]
+            upstreams: self.upstreams,
+            #[cfg(feature = "standby")]
+            standby: self.standby,
+        }
+    }
+
+    /// Changes the format in which request and response bodies (including errors) are encoded.
+    ///
+    /// By default, bodies are encoded as [`Json`].
+    ///
+    /// # Arguments
+    /// - `wire`: The [`WireFormat`] to use.
+    ///
+    /// # Returns
+    /// A new AxumServer that encodes bodies using the given `wire` format.
+    #[inline]
+    pub fn with_wire_format<W2>(self, wire: W2) -> AxumServer<A, D, T, W2> {
+        AxumServer {
+            addr: self.addr,
+            auth: self.auth,
+            data: self.data,
+            transformer: self.transformer,
+            wire,
+            uploads: self.uploads,
+            syslog: self.syslog,
+            trusted_proxies: self.trusted_proxies,
+            maintenance: self.maintenance,
+            verify: self.verify,
+            bootstrap: self.bootstrap,
+            no_active: self.no_active,
+            headers: self.headers,
+            log_bodies: self.log_bodies,
+            ping: self.ping,
+            layers: self.layers,
+            route_layers: self.route_layers,
+            required_roles: self.required_roles,
+            admin_roles: self.admin_roles,
+            confidential_roles: self.confidential_roles,
+            workers: self.workers,
+            user_ids: self.user_ids,
+            pseudonyms: self.pseudonyms,
+            #[cfg(feature = "cosign")]
+            cosign: self.cosign,
+            #[cfg(feature = "dev-auth")]
+            dev_tokens: self.dev_tokens,
+            #[cfg(feature = "federation")]
+            upstreams: self.upstreams,
+            #[cfg(feature = "standby")]
+            standby: self.standby,
+        }
+    }
+
+    /// Remembers successful authorizations for a while, such that clients sending the same token
+    /// on every request don't need to be authorized from scratch every time.
+    ///
+    /// See [`CachedAuthResolver`] for the caveats.
+    ///
+    /// # Arguments
+    /// - `ttl`: How long to remember authorizations. Keep this short, as tokens remain accepted
+    ///   for this long after they expire (or are revoked other than through this server).
+    /// - `capacity`: The maximum number of tokens to remember.
+    ///
+    /// # Returns
+    /// A new AxumServer that caches the decisions of its current [`AuthResolver`].
+    #[inline]
+    pub fn with_auth_cache(self, ttl: Duration, capacity: usize) -> AxumServer<CachedAuthResolver<A>, D, T, W>
+    where
+        A: AuthResolver,
+    {
+        AxumServer {
+            addr: self.addr,
+            auth: CachedAuthResolver::new(self.auth, ttl, capacity),
+            data: self.data,
+            transformer: self.transformer,
+            wire: self.wire,
+            uploads: self.uploads,
+            syslog: self.syslog,
+            trusted_proxies: self.trusted_proxies,
+            maintenance: self.maintenance,
+            verify: self.verify,
+            bootstrap: self.bootstrap,
+            no_active: self.no_active,
+            headers: self.headers,
+            log_bodies: self.log_bodies,
+            ping: self.ping,
+            layers: self.layers,
+            route_layers: self.route_layers,
+            required_roles: self.required_roles,
+            admin_roles: self.admin_roles,
+            confidential_roles: self.confidential_roles,
+            workers: self.workers,
+            user_ids: self.user_ids,
+            pseudonyms: self.pseudonyms,
+            #[cfg(feature = "cosign")]
+            cosign: self.cosign,
+            #[cfg(feature = "dev-auth")]
+            dev_tokens: self.dev_tokens,
+            #[cfg(feature = "federation")]
+            upstreams: self.upstreams,
+            #[cfg(feature = "standby")]
+            standby: self.standby,
+        }
+    }
+
+    /// Forwards new records in the audit log to a syslog collector while [`serve()`](AxumServer::serve())ing.
+    ///
+    /// # Arguments
+    /// - `forwarder`: The [`SyslogForwarder`] describing where and how to forward records.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_syslog(mut self, forwarder: SyslogForwarder) -> Self {
+        self.syslog = Some(forwarder);
+        self
+    }
+
+    /// Trusts the `Forwarded` and `X-Forwarded-For` headers of requests coming from the given
+    /// proxies when determining the address of the client (e.g., for the audit log).
+    ///
+    /// # Arguments
+    /// - `proxies`: The addresses (as [`IpAddr`]) or ranges (as [`IpCidr`]) of the trusted
+    ///   proxies.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_trusted_proxies(mut self, proxies: impl IntoIterator<Item = impl Into<IpCidr>>) -> Self {
+        self.trusted_proxies.extend(proxies.into_iter().map(Into::into));
+        self
+    }
+
+    /// Runs [`DatabaseConnector::maintain()`] every day at the given time while
+    /// [`serve()`](AxumServer::serve())ing, e.g., during quiet hours.
+    ///
+    /// # Arguments
+    /// - `at`: The time of day (in UTC) at which to run maintenance.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_maintenance(mut self, at: NaiveTime) -> Self {
+        self.maintenance = Some(at);
+        self
+    }
+
+    /// [Verifies](DatabaseConnector::verify()) the consistency of the backend before
+    /// [`serve()`](AxumServer::serve())ing anything, refusing to start if it finds any problems.
+    ///
+    /// Every finding is logged, such that operators can fix them before trying again.
+    ///
+    /// Note that [`into_router()`](AxumServer::into_router()) cannot refuse to start; applications
+    /// embedding the policy store should call [`verify_backend()`](AxumServer::verify_backend())
+    /// themselves instead.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_startup_verification(mut self) -> Self {
+        self.verify = true;
+        self
+    }
+
+    /// Adds and activates the given policy when starting against an empty database, such that
+    /// fresh deployments never run without an active policy.
+    ///
+    /// Databases that already contain any version (even if none is active) are left alone.
+    ///
+    /// # Arguments
+    /// - `policy`: The [`BootstrapPolicy`] to add.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_bootstrap_policy(mut self, policy: BootstrapPolicy) -> Self {
+        self.bootstrap = Some(policy);
+        self
+    }
+
+    /// Changes what the server replies to requests for the content of the active policy while
+    /// no policy is active.
+    ///
+    /// By default, such requests are answered with 404 NOT FOUND.
+    ///
+    /// # Arguments
+    /// - `behavior`: The [`NoActivePolicy`] to apply.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_no_active_policy(mut self, behavior: NoActivePolicy) -> Self {
+        self.no_active = behavior;
+        self
+    }
+
+    /// Runs the given [`Worker`] in the background while [`serve()`](AxumServer::serve())ing.
+    ///
+    /// The worker is started before the server accepts connections, ticked at its own interval,
+    /// and stopped once the server stops. Note that workers are only run when serving through
+    /// the [`Server`] trait, not through [`serve_router()`](AxumServer::serve_router()).
+    ///
+    /// # Arguments
+    /// - `worker`: The [`Worker`] to run.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_worker(mut self, worker: impl 'static + Send + Sync + Worker) -> Self {
+        self.workers.push(erase_worker(worker));
+        self
+    }
+
+    /// Adds a header to every response, unless the handler already set it.
+    ///
+    /// Adding the same header multiple times sends all given values.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the header.
+    /// - `value`: Its value.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Adds common security headers to every response.
+    ///
+    /// Specifically, this tells clients to only use HTTPS for the next year
+    /// (`Strict-Transport-Security`), to not guess content types (`X-Content-Type-Options`) and
+    /// to not load any resources referenced by responses (`Content-Security-Policy`).
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_security_headers(self) -> Self {
+        self.with_header(STRICT_TRANSPORT_SECURITY, HeaderValue::from_static("max-age=31536000; includeSubDomains"))
+            .with_header(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"))
+            .with_header(CONTENT_SECURITY_POLICY, HeaderValue::from_static("default-src 'none'; frame-ancestors 'none'"))
+    }
+
+    /// Logs the raw body of requests that fail to parse at TRACE level.
+    ///
+    /// This is off by default, as bodies may contain secrets (e.g., credentials accidentally
+    /// pasted into a policy). Bodies are never sent back to the client either way.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_raw_body_logging(mut self) -> Self {
+        self.log_bodies = true;
+        self
+    }
+
+    /// Sets how many bytes chunked uploads may hold in memory.
+    ///
+    /// Chunks that would exceed either limit are refused with 413 PAYLOAD TOO LARGE. By default,
+    /// single uploads are limited to [`DEFAULT_MAX_UPLOAD_SIZE`](crate::DEFAULT_MAX_UPLOAD_SIZE)
+    /// and all uploads in progress together to
+    /// [`DEFAULT_MAX_TOTAL_UPLOAD_SIZE`](crate::DEFAULT_MAX_TOTAL_UPLOAD_SIZE).
+    ///
+    /// # Arguments
+    /// - `per_upload`: The maximum number of bytes in a single upload.
+    /// - `total`: The maximum number of bytes in all uploads in progress together.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_upload_limits(mut self, per_upload: u64, total: u64) -> Self {
+        self.uploads.max_upload_size = per_upload;
+        self.uploads.max_total_size = total;
+        self
+    }
+
+    /// Sets how many chunked uploads a single user may have in progress.
+    ///
+    /// Initiating more is refused with 409 CONFLICT. By default, users may have up to
+    /// [`DEFAULT_MAX_UPLOADS_PER_USER`](crate::DEFAULT_MAX_UPLOADS_PER_USER) uploads in progress.
+    ///
+    /// # Arguments
+    /// - `max`: The maximum number of uploads per user.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_max_uploads_per_user(mut self, max: usize) -> Self {
+        self.uploads.max_per_user = max;
+        self
+    }
+
+    /// Stops serving [`PING_PATH`].
+    ///
+    /// The endpoint is served by default, as it's what container healthchecks (e.g., Docker's
+    /// `HEALTHCHECK` or a Kubernetes liveness probe) can call without a token. It reveals nothing
+    /// but the name and version of the server, but some deployments may prefer to not even reveal
+    /// that to unauthenticated clients.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn without_ping(mut self) -> Self {
+        self.ping = false;
+        self
+    }
+
+    /// Wraps a tower [`Layer`] around all routes while [`serve()`](AxumServer::serve())ing
+    /// (e.g., for compression, timeouts or custom telemetry).
+    ///
+    /// This includes any routes added to the [`Router`] given to
+    /// [`serve_router()`](AxumServer::serve_router()). Layers added later wrap around layers
+    /// added earlier.
+    ///
+    /// # Arguments
+    /// - `layer`: The [`Layer`] to add.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_layer<L>(mut self, layer: L) -> Self
+    where
+        L: 'static + Clone + Send + Sync + Layer<Route>,
+        L::Service: 'static + Clone + Send + Sync + Service<AxumRequest>,
+        <L::Service as Service<AxumRequest>>::Response: 'static + IntoResponse,
+        <L::Service as Service<AxumRequest>>::Error: 'static + Into<Infallible>,
+        <L::Service as Service<AxumRequest>>::Future: 'static + Send,
+    {
+        self.layers.push(erase_layer(layer));
+        self
+    }
+
+    /// Wraps a tower [`Layer`] around a single endpoint of the server.
+    ///
+    /// The layer runs before the request is authenticated, and inside any layers added with
+    /// [`with_layer()`](AxumServer::with_layer()). Layers added later wrap around layers added
+    /// earlier.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The [`EndpointPath`] of the endpoint to wrap (e.g., [`ADD_VERSION_PATH`]).
+    /// - `layer`: The [`Layer`] to add.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_route_layer<L>(mut self, endpoint: &EndpointPath, layer: L) -> Self
+    where
+        L: 'static + Clone + Send + Sync + Layer<Route>,
+        L::Service: 'static + Clone + Send + Sync + Service<AxumRequest>,
+        <L::Service as Service<AxumRequest>>::Response: 'static + IntoResponse,
+        <L::Service as Service<AxumRequest>>::Error: 'static + Into<Infallible>,
+        <L::Service as Service<AxumRequest>>::Future: 'static + Send,
+    {
+        self.route_layers.push(((endpoint.method.clone(), endpoint.path), erase_layer(layer)));
+        self
+    }
+
+    /// Only allows users with at least one of the given [roles](specifications::metadata::User::roles) to call an endpoint,
+    /// e.g., to only let policy administrators activate policies.
+    ///
+    /// Other users are answered with 403 FORBIDDEN. Roles are read from the [user](AsUser) in the
+    /// context of the [`AuthResolver`]; users of resolvers that don't assign any are never allowed
+    /// to call the endpoint. Calling this multiple times for the same endpoint requires a role
+    /// from every call.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The [`EndpointPath`] of the endpoint to restrict (e.g., [`ACTIVATE_PATH`]).
+    /// - `roles`: The roles that allow calling the endpoint.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_required_roles(mut self, endpoint: &EndpointPath, roles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.required_roles.push(((endpoint.method.clone(), endpoint.path), roles.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// Sets the roles of which users need at least one to call the administrative endpoints, i.e.,
+    /// [`PSEUDONYMIZE_USER_PATH`], [`ADD_KEY_PATH`], [`REVOKE_KEY_PATH`], [`REVOKE_TOKENS_PATH`],
+    /// [`FREEZE_VERSION_PATH`], [`UNFREEZE_VERSION_PATH`], [`ADD_TEMPLATE_PATH`],
+    /// [`PRUNE_ACTIVATIONS_PATH`], [`MAINTAIN_PATH`] and [`PROMOTE_STANDBY_PATH`].
+    ///
+    /// These endpoints are always restricted, on top of any
+    /// [required roles](AxumServer::with_required_roles()). By default, only users with the
+    /// [`DEFAULT_ADMIN_ROLE`] may call them; giving no roles at all locks them for everyone.
+    ///
+    /// # Arguments
+    /// - `roles`: The roles that allow calling the administrative endpoints.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_admin_roles(mut self, roles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.admin_roles = roles.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the roles of which users need at least one to read the content of versions marked as
+    /// [confidential](specifications::metadata::AttachedMetadata::confidential).
+    ///
+    /// Users with one of the [admin roles](AxumServer::with_admin_roles()) may always read it.
+    /// Everyone else can still see the metadata of confidential versions, but gets a 403 FORBIDDEN
+    /// when asking for their content. By default, only admins may read it.
+    ///
+    /// # Arguments
+    /// - `roles`: The roles that allow reading confidential content.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_confidential_roles(mut self, roles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.confidential_roles = roles.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Normalizes the IDs of authorized users to the given format, refusing requests of users
+    /// whose ID does not adhere to it.
+    ///
+    /// Only the user in the [`AccessContext`](specifications::metadata::AccessContext) handed to
+    /// the connector is normalized, not the one in the [`AuthResolver::Context`]. By default, IDs
+    /// are used as-is.
+    ///
+    /// # Arguments
+    /// - `format`: The [`UserIdFormat`] to normalize to.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_user_id_format(mut self, format: impl 'static + Send + Sync + UserIdFormat) -> Self {
+        self.user_ids = Arc::new(format);
+        self
+    }
+
+    /// Changes the key from which the pseudonyms of erased users are derived.
+    ///
+    /// By default, a random key is generated, so the same user gets a different pseudonym after
+    /// the server restarts. Set a fixed key to keep them stable, e.g., to recognise a user that is
+    /// erased twice or to share pseudonyms with a [standby](AxumServer::with_standby()).
+    ///
+    /// # Arguments
+    /// - `key`: The [`PseudonymKey`] to derive pseudonyms with.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_pseudonym_key(mut self, key: PseudonymKey) -> Self {
+        self.pseudonyms = key;
+        self
+    }
+
+    /// Serves [`DEV_TOKEN_PATH`], which mints tokens for any user who asks.
+    ///
+    /// This is meant for testing against a token-protected API locally without standing up an
+    /// identity provider. **Never enable this in production:** the endpoint is unauthenticated,
+    /// so anyone who can reach the server can act as anyone.
+    ///
+    /// # Arguments
+    /// - `minter`: The [`DevTokenMinter`] that signs the tokens. Its key should be accepted by
+    ///   the [`AuthResolver`].
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[cfg(feature = "dev-auth")]
+    #[inline]
+    pub fn with_dev_tokens(mut self, minter: DevTokenMinter) -> Self {
+        self.dev_tokens = Some(minter);
+        self
+    }
+
+    /// Verifies cosign signatures over the contents of [imported](AxumServer::import_version())
+    /// versions, recording the result in their
+    /// [verification](specifications::metadata::Metadata::verification).
+    ///
+    /// Without this, any verification sent along with imported versions is dropped, and so is
+    /// their signature.
+    ///
+    /// # Arguments
+    /// - `verifier`: The [`CosignVerifier`] that knows which identities to trust, and whether to
+    ///   reject versions that aren't signed by one of them.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[cfg(feature = "cosign")]
+    #[inline]
+    pub fn with_cosign_verification(mut self, verifier: CosignVerifier) -> Self {
+        self.cosign = Some(verifier);
+        self
+    }
+
+    /// Adds an upstream store to serve in the federated view at [`GET_FEDERATED_VERSIONS_PATH`].
+    ///
+    /// The federated view is read-only, and only served if at least one upstream is added. Any
+    /// credentials needed to read from the upstream should be configured on its `client`.
+    ///
+    /// # Arguments
+    /// - `name`: A name for the upstream, used to key its versions in the federated view.
+    /// - `client`: A [`reqwest_client::Client`] that reads from the upstream.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[cfg(feature = "federation")]
+    #[inline]
+    pub fn with_upstream(mut self, name: impl Into<String>, client: reqwest_client::Client) -> Self {
+        self.upstreams.push((name.into(), client));
+        self
+    }
+
+    /// Runs the server as a warm standby of another store.
+    ///
+    /// The server then replicates the changes made to the primary and refuses changes made to
+    /// itself, until it is promoted at [`PROMOTE_STANDBY_PATH`] (or automatically, if so
+    /// configured). Note that promotion is not remembered across restarts, so restart a
+    /// promoted server without a standby.
+    ///
+    /// # Arguments
+    /// - `standby`: The [`Standby`] describing the primary and when to promote.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[cfg(feature = "standby")]
+    #[inline]
+    pub fn with_standby(mut self, standby: Standby) -> Self {
+        self.standby = Some(standby);
+        self
+    }
+
+    /// Wraps the layers registered for the given endpoint around its router.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The [`EndpointPath`] of the endpoint served by `router`.
+    /// - `router`: The [`Router`] serving (only) that endpoint.
+    ///
+    /// # Returns
+    /// The given `router`, wrapped in the layers registered with
+    /// [`with_route_layer()`](AxumServer::with_route_layer()).
+    fn route_layered(&self, endpoint: &EndpointPath, router: Router) -> Router {
+        self.route_layers
+            .iter()
+            .filter(|((method, path), _)| *method == endpoint.method && *path == endpoint.path)
+            .fold(router, |router, (_, layer)| layer(router))
+    }
 }
-impl<A, D> AxumServer<A, D>
+impl<A, D, T, W> AxumServer<A, D, T, W>
 where
     A: 'static + Send + Sync + AuthResolver,
-    A::Context: 'static + Send + Sync + Clone,
+    A::Context: 'static + Send + Sync + Clone + AsUser,
     A::ClientError: 'static,
     A::ServerError: 'static,
     D: 'static + Send + Sync + DatabaseConnector,
-    D::Content: Send + DeserializeOwned + Serialize,
+    D::Content: Send + DeserializeOwned + Serialize + MaybeDenyAll,
     for<'s> D::Connection<'s>: Send,
+    T: 'static + Send + Sync + ContentTransformer<D::Content>,
+    W: WireFormat,
 {
     /// Builds an [`axum`] [`Router`] that encodes the paths of this server.
     ///
@@ -120,10 +956,18 @@ where
             .route(GET_ACTIVE_VERSION_PATH.path, GET_ACTIVE_VERSION_PATH.handler(Self::get_active_version))
             .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
             .with_state(this.clone());
+        let get_active_content: Router = Router::new()
+            .route(GET_ACTIVE_CONTENT_PATH.path, GET_ACTIVE_CONTENT_PATH.handler(Self::get_active_content))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
         let get_activator: Router = Router::new()
             .route(GET_ACTIVATOR_VERSION_PATH.path, GET_ACTIVATOR_VERSION_PATH.handler(Self::get_activator))
             .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
             .with_state(this.clone());
+        let get_activations: Router = Router::new()
+            .route(GET_ACTIVATIONS_PATH.path, GET_ACTIVATIONS_PATH.handler(Self::get_activations))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
         let get_version_metadata: Router = Router::new()
             .route(GET_VERSION_METADATA_PATH.path, GET_VERSION_METADATA_PATH.handler(Self::get_version_metadata))
             .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
@@ -132,18 +976,442 @@ where
             .route(GET_VERSION_CONTENT_PATH.path, GET_VERSION_CONTENT_PATH.handler(Self::get_version_content))
             .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
             .with_state(this.clone());
-        Router::<()>::new()
-            .merge(add_version)
-            .merge(activate)
-            .merge(deactivate)
-            .merge(get_versions)
-            .merge(get_active_version)
-            .merge(get_activator)
-            .merge(get_version_metadata)
-            .merge(get_version_content)
+        let get_version_content_raw: Router = Router::new()
+            .route(GET_VERSION_CONTENT_RAW_PATH.path, GET_VERSION_CONTENT_RAW_PATH.handler(Self::get_version_content_raw))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_version_provenance: Router = Router::new()
+            .route(GET_VERSION_PROVENANCE_PATH.path, GET_VERSION_PROVENANCE_PATH.handler(Self::get_version_provenance))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let freeze_version: Router = Router::new()
+            .route(FREEZE_VERSION_PATH.path, FREEZE_VERSION_PATH.handler(Self::freeze_version))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let unfreeze_version: Router = Router::new()
+            .route(UNFREEZE_VERSION_PATH.path, UNFREEZE_VERSION_PATH.handler(Self::unfreeze_version))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let add_template: Router = Router::new()
+            .route(ADD_TEMPLATE_PATH.path, ADD_TEMPLATE_PATH.handler(Self::add_template))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_templates: Router = Router::new()
+            .route(GET_TEMPLATES_PATH.path, GET_TEMPLATES_PATH.handler(Self::get_templates))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let instantiate_template: Router = Router::new()
+            .route(INSTANTIATE_TEMPLATE_PATH.path, INSTANTIATE_TEMPLATE_PATH.handler(Self::instantiate_template))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let add_schema: Router = Router::new()
+            .route(ADD_SCHEMA_PATH.path, ADD_SCHEMA_PATH.handler(Self::add_schema))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_schemas: Router = Router::new()
+            .route(GET_SCHEMAS_PATH.path, GET_SCHEMAS_PATH.handler(Self::get_schemas))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let create_branch: Router = Router::new()
+            .route(CREATE_BRANCH_PATH.path, CREATE_BRANCH_PATH.handler(Self::create_branch))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_branches: Router = Router::new()
+            .route(GET_BRANCHES_PATH.path, GET_BRANCHES_PATH.handler(Self::get_branches))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let initiate_upload: Router = Router::new()
+            .route(INITIATE_UPLOAD_PATH.path, INITIATE_UPLOAD_PATH.handler(Self::initiate_upload))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let upload_chunk: Router = Router::new()
+            .route(UPLOAD_CHUNK_PATH.path, UPLOAD_CHUNK_PATH.handler(Self::upload_chunk))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_upload: Router = Router::new()
+            .route(GET_UPLOAD_PATH.path, GET_UPLOAD_PATH.handler(Self::get_upload))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let finalize_upload: Router = Router::new()
+            .route(FINALIZE_UPLOAD_PATH.path, FINALIZE_UPLOAD_PATH.handler(Self::finalize_upload))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let cancel_upload: Router = Router::new()
+            .route(CANCEL_UPLOAD_PATH.path, CANCEL_UPLOAD_PATH.handler(Self::cancel_upload))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_audit_log: Router = Router::new()
+            .route(GET_AUDIT_LOG_PATH.path, GET_AUDIT_LOG_PATH.handler(Self::get_audit_log))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let verify_audit_log: Router = Router::new()
+            .route(VERIFY_AUDIT_LOG_PATH.path, VERIFY_AUDIT_LOG_PATH.handler(Self::verify_audit_log))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let export_audit_log: Router = Router::new()
+            .route(EXPORT_AUDIT_LOG_PATH.path, EXPORT_AUDIT_LOG_PATH.handler(Self::export_audit_log))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let promote: Router = Router::new()
+            .route(PROMOTE_PATH.path, PROMOTE_PATH.handler(Self::promote))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let import_version: Router = Router::new()
+            .route(IMPORT_VERSION_PATH.path, IMPORT_VERSION_PATH.handler(Self::import_version))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let pseudonymize_user: Router = Router::new()
+            .route(PSEUDONYMIZE_USER_PATH.path, PSEUDONYMIZE_USER_PATH.handler(Self::pseudonymize_user))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let add_decision: Router = Router::new()
+            .route(ADD_DECISION_PATH.path, ADD_DECISION_PATH.handler(Self::add_decision))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_decisions: Router = Router::new()
+            .route(GET_DECISIONS_PATH.path, GET_DECISIONS_PATH.handler(Self::get_decisions))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let count_decisions: Router = Router::new()
+            .route(COUNT_DECISIONS_PATH.path, COUNT_DECISIONS_PATH.handler(Self::count_decisions))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_decision: Router = Router::new()
+            .route(GET_DECISION_PATH.path, GET_DECISION_PATH.handler(Self::get_decision))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_changes: Router = Router::new()
+            .route(GET_CHANGES_PATH.path, GET_CHANGES_PATH.handler(Self::get_changes))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let prune_activations: Router = Router::new()
+            .route(PRUNE_ACTIVATIONS_PATH.path, PRUNE_ACTIVATIONS_PATH.handler(Self::prune_activations))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let maintain: Router = Router::new()
+            .route(MAINTAIN_PATH.path, MAINTAIN_PATH.handler(Self::maintain))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let add_key: Router = Router::new()
+            .route(ADD_KEY_PATH.path, ADD_KEY_PATH.handler(Self::add_key))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_keys: Router = Router::new()
+            .route(GET_KEYS_PATH.path, GET_KEYS_PATH.handler(Self::get_keys))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let revoke_key: Router = Router::new()
+            .route(REVOKE_KEY_PATH.path, REVOKE_KEY_PATH.handler(Self::revoke_key))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let revoke_tokens: Router = Router::new()
+            .route(REVOKE_TOKENS_PATH.path, REVOKE_TOKENS_PATH.handler(Self::revoke_tokens))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let get_revocations: Router = Router::new()
+            .route(GET_REVOCATIONS_PATH.path, GET_REVOCATIONS_PATH.handler(Self::get_revocations))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        let routes: Router = Router::<()>::new()
+            .merge(this.route_layered(&ADD_VERSION_PATH, add_version))
+            .merge(this.route_layered(&ACTIVATE_PATH, activate))
+            .merge(this.route_layered(&DEACTIVATE_PATH, deactivate))
+            .merge(Self::deny_all_routes(&this))
+            .merge(this.route_layered(&GET_VERSIONS_PATH, get_versions))
+            .merge(this.route_layered(&GET_ACTIVE_VERSION_PATH, get_active_version))
+            .merge(this.route_layered(&GET_ACTIVE_CONTENT_PATH, get_active_content))
+            .merge(this.route_layered(&GET_ACTIVATOR_VERSION_PATH, get_activator))
+            .merge(this.route_layered(&GET_ACTIVATIONS_PATH, get_activations))
+            .merge(this.route_layered(&GET_VERSION_METADATA_PATH, get_version_metadata))
+            .merge(this.route_layered(&GET_VERSION_CONTENT_PATH, get_version_content))
+            .merge(this.route_layered(&GET_VERSION_CONTENT_RAW_PATH, get_version_content_raw))
+            .merge(this.route_layered(&GET_VERSION_PROVENANCE_PATH, get_version_provenance))
+            .merge(this.route_layered(&FREEZE_VERSION_PATH, freeze_version))
+            .merge(this.route_layered(&UNFREEZE_VERSION_PATH, unfreeze_version))
+            .merge(this.route_layered(&ADD_TEMPLATE_PATH, add_template))
+            .merge(this.route_layered(&GET_TEMPLATES_PATH, get_templates))
+            .merge(this.route_layered(&INSTANTIATE_TEMPLATE_PATH, instantiate_template))
+            .merge(this.route_layered(&ADD_SCHEMA_PATH, add_schema))
+            .merge(this.route_layered(&GET_SCHEMAS_PATH, get_schemas))
+            .merge(this.route_layered(&CREATE_BRANCH_PATH, create_branch))
+            .merge(this.route_layered(&GET_BRANCHES_PATH, get_branches))
+            .merge(this.route_layered(&PROMOTE_PATH, promote))
+            .merge(this.route_layered(&IMPORT_VERSION_PATH, import_version))
+            .merge(this.route_layered(&INITIATE_UPLOAD_PATH, initiate_upload))
+            .merge(this.route_layered(&UPLOAD_CHUNK_PATH, upload_chunk))
+            .merge(this.route_layered(&GET_UPLOAD_PATH, get_upload))
+            .merge(this.route_layered(&FINALIZE_UPLOAD_PATH, finalize_upload))
+            .merge(this.route_layered(&CANCEL_UPLOAD_PATH, cancel_upload))
+            .merge(this.route_layered(&GET_AUDIT_LOG_PATH, get_audit_log))
+            .merge(this.route_layered(&VERIFY_AUDIT_LOG_PATH, verify_audit_log))
+            .merge(this.route_layered(&EXPORT_AUDIT_LOG_PATH, export_audit_log))
+            .merge(this.route_layered(&PSEUDONYMIZE_USER_PATH, pseudonymize_user))
+            .merge(this.route_layered(&ADD_DECISION_PATH, add_decision))
+            .merge(this.route_layered(&GET_DECISIONS_PATH, get_decisions))
+            .merge(this.route_layered(&COUNT_DECISIONS_PATH, count_decisions))
+            .merge(this.route_layered(&GET_DECISION_PATH, get_decision))
+            .merge(this.route_layered(&GET_CHANGES_PATH, get_changes))
+            .merge(this.route_layered(&PRUNE_ACTIVATIONS_PATH, prune_activations))
+            .merge(this.route_layered(&MAINTAIN_PATH, maintain))
+            .merge(this.route_layered(&ADD_KEY_PATH, add_key))
+            .merge(this.route_layered(&GET_KEYS_PATH, get_keys))
+            .merge(this.route_layered(&REVOKE_KEY_PATH, revoke_key))
+            .merge(this.route_layered(&REVOKE_TOKENS_PATH, revoke_tokens))
+            .merge(this.route_layered(&GET_REVOCATIONS_PATH, get_revocations));
+        Self::guard_standby(&this, routes)
+            .merge(Self::ping_routes(&this))
+            .merge(Self::dev_token_routes(&this))
+            .merge(Self::federation_routes(&this))
+            .merge(Self::standby_routes(&this))
+    }
+
+    /// Builds a [`Router`] serving [`PING_PATH`], unless [disabled](AxumServer::without_ping()).
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    ///
+    /// # Returns
+    /// A [`Router`] that answers pings, or nothing if they're disabled.
+    fn ping_routes(this: &Arc<Self>) -> Router {
+        if !this.ping {
+            return Router::new();
+        }
+
+        // NOTE: No auth check, as healthchecks typically have no token to send
+        let ping: Router = Router::new().route(PING_PATH.path, PING_PATH.handler(Self::ping)).with_state(this.clone());
+        this.route_layered(&PING_PATH, ping)
+    }
+
+    /// Builds a [`Router`] serving [`DENY_ALL_PATH`].
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    ///
+    /// # Returns
+    /// A [`Router`] that activates the built-in deny-all policy.
+    #[cfg(feature = "deny-all")]
+    fn deny_all_routes(this: &Arc<Self>) -> Router {
+        let deny_all: Router = Router::new()
+            .route(DENY_ALL_PATH.path, DENY_ALL_PATH.handler(Self::deny_all))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        this.route_layered(&DENY_ALL_PATH, deny_all)
+    }
+
+    /// Builds an empty [`Router`], as the deny-all policy is not compiled in.
+    #[cfg(not(feature = "deny-all"))]
+    #[inline]
+    fn deny_all_routes(_this: &Arc<Self>) -> Router { Router::new() }
+
+    /// Builds a [`Router`] serving [`DEV_TOKEN_PATH`], if [enabled](AxumServer::with_dev_tokens()).
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    ///
+    /// # Returns
+    /// A [`Router`] that serves development tokens, or nothing if they're not enabled.
+    #[cfg(feature = "dev-auth")]
+    fn dev_token_routes(this: &Arc<Self>) -> Router {
+        if this.dev_tokens.is_none() {
+            return Router::new();
+        }
+        tracing::warn!("Serving development tokens at '{}'; do NOT use this in production!", DEV_TOKEN_PATH.path);
+
+        // NOTE: No auth check, as the whole point is getting a token in the first place
+        let dev_token: Router = Router::new().route(DEV_TOKEN_PATH.path, DEV_TOKEN_PATH.handler(Self::dev_token)).with_state(this.clone());
+        this.route_layered(&DEV_TOKEN_PATH, dev_token)
+    }
+
+    /// Builds an empty [`Router`], as development tokens are not compiled in.
+    #[cfg(not(feature = "dev-auth"))]
+    #[inline]
+    fn dev_token_routes(_this: &Arc<Self>) -> Router { Router::new() }
+
+    /// Builds a [`Router`] serving [`GET_FEDERATED_VERSIONS_PATH`], if any
+    /// [upstreams](AxumServer::with_upstream()) are configured.
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    ///
+    /// # Returns
+    /// A [`Router`] that serves the federated view, or nothing if there's nothing to federate.
+    #[cfg(feature = "federation")]
+    fn federation_routes(this: &Arc<Self>) -> Router {
+        if this.upstreams.is_empty() {
+            return Router::new();
+        }
+        let get_federated_versions: Router = Router::new()
+            .route(GET_FEDERATED_VERSIONS_PATH.path, GET_FEDERATED_VERSIONS_PATH.handler(Self::get_federated_versions))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        this.route_layered(&GET_FEDERATED_VERSIONS_PATH, get_federated_versions)
+    }
+
+    /// Builds an empty [`Router`], as federation is not compiled in.
+    #[cfg(not(feature = "federation"))]
+    #[inline]
+    fn federation_routes(_this: &Arc<Self>) -> Router { Router::new() }
+
+    /// Builds a [`Router`] serving [`PROMOTE_STANDBY_PATH`], if a
+    /// [standby](AxumServer::with_standby()) is configured.
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    ///
+    /// # Returns
+    /// A [`Router`] that promotes the standby, or nothing if this server isn't one.
+    #[cfg(feature = "standby")]
+    fn standby_routes(this: &Arc<Self>) -> Router {
+        if this.standby.is_none() {
+            return Router::new();
+        }
+        let promote_standby: Router = Router::new()
+            .route(PROMOTE_STANDBY_PATH.path, PROMOTE_STANDBY_PATH.handler(Self::promote_standby))
+            .layer(axum::middleware::from_fn_with_state(this.clone(), Self::check))
+            .with_state(this.clone());
+        this.route_layered(&PROMOTE_STANDBY_PATH, promote_standby)
+    }
+
+    /// Builds an empty [`Router`], as standby mode is not compiled in.
+    #[cfg(not(feature = "standby"))]
+    #[inline]
+    fn standby_routes(_this: &Arc<Self>) -> Router { Router::new() }
+
+    /// Refuses changes made through the given router while this server is an unpromoted
+    /// [standby](AxumServer::with_standby()).
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    /// - `router`: The [`Router`] to guard.
+    ///
+    /// # Returns
+    /// The given `router`, refusing anything but reads while replicating.
+    #[cfg(feature = "standby")]
+    fn guard_standby(this: &Arc<Self>, router: Router) -> Router {
+        if this.standby.is_none() {
+            return router;
+        }
+        router.layer(axum::middleware::from_fn_with_state(this.clone(), Self::check_standby))
+    }
+
+    /// Returns the given [`Router`] as-is, as standby mode is not compiled in.
+    #[cfg(not(feature = "standby"))]
+    #[inline]
+    fn guard_standby(_this: &Arc<Self>, router: Router) -> Router { router }
+
+    /// Builds a [`Router`] that serves the policy store as part of a larger [`axum`] application.
+    ///
+    /// Unlike [`routes()`](AxumServer::routes()), the returned router includes the layers and
+    /// headers configured for all routes, and can be merged into (or
+    /// [nested](Router::nest()) under a prefix in) a router with any state `S`. Any background
+    /// tasks (e.g., [syslog forwarding](AxumServer::with_syslog()) or
+    /// [maintenance](AxumServer::with_maintenance())) are spawned on the current tokio runtime.
+    /// This includes adding the [bootstrap policy](AxumServer::with_bootstrap_policy()), so
+    /// requests may briefly see an empty database; failing to add it is logged instead of
+    /// returned.
+    ///
+    /// Note that the application must be served with
+    /// [`into_make_service_with_connect_info::<SocketAddr>()`](Router::into_make_service_with_connect_info()),
+    /// as the policy store uses the address of the client (e.g., for the audit log). The address
+    /// given to [`AxumServer::new()`] is not used.
+    ///
+    /// The backend is not [verified](AxumServer::with_startup_verification()) by this function,
+    /// as it has no way to refuse to start. Call [`verify_backend()`](AxumServer::verify_backend())
+    /// before building the router to do so.
+    ///
+    /// # Returns
+    /// A [`Router`] serving the policy store.
+    ///
+    /// # Panics
+    /// This function panics if it is called outside of a tokio runtime.
+    pub fn into_router<S>(self) -> Router<S>
+    where
+        S: 'static + Clone + Send + Sync,
+    {
+        let this: Arc<Self> = Arc::new(self);
+        if this.verify {
+            tracing::warn!("Startup verification is not done for embedded routers; call `AxumServer::verify_backend()` before building them");
+        }
+        if this.bootstrap.is_some() {
+            let this: Arc<Self> = this.clone();
+            tokio::spawn(async move {
+                if let Err(err) = Self::bootstrap(&this).await {
+                    error!("{}", trace!(("Failed to bootstrap backend database"), err));
+                }
+            });
+        }
+        Self::spawn_tasks(&this);
+        this.wrap_router(Self::routes(this.clone())).with_state(())
+    }
+
+    /// [Verifies](DatabaseConnector::verify()) the consistency of the backend, logging every
+    /// finding.
+    ///
+    /// This is what [`serve()`](AxumServer::serve()) does before serving anything if
+    /// [startup verification](AxumServer::with_startup_verification()) is enabled. Applications that
+    /// embed the policy store with [`into_router()`](AxumServer::into_router()) may call this
+    /// themselves before building the router (regardless of whether it's enabled).
+    ///
+    /// # Errors
+    /// This function errors if the backend is inconsistent, or if we failed to check it.
+    pub async fn verify_backend(&self) -> Result<(), Error> {
+        info!("Verifying backend database...");
+        match self.data.verify().await {
+            Ok(findings) if findings.is_empty() => {
+                info!("Backend database is consistent");
+                Ok(())
+            },
+            Ok(findings) => {
+                for finding in &findings {
+                    error!("{finding}");
+                }
+                Err(Error::Inconsistent { findings })
+            },
+            Err(err) => Err(Error::Verify { err: trace!(("Failed to verify backend database"), err).to_string() }),
+        }
+    }
+
+    /// Spawns the background tasks of the server (if any are configured).
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    fn spawn_tasks(this: &Arc<Self>) {
+        // Start forwarding the audit log in the background, if configured
+        if this.syslog.is_some() {
+            tokio::spawn(Self::forward_audit_log(this.clone()));
+        }
+        // Likewise for scheduled maintenance
+        if this.maintenance.is_some() {
+            tokio::spawn(Self::run_maintenance(this.clone()));
+        }
+        // And for replicating from a primary
+        #[cfg(feature = "standby")]
+        if this.standby.is_some() {
+            tokio::spawn(Self::replicate(this.clone()));
+        }
     }
 }
-impl<A, D> AxumServer<A, D> {
+impl<A, D, T, W> AxumServer<A, D, T, W> {
+    /// Wraps the layers and default headers configured for all routes around the given router.
+    ///
+    /// # Arguments
+    /// - `router`: The [`Router`] to wrap.
+    ///
+    /// # Returns
+    /// The given `router`, wrapped in the layers added with [`with_layer()`](AxumServer::with_layer())
+    /// and the headers added with [`with_header()`](AxumServer::with_header()). Finally, any error
+    /// response is turned into an RFC 7807 problem.
+    fn wrap_router(&self, router: Router<()>) -> Router<()> {
+        let router: Router<()> = self.layers.iter().fold(router, |router, layer| layer(router));
+        let router: Router<()> = if !self.headers.is_empty() {
+            router.layer(axum::middleware::map_response_with_state(Arc::new(self.headers.clone()), set_default_headers))
+        } else {
+            router
+        };
+        // NOTE: Outermost, such that errors produced by any layer are turned into problems too
+        router.layer(axum::middleware::from_fn(normalize_errors))
+    }
+
     /// Runs the given [`axum`] [`Router`].
     ///
     /// # Arguments
@@ -155,8 +1423,36 @@ impl<A, D> AxumServer<A, D> {
     ///
     /// # Errors
     /// This function may fail if it failed to bind the server at the internal address.
+    #[inline]
     pub async fn serve_router(this: Arc<Self>, router: Router<()>) -> Result<(), Error> {
+        Self::serve_router_with(this, router, (), std::future::pending()).await
+    }
+
+    /// Runs the given [`axum`] [`Router`] until the given `shutdown` future completes.
+    ///
+    /// Note that connections that are still being served when `shutdown` completes are not
+    /// waited for; they are left to finish (or not) in their own tasks.
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    /// - `router`: The [`Router`] to run.
+    /// - `hooks`: Some [`ServerHooks`] to notify when the server has bound its address and when
+    ///   it stops.
+    /// - `shutdown`: A future that, when it completes, stops accepting new connections.
+    ///
+    /// # Returns
+    /// This function does not return until `shutdown` completes.
+    ///
+    /// # Errors
+    /// This function may fail if it failed to bind the server at the internal address.
+    pub async fn serve_router_with(
+        this: Arc<Self>,
+        router: Router<()>,
+        mut hooks: impl ServerHooks,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<(), Error> {
         let span = span!(Level::INFO, "AxumServer::serve_router", state = "starting", client = Empty);
+        let router: Router<()> = this.wrap_router(router);
         let router: IntoMakeServiceWithConnectInfo<Router, SocketAddr> = Router::<()>::into_make_service_with_connect_info(router);
 
         // Bind the TCP Listener
@@ -165,13 +1461,26 @@ impl<A, D> AxumServer<A, D> {
             Ok(listener) => listener,
             Err(err) => return Err(Error::ListenerBind { addr: this.addr, err }),
         };
+        // NOTE: Might differ from `this.addr` if, e.g., port 0 was given
+        let local_addr: SocketAddr = listener.local_addr().map_err(|err| Error::ListenerAddr { addr: this.addr, err })?;
+        hooks.on_start(local_addr);
 
         // Accept new connections!
-        info!("Initialization OK, awaiting connections...");
+        info!("Initialization OK, awaiting connections on '{local_addr}'...");
         span.record("state", "running");
+        let mut shutdown = pin!(shutdown);
         loop {
-            // Accept a new connection
-            let (socket, remote_addr): (TcpStream, SocketAddr) = match listener.accept().await {
+            // Accept a new connection (or stop)
+            let res = match select(pin!(listener.accept()), shutdown.as_mut()).await {
+                Either::Left((res, _)) => res,
+                Either::Right(((), _)) => {
+                    info!("Shutdown requested, no longer accepting connections");
+                    span.record("state", "stopped");
+                    hooks.on_stop();
+                    return Ok(());
+                },
+            };
+            let (socket, remote_addr): (TcpStream, SocketAddr) = match res {
                 Ok(res) => res,
                 Err(err) => {
                     error!("{}", trace!(("Failed to accept incoming connection"), err));
@@ -204,26 +1513,47 @@ impl<A, D> AxumServer<A, D> {
         }
     }
 }
-impl<A, D> Server for AxumServer<A, D>
+impl<A, D, T, W> Server for AxumServer<A, D, T, W>
 where
     A: 'static + Send + Sync + AuthResolver,
-    A::Context: 'static + Send + Sync + Clone,
+    A::Context: 'static + Send + Sync + Clone + AsUser,
     A::ClientError: 'static,
     A::ServerError: 'static,
     D: 'static + Send + Sync + DatabaseConnector,
-    D::Content: Send + DeserializeOwned + Serialize,
+    D::Content: Send + DeserializeOwned + Serialize + MaybeDenyAll,
     for<'s> D::Connection<'s>: Send,
+    T: 'static + Send + Sync + ContentTransformer<D::Content>,
+    W: WireFormat,
 {
     type Error = Error;
 
-    fn serve(self) -> impl Future<Output = Result<(), Self::Error>> {
+    fn serve_with(mut self, hooks: impl ServerHooks, shutdown: impl Future<Output = ()>) -> impl Future<Output = Result<(), Self::Error>> {
+        let workers: Vec<WorkerTask> = std::mem::take(&mut self.workers);
         let this: Arc<Self> = Arc::new(self);
         async move {
             let _span = span!(Level::INFO, "AxumServer::serve");
 
-            // Simply depend on the two halves of the equation
+            // Refuse to serve an inconsistent backend, if asked to check
+            if this.verify {
+                this.verify_backend().await?;
+            }
+
+            // Make sure there's an active policy in a fresh database, if asked to
+            Self::bootstrap(&this).await?;
+
+            // Start any background tasks
+            Self::spawn_tasks(&this);
+
+            // Run the workers alongside the server, stopping them once it stops
+            let (stop, stopped) = stop_signal();
+            let workers = join_all(workers.into_iter().map(|worker| worker(stopped.clone())));
             let router: Router<()> = Self::routes(this.clone());
-            Self::serve_router(this, router).await
+            let server = async move {
+                let res = Self::serve_router_with(this, router, hooks, shutdown).await;
+                drop(stop);
+                res
+            };
+            join(server, workers).await.0
         }
     }
 }
@@ -0,0 +1,492 @@
+//  STANDBY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:55:48
+//  Last edited:
+//    17 Oct 2026, 02:16:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements running the server as a warm standby, which replicates
+//!   the changes of a primary until it is promoted to take over.
+//
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::extract::{Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use error_trace::trace;
+use futures::lock::Mutex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use specifications::audit::{Added, AuditAction, Change};
+use specifications::databaseconn::{ClientError, DatabaseConnection as _};
+use specifications::metadata::{Metadata, User};
+use specifications::revocation::AttachedRevocation;
+use specifications::{AuthResolver, ContentTransformer, DatabaseConnector};
+use thiserror::Error;
+use tracing::{Level, debug, error, info, span, warn};
+
+use crate::server::AxumServer;
+use crate::spec::{ApiErrorCode, GetChangesResponse};
+use crate::wire::WireFormat;
+
+
+/***** CONSTANTS *****/
+/// The name of the event cursor under which the standby remembers how far it has replicated.
+const STANDBY_CURSOR: &str = "standby";
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors emitted when replicating a change from the primary.
+#[derive(Debug, Error)]
+enum ReplicationError {
+    /// Failed to apply the change to the backend database. It may succeed when retried.
+    #[error("Failed to apply change {seq} ({kind}) from the primary: {err}")]
+    Apply { seq: u64, kind: &'static str, err: String },
+    /// Applying the change created another version than it did on the primary.
+    #[error("Change {seq} ({kind}) created version {actual} on this standby, but version {expected} on the primary")]
+    Diverged { seq: u64, kind: &'static str, expected: u64, actual: u64 },
+    /// The primary did not send along what the change added (e.g., the version it created).
+    #[error("Change {seq} ({kind}) added something, but the primary did not send it along")]
+    Incomplete { seq: u64, kind: &'static str },
+    /// The backend database refused the change, even though the primary accepted it.
+    #[error("Backend database refused change {seq} ({kind}) from the primary")]
+    Refused {
+        seq:  u64,
+        kind: &'static str,
+        #[source]
+        err:  ClientError,
+    },
+    /// The user pseudonymized by the change could not be found.
+    #[error("Change {seq} ({kind}) pseudonymized a user that this standby cannot identify (is it configured with the pseudonym key of its primary?)")]
+    Unidentified { seq: u64, kind: &'static str },
+}
+impl ReplicationError {
+    /// Returns whether replication can't continue after this error.
+    ///
+    /// # Returns
+    /// False if retrying the change may still succeed, or true if the standby has diverged from
+    /// its primary.
+    #[inline]
+    fn is_fatal(&self) -> bool { !matches!(self, Self::Apply { .. }) }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Builds a closure that wraps backend errors in a [`ReplicationError::Apply`].
+///
+/// # Arguments
+/// - `seq`: The sequence number of the change being applied.
+/// - `kind`: The kind of the change being applied.
+///
+/// # Returns
+/// A closure to pass to [`Result::map_err()`].
+#[inline]
+fn apply_failed<E: std::error::Error>(seq: u64, kind: &'static str) -> impl FnOnce(E) -> ReplicationError {
+    move |err| ReplicationError::Apply { seq, kind, err: trace!(("Backend database failed"), err).to_string() }
+}
+
+/// Checks that a change created the same version on this standby as it did on the primary.
+///
+/// # Arguments
+/// - `seq`: The sequence number of the change.
+/// - `kind`: The kind of the change.
+/// - `expected`: The version created on the primary.
+/// - `actual`: The version created on this standby.
+///
+/// # Errors
+/// This function errors with a [`ReplicationError::Diverged`] if the versions differ.
+#[inline]
+fn check_version(seq: u64, kind: &'static str, expected: u64, actual: u64) -> Result<(), ReplicationError> {
+    if expected != actual {
+        return Err(ReplicationError::Diverged { seq, kind, expected, actual });
+    }
+    Ok(())
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Configures running the server as a warm standby of another (primary) store.
+///
+/// A standby continuously [replicates](crate::spec::GET_CHANGES_PATH) the changes made to its
+/// primary into its own backend database, and refuses any changes sent to itself. Once
+/// [promoted](crate::spec::PROMOTE_STANDBY_PATH) (by hand, or automatically when the primary has
+/// been unreachable for a while), it stops replicating and accepts changes like any other store.
+///
+/// The standby should start with an empty database (or one that was replicated before), as
+/// changes are applied as-is and replication stops as soon as they create other versions than on
+/// the primary. To replicate pseudonymizations, give the standby the same
+/// [pseudonym key](AxumServer::with_pseudonym_key()) as its primary: it recognises the user by
+/// deriving their pseudonym from the IDs in the first audit record that referenced them.
+#[derive(Debug)]
+pub struct Standby {
+    /// The client used to read the changes from the primary.
+    primary: reqwest_client::Client,
+    /// How often to ask the primary for new changes.
+    interval: Duration,
+    /// After how long without reaching the primary to promote automatically, if ever.
+    auto_promote: Option<Duration>,
+    /// Whether the standby has been promoted.
+    promoted: AtomicBool,
+    /// The sequence number of the last change replicated from the primary. Locked while
+    /// changes are being applied.
+    cursor: Mutex<u64>,
+}
+impl Standby {
+    /// Constructor for a Standby that checks its primary every second and is only promoted by
+    /// hand.
+    ///
+    /// # Arguments
+    /// - `primary`: A [`reqwest_client::Client`] that reads from the primary. Any credentials
+    ///   needed to read its changes should be configured on it.
+    ///
+    /// # Returns
+    /// A new Standby.
+    #[inline]
+    pub fn new(primary: reqwest_client::Client) -> Self {
+        Self { primary, interval: Duration::from_secs(1), auto_promote: None, promoted: AtomicBool::new(false), cursor: Mutex::new(0) }
+    }
+
+    /// Changes how often the primary is asked for new changes.
+    ///
+    /// # Arguments
+    /// - `interval`: The time between checks.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Promotes the standby automatically once the primary has been unreachable for a while.
+    ///
+    /// Only do this if the primary cannot come back on its own while the standby has taken over,
+    /// as both would then accept changes.
+    ///
+    /// # Arguments
+    /// - `after`: How long the primary must be unreachable before promoting.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_auto_promotion(mut self, after: Duration) -> Self {
+        self.auto_promote = Some(after);
+        self
+    }
+
+    /// Returns whether the standby has been promoted, i.e., accepts changes.
+    ///
+    /// # Returns
+    /// True if promoted, or false if it is still replicating from its primary.
+    #[inline]
+    pub fn is_promoted(&self) -> bool { self.promoted.load(Ordering::SeqCst) }
+
+    /// Promotes the standby, such that it stops replicating and accepts changes.
+    ///
+    /// This waits for any change that is being replicated to be applied first.
+    ///
+    /// # Returns
+    /// The sequence number of the last change replicated from the primary.
+    pub(crate) async fn promote(&self) -> u64 {
+        self.promoted.store(true, Ordering::SeqCst);
+        *self.cursor.lock().await
+    }
+}
+
+
+
+impl<A, D, T, W> AxumServer<A, D, T, W>
+where
+    A: 'static + Send + Sync + AuthResolver,
+    D: 'static + Send + Sync + DatabaseConnector,
+    D::Content: Send + DeserializeOwned + Serialize,
+    for<'s> D::Connection<'s>: Send,
+    T: 'static + Send + Sync + ContentTransformer<D::Content>,
+{
+    /// Replicates the changes of the primary of the configured [`Standby`], if any, until it is
+    /// promoted.
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    ///
+    /// # Returns
+    /// This function returns once the standby is promoted, or when replication cannot continue
+    /// (or immediately, if no standby is configured).
+    pub(crate) async fn replicate(this: Arc<Self>) {
+        let Some(standby) = &this.standby else { return };
+        let _span = span!(Level::INFO, "AxumServer::replicate");
+
+        // Find out where we left off
+        let user: User = User { id: "standby".into(), name: "Standby replication".into(), issuer: None, roles: Vec::new() };
+        // NOTE: Map the connector's error first, as it need not be `Send` to be kept across the await
+        let conn: Result<D::Connection<'_>, String> =
+            this.data.connect(&user).await.map_err(|err| trace!(("Failed to connect to database"), err).to_string());
+        let cursor: Result<u64, String> = match conn {
+            Ok(mut conn) => conn.get_event_cursor(STANDBY_CURSOR).await.map_err(|err| trace!(("Failed to get replication cursor"), err).to_string()),
+            Err(err) => Err(err),
+        };
+        match cursor {
+            Ok(cursor) => *standby.cursor.lock().await = cursor,
+            Err(err) => {
+                error!("Failed to resume replication; standby will not replicate: {err}");
+                return;
+            },
+        }
+
+        // Check for new changes every so often
+        info!("Replicating from primary, starting after change {}", *standby.cursor.lock().await);
+        let mut last_contact: Instant = Instant::now();
+        let mut interval = tokio::time::interval(standby.interval);
+        loop {
+            interval.tick().await;
+            if standby.is_promoted() {
+                break;
+            }
+
+            // Ask the primary what changed since
+            let since: u64 = *standby.cursor.lock().await;
+            let res: GetChangesResponse<D::Content> = match standby.primary.get_changes(since, None).await {
+                Ok(res) => {
+                    last_contact = Instant::now();
+                    res
+                },
+                Err(err) => {
+                    error!("{}", trace!(("Failed to get changes from primary"), err));
+                    if standby.auto_promote.is_some_and(|after| last_contact.elapsed() >= after) {
+                        warn!("Primary unreachable for {:.2}s; promoting standby", last_contact.elapsed().as_secs_f32());
+                        standby.promote().await;
+                        break;
+                    }
+                    continue;
+                },
+            };
+
+            // Apply them in order, unless we're promoted in the meantime
+            let mut cursor = standby.cursor.lock().await;
+            for change in res.changes {
+                if standby.is_promoted() {
+                    break;
+                }
+                let seq: u64 = change.record.seq;
+                if seq <= *cursor {
+                    continue;
+                }
+                debug!("Replicating change {seq} ({})", change.record.action.kind());
+                match this.replicate_change(change).await {
+                    Ok(()) => *cursor = seq,
+                    Err(err) if err.is_fatal() => {
+                        error!("{}; standby has diverged from its primary and stops replicating", trace!(("Failed to replicate change {seq}"), err));
+                        return;
+                    },
+                    Err(err) => {
+                        // NOTE: We don't update the cursor, so we retry the next tick
+                        error!("{}", trace!(("Failed to replicate change {seq}"), err));
+                        break;
+                    },
+                }
+            }
+        }
+        info!("Standby promoted; stopped replicating from primary");
+    }
+
+    /// Applies a single change from the primary to the backend database.
+    ///
+    /// The change is made on behalf of the user who made it on the primary, such that the audit
+    /// logs of both stores agree.
+    ///
+    /// # Arguments
+    /// - `change`: The [`Change`] to apply.
+    ///
+    /// # Errors
+    /// This function errors if we failed to apply the change, or if applying it did not have the
+    /// same result as on the primary.
+    async fn replicate_change(&self, change: Change<D::Content>) -> Result<(), ReplicationError> {
+        let Change { record, metadata, content, added } = change;
+        let (seq, kind): (u64, &'static str) = (record.seq, record.action.kind());
+        let user: User = User { id: record.user.clone(), name: record.user.clone(), issuer: None, roles: Vec::new() };
+        let mut conn: D::Connection<'_> = self.data.connect_from(&user, record.client).await.map_err(apply_failed(seq, kind))?;
+
+        // Changes that create a version must come with it
+        let created: Option<(Metadata, D::Content)> = match (record.action.created_version(), metadata, content) {
+            (Some(_), Some(metadata), Some(content)) => Some((metadata, content)),
+            (Some(_), _, _) => return Err(ReplicationError::Incomplete { seq, kind }),
+            (None, _, _) => None,
+        };
+
+        // Then make the same change here
+        match record.action {
+            AuditAction::AddVersion { version, .. } => {
+                // SAFETY: Checked above
+                let (metadata, content): (Metadata, D::Content) = created.unwrap();
+                let actual: u64 = conn.add_version(metadata.attached, content).await.map_err(apply_failed(seq, kind))?;
+                check_version(seq, kind, version, actual)?;
+            },
+            AuditAction::Import { version, store, source, .. } => {
+                // SAFETY: Checked above
+                let (mut metadata, content): (Metadata, D::Content) = created.unwrap();
+                // NOTE: The metadata describes the version on the primary, but the import records where it came from
+                metadata.version = source;
+                let actual: u64 = conn
+                    .import_version(&store, metadata, content)
+                    .await
+                    .map_err(apply_failed(seq, kind))?
+                    .map_err(|err| ReplicationError::Refused { seq, kind, err })?;
+                check_version(seq, kind, version, actual)?;
+            },
+            AuditAction::Promote { version, target, promoted } => {
+                // NOTE: Forced, as the primary already decided the promotion was fine
+                let actual: u64 = conn
+                    .promote(version, &target, true)
+                    .await
+                    .map_err(apply_failed(seq, kind))?
+                    .map_err(|err| ReplicationError::Refused { seq, kind, err })?;
+                check_version(seq, kind, promoted, actual)?;
+            },
+            AuditAction::Activate { version } => conn.activate(version).await.map_err(apply_failed(seq, kind))?,
+            AuditAction::Deactivate { .. } => conn.deactivate().await.map_err(apply_failed(seq, kind))?,
+            AuditAction::CreateBranch { name, from } => {
+                conn.create_branch(&name, from).await.map_err(apply_failed(seq, kind))?.map_err(|err| ReplicationError::Refused { seq, kind, err })?
+            },
+            AuditAction::FreezeVersion { version, reason } => conn
+                .freeze_version(version, &reason)
+                .await
+                .map_err(apply_failed(seq, kind))?
+                .map_err(|err| ReplicationError::Refused { seq, kind, err })?,
+            AuditAction::UnfreezeVersion { version } => {
+                conn.unfreeze_version(version).await.map_err(apply_failed(seq, kind))?.map_err(|err| ReplicationError::Refused { seq, kind, err })?
+            },
+            AuditAction::PruneActivations { before, .. } => {
+                conn.prune_activations(before).await.map_err(apply_failed(seq, kind))?;
+            },
+            AuditAction::RevokeToken { jti } => conn.revoke_tokens(AttachedRevocation::Token { jti }).await.map_err(apply_failed(seq, kind))?,
+            AuditAction::RevokeUserTokens { user, before } => {
+                conn.revoke_tokens(AttachedRevocation::User { user, before }).await.map_err(apply_failed(seq, kind))?
+            },
+            AuditAction::RevokeKey { id } => {
+                conn.revoke_key(&id).await.map_err(apply_failed(seq, kind))?.map_err(|err| ReplicationError::Refused { seq, kind, err })?
+            },
+            AuditAction::AddTemplate { .. } => match added {
+                Some(Added::Template(template)) => conn.add_template(template).await.map_err(apply_failed(seq, kind))?,
+                _ => return Err(ReplicationError::Incomplete { seq, kind }),
+            },
+            AuditAction::AddSchema { .. } => match added {
+                Some(Added::Schema(schema)) => {
+                    conn.add_schema(schema).await.map_err(apply_failed(seq, kind))?.map_err(|err| ReplicationError::Refused { seq, kind, err })?
+                },
+                _ => return Err(ReplicationError::Incomplete { seq, kind }),
+            },
+            AuditAction::AddKey { .. } => match added {
+                Some(Added::Key(key)) => {
+                    conn.add_key(key).await.map_err(apply_failed(seq, kind))?.map_err(|err| ReplicationError::Refused { seq, kind, err })?
+                },
+                _ => return Err(ReplicationError::Incomplete { seq, kind }),
+            },
+            AuditAction::AddDecision { version, id } => {
+                conn.add_decision(version, &id).await.map_err(apply_failed(seq, kind))?.map_err(|err| ReplicationError::Refused { seq, kind, err })?
+            },
+            AuditAction::Pseudonymize { pseudonym, rehashed_from: Some(first) } => {
+                let id: String = self.find_pseudonymized(&mut conn, seq, kind, &pseudonym, first).await?;
+                conn.pseudonymize_user(&id, &pseudonym).await.map_err(apply_failed(seq, kind))?.map_err(|err| ReplicationError::Refused {
+                    seq,
+                    kind,
+                    err,
+                })?;
+            },
+            AuditAction::Pseudonymize { rehashed_from: None, .. } => {
+                // NOTE: Every replicated change is recorded in the audit log, so nothing here references the user either
+                debug!("Not pseudonymizing in change {seq}, as no audit record referenced the user");
+            },
+        }
+
+        // Remember we did
+        // NOTE: If this fails, the change is applied again next time, which the version checks catch for most changes
+        conn.ack_events(STANDBY_CURSOR, seq).await.map_err(apply_failed(seq, kind))
+    }
+
+    /// Finds which user a pseudonymization on the primary replaced.
+    ///
+    /// As changes are replicated one-to-one, the first audit record that referenced the user on
+    /// the primary has the same sequence number here. The user is whoever in that record gets the
+    /// same pseudonym.
+    ///
+    /// # Arguments
+    /// - `conn`: The connection to the backend database.
+    /// - `seq`: The sequence number of the pseudonymization.
+    /// - `kind`: The kind of the pseudonymization.
+    /// - `pseudonym`: The pseudonym the user got.
+    /// - `first`: The sequence number of the first audit record that referenced the user.
+    ///
+    /// # Returns
+    /// The ID of the user.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read the record, or if nobody in it gets the pseudonym
+    /// (e.g., because the primary uses another key).
+    async fn find_pseudonymized(
+        &self,
+        conn: &mut D::Connection<'_>,
+        seq: u64,
+        kind: &'static str,
+        pseudonym: &str,
+        first: u64,
+    ) -> Result<String, ReplicationError> {
+        let changes: Vec<Change<D::Content>> = conn.get_changes(first.saturating_sub(1), 1).await.map_err(apply_failed(seq, kind))?;
+        let Some(record) = changes.into_iter().map(|change| change.record).find(|record| record.seq == first) else {
+            return Err(ReplicationError::Unidentified { seq, kind });
+        };
+        let id: Option<String> = [Some(record.user.as_str()), record.action.subject()]
+            .into_iter()
+            .flatten()
+            .find(|id| self.pseudonyms.pseudonym(id) == pseudonym)
+            .map(String::from);
+        id.ok_or(ReplicationError::Unidentified { seq, kind })
+    }
+}
+impl<A, D, T, W> AxumServer<A, D, T, W>
+where
+    A: 'static + Send + Sync,
+    D: 'static + Send + Sync,
+    T: 'static + Send + Sync,
+    W: WireFormat,
+{
+    /// Middleware that refuses changes while the configured [`Standby`], if any, has not been
+    /// promoted.
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    /// - `request`: The [`Request`] to check.
+    /// - `next`: The rest of the router to call if the request is allowed.
+    ///
+    /// # Returns
+    /// The [`Response`] of the rest of the router, or a 503 SERVICE UNAVAILABLE if the request
+    /// would change the store.
+    pub(crate) async fn check_standby(State(this): State<Arc<Self>>, request: Request, next: Next) -> Response {
+        let read_only: bool = this.standby.as_ref().is_some_and(|standby| !standby.is_promoted());
+        if read_only && !matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+            debug!("Refusing {} {} on unpromoted standby", request.method(), request.uri().path());
+            return this.wire.error_response(
+                ApiErrorCode::Unavailable,
+                "This server is a read-only standby; send changes to its primary or promote it first".into(),
+            );
+        }
+        next.run(request).await
+    }
+}
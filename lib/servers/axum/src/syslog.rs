@@ -0,0 +1,211 @@
+//  SYSLOG.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:44:21
+//  Last edited:
+//    16 Oct 2026, 17:52:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements pushing the audit log to a syslog collector, such that a
+//!   SIEM can ingest store activity without polling the API.
+//
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::SecondsFormat;
+use error_trace::trace;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use specifications::audit::AuditRecord;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::User;
+use specifications::{AuthResolver, ContentTransformer, DatabaseConnector};
+use tokio::net::UdpSocket;
+use tracing::{Level, debug, error, info, span};
+
+use crate::server::AxumServer;
+use crate::spec::ExportFormat;
+
+
+/***** CONSTANTS *****/
+/// The syslog priority of forwarded records, i.e., facility 13 (log audit) at severity 5 (notice).
+const SYSLOG_PRIORITY: u8 = 13 * 8 + 5;
+
+
+
+
+
+/***** LIBRARY *****/
+/// Configures forwarding of the audit log to a syslog collector.
+///
+/// Records are sent as RFC 5424 messages over UDP. Only records appended after the server has
+/// started are forwarded; use the export endpoint to backfill older ones.
+#[derive(Clone, Debug)]
+pub struct SyslogForwarder {
+    /// The address of the syslog collector.
+    pub addr:     SocketAddr,
+    /// The format of the message body of every record.
+    pub format:   ExportFormat,
+    /// The hostname to report in every message.
+    pub hostname: String,
+    /// How often to check the audit log for new records.
+    pub interval: Duration,
+}
+impl SyslogForwarder {
+    /// Constructor for a SyslogForwarder that sends CEF events every 5 seconds.
+    ///
+    /// # Arguments
+    /// - `addr`: The address of the syslog collector.
+    ///
+    /// # Returns
+    /// A new SyslogForwarder.
+    #[inline]
+    pub fn new(addr: impl Into<SocketAddr>) -> Self {
+        Self { addr: addr.into(), format: ExportFormat::Cef, hostname: "-".into(), interval: Duration::from_secs(5) }
+    }
+
+    /// Changes the format of the message body of every record.
+    ///
+    /// # Arguments
+    /// - `format`: The [`ExportFormat`] to use.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Changes the hostname reported in every message.
+    ///
+    /// # Arguments
+    /// - `hostname`: The hostname to report.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.hostname = hostname.into();
+        self
+    }
+
+    /// Changes how often the audit log is checked for new records.
+    ///
+    /// # Arguments
+    /// - `interval`: The time between checks.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Formats a record as a syslog message.
+    ///
+    /// # Arguments
+    /// - `record`: The [`AuditRecord`] to format.
+    ///
+    /// # Returns
+    /// The RFC 5424 message to send.
+    fn message(&self, record: &AuditRecord) -> String {
+        let body: String = match self.format {
+            // SAFETY: Serializing an audit record never fails
+            ExportFormat::Ndjson => serde_json::to_string(record).unwrap(),
+            ExportFormat::Cef => record.to_cef(env!("CARGO_PKG_VERSION")),
+        };
+        format!(
+            "<{SYSLOG_PRIORITY}>1 {} {} policy-store - {} - {body}",
+            record.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true),
+            self.hostname,
+            record.action.kind(),
+        )
+    }
+}
+
+
+
+impl<A, D, T, W> AxumServer<A, D, T, W>
+where
+    A: 'static + Send + Sync + AuthResolver,
+    D: 'static + Send + Sync + DatabaseConnector,
+    D::Content: Send + DeserializeOwned + Serialize,
+    for<'s> D::Connection<'s>: Send,
+    T: 'static + Send + Sync + ContentTransformer<D::Content>,
+{
+    /// Forwards new records in the audit log to the configured [`SyslogForwarder`], if any.
+    ///
+    /// # Arguments
+    /// - `this`: Is like `self`, but then wrapped in an [`Arc`].
+    ///
+    /// # Returns
+    /// This function does not return for as long as the server runs (unless no forwarder is
+    /// configured, in which case it returns immediately).
+    pub(crate) async fn forward_audit_log(this: Arc<Self>) {
+        let Some(fwd) = &this.syslog else { return };
+        let _span = span!(Level::INFO, "AxumServer::forward_audit_log", collector = fwd.addr.to_string());
+        let user: User = User { id: "syslog".into(), name: "Syslog forwarder".into(), issuer: None, roles: Vec::new() };
+
+        // Prepare the socket
+        let socket: UdpSocket = match UdpSocket::bind(if fwd.addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }).await {
+            Ok(socket) => socket,
+            Err(err) => {
+                error!("{}", trace!(("Failed to bind socket for syslog forwarding; audit log will not be forwarded"), err));
+                return;
+            },
+        };
+
+        // Check for new records every so often
+        info!("Forwarding audit log to syslog collector at '{}'", fwd.addr);
+        let mut last: Option<AuditRecord> = None;
+        let mut first: bool = true;
+        let mut interval = tokio::time::interval(fwd.interval);
+        loop {
+            interval.tick().await;
+
+            // Get the records we haven't seen yet
+            let mut conn: D::Connection<'_> = match this.data.connect(&user).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    error!("{}", trace!(("Failed to connect to database for syslog forwarding"), err));
+                    continue;
+                },
+            };
+            let records: Vec<AuditRecord> = match conn.get_audit_log(last.as_ref().map(|last| last.timestamp)).await {
+                Ok(records) => records,
+                Err(err) => {
+                    error!("{}", trace!(("Failed to get audit log for syslog forwarding"), err));
+                    continue;
+                },
+            };
+            drop(conn);
+            let records: Vec<AuditRecord> =
+                records.into_iter().filter(|record| last.as_ref().map(|last| record.seq > last.seq).unwrap_or(true)).collect();
+
+            // The first time, only remember where the log is at
+            if first {
+                first = false;
+                last = records.into_iter().last();
+                continue;
+            }
+
+            // Send the rest
+            for record in records {
+                debug!("Forwarding audit record {} to syslog", record.seq);
+                if let Err(err) = socket.send_to(fwd.message(&record).as_bytes(), fwd.addr).await {
+                    // NOTE: We don't update `last`, so we retry the next tick
+                    error!("{}", trace!(("Failed to send audit record {} to syslog collector at '{}'", record.seq, fwd.addr), err));
+                    break;
+                }
+                last = Some(record);
+            }
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//  TESTING.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:19:07
+//  Last edited:
+//    16 Oct 2026, 22:45:19
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements utilities for testing the [`AxumServer`] end-to-end,
+//!   i.e., over an actual socket.
+//
+
+use std::net::SocketAddr;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderMap, Method, StatusCode};
+use no_op_auth::NoOpResolver;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use specifications::ContentTransformer;
+use sqlite_database::{MIGRATIONS, SQLiteDatabase};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use crate::server::{AxumServer, DEFAULT_ADMIN_ROLE};
+use crate::spec::{ErrorResponse, PROBLEM_CONTENT_TYPE};
+use crate::wire::WireFormat;
+
+
+/***** LIBRARY *****/
+/// The [`AxumServer`] as configured by a [`TestServer`], before any customization.
+pub type TestAxumServer = AxumServer<NoOpResolver, SQLiteDatabase<Value>>;
+
+
+
+/// Runs an [`AxumServer`] in the background for as long as it lives.
+///
+/// The server binds an ephemeral port on localhost, stores arbitrary JSON policies in a fresh
+/// in-memory database, and lets every request through as the same user (see [`NoOpResolver`]).
+///
+/// All functions panic instead of returning errors, as they are meant to be used in tests.
+#[derive(Debug)]
+pub struct TestServer {
+    /// The address the server is listening on.
+    addr:   SocketAddr,
+    /// The client used to send requests to it.
+    client: reqwest::Client,
+    /// The task running the server.
+    handle: JoinHandle<()>,
+}
+impl TestServer {
+    /// Starts a TestServer with the default configuration.
+    ///
+    /// # Returns
+    /// A new TestServer that is ready to accept requests.
+    ///
+    /// # Panics
+    /// This function panics if the database or the socket could not be setup. It must be called
+    /// from within a tokio runtime.
+    #[inline]
+    pub async fn start() -> Self { Self::start_with(|server| server).await }
+
+    /// Starts a TestServer with a custom configuration.
+    ///
+    /// # Arguments
+    /// - `configure`: A closure that configures the server (e.g., by calling
+    ///   [`AxumServer::with_transformer()`] or [`AxumServer::with_layer()`]). Note that the
+    ///   address given to it is ignored.
+    ///
+    /// # Returns
+    /// A new TestServer that is ready to accept requests.
+    ///
+    /// # Panics
+    /// This function panics if the database or the socket could not be setup. It must be called
+    /// from within a tokio runtime.
+    #[inline]
+    pub async fn start_with<T, W>(configure: impl FnOnce(TestAxumServer) -> AxumServer<NoOpResolver, SQLiteDatabase<Value>, T, W>) -> Self
+    where
+        T: 'static + Send + Sync + ContentTransformer<Value>,
+        W: WireFormat,
+    {
+        Self::start_as(NoOpResolver::new(), configure).await
+    }
+
+    /// Starts a TestServer with the default configuration, of which the user has the
+    /// [`DEFAULT_ADMIN_ROLE`].
+    ///
+    /// # Returns
+    /// A new TestServer that is ready to accept requests, including to administrative endpoints.
+    ///
+    /// # Panics
+    /// This function panics if the database or the socket could not be setup. It must be called
+    /// from within a tokio runtime.
+    #[inline]
+    pub async fn start_admin() -> Self { Self::start_admin_with(|server| server).await }
+
+    /// Starts a TestServer with a custom configuration, of which the user has the
+    /// [`DEFAULT_ADMIN_ROLE`].
+    ///
+    /// # Arguments
+    /// - `configure`: A closure that configures the server. Note that the address given to it is
+    ///   ignored.
+    ///
+    /// # Returns
+    /// A new TestServer that is ready to accept requests, including to administrative endpoints.
+    ///
+    /// # Panics
+    /// This function panics if the database or the socket could not be setup. It must be called
+    /// from within a tokio runtime.
+    #[inline]
+    pub async fn start_admin_with<T, W>(configure: impl FnOnce(TestAxumServer) -> AxumServer<NoOpResolver, SQLiteDatabase<Value>, T, W>) -> Self
+    where
+        T: 'static + Send + Sync + ContentTransformer<Value>,
+        W: WireFormat,
+    {
+        Self::start_as(NoOpResolver::new().with_roles(&[DEFAULT_ADMIN_ROLE]), configure).await
+    }
+
+    /// Starts a TestServer that lets every request through as the user of the given resolver.
+    ///
+    /// # Arguments
+    /// - `auth`: The [`NoOpResolver`] to authorize requests with.
+    /// - `configure`: A closure that configures the server.
+    ///
+    /// # Returns
+    /// A new TestServer that is ready to accept requests.
+    ///
+    /// # Panics
+    /// This function panics if the database or the socket could not be setup. It must be called
+    /// from within a tokio runtime.
+    async fn start_as<T, W>(
+        auth: NoOpResolver,
+        configure: impl FnOnce(TestAxumServer) -> AxumServer<NoOpResolver, SQLiteDatabase<Value>, T, W>,
+    ) -> Self
+    where
+        T: 'static + Send + Sync + ContentTransformer<Value>,
+        W: WireFormat,
+    {
+        let db: SQLiteDatabase<Value> = SQLiteDatabase::in_memory_async(MIGRATIONS).await.expect("failed to create in-memory database");
+        let server = configure(AxumServer::new(([127, 0, 0, 1], 0), auth, db));
+
+        // Bind ourselves to learn which port we got
+        let listener: TcpListener = TcpListener::bind(("127.0.0.1", 0)).await.expect("failed to bind test server");
+        let addr: SocketAddr = listener.local_addr().expect("failed to get address of test server");
+        let router: Router = server.into_router();
+        let handle: JoinHandle<()> = tokio::spawn(async move {
+            axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>()).await.expect("failed to run test server")
+        });
+        Self { addr, client: reqwest::Client::new(), handle }
+    }
+
+
+
+    /// Returns the address the server is listening on.
+    ///
+    /// # Returns
+    /// A [`SocketAddr`] on localhost.
+    #[inline]
+    pub fn addr(&self) -> SocketAddr { self.addr }
+
+    /// Returns the full URL of a path on the server.
+    ///
+    /// # Arguments
+    /// - `path`: The path (and query) to get the URL of, e.g., `/v2/policies`.
+    ///
+    /// # Returns
+    /// The URL as a string.
+    #[inline]
+    pub fn url(&self, path: &str) -> String { format!("http://{}{path}", self.addr) }
+
+    /// Prepares a custom request to the server.
+    ///
+    /// # Arguments
+    /// - `method`: The [`Method`] of the request.
+    /// - `path`: The path (and query) to send it to.
+    ///
+    /// # Returns
+    /// A [`reqwest::RequestBuilder`] to finish and pass to [`send()`](TestServer::send()).
+    #[inline]
+    pub fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder { self.client.request(method, self.url(path)) }
+
+    /// Sends a custom request to the server.
+    ///
+    /// # Arguments
+    /// - `request`: The [`reqwest::RequestBuilder`] to send, e.g., from [`request()`](TestServer::request()).
+    ///
+    /// # Returns
+    /// The server's [`TestResponse`], whatever its status code.
+    ///
+    /// # Panics
+    /// This function panics if the request could not be sent or the response not received.
+    pub async fn send(&self, request: reqwest::RequestBuilder) -> TestResponse {
+        let res: reqwest::Response = request.send().await.expect("failed to send request to test server");
+        let status: StatusCode = res.status();
+        let headers: HeaderMap = res.headers().clone();
+        let body: Bytes = res.bytes().await.expect("failed to receive response from test server");
+        TestResponse { status, headers, body }
+    }
+
+    /// Sends a `GET`-request to the server.
+    ///
+    /// # Arguments
+    /// - `path`: The path (and query) to send it to.
+    ///
+    /// # Returns
+    /// The server's [`TestResponse`].
+    #[inline]
+    pub async fn get(&self, path: &str) -> TestResponse { self.send(self.request(Method::GET, path)).await }
+
+    /// Sends a `POST`-request with a JSON body to the server.
+    ///
+    /// # Arguments
+    /// - `path`: The path (and query) to send it to.
+    /// - `body`: The body to send.
+    ///
+    /// # Returns
+    /// The server's [`TestResponse`].
+    #[inline]
+    pub async fn post<B: ?Sized + Serialize>(&self, path: &str, body: &B) -> TestResponse {
+        self.send(self.request(Method::POST, path).json(body)).await
+    }
+
+    /// Sends a `PUT`-request with a JSON body to the server.
+    ///
+    /// # Arguments
+    /// - `path`: The path (and query) to send it to.
+    /// - `body`: The body to send.
+    ///
+    /// # Returns
+    /// The server's [`TestResponse`].
+    #[inline]
+    pub async fn put<B: ?Sized + Serialize>(&self, path: &str, body: &B) -> TestResponse {
+        self.send(self.request(Method::PUT, path).json(body)).await
+    }
+
+    /// Sends a `PATCH`-request with a JSON body to the server.
+    ///
+    /// # Arguments
+    /// - `path`: The path (and query) to send it to.
+    /// - `body`: The body to send.
+    ///
+    /// # Returns
+    /// The server's [`TestResponse`].
+    #[inline]
+    pub async fn patch<B: ?Sized + Serialize>(&self, path: &str, body: &B) -> TestResponse {
+        self.send(self.request(Method::PATCH, path).json(body)).await
+    }
+
+    /// Sends a `DELETE`-request to the server.
+    ///
+    /// # Arguments
+    /// - `path`: The path (and query) to send it to.
+    ///
+    /// # Returns
+    /// The server's [`TestResponse`].
+    #[inline]
+    pub async fn delete(&self, path: &str) -> TestResponse { self.send(self.request(Method::DELETE, path)).await }
+}
+impl Drop for TestServer {
+    #[inline]
+    fn drop(&mut self) { self.handle.abort(); }
+}
+
+
+
+/// A response received from a [`TestServer`].
+#[derive(Clone, Debug)]
+pub struct TestResponse {
+    /// The status code of the response.
+    pub status:  StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// The raw body of the response.
+    pub body:    Bytes,
+}
+impl TestResponse {
+    /// Returns the body as text.
+    ///
+    /// # Returns
+    /// The body, with any invalid UTF-8 replaced.
+    #[inline]
+    pub fn text(&self) -> String { String::from_utf8_lossy(&self.body).into() }
+
+    /// Parses the body as JSON.
+    ///
+    /// # Returns
+    /// The parsed `T`.
+    ///
+    /// # Panics
+    /// This function panics if the body is not a valid JSON encoding of a `T`.
+    #[track_caller]
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        match serde_json::from_slice(&self.body) {
+            Ok(body) => body,
+            Err(err) => panic!("response body is not a valid {}: {err}\n{}", std::any::type_name::<T>(), self.text()),
+        }
+    }
+
+    /// Parses the body as problem, i.e., an [`ErrorResponse`].
+    ///
+    /// # Returns
+    /// The parsed [`ErrorResponse`].
+    ///
+    /// # Panics
+    /// This function panics if the response is not a problem, or if its status does not match
+    /// the status code of the response.
+    #[track_caller]
+    pub fn problem(&self) -> ErrorResponse {
+        assert_eq!(self.headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok()), Some(PROBLEM_CONTENT_TYPE), "{}", self.text());
+        let problem: ErrorResponse = self.json();
+        assert_eq!(problem.status, self.status.as_u16(), "{}", self.text());
+        problem
+    }
+}
@@ -0,0 +1,311 @@
+//  UPLOADS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:25:13
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Keeps track of chunked uploads that are in progress.
+//
+
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use specifications::metadata::AttachedMetadata;
+use tracing::debug;
+
+use crate::spec::ApiErrorCode;
+
+
+/***** CONSTANTS *****/
+/// The time after which an upload that hasn't received any chunks is dropped.
+pub(crate) const UPLOAD_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// The default maximum number of bytes in a single upload (64 MiB).
+pub const DEFAULT_MAX_UPLOAD_SIZE: u64 = 64 * 1024 * 1024;
+/// The default maximum number of bytes in all uploads in progress together (1 GiB).
+pub const DEFAULT_MAX_TOTAL_UPLOAD_SIZE: u64 = 1024 * 1024 * 1024;
+/// The default maximum number of uploads a single user may have in progress.
+pub const DEFAULT_MAX_UPLOADS_PER_USER: usize = 16;
+
+
+
+
+
+/***** LIBRARY *****/
+/// A single upload in progress.
+#[derive(Clone, Debug)]
+pub(crate) struct Upload {
+    /// The ID of the user who initiated the upload. Only they can continue it.
+    pub(crate) owner:    String,
+    /// The metadata to attach to the policy once finalized.
+    pub(crate) metadata: AttachedMetadata,
+    /// The total length of the contents, if known.
+    pub(crate) length:   Option<u64>,
+    /// The bytes received so far.
+    pub(crate) data:     Vec<u8>,
+    /// The last time a chunk was received.
+    pub(crate) touched:  Instant,
+}
+
+/// An upload that has been taken out of the [`Uploads`], e.g., to finalize it.
+///
+/// Unless [committed](TakenUpload::commit()), the upload is put back when this is dropped. This
+/// way, it survives finalizing failing or being cancelled halfway (e.g., because the client
+/// disconnected), such that it can simply be tried again. Until then, its bytes still count
+/// towards the maximum size of all uploads in progress together.
+#[derive(Debug)]
+pub(crate) struct TakenUpload<'u> {
+    /// The uploads to put the upload back into.
+    uploads: &'u Uploads,
+    /// The ID of the upload.
+    id:      u64,
+    /// The upload itself. Only [`None`] once committed.
+    upload:  Option<Upload>,
+}
+impl TakenUpload<'_> {
+    /// Drops the upload for good, as it's done with.
+    #[inline]
+    pub(crate) fn commit(mut self) {
+        let _uploads = self.uploads.uploads.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(upload) = self.upload.take() {
+            self.uploads.taken.fetch_sub(upload.data.len() as u64, Ordering::Relaxed);
+        }
+    }
+}
+impl Deref for TakenUpload<'_> {
+    type Target = Upload;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target { self.upload.as_ref().unwrap() }
+}
+impl Drop for TakenUpload<'_> {
+    fn drop(&mut self) {
+        if let Some(upload) = self.upload.take() {
+            debug!("Putting back upload {} after it was not finalized", self.id);
+            let mut uploads = self.uploads.uploads.lock().unwrap_or_else(|err| err.into_inner());
+            self.uploads.taken.fetch_sub(upload.data.len() as u64, Ordering::Relaxed);
+            uploads.insert(self.id, upload);
+        }
+    }
+}
+
+/// Keeps track of all uploads in progress.
+///
+/// Note that uploads are kept in memory only. As such, they don't survive restarts of the server,
+/// and resuming them only works against the same server instance.
+#[derive(Debug)]
+pub(crate) struct Uploads {
+    /// The uploads, by ID.
+    pub(crate) uploads: Mutex<HashMap<u64, Upload>>,
+    /// The ID to give to the next upload.
+    pub(crate) next_id: AtomicU64,
+    /// The number of bytes in uploads that are [taken out](Uploads::take()). Only changed while
+    /// holding the lock on `uploads`.
+    pub(crate) taken: AtomicU64,
+    /// The maximum number of bytes in a single upload.
+    pub(crate) max_upload_size: u64,
+    /// The maximum number of bytes in all uploads in progress together.
+    pub(crate) max_total_size: u64,
+    /// The maximum number of uploads a single user may have in progress. This also bounds the
+    /// memory taken by uploads that are initiated but never receive any bytes.
+    pub(crate) max_per_user: usize,
+}
+impl Default for Uploads {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            uploads: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+            taken: AtomicU64::new(0),
+            max_upload_size: DEFAULT_MAX_UPLOAD_SIZE,
+            max_total_size: DEFAULT_MAX_TOTAL_UPLOAD_SIZE,
+            max_per_user: DEFAULT_MAX_UPLOADS_PER_USER,
+        }
+    }
+}
+impl Uploads {
+    /// Locks the uploads, dropping any that have timed out.
+    ///
+    /// # Returns
+    /// A [`MutexGuard`] to the remaining uploads.
+    pub(crate) fn lock(&self) -> MutexGuard<'_, HashMap<u64, Upload>> {
+        let mut uploads = self.uploads.lock().unwrap_or_else(|err| err.into_inner());
+        uploads.retain(|id, upload| {
+            let keep: bool = upload.touched.elapsed() < UPLOAD_TIMEOUT;
+            if !keep {
+                debug!("Dropping upload {id} after timeout");
+            }
+            keep
+        });
+        uploads
+    }
+
+    /// Registers a new upload.
+    ///
+    /// # Arguments
+    /// - `owner`: The ID of the user initiating the upload.
+    /// - `metadata`: The [`AttachedMetadata`] of the policy to upload.
+    /// - `length`: The total length of the upload, if known.
+    ///
+    /// # Returns
+    /// The identifier of the new upload.
+    ///
+    /// # Errors
+    /// This function errors with [`ApiErrorCode::PayloadTooLarge`] if `length` exceeds the
+    /// maximum size of a single upload, or with [`ApiErrorCode::Conflict`] if `owner` already has
+    /// the maximum number of uploads in progress.
+    pub(crate) fn initiate(&self, owner: String, metadata: AttachedMetadata, length: Option<u64>) -> Result<u64, (ApiErrorCode, String)> {
+        if let Some(length) = length {
+            if length > self.max_upload_size {
+                return Err((
+                    ApiErrorCode::PayloadTooLarge,
+                    format!("Upload length of {length} bytes exceeds maximum of {} bytes", self.max_upload_size),
+                ));
+            }
+        }
+
+        let mut uploads = self.lock();
+        if uploads.values().filter(|upload| upload.owner == owner).count() >= self.max_per_user {
+            return Err((ApiErrorCode::Conflict, format!("Already {} uploads in progress; finalize or cancel some first", self.max_per_user)));
+        }
+        let id: u64 = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        uploads.insert(id, Upload { owner, metadata, length, data: Vec::new(), touched: Instant::now() });
+        Ok(id)
+    }
+
+    /// Appends a chunk to an upload.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the upload to append to.
+    /// - `owner`: The ID of the user appending.
+    /// - `offset`: Where the chunk starts.
+    /// - `chunk`: The chunk itself.
+    ///
+    /// # Returns
+    /// The new offset and the total length of the upload, if known.
+    ///
+    /// # Errors
+    /// This function errors if no upload `id` exists for `owner`, if `offset` doesn't match that
+    /// of the upload, or if the chunk would exceed the upload's length or any of the size limits.
+    pub(crate) fn append(&self, id: u64, owner: &str, offset: u64, chunk: Vec<u8>) -> Result<(u64, Option<u64>), (ApiErrorCode, String)> {
+        let mut uploads = self.lock();
+        let total: u64 = self.taken.load(Ordering::Relaxed) + uploads.values().map(|upload| upload.data.len() as u64).sum::<u64>();
+        let upload: &mut Upload = match uploads.get_mut(&id) {
+            Some(upload) if upload.owner == owner => upload,
+            _ => return Err((ApiErrorCode::UploadNotFound, format!("Unknown upload {id}"))),
+        };
+        if offset != upload.data.len() as u64 {
+            return Err((ApiErrorCode::Conflict, format!("Expected chunk at offset {}, got offset {offset}", upload.data.len())));
+        }
+        let end: u64 = offset + chunk.len() as u64;
+        if let Some(length) = upload.length {
+            if end > length {
+                return Err((ApiErrorCode::PayloadTooLarge, format!("Chunk exceeds declared upload length of {length} bytes")));
+            }
+        }
+        if end > self.max_upload_size {
+            return Err((ApiErrorCode::PayloadTooLarge, format!("Chunk exceeds maximum upload size of {} bytes", self.max_upload_size)));
+        }
+        if total + chunk.len() as u64 > self.max_total_size {
+            return Err((ApiErrorCode::PayloadTooLarge, "Too many bytes in uploads in progress; try again later".into()));
+        }
+        upload.data.extend(chunk);
+        upload.touched = Instant::now();
+        Ok((upload.data.len() as u64, upload.length))
+    }
+
+    /// Takes an upload out, such that nobody else can touch it while it's being finalized.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the upload to take.
+    /// - `owner`: The ID of the user taking it.
+    ///
+    /// # Returns
+    /// A [`TakenUpload`] that puts the upload back when dropped, or [`None`] if no upload `id`
+    /// exists for `owner`.
+    pub(crate) fn take(&self, id: u64, owner: &str) -> Option<TakenUpload<'_>> {
+        let mut uploads = self.lock();
+        if uploads.get(&id)?.owner != owner {
+            return None;
+        }
+        let upload: Option<Upload> = uploads.remove(&id);
+        self.taken.fetch_add(upload.as_ref().map(|upload| upload.data.len() as u64).unwrap_or(0), Ordering::Relaxed);
+        Some(TakenUpload { uploads: self, id, upload })
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates metadata for a test upload.
+    fn metadata() -> AttachedMetadata {
+        AttachedMetadata {
+            name: "test".into(),
+            description: "A test upload".into(),
+            language: "json".into(),
+            branch: "main".into(),
+            schema: None,
+            confidential: false,
+        }
+    }
+
+    #[test]
+    fn test_taken_uploads_count_towards_total() {
+        let uploads = Uploads { max_upload_size: 4, max_total_size: 6, ..Default::default() };
+        let first: u64 = uploads.initiate("alice".into(), metadata(), None).unwrap();
+        let second: u64 = uploads.initiate("alice".into(), metadata(), None).unwrap();
+        uploads.append(first, "alice", 0, b"abcd".to_vec()).unwrap();
+
+        // While the first is being finalized, it still takes up space...
+        let taken: TakenUpload = uploads.take(first, "alice").unwrap();
+        assert_eq!(uploads.append(second, "alice", 0, b"abc".to_vec()).unwrap_err().0, ApiErrorCode::PayloadTooLarge);
+
+        // ...also if it's put back...
+        drop(taken);
+        assert_eq!(uploads.append(second, "alice", 0, b"abc".to_vec()).unwrap_err().0, ApiErrorCode::PayloadTooLarge);
+
+        // ...until it's committed
+        uploads.take(first, "alice").unwrap().commit();
+        assert_eq!(uploads.append(second, "alice", 0, b"abc".to_vec()).unwrap(), (3, None));
+    }
+
+    #[test]
+    fn test_uploads_per_user() {
+        let uploads = Uploads { max_per_user: 2, ..Default::default() };
+        let first: u64 = uploads.initiate("alice".into(), metadata(), None).unwrap();
+        uploads.initiate("alice".into(), metadata(), None).unwrap();
+        assert_eq!(uploads.initiate("alice".into(), metadata(), None).unwrap_err().0, ApiErrorCode::Conflict);
+
+        // Other users are not affected
+        uploads.initiate("bob".into(), metadata(), None).unwrap();
+
+        // Finishing an upload makes room again
+        uploads.take(first, "alice").unwrap().commit();
+        uploads.initiate("alice".into(), metadata(), None).unwrap();
+    }
+
+    #[test]
+    fn test_survives_poisoning() {
+        let uploads = Uploads::default();
+        let id: u64 = uploads.initiate("alice".into(), metadata(), None).unwrap();
+        let _ = std::panic::catch_unwind(|| {
+            let _uploads = uploads.lock();
+            panic!("Poisoning the lock");
+        });
+        assert_eq!(uploads.append(id, "alice", 0, b"abc".to_vec()).unwrap(), (3, None));
+    }
+}
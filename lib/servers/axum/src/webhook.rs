@@ -0,0 +1,126 @@
+//  WEBHOOK.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:04:06
+//  Last edited:
+//    16 Oct 2026, 17:04:06
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements an [`EventSink`] that delivers events to a webhook.
+//
+
+use std::future::Future;
+use std::time::Duration;
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use serde_json::json;
+use specifications::event::{EventRecord, EventSink};
+use thiserror::Error;
+use tracing::{Level, debug, span};
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`WebhookSink`].
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    /// Failed to send the request to the webhook.
+    #[error("Failed to send events to webhook {url:?}")]
+    Send {
+        url: String,
+        #[source]
+        err: reqwest::Error,
+    },
+    /// The webhook did not accept the events.
+    #[error("Webhook {url:?} rejected events with status {status}")]
+    Status { url: String, status: StatusCode },
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Delivers events by `POST`ing them to a URL.
+///
+/// Every batch is sent as a JSON object with an `events` field listing the
+/// [`EventRecord`]s. The webhook must reply with a `2xx` status to acknowledge them; anything else
+/// makes the batch be delivered again later.
+#[derive(Clone, Debug)]
+pub struct WebhookSink {
+    /// The name of the sink.
+    name:    String,
+    /// The URL to send events to.
+    url:     String,
+    /// Additional headers to send along, e.g., for authentication.
+    headers: HeaderMap,
+    /// The client used to send requests.
+    client:  reqwest::Client,
+}
+impl WebhookSink {
+    /// Constructor for the WebhookSink.
+    ///
+    /// By default, the webhook gets 10 seconds to reply.
+    ///
+    /// # Arguments
+    /// - `name`: A unique name for the sink, used to remember up to where it received events.
+    /// - `url`: The URL to `POST` events to.
+    ///
+    /// # Returns
+    /// A new WebhookSink.
+    #[inline]
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self { name: name.into(), url: url.into(), headers: HeaderMap::new(), client: reqwest::Client::new() }.with_timeout(Duration::from_secs(10))
+    }
+
+    /// Adds a header to every request, e.g., a shared secret for the webhook to check.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the header.
+    /// - `value`: Its value.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Changes how long the webhook gets to reply before delivery is considered failed.
+    ///
+    /// # Arguments
+    /// - `timeout`: The maximum time per request.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        // NOTE: Building only fails if TLS can't be initialized, in which case the default client fails too
+        self.client = reqwest::Client::builder().timeout(timeout).build().unwrap_or_default();
+        self
+    }
+}
+impl EventSink for WebhookSink {
+    type Error = WebhookError;
+
+
+    #[inline]
+    fn name(&self) -> &str { &self.name }
+
+    fn deliver(&self, events: &[EventRecord]) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        let request = self.client.post(&self.url).headers(self.headers.clone()).json(&json!({ "events": events }));
+        async move {
+            let _span = span!(Level::INFO, "WebhookSink::deliver", url = self.url, events = events.len());
+
+            debug!("Sending {} event(s) to webhook {:?}...", events.len(), self.url);
+            let res = request.send().await.map_err(|err| WebhookError::Send { url: self.url.clone(), err })?;
+            if !res.status().is_success() {
+                return Err(WebhookError::Status { url: self.url.clone(), status: res.status() });
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//  WIRE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:40:02
+//  Last edited:
+//    16 Oct 2026, 16:57:08
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines how request and response bodies are encoded on the wire.
+//
+
+use std::error::Error;
+
+use axum::http::StatusCode;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse as _, Response};
+use error_trace::trace;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use specifications::authresolver::HttpError;
+use tracing::error;
+
+use crate::spec::{ApiErrorCode, BodyPosition, ErrorResponse};
+
+
+/***** ERRORS *****/
+/// Defines errors when encoding or decoding [`Cbor`].
+#[cfg(feature = "cbor")]
+#[derive(Debug, thiserror::Error)]
+pub enum CborError {
+    /// Failed to serialize a value.
+    #[error("Failed to serialize value as CBOR")]
+    Serialize {
+        #[source]
+        err: ciborium::ser::Error<std::io::Error>,
+    },
+    /// Failed to deserialize a value.
+    #[error("Failed to deserialize value as CBOR")]
+    Deserialize {
+        #[source]
+        err: ciborium::de::Error<std::io::Error>,
+    },
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines a format in which request and response bodies are encoded.
+pub trait WireFormat: 'static + Send + Sync {
+    /// The error returned when encoding or decoding fails.
+    type Error: 'static + Send + Sync + Error;
+
+
+    /// Returns the MIME type of bodies in this format.
+    ///
+    /// # Returns
+    /// A string to send as the `Content-Type` of responses.
+    fn content_type(&self) -> &'static str;
+
+    /// Encodes a value in this format.
+    ///
+    /// # Arguments
+    /// - `value`: The value to encode.
+    ///
+    /// # Returns
+    /// The raw bytes of the encoded value.
+    ///
+    /// # Errors
+    /// This function may error if the value cannot be represented in this format.
+    fn serialize<T: ?Sized + Serialize>(&self, value: &T) -> Result<Vec<u8>, Self::Error>;
+
+    /// Decodes a value in this format.
+    ///
+    /// # Arguments
+    /// - `raw`: The raw bytes to decode.
+    ///
+    /// # Returns
+    /// The decoded value.
+    ///
+    /// # Errors
+    /// This function may error if `raw` is not a valid encoding of a `T`.
+    fn deserialize<T: DeserializeOwned>(&self, raw: &[u8]) -> Result<T, Self::Error>;
+
+    /// Finds where in a body decoding failed.
+    ///
+    /// By default, no position is reported.
+    ///
+    /// # Arguments
+    /// - `err`: The error returned by [`deserialize()`](WireFormat::deserialize()).
+    ///
+    /// # Returns
+    /// The [`BodyPosition`] of the problem, if known.
+    #[inline]
+    fn error_position(&self, err: &Self::Error) -> Option<BodyPosition> {
+        let _ = err;
+        None
+    }
+
+    /// Checks whether request bodies of the given MIME type can be decoded by this format.
+    ///
+    /// By default, only [`content_type()`](WireFormat::content_type()) itself is accepted
+    /// (ignoring case and any parameters, such as `charset`).
+    ///
+    /// # Arguments
+    /// - `content_type`: The `Content-Type` given in a request.
+    ///
+    /// # Returns
+    /// True if [`deserialize()`](WireFormat::deserialize()) should be attempted, or false if the
+    /// request should be rejected.
+    fn accepts(&self, content_type: &str) -> bool {
+        let essence: &str = content_type.split(';').next().unwrap_or(content_type).trim();
+        essence.eq_ignore_ascii_case(self.content_type())
+    }
+
+
+    /// Builds a response with a value encoded in this format as body.
+    ///
+    /// # Arguments
+    /// - `status`: The [`StatusCode`] of the response.
+    /// - `value`: The value to send.
+    ///
+    /// # Returns
+    /// A [`Response`] to send back, which is a 500 INTERNAL SERVER ERROR if encoding failed.
+    fn response<T: ?Sized + Serialize>(&self, status: StatusCode, value: &T) -> Response {
+        match self.serialize(value) {
+            Ok(body) => (status, [(CONTENT_TYPE, self.content_type())], body).into_response(),
+            Err(err) => {
+                let msg: String = "Failed to serialize result".to_string();
+                error!("{}", trace!(("{msg}"), err));
+                self.error_response(ApiErrorCode::Internal, msg)
+            },
+        }
+    }
+
+    /// Builds a response with an [`ErrorResponse`] as body.
+    ///
+    /// # Arguments
+    /// - `code`: The [`ApiErrorCode`] identifying what went wrong, which also determines the
+    ///   status code of the response.
+    /// - `message`: The message describing what went wrong.
+    ///
+    /// # Returns
+    /// A [`Response`] to send back.
+    #[inline]
+    fn error_response(&self, code: ApiErrorCode, message: String) -> Response { self.problem_response(ErrorResponse::new(code, message)) }
+
+    /// Builds a response with an [`ErrorResponse`] describing an error that is the client's fault
+    /// as body.
+    ///
+    /// # Arguments
+    /// - `code`: The [`ApiErrorCode`] identifying what went wrong, which also determines the
+    ///   status code of the response.
+    /// - `message`: The message describing what went wrong.
+    /// - `err`: The [`HttpError`] that went wrong, whose [body](HttpError::body()) is sent as
+    ///   details.
+    ///
+    /// # Returns
+    /// A [`Response`] to send back.
+    #[inline]
+    fn http_error_response<E: ?Sized + HttpError>(&self, code: ApiErrorCode, message: String, err: &E) -> Response {
+        self.problem_response(ErrorResponse::new(code, message).with_details(err.body()))
+    }
+
+    /// Builds a response with the given [`ErrorResponse`] as body.
+    ///
+    /// Regardless of the format, errors are always encoded as `application/problem+json` (see RFC
+    /// 7807), such that generic HTTP tooling understands them.
+    ///
+    /// # Arguments
+    /// - `problem`: The [`ErrorResponse`] to send.
+    ///
+    /// # Returns
+    /// A [`Response`] to send back, with the `problem` attached as extension such that the server
+    /// can complete it with the ID and path of the request.
+    fn problem_response(&self, problem: ErrorResponse) -> Response { crate::problem::render(problem) }
+}
+
+
+
+/// Encodes bodies as JSON. This is the default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Json;
+impl WireFormat for Json {
+    type Error = serde_json::Error;
+
+    #[inline]
+    fn content_type(&self) -> &'static str { "application/json" }
+
+    #[inline]
+    fn serialize<T: ?Sized + Serialize>(&self, value: &T) -> Result<Vec<u8>, Self::Error> { serde_json::to_vec(value) }
+
+    #[inline]
+    fn deserialize<T: DeserializeOwned>(&self, raw: &[u8]) -> Result<T, Self::Error> { serde_json::from_slice(raw) }
+
+    #[inline]
+    fn error_position(&self, err: &Self::Error) -> Option<BodyPosition> {
+        // NOTE: Line 0 means serde_json doesn't know (e.g., for I/O errors)
+        if err.line() > 0 { Some(BodyPosition { line: err.line() as u64, column: err.column() as u64 }) } else { None }
+    }
+}
+
+/// Encodes bodies as CBOR (see RFC 8949).
+#[cfg(feature = "cbor")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cbor;
+#[cfg(feature = "cbor")]
+impl WireFormat for Cbor {
+    type Error = CborError;
+
+    #[inline]
+    fn content_type(&self) -> &'static str { "application/cbor" }
+
+    #[inline]
+    fn serialize<T: ?Sized + Serialize>(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        let mut raw: Vec<u8> = Vec::new();
+        ciborium::into_writer(value, &mut raw).map_err(|err| CborError::Serialize { err })?;
+        Ok(raw)
+    }
+
+    #[inline]
+    fn deserialize<T: DeserializeOwned>(&self, raw: &[u8]) -> Result<T, Self::Error> {
+        ciborium::from_reader(raw).map_err(|err| CborError::Deserialize { err })
+    }
+}
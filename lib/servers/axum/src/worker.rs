@@ -0,0 +1,101 @@
+//  WORKER.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:00:27
+//  Last edited:
+//    17 Oct 2026, 01:39:06
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements running [`Worker`]s alongside the server.
+//
+
+use std::future::Future;
+use std::pin::{Pin, pin};
+
+use error_trace::trace;
+use futures::FutureExt as _;
+use futures::channel::oneshot;
+use futures::future::{Either, Shared, select};
+use specifications::Worker;
+use tracing::{Level, debug, error, info, span};
+
+
+/***** AUXILLARY *****/
+/// Completes once the server stops.
+pub(crate) type StopSignal = Shared<oneshot::Receiver<()>>;
+
+/// A type-erased [`Worker`], ready to be run until the [`StopSignal`] completes.
+pub(crate) type WorkerTask = Box<dyn Send + Sync + FnOnce(StopSignal) -> Pin<Box<dyn Send + Future<Output = ()>>>>;
+
+
+
+
+
+/***** LIBRARY *****/
+/// Type-erases a [`Worker`] such that it can be stored in the [`AxumServer`](crate::AxumServer).
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] to erase.
+///
+/// # Returns
+/// A [`WorkerTask`] that runs the `worker` until stopped.
+pub(crate) fn erase_worker<W>(worker: W) -> WorkerTask
+where
+    W: 'static + Send + Sync + Worker,
+{
+    Box::new(move |stop: StopSignal| Box::pin(run_worker(worker, stop)))
+}
+
+/// Runs a [`Worker`] through its lifecycle.
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] to run.
+/// - `stop`: A [`StopSignal`] that completes once the worker should stop.
+///
+/// # Returns
+/// This function does not return until `stop` completes (or the worker fails to start).
+async fn run_worker<W: Worker>(mut worker: W, mut stop: StopSignal) {
+    let name: String = worker.name().into();
+    let _span = span!(Level::INFO, "AxumServer::run_worker", worker = name);
+
+    info!("Starting worker {name:?}");
+    if let Err(err) = worker.start().await {
+        error!("{}", trace!(("Failed to start worker {name:?}"), err));
+        return;
+    }
+
+    // Wait until the next tick (or until the worker wakes up), or until told to stop
+    // NOTE: Ticks themselves are not raced, as they may not be cancellation-safe
+    loop {
+        let ticking: bool = {
+            let sleep = pin!(tokio::time::sleep(worker.interval()));
+            let wake = pin!(worker.wake());
+            matches!(select(select(sleep, wake), &mut stop).await, Either::Left(_))
+        };
+        if !ticking {
+            break;
+        }
+
+        debug!("Ticking worker {name:?}");
+        if let Err(err) = worker.tick().await {
+            error!("{}", trace!(("Worker {name:?} failed to tick"), err));
+        }
+    }
+
+    info!("Stopping worker {name:?}");
+    if let Err(err) = worker.stop().await {
+        error!("{}", trace!(("Failed to stop worker {name:?}"), err));
+    }
+}
+
+/// Creates a [`StopSignal`] and the handle that triggers it.
+///
+/// # Returns
+/// A tuple of a sender that triggers the signal when dropped, and the signal itself.
+pub(crate) fn stop_signal() -> (oneshot::Sender<()>, StopSignal) {
+    let (tx, rx) = oneshot::channel();
+    (tx, rx.shared())
+}
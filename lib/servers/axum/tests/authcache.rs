@@ -0,0 +1,163 @@
+//  AUTHCACHE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:49:24
+//  Last edited:
+//    17 Oct 2026, 02:19:51
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the [`CachedAuthResolver`].
+//
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode};
+use axum_server::CachedAuthResolver;
+use specifications::AuthResolver;
+use specifications::authresolver::HttpError;
+use specifications::metadata::User;
+use thiserror::Error;
+
+
+/***** HELPERS *****/
+/// The error returned by the [`CountingResolver`] for unknown tokens.
+#[derive(Debug, Error)]
+#[error("Unknown token")]
+struct UnknownToken;
+impl HttpError for UnknownToken {
+    #[inline]
+    fn status_code(&self) -> StatusCode { StatusCode::UNAUTHORIZED }
+}
+
+/// A resolver that only accepts token `good` (also when followed by byte `0xFF`) or anonymous
+/// `GET`s, and counts how often it's asked.
+#[derive(Default)]
+struct CountingResolver {
+    calls: AtomicUsize,
+}
+impl AuthResolver for CountingResolver {
+    type Context = User;
+    type ClientError = UnknownToken;
+    type ServerError = Infallible;
+
+    fn authorize(&self, headers: &HeaderMap) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>> {
+        async move {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match headers.get(AUTHORIZATION).map(HeaderValue::as_bytes) {
+                Some(b"Bearer good" | b"Bearer good\xFF") => {
+                    Ok(Ok(User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() }))
+                },
+                _ => Ok(Err(UnknownToken)),
+            }
+        }
+    }
+
+    fn authorize_request(
+        &self,
+        method: &Method,
+        path: &str,
+        headers: &HeaderMap,
+    ) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>> {
+        let anonymous: bool = method == Method::GET && path.starts_with("/v2/") && !headers.contains_key(AUTHORIZATION);
+        async move {
+            if anonymous {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                return Ok(Ok(User { id: "anonymous".into(), name: "Anonymous".into(), issuer: None, roles: Vec::new() }));
+            }
+            self.authorize(headers).await
+        }
+    }
+}
+
+/// Returns headers carrying the given bearer token.
+fn bearer(token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}")).unwrap());
+    headers
+}
+
+/// Returns how often the resolver nested in the given one was called.
+fn resolver_calls(auth: &CachedAuthResolver<CountingResolver>) -> usize { auth.resolver().calls.load(Ordering::SeqCst) }
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_hit() {
+    let auth = CachedAuthResolver::new(CountingResolver::default(), Duration::from_secs(60), 16);
+    for _ in 0..3 {
+        assert_eq!(auth.authorize(&bearer("good")).await.unwrap().unwrap().id, "amy");
+    }
+    assert_eq!(resolver_calls(&auth), 1);
+
+    auth.clear();
+    auth.authorize(&bearer("good")).await.unwrap().unwrap();
+    assert_eq!(resolver_calls(&auth), 2);
+}
+
+#[tokio::test]
+async fn test_invalidate() {
+    let auth = CachedAuthResolver::new(CountingResolver::default(), Duration::from_secs(60), 16);
+    auth.authorize(&bearer("good")).await.unwrap().unwrap();
+    auth.invalidate();
+    auth.authorize(&bearer("good")).await.unwrap().unwrap();
+    assert_eq!(resolver_calls(&auth), 2);
+}
+
+#[tokio::test]
+async fn test_raw_bytes() {
+    // Tokens that only differ in bytes that aren't valid UTF-8 must not be mistaken for each other
+    let auth = CachedAuthResolver::new(CountingResolver::default(), Duration::from_secs(60), 16);
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_bytes(b"Bearer good\xFF").unwrap());
+    auth.authorize(&headers).await.unwrap().unwrap();
+    headers.insert(AUTHORIZATION, HeaderValue::from_bytes(b"Bearer good\xFE").unwrap());
+    assert!(auth.authorize(&headers).await.unwrap().is_err());
+    assert_eq!(resolver_calls(&auth), 2);
+}
+
+#[tokio::test]
+async fn test_failures_not_cached() {
+    let auth = CachedAuthResolver::new(CountingResolver::default(), Duration::from_secs(60), 16);
+    for _ in 0..2 {
+        assert!(auth.authorize(&bearer("bad")).await.unwrap().is_err());
+    }
+    for _ in 0..2 {
+        assert!(auth.authorize(&HeaderMap::new()).await.unwrap().is_err());
+    }
+    assert_eq!(resolver_calls(&auth), 4);
+}
+
+#[tokio::test]
+async fn test_expiry() {
+    let auth = CachedAuthResolver::new(CountingResolver::default(), Duration::ZERO, 16);
+    auth.authorize(&bearer("good")).await.unwrap().unwrap();
+    auth.authorize(&bearer("good")).await.unwrap().unwrap();
+    assert_eq!(resolver_calls(&auth), 2);
+}
+
+#[tokio::test]
+async fn test_request_aware() {
+    let auth = CachedAuthResolver::new(CountingResolver::default(), Duration::from_secs(60), 16);
+
+    // The method and path are passed on
+    assert_eq!(auth.authorize_request(&Method::GET, "/v2/policies", &HeaderMap::new()).await.unwrap().unwrap().id, "anonymous");
+    assert!(auth.authorize_request(&Method::POST, "/v2/policies", &HeaderMap::new()).await.unwrap().is_err());
+    assert_eq!(resolver_calls(&auth), 2);
+
+    // Decisions are remembered per endpoint
+    for _ in 0..2 {
+        auth.authorize_request(&Method::GET, "/v2/policies", &bearer("good")).await.unwrap().unwrap();
+        auth.authorize_request(&Method::POST, "/v2/policies", &bearer("good")).await.unwrap().unwrap();
+    }
+    assert_eq!(resolver_calls(&auth), 4);
+}
@@ -0,0 +1,176 @@
+//  COSIGN.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:42:16
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests verifying cosign signatures of imported versions.
+//
+
+use axum::http::StatusCode;
+use axum_server::spec::{
+    ADD_VERSION_PATH, AddVersionRequest, AddVersionResponse, ApiErrorCode, ContentSignature, GET_VERSION_METADATA_PATH, GetVersionMetadataResponse,
+    IMPORT_VERSION_PATH, ImportVersionRequest,
+};
+use axum_server::testing::TestServer;
+use axum_server::{CosignError, CosignVerifier};
+use base64ct::{Base64, Encoding as _};
+use chrono::Utc;
+use p256::ecdsa::signature::Signer as _;
+use p256::ecdsa::{Signature, SigningKey};
+use p256::pkcs8::{EncodePublicKey as _, LineEnding};
+use serde_json::{Value, json};
+use specifications::canonical::{content_hash, to_canonical_string};
+use specifications::metadata::{AttachedMetadata, Metadata, Verification};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates a key to sign with.
+///
+/// # Arguments
+/// - `seed`: Makes the key unique.
+///
+/// # Returns
+/// A new [`SigningKey`], and its public key as PEM.
+fn key(seed: u8) -> (SigningKey, String) {
+    let key = SigningKey::from_bytes(&[seed; 32].into()).unwrap();
+    let pem: String = key.verifying_key().to_public_key_pem(LineEnding::LF).unwrap();
+    (key, pem)
+}
+
+/// Signs something the way `cosign sign-blob` does.
+///
+/// # Arguments
+/// - `key`: The [`SigningKey`] to sign with.
+/// - `what`: The text to sign.
+///
+/// # Returns
+/// The signature, base64-encoded.
+fn sign(key: &SigningKey, what: &str) -> String {
+    let signature: Signature = key.sign(what.as_bytes());
+    Base64::encode_string(signature.to_der().as_bytes())
+}
+
+/// Adds a policy version to a server and returns what's needed to import it elsewhere.
+///
+/// # Arguments
+/// - `server`: The [`TestServer`] to add it to.
+/// - `contents`: The contents of the policy.
+///
+/// # Returns
+/// The [`Metadata`] of the new version.
+async fn add_version(server: &TestServer, contents: Value) -> Metadata {
+    let metadata = AttachedMetadata {
+        name: "test".into(),
+        description: "A test policy".into(),
+        language: "json".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+    let res = server.post(ADD_VERSION_PATH.path, &AddVersionRequest { metadata, contents }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let version: u64 = res.json::<AddVersionResponse>().version;
+    server.get(&GET_VERSION_METADATA_PATH.instantiated_path([version.to_string().as_str()])).await.json::<GetVersionMetadataResponse>().metadata
+}
+
+/// Imports a policy version and returns the metadata of its copy.
+///
+/// # Arguments
+/// - `server`: The [`TestServer`] to import into.
+/// - `req`: The [`ImportVersionRequest`] to send.
+///
+/// # Returns
+/// The [`Metadata`] of the copy, or the status code and error code if the import failed.
+async fn import(server: &TestServer, req: &ImportVersionRequest<Value>) -> Result<Metadata, (StatusCode, ApiErrorCode)> {
+    let res = server.post(IMPORT_VERSION_PATH.path, req).await;
+    if res.status != StatusCode::OK {
+        return Err((res.status, res.problem().code));
+    }
+    let version: u64 = res.json::<AddVersionResponse>().version;
+    Ok(server.get(&GET_VERSION_METADATA_PATH.instantiated_path([version.to_string().as_str()])).await.json::<GetVersionMetadataResponse>().metadata)
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_import_strict() {
+    let (release, release_pem) = key(1);
+    let (mallory, _) = key(2);
+    let verifier = CosignVerifier::new().with_identity("release", &release_pem).unwrap().with_strict_mode(true);
+    let staging = TestServer::start().await;
+    let production = TestServer::start_admin_with(|server| server.with_cosign_verification(verifier)).await;
+
+    let contents = json!({ "allow": ["alice"] });
+    let canonical: String = to_canonical_string(&contents).unwrap();
+    let metadata: Metadata = add_version(&staging, contents.clone()).await;
+
+    // Signed by a trusted identity is fine, and recorded
+    let signature = ContentSignature::Blob { signature: sign(&release, &canonical) };
+    let req =
+        ImportVersionRequest { store: "staging".into(), metadata: metadata.clone(), contents: contents.clone(), signature: Some(signature) };
+    let verification: Verification = import(&production, &req).await.unwrap().verification.expect("import should have been verified");
+    assert_eq!(verification.identity, "release");
+    assert_eq!(verification.digest, format!("sha256:{}", content_hash(&canonical)));
+
+    // Unsigned, signed by someone else or signed over something else is not
+    for signature in [
+        None,
+        Some(ContentSignature::Blob { signature: sign(&mallory, &canonical) }),
+        Some(ContentSignature::Blob { signature: sign(&release, "{}") }),
+        Some(ContentSignature::Blob { signature: "not base64!".into() }),
+    ] {
+        let req = ImportVersionRequest { store: "staging".into(), metadata: metadata.clone(), contents: contents.clone(), signature };
+        assert_eq!(import(&production, &req).await.unwrap_err(), (StatusCode::BAD_REQUEST, ApiErrorCode::ValidationFailed));
+    }
+}
+
+#[tokio::test]
+async fn test_import_lenient() {
+    let (_, release_pem) = key(1);
+    let verifier = CosignVerifier::new().with_identity("release", &release_pem).unwrap();
+    let staging = TestServer::start().await;
+    let production = TestServer::start_admin_with(|server| server.with_cosign_verification(verifier)).await;
+
+    // Unsigned versions are accepted, but a verification sent along is not believed
+    let contents = json!({ "allow": ["alice"] });
+    let mut metadata: Metadata = add_version(&staging, contents.clone()).await;
+    metadata.verification = Some(Verification { identity: "release".into(), digest: "sha256:00".into(), verified: Utc::now() });
+    let req = ImportVersionRequest { store: "staging".into(), metadata, contents, signature: None };
+    assert!(import(&production, &req).await.unwrap().verification.is_none());
+}
+
+#[test]
+fn test_verify_manifest() {
+    let (release, release_pem) = key(1);
+    let verifier = CosignVerifier::new().with_identity("release", &release_pem).unwrap();
+    let canonical: String = to_canonical_string(&json!({ "allow": ["alice"] })).unwrap();
+
+    // The payload refers to the manifest, which refers to the contents
+    let layer: String = format!("sha256:{}", content_hash(&canonical));
+    let manifest: String =
+        json!({ "schemaVersion": 2, "layers": [{ "mediaType": "application/json", "digest": layer, "size": canonical.len() }] }).to_string();
+    let manifest_digest: String = format!("sha256:{}", content_hash(&manifest));
+    let payload: String =
+        json!({ "critical": { "identity": {}, "image": { "docker-manifest-digest": manifest_digest }, "type": "cosign container image signature" } })
+            .to_string();
+    let signature = ContentSignature::Manifest { manifest: manifest.clone(), payload: payload.clone(), signature: sign(&release, &payload) };
+    let verification: Verification = verifier.verify(&canonical, Some(&signature)).unwrap();
+    assert_eq!(verification.identity, "release");
+    assert_eq!(verification.digest, manifest_digest);
+
+    // Any other contents don't match the manifest
+    assert!(matches!(verifier.verify("{}", Some(&signature)), Err(CosignError::ContentMismatch { .. })));
+
+    // And any other manifest doesn't match the payload
+    let signature = ContentSignature::Manifest { manifest: format!("{manifest} "), payload: payload.clone(), signature: sign(&release, &payload) };
+    assert!(matches!(verifier.verify(&canonical, Some(&signature)), Err(CosignError::ManifestMismatch { .. })));
+}
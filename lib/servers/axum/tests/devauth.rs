@@ -0,0 +1,53 @@
+//  DEVAUTH.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:52:29
+//  Last edited:
+//    16 Oct 2026, 16:52:29
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the [`DevTokenMinter`].
+//
+
+use axum_server::DevTokenMinter;
+use axum_server::spec::DevTokenRequest;
+use chrono::TimeDelta;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use serde_json::{Map, Value};
+
+
+/***** TESTS *****/
+#[test]
+fn test_dev_token_claims() {
+    let minter = DevTokenMinter::new(Some("A".into()), &[0; 32]).with_initiator_claim("username").with_ttl(TimeDelta::minutes(5));
+    let res = minter.mint(&DevTokenRequest { user: "amy".into(), name: None, roles: vec!["admin".into()] }).unwrap();
+
+    // The header should point to the key
+    assert_eq!(decode_header(&res.token).unwrap().kid.as_deref(), Some("A"));
+
+    // The claims should describe the user
+    let claims: Map<String, Value> = decode(&res.token, &DecodingKey::from_secret(&[0; 32]), &Validation::new(Algorithm::HS256)).unwrap().claims;
+    assert_eq!(claims["username"], "amy");
+    assert_eq!(claims["name"], "amy");
+    assert_eq!(claims["roles"], Value::Array(vec!["admin".into()]));
+    assert_eq!(claims["exp"], res.expires.timestamp());
+    assert_eq!(claims["exp"].as_i64().unwrap() - claims["iat"].as_i64().unwrap(), 300);
+    assert!(claims["jti"].is_string());
+}
+
+#[test]
+fn test_dev_token_unique() {
+    let minter = DevTokenMinter::new(None, b"secret");
+    let req = DevTokenRequest { user: "amy".into(), name: Some("Amy".into()), roles: Vec::new() };
+    assert_ne!(minter.mint(&req).unwrap().token, minter.mint(&req).unwrap().token);
+}
+
+#[test]
+fn test_dev_token_wrong_key() {
+    let minter = DevTokenMinter::new(Some("A".into()), &[0; 32]);
+    let res = minter.mint(&DevTokenRequest { user: "amy".into(), name: None, roles: Vec::new() }).unwrap();
+    assert!(decode::<Map<String, Value>>(&res.token, &DecodingKey::from_secret(&[1; 32]), &Validation::new(Algorithm::HS256)).is_err());
+}
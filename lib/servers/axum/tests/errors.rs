@@ -0,0 +1,178 @@
+//  ERRORS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:19:07
+//  Last edited:
+//    17 Oct 2026, 01:53:06
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests that failing requests are answered with problems, whether
+//!   they fail in our handlers or before reaching them.
+//
+
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{Method, StatusCode};
+use axum_server::spec::{
+    ACTIVATE_PATH, ADD_KEY_PATH, ADD_SCHEMA_PATH, ADD_TEMPLATE_PATH, ADD_VERSION_PATH, ActivateRequest, AddKeyRequest, AddSchemaRequest,
+    AddTemplateRequest, ApiErrorCode, BodyPosition, CREATE_BRANCH_PATH, CreateBranchRequest, DENY_ALL_PATH, EXPORT_AUDIT_LOG_PATH,
+    FREEZE_VERSION_PATH, FreezeVersionRequest, GET_AUDIT_LOG_PATH, GET_VERSION_METADATA_PATH, GET_VERSIONS_PATH, GRPC_TIMEOUT_HEADER,
+    IMPORT_VERSION_PATH, MAINTAIN_PATH, PRUNE_ACTIVATIONS_PATH, PSEUDONYMIZE_USER_PATH, PruneActivationsRequest, REQUEST_DEADLINE_HEADER,
+    REQUEST_ID_HEADER, REVOKE_KEY_PATH, REVOKE_TOKENS_PATH, RevokeTokensRequest, UNFREEZE_VERSION_PATH,
+};
+use axum_server::testing::TestServer;
+use chrono::Utc;
+use serde_json::json;
+use specifications::key::{AttachedKey, KeyKind};
+use specifications::revocation::AttachedRevocation;
+use specifications::schema::AttachedSchema;
+use specifications::template::AttachedTemplate;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_malformed_body() {
+    let server = TestServer::start().await;
+    let res =
+        server.send(server.request(ADD_VERSION_PATH.method, ADD_VERSION_PATH.path).header(CONTENT_TYPE, "application/json").body("{\n  oops")).await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    let problem = res.problem();
+    assert_eq!(problem.code, ApiErrorCode::MalformedBody);
+    assert_eq!(problem.position, Some(BodyPosition { line: 2, column: 3 }));
+    assert_eq!(problem.instance.as_deref(), Some(ADD_VERSION_PATH.path));
+}
+
+#[tokio::test]
+async fn test_unsupported_media_type() {
+    let server = TestServer::start().await;
+    let res = server.send(server.request(ADD_VERSION_PATH.method, ADD_VERSION_PATH.path).header(CONTENT_TYPE, "text/plain").body("{}")).await;
+    assert_eq!(res.status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(res.problem().code, ApiErrorCode::UnsupportedMediaType);
+}
+
+#[tokio::test]
+async fn test_invalid_path_argument() {
+    let server = TestServer::start().await;
+    let res = server.get(&GET_VERSION_METADATA_PATH.instantiated_path(["latest"])).await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    assert_eq!(res.problem().code, ApiErrorCode::BadRequest);
+}
+
+#[tokio::test]
+async fn test_unknown_route_and_method() {
+    let server = TestServer::start().await;
+    let res = server.get("/v2/nonexistent").await;
+    assert_eq!(res.status, StatusCode::NOT_FOUND);
+    assert_eq!(res.problem().code, ApiErrorCode::NotFound);
+
+    let res = server.send(server.request(Method::PATCH, ACTIVATE_PATH.path)).await;
+    assert_eq!(res.status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(res.problem().code, ApiErrorCode::MethodNotAllowed);
+}
+
+#[tokio::test]
+async fn test_request_ids() {
+    let server = TestServer::start().await;
+
+    // Given IDs are echoed, both in the header and the problem
+    let res = server.send(server.request(Method::GET, "/v2/nonexistent").header(REQUEST_ID_HEADER, "test-123")).await;
+    assert_eq!(res.headers.get(REQUEST_ID_HEADER).unwrap(), "test-123");
+    assert_eq!(res.problem().request_id.as_deref(), Some("test-123"));
+
+    // Otherwise, every request gets a fresh one
+    let first = server.get("/v2/policies").await;
+    let second = server.get("/v2/policies").await;
+    assert_eq!(first.status, StatusCode::OK);
+    assert_ne!(first.headers.get(REQUEST_ID_HEADER).unwrap(), second.headers.get(REQUEST_ID_HEADER).unwrap());
+}
+
+#[tokio::test]
+async fn test_request_deadlines() {
+    let server = TestServer::start().await;
+
+    // Requests whose deadline already passed aren't handled at all
+    let res = server.send(server.request(Method::GET, GET_VERSIONS_PATH.path).header(REQUEST_DEADLINE_HEADER, "2000-01-01T00:00:00Z")).await;
+    assert_eq!(res.status, StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(res.problem().code, ApiErrorCode::DeadlineExceeded);
+
+    // Others are, as long as they're given in a format we understand
+    let res = server.send(server.request(Method::GET, GET_VERSIONS_PATH.path).header(GRPC_TIMEOUT_HEADER, "10S")).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res = server.send(server.request(Method::GET, GET_VERSIONS_PATH.path).header(GRPC_TIMEOUT_HEADER, "soon")).await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    assert_eq!(res.problem().code, ApiErrorCode::BadRequest);
+}
+
+#[tokio::test]
+async fn test_required_roles() {
+    // The `NoOpResolver` doesn't assign any roles, so its user may only call unrestricted endpoints
+    let server = TestServer::start_with(|server| server.with_required_roles(&ACTIVATE_PATH, ["policy-admin"])).await;
+    let res = server.put(ACTIVATE_PATH.path, &ActivateRequest { version: 1 }).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    assert_eq!(res.problem().code, ApiErrorCode::Forbidden);
+
+    let res = server.get(GET_VERSIONS_PATH.path).await;
+    assert_eq!(res.status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_admin_roles() {
+    // Administrative endpoints are restricted even if no roles are configured at all
+    let server = TestServer::start().await;
+    let res = server.send(server.request(PSEUDONYMIZE_USER_PATH.method, &PSEUDONYMIZE_USER_PATH.instantiated_path(["johnsmith"]))).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    assert_eq!(res.problem().code, ApiErrorCode::Forbidden);
+    let key = AttachedKey { id: "signer".into(), kind: KeyKind::Oct, material: "c2VjcmV0".into() };
+    let res = server.post(ADD_KEY_PATH.path, &AddKeyRequest { key }).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let res = server.send(server.request(REVOKE_KEY_PATH.method, &REVOKE_KEY_PATH.instantiated_path(["signer"]))).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let revocation = AttachedRevocation::Token { jti: "token".into() };
+    let res = server.post(REVOKE_TOKENS_PATH.path, &RevokeTokensRequest { revocation }).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let res = server.post(&FREEZE_VERSION_PATH.instantiated_path(["1"]), &FreezeVersionRequest { reason: "test".into() }).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let res = server.delete(&UNFREEZE_VERSION_PATH.instantiated_path(["1"])).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let template = AttachedTemplate {
+        name: "allow".into(),
+        description: "Allows everything".into(),
+        language: "json".into(),
+        parameters: Vec::new(),
+        skeleton: json!(true),
+    };
+    let res = server.post(ADD_TEMPLATE_PATH.path, &AddTemplateRequest { template }).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let schema = AttachedSchema { name: "anything".into(), description: "Allows anything".into(), schema: json!(true) };
+    let res = server.post(ADD_SCHEMA_PATH.path, &AddSchemaRequest { schema }).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let res = server.send(server.request(IMPORT_VERSION_PATH.method, IMPORT_VERSION_PATH.path)).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let res = server.get(GET_AUDIT_LOG_PATH.path).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let res = server.get(EXPORT_AUDIT_LOG_PATH.path).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let res = server.post(PRUNE_ACTIVATIONS_PATH.path, &PruneActivationsRequest { before: Utc::now() }).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let res = server.send(server.request(MAINTAIN_PATH.method, MAINTAIN_PATH.path)).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    let res = server.send(server.request(DENY_ALL_PATH.method, DENY_ALL_PATH.path)).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+
+    // Also when they are configured, as long as the user has none of them
+    let server = TestServer::start_with(|server| server.with_admin_roles(["policy-admin"])).await;
+    let res = server.send(server.request(PSEUDONYMIZE_USER_PATH.method, &PSEUDONYMIZE_USER_PATH.instantiated_path(["johnsmith"]))).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_client_error_details() {
+    let server = TestServer::start().await;
+    let res = server.post(CREATE_BRANCH_PATH.path, &CreateBranchRequest { name: "feature".into(), from: 42 }).await;
+    assert_eq!(res.status, StatusCode::NOT_FOUND);
+    let problem = res.problem();
+    assert_eq!(problem.code, ApiErrorCode::VersionNotFound);
+    assert_eq!(problem.details, Some(json!({ "version": 42 })));
+}
@@ -0,0 +1,67 @@
+//  FEDERATION.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:06:26
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the federated view over upstream stores.
+//
+
+use axum::http::StatusCode;
+use axum_server::spec::{
+    ACTIVATE_PATH, ADD_VERSION_PATH, ActivateRequest, AddVersionRequest, AddVersionResponse, GET_FEDERATED_VERSIONS_PATH,
+    GetFederatedVersionsResponse,
+};
+use axum_server::testing::TestServer;
+use reqwest_client::{Client, RetryPolicy};
+use serde_json::json;
+use specifications::metadata::AttachedMetadata;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_federated_versions() {
+    // Prepare an upstream with an active version
+    let upstream = TestServer::start().await;
+    let metadata = AttachedMetadata {
+        name: "test".into(),
+        description: "A test policy".into(),
+        language: "json".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+    let res = upstream.post(ADD_VERSION_PATH.path, &AddVersionRequest { metadata, contents: json!(true) }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let version: u64 = res.json::<AddVersionResponse>().version;
+    let res = upstream.put(ACTIVATE_PATH.path, &ActivateRequest { version }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+
+    // Federate it with one that doesn't exist
+    let server = TestServer::start_with(|server| {
+        server
+            .with_upstream("good", Client::new(upstream.url("")))
+            .with_upstream("bad", Client::new("http://127.0.0.1:1").with_retry_policy(RetryPolicy::none()))
+    })
+    .await;
+    let res = server.get(GET_FEDERATED_VERSIONS_PATH.path).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res: GetFederatedVersionsResponse = res.json();
+    assert_eq!(res.stores.len(), 2);
+    assert!(res.stores["good"].versions.contains_key(&version));
+    assert_eq!(res.stores["good"].active, Some(version));
+    assert_eq!(res.stores["good"].error, None);
+    assert!(res.stores["bad"].versions.is_empty());
+    assert!(res.stores["bad"].error.is_some());
+}
+
+#[tokio::test]
+async fn test_federated_versions_without_upstreams() {
+    let server = TestServer::start().await;
+    assert_eq!(server.get(GET_FEDERATED_VERSIONS_PATH.path).await.status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,37 @@
+//  PING.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:14:00
+//  Last edited:
+//    16 Oct 2026, 17:14:00
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the unauthenticated ping endpoint used for container
+//!   healthchecks.
+//
+
+use axum::http::StatusCode;
+use axum_server::spec::{PING_PATH, PingResponse};
+use axum_server::testing::TestServer;
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_ping() {
+    // NOTE: Required roles are checked with the token, so this shows no token is needed
+    let server = TestServer::start_with(|server| server.with_required_roles(&PING_PATH, ["nobody"])).await;
+    let res = server.get(PING_PATH.path).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res: PingResponse = res.json();
+    assert_eq!(res.name, "axum-server");
+    assert!(!res.version.is_empty());
+}
+
+#[tokio::test]
+async fn test_ping_disabled() {
+    let server = TestServer::start_with(|server| server.without_ping()).await;
+    assert_eq!(server.get(PING_PATH.path).await.status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,204 @@
+//  SERVE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:58:56
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests running the [`AxumServer`] through the [`Server`] trait,
+//!   including its lifecycle hooks and background workers.
+//
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use axum_server::spec::GET_VERSIONS_PATH;
+use axum_server::{AxumServer, BootstrapPolicy, Error};
+use futures::channel::oneshot;
+use no_op_auth::NoOpResolver;
+use serde_json::{Value, json};
+use specifications::consistency::Finding;
+use specifications::databaseconn::DatabaseConnection as _;
+use specifications::metadata::{AttachedMetadata, User};
+use specifications::{DatabaseConnector as _, Server, ServerHooks, Worker};
+use sqlite_database::{MIGRATIONS, SQLiteDatabase};
+
+
+/***** HELPERS *****/
+/// Reports the lifecycle of the server back to the test.
+struct TestHooks {
+    /// Where to send the bound address to.
+    started: Option<oneshot::Sender<SocketAddr>>,
+    /// Set when the server stops.
+    stopped: Arc<AtomicBool>,
+}
+impl ServerHooks for TestHooks {
+    fn on_start(&mut self, addr: SocketAddr) {
+        if let Some(started) = self.started.take() {
+            let _ = started.send(addr);
+        }
+    }
+
+    fn on_stop(&mut self) { self.stopped.store(true, Ordering::SeqCst); }
+}
+
+/// Counts how often it is ticked.
+struct TestWorker {
+    /// The number of times the worker was ticked.
+    ticks:   Arc<AtomicUsize>,
+    /// Set when the worker is stopped.
+    stopped: Arc<AtomicBool>,
+}
+impl Worker for TestWorker {
+    type Error = Infallible;
+
+    fn interval(&self) -> Duration { Duration::from_millis(10) }
+
+    fn tick(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        self.ticks.fetch_add(1, Ordering::SeqCst);
+        async { Ok(()) }
+    }
+
+    fn stop(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        self.stopped.store(true, Ordering::SeqCst);
+        async { Ok(()) }
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_serve_with_hooks() {
+    let db: SQLiteDatabase<Value> = SQLiteDatabase::in_memory_async(MIGRATIONS).await.unwrap();
+    let server = AxumServer::new(([127, 0, 0, 1], 0), NoOpResolver::new(), db);
+
+    let (started_tx, started_rx) = oneshot::channel::<SocketAddr>();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let stopped = Arc::new(AtomicBool::new(false));
+    let hooks = TestHooks { started: Some(started_tx), stopped: stopped.clone() };
+
+    let (res, ()) = tokio::join!(
+        server.serve_with(hooks, async move {
+            let _ = shutdown_rx.await;
+        }),
+        async move {
+            // The server should tell us the port it actually got
+            let addr: SocketAddr = started_rx.await.unwrap();
+            assert_ne!(addr.port(), 0);
+
+            let res = reqwest::get(format!("http://{addr}{}", GET_VERSIONS_PATH.path)).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+
+            shutdown_tx.send(()).unwrap();
+        }
+    );
+    res.unwrap();
+    assert!(stopped.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_serve_with_worker() {
+    let db: SQLiteDatabase<Value> = SQLiteDatabase::in_memory_async(MIGRATIONS).await.unwrap();
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let stopped = Arc::new(AtomicBool::new(false));
+    let server =
+        AxumServer::new(([127, 0, 0, 1], 0), NoOpResolver::new(), db).with_worker(TestWorker { ticks: ticks.clone(), stopped: stopped.clone() });
+
+    // Let the worker tick a couple of times before shutting down
+    server
+        .serve_with((), async {
+            while ticks.load(Ordering::SeqCst) < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    assert!(ticks.load(Ordering::SeqCst) >= 2);
+    assert!(stopped.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_serve_verifies_database() {
+    // A consistent database is served as usual
+    let db: SQLiteDatabase<Value> = SQLiteDatabase::in_memory_async(MIGRATIONS).await.unwrap();
+    AxumServer::new(([127, 0, 0, 1], 0), NoOpResolver::new(), db).with_startup_verification().serve_with((), async {}).await.unwrap();
+
+    // Content that doesn't fit what the server expects is found before any request runs into it
+    let path = std::env::temp_dir().join(format!("policy-store-serve-verify-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let db: SQLiteDatabase<Value> = SQLiteDatabase::new_async(&path, MIGRATIONS).await.unwrap();
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let metadata = AttachedMetadata {
+        name: "test".into(),
+        description: "A test policy".into(),
+        language: "test".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+    db.connect(&user).await.unwrap().add_version(metadata, json!("not a boolean")).await.unwrap();
+    drop(db);
+
+    let db: SQLiteDatabase<bool> = SQLiteDatabase::new_async(&path, MIGRATIONS).await.unwrap();
+    let res = AxumServer::new(([127, 0, 0, 1], 0), NoOpResolver::new(), db).with_startup_verification().serve_with((), async {}).await;
+    match res {
+        Err(Error::Inconsistent { findings }) => assert!(matches!(findings.as_slice(), [Finding::InvalidContent { version: 1, .. }])),
+        res => panic!("Expected the server to refuse an inconsistent database, got {res:?}"),
+    }
+
+    // Embedding applications can do the same check themselves
+    let db: SQLiteDatabase<bool> = SQLiteDatabase::new_async(&path, MIGRATIONS).await.unwrap();
+    let res = AxumServer::new(([127, 0, 0, 1], 0), NoOpResolver::new(), db).verify_backend().await;
+    assert!(matches!(res, Err(Error::Inconsistent { .. })), "Expected an inconsistent database, got {res:?}");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_serve_bootstraps_empty_database() {
+    let path = std::env::temp_dir().join(format!("policy-store-serve-bootstrap-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let user = User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() };
+    let metadata = AttachedMetadata {
+        name: "allow".into(),
+        description: "Allows everything".into(),
+        language: "test".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+
+    // An empty database gets the policy...
+    let db: SQLiteDatabase<Value> = SQLiteDatabase::new_async(&path, MIGRATIONS).await.unwrap();
+    AxumServer::new(([127, 0, 0, 1], 0), NoOpResolver::new(), db)
+        .with_bootstrap_policy(BootstrapPolicy::new(metadata.clone(), json!(true)))
+        .serve_with((), async {})
+        .await
+        .unwrap();
+    let db: SQLiteDatabase<Value> = SQLiteDatabase::new_async(&path, MIGRATIONS).await.unwrap();
+    let mut conn = db.connect(&user).await.unwrap();
+    assert_eq!(conn.get_active_version().await.unwrap(), Some(1));
+    assert_eq!(conn.get_version_content(1).await.unwrap(), Some(json!(true)));
+    drop(conn);
+
+    // ...but once there's anything in it, it is left alone
+    AxumServer::new(([127, 0, 0, 1], 0), NoOpResolver::new(), db)
+        .with_bootstrap_policy(BootstrapPolicy::new(metadata, json!(false)))
+        .serve_with((), async {})
+        .await
+        .unwrap();
+    let db: SQLiteDatabase<Value> = SQLiteDatabase::new_async(&path, MIGRATIONS).await.unwrap();
+    assert_eq!(db.connect(&user).await.unwrap().get_versions().await.unwrap().len(), 1);
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,206 @@
+//  STANDBY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:55:48
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests replicating from a primary as its warm standby, and promoting
+//!   the standby to take over.
+//
+
+use std::time::Duration;
+
+use axum::http::{Method, StatusCode};
+use axum_server::spec::{
+    ACTIVATE_PATH, ADD_DECISION_PATH, ADD_KEY_PATH, ADD_SCHEMA_PATH, ADD_TEMPLATE_PATH, ADD_VERSION_PATH, ActivateRequest, AddDecisionRequest,
+    AddKeyRequest, AddSchemaRequest, AddTemplateRequest, AddVersionRequest, AddVersionResponse, ApiErrorCode, GET_ACTIVATIONS_PATH,
+    GET_ACTIVE_VERSION_PATH, GET_DECISION_PATH, GET_KEYS_PATH, GET_SCHEMAS_PATH, GET_TEMPLATES_PATH, GET_VERSION_METADATA_PATH,
+    GetActivationsResponse, GetActiveVersionResponse, GetDecisionResponse, GetKeysResponse, GetSchemasResponse, GetTemplatesResponse,
+    GetVersionMetadataResponse, PROMOTE_STANDBY_PATH, PSEUDONYMIZE_USER_PATH, PromoteStandbyResponse, PseudonymizeUserResponse,
+};
+use axum_server::testing::TestServer;
+use axum_server::{PseudonymKey, Standby};
+use reqwest_client::Client;
+use serde_json::json;
+use specifications::key::{AttachedKey, KeyKind};
+use specifications::metadata::AttachedMetadata;
+use specifications::schema::AttachedSchema;
+use specifications::template::AttachedTemplate;
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates a request to add a version.
+///
+/// # Returns
+/// An [`AddVersionRequest`] for a trivial policy on the main branch.
+fn add_version() -> AddVersionRequest<serde_json::Value> {
+    let metadata = AttachedMetadata {
+        name: "test".into(),
+        description: "A test policy".into(),
+        language: "json".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+    AddVersionRequest { metadata, contents: json!(true) }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_standby_replicates_and_promotes() {
+    // Prepare a primary with an active version
+    let primary = TestServer::start().await;
+    let res = primary.post(ADD_VERSION_PATH.path, &add_version()).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let version: u64 = res.json::<AddVersionResponse>().version;
+    let res = primary.put(ACTIVATE_PATH.path, &ActivateRequest { version }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+
+    // The standby catches up on its own
+    let standby = TestServer::start_admin_with(|server| {
+        server.with_standby(Standby::new(Client::new(primary.url(""))).with_interval(Duration::from_millis(50)))
+    })
+    .await;
+    let mut active: Option<u64> = None;
+    for _ in 0..100 {
+        active = standby.get(GET_ACTIVE_VERSION_PATH.path).await.json::<GetActiveVersionResponse>().version;
+        if active.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(active, Some(version));
+
+    // But refuses changes of its own
+    let res = standby.post(ADD_VERSION_PATH.path, &add_version()).await;
+    assert_eq!(res.status, StatusCode::SERVICE_UNAVAILABLE, "{}", res.text());
+    assert_eq!(res.problem().code, ApiErrorCode::Unavailable);
+
+    // Until promoted
+    let res = standby.send(standby.request(Method::POST, PROMOTE_STANDBY_PATH.path)).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    assert_eq!(res.json::<PromoteStandbyResponse>().cursor, 2);
+    let res = standby.post(ADD_VERSION_PATH.path, &add_version()).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    assert_eq!(res.json::<AddVersionResponse>().version, version + 1);
+}
+
+#[tokio::test]
+async fn test_standby_replicates_additions() {
+    // Prepare a primary with a version and everything that can be added to the store besides
+    let primary = TestServer::start_admin().await;
+    let res = primary.post(ADD_VERSION_PATH.path, &add_version()).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let version: u64 = res.json::<AddVersionResponse>().version;
+    let template = AttachedTemplate {
+        name: "allow".into(),
+        description: "Allows everything".into(),
+        language: "json".into(),
+        parameters: Vec::new(),
+        skeleton: json!(true),
+    };
+    let res = primary.post(ADD_TEMPLATE_PATH.path, &AddTemplateRequest { template }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let schema = AttachedSchema { name: "anything".into(), description: "Allows anything".into(), schema: json!(true) };
+    let res = primary.post(ADD_SCHEMA_PATH.path, &AddSchemaRequest { schema }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res = primary.post(&ADD_DECISION_PATH.instantiated_path([version.to_string().as_str()]), &AddDecisionRequest { id: "d1".into() }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let key = AttachedKey { id: "signer".into(), kind: KeyKind::Oct, material: "c2VjcmV0".into() };
+    let res = primary.post(ADD_KEY_PATH.path, &AddKeyRequest { key }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+
+    // The standby replicates all of it, in order
+    let standby = TestServer::start_admin_with(|server| {
+        server.with_standby(Standby::new(Client::new(primary.url(""))).with_interval(Duration::from_millis(50)))
+    })
+    .await;
+    let mut keys: Vec<String> = Vec::new();
+    for _ in 0..100 {
+        keys = standby.get(GET_KEYS_PATH.path).await.json::<GetKeysResponse>().keys.into_keys().collect();
+        if !keys.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(keys, vec!["signer".to_string()]);
+    let templates = standby.get(GET_TEMPLATES_PATH.path).await.json::<GetTemplatesResponse>().templates;
+    assert_eq!(templates.get("allow").map(|template| template.attached.skeleton.clone()), Some(json!(true)));
+    let schemas = standby.get(GET_SCHEMAS_PATH.path).await.json::<GetSchemasResponse>().schemas;
+    assert!(schemas.contains_key("anything"));
+    let res = standby.get(&GET_DECISION_PATH.instantiated_path(["d1"])).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    assert_eq!(res.json::<GetDecisionResponse>().decision.version, version);
+}
+
+#[tokio::test]
+async fn test_standby_replicates_pseudonymization() {
+    // Prepare a primary with a version activated by the user, and a standby that replicated it
+    let primary = TestServer::start_admin_with(|server| server.with_pseudonym_key(PseudonymKey::new(*b"secret"))).await;
+    let res = primary.post(ADD_VERSION_PATH.path, &add_version()).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let version: u64 = res.json::<AddVersionResponse>().version;
+    let res = primary.put(ACTIVATE_PATH.path, &ActivateRequest { version }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let standby = TestServer::start_admin_with(|server| {
+        server
+            .with_pseudonym_key(PseudonymKey::new(*b"secret"))
+            .with_standby(Standby::new(Client::new(primary.url(""))).with_interval(Duration::from_millis(50)))
+    })
+    .await;
+    let mut active: Option<u64> = None;
+    for _ in 0..100 {
+        active = standby.get(GET_ACTIVE_VERSION_PATH.path).await.json::<GetActiveVersionResponse>().version;
+        if active.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(active, Some(version));
+
+    // Pseudonymizing the user on the primary...
+    let res = primary.send(primary.request(PSEUDONYMIZE_USER_PATH.method, &PSEUDONYMIZE_USER_PATH.instantiated_path(["johnsmith"]))).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let pseudonym: String = res.json::<PseudonymizeUserResponse>().pseudonym;
+
+    // ...pseudonymizes them on the standby too
+    let path = GET_VERSION_METADATA_PATH.instantiated_path([version.to_string().as_str()]);
+    let mut creator: Option<String> = None;
+    for _ in 0..100 {
+        let res = standby.get(&path).await;
+        if res.status == StatusCode::OK {
+            creator = Some(res.json::<GetVersionMetadataResponse>().metadata.creator.id);
+            if creator.as_ref() == Some(&pseudonym) {
+                break;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(creator, Some(pseudonym.clone()));
+    let activations = standby.get(GET_ACTIVATIONS_PATH.path).await.json::<GetActivationsResponse>().activations;
+    assert_eq!(activations.iter().map(|activation| activation.activator.id.as_str()).collect::<Vec<&str>>(), vec![pseudonym.as_str()]);
+}
+
+#[tokio::test]
+async fn test_promote_as_non_admin() {
+    let primary = TestServer::start().await;
+    let standby = TestServer::start_with(|server| server.with_standby(Standby::new(Client::new(primary.url(""))))).await;
+    let res = standby.send(standby.request(Method::POST, PROMOTE_STANDBY_PATH.path)).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    assert_eq!(res.problem().code, ApiErrorCode::Forbidden);
+}
+
+#[tokio::test]
+async fn test_promote_without_standby() {
+    let server = TestServer::start_admin().await;
+    assert_eq!(server.send(server.request(Method::POST, PROMOTE_STANDBY_PATH.path)).await.status, StatusCode::NOT_FOUND);
+}
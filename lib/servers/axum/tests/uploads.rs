@@ -0,0 +1,169 @@
+//  UPLOADS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:19:07
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the endpoints for chunked uploads of policy versions.
+//
+
+use axum::http::StatusCode;
+use axum_server::spec::{
+    AddVersionResponse, ApiErrorCode, CANCEL_UPLOAD_PATH, FINALIZE_UPLOAD_PATH, GET_UPLOAD_PATH, GET_VERSION_CONTENT_PATH, GetVersionContentResponse,
+    INITIATE_UPLOAD_PATH, InitiateUploadRequest, InitiateUploadResponse, UPLOAD_CHUNK_PATH, UPLOAD_OFFSET_HEADER, UploadStatusResponse,
+};
+use axum_server::testing::{TestResponse, TestServer};
+use serde_json::{Value, json};
+use specifications::metadata::AttachedMetadata;
+
+
+/***** HELPER FUNCTIONS *****/
+/// Initiates an upload on the server.
+///
+/// # Arguments
+/// - `server`: The [`TestServer`] to upload to.
+/// - `length`: The total length of the upload, if known.
+///
+/// # Returns
+/// The path of the new upload.
+async fn initiate(server: &TestServer, length: Option<u64>) -> String {
+    let metadata = AttachedMetadata {
+        name: "uploaded".into(),
+        description: "An uploaded policy".into(),
+        language: "json".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+    let res = server.post(INITIATE_UPLOAD_PATH.path, &InitiateUploadRequest { metadata, length }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    UPLOAD_CHUNK_PATH.instantiated_path([res.json::<InitiateUploadResponse>().id.to_string().as_str()]).into_owned()
+}
+
+/// Sends a chunk of an upload.
+///
+/// # Arguments
+/// - `server`: The [`TestServer`] to upload to.
+/// - `path`: The path of the upload.
+/// - `offset`: Where the chunk starts.
+/// - `chunk`: The chunk itself.
+///
+/// # Returns
+/// The server's [`TestResponse`].
+async fn send_chunk(server: &TestServer, path: &str, offset: u64, chunk: &'static str) -> TestResponse {
+    server.send(server.request(UPLOAD_CHUNK_PATH.method, path).header(UPLOAD_OFFSET_HEADER, offset).body(chunk)).await
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_upload_in_chunks() {
+    let server = TestServer::start().await;
+    let path: String = initiate(&server, Some(16)).await;
+
+    let res = send_chunk(&server, &path, 0, "{\"allow\": ").await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    assert_eq!(res.json::<UploadStatusResponse>().offset, 10);
+    let res = send_chunk(&server, &path, 10, "[\"b\"]}").await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+
+    let res = server.get(&path).await;
+    assert_eq!(res.json::<UploadStatusResponse>().offset, 16);
+
+    // Finalize it and check it ended up as a version
+    let res = server.send(server.request(FINALIZE_UPLOAD_PATH.method, &format!("{path}/finalize"))).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let version: u64 = res.json::<AddVersionResponse>().version;
+    let res = server.get(&GET_VERSION_CONTENT_PATH.instantiated_path([version.to_string().as_str()])).await;
+    assert_eq!(res.json::<GetVersionContentResponse<Value>>().content, json!({ "allow": ["b"] }));
+
+    // The upload itself is gone
+    assert_eq!(server.get(&path).await.problem().code, ApiErrorCode::UploadNotFound);
+}
+
+#[tokio::test]
+async fn test_upload_errors() {
+    let server = TestServer::start().await;
+    let path: String = initiate(&server, Some(4)).await;
+
+    let res = send_chunk(&server, &path, 2, "ab").await;
+    assert_eq!(res.status, StatusCode::CONFLICT);
+    assert_eq!(res.problem().code, ApiErrorCode::Conflict);
+
+    let res = send_chunk(&server, &path, 0, "abcdef").await;
+    assert_eq!(res.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(res.problem().code, ApiErrorCode::PayloadTooLarge);
+
+    let res = server.send(server.request(UPLOAD_CHUNK_PATH.method, &path).body("ab")).await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    assert_eq!(res.problem().code, ApiErrorCode::BadRequest);
+
+    // Incomplete uploads can't be finalized, only cancelled
+    let res = server.send(server.request(FINALIZE_UPLOAD_PATH.method, &format!("{path}/finalize"))).await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    let res = server.send(server.request(CANCEL_UPLOAD_PATH.method, &path)).await;
+    assert_eq!(res.status, StatusCode::OK);
+    let res = server.get(&GET_UPLOAD_PATH.instantiated_path(["999"])).await;
+    assert_eq!(res.problem().code, ApiErrorCode::UploadNotFound);
+}
+
+#[tokio::test]
+async fn test_upload_limits() {
+    let server = TestServer::start_with(|server| server.with_upload_limits(4, 6)).await;
+
+    // Declared lengths over the limit are refused upfront
+    let metadata = AttachedMetadata {
+        name: "uploaded".into(),
+        description: "An uploaded policy".into(),
+        language: "json".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+    let res = server.post(INITIATE_UPLOAD_PATH.path, &InitiateUploadRequest { metadata, length: Some(5) }).await;
+    assert_eq!(res.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(res.problem().code, ApiErrorCode::PayloadTooLarge);
+
+    // Undeclared ones are refused once they grow too large, even within a single chunk
+    let first: String = initiate(&server, None).await;
+    let res = send_chunk(&server, &first, 0, "abcdefgh").await;
+    assert_eq!(res.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(res.problem().code, ApiErrorCode::PayloadTooLarge);
+    assert_eq!(send_chunk(&server, &first, 0, "abc").await.status, StatusCode::OK);
+    let res = send_chunk(&server, &first, 3, "de").await;
+    assert_eq!(res.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(res.problem().code, ApiErrorCode::PayloadTooLarge);
+
+    // As are uploads that together grow too large
+    let second: String = initiate(&server, None).await;
+    assert_eq!(send_chunk(&server, &second, 0, "abc").await.status, StatusCode::OK);
+    let res = send_chunk(&server, &second, 3, "d").await;
+    assert_eq!(res.status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    // Until some space is freed
+    assert_eq!(server.send(server.request(CANCEL_UPLOAD_PATH.method, &first)).await.status, StatusCode::OK);
+    assert_eq!(send_chunk(&server, &second, 3, "d").await.status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_failed_finalize_keeps_upload() {
+    let server = TestServer::start().await;
+    let path: String = initiate(&server, Some(4)).await;
+    let res = send_chunk(&server, &path, 0, "{nop").await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+
+    // Finalizing fails, but the upload is still there to fix
+    let res = server.send(server.request(FINALIZE_UPLOAD_PATH.method, &format!("{path}/finalize"))).await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    let res = server.get(&path).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    assert_eq!(res.json::<UploadStatusResponse>().offset, 4);
+}
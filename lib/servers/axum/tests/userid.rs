@@ -0,0 +1,65 @@
+//  USERID.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:50:06
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests normalizing the IDs of authorized users.
+//
+
+use axum::http::StatusCode;
+use axum_server::spec::{
+    ADD_VERSION_PATH, AddVersionRequest, AddVersionResponse, ApiErrorCode, GET_VERSION_METADATA_PATH, GET_VERSIONS_PATH, GetVersionMetadataResponse,
+};
+use axum_server::testing::TestServer;
+use serde_json::json;
+use specifications::metadata::AttachedMetadata;
+use specifications::userid::{Urn, UserIdError, UserIdFormat};
+
+
+/***** HELPERS *****/
+/// Puts plain IDs in the `example` URN namespace.
+struct Namespaced;
+impl UserIdFormat for Namespaced {
+    fn normalize(&self, id: &str) -> Result<String, UserIdError> {
+        if id.starts_with("urn:") { Urn.normalize(id) } else { Urn.normalize(&format!("urn:example:{id}")) }
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_normalized_user_id() {
+    let server = TestServer::start_with(|server| server.with_user_id_format(Namespaced)).await;
+    let metadata = AttachedMetadata {
+        name: "test".into(),
+        description: "A test policy".into(),
+        language: "json".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+    let res = server.post(ADD_VERSION_PATH.path, &AddVersionRequest { metadata, contents: json!(true) }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let version: u64 = res.json::<AddVersionResponse>().version;
+
+    // The version is stored with the normalized ID
+    let res = server.get(&GET_VERSION_METADATA_PATH.instantiated_path([version.to_string().as_str()])).await;
+    assert_eq!(res.json::<GetVersionMetadataResponse>().metadata.creator.id, "urn:example:johnsmith");
+}
+
+#[tokio::test]
+async fn test_illegal_user_id() {
+    let server = TestServer::start_with(|server| server.with_user_id_format(Urn)).await;
+    let res = server.get(GET_VERSIONS_PATH.path).await;
+    assert_eq!(res.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(res.problem().code, ApiErrorCode::Unauthorized);
+}
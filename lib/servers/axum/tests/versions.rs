@@ -0,0 +1,369 @@
+//  VERSIONS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:19:07
+//  Last edited:
+//    17 Oct 2026, 03:32:33
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the endpoints for adding, activating and retrieving policy
+//!   versions.
+//
+
+use axum::http::header::{CONTENT_RANGE, ETAG, IF_NONE_MATCH, RANGE};
+use axum::http::{Method, StatusCode};
+use axum_server::spec::{
+    ACTIVATE_PATH, ADD_VERSION_PATH, ActivateRequest, AddVersionRequest, AddVersionResponse, ApiErrorCode, DEACTIVATE_PATH, DENY_ALL_PATH,
+    DenyAllResponse, FREEZE_VERSION_PATH, FreezeVersionRequest, GET_ACTIVE_CONTENT_PATH, GET_ACTIVE_VERSION_PATH, GET_VERSION_CONTENT_PATH,
+    GET_VERSION_CONTENT_RAW_PATH, GET_VERSION_METADATA_PATH, GET_VERSION_PROVENANCE_PATH, GET_VERSIONS_PATH, GetActiveContentResponse,
+    GetActiveVersionResponse, GetVersionContentResponse, GetVersionMetadataResponse, GetVersionProvenanceResponse, GetVersionsResponse,
+    IMPORT_VERSION_PATH, ImportDryRunResponse, ImportVersionRequest, PSEUDONYMIZE_USER_PATH, PseudonymizeUserResponse, UNFREEZE_VERSION_PATH,
+};
+use axum_server::testing::TestServer;
+use axum_server::{DEFAULT_ADMIN_ROLE, NoActivePolicy, PseudonymKey};
+use serde_json::{Value, json};
+use specifications::metadata::{AttachedMetadata, Metadata};
+use specifications::provenance::{Creation, PREDICATE_TYPE, Provenance, STATEMENT_TYPE};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Adds a policy version to the server.
+///
+/// # Arguments
+/// - `server`: The [`TestServer`] to add it to.
+/// - `contents`: The contents of the policy.
+///
+/// # Returns
+/// The version number of the new policy.
+async fn add_version(server: &TestServer, contents: Value) -> u64 {
+    let metadata = AttachedMetadata {
+        name: "test".into(),
+        description: "A test policy".into(),
+        language: "json".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: false,
+    };
+    let res = server.post(ADD_VERSION_PATH.path, &AddVersionRequest { metadata, contents }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    res.json::<AddVersionResponse>().version
+}
+
+
+
+
+
+/***** TESTS *****/
+#[tokio::test]
+async fn test_add_and_get_version() {
+    let server = TestServer::start().await;
+    let version: u64 = add_version(&server, json!({ "allow": ["alice"] })).await;
+
+    let res = server.get(GET_VERSIONS_PATH.path).await;
+    assert_eq!(res.status, StatusCode::OK);
+    assert!(res.json::<GetVersionsResponse>().versions.contains_key(&version));
+
+    let res = server.get(&GET_VERSION_METADATA_PATH.instantiated_path([version.to_string().as_str()])).await;
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.json::<GetVersionMetadataResponse>().metadata.attached.name, "test");
+
+    let res = server.get(&GET_VERSION_CONTENT_PATH.instantiated_path([version.to_string().as_str()])).await;
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.json::<GetVersionContentResponse<Value>>().content, json!({ "allow": ["alice"] }));
+}
+
+#[tokio::test]
+async fn test_get_unknown_version() {
+    let server = TestServer::start().await;
+    for path in [GET_VERSION_METADATA_PATH, GET_VERSION_CONTENT_PATH, GET_VERSION_CONTENT_RAW_PATH, GET_VERSION_PROVENANCE_PATH] {
+        let res = server.get(&path.instantiated_path(["42"])).await;
+        assert_eq!(res.status, StatusCode::NOT_FOUND);
+        assert_eq!(res.problem().code, ApiErrorCode::VersionNotFound);
+    }
+}
+
+#[tokio::test]
+async fn test_activate_and_deactivate() {
+    let server = TestServer::start().await;
+    let version: u64 = add_version(&server, json!(true)).await;
+
+    // Nothing is active at first
+    let res = server.get(GET_ACTIVE_VERSION_PATH.path).await;
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.json::<GetActiveVersionResponse>().version, None);
+
+    let res = server.put(ACTIVATE_PATH.path, &ActivateRequest { version }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res = server.get(GET_ACTIVE_VERSION_PATH.path).await;
+    assert_eq!(res.json::<GetActiveVersionResponse>().version, Some(version));
+
+    let res = server.delete(DEACTIVATE_PATH.path).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res = server.get(GET_ACTIVE_VERSION_PATH.path).await;
+    assert_eq!(res.json::<GetActiveVersionResponse>().version, None);
+}
+
+#[tokio::test]
+async fn test_deny_all() {
+    let server = TestServer::start_admin().await;
+    let version: u64 = add_version(&server, json!(true)).await;
+    let res = server.put(ACTIVATE_PATH.path, &ActivateRequest { version }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+
+    // The deny-all policy replaces the active one
+    let res = server.send(server.request(Method::POST, DENY_ALL_PATH.path)).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let deny_all: u64 = res.json::<DenyAllResponse>().version;
+    assert_ne!(deny_all, version);
+    let res = server.get(GET_ACTIVE_VERSION_PATH.path).await;
+    assert_eq!(res.json::<GetActiveVersionResponse>().version, Some(deny_all));
+    let res = server.get(&GET_VERSION_CONTENT_PATH.instantiated_path([deny_all.to_string().as_str()])).await;
+    assert_eq!(res.json::<GetVersionContentResponse<Value>>().content, json!(false));
+
+    // It is well-known, so it's reused the next time...
+    let res = server.send(server.request(Method::POST, DENY_ALL_PATH.path)).await;
+    assert_eq!(res.json::<DenyAllResponse>().version, deny_all);
+
+    // ...unless it can no longer be activated
+    let res =
+        server.post(&FREEZE_VERSION_PATH.instantiated_path([deny_all.to_string().as_str()]), &FreezeVersionRequest { reason: "test".into() }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res = server.send(server.request(Method::POST, DENY_ALL_PATH.path)).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    assert!(res.json::<DenyAllResponse>().version > deny_all);
+}
+
+#[tokio::test]
+async fn test_get_active_content() {
+    let server = TestServer::start().await;
+    let version: u64 = add_version(&server, json!(true)).await;
+
+    // Nothing is active at first
+    let res = server.get(GET_ACTIVE_CONTENT_PATH.path).await;
+    assert_eq!(res.status, StatusCode::NOT_FOUND);
+    assert_eq!(res.problem().code, ApiErrorCode::NotFound);
+
+    let res = server.put(ACTIVATE_PATH.path, &ActivateRequest { version }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res = server.get(GET_ACTIVE_CONTENT_PATH.path).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res: GetActiveContentResponse<Value> = res.json();
+    assert_eq!((res.version, res.fallback, res.content), (Some(version), false, json!(true)));
+}
+
+#[tokio::test]
+async fn test_get_active_content_fallback() {
+    // Either fall back to the built-in deny-all policy...
+    let server = TestServer::start_with(|server| server.with_no_active_policy(NoActivePolicy::DenyAll)).await;
+    let res = server.get(GET_ACTIVE_CONTENT_PATH.path).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res: GetActiveContentResponse<Value> = res.json();
+    assert_eq!((res.version, res.fallback, res.content), (None, true, json!(false)));
+
+    // ...or to a configured version, which is an error if it doesn't exist
+    let server = TestServer::start_with(|server| server.with_no_active_policy(NoActivePolicy::Fallback(1))).await;
+    let res = server.get(GET_ACTIVE_CONTENT_PATH.path).await;
+    assert_eq!(res.status, StatusCode::INTERNAL_SERVER_ERROR);
+    let version: u64 = add_version(&server, json!({ "allow": ["alice"] })).await;
+    assert_eq!(version, 1);
+    let res = server.get(GET_ACTIVE_CONTENT_PATH.path).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res: GetActiveContentResponse<Value> = res.json();
+    assert_eq!((res.version, res.fallback, res.content), (Some(version), true, json!({ "allow": ["alice"] })));
+}
+
+#[tokio::test]
+async fn test_freeze_version() {
+    let server = TestServer::start_admin().await;
+    let version: u64 = add_version(&server, json!(true)).await;
+    let path: String = FREEZE_VERSION_PATH.instantiated_path([version.to_string().as_str()]).into_owned();
+
+    // Freezing it is visible in its metadata, and prevents activating it
+    let res = server.post(&path, &FreezeVersionRequest { reason: "suspected to be malicious".into() }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res = server.get(&GET_VERSION_METADATA_PATH.instantiated_path([version.to_string().as_str()])).await;
+    let metadata: Metadata = res.json::<GetVersionMetadataResponse>().metadata;
+    assert_eq!(metadata.frozen.expect("version should be frozen").reason, "suspected to be malicious");
+    let res = server.put(ACTIVATE_PATH.path, &ActivateRequest { version }).await;
+    assert_eq!(res.status, StatusCode::CONFLICT);
+    assert_eq!(res.problem().code, ApiErrorCode::VersionFrozen);
+
+    // Until it is unfrozen
+    let res = server.delete(&UNFREEZE_VERSION_PATH.instantiated_path([version.to_string().as_str()])).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res = server.put(ACTIVATE_PATH.path, &ActivateRequest { version }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+
+    // Unknown versions cannot be frozen
+    let res = server.post(&FREEZE_VERSION_PATH.instantiated_path(["42"]), &FreezeVersionRequest { reason: "typo".into() }).await;
+    assert_eq!(res.status, StatusCode::NOT_FOUND);
+    assert_eq!(res.problem().code, ApiErrorCode::VersionNotFound);
+}
+
+#[tokio::test]
+async fn test_active_version_revalidation() {
+    let server = TestServer::start().await;
+    let version: u64 = add_version(&server, json!(true)).await;
+
+    let res = server.get(GET_ACTIVE_VERSION_PATH.path).await;
+    let etag = res.headers.get(ETAG).expect("no ETag in response").clone();
+    let res = server.send(server.request(GET_ACTIVE_VERSION_PATH.method, GET_ACTIVE_VERSION_PATH.path).header(IF_NONE_MATCH, etag.clone())).await;
+    assert_eq!(res.status, StatusCode::NOT_MODIFIED);
+
+    // Once something else is active, the old tag no longer matches
+    server.put(ACTIVATE_PATH.path, &ActivateRequest { version }).await;
+    let res = server.send(server.request(GET_ACTIVE_VERSION_PATH.method, GET_ACTIVE_VERSION_PATH.path).header(IF_NONE_MATCH, etag)).await;
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.json::<GetActiveVersionResponse>().version, Some(version));
+}
+
+#[tokio::test]
+async fn test_raw_content_ranges() {
+    let server = TestServer::start().await;
+    let version: u64 = add_version(&server, json!("abcdefghij")).await;
+    let path: String = GET_VERSION_CONTENT_RAW_PATH.instantiated_path([version.to_string().as_str()]).into_owned();
+
+    let res = server.get(&path).await;
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.text(), "\"abcdefghij\"");
+
+    let res = server.send(server.request(GET_VERSION_CONTENT_RAW_PATH.method, &path).header(RANGE, "bytes=1-3")).await;
+    assert_eq!(res.status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(res.text(), "abc");
+    assert_eq!(res.headers.get(CONTENT_RANGE).unwrap(), "bytes 1-3/12");
+
+    let res = server.send(server.request(GET_VERSION_CONTENT_RAW_PATH.method, &path).header(RANGE, "bytes=100-")).await;
+    assert_eq!(res.status, StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(res.problem().code, ApiErrorCode::RangeNotSatisfiable);
+    assert_eq!(res.headers.get(CONTENT_RANGE).unwrap(), "bytes */12");
+}
+
+#[tokio::test]
+async fn test_get_provenance() {
+    let server = TestServer::start().await;
+    let version: u64 = add_version(&server, json!({ "allow": ["alice"] })).await;
+
+    let res = server.get(&GET_VERSION_PROVENANCE_PATH.instantiated_path([version.to_string().as_str()])).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let provenance: Provenance = res.json::<GetVersionProvenanceResponse>().provenance;
+    assert_eq!((provenance.statement_type.as_str(), provenance.predicate_type.as_str()), (STATEMENT_TYPE, PREDICATE_TYPE));
+    assert_eq!(provenance.subject[0].name, format!("test:{version}"));
+    assert_eq!(provenance.predicate.build_definition.internal_parameters.creation, Creation::Added);
+    assert_eq!(provenance.predicate.build_definition.external_parameters.description, "A test policy");
+}
+
+#[tokio::test]
+async fn test_import_version() {
+    let staging = TestServer::start().await;
+    let production = TestServer::start_admin().await;
+    let source: u64 = add_version(&staging, json!({ "allow": ["alice"] })).await;
+    let metadata: Metadata =
+        staging.get(&GET_VERSION_METADATA_PATH.instantiated_path([source.to_string().as_str()])).await.json::<GetVersionMetadataResponse>().metadata;
+
+    // The copy should remember where it came from
+    let req =
+        ImportVersionRequest { store: "staging".into(), metadata: metadata.clone(), contents: json!({ "allow": ["alice"] }), signature: None };
+    let res = production.post(IMPORT_VERSION_PATH.path, &req).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let version: u64 = res.json::<AddVersionResponse>().version;
+    let res = production.get(&GET_VERSION_METADATA_PATH.instantiated_path([version.to_string().as_str()])).await;
+    let imported: Metadata = res.json::<GetVersionMetadataResponse>().metadata;
+    assert_eq!(imported.hash, metadata.hash);
+    assert_eq!(imported.origin.map(|origin| (origin.store, origin.version)), Some(("staging".into(), source)));
+
+    // Tampered contents should be refused
+    let req = ImportVersionRequest { store: "staging".into(), metadata, contents: json!({ "allow": ["mallory"] }), signature: None };
+    let res = production.post(IMPORT_VERSION_PATH.path, &req).await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    assert_eq!(res.problem().code, ApiErrorCode::BadRequest);
+}
+
+#[tokio::test]
+async fn test_import_dry_run() {
+    let staging = TestServer::start().await;
+    let production = TestServer::start_admin().await;
+    let source: u64 = add_version(&staging, json!({ "allow": ["alice"] })).await;
+    let metadata: Metadata =
+        staging.get(&GET_VERSION_METADATA_PATH.instantiated_path([source.to_string().as_str()])).await.json::<GetVersionMetadataResponse>().metadata;
+    let dry_run = |req: &ImportVersionRequest<Value>| {
+        production.send(production.request(IMPORT_VERSION_PATH.method, &format!("{}?dry_run=true", IMPORT_VERSION_PATH.path)).json(req))
+    };
+
+    // A dry run reports what would change, without changing it
+    let req =
+        ImportVersionRequest { store: "staging".into(), metadata: metadata.clone(), contents: json!({ "allow": ["alice"] }), signature: None };
+    let res = dry_run(&req).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let preview: ImportDryRunResponse = res.json();
+    assert_eq!((preview.branch.as_str(), preview.head, preview.hash), ("main", None, metadata.hash.clone().unwrap()));
+    assert!(preview.duplicates.is_empty());
+    assert!(production.get(GET_VERSIONS_PATH.path).await.json::<GetVersionsResponse>().versions.is_empty());
+
+    // Once imported, it reports the copy as a duplicate
+    let res = production.post(IMPORT_VERSION_PATH.path, &req).await;
+    let version: u64 = res.json::<AddVersionResponse>().version;
+    let preview: ImportDryRunResponse = dry_run(&req).await.json();
+    assert_eq!((preview.head, preview.duplicates), (Some(version), vec![version]));
+
+    // Tampered contents are refused as usual
+    let req = ImportVersionRequest { store: "staging".into(), metadata, contents: json!({ "allow": ["mallory"] }), signature: None };
+    let res = dry_run(&req).await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    assert_eq!(production.get(GET_VERSIONS_PATH.path).await.json::<GetVersionsResponse>().versions.len(), 1);
+}
+
+#[tokio::test]
+async fn test_pseudonymize_user() {
+    let key = PseudonymKey::new(*b"secret");
+    let server = TestServer::start_admin_with(|server| server.with_pseudonym_key(PseudonymKey::new(*b"secret"))).await;
+    let version: u64 = add_version(&server, json!({ "allow": ["alice"] })).await;
+
+    // The pseudonym is derived from the user's ID with the key
+    let res = server.send(server.request(PSEUDONYMIZE_USER_PATH.method, &PSEUDONYMIZE_USER_PATH.instantiated_path(["johnsmith"]))).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let pseudonym: String = res.json::<PseudonymizeUserResponse>().pseudonym;
+    assert_eq!(pseudonym, key.pseudonym("johnsmith"));
+    let res = server.get(&GET_VERSION_METADATA_PATH.instantiated_path([version.to_string().as_str()])).await;
+    assert_eq!(res.json::<GetVersionMetadataResponse>().metadata.creator.id, pseudonym);
+}
+
+#[tokio::test]
+async fn test_confidential_content() {
+    let metadata = AttachedMetadata {
+        name: "secret".into(),
+        description: "A confidential policy".into(),
+        language: "json".into(),
+        branch: "main".into(),
+        schema: None,
+        confidential: true,
+    };
+
+    // Users without the right roles only get to see the metadata
+    let server = TestServer::start().await;
+    let res = server.post(ADD_VERSION_PATH.path, &AddVersionRequest { metadata: metadata.clone(), contents: json!({ "allow": ["alice"] }) }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let version: String = res.json::<AddVersionResponse>().version.to_string();
+    let res = server.get(&GET_VERSION_METADATA_PATH.instantiated_path([version.as_str()])).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    assert!(res.json::<GetVersionMetadataResponse>().metadata.attached.confidential);
+    for path in [GET_VERSION_CONTENT_PATH, GET_VERSION_CONTENT_RAW_PATH] {
+        let res = server.get(&path.instantiated_path([version.as_str()])).await;
+        assert_eq!(res.status, StatusCode::FORBIDDEN);
+        assert_eq!(res.problem().code, ApiErrorCode::Forbidden);
+    }
+    let res = server.put(ACTIVATE_PATH.path, &ActivateRequest { version: version.parse().unwrap() }).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    let res = server.get(GET_ACTIVE_CONTENT_PATH.path).await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+
+    // Users with one of the confidential roles may read it
+    let server = TestServer::start_admin_with(|server| server.with_admin_roles(["root"]).with_confidential_roles([DEFAULT_ADMIN_ROLE])).await;
+    let res = server.post(ADD_VERSION_PATH.path, &AddVersionRequest { metadata, contents: json!({ "allow": ["alice"] }) }).await;
+    let version: String = res.json::<AddVersionResponse>().version.to_string();
+    let res = server.get(&GET_VERSION_CONTENT_PATH.instantiated_path([version.as_str()])).await;
+    assert_eq!(res.status, StatusCode::OK, "{}", res.text());
+    assert_eq!(res.json::<GetVersionContentResponse<Value>>().content, json!({ "allow": ["alice"] }));
+}
@@ -0,0 +1,48 @@
+//  BUILD.rs
+//    by Lut99
+//
+//  Created:
+//    17 Oct 2026, 02:39:52
+//  Last edited:
+//    17 Oct 2026, 02:39:52
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Generates the gRPC messages and service stubs from
+//!   `proto/policy_store.proto`.
+//!
+//!   The `.proto` files are parsed with `protox`, such that building
+//!   doesn't need `protoc` installed.
+//
+
+
+/***** CONSTANTS *****/
+/// The messages defined by `metadata.proto`, which the `specifications` crate already provides.
+const METADATA_MESSAGES: [&str; 8] = ["User", "AttachedMetadata", "Origin", "Verification", "Freeze", "Metadata", "Branch", "Activation"];
+
+
+
+
+
+/***** ENTRYPOINT *****/
+fn main() {
+    // Emit that rebuilding on changes to the definitions is necessary
+    println!("cargo:rerun-if-changed=./proto");
+    println!("cargo:rerun-if-changed=../../spec/proto");
+
+    // Parse the definitions
+    let fds = match protox::compile(["policy_store.proto"], ["./proto", "../../spec/proto"]) {
+        Ok(fds) => fds,
+        Err(err) => panic!("Failed to parse 'proto/policy_store.proto': {err}"),
+    };
+
+    // Generate the code, re-using the metadata messages of the `specifications` crate
+    let mut builder = tonic_prost_build::configure().build_transport(false);
+    for message in METADATA_MESSAGES {
+        builder = builder.extern_path(format!(".policy_store.v2.{message}"), format!("::specifications::proto::{message}"));
+    }
+    if let Err(err) = builder.compile_fds(fds) {
+        panic!("Failed to generate code for 'proto/policy_store.proto': {err}");
+    }
+}
@@ -0,0 +1,62 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:46:23
+//  Last edited:
+//    17 Oct 2026, 02:43:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Pseudo-server that defines the gRPC service, methods and messages
+//!   for a `tonic`-based policy store server.
+//!
+//!   The messages and the client and server stubs of the service (see
+//!   [`policy_store_client`] and [`policy_store_server`]) are generated
+//!   from `proto/policy_store.proto` (see [`PROTO`]). The metadata
+//!   messages are re-exported from [`specifications::proto`].
+//
+
+pub use specifications::proto::{
+    Activation, AttachedMetadata, Branch, ConvertError, Metadata, PROTO as METADATA_PROTO, User, from_timestamp, to_timestamp,
+};
+
+
+/***** CONSTANTS *****/
+/// The `.proto` definitions of the API, e.g., for generating clients in other languages.
+///
+/// Note that this imports `metadata.proto`, which is given by [`METADATA_PROTO`].
+pub const PROTO: &str = include_str!("../proto/policy_store.proto");
+
+/// The fully qualified name of the policy store service.
+pub const SERVICE_NAME: &str = "policy_store.v2.PolicyStore";
+
+/// Path of the method to add a new policy version.
+pub const ADD_VERSION_METHOD: &str = "/policy_store.v2.PolicyStore/AddVersion";
+/// Path of the method to activate a policy version.
+pub const ACTIVATE_METHOD: &str = "/policy_store.v2.PolicyStore/Activate";
+/// Path of the method to deactivate the active policy version.
+pub const DEACTIVATE_METHOD: &str = "/policy_store.v2.PolicyStore/Deactivate";
+/// Path of the method to list all policy versions.
+pub const GET_VERSIONS_METHOD: &str = "/policy_store.v2.PolicyStore/GetVersions";
+/// Path of the method to get the active policy version.
+pub const GET_ACTIVE_VERSION_METHOD: &str = "/policy_store.v2.PolicyStore/GetActiveVersion";
+/// Path of the method to get who activated the active policy version.
+pub const GET_ACTIVATOR_METHOD: &str = "/policy_store.v2.PolicyStore/GetActivator";
+/// Path of the method to get the metadata of a policy version.
+pub const GET_VERSION_METADATA_METHOD: &str = "/policy_store.v2.PolicyStore/GetVersionMetadata";
+/// Path of the method to get the content of a policy version.
+pub const GET_VERSION_CONTENT_METHOD: &str = "/policy_store.v2.PolicyStore/GetVersionContent";
+
+
+
+
+
+/***** LIBRARY *****/
+// NOTE: Generated from `proto/policy_store.proto` by the build script, so it isn't linted
+#[allow(clippy::all, clippy::pedantic)]
+mod generated {
+    tonic::include_proto!("policy_store.v2");
+}
+pub use generated::*;
@@ -0,0 +1,43 @@
+//  PROTO.rs
+//    by Lut99
+//
+//  Created:
+//    17 Oct 2026, 00:07:35
+//  Last edited:
+//    17 Oct 2026, 02:43:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests that the method paths match the `.proto` file defining the
+//!   service.
+//
+
+use tonic_server_spec::{
+    ACTIVATE_METHOD, ADD_VERSION_METHOD, DEACTIVATE_METHOD, GET_ACTIVATOR_METHOD, GET_ACTIVE_VERSION_METHOD, GET_VERSION_CONTENT_METHOD,
+    GET_VERSION_METADATA_METHOD, GET_VERSIONS_METHOD, PROTO, SERVICE_NAME,
+};
+
+
+/***** TESTS *****/
+#[test]
+fn test_methods_match_proto() {
+    let mut proto: Vec<String> = PROTO
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("rpc "))
+        .map(|rpc| format!("/{SERVICE_NAME}/{}", rpc.split('(').next().unwrap_or(rpc).trim()))
+        .collect();
+    let mut rust: Vec<&str> = vec![
+        ADD_VERSION_METHOD,
+        ACTIVATE_METHOD,
+        DEACTIVATE_METHOD,
+        GET_VERSIONS_METHOD,
+        GET_ACTIVE_VERSION_METHOD,
+        GET_ACTIVATOR_METHOD,
+        GET_VERSION_METADATA_METHOD,
+        GET_VERSION_CONTENT_METHOD,
+    ];
+    proto.sort();
+    rust.sort();
+    assert_eq!(proto, rust);
+}
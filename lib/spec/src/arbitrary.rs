@@ -0,0 +1,414 @@
+//  ARBITRARY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:30:16
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements [`Arbitrary`] for the public types of this crate, such
+//!   that they can be generated in property-based tests.
+//
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use proptest::prelude::*;
+use serde_json::Value;
+
+use crate::audit::{Added, AuditAction, AuditRecord, Change};
+use crate::decision::Decision;
+use crate::event::{EventRecord, PolicyEvent};
+use crate::key::{AttachedKey, Key, KeyKind};
+use crate::metadata::{Activation, AttachedMetadata, Branch, Freeze, Metadata, Origin, User, Verification};
+use crate::provenance::Provenance;
+use crate::revocation::{AttachedRevocation, Revocation};
+use crate::schema::{AttachedSchema, Schema};
+use crate::template::{AttachedTemplate, Template, TemplateParameter};
+
+
+/***** LIBRARY *****/
+/// Generates timestamps anywhere between the UNIX epoch and the end of the year 9999, with
+/// nanosecond precision.
+///
+/// # Returns
+/// A [`Strategy`] for [`DateTime`]s.
+pub fn arb_datetime() -> impl Strategy<Value = DateTime<Utc>> {
+    (0i64..=253_402_300_799, 0u32..1_000_000_000).prop_map(|(secs, nanos)| {
+        // SAFETY: Both are within range by construction
+        DateTime::from_timestamp(secs, nanos).unwrap()
+    })
+}
+
+/// Generates arbitrary JSON, nested a few levels deep.
+///
+/// Numbers are limited to integers and finite floats, as JSON can't represent anything else.
+/// Floats are further limited to ones with few enough decimal digits that any JSON parser reads
+/// them back exactly (i.e., without serde_json's `float_roundtrip`).
+///
+/// # Returns
+/// A [`Strategy`] for [`Value`]s.
+pub fn arb_json() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::from),
+        any::<u64>().prop_map(Value::from),
+        // NOTE: A mantissa of at most 7 digits over at most 2^8 has at most 15 significant digits
+        (-(1i32 << 20)..(1i32 << 20), 0u32..8).prop_map(|(mantissa, exp)| Value::from(f64::from(mantissa) / f64::from(1u32 << exp))),
+        any::<String>().prop_map(Value::String),
+    ];
+    leaf.prop_recursive(3, 32, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            prop::collection::hash_map(any::<String>(), inner, 0..4).prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+
+
+impl Arbitrary for User {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<String>(), any::<Option<String>>(), prop::collection::vec(any::<String>(), 0..4))
+            .prop_map(|(id, name, issuer, roles)| Self { id, name, issuer, roles })
+            .boxed()
+    }
+}
+
+impl Arbitrary for AttachedMetadata {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<String>(), any::<String>(), any::<String>(), any::<Option<String>>(), any::<bool>())
+            .prop_map(|(name, description, language, branch, schema, confidential)| Self {
+                name,
+                description,
+                language,
+                branch,
+                schema,
+                confidential,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Metadata {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (
+            any::<AttachedMetadata>(),
+            arb_datetime(),
+            any::<User>(),
+            any::<u64>(),
+            any::<Option<u64>>(),
+            any::<Option<u64>>(),
+            any::<Option<String>>(),
+            any::<Option<Origin>>(),
+            any::<Option<Verification>>(),
+            any::<Option<Freeze>>(),
+        )
+            .prop_map(|(attached, created, creator, version, parent, promoted_from, hash, origin, verification, frozen)| Self {
+                attached,
+                created,
+                creator,
+                version,
+                parent,
+                promoted_from,
+                hash,
+                origin,
+                verification,
+                frozen,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Origin {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<u64>(), any::<String>(), arb_datetime())
+            .prop_map(|(store, version, creator, created)| Self { store, version, creator, created })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Verification {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<String>(), arb_datetime()).prop_map(|(identity, digest, verified)| Self { identity, digest, verified }).boxed()
+    }
+}
+
+impl Arbitrary for Freeze {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), arb_datetime(), any::<User>()).prop_map(|(reason, frozen, freezer)| Self { reason, frozen, freezer }).boxed()
+    }
+}
+
+impl Arbitrary for Branch {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<Option<u64>>(), any::<Option<u64>>(), arb_datetime(), any::<User>())
+            .prop_map(|(name, head, base, created, creator)| Self { name, head, base, created, creator })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Activation {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), arb_datetime(), any::<User>(), any::<Option<IpAddr>>(), proptest::option::of(arb_datetime()), any::<Option<User>>())
+            .prop_map(|(version, activated, activator, client, deactivated, deactivator)| Self {
+                version,
+                activated,
+                activator,
+                client,
+                deactivated,
+                deactivator,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Decision {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<u64>(), arb_datetime(), any::<User>())
+            .prop_map(|(id, version, recorded, reporter)| Self { id, version, recorded, reporter })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Provenance {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        // NOTE: Statements are always generated from metadata, so there's no point in generating
+        // impossible ones
+        (any::<Metadata>(), any::<Option<IpAddr>>(), any::<Option<String>>())
+            .prop_map(|(metadata, client, request_id)| Self::new(&metadata, client, request_id))
+            .boxed()
+    }
+}
+
+impl Arbitrary for KeyKind {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![Just(Self::Oct), Just(Self::Rsa), Just(Self::Ec), Just(Self::Ed)].boxed()
+    }
+}
+
+impl Arbitrary for AttachedKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<KeyKind>(), any::<String>()).prop_map(|(id, kind, material)| Self { id, kind, material }).boxed()
+    }
+}
+
+impl Arbitrary for Key {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<AttachedKey>(), arb_datetime(), any::<User>(), proptest::option::of(arb_datetime()))
+            .prop_map(|(attached, created, creator, revoked)| Self { attached, created, creator, revoked })
+            .boxed()
+    }
+}
+
+impl Arbitrary for AttachedRevocation {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            any::<String>().prop_map(|jti| Self::Token { jti }),
+            (any::<String>(), arb_datetime()).prop_map(|(user, before)| Self::User { user, before }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for Revocation {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<AttachedRevocation>(), arb_datetime(), any::<User>())
+            .prop_map(|(attached, created, creator)| Self { attached, created, creator })
+            .boxed()
+    }
+}
+
+impl Arbitrary for AttachedSchema {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<String>(), arb_json()).prop_map(|(name, description, schema)| Self { name, description, schema }).boxed()
+    }
+}
+
+impl Arbitrary for Schema {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<AttachedSchema>(), arb_datetime(), any::<User>()).prop_map(|(attached, created, creator)| Self { attached, created, creator }).boxed()
+    }
+}
+
+impl Arbitrary for TemplateParameter {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<String>(), proptest::option::of(arb_json()))
+            .prop_map(|(name, description, default)| Self { name, description, default })
+            .boxed()
+    }
+}
+
+impl Arbitrary for AttachedTemplate {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<String>(), any::<String>(), prop::collection::vec(any::<TemplateParameter>(), 0..4), arb_json())
+            .prop_map(|(name, description, language, parameters, skeleton)| Self { name, description, language, parameters, skeleton })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Template {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<AttachedTemplate>(), arb_datetime(), any::<User>())
+            .prop_map(|(attached, created, creator)| Self { attached, created, creator })
+            .boxed()
+    }
+}
+
+impl Arbitrary for AuditAction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            (any::<u64>(), any::<String>(), any::<String>()).prop_map(|(version, name, branch)| Self::AddVersion { version, name, branch }),
+            any::<u64>().prop_map(|version| Self::Activate { version }),
+            any::<u64>().prop_map(|version| Self::Deactivate { version }),
+            any::<String>().prop_map(|name| Self::AddTemplate { name }),
+            any::<String>().prop_map(|name| Self::AddSchema { name }),
+            (any::<String>(), any::<u64>()).prop_map(|(name, from)| Self::CreateBranch { name, from }),
+            (any::<u64>(), any::<String>(), any::<u64>()).prop_map(|(version, target, promoted)| Self::Promote { version, target, promoted }),
+            (any::<u64>(), any::<String>(), any::<String>(), any::<String>(), any::<u64>())
+                .prop_map(|(version, name, branch, store, source)| Self::Import { version, name, branch, store, source }),
+            (any::<String>(), any::<Option<u64>>()).prop_map(|(pseudonym, rehashed_from)| Self::Pseudonymize { pseudonym, rehashed_from }),
+            (arb_datetime(), any::<u64>()).prop_map(|(before, rows)| Self::PruneActivations { before, rows }),
+            any::<String>().prop_map(|id| Self::AddKey { id }),
+            any::<String>().prop_map(|id| Self::RevokeKey { id }),
+            any::<String>().prop_map(|jti| Self::RevokeToken { jti }),
+            (any::<String>(), arb_datetime()).prop_map(|(user, before)| Self::RevokeUserTokens { user, before }),
+            (any::<u64>(), any::<String>()).prop_map(|(version, reason)| Self::FreezeVersion { version, reason }),
+            any::<u64>().prop_map(|version| Self::UnfreezeVersion { version }),
+            (any::<u64>(), any::<String>()).prop_map(|(version, id)| Self::AddDecision { version, id }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for AuditRecord {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), arb_datetime(), any::<String>(), any::<Option<IpAddr>>(), any::<AuditAction>(), any::<Option<String>>(), any::<String>())
+            .prop_map(|(seq, timestamp, user, client, action, prev_hash, hash)| Self { seq, timestamp, user, client, action, prev_hash, hash })
+            .boxed()
+    }
+}
+
+impl Arbitrary for PolicyEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            (any::<u64>(), any::<String>(), any::<String>()).prop_map(|(version, name, branch)| Self::VersionAdded { version, name, branch }),
+            (any::<u64>(), any::<String>(), any::<u64>()).prop_map(|(version, target, promoted)| Self::VersionPromoted { version, target, promoted }),
+            any::<u64>().prop_map(|version| Self::Activated { version }),
+            any::<u64>().prop_map(|version| Self::Deactivated { version }),
+            (any::<String>(), any::<u64>()).prop_map(|(name, from)| Self::BranchCreated { name, from }),
+            any::<String>().prop_map(|name| Self::TemplateAdded { name }),
+            any::<String>().prop_map(|name| Self::SchemaAdded { name }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for EventRecord {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), arb_datetime(), any::<String>(), any::<PolicyEvent>())
+            .prop_map(|(seq, timestamp, user, event)| Self { seq, timestamp, user, event })
+            .boxed()
+    }
+}
+
+impl<C: 'static + Arbitrary> Arbitrary for Change<C> {
+    type Parameters = C::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        (any::<AuditRecord>(), any::<Option<Metadata>>(), proptest::option::of(any_with::<C>(args)), any::<Option<Added>>())
+            .prop_map(|(record, metadata, content, added)| Self { record, metadata, content, added })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Added {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            any::<AttachedTemplate>().prop_map(Self::Template),
+            any::<AttachedSchema>().prop_map(Self::Schema),
+            any::<AttachedKey>().prop_map(Self::Key),
+        ]
+        .boxed()
+    }
+}
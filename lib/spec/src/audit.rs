@@ -0,0 +1,487 @@
+//  AUDIT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:38:28
+//  Last edited:
+//    17 Oct 2026, 02:16:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the audit log, a hash-chained record of every change made to
+//!   a policy store.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::net::IpAddr;
+
+use chrono::{DateTime, SubsecRound as _, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::canonical::{content_hash, to_canonical_string};
+use crate::key::AttachedKey;
+use crate::metadata::Metadata;
+use crate::schema::AttachedSchema;
+use crate::template::AttachedTemplate;
+
+
+/***** ERRORS *****/
+/// Defines the ways in which an audit log may fail [verification](verify_chain()).
+#[derive(Debug, Error)]
+pub enum ChainError {
+    /// A record doesn't follow up on the previous one.
+    #[error("Audit record {seq} does not follow up on the previous record (expected sequence number {expected})")]
+    Gap { seq: u64, expected: u64 },
+    /// A record's hash does not match its contents.
+    #[error("Audit record {seq} has been tampered with (hash does not match its contents)")]
+    HashMismatch { seq: u64 },
+    /// A record does not refer to the hash of the previous record.
+    #[error("Audit record {seq} does not refer to the hash of the previous record")]
+    BrokenLink { seq: u64 },
+}
+impl ChainError {
+    /// Returns the sequence number of the record at which the chain broke.
+    ///
+    /// # Returns
+    /// The sequence number of the offending record.
+    #[inline]
+    pub fn seq(&self) -> u64 {
+        match self {
+            Self::Gap { seq, .. } | Self::HashMismatch { seq } | Self::BrokenLink { seq } => *seq,
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Escapes a value for use in the header of a CEF event.
+///
+/// # Arguments
+/// - `value`: The value to escape.
+///
+/// # Returns
+/// The escaped value.
+fn cef_escape_header(value: &str) -> String { value.replace('\\', "\\\\").replace('|', "\\|").replace(['\r', '\n'], " ") }
+
+/// Escapes a value for use in the extension of a CEF event.
+///
+/// # Arguments
+/// - `value`: The value to escape.
+///
+/// # Returns
+/// The escaped value.
+fn cef_escape_ext(value: &str) -> String { value.replace('\\', "\\\\").replace('=', "\\=").replace('\r', "\\r").replace('\n', "\\n") }
+
+
+
+
+
+/***** LIBRARY *****/
+/// Describes a change made to the store.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A new policy version was added.
+    AddVersion { version: u64, name: String, branch: String },
+    /// A policy version was activated.
+    Activate { version: u64 },
+    /// The active policy version was deactivated.
+    Deactivate { version: u64 },
+    /// A template was registered (or replaced).
+    AddTemplate { name: String },
+    /// A content schema was registered.
+    AddSchema { name: String },
+    /// A branch was forked from an existing version.
+    CreateBranch { name: String, from: u64 },
+    /// A policy version was promoted into another branch.
+    Promote { version: u64, target: String, promoted: u64 },
+    /// A policy version was imported from another store.
+    Import { version: u64, name: String, branch: String, store: String, source: u64 },
+    /// A user was pseudonymized. Only the pseudonym is recorded, not the original ID.
+    ///
+    /// `rehashed_from` is the sequence number of the first audit record that referenced the user,
+    /// from which on the log was re-hashed. It is [`None`] if no audit record referenced them
+    /// (or for pseudonymizations recorded before it was).
+    Pseudonymize {
+        pseudonym:     String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rehashed_from: Option<u64>,
+    },
+    /// Historical activation rows that ended before some time were removed.
+    PruneActivations { before: DateTime<Utc>, rows: u64 },
+    /// A verification key was registered.
+    AddKey { id: String },
+    /// A verification key was revoked.
+    RevokeKey { id: String },
+    /// A single token was revoked.
+    RevokeToken { jti: String },
+    /// All tokens of a user issued before some time were revoked.
+    RevokeUserTokens { user: String, before: DateTime<Utc> },
+    /// A policy version was frozen, such that it can no longer be activated or promoted.
+    FreezeVersion { version: u64, reason: String },
+    /// A frozen policy version was unfrozen.
+    UnfreezeVersion { version: u64 },
+    /// A decision taken under a policy version was registered.
+    AddDecision { version: u64, id: String },
+}
+impl AuditAction {
+    /// Returns a short, machine-readable identifier for the kind of action.
+    ///
+    /// # Returns
+    /// The same identifier as used in the serialized `kind` field.
+    #[inline]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::AddVersion { .. } => "add_version",
+            Self::Activate { .. } => "activate",
+            Self::Deactivate { .. } => "deactivate",
+            Self::AddTemplate { .. } => "add_template",
+            Self::AddSchema { .. } => "add_schema",
+            Self::CreateBranch { .. } => "create_branch",
+            Self::Promote { .. } => "promote",
+            Self::Import { .. } => "import",
+            Self::Pseudonymize { .. } => "pseudonymize",
+            Self::PruneActivations { .. } => "prune_activations",
+            Self::AddKey { .. } => "add_key",
+            Self::RevokeKey { .. } => "revoke_key",
+            Self::RevokeToken { .. } => "revoke_token",
+            Self::RevokeUserTokens { .. } => "revoke_user_tokens",
+            Self::FreezeVersion { .. } => "freeze_version",
+            Self::UnfreezeVersion { .. } => "unfreeze_version",
+            Self::AddDecision { .. } => "add_decision",
+        }
+    }
+
+    /// Returns how security-relevant the action is, on the CEF scale of 0 (lowest) to 10
+    /// (highest).
+    ///
+    /// Actions that change which policy is enforced are considered more relevant than those that
+    /// merely add something to the store.
+    ///
+    /// # Returns
+    /// The severity of the action.
+    #[inline]
+    pub fn severity(&self) -> u8 {
+        match self {
+            Self::Activate { .. }
+            | Self::Deactivate { .. }
+            | Self::AddKey { .. }
+            | Self::RevokeKey { .. }
+            | Self::RevokeToken { .. }
+            | Self::RevokeUserTokens { .. }
+            | Self::FreezeVersion { .. }
+            | Self::UnfreezeVersion { .. } => 6,
+            Self::Promote { .. } | Self::Import { .. } | Self::Pseudonymize { .. } => 5,
+            Self::AddVersion { .. } | Self::AddTemplate { .. } | Self::AddSchema { .. } | Self::CreateBranch { .. } => 3,
+            Self::PruneActivations { .. } | Self::AddDecision { .. } => 2,
+        }
+    }
+
+    /// Returns the policy version created by this action, if any.
+    ///
+    /// # Returns
+    /// The number of the new version, or [`None`] if the action did not create one.
+    #[inline]
+    pub fn created_version(&self) -> Option<u64> {
+        match self {
+            Self::AddVersion { version, .. } => Some(*version),
+            Self::Promote { promoted, .. } => Some(*promoted),
+            Self::Import { version, .. } => Some(*version),
+            Self::Activate { .. }
+            | Self::Deactivate { .. }
+            | Self::AddTemplate { .. }
+            | Self::AddSchema { .. }
+            | Self::CreateBranch { .. }
+            | Self::Pseudonymize { .. }
+            | Self::PruneActivations { .. }
+            | Self::AddKey { .. }
+            | Self::RevokeKey { .. }
+            | Self::RevokeToken { .. }
+            | Self::RevokeUserTokens { .. }
+            | Self::FreezeVersion { .. }
+            | Self::UnfreezeVersion { .. }
+            | Self::AddDecision { .. } => None,
+        }
+    }
+
+    /// Returns the ID of the user this action is about (e.g., whose tokens were revoked), if any.
+    ///
+    /// Like [`AuditAction::pseudonymize()`], this does not cover the user who took the action.
+    ///
+    /// # Returns
+    /// The ID of the user, or [`None`] if the action is not about any.
+    #[inline]
+    pub fn subject(&self) -> Option<&str> {
+        match self {
+            Self::RevokeUserTokens { user, .. } => Some(user),
+            Self::AddVersion { .. }
+            | Self::Activate { .. }
+            | Self::Deactivate { .. }
+            | Self::AddTemplate { .. }
+            | Self::AddSchema { .. }
+            | Self::CreateBranch { .. }
+            | Self::Promote { .. }
+            | Self::Import { .. }
+            | Self::Pseudonymize { .. }
+            | Self::PruneActivations { .. }
+            | Self::AddKey { .. }
+            | Self::RevokeKey { .. }
+            | Self::RevokeToken { .. }
+            | Self::FreezeVersion { .. }
+            | Self::UnfreezeVersion { .. }
+            | Self::AddDecision { .. } => None,
+        }
+    }
+
+    /// Replaces the ID of a user mentioned by this action with a pseudonym, if it is the given
+    /// one.
+    ///
+    /// Only covers the users the action is about (e.g., whose tokens were revoked), not the user
+    /// who took it, as that is part of the [`AuditRecord`].
+    ///
+    /// # Arguments
+    /// - `id`: The ID of the user to replace.
+    /// - `pseudonym`: The ID to replace it with.
+    ///
+    /// # Returns
+    /// `1` if the ID was replaced, or `0` otherwise, such that it can be counted.
+    #[inline]
+    pub fn pseudonymize(&mut self, id: &str, pseudonym: &str) -> u64 {
+        match self {
+            Self::RevokeUserTokens { user, .. } if user == id => {
+                *user = pseudonym.into();
+                1
+            },
+            Self::AddVersion { .. }
+            | Self::Activate { .. }
+            | Self::Deactivate { .. }
+            | Self::AddTemplate { .. }
+            | Self::AddSchema { .. }
+            | Self::CreateBranch { .. }
+            | Self::Promote { .. }
+            | Self::Import { .. }
+            | Self::Pseudonymize { .. }
+            | Self::PruneActivations { .. }
+            | Self::AddKey { .. }
+            | Self::RevokeKey { .. }
+            | Self::RevokeToken { .. }
+            | Self::RevokeUserTokens { .. }
+            | Self::FreezeVersion { .. }
+            | Self::UnfreezeVersion { .. }
+            | Self::AddDecision { .. } => 0,
+        }
+    }
+}
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::AddVersion { version, name, branch } => write!(f, "Added policy version {version} ({name:?}) to branch {branch:?}"),
+            Self::Activate { version } => write!(f, "Activated policy version {version}"),
+            Self::Deactivate { version } => write!(f, "Deactivated policy version {version}"),
+            Self::AddTemplate { name } => write!(f, "Added template {name:?}"),
+            Self::AddSchema { name } => write!(f, "Added schema {name:?}"),
+            Self::CreateBranch { name, from } => write!(f, "Created branch {name:?} from policy version {from}"),
+            Self::Promote { version, target, promoted } => {
+                write!(f, "Promoted policy version {version} into branch {target:?} as version {promoted}")
+            },
+            Self::Import { version, name, branch, store, source } => {
+                write!(f, "Imported policy version {source} ({name:?}) from store {store:?} into branch {branch:?} as version {version}")
+            },
+            Self::Pseudonymize { pseudonym, .. } => write!(f, "Pseudonymized a user as {pseudonym:?}"),
+            Self::PruneActivations { before, rows } => write!(f, "Pruned {rows} activation record(s) that ended before {}", before.to_rfc3339()),
+            Self::AddKey { id } => write!(f, "Added verification key {id:?}"),
+            Self::RevokeKey { id } => write!(f, "Revoked verification key {id:?}"),
+            Self::RevokeToken { jti } => write!(f, "Revoked token {jti:?}"),
+            Self::RevokeUserTokens { user, before } => write!(f, "Revoked all tokens of user {user:?} issued before {}", before.to_rfc3339()),
+            Self::FreezeVersion { version, reason } => write!(f, "Froze policy version {version} ({reason:?})"),
+            Self::UnfreezeVersion { version } => write!(f, "Unfroze policy version {version}"),
+            Self::AddDecision { version, id } => write!(f, "Added decision {id:?} taken under policy version {version}"),
+        }
+    }
+}
+/// A single record in the audit log.
+///
+/// Every record includes the hash of the record before it, such that changing or removing any
+/// record invalidates all records after it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AuditRecord {
+    /// The sequence number of this record. The first record has number 1.
+    pub seq: u64,
+    /// The time at which the change was made.
+    pub timestamp: DateTime<Utc>,
+    /// The ID of the user who made the change.
+    pub user: String,
+    /// The address of the client from which the change was made, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<IpAddr>,
+    /// The change made.
+    pub action: AuditAction,
+    /// The hash of the previous record, or [`None`] if this is the first.
+    pub prev_hash: Option<String>,
+    /// The hash of this record (including `prev_hash`).
+    pub hash: String,
+}
+impl AuditRecord {
+    /// Constructor for an AuditRecord that follows up on the given one.
+    ///
+    /// The timestamp is set to now, and the hash is computed.
+    ///
+    /// # Arguments
+    /// - `prev`: The last record currently in the log, if any.
+    /// - `user`: The ID of the user who made the change.
+    /// - `client`: The address of the client from which the change was made, if known.
+    /// - `action`: The [`AuditAction`] describing the change.
+    ///
+    /// # Returns
+    /// A new AuditRecord that can be appended to the log.
+    pub fn new(prev: Option<&AuditRecord>, user: impl Into<String>, client: Option<IpAddr>, action: AuditAction) -> Self {
+        let mut res = Self {
+            seq: prev.map(|prev| prev.seq + 1).unwrap_or(1),
+            // NOTE: Truncate to microseconds, as not all backends store more precise timestamps
+            timestamp: Utc::now().trunc_subsecs(6),
+            user: user.into(),
+            client,
+            action,
+            prev_hash: prev.map(|prev| prev.hash.clone()),
+            hash: String::new(),
+        };
+        res.hash = res.compute_hash();
+        res
+    }
+
+    /// Computes what the hash of this record should be, based on its contents.
+    ///
+    /// # Returns
+    /// The hex-encoded SHA-256 hash of the canonical JSON of all fields but `hash`.
+    pub fn compute_hash(&self) -> String {
+        let mut body = json!({
+            "seq": self.seq,
+            "timestamp": self.timestamp,
+            "user": self.user,
+            "action": self.action,
+            "prev_hash": self.prev_hash,
+        });
+        // NOTE: Only hashed when present, such that records made before it was recorded still verify
+        if let Some(client) = self.client {
+            body["client"] = json!(client);
+        }
+        // SAFETY: Serializing a JSON value never fails
+        content_hash(&to_canonical_string(&body).unwrap())
+    }
+
+    /// Formats this record as an ArcSight Common Event Format (CEF) event, for ingestion by a
+    /// SIEM.
+    ///
+    /// # Arguments
+    /// - `product_version`: The version of the policy store reported in the event header.
+    ///
+    /// # Returns
+    /// A single line (without trailing newline) with the CEF event.
+    pub fn to_cef(&self, product_version: &str) -> String {
+        let mut ext: String = format!(
+            "rt={} suser={} act={} msg={} cn1Label=seq cn1={} cs1Label=hash cs1={}",
+            self.timestamp.timestamp_millis(),
+            cef_escape_ext(&self.user),
+            self.action.kind(),
+            cef_escape_ext(&self.action.to_string()),
+            self.seq,
+            self.hash,
+        );
+        if let Some(client) = self.client {
+            ext.push_str(&format!(" src={client}"));
+        }
+        if let Some(prev_hash) = &self.prev_hash {
+            ext.push_str(&format!(" cs2Label=prevHash cs2={prev_hash}"));
+        }
+        format!(
+            "CEF:0|BraneFramework|policy-store|{}|{}|{}|{}|{ext}",
+            cef_escape_header(product_version),
+            self.action.kind(),
+            cef_escape_header(&self.action.to_string()),
+            self.action.severity(),
+        )
+    }
+}
+
+
+
+/// Anything but a policy version that was added by a [`Change`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Added {
+    /// The template registered by an [`AuditAction::AddTemplate`].
+    Template(AttachedTemplate),
+    /// The schema registered by an [`AuditAction::AddSchema`].
+    Schema(AttachedSchema),
+    /// The key registered by an [`AuditAction::AddKey`].
+    Key(AttachedKey),
+}
+
+/// A change made to the store, as returned by the changes feed.
+///
+/// This is an [`AuditRecord`] enriched with the policy version it created, if any, such that
+/// downstream consumers can synchronize without fetching every version separately.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Change<C> {
+    /// The audit record describing the change. Its sequence number serves as the cursor.
+    pub record:   AuditRecord,
+    /// The metadata of the policy version created by this change, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+    /// The content of the policy version created by this change, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content:  Option<C>,
+    /// Whatever else the change added, if anything.
+    ///
+    /// Backends leave this empty; the changes feed looks it up by the name or ID in the record. As
+    /// templates can be replaced, this is the template as it is now, not as it was added.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added:    Option<Added>,
+}
+
+
+
+/// Verifies the integrity of (a consecutive part of) the audit log.
+///
+/// # Arguments
+/// - `records`: The records to verify, in order. If the first is not the very first record in
+///   the log, its link to the previous record cannot be checked.
+///
+/// # Returns
+/// The number of records verified.
+///
+/// # Errors
+/// This function errors if any of the records was tampered with, or if records were removed.
+pub fn verify_chain<'r>(records: impl IntoIterator<Item = &'r AuditRecord>) -> Result<u64, ChainError> {
+    let mut prev: Option<&AuditRecord> = None;
+    let mut count: u64 = 0;
+    for record in records {
+        if record.hash != record.compute_hash() {
+            return Err(ChainError::HashMismatch { seq: record.seq });
+        }
+        match prev {
+            Some(prev) => {
+                if record.seq != prev.seq + 1 {
+                    return Err(ChainError::Gap { seq: record.seq, expected: prev.seq + 1 });
+                }
+                if record.prev_hash.as_ref() != Some(&prev.hash) {
+                    return Err(ChainError::BrokenLink { seq: record.seq });
+                }
+            },
+            None => {
+                if record.seq == 1 && record.prev_hash.is_some() {
+                    return Err(ChainError::BrokenLink { seq: record.seq });
+                }
+            },
+        }
+        prev = Some(record);
+        count += 1;
+    }
+    Ok(count)
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 10:31:06
 //  Last edited:
-//    04 Nov 2024, 15:26:10
+//    17 Oct 2026, 02:19:51
 //  Auto updated?
 //    Yes
 //
@@ -17,17 +17,34 @@ use std::convert::Infallible;
 use std::error::Error;
 use std::future::Future;
 
-use http::{HeaderMap, StatusCode};
+use http::{HeaderMap, Method, StatusCode};
+use serde_json::Value;
+
+use crate::metadata::User;
 
 
 /***** AUXILLARY *****/
 /// Extends an [`Error`] with the ability to associate status codes with it.
+///
+/// This is the one abstraction for errors that are the client's fault, used by
+/// [`AuthResolver`]s and [`DatabaseConnection`](crate::databaseconn::DatabaseConnection)s alike.
 pub trait HttpError: Error {
     /// Returns the status code associated with this error.
     ///
     /// # Returns
     /// A [`StatusCode`].
     fn status_code(&self) -> StatusCode;
+
+    /// Returns machine-readable details about this error to send to the client, on top of its
+    /// message.
+    ///
+    /// Note that these are sent as-is, so they must not contain anything secret (e.g., tokens).
+    /// By default, there are no details.
+    ///
+    /// # Returns
+    /// A JSON [`Value`] with the details, or [`Value::Null`] if there are none.
+    #[inline]
+    fn body(&self) -> Value { Value::Null }
 }
 
 // Default impls
@@ -38,6 +55,24 @@ impl HttpError for Infallible {
 
 
 
+/// Allows [`AuthResolver::Context`]s to carry more than a [`User`] (e.g., a tenant or scopes),
+/// while still telling on who's behalf actions are taken.
+pub trait AsUser {
+    /// Returns the user identified by this context.
+    ///
+    /// # Returns
+    /// A reference to the [`User`] on who's behalf actions are taken.
+    fn as_user(&self) -> &User;
+}
+
+// Default impls
+impl AsUser for User {
+    #[inline]
+    fn as_user(&self) -> &User { self }
+}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -73,4 +108,40 @@ pub trait AuthResolver {
     /// The first will always result in a (vague) 500 INTERNAL SERVER ERROR to the user, whereas
     /// the second may communicate custom status codes.
     fn authorize(&self, headers: &HeaderMap) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>>;
+
+    /// Resolves the given HTTP request to some authorization context, knowing what it requests.
+    ///
+    /// This allows resolvers to make decisions that depend on the endpoint called (e.g., allowing
+    /// `GET`s anonymously). Servers should call this instead of [`AuthResolver::authorize()`].
+    ///
+    /// By default, this ignores the `method` and `path` and simply calls
+    /// [`AuthResolver::authorize()`].
+    ///
+    /// # Arguments
+    /// - `method`: The [`Method`] of the HTTP request to resolve.
+    /// - `path`: The path of the HTTP request to resolve, as received (i.e., without query).
+    /// - `headers`: The headers of the HTTP request to resolve.
+    ///
+    /// # Returns
+    /// An [`AuthResolver::Context`] that can be used to identify the user later.
+    ///
+    /// # Errors
+    /// This function errors in the same way as [`AuthResolver::authorize()`].
+    #[inline]
+    fn authorize_request(
+        &self,
+        method: &Method,
+        path: &str,
+        headers: &HeaderMap,
+    ) -> impl Send + Future<Output = Result<Result<Self::Context, Self::ClientError>, Self::ServerError>> {
+        let _ = (method, path);
+        self.authorize(headers)
+    }
+
+    /// Forgets anything remembered about earlier authorizations (e.g., cached tokens or keys), such
+    /// that revoking tokens or keys takes effect immediately.
+    ///
+    /// Servers should call this after revoking something. By default, this does nothing.
+    #[inline]
+    fn invalidate(&self) {}
 }
@@ -0,0 +1,114 @@
+//  CANONICAL.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:22:02
+//  Last edited:
+//    16 Oct 2026, 14:22:02
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a canonical JSON form for policy content, such that
+//!   cosmetically different uploads of the same policy serialize (and
+//!   hash) identically.
+//
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+use sha2::{Digest as _, Sha256};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Normalizes a JSON number.
+///
+/// Floats without fractional part that fit in an integer are written as that integer, and
+/// negative zero becomes zero.
+///
+/// # Arguments
+/// - `num`: The [`Number`] to normalize.
+///
+/// # Returns
+/// A normalized [`Number`].
+fn normalize_number(num: &Number) -> Number {
+    if num.is_i64() || num.is_u64() {
+        return num.clone();
+    }
+    if let Some(f) = num.as_f64() {
+        if f.fract() == 0.0 {
+            if f >= i64::MIN as f64 && f < i64::MAX as f64 {
+                return Number::from(f as i64);
+            } else if f >= 0.0 && f < u64::MAX as f64 {
+                return Number::from(f as u64);
+            }
+        }
+    }
+    num.clone()
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Brings a JSON value into its canonical form.
+///
+/// This sorts the keys of all objects and [normalizes](normalize_number()) all numbers.
+///
+/// # Arguments
+/// - `value`: The [`Value`] to canonicalize.
+///
+/// # Returns
+/// A new [`Value`] in canonical form.
+pub fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Number(num) => Value::Number(normalize_number(num)),
+        Value::Array(elems) => Value::Array(elems.iter().map(canonicalize).collect()),
+        Value::Object(fields) => {
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+            let mut res: Map<String, Value> = Map::with_capacity(fields.len());
+            for key in keys {
+                res.insert(key.clone(), canonicalize(&fields[key]));
+            }
+            Value::Object(res)
+        },
+        Value::Null | Value::Bool(_) | Value::String(_) => value.clone(),
+    }
+}
+
+/// Serializes some content to its canonical JSON text.
+///
+/// The text is [canonicalized](canonicalize()) and contains no whitespace.
+///
+/// # Arguments
+/// - `content`: The content to serialize.
+///
+/// # Returns
+/// A [`String`] with the canonical JSON text.
+///
+/// # Errors
+/// This function errors if the `content` failed to serialize to JSON.
+#[inline]
+pub fn to_canonical_string<C: ?Sized + Serialize>(content: &C) -> Result<String, serde_json::Error> {
+    Ok(canonicalize(&serde_json::to_value(content)?).to_string())
+}
+
+/// Computes the hash of some canonical JSON text.
+///
+/// # Arguments
+/// - `canonical`: The text to hash, as returned by [`to_canonical_string()`].
+///
+/// # Returns
+/// The lowercase, hex-encoded SHA-256 hash of the text.
+pub fn content_hash(canonical: &str) -> String {
+    let hash = Sha256::digest(canonical.as_bytes());
+    let mut res: String = String::with_capacity(2 * hash.len());
+    for byte in hash {
+        // SAFETY: Writing to a string never fails
+        write!(res, "{byte:02x}").unwrap();
+    }
+    res
+}
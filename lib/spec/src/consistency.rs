@@ -0,0 +1,63 @@
+//  CONSISTENCY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:30:22
+//  Last edited:
+//    16 Oct 2026, 17:30:22
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines what a consistency check of a backend can find wrong with
+//!   it.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+
+/***** LIBRARY *****/
+/// Describes a single violated invariant found by
+/// [`DatabaseConnector::verify()`](crate::DatabaseConnector::verify()).
+///
+/// Findings describe problems that would otherwise only surface at request time, e.g., after a
+/// botched upgrade or manual edits to the backend.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Finding {
+    /// The active version does not exist.
+    DanglingActive { version: u64 },
+    /// An activation in the history refers to a version that does not exist.
+    OrphanedActivation { version: u64, activated: DateTime<Utc> },
+    /// A version is not numbered higher than a version it was derived from (as parent or as
+    /// promotion source), meaning version numbers went backwards.
+    NonMonotoneVersion { version: u64, parent: u64 },
+    /// The content of a version is not valid (i.e., not JSON, or not of the expected shape).
+    InvalidContent { version: u64, reason: String },
+    /// A version has no content hash, e.g., because it was added by a release that didn't compute
+    /// them.
+    MissingHash { version: u64 },
+    /// The content hash of a version does not match its content.
+    HashMismatch { version: u64, expected: String, actual: String },
+}
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::DanglingActive { version } => write!(f, "Active policy version {version} does not exist"),
+            Self::OrphanedActivation { version, activated } => {
+                write!(f, "Activation of policy version {version} on {activated} refers to a version that does not exist")
+            },
+            Self::NonMonotoneVersion { version, parent } => {
+                write!(f, "Policy version {version} is not numbered higher than version {parent} it derives from")
+            },
+            Self::InvalidContent { version, reason } => write!(f, "Policy version {version} has invalid content: {reason}"),
+            Self::MissingHash { version } => write!(f, "Policy version {version} has no content hash"),
+            Self::HashMismatch { version, expected, actual } => {
+                write!(f, "Content hash of policy version {version} does not match its content (expected {expected}, got {actual})")
+            },
+        }
+    }
+}
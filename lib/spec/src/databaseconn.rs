@@ -4,7 +4,7 @@
 //  Created:
 //    18 Oct 2024, 17:38:33
 //  Last edited:
-//    11 Nov 2024, 11:26:25
+//    17 Oct 2026, 01:55:38
 //  Auto updated?
 //    Yes
 //
@@ -13,12 +13,110 @@
 //
 
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::error::Error;
 use std::future::Future;
+use std::net::IpAddr;
 use std::rc::Rc;
 use std::sync::Arc;
 
-use crate::metadata::{AttachedMetadata, Metadata, User};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde_json::{Value, json};
+use thiserror::Error;
+
+use crate::audit::{AuditRecord, Change};
+use crate::authresolver::HttpError;
+use crate::consistency::Finding;
+use crate::decision::Decision;
+use crate::key::{AttachedKey, Key};
+use crate::metadata::{AccessContext, Activation, AttachedMetadata, Branch, Metadata, User};
+use crate::provenance::Provenance;
+use crate::revocation::{AttachedRevocation, Revocation};
+use crate::schema::{AttachedSchema, Schema};
+use crate::template::{AttachedTemplate, Template};
+
+
+/***** ERRORS *****/
+/// Defines errors that are caused by the request given to a [`DatabaseConnection`] rather than by
+/// the backend.
+///
+/// Operations that may fail this way return them in an inner [`Result`], much like an
+/// [`AuthResolver`](crate::AuthResolver) does.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// A branch with the given name already exists.
+    #[error("Branch {name:?} already exists")]
+    BranchExists { name: String },
+    /// A decision with the given identifier has already been registered.
+    #[error("Decision {id:?} already exists")]
+    DecisionExists { id: String },
+    /// The content of an imported version does not match the hash it had in its original store.
+    #[error("Content of imported policy version {version} has hash {actual:?}, but it was recorded with hash {expected:?}")]
+    HashMismatch { version: u64, expected: String, actual: String },
+    /// A verification key with the given identifier has already been registered (and perhaps
+    /// revoked since).
+    #[error("Key {id:?} already exists")]
+    KeyExists { id: String },
+    /// Both the source and target branch of a promotion have advanced since they last met.
+    #[error(
+        "Cannot promote version {version} from branch {source_branch:?} into branch {target:?}, because {target:?} has advanced to version \
+         {head} since {}",
+        if let Some(base) = base { format!("version {base}") } else { "the branches have no common history".into() }
+    )]
+    PromoteConflict { version: u64, source_branch: String, target: String, head: u64, base: Option<u64> },
+    /// Attempted to promote a version into the branch it is already on.
+    #[error("Cannot promote version {version} into branch {branch:?}, because it is already on it")]
+    PromoteSameBranch { version: u64, branch: String },
+    /// A schema with the given name already exists.
+    #[error("Schema {name:?} already exists")]
+    SchemaExists { name: String },
+    /// The given branch does not exist.
+    #[error("Unknown branch {name:?}")]
+    UnknownBranch { name: String },
+    /// The given verification key does not exist.
+    #[error("Unknown key {id:?}")]
+    UnknownKey { id: String },
+    /// The given user does not appear anywhere in the store.
+    #[error("Unknown user {id:?}")]
+    UnknownUser { id: String },
+    /// The given version does not exist.
+    #[error("Unknown policy version {version}")]
+    UnknownVersion { version: u64 },
+    /// The given version is frozen, and cannot be used until it is unfrozen.
+    #[error("Policy version {version} is frozen")]
+    VersionFrozen { version: u64 },
+}
+impl HttpError for ClientError {
+    #[inline]
+    fn status_code(&self) -> StatusCode {
+        use ClientError::*;
+        match self {
+            BranchExists { .. } | DecisionExists { .. } | KeyExists { .. } | PromoteConflict { .. } | SchemaExists { .. } | VersionFrozen { .. } => {
+                StatusCode::CONFLICT
+            },
+            HashMismatch { .. } | PromoteSameBranch { .. } => StatusCode::BAD_REQUEST,
+            UnknownBranch { .. } | UnknownKey { .. } | UnknownUser { .. } | UnknownVersion { .. } => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn body(&self) -> Value {
+        use ClientError::*;
+        match self {
+            BranchExists { name } | SchemaExists { name } | UnknownBranch { name } => json!({ "name": name }),
+            DecisionExists { id } | KeyExists { id } | UnknownKey { id } | UnknownUser { id } => json!({ "id": id }),
+            PromoteConflict { version, source_branch, target, head, base } => {
+                json!({ "version": version, "source_branch": source_branch, "target": target, "head": head, "base": base })
+            },
+            HashMismatch { version, expected, actual } => json!({ "version": version, "expected": expected, "actual": actual }),
+            PromoteSameBranch { version, branch } => json!({ "version": version, "branch": branch }),
+            UnknownVersion { version } | VersionFrozen { version } => json!({ "version": version }),
+        }
+    }
+}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -47,6 +145,73 @@ pub trait DatabaseConnector {
     /// # Errors
     /// This function can error if it failed to create the new connection.
     fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>>;
+
+    /// Creates a connection to the backend that is contextualized to a particular user connecting
+    /// from a particular client.
+    ///
+    /// Backends that record where changes came from (e.g., in the audit log) should override
+    /// this. By default, the `client` is ignored and this simply calls
+    /// [`connect()`](DatabaseConnector::connect()).
+    ///
+    /// # Arguments
+    /// - `user`: Some [`User`] on who's behalf actions are taken. It is assumed they are already
+    ///   authenticated somehow.
+    /// - `client`: The address of the client from which the `user` connects, if known.
+    ///
+    /// # Errors
+    /// This function can error if it failed to create the new connection.
+    #[inline]
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        let _ = client;
+        self.connect(user)
+    }
+
+    /// Creates a connection to the backend that is contextualized to everything known about the
+    /// access, e.g., to record richer provenance or to implement row-level security.
+    ///
+    /// Servers should prefer this over [`connect_from()`](DatabaseConnector::connect_from()). By
+    /// default, everything but the user and client is ignored and this simply calls
+    /// [`connect_from()`](DatabaseConnector::connect_from()).
+    ///
+    /// Connectors that queue work (e.g., on a pool of connections) should override this to not
+    /// start any work after the context's [deadline](AccessContext::deadline) has passed.
+    ///
+    /// # Arguments
+    /// - `context`: The [`AccessContext`] describing who accesses the store, and how. It is
+    ///   assumed its user is already authenticated somehow.
+    ///
+    /// # Errors
+    /// This function can error if it failed to create the new connection.
+    #[inline]
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        self.connect_from(&context.user, context.client)
+    }
+
+    /// Performs routine maintenance on the backend, e.g., reclaiming unused space.
+    ///
+    /// This may be expensive and is intended to be run during quiet hours. By default, it does
+    /// nothing; backends that need maintenance should override it.
+    ///
+    /// # Errors
+    /// This function can error if the maintenance failed.
+    #[inline]
+    fn maintain(&self) -> impl Send + Future<Output = Result<(), Self::Error>> { async { Ok(()) } }
+
+    /// Checks the invariants of the backend, e.g., that the active version exists and that the
+    /// content of every version matches its hash.
+    ///
+    /// This reads everything in the backend and is intended to be run at startup (or by
+    /// operators), such that problems surface before requests run into them. By default, it finds
+    /// nothing; backends that can get into inconsistent states should override it.
+    ///
+    /// # Returns
+    /// A list of [`Finding`]s describing every violated invariant. Empty if all is well.
+    ///
+    /// # Errors
+    /// This function can error if the backend could not be read. Violated invariants are never
+    /// errors.
+    #[inline]
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> { async { Ok(Vec::new()) } }
 }
 
 // Pointer-like impls
@@ -62,6 +227,22 @@ impl<T: DatabaseConnector> DatabaseConnector for &T {
     fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
         <T as DatabaseConnector>::connect(self, user)
     }
+
+    #[inline]
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        <T as DatabaseConnector>::connect_from(self, user, client)
+    }
+
+    #[inline]
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        <T as DatabaseConnector>::connect_with(self, context)
+    }
+
+    #[inline]
+    fn maintain(&self) -> impl Send + Future<Output = Result<(), Self::Error>> { <T as DatabaseConnector>::maintain(self) }
+
+    #[inline]
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> { <T as DatabaseConnector>::verify(self) }
 }
 impl<T: DatabaseConnector> DatabaseConnector for &mut T {
     type Content = T::Content;
@@ -75,6 +256,22 @@ impl<T: DatabaseConnector> DatabaseConnector for &mut T {
     fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
         <T as DatabaseConnector>::connect(self, user)
     }
+
+    #[inline]
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        <T as DatabaseConnector>::connect_from(self, user, client)
+    }
+
+    #[inline]
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        <T as DatabaseConnector>::connect_with(self, context)
+    }
+
+    #[inline]
+    fn maintain(&self) -> impl Send + Future<Output = Result<(), Self::Error>> { <T as DatabaseConnector>::maintain(self) }
+
+    #[inline]
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> { <T as DatabaseConnector>::verify(self) }
 }
 impl<T: DatabaseConnector> DatabaseConnector for Rc<T> {
     type Content = T::Content;
@@ -88,6 +285,22 @@ impl<T: DatabaseConnector> DatabaseConnector for Rc<T> {
     fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
         <T as DatabaseConnector>::connect(self, user)
     }
+
+    #[inline]
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        <T as DatabaseConnector>::connect_from(self, user, client)
+    }
+
+    #[inline]
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        <T as DatabaseConnector>::connect_with(self, context)
+    }
+
+    #[inline]
+    fn maintain(&self) -> impl Send + Future<Output = Result<(), Self::Error>> { <T as DatabaseConnector>::maintain(self) }
+
+    #[inline]
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> { <T as DatabaseConnector>::verify(self) }
 }
 impl<T: DatabaseConnector> DatabaseConnector for Arc<T> {
     type Content = T::Content;
@@ -101,11 +314,82 @@ impl<T: DatabaseConnector> DatabaseConnector for Arc<T> {
     fn connect<'s>(&'s self, user: &'s User) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
         <T as DatabaseConnector>::connect(self, user)
     }
+
+    #[inline]
+    fn connect_from<'s>(&'s self, user: &'s User, client: Option<IpAddr>) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        <T as DatabaseConnector>::connect_from(self, user, client)
+    }
+
+    #[inline]
+    fn connect_with<'s>(&'s self, context: &'s AccessContext) -> impl Send + Future<Output = Result<Self::Connection<'s>, Self::Error>> {
+        <T as DatabaseConnector>::connect_with(self, context)
+    }
+
+    #[inline]
+    fn maintain(&self) -> impl Send + Future<Output = Result<(), Self::Error>> { <T as DatabaseConnector>::maintain(self) }
+
+    #[inline]
+    fn verify(&self) -> impl Send + Future<Output = Result<Vec<Finding>, Self::Error>> { <T as DatabaseConnector>::verify(self) }
+}
+
+
+
+/// Defines backends that can push notifications when the store changes, e.g., through
+/// PostgreSQL's `LISTEN`/`NOTIFY`.
+///
+/// This allows replicas and caches to learn about changes as they happen, instead of polling
+/// [`DatabaseConnection::get_changes()`]. Changes are identified by the sequence number of their
+/// [`AuditRecord`]; listeners are expected to fetch the changes themselves once notified.
+pub trait ChangeListener {
+    /// The type of errors returned by the listener.
+    type Error: Error;
+
+
+    /// Waits until a change is made to the store after a given point.
+    ///
+    /// # Arguments
+    /// - `since`: The sequence number of the last change already seen (or `0` to start at the
+    ///   beginning).
+    ///
+    /// # Returns
+    /// The sequence number of the latest change, which is always higher than `since`. The future
+    /// completes immediately if such a change was already made.
+    ///
+    /// # Errors
+    /// This function can error if the backend failed to listen for changes.
+    fn next_change(&self, since: u64) -> impl Send + Future<Output = Result<u64, Self::Error>>;
 }
 
+// Default impls
+impl ChangeListener for () {
+    type Error = Infallible;
+
+    /// Never completes, as there is nothing to be notified by.
+    #[inline]
+    fn next_change(&self, _since: u64) -> impl Send + Future<Output = Result<u64, Self::Error>> { std::future::pending() }
+}
+
+// Pointer-like impls
+impl<T: ChangeListener> ChangeListener for &T {
+    type Error = T::Error;
+
+    #[inline]
+    fn next_change(&self, since: u64) -> impl Send + Future<Output = Result<u64, Self::Error>> { <T as ChangeListener>::next_change(self, since) }
+}
+impl<T: ChangeListener> ChangeListener for Arc<T> {
+    type Error = T::Error;
 
+    #[inline]
+    fn next_change(&self, since: u64) -> impl Send + Future<Output = Result<u64, Self::Error>> { <T as ChangeListener>::next_change(self, since) }
+}
 
 /// Defines how to interact with the backend database once a connection has been made.
+///
+/// # Cancellation
+/// Callers may drop the futures returned by any method before they complete, e.g., because the
+/// client they serve has disconnected. Implementations must make sure that this never leaves a
+/// change half-applied, nor the connection (or whatever pool it came from) unusable: every change
+/// is either made completely or not at all, even if the caller never learns which.
 pub trait DatabaseConnection {
     /// The type of things stored in the backend database.
     type Content;
@@ -116,6 +400,9 @@ pub trait DatabaseConnection {
     // Mutations
     /// Adds a new policy to the database.
     ///
+    /// Version numbers are allocated monotonically, i.e., every new version is higher than all
+    /// existing ones regardless of when they were created. A number is never reused.
+    ///
     /// # Arguments
     /// - `metadata`: The [`AttachedMetadata`] that describes the context of the request.
     /// - `content`: The [`DatabaseConnector::Content`] that is the body of the policy to store.
@@ -134,15 +421,271 @@ pub trait DatabaseConnection {
     /// - `version`: The version number of the (already submitted) policy to make active.
     ///
     /// # Errors
-    /// This function may error if it failed to set the active policy in the backend database or if
-    /// `version` does not exist.
+    /// This function may error if it failed to set the active policy in the backend database, if
+    /// `version` does not exist or if it is [frozen](DatabaseConnection::freeze_version()).
     fn activate(&mut self, version: u64) -> impl Send + Future<Output = Result<(), Self::Error>>;
     /// "Panic button" that replaces the currently active policy with a policy that always denies
     /// all incoming requests.
     ///
+    /// Note that what the reasoner does without an active policy is up to the reasoner. To be
+    /// explicit about it, activate the [deny-all](crate::DenyAll) policy instead.
+    ///
     /// # Errors
     /// This function may error if it failed to set the active policy in the backend database.
     fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>>;
+    /// Freezes a version, such that it cannot be activated or promoted until it is unfrozen.
+    ///
+    /// Meant for incident response, e.g., to take a version that is suspected to be malicious out
+    /// of circulation while it is investigated. Note that freezing the active version does _not_
+    /// deactivate it; use [`DatabaseConnection::deactivate()`] for that.
+    ///
+    /// Freezing an already frozen version replaces the recorded reason and user.
+    ///
+    /// # Arguments
+    /// - `version`: The version to freeze.
+    /// - `reason`: Why the version is frozen.
+    ///
+    /// # Errors
+    /// This function may error if it failed to update the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, i.e., when the version does
+    ///   not exist.
+    fn freeze_version(&mut self, version: u64, reason: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>>;
+    /// Unfreezes a [frozen](DatabaseConnection::freeze_version()) version, such that it can be
+    /// used again.
+    ///
+    /// Unfreezing a version that is not frozen does nothing.
+    ///
+    /// # Arguments
+    /// - `version`: The version to unfreeze.
+    ///
+    /// # Errors
+    /// This function may error if it failed to update the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, i.e., when the version does
+    ///   not exist.
+    fn unfreeze_version(&mut self, version: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>>;
+    /// Registers a new policy template in the database.
+    ///
+    /// If a template with the same name already exists, it is replaced.
+    ///
+    /// # Arguments
+    /// - `template`: The [`AttachedTemplate`] to register.
+    ///
+    /// # Errors
+    /// This function may error if it failed to store the template in the backend database.
+    fn add_template(&mut self, template: AttachedTemplate) -> impl Send + Future<Output = Result<(), Self::Error>>;
+    /// Creates a new branch that is forked from an existing version.
+    ///
+    /// Note that branches are also created implicitly when a version is appended to a branch
+    /// that doesn't exist yet. Such branches have no base version.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new branch.
+    /// - `from`: The version to fork the new branch from. This becomes its first head.
+    ///
+    /// # Errors
+    /// This function may error if it failed to store the branch in the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, i.e., when a branch with
+    ///   `name` already exists or when `from` does not exist.
+    fn create_branch(&mut self, name: &str, from: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>>;
+    /// Promotes a version from its own branch into another branch.
+    ///
+    /// This appends a copy of the version to the target branch, recording the original version as
+    /// its [provenance](Metadata::promoted_from). The promoting user becomes the creator of the
+    /// copy.
+    ///
+    /// Unless `force` is given, the promotion is refused if the target branch has advanced since
+    /// the two branches last met (i.e., since the source was forked from it or since the last
+    /// promotion from the source), as this would silently discard the target's changes.
+    ///
+    /// # Arguments
+    /// - `version`: The version to promote.
+    /// - `target`: The name of the branch to promote the version into.
+    /// - `force`: Whether to promote even if both branches have advanced.
+    ///
+    /// # Returns
+    /// The version number of the promoted copy.
+    ///
+    /// # Errors
+    /// This function may error if it failed to store the promotion in the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, e.g., when the version or
+    ///   target branch doesn't exist, when the version is frozen, or when a conflict is detected.
+    fn promote(&mut self, version: u64, target: &str, force: bool) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>>;
+    /// Imports a version copied over from another store, e.g., to promote it from staging to
+    /// production.
+    ///
+    /// This appends a copy of the version to the same branch as in the other store, recording
+    /// where it came from as its [origin](Metadata::origin). The importing user becomes the creator
+    /// of the copy. If the version was itself imported, its origin is kept as-is, such that it
+    /// always points to where the version was first written.
+    ///
+    /// The [verification](Metadata::verification) in `metadata` is recorded as-is, so callers must
+    /// only set it after verifying the content's signature themselves.
+    ///
+    /// # Arguments
+    /// - `store`: The name of the store the version is copied from.
+    /// - `metadata`: The [`Metadata`] of the version in that store.
+    /// - `content`: The content of the version.
+    ///
+    /// # Returns
+    /// The version number of the imported copy.
+    ///
+    /// # Errors
+    /// This function may error if it failed to store the version in the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, i.e., when the `content`
+    ///   does not match the [hash](Metadata::hash) it was recorded with.
+    fn import_version(
+        &mut self,
+        store: &str,
+        metadata: Metadata,
+        content: Self::Content,
+    ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>>;
+    /// Registers a new content schema in the database.
+    ///
+    /// Schemas cannot be replaced once registered, as versions keep referring to them by name.
+    ///
+    /// # Arguments
+    /// - `schema`: The [`AttachedSchema`] to register.
+    ///
+    /// # Errors
+    /// This function may error if it failed to store the schema in the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, i.e., when a schema with the
+    ///   same name already exists.
+    fn add_schema(&mut self, schema: AttachedSchema) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>>;
+    /// Replaces every reference to a user with a pseudonym, e.g., to honour a request to erase
+    /// their personal data.
+    ///
+    /// This affects everything the user created or (de)activated, as well as their records in the
+    /// audit log (including the addresses they connected from). Because this changes the audit
+    /// log, its records are re-hashed from the first affected record onwards. The pseudonymization
+    /// itself is recorded in the audit log too, without mentioning the original ID.
+    ///
+    /// Revocations of all of the user's tokens keep their ID, as that is what their tokens are
+    /// checked against; they would be valid again otherwise.
+    ///
+    /// # Arguments
+    /// - `id`: The ID of the user to pseudonymize.
+    /// - `pseudonym`: The ID to replace it with.
+    ///
+    /// # Returns
+    /// The number of references to the user (including in audit records) that were replaced or,
+    /// for the addresses they connected from, forgotten. This is not the number of rows, as a row
+    /// may reference the user more than once (e.g., a version both created and connected from by
+    /// them counts twice).
+    ///
+    /// # Errors
+    /// This function may error if it failed to update the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, i.e., when the user does not
+    ///   appear anywhere in the store.
+    fn pseudonymize_user(&mut self, id: &str, pseudonym: &str) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>>;
+    /// Registers that a decision was taken under a particular policy version.
+    ///
+    /// Unlike other mutations, this is not recorded in the audit log, as it doesn't change the
+    /// policies stored.
+    ///
+    /// # Arguments
+    /// - `version`: The policy version under which the decision was taken.
+    /// - `id`: The identifier of the decision.
+    ///
+    /// # Errors
+    /// This function may error if it failed to store the decision in the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, i.e., when the version
+    ///   doesn't exist or the decision has already been registered.
+    fn add_decision(&mut self, version: u64, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>>;
+    /// Removes historical activation records that ended before a given time.
+    ///
+    /// The currently active version is never affected, and neither is the audit log, which keeps
+    /// a full record of every (de)activation. However, asking which version was active at a time
+    /// before `before` may no longer give an answer afterwards. The pruning itself is recorded in
+    /// the audit log.
+    ///
+    /// # Arguments
+    /// - `before`: The time before which activations must have ended to be removed.
+    ///
+    /// # Returns
+    /// The number of activation records removed.
+    ///
+    /// # Errors
+    /// This function may error if it failed to update the backend database.
+    fn prune_activations(&mut self, before: DateTime<Utc>) -> impl Send + Future<Output = Result<u64, Self::Error>>;
+    /// Registers a new verification key in the database.
+    ///
+    /// Key identifiers are never reused, so a key cannot be registered under the identifier of a
+    /// revoked one.
+    ///
+    /// # Arguments
+    /// - `key`: The [`AttachedKey`] to register.
+    ///
+    /// # Errors
+    /// This function may error if it failed to store the key in the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, i.e., when a key with the
+    ///   same identifier already exists.
+    fn add_key(&mut self, key: AttachedKey) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>>;
+    /// Revokes a verification key, such that it no longer verifies anything.
+    ///
+    /// Revoking a key that is already revoked does nothing.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the key to revoke.
+    ///
+    /// # Errors
+    /// This function may error if it failed to update the backend database.
+    ///
+    /// There are two levels at which it can do so:
+    /// - The _outer_ [`Result`] is used to indicate backend errors; and
+    /// - The _inner_ [`Result`] is used to indicate [`ClientError`]s, i.e., when the key does not
+    ///   exist.
+    fn revoke_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>>;
+    /// Revokes tokens, such that authentication schemes reject them from now on.
+    ///
+    /// Revoking a token that is already revoked does nothing. Revoking the tokens of a user
+    /// multiple times only keeps the latest `before`.
+    ///
+    /// # Arguments
+    /// - `revocation`: The [`AttachedRevocation`] describing which tokens to revoke.
+    ///
+    /// # Errors
+    /// This function may error if it failed to update the backend database.
+    fn revoke_tokens(&mut self, revocation: AttachedRevocation) -> impl Send + Future<Output = Result<(), Self::Error>>;
+    /// Acknowledges that all events up to (and including) some point in the audit log have been
+    /// delivered to an event sink.
+    ///
+    /// This only ever moves the sink's cursor forward; acknowledging an earlier point does
+    /// nothing. Acknowledgements are not recorded in the audit log themselves.
+    ///
+    /// # Arguments
+    /// - `sink`: The name of the sink that received the events.
+    /// - `seq`: The sequence number of the last audit record delivered.
+    ///
+    /// # Errors
+    /// This function may error if it failed to update the backend database.
+    fn ack_events(&mut self, sink: &str, seq: u64) -> impl Send + Future<Output = Result<(), Self::Error>>;
 
     // Read-only
     /// Gets a list of all versions in the database together with their metadata.
@@ -161,6 +704,18 @@ pub trait DatabaseConnection {
     /// # Errors
     /// This function may error if it failed to get the policies from the backend database.
     fn get_active_version(&mut self) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>>;
+    /// Retrieves the version that was active at a given time from the policy database.
+    ///
+    /// # Arguments
+    /// - `at`: The time at which to look.
+    ///
+    /// # Returns
+    /// The version number active at `at`, or [`None`] if none was.
+    ///
+    /// # Errors
+    /// This function may error if it failed to get the activation history from the backend
+    /// database.
+    fn get_active_version_at(&mut self, at: DateTime<Utc>) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>>;
     /// Retrieves the person who activated the policy.
     ///
     /// # Returns
@@ -169,6 +724,27 @@ pub trait DatabaseConnection {
     /// # Errors
     /// This function may error if it failed to get the policies from the backend database.
     fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>>;
+    /// Retrieves the activation of the currently active policy, i.e., which version, by whom and
+    /// since when.
+    ///
+    /// # Returns
+    /// The [`Activation`] of the active version, or [`None`] if none is.
+    ///
+    /// # Errors
+    /// This function may error if it failed to get the policies from the backend database.
+    fn get_activation(&mut self) -> impl Send + Future<Output = Result<Option<Activation>, Self::Error>>;
+    /// Retrieves the history of which versions were active when.
+    ///
+    /// Note that this may not go back further than the last time activations were
+    /// [pruned](DatabaseConnection::prune_activations()).
+    ///
+    /// # Returns
+    /// Every [`Activation`], ordered by the time it happened.
+    ///
+    /// # Errors
+    /// This function may error if it failed to get the activation history from the backend
+    /// database.
+    fn get_activations(&mut self) -> impl Send + Future<Output = Result<Vec<Activation>, Self::Error>>;
     /// Retrieves a particular policy version's metadata from the database.
     ///
     /// # Arguments
@@ -193,6 +769,187 @@ pub trait DatabaseConnection {
     /// This function may error if it failed to retrieve the version from the backend database, or
     /// if that version didn't exist.
     fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>>;
+    /// Retrieves the provenance of a particular policy version.
+    ///
+    /// This is generated from the version's metadata and whatever else was recorded when it was
+    /// written. Versions written before the backend recorded anything else still have provenance,
+    /// just with less in it.
+    ///
+    /// # Arguments
+    /// - `version`: The policy version to retrieve the provenance of.
+    ///
+    /// # Returns
+    /// A [`Provenance`] statement about the requested version, or [`None`] if the given version
+    /// wasn't found.
+    ///
+    /// # Errors
+    /// This function may error if it failed to retrieve the provenance from the backend database.
+    fn get_version_provenance(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Provenance>, Self::Error>>;
+    /// Gets a list of all templates in the database.
+    ///
+    /// # Returns
+    /// A map that enumerates all templates by name.
+    ///
+    /// # Errors
+    /// This function may error if it failed to get the templates from the backend database.
+    fn get_templates(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Template>, Self::Error>>;
+    /// Retrieves a particular template from the database.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the template to retrieve.
+    ///
+    /// # Returns
+    /// The requested [`Template`], or [`None`] if no template with that name was found.
+    ///
+    /// # Errors
+    /// This function may error if it failed to retrieve the template from the backend database.
+    fn get_template(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Template>, Self::Error>>;
+    /// Gets a list of all branches in the database.
+    ///
+    /// # Returns
+    /// A map that enumerates all branches by name, including their latest versions.
+    ///
+    /// # Errors
+    /// This function may error if it failed to get the branches from the backend database.
+    fn get_branches(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Branch>, Self::Error>>;
+    /// Gets a list of all content schemas in the database.
+    ///
+    /// # Returns
+    /// A map that enumerates all schemas by name.
+    ///
+    /// # Errors
+    /// This function may error if it failed to get the schemas from the backend database.
+    fn get_schemas(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Schema>, Self::Error>>;
+    /// Retrieves a particular content schema from the database.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the schema to retrieve.
+    ///
+    /// # Returns
+    /// The requested [`Schema`], or [`None`] if no schema with that name was found.
+    ///
+    /// # Errors
+    /// This function may error if it failed to retrieve the schema from the backend database.
+    fn get_schema(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Schema>, Self::Error>>;
+    /// Retrieves the decisions taken under a particular policy version.
+    ///
+    /// # Arguments
+    /// - `version`: The policy version to retrieve the decisions of.
+    ///
+    /// # Returns
+    /// The [`Decision`]s, ordered by the time they were registered, or [`None`] if the version
+    /// doesn't exist.
+    ///
+    /// # Errors
+    /// This function may error if it failed to retrieve the decisions from the backend database.
+    fn get_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Vec<Decision>>, Self::Error>>;
+    /// Counts the decisions taken under a particular policy version.
+    ///
+    /// # Arguments
+    /// - `version`: The policy version to count the decisions of.
+    ///
+    /// # Returns
+    /// The number of decisions, or [`None`] if the version doesn't exist.
+    ///
+    /// # Errors
+    /// This function may error if it failed to count the decisions in the backend database.
+    fn count_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>>;
+    /// Retrieves a particular decision, e.g., to find under which policy version it was taken.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the decision.
+    ///
+    /// # Returns
+    /// The [`Decision`], or [`None`] if no decision with that identifier was registered.
+    ///
+    /// # Errors
+    /// This function may error if it failed to retrieve the decision from the backend database.
+    fn get_decision(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Decision>, Self::Error>>;
+    /// Gets a list of all verification keys in the database, including revoked ones.
+    ///
+    /// # Returns
+    /// A map that enumerates all keys by identifier.
+    ///
+    /// # Errors
+    /// This function may error if it failed to get the keys from the backend database.
+    fn get_keys(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Key>, Self::Error>>;
+    /// Retrieves a particular verification key from the database.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the key to retrieve.
+    ///
+    /// # Returns
+    /// The requested [`Key`] (which may be revoked), or [`None`] if no key with that identifier
+    /// was registered.
+    ///
+    /// # Errors
+    /// This function may error if it failed to retrieve the key from the backend database.
+    fn get_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Key>, Self::Error>>;
+    /// Gets a list of all token revocations in the database.
+    ///
+    /// # Returns
+    /// A list of [`Revocation`]s, in the order they were made.
+    ///
+    /// # Errors
+    /// This function may error if it failed to get the revocations from the backend database.
+    fn get_revocations(&mut self) -> impl Send + Future<Output = Result<Vec<Revocation>, Self::Error>>;
+    /// Checks whether a token has been revoked.
+    ///
+    /// # Arguments
+    /// - `jti`: The identifier of the token, if it has any.
+    /// - `user`: The identifier of the user the token belongs to.
+    /// - `issued`: The time the token was issued, if known. Tokens of which this is unknown are
+    ///   considered revoked if any of the user's tokens are.
+    ///
+    /// # Returns
+    /// Whether the token has been revoked, either by itself or together with all tokens of its
+    /// user.
+    ///
+    /// # Errors
+    /// This function may error if it failed to get the revocations from the backend database.
+    fn is_revoked(&mut self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>>;
+    /// Retrieves the audit log from the database.
+    ///
+    /// Every mutation done through a [`DatabaseConnection`] is expected to append an
+    /// [`AuditRecord`] to this log in the same transaction.
+    ///
+    /// # Arguments
+    /// - `since`: If given, only records made at or after this time are returned.
+    ///
+    /// # Returns
+    /// The (matching) [`AuditRecord`]s, ordered by sequence number.
+    ///
+    /// # Errors
+    /// This function may error if it failed to retrieve the audit log from the backend database.
+    fn get_audit_log(&mut self, since: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<Vec<AuditRecord>, Self::Error>>;
+    /// Retrieves the changes made to the store after a given point.
+    ///
+    /// Changes are [`AuditRecord`]s enriched with the policy version they created, if any. Their
+    /// sequence numbers serve as a monotonic cursor.
+    ///
+    /// # Arguments
+    /// - `since`: The sequence number of the last change already seen (or `0` to start at the
+    ///   beginning).
+    /// - `limit`: The maximum number of changes to return.
+    ///
+    /// # Returns
+    /// The [`Change`]s after `since`, ordered by sequence number.
+    ///
+    /// # Errors
+    /// This function may error if it failed to retrieve the changes from the backend database.
+    fn get_changes(&mut self, since: u64, limit: u64) -> impl Send + Future<Output = Result<Vec<Change<Self::Content>>, Self::Error>>;
+    /// Retrieves up to where an event sink has acknowledged receiving events.
+    ///
+    /// # Arguments
+    /// - `sink`: The name of the sink.
+    ///
+    /// # Returns
+    /// The sequence number of the last audit record [acknowledged](DatabaseConnection::ack_events()),
+    /// or `0` if the sink never acknowledged anything.
+    ///
+    /// # Errors
+    /// This function may error if it failed to retrieve the cursor from the backend database.
+    fn get_event_cursor(&mut self, sink: &str) -> impl Send + Future<Output = Result<u64, Self::Error>>;
 }
 
 
@@ -211,6 +968,67 @@ impl<T: DatabaseConnection> DatabaseConnection for &mut T {
     }
     #[inline]
     fn deactivate(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> { <T as DatabaseConnection>::deactivate(self) }
+    #[inline]
+    fn freeze_version(&mut self, version: u64, reason: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::freeze_version(self, version, reason)
+    }
+    #[inline]
+    fn unfreeze_version(&mut self, version: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::unfreeze_version(self, version)
+    }
+    #[inline]
+    fn add_template(&mut self, template: AttachedTemplate) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        <T as DatabaseConnection>::add_template(self, template)
+    }
+    #[inline]
+    fn create_branch(&mut self, name: &str, from: u64) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::create_branch(self, name, from)
+    }
+    #[inline]
+    fn promote(&mut self, version: u64, target: &str, force: bool) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::promote(self, version, target, force)
+    }
+    #[inline]
+    fn import_version(
+        &mut self,
+        store: &str,
+        metadata: Metadata,
+        content: Self::Content,
+    ) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::import_version(self, store, metadata, content)
+    }
+    #[inline]
+    fn add_schema(&mut self, schema: AttachedSchema) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::add_schema(self, schema)
+    }
+    #[inline]
+    fn pseudonymize_user(&mut self, id: &str, pseudonym: &str) -> impl Send + Future<Output = Result<Result<u64, ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::pseudonymize_user(self, id, pseudonym)
+    }
+    #[inline]
+    fn add_decision(&mut self, version: u64, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::add_decision(self, version, id)
+    }
+    #[inline]
+    fn prune_activations(&mut self, before: DateTime<Utc>) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        <T as DatabaseConnection>::prune_activations(self, before)
+    }
+    #[inline]
+    fn add_key(&mut self, key: AttachedKey) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::add_key(self, key)
+    }
+    #[inline]
+    fn revoke_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Result<(), ClientError>, Self::Error>> {
+        <T as DatabaseConnection>::revoke_key(self, id)
+    }
+    #[inline]
+    fn revoke_tokens(&mut self, revocation: AttachedRevocation) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        <T as DatabaseConnection>::revoke_tokens(self, revocation)
+    }
+    #[inline]
+    fn ack_events(&mut self, sink: &str, seq: u64) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        <T as DatabaseConnection>::ack_events(self, sink, seq)
+    }
 
     #[inline]
     fn get_versions(&mut self) -> impl Send + Future<Output = Result<HashMap<u64, Metadata>, Self::Error>> {
@@ -221,8 +1039,20 @@ impl<T: DatabaseConnection> DatabaseConnection for &mut T {
         <T as DatabaseConnection>::get_active_version(self)
     }
     #[inline]
+    fn get_active_version_at(&mut self, at: DateTime<Utc>) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        <T as DatabaseConnection>::get_active_version_at(self, at)
+    }
+    #[inline]
     fn get_activator(&mut self) -> impl Send + Future<Output = Result<Option<User>, Self::Error>> { <T as DatabaseConnection>::get_activator(self) }
     #[inline]
+    fn get_activation(&mut self) -> impl Send + Future<Output = Result<Option<Activation>, Self::Error>> {
+        <T as DatabaseConnection>::get_activation(self)
+    }
+    #[inline]
+    fn get_activations(&mut self) -> impl Send + Future<Output = Result<Vec<Activation>, Self::Error>> {
+        <T as DatabaseConnection>::get_activations(self)
+    }
+    #[inline]
     fn get_version_metadata(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Metadata>, Self::Error>> {
         <T as DatabaseConnection>::get_version_metadata(self, version)
     }
@@ -230,4 +1060,64 @@ impl<T: DatabaseConnection> DatabaseConnection for &mut T {
     fn get_version_content(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Self::Content>, Self::Error>> {
         <T as DatabaseConnection>::get_version_content(self, version)
     }
+    #[inline]
+    fn get_version_provenance(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Provenance>, Self::Error>> {
+        <T as DatabaseConnection>::get_version_provenance(self, version)
+    }
+    #[inline]
+    fn get_templates(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Template>, Self::Error>> {
+        <T as DatabaseConnection>::get_templates(self)
+    }
+    #[inline]
+    fn get_template(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Template>, Self::Error>> {
+        <T as DatabaseConnection>::get_template(self, name)
+    }
+    #[inline]
+    fn get_branches(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Branch>, Self::Error>> {
+        <T as DatabaseConnection>::get_branches(self)
+    }
+    #[inline]
+    fn get_schemas(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Schema>, Self::Error>> {
+        <T as DatabaseConnection>::get_schemas(self)
+    }
+    #[inline]
+    fn get_schema(&mut self, name: &str) -> impl Send + Future<Output = Result<Option<Schema>, Self::Error>> {
+        <T as DatabaseConnection>::get_schema(self, name)
+    }
+    #[inline]
+    fn get_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<Vec<Decision>>, Self::Error>> {
+        <T as DatabaseConnection>::get_decisions(self, version)
+    }
+    #[inline]
+    fn count_decisions(&mut self, version: u64) -> impl Send + Future<Output = Result<Option<u64>, Self::Error>> {
+        <T as DatabaseConnection>::count_decisions(self, version)
+    }
+    #[inline]
+    fn get_decision(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Decision>, Self::Error>> {
+        <T as DatabaseConnection>::get_decision(self, id)
+    }
+    #[inline]
+    fn get_keys(&mut self) -> impl Send + Future<Output = Result<HashMap<String, Key>, Self::Error>> { <T as DatabaseConnection>::get_keys(self) }
+    #[inline]
+    fn get_key(&mut self, id: &str) -> impl Send + Future<Output = Result<Option<Key>, Self::Error>> { <T as DatabaseConnection>::get_key(self, id) }
+    #[inline]
+    fn get_revocations(&mut self) -> impl Send + Future<Output = Result<Vec<Revocation>, Self::Error>> {
+        <T as DatabaseConnection>::get_revocations(self)
+    }
+    #[inline]
+    fn is_revoked(&mut self, jti: Option<&str>, user: &str, issued: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<bool, Self::Error>> {
+        <T as DatabaseConnection>::is_revoked(self, jti, user, issued)
+    }
+    #[inline]
+    fn get_audit_log(&mut self, since: Option<DateTime<Utc>>) -> impl Send + Future<Output = Result<Vec<AuditRecord>, Self::Error>> {
+        <T as DatabaseConnection>::get_audit_log(self, since)
+    }
+    #[inline]
+    fn get_changes(&mut self, since: u64, limit: u64) -> impl Send + Future<Output = Result<Vec<Change<Self::Content>>, Self::Error>> {
+        <T as DatabaseConnection>::get_changes(self, since, limit)
+    }
+    #[inline]
+    fn get_event_cursor(&mut self, sink: &str) -> impl Send + Future<Output = Result<u64, Self::Error>> {
+        <T as DatabaseConnection>::get_event_cursor(self, sink)
+    }
 }
@@ -0,0 +1,37 @@
+//  DECISION.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:58:14
+//  Last edited:
+//    16 Oct 2026, 14:58:14
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines decisions, i.e., records of a reasoner having taken a
+//!   decision under a particular policy version.
+//
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::User;
+
+
+/***** LIBRARY *****/
+/// Records that a reasoner took a decision under a particular policy version.
+///
+/// The store doesn't know anything about the decision itself; it only links its identifier to the
+/// version, such that policy usage can be traced back.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Decision {
+    /// The identifier of the decision, as chosen by the reasoner. Unique within a store.
+    pub id: String,
+    /// The policy version under which the decision was taken.
+    pub version: u64,
+    /// The time the decision was registered.
+    pub recorded: DateTime<Utc>,
+    /// Defines who has registered the decision (typically, the reasoner).
+    pub reporter: User,
+}
@@ -0,0 +1,71 @@
+//  DENYALL.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:29:08
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the [`DenyAll`] trait, which describes the well-known policy
+//!   that denies everything for a particular type of policy content.
+//
+
+use serde_json::Value;
+
+use crate::metadata::AttachedMetadata;
+
+
+/***** CONSTANTS *****/
+/// The branch on which the built-in deny-all policy version is stored.
+///
+/// Its head is the well-known deny-all version, which is (re)used every time it is activated.
+pub const DENY_ALL_BRANCH: &str = "deny-all";
+
+
+
+
+
+/***** LIBRARY *****/
+/// Describes the policy that denies all requests for some type of policy content.
+///
+/// This policy is activated in emergencies, such that the behaviour of the reasoner is
+/// deterministic while the store is locked down (unlike when no policy is active at all).
+pub trait DenyAll: Sized {
+    /// Returns the content of a policy that denies all requests.
+    ///
+    /// # Returns
+    /// A new instance of Self that, when active, makes the reasoner deny everything.
+    fn deny_all() -> Self;
+
+    /// Returns the metadata with which the [deny-all](DenyAll::deny_all()) policy is stored.
+    ///
+    /// By default, this stores the policy on the [`DENY_ALL_BRANCH`] in the `builtin` language.
+    ///
+    /// # Returns
+    /// Some [`AttachedMetadata`] describing the deny-all policy. Its branch should always be the
+    /// [`DENY_ALL_BRANCH`].
+    #[inline]
+    fn metadata() -> AttachedMetadata {
+        AttachedMetadata {
+            name: "deny-all".into(),
+            description: "Built-in policy that denies all requests.".into(),
+            language: "builtin".into(),
+            branch: DENY_ALL_BRANCH.into(),
+            schema: None,
+            confidential: false,
+        }
+    }
+}
+
+// Default impls
+impl DenyAll for bool {
+    #[inline]
+    fn deny_all() -> Self { false }
+}
+impl DenyAll for Value {
+    #[inline]
+    fn deny_all() -> Self { Value::Bool(false) }
+}
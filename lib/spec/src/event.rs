@@ -0,0 +1,467 @@
+//  EVENT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:01:14
+//  Last edited:
+//    17 Oct 2026, 02:16:56
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the events that a policy store emits when its policies
+//!   change, such that every consumer (webhooks, streams, message buses,
+//!   ...) sees the same payloads.
+//!
+//!   Also implements delivering them: the audit log, which is written in
+//!   the same transaction as every mutation, serves as an outbox that
+//!   [`EventDelivery`] fans out to [`EventSink`]s.
+//
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::audit::{AuditAction, AuditRecord, Change};
+use crate::databaseconn::{ChangeListener, DatabaseConnection, DatabaseConnector};
+use crate::metadata::User;
+use crate::worker::Worker;
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`EventDelivery`] worker.
+#[derive(Debug, Error)]
+pub enum DeliveryError {
+    /// Failed to acknowledge delivered events.
+    #[error("Failed to acknowledge events up to {seq} for sink {sink:?}")]
+    Ack {
+        sink: String,
+        seq:  u64,
+        #[source]
+        err:  Box<dyn 'static + Error>,
+    },
+    /// Failed to connect to the backend database.
+    #[error("Failed to connect to the backend database")]
+    Connect {
+        #[source]
+        err: Box<dyn 'static + Error>,
+    },
+    /// The sink failed to receive events.
+    #[error("Failed to deliver {count} event(s) to sink {sink:?}")]
+    Deliver {
+        sink:  String,
+        count: usize,
+        #[source]
+        err:   Box<dyn 'static + Error>,
+    },
+    /// Failed to retrieve the changes to deliver.
+    #[error("Failed to retrieve changes after {since} for sink {sink:?}")]
+    GetChanges {
+        sink:  String,
+        since: u64,
+        #[source]
+        err:   Box<dyn 'static + Error>,
+    },
+    /// Failed to retrieve up to where a sink has acknowledged events.
+    #[error("Failed to retrieve the event cursor of sink {sink:?}")]
+    GetCursor {
+        sink: String,
+        #[source]
+        err:  Box<dyn 'static + Error>,
+    },
+}
+
+
+
+
+/***** LIBRARY *****/
+/// Abstracts over something that receives [`EventRecord`]s, e.g., a webhook or a message bus.
+///
+/// Sinks receive events **at least once**: if the [`EventDelivery`] crashes between delivering
+/// events and acknowledging them, they will be delivered again. Sinks (or their consumers) should
+/// use [`EventRecord::seq`] to filter out duplicates.
+pub trait EventSink {
+    /// The type of errors emitted when delivering fails.
+    type Error: Error;
+
+
+    /// Returns the name of this sink.
+    ///
+    /// This is used to remember up to where the sink has received events, so it must be unique
+    /// and remain the same across restarts.
+    ///
+    /// # Returns
+    /// The name of the sink.
+    fn name(&self) -> &str;
+
+    /// Delivers a batch of events to the sink.
+    ///
+    /// # Arguments
+    /// - `events`: The [`EventRecord`]s to deliver, ordered by sequence number.
+    ///
+    /// # Errors
+    /// This function errors if the events could not (all) be delivered, in which case the whole
+    /// batch is delivered again later.
+    fn deliver(&self, events: &[EventRecord]) -> impl Send + Future<Output = Result<(), Self::Error>>;
+}
+
+
+
+/// Describes a change to the policies in the store.
+///
+/// Unlike an [`AuditAction`], this only covers changes that affect policies (e.g., not key
+/// management), and it is the payload that is sent to anyone subscribing to changes. New
+/// variants may be added in the future.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum PolicyEvent {
+    /// A new policy version was added.
+    VersionAdded { version: u64, name: String, branch: String },
+    /// A policy version was promoted into another branch, creating a new version there.
+    VersionPromoted { version: u64, target: String, promoted: u64 },
+    /// A policy version was activated.
+    Activated { version: u64 },
+    /// The active policy version was deactivated.
+    Deactivated { version: u64 },
+    /// A branch was forked from an existing version.
+    BranchCreated { name: String, from: u64 },
+    /// A template was registered (or replaced).
+    TemplateAdded { name: String },
+    /// A content schema was registered.
+    SchemaAdded { name: String },
+}
+impl PolicyEvent {
+    /// Returns the event describing the given change, if it affects policies.
+    ///
+    /// # Arguments
+    /// - `action`: The [`AuditAction`] describing the change.
+    ///
+    /// # Returns
+    /// A new PolicyEvent, or [`None`] if the change does not affect policies.
+    pub fn from_action(action: &AuditAction) -> Option<Self> {
+        match action {
+            AuditAction::AddVersion { version, name, branch } | AuditAction::Import { version, name, branch, .. } => {
+                Some(Self::VersionAdded { version: *version, name: name.clone(), branch: branch.clone() })
+            },
+            AuditAction::Promote { version, target, promoted } => {
+                Some(Self::VersionPromoted { version: *version, target: target.clone(), promoted: *promoted })
+            },
+            AuditAction::Activate { version } => Some(Self::Activated { version: *version }),
+            AuditAction::Deactivate { version } => Some(Self::Deactivated { version: *version }),
+            AuditAction::CreateBranch { name, from } => Some(Self::BranchCreated { name: name.clone(), from: *from }),
+            AuditAction::AddTemplate { name } => Some(Self::TemplateAdded { name: name.clone() }),
+            AuditAction::AddSchema { name } => Some(Self::SchemaAdded { name: name.clone() }),
+            AuditAction::Pseudonymize { .. }
+            | AuditAction::PruneActivations { .. }
+            | AuditAction::AddKey { .. }
+            | AuditAction::RevokeKey { .. }
+            | AuditAction::RevokeToken { .. }
+            | AuditAction::RevokeUserTokens { .. }
+            | AuditAction::FreezeVersion { .. }
+            | AuditAction::UnfreezeVersion { .. }
+            | AuditAction::AddDecision { .. } => None,
+        }
+    }
+
+    /// Returns a short, machine-readable identifier for the kind of event.
+    ///
+    /// # Returns
+    /// The same identifier as used in the serialized `event` field.
+    #[inline]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::VersionAdded { .. } => "version_added",
+            Self::VersionPromoted { .. } => "version_promoted",
+            Self::Activated { .. } => "activated",
+            Self::Deactivated { .. } => "deactivated",
+            Self::BranchCreated { .. } => "branch_created",
+            Self::TemplateAdded { .. } => "template_added",
+            Self::SchemaAdded { .. } => "schema_added",
+        }
+    }
+
+    /// Returns the policy version that is enforced after this event, if it changed.
+    ///
+    /// # Returns
+    /// `Some(Some(version))` if `version` was activated, `Some(None)` if the active version was
+    /// deactivated, or [`None`] if the event does not change which version is active.
+    #[inline]
+    pub fn active_version(&self) -> Option<Option<u64>> {
+        match self {
+            Self::Activated { version } => Some(Some(*version)),
+            Self::Deactivated { .. } => Some(None),
+            Self::VersionAdded { .. }
+            | Self::VersionPromoted { .. }
+            | Self::BranchCreated { .. }
+            | Self::TemplateAdded { .. }
+            | Self::SchemaAdded { .. } => None,
+        }
+    }
+}
+impl Display for PolicyEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::VersionAdded { version, name, branch } => write!(f, "Policy version {version} ({name:?}) was added to branch {branch:?}"),
+            Self::VersionPromoted { version, target, promoted } => {
+                write!(f, "Policy version {version} was promoted into branch {target:?} as version {promoted}")
+            },
+            Self::Activated { version } => write!(f, "Policy version {version} was activated"),
+            Self::Deactivated { version } => write!(f, "Policy version {version} was deactivated"),
+            Self::BranchCreated { name, from } => write!(f, "Branch {name:?} was created from policy version {from}"),
+            Self::TemplateAdded { name } => write!(f, "Template {name:?} was added"),
+            Self::SchemaAdded { name } => write!(f, "Schema {name:?} was added"),
+        }
+    }
+}
+
+/// A [`PolicyEvent`], together with when and by whom it happened.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventRecord {
+    /// The sequence number of the audit record that caused this event. Events are delivered in
+    /// order of this number, but not every number has an event.
+    pub seq: u64,
+    /// The time at which the event happened.
+    pub timestamp: DateTime<Utc>,
+    /// The ID of the user who caused the event.
+    pub user: String,
+    /// What happened.
+    #[serde(flatten)]
+    pub event: PolicyEvent,
+}
+impl EventRecord {
+    /// Returns the event recorded in the given audit record, if it affects policies.
+    ///
+    /// # Arguments
+    /// - `record`: The [`AuditRecord`] describing the change.
+    ///
+    /// # Returns
+    /// A new EventRecord, or [`None`] if the change does not affect policies.
+    #[inline]
+    pub fn from_record(record: &AuditRecord) -> Option<Self> {
+        PolicyEvent::from_action(&record.action).map(|event| Self { seq: record.seq, timestamp: record.timestamp, user: record.user.clone(), event })
+    }
+}
+
+
+
+/// A [`Worker`] that delivers the events in the audit log to an [`EventSink`].
+///
+/// Every tick, all events since the sink's last acknowledgement are delivered in batches, and
+/// every delivered batch is acknowledged in the backend database. As such, no event is missed,
+/// not even when the server crashes; see [`EventSink`] for what that means for duplicates.
+///
+/// When delivery fails, it is retried at the next tick. Every consecutive failure doubles the
+/// interval (up to 64 times), such that an unreachable sink isn't hammered.
+///
+/// If the backend can push notifications, give the worker a [`ChangeListener`] through
+/// [`EventDelivery::with_listener()`] to deliver events as soon as they happen.
+///
+/// The worker connects to the database for every tick, so it needs a connector that can hand out
+/// connections for any lifetime. Share it with the rest of the program through an
+/// [`Arc`](std::sync::Arc) rather than lending a reference.
+pub struct EventDelivery<D, S, L = ()> {
+    /// The database to read events from and acknowledge them in.
+    data:     D,
+    /// The user on who's behalf we connect to the database.
+    user:     User,
+    /// The sink to deliver events to.
+    sink:     S,
+    /// Wakes us up when the store changes, if the backend supports it.
+    listener: L,
+    /// How long to wait between ticks when all is well.
+    interval: Duration,
+    /// The maximum number of audit records to consider per batch.
+    batch:    u64,
+    /// The number of ticks that failed in a row.
+    failures: u32,
+    /// The sequence number of the last audit record seen by the sink.
+    seen:     AtomicU64,
+}
+impl<D, S> EventDelivery<D, S> {
+    /// Constructor for the EventDelivery.
+    ///
+    /// By default, the database is checked for new events every second, and up to 100 audit
+    /// records are considered per batch.
+    ///
+    /// # Arguments
+    /// - `data`: The [`DatabaseConnector`] to read events from, typically in an
+    ///   [`Arc`](std::sync::Arc).
+    /// - `user`: The (service) [`User`] on who's behalf we connect to the database.
+    /// - `sink`: The [`EventSink`] to deliver events to.
+    ///
+    /// # Returns
+    /// A new EventDelivery, ready to be run as a [`Worker`].
+    #[inline]
+    pub const fn new(data: D, user: User, sink: S) -> Self {
+        Self { data, user, sink, listener: (), interval: Duration::from_secs(1), batch: 100, failures: 0, seen: AtomicU64::new(0) }
+    }
+}
+impl<D, S, L> EventDelivery<D, S, L> {
+    /// Wakes the worker as soon as the given listener reports a change, instead of only checking
+    /// the database every interval.
+    ///
+    /// The interval still applies, in case a notification is missed (e.g., while the listener
+    /// reconnects). While delivery is failing, the worker backs off as usual.
+    ///
+    /// # Arguments
+    /// - `listener`: The [`ChangeListener`] of the database to read events from.
+    ///
+    /// # Returns
+    /// A new EventDelivery that is woken by the given `listener`.
+    #[inline]
+    pub fn with_listener<L2>(self, listener: L2) -> EventDelivery<D, S, L2> {
+        EventDelivery {
+            data: self.data,
+            user: self.user,
+            sink: self.sink,
+            listener,
+            interval: self.interval,
+            batch: self.batch,
+            failures: self.failures,
+            seen: self.seen,
+        }
+    }
+
+    /// Changes how often the database is checked for new events.
+    ///
+    /// # Arguments
+    /// - `interval`: The time between checks when all is well.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Changes how many audit records are considered per batch.
+    ///
+    /// Note that batches may contain fewer events, as not every record describes a
+    /// [`PolicyEvent`].
+    ///
+    /// # Arguments
+    /// - `batch`: The maximum number of records per batch. Values below 1 are treated as 1.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_batch_size(mut self, batch: u64) -> Self {
+        self.batch = batch.max(1);
+        self
+    }
+
+    /// Returns the sink this worker delivers to.
+    ///
+    /// # Returns
+    /// A reference to the internal [`EventSink`].
+    #[inline]
+    pub const fn sink(&self) -> &S { &self.sink }
+}
+impl<D, S, L> EventDelivery<D, S, L>
+where
+    D: Sync + DatabaseConnector,
+    D::Error: 'static,
+    D::Content: Send,
+    for<'s> D::Connection<'s>: Send,
+    for<'s> <D::Connection<'s> as DatabaseConnection>::Error: 'static,
+    S: Sync + EventSink,
+    S::Error: 'static,
+{
+    /// Delivers all events that the sink hasn't acknowledged yet.
+    ///
+    /// # Returns
+    /// The number of events delivered.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read from or write to the database, or if the sink
+    /// failed to receive a batch. Any batches delivered before that are acknowledged.
+    pub async fn deliver(&self) -> Result<usize, DeliveryError> {
+        let name: &str = self.sink.name();
+        let mut conn = self.data.connect(&self.user).await.map_err(|err| DeliveryError::Connect { err: Box::new(err) })?;
+        let mut since: u64 = conn.get_event_cursor(name).await.map_err(|err| DeliveryError::GetCursor { sink: name.into(), err: Box::new(err) })?;
+
+        let mut delivered: usize = 0;
+        loop {
+            // Get the next batch
+            let changes: Vec<Change<D::Content>> = conn.get_changes(since, self.batch).await.map_err(|err| DeliveryError::GetChanges {
+                sink: name.into(),
+                since,
+                err: Box::new(err),
+            })?;
+            let Some(last) = changes.last().map(|change| change.record.seq) else { break };
+            let events: Vec<EventRecord> = changes.iter().filter_map(|change| EventRecord::from_record(&change.record)).collect();
+
+            // Deliver, then acknowledge
+            if !events.is_empty() {
+                self.sink.deliver(&events).await.map_err(|err| DeliveryError::Deliver {
+                    sink:  name.into(),
+                    count: events.len(),
+                    err:   Box::new(err),
+                })?;
+                delivered += events.len();
+            }
+            // NOTE: Also done for batches without events, to skip over them next time
+            conn.ack_events(name, last).await.map_err(|err| DeliveryError::Ack { sink: name.into(), seq: last, err: Box::new(err) })?;
+            since = last;
+
+            if (changes.len() as u64) < self.batch {
+                break;
+            }
+        }
+        self.seen.store(since, Ordering::Relaxed);
+        Ok(delivered)
+    }
+}
+impl<D, S, L> Worker for EventDelivery<D, S, L>
+where
+    D: Send + Sync + DatabaseConnector,
+    D::Error: 'static,
+    D::Content: Send,
+    for<'s> D::Connection<'s>: Send,
+    for<'s> <D::Connection<'s> as DatabaseConnection>::Error: 'static,
+    S: Send + Sync + EventSink,
+    S::Error: 'static,
+    L: Send + Sync + ChangeListener,
+{
+    type Error = DeliveryError;
+
+
+    #[inline]
+    fn name(&self) -> &str { self.sink.name() }
+
+    #[inline]
+    fn interval(&self) -> Duration { self.interval * (1 << self.failures.min(6)) }
+
+    fn wake(&self) -> impl Send + Future<Output = ()> {
+        async move {
+            // NOTE: Waking up while failing would defeat the backoff. Failing to listen isn't
+            // fatal either, as we're still ticked every interval.
+            if self.failures > 0 || self.listener.next_change(self.seen.load(Ordering::Relaxed)).await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+
+    fn tick(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> {
+        async move {
+            match self.deliver().await {
+                Ok(_) => {
+                    self.failures = 0;
+                    Ok(())
+                },
+                Err(err) => {
+                    self.failures = self.failures.saturating_add(1);
+                    Err(err)
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//  KEY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:44:37
+//  Last edited:
+//    16 Oct 2026, 16:44:37
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines verification keys, i.e., keys stored in the policy store
+//!   itself that authentication schemes may use to verify tokens.
+//
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::User;
+
+
+/***** LIBRARY *****/
+/// The kinds of verification keys that can be stored.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyKind {
+    /// A shared secret, given as (URL-safe, unpadded) Base64.
+    Oct,
+    /// An RSA public key, given as PEM.
+    Rsa,
+    /// An elliptic curve public key, given as PEM.
+    Ec,
+    /// An Ed25519 public key, given as PEM.
+    Ed,
+}
+
+/// A verification key as given by the user when registering it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AttachedKey {
+    /// The identifier of the key, matched against the `kid` of tokens. Unique within a store, and
+    /// never reused, even after the key is revoked.
+    pub id: String,
+    /// What kind of key this is.
+    pub kind: KeyKind,
+    /// The key itself, encoded as described by its [`KeyKind`].
+    pub material: String,
+}
+
+/// A verification key as it is stored in the backend database.
+///
+/// Includes whatever is [attached](AttachedKey), but also things inferred when registering it
+/// (e.g., created time).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Key {
+    /// Whatever the user attached at runtime.
+    pub attached: AttachedKey,
+
+    /// The time the key was registered.
+    pub created: DateTime<Utc>,
+    /// Defines who has registered the key.
+    pub creator: User,
+    /// The time the key was revoked, if it was. Revoked keys no longer verify anything.
+    pub revoked: Option<DateTime<Utc>>,
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    18 Oct 2024, 17:38:02
 //  Last edited:
-//    23 Oct 2024, 11:37:29
+//    16 Oct 2026, 18:29:08
 //  Auto updated?
 //    Yes
 //
@@ -14,12 +14,36 @@
 //
 
 // Declare modules
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+pub mod audit;
 pub mod authresolver;
+pub mod canonical;
+pub mod consistency;
 pub mod databaseconn;
+pub mod decision;
+pub mod denyall;
+pub mod event;
+pub mod key;
 pub mod metadata;
+#[cfg(feature = "prost")]
+pub mod proto;
+pub mod provenance;
+pub mod revocation;
+pub mod schema;
 pub mod server;
+pub mod template;
+#[cfg(feature = "test-utils")]
+pub mod testing;
+pub mod transformer;
+pub mod userid;
+pub mod worker;
 
 // Import some things into the main scope
 pub use authresolver::AuthResolver;
 pub use databaseconn::DatabaseConnector;
-pub use server::Server;
+pub use denyall::DenyAll;
+pub use server::{Server, ServerHooks};
+pub use transformer::ContentTransformer;
+pub use userid::UserIdFormat;
+pub use worker::Worker;
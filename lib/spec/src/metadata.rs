@@ -4,7 +4,7 @@
 //  Created:
 //    18 Oct 2024, 17:50:16
 //  Last edited:
-//    25 Nov 2024, 10:46:35
+//    17 Oct 2026, 03:20:17
 //  Auto updated?
 //    Yes
 //
@@ -12,10 +12,24 @@
 //!   Defines metadata that is associated with every policy.
 //
 
+use std::net::IpAddr;
+use std::time::Instant;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 
+/***** CONSTANTS *****/
+/// The highest version number a policy can have.
+///
+/// Versions are numbered from 1 onwards. Because backends typically store them as signed 64-bit
+/// integers, versions above [`i64::MAX`] cannot exist; connectors treat them as unknown.
+pub const MAX_VERSION: u64 = i64::MAX as u64;
+
+
+
+
+
 /***** LIBRARY *****/
 /// Represents the relevant information about a creator/editor/w/e.
 ///
@@ -24,10 +38,106 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct User {
     /// Some machine-relevant identifier of the creator.
-    pub id:   String,
+    pub id:     String,
     /// Some human-relevant identifier of the creator.
-    pub name: String,
+    pub name:   String,
+    /// The organization vouching for the user (e.g., the issuer of their token), if known.
+    ///
+    /// The same `id` may refer to different users when issued by different organizations, which
+    /// is common when sharing data across domains.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    /// The roles (or permissions) granted to the user by the [`AuthResolver`](crate::AuthResolver),
+    /// if it knows about any.
+    ///
+    /// These only live as long as the request that was authorized; they are never stored.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles:  Vec<String>,
+}
+
+/// Everything known about who accesses the store, and how.
+///
+/// This is what servers hand to [`DatabaseConnector::connect_with()`](crate::DatabaseConnector::connect_with()),
+/// such that connectors can record richer provenance or restrict what the user sees.
+#[derive(Clone, Debug)]
+pub struct AccessContext {
+    /// The (authenticated) [`User`] on who's behalf actions are taken, including their roles.
+    pub user: User,
+    /// The address of the client from which the `user` connects, if known.
+    pub client: Option<IpAddr>,
+    /// The ID of the request in which the `user` connects, if any.
+    pub request_id: Option<String>,
+    /// The moment after which the client no longer cares about the result, if it told us.
+    ///
+    /// Connectors should not start work after this moment, as it would only compete with
+    /// requests that can still be answered.
+    pub deadline: Option<Instant>,
 }
+impl AccessContext {
+    /// Constructor for an AccessContext that only knows the user.
+    ///
+    /// # Arguments
+    /// - `user`: The [`User`] on who's behalf actions are taken.
+    ///
+    /// # Returns
+    /// A new AccessContext.
+    #[inline]
+    pub const fn new(user: User) -> Self { Self { user, client: None, request_id: None, deadline: None } }
+
+    /// Sets the address of the client from which the user connects.
+    ///
+    /// # Arguments
+    /// - `client`: The address of the client.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_client(mut self, client: IpAddr) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets the ID of the request in which the user connects.
+    ///
+    /// # Arguments
+    /// - `request_id`: The ID of the request.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Sets the moment after which the client no longer cares about the result.
+    ///
+    /// # Arguments
+    /// - `deadline`: The moment the client gives up.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Returns whether the [deadline](AccessContext::deadline) has passed, i.e., whether any
+    /// further work for this access is wasted.
+    ///
+    /// # Returns
+    /// True if there is a deadline and it lies in the past, or false otherwise.
+    #[inline]
+    pub fn expired(&self) -> bool { self.deadline.is_some_and(|deadline| deadline <= Instant::now()) }
+}
+
+/// Returns the name of the branch that versions are added to if no branch is given.
+///
+/// # Returns
+/// The string `main`.
+#[inline]
+pub fn default_branch() -> String { "main".into() }
 
 /// Metadata that is given by the user as an attachment to a policy.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -39,6 +149,56 @@ pub struct AttachedMetadata {
     /// A description of the policy language that the snippet is written in. Reasoners may decide
     /// to accept or deny the policy based on this identifier.
     pub language: String,
+    /// The branch to which the policy is appended. Branches are created when first used.
+    #[serde(default = "default_branch")]
+    pub branch: String,
+    /// The name of the [content schema](crate::schema::Schema) that the policy adheres to, if
+    /// any. Content is validated against it when the policy is added.
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// Whether the content of the policy is confidential. If so, servers only reveal it to users
+    /// with the right roles; everyone else can still see its metadata.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub confidential: bool,
+}
+
+/// Describes where a policy snippet was imported from, if it was copied over from another store.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Origin {
+    /// The name of the store the snippet was copied from.
+    pub store:   String,
+    /// The version number the snippet had in that store.
+    pub version: u64,
+    /// The identifier of the user who originally wrote the snippet.
+    pub creator: String,
+    /// The time the snippet was originally created.
+    pub created: DateTime<Utc>,
+}
+
+/// Describes how the content of a policy snippet was found to be signed by a trusted identity.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Verification {
+    /// The name of the trusted identity whose signature was verified.
+    pub identity: String,
+    /// The digest of what was signed, e.g., `sha256:...` of the content or of the OCI manifest
+    /// holding it.
+    pub digest:   String,
+    /// The time the signature was verified.
+    pub verified: DateTime<Utc>,
+}
+
+/// Describes why and by whom a policy snippet was frozen.
+///
+/// Frozen snippets cannot be activated or promoted until they are unfrozen, e.g., while they are
+/// investigated for being malicious.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Freeze {
+    /// Why the snippet was frozen.
+    pub reason:  String,
+    /// The time the snippet was frozen.
+    pub frozen:  DateTime<Utc>,
+    /// Defines who has frozen the snippet.
+    pub freezer: User,
 }
 
 /// Metadata associated with a policy snippet.
@@ -54,6 +214,70 @@ pub struct Metadata {
     pub created: DateTime<Utc>,
     /// Defines who has written a policy.
     pub creator: User,
-    /// The version number of this snippet.
+    /// The version number of this snippet. Always in the range `1..=`[`MAX_VERSION`].
     pub version: u64,
+    /// The version that was the latest of the branch when this snippet was appended to it, if
+    /// any.
+    pub parent: Option<u64>,
+    /// The version on another branch that this snippet was promoted from, if any.
+    #[serde(default)]
+    pub promoted_from: Option<u64>,
+    /// The hash of the snippet's content in [canonical form](crate::canonical), if computed.
+    ///
+    /// This may be missing for snippets stored before content was hashed.
+    #[serde(default)]
+    pub hash: Option<String>,
+    /// The store and version this snippet was imported from, if it was copied over from another
+    /// store.
+    #[serde(default)]
+    pub origin: Option<Origin>,
+    /// How the content of this snippet was found to be signed when it was imported, if it was.
+    ///
+    /// Only ever set by the store itself; whatever clients send is ignored.
+    #[serde(default)]
+    pub verification: Option<Verification>,
+    /// Why and by whom this snippet was frozen, if it currently is.
+    ///
+    /// Only ever set by the store itself; it is not carried over when the snippet is imported
+    /// into another store.
+    #[serde(default)]
+    pub frozen: Option<Freeze>,
+}
+
+/// Describes a named lineage of policy versions.
+///
+/// Versions are appended to a branch, each branch tracking its own latest version. Note that
+/// version numbers are still unique across all branches.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Branch {
+    /// The name of the branch.
+    pub name: String,
+    /// The latest version appended to this branch, if any.
+    pub head: Option<u64>,
+    /// The version this branch was forked from, if any.
+    pub base: Option<u64>,
+
+    /// The time the branch was created.
+    pub created: DateTime<Utc>,
+    /// Defines who has created the branch.
+    pub creator: User,
+}
+
+/// Describes a period during which a policy version was active.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Activation {
+    /// The version that was activated.
+    pub version: u64,
+
+    /// The time the version was activated.
+    pub activated:   DateTime<Utc>,
+    /// Defines who has activated the version.
+    pub activator:   User,
+    /// The address of the client from which the version was activated, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client:      Option<IpAddr>,
+    /// The time the version was deactivated, or [`None`] if it is still active.
+    pub deactivated: Option<DateTime<Utc>>,
+    /// Defines who has deactivated the version, if anyone.
+    pub deactivator: Option<User>,
 }
@@ -0,0 +1,738 @@
+//  PROTO.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:49:06
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines protobuf representations of the [metadata](crate::metadata)
+//!   types, such that they can be sent over protocols other than JSON.
+//!
+//!   The messages are those described in `proto/metadata.proto` (see
+//!   [`PROTO`]), written out as `prost-build` would generate them.
+//
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use prost_types::Timestamp;
+use thiserror::Error;
+
+use crate::metadata;
+
+
+/***** ERRORS *****/
+/// Defines errors when converting messages to [metadata](crate::metadata) types.
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    /// A field that is required by the store was not given.
+    #[error("Missing field {field:?} in {message} message")]
+    MissingField { message: &'static str, field: &'static str },
+    /// A timestamp could not be represented.
+    #[error("Timestamp {seconds}s + {nanos}ns is out of range")]
+    TimestampOutOfRange { seconds: i64, nanos: i32 },
+    /// A client address was not a valid IP address.
+    #[error("Client address {client:?} is not a valid IP address")]
+    IllegalClient { client: String },
+}
+
+
+
+
+
+/***** CONSTANTS *****/
+/// The `.proto` definitions of the messages in this module.
+pub const PROTO: &str = include_str!("../proto/metadata.proto");
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Converts a [`DateTime`] to a protobuf [`Timestamp`].
+///
+/// # Arguments
+/// - `time`: The [`DateTime`] to convert.
+///
+/// # Returns
+/// An equivalent [`Timestamp`].
+#[inline]
+pub fn to_timestamp(time: DateTime<Utc>) -> Timestamp {
+    // NOTE: Subsecond nanos are always below 2 billion, so fit in an i32
+    Timestamp { seconds: time.timestamp(), nanos: time.timestamp_subsec_nanos() as i32 }
+}
+
+/// Converts a protobuf [`Timestamp`] to a [`DateTime`].
+///
+/// # Arguments
+/// - `time`: The [`Timestamp`] to convert.
+///
+/// # Returns
+/// An equivalent [`DateTime`].
+///
+/// # Errors
+/// This function errors if the timestamp is not normalized or out of the range of [`DateTime`].
+#[inline]
+pub fn from_timestamp(time: &Timestamp) -> Result<DateTime<Utc>, ConvertError> {
+    u32::try_from(time.nanos)
+        .ok()
+        .and_then(|nanos| DateTime::from_timestamp(time.seconds, nanos))
+        .ok_or(ConvertError::TimestampOutOfRange { seconds: time.seconds, nanos: time.nanos })
+}
+
+/// Unwraps a required message field.
+///
+/// # Arguments
+/// - `value`: The field's value.
+/// - `message`: The name of the message the field is in.
+/// - `field`: The name of the field.
+///
+/// # Returns
+/// The field's value if it was given.
+///
+/// # Errors
+/// This function errors if the field was not given.
+#[inline]
+fn required<T>(value: Option<T>, message: &'static str, field: &'static str) -> Result<T, ConvertError> {
+    value.ok_or(ConvertError::MissingField { message, field })
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Protobuf flavour of [`metadata::User`].
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct User {
+    /// Some identifier of the user.
+    #[prost(string, tag = "1")]
+    pub id:     String,
+    /// Some human-readable name of the user.
+    #[prost(string, tag = "2")]
+    pub name:   String,
+    /// The organization vouching for the user, if known.
+    #[prost(string, optional, tag = "3")]
+    pub issuer: Option<String>,
+}
+impl From<metadata::User> for User {
+    #[inline]
+    fn from(value: metadata::User) -> Self { Self { id: value.id, name: value.name, issuer: value.issuer } }
+}
+impl From<User> for metadata::User {
+    #[inline]
+    fn from(value: User) -> Self { Self { id: value.id, name: value.name, issuer: value.issuer, roles: Vec::new() } }
+}
+
+
+
+/// Protobuf flavour of [`metadata::AttachedMetadata`].
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct AttachedMetadata {
+    /// Human-readable name of the policy.
+    #[prost(string, tag = "1")]
+    pub name: String,
+    /// Human-readable description of the policy.
+    #[prost(string, tag = "2")]
+    pub description: String,
+    /// The language of the policy.
+    #[prost(string, tag = "3")]
+    pub language: String,
+    /// The branch the policy is on (`main` if empty).
+    #[prost(string, tag = "4")]
+    pub branch: String,
+    /// The name of the schema the policy's content must adhere to, if any.
+    #[prost(string, optional, tag = "5")]
+    pub schema: Option<String>,
+    /// Whether the content of the policy is only revealed to users with the right roles.
+    #[prost(bool, tag = "6")]
+    pub confidential: bool,
+}
+impl From<metadata::AttachedMetadata> for AttachedMetadata {
+    #[inline]
+    fn from(value: metadata::AttachedMetadata) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            language: value.language,
+            branch: value.branch,
+            schema: value.schema,
+            confidential: value.confidential,
+        }
+    }
+}
+impl From<AttachedMetadata> for metadata::AttachedMetadata {
+    #[inline]
+    fn from(value: AttachedMetadata) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            language: value.language,
+            branch: if value.branch.is_empty() { metadata::default_branch() } else { value.branch },
+            schema: value.schema,
+            confidential: value.confidential,
+        }
+    }
+}
+
+
+
+/// Protobuf flavour of [`metadata::Origin`].
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct Origin {
+    /// The name of the store the policy was copied from.
+    #[prost(string, tag = "1")]
+    pub store:   String,
+    /// The version number the policy had in that store.
+    #[prost(uint64, tag = "2")]
+    pub version: u64,
+    /// The identifier of the user who originally wrote the policy.
+    #[prost(string, tag = "3")]
+    pub creator: String,
+    /// When the policy was originally created.
+    #[prost(message, optional, tag = "4")]
+    pub created: Option<Timestamp>,
+}
+impl From<metadata::Origin> for Origin {
+    #[inline]
+    fn from(value: metadata::Origin) -> Self {
+        Self { store: value.store, version: value.version, creator: value.creator, created: Some(to_timestamp(value.created)) }
+    }
+}
+impl TryFrom<Origin> for metadata::Origin {
+    type Error = ConvertError;
+
+    #[inline]
+    fn try_from(value: Origin) -> Result<Self, Self::Error> {
+        Ok(Self {
+            store:   value.store,
+            version: value.version,
+            creator: value.creator,
+            created: from_timestamp(&required(value.created, "Origin", "created")?)?,
+        })
+    }
+}
+
+
+
+/// Protobuf flavour of [`metadata::Verification`].
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct Verification {
+    /// The name of the trusted identity whose signature was verified.
+    #[prost(string, tag = "1")]
+    pub identity: String,
+    /// The digest of what was signed.
+    #[prost(string, tag = "2")]
+    pub digest:   String,
+    /// When the signature was verified.
+    #[prost(message, optional, tag = "3")]
+    pub verified: Option<Timestamp>,
+}
+impl From<metadata::Verification> for Verification {
+    #[inline]
+    fn from(value: metadata::Verification) -> Self {
+        Self { identity: value.identity, digest: value.digest, verified: Some(to_timestamp(value.verified)) }
+    }
+}
+impl TryFrom<Verification> for metadata::Verification {
+    type Error = ConvertError;
+
+    #[inline]
+    fn try_from(value: Verification) -> Result<Self, Self::Error> {
+        Ok(Self {
+            identity: value.identity,
+            digest:   value.digest,
+            verified: from_timestamp(&required(value.verified, "Verification", "verified")?)?,
+        })
+    }
+}
+
+
+
+/// Protobuf flavour of [`metadata::Freeze`].
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct Freeze {
+    /// Why the policy was frozen.
+    #[prost(string, tag = "1")]
+    pub reason:  String,
+    /// When the policy was frozen.
+    #[prost(message, optional, tag = "2")]
+    pub frozen:  Option<Timestamp>,
+    /// Who froze the policy.
+    #[prost(message, optional, tag = "3")]
+    pub freezer: Option<User>,
+}
+impl From<metadata::Freeze> for Freeze {
+    #[inline]
+    fn from(value: metadata::Freeze) -> Self {
+        Self { reason: value.reason, frozen: Some(to_timestamp(value.frozen)), freezer: Some(value.freezer.into()) }
+    }
+}
+impl TryFrom<Freeze> for metadata::Freeze {
+    type Error = ConvertError;
+
+    #[inline]
+    fn try_from(value: Freeze) -> Result<Self, Self::Error> {
+        Ok(Self {
+            reason:  value.reason,
+            frozen:  from_timestamp(&required(value.frozen, "Freeze", "frozen")?)?,
+            freezer: required(value.freezer, "Freeze", "freezer")?.into(),
+        })
+    }
+}
+
+
+
+/// Protobuf flavour of [`metadata::Metadata`].
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct Metadata {
+    /// The metadata given by the user.
+    #[prost(message, optional, tag = "1")]
+    pub attached: Option<AttachedMetadata>,
+    /// When the policy was added.
+    #[prost(message, optional, tag = "2")]
+    pub created: Option<Timestamp>,
+    /// Who added the policy.
+    #[prost(message, optional, tag = "3")]
+    pub creator: Option<User>,
+    /// The version number of the policy.
+    #[prost(uint64, tag = "4")]
+    pub version: u64,
+    /// The version this policy was based on, if any.
+    #[prost(uint64, optional, tag = "5")]
+    pub parent: Option<u64>,
+    /// The version this policy was promoted from, if any.
+    #[prost(uint64, optional, tag = "6")]
+    pub promoted_from: Option<u64>,
+    /// The hash of the policy's content, if known.
+    #[prost(string, optional, tag = "7")]
+    pub hash: Option<String>,
+    /// Where the policy was imported from, if it was copied over from another store.
+    #[prost(message, optional, tag = "8")]
+    pub origin: Option<Origin>,
+    /// How the policy's content was found to be signed when it was imported, if it was.
+    #[prost(message, optional, tag = "9")]
+    pub verification: Option<Verification>,
+    /// Why and by whom the policy was frozen, if it currently is.
+    #[prost(message, optional, tag = "10")]
+    pub frozen: Option<Freeze>,
+}
+impl From<metadata::Metadata> for Metadata {
+    #[inline]
+    fn from(value: metadata::Metadata) -> Self {
+        Self {
+            attached: Some(value.attached.into()),
+            created: Some(to_timestamp(value.created)),
+            creator: Some(value.creator.into()),
+            version: value.version,
+            parent: value.parent,
+            promoted_from: value.promoted_from,
+            hash: value.hash,
+            origin: value.origin.map(Origin::from),
+            verification: value.verification.map(Verification::from),
+            frozen: value.frozen.map(Freeze::from),
+        }
+    }
+}
+impl TryFrom<Metadata> for metadata::Metadata {
+    type Error = ConvertError;
+
+    #[inline]
+    fn try_from(value: Metadata) -> Result<Self, Self::Error> {
+        Ok(Self {
+            attached: required(value.attached, "Metadata", "attached")?.into(),
+            created: from_timestamp(&required(value.created, "Metadata", "created")?)?,
+            creator: required(value.creator, "Metadata", "creator")?.into(),
+            version: value.version,
+            parent: value.parent,
+            promoted_from: value.promoted_from,
+            hash: value.hash,
+            origin: value.origin.map(metadata::Origin::try_from).transpose()?,
+            verification: value.verification.map(metadata::Verification::try_from).transpose()?,
+            frozen: value.frozen.map(metadata::Freeze::try_from).transpose()?,
+        })
+    }
+}
+
+
+
+/// Protobuf flavour of [`metadata::Branch`].
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct Branch {
+    /// The name of the branch.
+    #[prost(string, tag = "1")]
+    pub name:    String,
+    /// The latest version appended to this branch, if any.
+    #[prost(uint64, optional, tag = "2")]
+    pub head:    Option<u64>,
+    /// The version this branch was forked from, if any.
+    #[prost(uint64, optional, tag = "3")]
+    pub base:    Option<u64>,
+    /// When the branch was created.
+    #[prost(message, optional, tag = "4")]
+    pub created: Option<Timestamp>,
+    /// Who created the branch.
+    #[prost(message, optional, tag = "5")]
+    pub creator: Option<User>,
+}
+impl From<metadata::Branch> for Branch {
+    #[inline]
+    fn from(value: metadata::Branch) -> Self {
+        Self {
+            name:    value.name,
+            head:    value.head,
+            base:    value.base,
+            created: Some(to_timestamp(value.created)),
+            creator: Some(value.creator.into()),
+        }
+    }
+}
+impl TryFrom<Branch> for metadata::Branch {
+    type Error = ConvertError;
+
+    #[inline]
+    fn try_from(value: Branch) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name:    value.name,
+            head:    value.head,
+            base:    value.base,
+            created: from_timestamp(&required(value.created, "Branch", "created")?)?,
+            creator: required(value.creator, "Branch", "creator")?.into(),
+        })
+    }
+}
+
+
+
+/// Protobuf flavour of [`metadata::Activation`].
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct Activation {
+    /// The version that was activated.
+    #[prost(uint64, tag = "1")]
+    pub version:     u64,
+    /// When the version was activated.
+    #[prost(message, optional, tag = "2")]
+    pub activated:   Option<Timestamp>,
+    /// Who activated the version.
+    #[prost(message, optional, tag = "3")]
+    pub activator:   Option<User>,
+    /// When the version was deactivated, if it has been.
+    #[prost(message, optional, tag = "4")]
+    pub deactivated: Option<Timestamp>,
+    /// Who deactivated the version, if anyone.
+    #[prost(message, optional, tag = "5")]
+    pub deactivator: Option<User>,
+    /// The address of the client from which the version was activated, if known.
+    #[prost(string, optional, tag = "6")]
+    pub client:      Option<String>,
+}
+impl From<metadata::Activation> for Activation {
+    #[inline]
+    fn from(value: metadata::Activation) -> Self {
+        Self {
+            version:     value.version,
+            activated:   Some(to_timestamp(value.activated)),
+            activator:   Some(value.activator.into()),
+            deactivated: value.deactivated.map(to_timestamp),
+            deactivator: value.deactivator.map(User::from),
+            client:      value.client.as_ref().map(IpAddr::to_string),
+        }
+    }
+}
+impl TryFrom<Activation> for metadata::Activation {
+    type Error = ConvertError;
+
+    #[inline]
+    fn try_from(value: Activation) -> Result<Self, Self::Error> {
+        Ok(Self {
+            version:     value.version,
+            activated:   from_timestamp(&required(value.activated, "Activation", "activated")?)?,
+            activator:   required(value.activator, "Activation", "activator")?.into(),
+            client:      value.client.map(|client| client.parse().map_err(|_| ConvertError::IllegalClient { client })).transpose()?,
+            deactivated: value.deactivated.as_ref().map(from_timestamp).transpose()?,
+            deactivator: value.deactivator.map(metadata::User::from),
+        })
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::net::Ipv4Addr;
+
+    use chrono::{TimeDelta, TimeZone as _};
+    use prost::Message as _;
+
+    use super::*;
+
+
+    /// The scalar types of protobuf, which are spelled the same in `.proto` files and `prost`
+    /// attributes.
+    const PROTO_SCALARS: [&str; 15] = [
+        "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32", "fixed64", "sfixed32", "sfixed64", "bool", "string",
+        "bytes",
+    ];
+
+    /// Describes the type of a field of a message by how it is encoded.
+    ///
+    /// # Arguments
+    /// - `label`: Whether the field is `optional`, `repeated` or neither (empty).
+    /// - `ty`: The scalar type of the field, or the name of its message type.
+    ///
+    /// # Returns
+    /// A description of the type. Messages are described by the last segment of their name, and are
+    /// always optional.
+    fn describe_type(label: &str, ty: &str) -> String {
+        if PROTO_SCALARS.contains(&ty) {
+            if label.is_empty() { ty.into() } else { format!("{label} {ty}") }
+        } else {
+            let name: &str = ty.rsplit(['.', ':']).next().unwrap_or(ty);
+            if label == "repeated" { format!("repeated message {name}") } else { format!("message {name}") }
+        }
+    }
+
+    /// Finds the messages defined in a `.proto` file.
+    ///
+    /// # Arguments
+    /// - `proto`: The contents of the `.proto` file.
+    ///
+    /// # Returns
+    /// The fields of every message by name, as a map of tags to the name and the
+    /// [description](describe_type()) of the field.
+    fn proto_messages(proto: &str) -> BTreeMap<String, BTreeMap<u32, (String, String)>> {
+        let mut messages: BTreeMap<String, BTreeMap<u32, (String, String)>> = BTreeMap::new();
+        let mut current: Option<String> = None;
+        for line in proto.lines() {
+            let line: &str = line.split("//").next().unwrap_or("").trim();
+            if let Some(name) = line.strip_prefix("message ") {
+                let name: String = name.trim_end_matches('}').trim_end().trim_end_matches('{').trim().into();
+                messages.insert(name.clone(), BTreeMap::new());
+                // Empty messages are closed on the same line
+                current = if line.ends_with('}') { None } else { Some(name) };
+                continue;
+            }
+            let Some(message) = &current else { continue };
+            if line == "}" {
+                current = None;
+                continue;
+            }
+            let Some((decl, tag)) = line.strip_suffix(';').and_then(|line| line.split_once('=')) else { continue };
+            let tag: u32 = tag.trim().parse().unwrap_or_else(|_| panic!("Illegal tag in field {line:?} of message {message:?}"));
+            let (decl, name): (&str, &str) = decl.trim().rsplit_once(' ').unwrap_or_else(|| panic!("Illegal field {line:?} in message {message:?}"));
+            let ty: String = if let Some(map) = decl.strip_prefix("map<").and_then(|map| map.strip_suffix('>')) {
+                let (key, value): (&str, &str) = map.split_once(',').unwrap_or_else(|| panic!("Illegal map {line:?} in message {message:?}"));
+                format!("map<{}, {}>", key.trim(), describe_type("", value.trim()))
+            } else {
+                match decl.split_once(' ') {
+                    Some((label, ty)) => describe_type(label, ty.trim()),
+                    None => describe_type("", decl),
+                }
+            };
+            messages.get_mut(message).unwrap().insert(tag, (name.into(), ty));
+        }
+        messages
+    }
+
+    /// Finds the messages derived with `prost` in a Rust source file.
+    ///
+    /// # Arguments
+    /// - `rust`: The contents of the source file.
+    ///
+    /// # Returns
+    /// The fields of every message by name, as a map of tags to the name and the
+    /// [description](describe_type()) of the field.
+    fn prost_messages(rust: &str) -> BTreeMap<String, BTreeMap<u32, (String, String)>> {
+        let mut messages: BTreeMap<String, BTreeMap<u32, (String, String)>> = BTreeMap::new();
+        let mut derived: bool = false;
+        let mut current: Option<String> = None;
+        let mut attr: Option<&str> = None;
+        for line in rust.lines() {
+            let line: &str = line.trim();
+            if line.starts_with("#[derive(") {
+                derived = line.contains("prost::Message");
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("pub struct ") {
+                if derived {
+                    let name: String = name.trim_end_matches("{}").trim_end().trim_end_matches('{').trim().into();
+                    messages.insert(name.clone(), BTreeMap::new());
+                    current = if line.ends_with("{}") { None } else { Some(name) };
+                }
+                derived = false;
+                continue;
+            }
+            let Some(message) = &current else { continue };
+            if line == "}" {
+                current = None;
+            } else if let Some(prost) = line.strip_prefix("#[prost(").and_then(|line| line.strip_suffix(")]")) {
+                attr = Some(prost);
+            } else if let (Some(prost), Some(field)) = (attr.take(), line.strip_prefix("pub ")) {
+                let (name, rust_ty): (&str, &str) = field.split_once(':').unwrap_or_else(|| panic!("Illegal field {line:?} in message {message:?}"));
+                let rust_ty: &str = rust_ty.trim().trim_end_matches(',');
+                // The innermost type, e.g., `User` in `Option<User>`, or the value type of a map
+                let inner: &str = rust_ty.rsplit(['<', ' ']).next().unwrap_or(rust_ty).trim_end_matches('>');
+
+                // Split the attribute on commas, except those within the quotes of a map
+                let mut items: Vec<&str> = Vec::new();
+                let mut start: usize = 0;
+                let mut quoted: bool = false;
+                for (i, c) in prost.char_indices() {
+                    match c {
+                        '"' => quoted = !quoted,
+                        ',' if !quoted => {
+                            items.push(prost[start..i].trim());
+                            start = i + 1;
+                        },
+                        _ => {},
+                    }
+                }
+                items.push(prost[start..].trim());
+
+                let kind: &str = items[0].split('=').next().unwrap_or("").trim();
+                let label: &str = items.iter().find(|item| **item == "optional" || **item == "repeated").copied().unwrap_or("");
+                let tag: u32 = items
+                    .iter()
+                    .find_map(|item| item.strip_prefix("tag = \"").and_then(|tag| tag.strip_suffix('"')))
+                    .and_then(|tag| tag.parse().ok())
+                    .unwrap_or_else(|| panic!("Missing tag for field {name:?} of message {message:?}"));
+                let ty: String = match kind {
+                    "map" => {
+                        let (key, value): (&str, &str) = items[0]
+                            .split_once('"')
+                            .and_then(|(_, map)| map.trim_end_matches('"').split_once(','))
+                            .unwrap_or_else(|| panic!("Illegal map for field {name:?} of message {message:?}"));
+                        let value: &str = if value.trim() == "message" { inner } else { value.trim() };
+                        format!("map<{}, {}>", key.trim(), describe_type("", value))
+                    },
+                    "message" => describe_type(label, inner),
+                    scalar => describe_type(label, scalar),
+                };
+                messages.get_mut(message).unwrap().insert(tag, (name.trim().into(), ty));
+            }
+        }
+        messages
+    }
+
+    /// Asserts that the messages derived with `prost` in some Rust source match those defined in a
+    /// `.proto` file, i.e., that they have the same fields with the same tags and types.
+    ///
+    /// This catches hand-written messages that drifted from their definitions.
+    ///
+    /// # Arguments
+    /// - `proto`: The contents of the `.proto` file.
+    /// - `rust`: The contents of the Rust source file with the messages.
+    ///
+    /// # Panics
+    /// This function panics if any message is defined in one but not the other, or if any of them
+    /// differs.
+    fn assert_proto_matches(proto: &str, rust: &str) {
+        let proto: BTreeMap<String, BTreeMap<u32, (String, String)>> = proto_messages(proto);
+        let rust: BTreeMap<String, BTreeMap<u32, (String, String)>> = prost_messages(rust);
+        assert_eq!(proto.keys().collect::<Vec<_>>(), rust.keys().collect::<Vec<_>>(), "Messages differ between .proto file and Rust");
+        for (name, fields) in &proto {
+            assert_eq!(fields, &rust[name], "Fields of message {name:?} differ between .proto file (left) and Rust (right)");
+        }
+    }
+
+
+    /// Returns a fixed time with nanosecond precision to test with.
+    fn time() -> DateTime<Utc> { Utc.with_ymd_and_hms(2026, 10, 16, 13, 37, 42).unwrap() + TimeDelta::nanoseconds(123456789) }
+
+
+    #[test]
+    fn test_metadata_roundtrip() {
+        let meta = metadata::Metadata {
+            attached: metadata::AttachedMetadata {
+                name: "a".into(),
+                description: "d".into(),
+                language: "eflint".into(),
+                branch: "dev".into(),
+                schema: None,
+                confidential: false,
+            },
+            created: time(),
+            creator: metadata::User {
+                id:     "amy".into(),
+                name:   "Amy".into(),
+                issuer: Some("https://idp.example.com".into()),
+                roles:  Vec::new(),
+            },
+            version: 42,
+            parent: Some(41),
+            promoted_from: None,
+            hash: Some("abc".into()),
+            origin: Some(metadata::Origin { store: "staging".into(), version: 7, creator: "bob".into(), created: time() }),
+            verification: Some(metadata::Verification { identity: "release".into(), digest: "sha256:abc".into(), verified: time() }),
+            frozen: Some(metadata::Freeze {
+                reason:  "under investigation".into(),
+                frozen:  time(),
+                freezer: metadata::User { id: "bob".into(), name: "Bob".into(), issuer: None, roles: Vec::new() },
+            }),
+        };
+
+        let raw: Vec<u8> = Metadata::from(meta).encode_to_vec();
+        let meta: metadata::Metadata = Metadata::decode(raw.as_slice()).unwrap().try_into().unwrap();
+        assert_eq!(meta.attached.branch, "dev");
+        assert_eq!(meta.created, time());
+        assert_eq!((meta.creator.id.as_str(), meta.creator.issuer.as_deref()), ("amy", Some("https://idp.example.com")));
+        assert_eq!((meta.version, meta.parent, meta.promoted_from), (42, Some(41), None));
+        assert_eq!(meta.hash.as_deref(), Some("abc"));
+        let origin: metadata::Origin = meta.origin.unwrap();
+        assert_eq!((origin.store.as_str(), origin.version, origin.creator.as_str(), origin.created), ("staging", 7, "bob", time()));
+        let verification: metadata::Verification = meta.verification.unwrap();
+        assert_eq!((verification.identity.as_str(), verification.digest.as_str(), verification.verified), ("release", "sha256:abc", time()));
+        let frozen: metadata::Freeze = meta.frozen.unwrap();
+        assert_eq!((frozen.reason.as_str(), frozen.frozen, frozen.freezer.id.as_str()), ("under investigation", time(), "bob"));
+    }
+
+    #[test]
+    fn test_activation_roundtrip() {
+        let act = metadata::Activation {
+            version:     1,
+            activated:   time(),
+            activator:   metadata::User { id: "amy".into(), name: "Amy".into(), issuer: None, roles: Vec::new() },
+            client:      Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            deactivated: None,
+            deactivator: None,
+        };
+
+        let raw: Vec<u8> = Activation::from(act).encode_to_vec();
+        let act: metadata::Activation = Activation::decode(raw.as_slice()).unwrap().try_into().unwrap();
+        assert_eq!(act.activated, time());
+        assert_eq!(act.client, Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))));
+        assert!(act.deactivated.is_none() && act.deactivator.is_none());
+    }
+
+    #[test]
+    fn test_empty_branch_is_default() {
+        let attached = AttachedMetadata {
+            name: "a".into(),
+            description: "d".into(),
+            language: "eflint".into(),
+            branch: String::new(),
+            schema: None,
+            confidential: false,
+        };
+        assert_eq!(metadata::AttachedMetadata::from(attached).branch, metadata::default_branch());
+    }
+
+    #[test]
+    fn test_missing_field() {
+        assert!(matches!(
+            metadata::Metadata::try_from(Metadata::default()),
+            Err(ConvertError::MissingField { message: "Metadata", field: "attached" })
+        ));
+    }
+
+    #[test]
+    fn test_messages_match_proto() { assert_proto_matches(PROTO, include_str!("proto.rs")); }
+}
@@ -0,0 +1,240 @@
+//  PROVENANCE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:47:42
+//  Last edited:
+//    16 Oct 2026, 17:47:42
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines provenance statements for policy versions, in the style of
+//!   in-toto attestations with a SLSA provenance predicate.
+//!
+//!   The statement is not signed; it merely collects what the store knows
+//!   about how a version came to be, such that it can be fed into tooling
+//!   that audits supply chains.
+//
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::{AttachedMetadata, Metadata};
+
+
+/***** CONSTANTS *****/
+/// The type of the in-toto statement.
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+/// The type of the predicate in the statement.
+pub const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+/// The type of "build" described by the predicate, i.e., writing a version to the store.
+pub const BUILD_TYPE: &str = "https://github.com/epi-project/policy-store/provenance/v1";
+/// The ID of the "builder" in the predicate, i.e., the store.
+pub const BUILDER_ID: &str = "https://github.com/epi-project/policy-store";
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Describes the artifact a [`Provenance`] statement is about.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Subject {
+    /// The name of the artifact, i.e., `<NAME>:<VERSION>` of the policy version.
+    pub name:   String,
+    /// The digests of the artifact by algorithm, i.e., the content hash as `sha256`.
+    pub digest: BTreeMap<String, String>,
+}
+
+/// Describes how a version came to be in the store.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Creation {
+    /// The version was added by a client.
+    Added,
+    /// The version was promoted from another branch.
+    Promoted,
+    /// The version was imported from another store.
+    Imported,
+}
+
+/// Describes a check that the content of a version passed before it was stored.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Validation {
+    /// The content adheres to a [content schema](crate::schema::Schema).
+    Schema {
+        /// The name of the schema.
+        name: String,
+    },
+    /// The content of an imported version matched the hash it was recorded with.
+    Hash {
+        /// The hash, as `sha256:<HEX>`.
+        digest: String,
+    },
+    /// The content was signed by a trusted identity.
+    Signature {
+        /// The name of the identity.
+        identity: String,
+        /// The digest of what was signed.
+        digest:   String,
+    },
+}
+
+/// Refers to an artifact the version was made from.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ResourceDescriptor {
+    /// The name of the artifact, i.e., `<VERSION>` for versions in the same store or
+    /// `<STORE>/<VERSION>` for versions in another.
+    pub name:   String,
+    /// The digests of the artifact by algorithm.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub digest: BTreeMap<String, String>,
+}
+
+/// The parameters of writing a version that the store decided itself.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct InternalParameters {
+    /// How the version was written.
+    pub creation:    Creation,
+    /// The ID of the user who wrote the version.
+    pub uploader:    String,
+    /// The address of the client from which the version was written, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client:      Option<IpAddr>,
+    /// The checks the content passed before it was written.
+    #[serde(default)]
+    pub validations: Vec<Validation>,
+}
+
+/// Describes what was asked of the store, and how it went about it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDefinition {
+    /// Always [`BUILD_TYPE`].
+    pub build_type: String,
+    /// The metadata the client sent along with the version.
+    pub external_parameters: AttachedMetadata,
+    /// What the store added to it.
+    pub internal_parameters: InternalParameters,
+    /// The versions this one was copied from, if any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resolved_dependencies: Vec<ResourceDescriptor>,
+}
+
+/// Identifies the store that wrote a version.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Builder {
+    /// Always [`BUILDER_ID`].
+    pub id: String,
+}
+
+/// Describes the request that wrote a version.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunMetadata {
+    /// The ID of the request that wrote the version, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invocation_id: Option<String>,
+    /// The time the version was written.
+    pub finished_on:   DateTime<Utc>,
+}
+
+/// Describes who wrote a version, and when.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RunDetails {
+    /// The store that wrote the version.
+    pub builder:  Builder,
+    /// The request that wrote it.
+    pub metadata: RunMetadata,
+}
+
+/// The SLSA provenance predicate of a [`Provenance`] statement.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlsaProvenance {
+    /// What was asked of the store.
+    pub build_definition: BuildDefinition,
+    /// Who asked it, and when.
+    pub run_details:      RunDetails,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// An in-toto statement describing how a policy version came to be in the store.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Provenance {
+    /// Always [`STATEMENT_TYPE`].
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    /// The version the statement is about.
+    pub subject: Vec<Subject>,
+    /// Always [`PREDICATE_TYPE`].
+    #[serde(rename = "predicateType")]
+    pub predicate_type: String,
+    /// The provenance itself.
+    pub predicate: SlsaProvenance,
+}
+impl Provenance {
+    /// Constructor for the Provenance of a version.
+    ///
+    /// Everything is derived from the version's `metadata`, except for what is only known while
+    /// it is being written. Its [schema](AttachedMetadata::schema) and
+    /// [verification](Metadata::verification) are recorded as passed validations, as the store
+    /// does not accept versions that fail them.
+    ///
+    /// # Arguments
+    /// - `metadata`: The [`Metadata`] of the version.
+    /// - `client`: The address of the client from which the version was written, if known.
+    /// - `request_id`: The ID of the request in which the version was written, if known.
+    ///
+    /// # Returns
+    /// A new Provenance statement.
+    pub fn new(metadata: &Metadata, client: Option<IpAddr>, request_id: Option<String>) -> Self {
+        let digest: BTreeMap<String, String> = metadata.hash.iter().map(|hash| ("sha256".to_string(), hash.clone())).collect();
+
+        // Find out how it was written, and from what
+        let (creation, resolved_dependencies): (Creation, Vec<ResourceDescriptor>) = match (metadata.promoted_from, &metadata.origin) {
+            (Some(source), _) => (Creation::Promoted, vec![ResourceDescriptor { name: source.to_string(), digest: digest.clone() }]),
+            (None, Some(origin)) => {
+                (Creation::Imported, vec![ResourceDescriptor { name: format!("{}/{}", origin.store, origin.version), digest: digest.clone() }])
+            },
+            (None, None) => (Creation::Added, Vec::new()),
+        };
+        let mut validations: Vec<Validation> = Vec::new();
+        if let Some(name) = &metadata.attached.schema {
+            validations.push(Validation::Schema { name: name.clone() });
+        }
+        if let (Creation::Imported, Some(hash)) = (creation, &metadata.hash) {
+            validations.push(Validation::Hash { digest: format!("sha256:{hash}") });
+        }
+        if let Some(verification) = &metadata.verification {
+            validations.push(Validation::Signature { identity: verification.identity.clone(), digest: verification.digest.clone() });
+        }
+
+        Self {
+            statement_type: STATEMENT_TYPE.into(),
+            subject: vec![Subject { name: format!("{}:{}", metadata.attached.name, metadata.version), digest }],
+            predicate_type: PREDICATE_TYPE.into(),
+            predicate: SlsaProvenance {
+                build_definition: BuildDefinition {
+                    build_type: BUILD_TYPE.into(),
+                    external_parameters: metadata.attached.clone(),
+                    internal_parameters: InternalParameters { creation, uploader: metadata.creator.id.clone(), client, validations },
+                    resolved_dependencies,
+                },
+                run_details:      RunDetails {
+                    builder:  Builder { id: BUILDER_ID.into() },
+                    metadata: RunMetadata { invocation_id: request_id, finished_on: metadata.created },
+                },
+            },
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//  REVOCATION.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:48:23
+//  Last edited:
+//    17 Oct 2026, 01:20:21
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines token revocations, i.e., tokens that authentication schemes
+//!   should reject even though they are validly signed.
+//
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::User;
+
+
+/***** LIBRARY *****/
+/// Describes which tokens are revoked, as given by the user when revoking them.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AttachedRevocation {
+    /// Revokes a single token by its identifier (i.e., its `jti` claim).
+    Token { jti: String },
+    /// Revokes all tokens of a user that were issued before some time.
+    ///
+    /// Tokens that don't say when they were issued (i.e., lack an `iat` claim) are always
+    /// revoked.
+    User { user: String, before: DateTime<Utc> },
+}
+
+/// A revocation as it is stored in the backend database.
+///
+/// Includes whatever is [attached](AttachedRevocation), but also things inferred when revoking
+/// (e.g., created time).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Revocation {
+    /// Whatever the user attached at runtime.
+    pub attached: AttachedRevocation,
+
+    /// The time the tokens were revoked.
+    pub created: DateTime<Utc>,
+    /// Defines who has revoked the tokens.
+    pub creator: User,
+}
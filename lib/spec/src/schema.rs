@@ -0,0 +1,104 @@
+//  SCHEMA.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:35:16
+//  Last edited:
+//    16 Oct 2026, 19:43:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines content schemas, i.e., named JSON schemas that policy content
+//!   can be validated against at ingest time.
+//
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::metadata::User;
+
+
+/***** ERRORS *****/
+/// Defines errors originating from validating content against a [`Schema`].
+#[derive(Debug, Error)]
+pub enum ValidateError {
+    /// The schema itself is not a valid JSON schema.
+    #[error("Schema {schema:?} is not a valid JSON schema: {err}")]
+    IllegalSchema { schema: String, err: String },
+    /// The content did not match the schema.
+    #[error("Content does not match schema {schema:?}:{}", errors.iter().flat_map(|err| ["\n - ", err.as_str()]).collect::<String>())]
+    Mismatch { schema: String, errors: Vec<String> },
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A content schema as given by the user when registering it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AttachedSchema {
+    /// The name of the schema. Unique within a store.
+    pub name: String,
+    /// Some description of the schema for recognition.
+    pub description: String,
+    /// The JSON schema itself.
+    pub schema: Value,
+}
+impl AttachedSchema {
+    /// Checks whether this is a valid JSON schema.
+    ///
+    /// Only available with the `schema-validation` feature.
+    ///
+    /// # Errors
+    /// This function errors if the schema is not a valid JSON schema.
+    #[cfg(feature = "schema-validation")]
+    pub fn check(&self) -> Result<(), ValidateError> {
+        jsonschema::validator_for(&self.schema)
+            .map(|_| ())
+            .map_err(|err| ValidateError::IllegalSchema { schema: self.name.clone(), err: err.to_string() })
+    }
+}
+
+/// A content schema as it is stored in the backend database.
+///
+/// Includes whatever is [attached](AttachedSchema), but also things inferred when registering it
+/// (e.g., created time).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Schema {
+    /// Whatever the user attached at runtime.
+    pub attached: AttachedSchema,
+
+    /// The time the schema was registered.
+    pub created: DateTime<Utc>,
+    /// Defines who has registered the schema.
+    pub creator: User,
+}
+impl Schema {
+    /// Validates the given content against this schema.
+    ///
+    /// Only available with the `schema-validation` feature.
+    ///
+    /// # Arguments
+    /// - `content`: The content to validate, as JSON.
+    ///
+    /// # Errors
+    /// This function errors if the content does not match the schema, or if the schema is not a
+    /// valid JSON schema to begin with.
+    #[cfg(feature = "schema-validation")]
+    pub fn validate(&self, content: &Value) -> Result<(), ValidateError> {
+        let validator = jsonschema::validator_for(&self.attached.schema)
+            .map_err(|err| ValidateError::IllegalSchema { schema: self.attached.name.clone(), err: err.to_string() })?;
+        let errors: Vec<String> = validator
+            .iter_errors(content)
+            .map(|err| {
+                let path: String = err.instance_path.to_string();
+                format!("{err} (at {})", if path.is_empty() { "/" } else { &path })
+            })
+            .collect();
+        if errors.is_empty() { Ok(()) } else { Err(ValidateError::Mismatch { schema: self.attached.name.clone(), errors }) }
+    }
+}
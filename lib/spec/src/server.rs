@@ -4,7 +4,7 @@
 //  Created:
 //    23 Oct 2024, 11:37:44
 //  Last edited:
-//    24 Oct 2024, 13:47:34
+//    16 Oct 2026, 16:58:56
 //  Auto updated?
 //    Yes
 //
@@ -15,6 +15,31 @@
 
 use std::error::Error;
 use std::future::Future;
+use std::net::SocketAddr;
+
+
+/***** AUXILLARY *****/
+/// Observes the lifecycle of a [`Server`], e.g., to learn the port it got when binding to port 0.
+///
+/// All methods do nothing by default.
+pub trait ServerHooks {
+    /// Called once the server is listening, but before it accepts any connections.
+    ///
+    /// # Arguments
+    /// - `addr`: The address the server is actually bound to.
+    #[inline]
+    fn on_start(&mut self, addr: SocketAddr) { let _ = addr; }
+
+    /// Called once the server has stopped accepting connections, right before it returns.
+    #[inline]
+    fn on_stop(&mut self) {}
+}
+
+// Default impls
+impl ServerHooks for () {}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -35,5 +60,23 @@ pub trait Server {
     /// # Errors
     /// This function may error if the server failed to listen of if a fatal server errors comes
     /// along as it serves. However, client-side errors should not trigger errors at this level.
-    fn serve(self) -> impl Future<Output = Result<(), Self::Error>>;
+    #[inline]
+    fn serve(self) -> impl Future<Output = Result<(), Self::Error>>
+    where
+        Self: Sized,
+    {
+        self.serve_with((), std::future::pending())
+    }
+
+    /// Runs this server until told to stop, reporting on its lifecycle as it goes.
+    ///
+    /// # Arguments
+    /// - `hooks`: Some [`ServerHooks`] to call when the server starts and stops.
+    /// - `shutdown`: A future that, once it completes, makes the server stop accepting
+    ///   connections and return.
+    ///
+    /// # Errors
+    /// This function may error if the server failed to listen of if a fatal server errors comes
+    /// along as it serves. However, client-side errors should not trigger errors at this level.
+    fn serve_with(self, hooks: impl ServerHooks, shutdown: impl Future<Output = ()>) -> impl Future<Output = Result<(), Self::Error>>;
 }
@@ -0,0 +1,179 @@
+//  TEMPLATE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 10:12:31
+//  Last edited:
+//    17 Oct 2026, 00:03:33
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines policy templates, i.e., reusable policy skeletons with
+//!   parameters that can be instantiated into new policy versions.
+//
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::metadata::User;
+
+
+/***** ERRORS *****/
+/// Defines errors originating from instantiating a [`Template`].
+#[derive(Debug, Error)]
+pub enum InstantiateError {
+    /// A parameter was given that the template doesn't define.
+    #[error("Unknown parameter {name:?} given for template {template:?}")]
+    UnknownParameter { template: String, name: String },
+    /// A parameter was not given and the template has no default for it.
+    #[error("Missing value for parameter {name:?} of template {template:?}")]
+    MissingParameter { template: String, name: String },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Replaces all placeholders in the given JSON value.
+///
+/// Placeholders are strings of the form `{{name}}`, where whitespace around the name is ignored.
+/// If a string consists of a placeholder only, it is replaced by the (typed) value of the
+/// parameter. Otherwise, every occurrence within the string is replaced by the textual
+/// representation of the value. Placeholders in the values themselves are left as-is.
+///
+/// # Arguments
+/// - `value`: The [`Value`] to replace placeholders in.
+/// - `values`: The values to substitute, by parameter name.
+///
+/// # Returns
+/// A new [`Value`] with all placeholders replaced.
+fn substitute(value: &Value, values: &HashMap<&str, &Value>) -> Value {
+    match value {
+        Value::String(s) => {
+            // Check if this is a full placeholder we can substitute with a typed value
+            if let Some(name) = s.strip_prefix("{{").and_then(|s| s.strip_suffix("}}")) {
+                if let Some(value) = values.get(name.trim()) {
+                    return (*value).clone();
+                }
+            }
+
+            // Otherwise, replace any embedded ones textually. This is done in a single pass over
+            // the skeleton, such that we never look for placeholders in what we substituted.
+            let mut res: String = String::with_capacity(s.len());
+            let mut rest: &str = s;
+            while let Some(start) = rest.find("{{") {
+                let Some(len) = rest[start + 2..].find("}}") else { break };
+                let end: usize = start + 2 + len + 2;
+                match values.get(rest[start + 2..end - 2].trim()) {
+                    Some(value) => {
+                        res.push_str(&rest[..start]);
+                        match value {
+                            Value::String(text) => res.push_str(text),
+                            other => res.push_str(&other.to_string()),
+                        }
+                        rest = &rest[end..];
+                    },
+                    None => {
+                        // Not a placeholder, but one might start within
+                        res.push_str(&rest[..start + 2]);
+                        rest = &rest[start + 2..];
+                    },
+                }
+            }
+            res.push_str(rest);
+            Value::String(res)
+        },
+        Value::Array(elems) => Value::Array(elems.iter().map(|elem| substitute(elem, values)).collect()),
+        Value::Object(fields) => Value::Object(fields.iter().map(|(key, field)| (key.clone(), substitute(field, values))).collect()),
+        Value::Null | Value::Bool(_) | Value::Number(_) => value.clone(),
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Describes a single parameter of a [`Template`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TemplateParameter {
+    /// The name of the parameter. Occurs as `{{name}}` in the template's skeleton.
+    pub name: String,
+    /// Some description of what the parameter is for.
+    pub description: String,
+    /// A value to use when the parameter isn't given upon instantiation, if any.
+    #[serde(default)]
+    pub default: Option<Value>,
+}
+
+/// A template as given by the user when registering it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AttachedTemplate {
+    /// The name of the template. Unique within a store.
+    pub name: String,
+    /// Some description of the template for recognition.
+    pub description: String,
+    /// The policy language of the policies instantiated from this template.
+    pub language: String,
+    /// The parameters that can be filled in when instantiating the template.
+    pub parameters: Vec<TemplateParameter>,
+    /// The body of the policy, with placeholders for the parameters.
+    pub skeleton: Value,
+}
+
+/// A template as it is stored in the backend database.
+///
+/// Includes whatever is [attached](AttachedTemplate), but also things inferred when registering
+/// it (e.g., created time).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Template {
+    /// Whatever the user attached at runtime.
+    pub attached: AttachedTemplate,
+
+    /// The time the template was registered.
+    pub created: DateTime<Utc>,
+    /// Defines who has registered the template.
+    pub creator: User,
+}
+impl Template {
+    /// Instantiates this template into a new policy body.
+    ///
+    /// # Arguments
+    /// - `values`: The values to give to the template's parameters. Any parameter omitted will
+    ///   be given its default value.
+    ///
+    /// # Returns
+    /// A [`Value`] representing the skeleton with all placeholders replaced.
+    ///
+    /// # Errors
+    /// This function errors if a value is given for an unknown parameter, or if a parameter
+    /// without default value is omitted.
+    pub fn instantiate(&self, values: &HashMap<String, Value>) -> Result<Value, InstantiateError> {
+        // Ensure no unknown parameters are given
+        for name in values.keys() {
+            if !self.attached.parameters.iter().any(|param| &param.name == name) {
+                return Err(InstantiateError::UnknownParameter { template: self.attached.name.clone(), name: name.clone() });
+            }
+        }
+
+        // Resolve the values for every parameter
+        let mut resolved: HashMap<&str, &Value> = HashMap::with_capacity(self.attached.parameters.len());
+        for param in &self.attached.parameters {
+            match values.get(&param.name).or(param.default.as_ref()) {
+                Some(value) => {
+                    resolved.insert(&param.name, value);
+                },
+                None => return Err(InstantiateError::MissingParameter { template: self.attached.name.clone(), name: param.name.clone() }),
+            }
+        }
+
+        // Now substitute them
+        Ok(substitute(&self.attached.skeleton, &resolved))
+    }
+}
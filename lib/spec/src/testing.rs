@@ -0,0 +1,61 @@
+//  TESTING.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:44:37
+//  Last edited:
+//    17 Oct 2026, 03:20:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Provides helpers shared by the tests of the backends implementing
+//!   this crate's interfaces.
+//
+
+use crate::metadata::AttachedMetadata;
+
+
+/***** LIBRARY *****/
+/// Creates some metadata to add versions with.
+///
+/// # Returns
+/// Some [`AttachedMetadata`] for the main branch.
+#[inline]
+pub fn metadata() -> AttachedMetadata { metadata_on("main") }
+
+/// Creates some metadata to add versions to a particular branch with.
+///
+/// # Arguments
+/// - `branch`: The branch to add the version to.
+///
+/// # Returns
+/// Some [`AttachedMetadata`] for the given branch.
+pub fn metadata_on(branch: &str) -> AttachedMetadata {
+    AttachedMetadata {
+        name: "test".into(),
+        description: "A test policy".into(),
+        language: "test".into(),
+        branch: branch.into(),
+        schema: None,
+        confidential: false,
+    }
+}
+
+/// Reads where to find an external server to test against from the environment.
+///
+/// # Arguments
+/// - `var`: The environment variable that holds the URL of the server.
+///
+/// # Returns
+/// The URL of the server, or [`None`] if `var` is not set. In that case, a message that the test
+/// is skipped is printed too.
+pub fn test_url(var: &str) -> Option<String> {
+    match std::env::var(var) {
+        Ok(url) => Some(url),
+        Err(_) => {
+            println!("Skipping test, as {var} is not set");
+            None
+        },
+    }
+}
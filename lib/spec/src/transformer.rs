@@ -0,0 +1,96 @@
+//  TRANSFORMER.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:20:01
+//  Last edited:
+//    16 Oct 2026, 14:20:01
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the [`ContentTransformer`] trait, which can rewrite policy
+//!   content before it is stored (e.g., normalizing or annotating it).
+//
+
+use std::convert::Infallible;
+use std::error::Error;
+
+use thiserror::Error;
+
+use crate::metadata::AttachedMetadata;
+
+
+/***** ERRORS *****/
+/// Defines errors originating from a chain of two [`ContentTransformer`]s.
+#[derive(Debug, Error)]
+pub enum ChainError<E1, E2> {
+    /// The first transformer in the chain failed.
+    #[error(transparent)]
+    First(E1),
+    /// The second transformer in the chain failed.
+    #[error(transparent)]
+    Second(E2),
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Rewrites policy content on ingest, before it is handed to the backend database.
+///
+/// Transformers can be chained by combining them in a tuple; `(T1, T2)` first runs `T1` and then
+/// feeds its result to `T2`. The unit type `()` is the identity transformer.
+///
+/// # Generics
+/// - `C`: The type of content transformed (typically a
+///   [`DatabaseConnector::Content`](crate::databaseconn::DatabaseConnector::Content)).
+pub trait ContentTransformer<C> {
+    /// The type of errors emitted when content cannot be transformed.
+    ///
+    /// These are considered to be caused by the content given by the user.
+    type Error: 'static + Send + Sync + Error;
+
+
+    /// Transforms the given content.
+    ///
+    /// # Arguments
+    /// - `metadata`: The [`AttachedMetadata`] uploaded alongside the `content`.
+    /// - `content`: The content to transform.
+    ///
+    /// # Returns
+    /// The transformed content, which is what will be stored.
+    ///
+    /// # Errors
+    /// This function may error if the content could not be transformed. This is reported to the
+    /// user as a 400 BAD REQUEST.
+    fn transform(&self, metadata: &AttachedMetadata, content: C) -> Result<C, Self::Error>;
+}
+
+// Default impls
+impl<C> ContentTransformer<C> for () {
+    type Error = Infallible;
+
+    #[inline]
+    fn transform(&self, _metadata: &AttachedMetadata, content: C) -> Result<C, Self::Error> { Ok(content) }
+}
+impl<C, T1, T2> ContentTransformer<C> for (T1, T2)
+where
+    T1: ContentTransformer<C>,
+    T2: ContentTransformer<C>,
+{
+    type Error = ChainError<T1::Error, T2::Error>;
+
+    #[inline]
+    fn transform(&self, metadata: &AttachedMetadata, content: C) -> Result<C, Self::Error> {
+        let content: C = self.0.transform(metadata, content).map_err(ChainError::First)?;
+        self.1.transform(metadata, content).map_err(ChainError::Second)
+    }
+}
+impl<C, T: ContentTransformer<C>> ContentTransformer<C> for &T {
+    type Error = T::Error;
+
+    #[inline]
+    fn transform(&self, metadata: &AttachedMetadata, content: C) -> Result<C, Self::Error> { (**self).transform(metadata, content) }
+}
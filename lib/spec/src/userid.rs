@@ -0,0 +1,212 @@
+//  USERID.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:50:06
+//  Last edited:
+//    16 Oct 2026, 17:50:06
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the [`UserIdFormat`] trait, which validates and normalizes
+//!   the [IDs](crate::metadata::User::id) of users before they are
+//!   trusted or stored.
+//
+
+use thiserror::Error;
+
+
+/***** ERRORS *****/
+/// Defines errors when a user ID does not adhere to a [`UserIdFormat`].
+#[derive(Debug, Error)]
+pub enum UserIdError {
+    /// The ID is not syntactically valid in the format.
+    #[error("User ID {id:?} is not a valid {format}: {reason}")]
+    Illegal { id: String, format: &'static str, reason: &'static str },
+    /// The ID is valid, but not in its normal form.
+    #[error("User ID {id:?} is not in normal form (expected {normal:?})")]
+    NotNormalized { id: String, normal: String },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Normalizes the percent-encoded characters in (part of) an ID to use uppercase hexadecimals.
+///
+/// # Arguments
+/// - `id`: The full ID, for use in errors.
+/// - `part`: The part of the ID to normalize.
+/// - `format`: The name of the format, for use in errors.
+/// - `allowed`: Decides which (non-percent-encoded) characters may appear in `part`.
+///
+/// # Returns
+/// The normalized `part`.
+///
+/// # Errors
+/// This function errors if `part` contains a character that is not `allowed` or a malformed
+/// percent-encoding.
+fn normalize_part(id: &str, part: &str, format: &'static str, allowed: impl Fn(char) -> bool) -> Result<String, UserIdError> {
+    let mut res: String = String::with_capacity(part.len());
+    let mut chars = part.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            match (chars.next(), chars.next()) {
+                (Some(hi), Some(lo)) if hi.is_ascii_hexdigit() && lo.is_ascii_hexdigit() => {
+                    res.push('%');
+                    res.push(hi.to_ascii_uppercase());
+                    res.push(lo.to_ascii_uppercase());
+                },
+                _ => return Err(UserIdError::Illegal { id: id.into(), format, reason: "malformed percent-encoding" }),
+            }
+        } else if allowed(c) {
+            res.push(c);
+        } else {
+            return Err(UserIdError::Illegal { id: id.into(), format, reason: "illegal character" });
+        }
+    }
+    Ok(res)
+}
+
+/// Splits the scheme off an ID, matching it case-insensitively.
+///
+/// # Arguments
+/// - `id`: The ID to split.
+/// - `scheme`: The (lowercase) scheme the ID must start with.
+/// - `format`: The name of the format, for use in errors.
+///
+/// # Returns
+/// The remainder of the ID after the scheme and its colon.
+///
+/// # Errors
+/// This function errors if the ID does not start with the given scheme.
+fn strip_scheme<'i>(id: &'i str, scheme: &str, format: &'static str) -> Result<&'i str, UserIdError> {
+    match id.split_once(':') {
+        Some((prefix, rest)) if prefix.eq_ignore_ascii_case(scheme) => Ok(rest),
+        _ => Err(UserIdError::Illegal { id: id.into(), format, reason: "missing scheme" }),
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Validates and normalizes the [IDs](crate::metadata::User::id) of users.
+///
+/// Deployments identifying users by, e.g., DIDs or URNs use this to keep the IDs in the store
+/// consistent. Servers [normalize](UserIdFormat::normalize()) the IDs produced by their
+/// [`AuthResolver`](crate::AuthResolver), and connectors [validate](UserIdFormat::validate())
+/// the IDs they are about to store, such that the same user is never stored under two spellings.
+///
+/// The unit type `()` accepts any ID as-is.
+pub trait UserIdFormat {
+    /// Normalizes the given ID.
+    ///
+    /// # Arguments
+    /// - `id`: The ID to normalize.
+    ///
+    /// # Returns
+    /// The normal form of the `id`.
+    ///
+    /// # Errors
+    /// This function errors if the `id` is not valid in this format.
+    fn normalize(&self, id: &str) -> Result<String, UserIdError>;
+
+    /// Checks that the given ID is valid and already in normal form.
+    ///
+    /// By default, this [normalizes](UserIdFormat::normalize()) the ID and compares the result.
+    ///
+    /// # Arguments
+    /// - `id`: The ID to check.
+    ///
+    /// # Errors
+    /// This function errors if the `id` is not valid in this format, or if it is not in normal
+    /// form.
+    #[inline]
+    fn validate(&self, id: &str) -> Result<(), UserIdError> {
+        let normal: String = self.normalize(id)?;
+        if normal != id {
+            return Err(UserIdError::NotNormalized { id: id.into(), normal });
+        }
+        Ok(())
+    }
+}
+
+// Default impls
+impl UserIdFormat for () {
+    #[inline]
+    fn normalize(&self, id: &str) -> Result<String, UserIdError> { Ok(id.into()) }
+
+    #[inline]
+    fn validate(&self, _id: &str) -> Result<(), UserIdError> { Ok(()) }
+}
+impl<T: ?Sized + UserIdFormat> UserIdFormat for &T {
+    #[inline]
+    fn normalize(&self, id: &str) -> Result<String, UserIdError> { (**self).normalize(id) }
+
+    #[inline]
+    fn validate(&self, id: &str) -> Result<(), UserIdError> { (**self).validate(id) }
+}
+
+
+
+/// Identifies users by URNs (e.g., `urn:example:users:amy`), as defined in
+/// [RFC 8141](https://www.rfc-editor.org/rfc/rfc8141).
+///
+/// Normalizing lowercases the scheme and namespace identifier, and uppercases percent-encodings in
+/// the namespace-specific string. Components (`?+`, `?=` and `#`) are not accepted.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Urn;
+impl UserIdFormat for Urn {
+    fn normalize(&self, id: &str) -> Result<String, UserIdError> {
+        let rest: &str = strip_scheme(id, "urn", "URN")?;
+        let Some((nid, nss)) = rest.split_once(':') else {
+            return Err(UserIdError::Illegal { id: id.into(), format: "URN", reason: "missing namespace-specific string" });
+        };
+
+        // The namespace identifier is case-insensitive
+        let nid_ok: bool = (2..=32).contains(&nid.len())
+            && nid.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            && !nid.starts_with('-')
+            && !nid.ends_with('-');
+        if !nid_ok {
+            return Err(UserIdError::Illegal { id: id.into(), format: "URN", reason: "illegal namespace identifier" });
+        }
+
+        // The rest is not, except for percent-encodings
+        if nss.is_empty() {
+            return Err(UserIdError::Illegal { id: id.into(), format: "URN", reason: "empty namespace-specific string" });
+        }
+        let nss: String = normalize_part(id, nss, "URN", |c| c.is_ascii_alphanumeric() || "-._~!$&'()*+,;=:@/".contains(c))?;
+        Ok(format!("urn:{}:{nss}", nid.to_ascii_lowercase()))
+    }
+}
+
+/// Identifies users by DIDs (e.g., `did:web:example.com:amy`), as defined in
+/// [DID Core](https://www.w3.org/TR/did-core/#did-syntax).
+///
+/// Normalizing lowercases the scheme and uppercases percent-encodings in the method-specific ID.
+/// DID URLs (i.e., with a path, query or fragment) are not accepted.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Did;
+impl UserIdFormat for Did {
+    fn normalize(&self, id: &str) -> Result<String, UserIdError> {
+        let rest: &str = strip_scheme(id, "did", "DID")?;
+        let Some((method, msid)) = rest.split_once(':') else {
+            return Err(UserIdError::Illegal { id: id.into(), format: "DID", reason: "missing method-specific ID" });
+        };
+
+        // NOTE: Method names are case-sensitive, and always lowercase
+        if method.is_empty() || !method.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()) {
+            return Err(UserIdError::Illegal { id: id.into(), format: "DID", reason: "illegal method name" });
+        }
+        if msid.is_empty() || msid.ends_with(':') {
+            return Err(UserIdError::Illegal { id: id.into(), format: "DID", reason: "empty method-specific ID" });
+        }
+        let msid: String = normalize_part(id, msid, "DID", |c| c.is_ascii_alphanumeric() || ".-_:".contains(c))?;
+        Ok(format!("did:{method}:{msid}"))
+    }
+}
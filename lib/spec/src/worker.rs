@@ -0,0 +1,86 @@
+//  WORKER.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:00:27
+//  Last edited:
+//    17 Oct 2026, 01:39:06
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements an abstraction over background work that a server runs
+//!   periodically while it serves, e.g., pruning old records.
+//
+
+use std::error::Error;
+use std::future::Future;
+use std::time::Duration;
+
+
+/***** LIBRARY *****/
+/// Abstracts over some background work that a [`Server`](crate::Server) runs periodically while
+/// it serves.
+///
+/// The server calls [`Worker::start()`] once before the first tick, then
+/// [`Worker::tick()`] every [`Worker::interval()`] (or as soon as the worker
+/// [wakes](Worker::wake())) for as long as it serves, and finally
+/// [`Worker::stop()`] once it stops. A tick is never interrupted; if the server stops during a
+/// tick, it waits for that tick to complete before stopping the worker.
+///
+/// Errors are reported by the server, but do not stop it. A worker that fails to start is never
+/// ticked; a worker that fails to tick is simply ticked again at the next interval.
+pub trait Worker {
+    /// The type of errors emitted by this worker.
+    type Error: Error;
+
+
+    /// Returns a name for this worker, used to identify it in logs.
+    ///
+    /// By default, this is the name of its type.
+    ///
+    /// # Returns
+    /// A human-readable name.
+    #[inline]
+    fn name(&self) -> &str { std::any::type_name::<Self>() }
+
+    /// Returns how long to wait between ticks.
+    ///
+    /// This is asked anew after every tick, so workers may back off or speed up as they see fit.
+    ///
+    /// # Returns
+    /// The [`Duration`] until the next tick.
+    fn interval(&self) -> Duration;
+
+    /// Waits until the worker should tick before its [interval](Worker::interval()) has passed,
+    /// e.g., because a [`ChangeListener`](crate::databaseconn::ChangeListener) reported a change.
+    ///
+    /// The returned future is dropped as soon as the interval passes or the server stops, so it
+    /// must be safe to cancel. By default, it never completes.
+    #[inline]
+    fn wake(&self) -> impl Send + Future<Output = ()> { std::future::pending() }
+
+    /// Prepares the worker before its first tick.
+    ///
+    /// By default, this does nothing.
+    ///
+    /// # Errors
+    /// This function can error if the worker failed to start, in which case it is never ticked.
+    #[inline]
+    fn start(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> { async { Ok(()) } }
+
+    /// Performs one round of work.
+    ///
+    /// # Errors
+    /// This function can error if this round of work failed.
+    fn tick(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>>;
+
+    /// Cleans up the worker after the server has stopped.
+    ///
+    /// By default, this does nothing.
+    ///
+    /// # Errors
+    /// This function can error if the worker failed to clean up after itself.
+    #[inline]
+    fn stop(&mut self) -> impl Send + Future<Output = Result<(), Self::Error>> { async { Ok(()) } }
+}
@@ -0,0 +1,106 @@
+//  ROUNDTRIP.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:30:16
+//  Last edited:
+//    16 Oct 2026, 17:47:42
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests that the types shared over the wire survive a serde
+//!   round-trip unchanged.
+//
+
+use proptest::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use specifications::audit::{AuditRecord, Change};
+use specifications::decision::Decision;
+use specifications::event::{EventRecord, PolicyEvent};
+use specifications::key::Key;
+use specifications::metadata::{Activation, AttachedMetadata, Branch, Metadata, User};
+use specifications::provenance::Provenance;
+use specifications::revocation::Revocation;
+use specifications::schema::Schema;
+use specifications::template::Template;
+
+
+/***** HELPERS *****/
+/// Serializes the given value to JSON text, parses it back and asserts it serializes the same
+/// way again.
+///
+/// We compare the serialized forms instead of the values themselves, as the types don't
+/// implement [`PartialEq`] and because it's the wire format we want to guard anyway.
+fn assert_roundtrip<T: Serialize + DeserializeOwned>(value: &T) -> Result<(), TestCaseError> {
+    let before: serde_json::Value = serde_json::to_value(value).unwrap();
+    let parsed: T = serde_json::from_str(&serde_json::to_string(value).unwrap()).map_err(|err| TestCaseError::fail(err.to_string()))?;
+    prop_assert_eq!(before, serde_json::to_value(&parsed).unwrap());
+    Ok(())
+}
+
+
+
+
+
+/***** TESTS *****/
+proptest! {
+    #[test]
+    fn test_user_roundtrip(value: User) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_attached_metadata_roundtrip(value: AttachedMetadata) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_metadata_roundtrip(value: Metadata) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_branch_roundtrip(value: Branch) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_activation_roundtrip(value: Activation) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_decision_roundtrip(value: Decision) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_provenance_roundtrip(value: Provenance) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_key_roundtrip(value: Key) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_revocation_roundtrip(value: Revocation) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_schema_roundtrip(value: Schema) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_template_roundtrip(value: Template) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_audit_record_roundtrip(value: AuditRecord) {
+        // This one implements `PartialEq`, so we can be a bit stricter
+        let parsed: AuditRecord = serde_json::from_str(&serde_json::to_string(&value).unwrap()).unwrap();
+        prop_assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn test_change_roundtrip(value: Change<String>) { assert_roundtrip(&value)?; }
+
+    #[test]
+    fn test_policy_event_roundtrip(value: PolicyEvent) {
+        let parsed: PolicyEvent = serde_json::from_str(&serde_json::to_string(&value).unwrap()).unwrap();
+        prop_assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn test_event_record_roundtrip(value: EventRecord) {
+        // NOTE: The event is flattened into the record, so make sure that its tag survives that
+        let json: serde_json::Value = serde_json::to_value(&value).unwrap();
+        prop_assert_eq!(json["event"].as_str(), Some(value.event.kind()));
+        let parsed: EventRecord = serde_json::from_value(json).unwrap();
+        prop_assert_eq!(parsed, value);
+    }
+}
@@ -0,0 +1,67 @@
+//  TEMPLATE.rs
+//    by Lut99
+//
+//  Created:
+//    17 Oct 2026, 00:03:15
+//  Last edited:
+//    17 Oct 2026, 00:03:15
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests instantiating templates.
+//
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde_json::{Value, json};
+use specifications::metadata::User;
+use specifications::template::{AttachedTemplate, Template, TemplateParameter};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates a template with the given skeleton and parameters without defaults.
+///
+/// # Arguments
+/// - `skeleton`: The skeleton of the template.
+/// - `parameters`: The names of its parameters.
+///
+/// # Returns
+/// A new [`Template`].
+fn template(skeleton: Value, parameters: &[&str]) -> Template {
+    Template {
+        attached: AttachedTemplate {
+            name: "test".into(),
+            description: "A template to test with".into(),
+            language: "eflint-json".into(),
+            parameters: parameters.iter().map(|name| TemplateParameter { name: (*name).into(), description: String::new(), default: None }).collect(),
+            skeleton,
+        },
+        created:  Utc::now(),
+        creator:  User { id: "tester".into(), name: "Tester".into(), issuer: None, roles: Vec::new() },
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[test]
+fn test_instantiate() {
+    let template = template(json!({ "who": "{{ user }}", "limit": "{{limit}}", "text": "{{user}} may do {{ limit }} things" }), &["user", "limit"]);
+    let values: HashMap<String, Value> = HashMap::from([("user".into(), json!("amy")), ("limit".into(), json!(3))]);
+
+    // Full placeholders keep their type, embedded ones become text; both may have whitespace around the name
+    assert_eq!(template.instantiate(&values).unwrap(), json!({ "who": "amy", "limit": 3, "text": "amy may do 3 things" }));
+}
+
+#[test]
+fn test_instantiate_placeholders_in_values() {
+    let template = template(json!(["{{a}}", "{{a}} and {{ b }}", "{{ {{b}}", "{{unknown}}"]), &["a", "b"]);
+    let values: HashMap<String, Value> = HashMap::from([("a".into(), json!("{{b}}")), ("b".into(), json!("x"))]);
+
+    // What is substituted is never substituted into again, regardless of the order of the parameters
+    assert_eq!(template.instantiate(&values).unwrap(), json!(["{{b}}", "{{b}} and x", "{{ x", "{{unknown}}"]));
+}
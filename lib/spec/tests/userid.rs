@@ -0,0 +1,47 @@
+//  USERID.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:50:06
+//  Last edited:
+//    16 Oct 2026, 17:50:06
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the built-in formats for user IDs.
+//
+
+use specifications::userid::{Did, Urn, UserIdError, UserIdFormat};
+
+
+/***** TESTS *****/
+#[test]
+fn test_urn() {
+    assert_eq!(Urn.normalize("URN:Example:users:Amy").unwrap(), "urn:example:users:Amy");
+    assert_eq!(Urn.normalize("urn:example:a%2fb").unwrap(), "urn:example:a%2Fb");
+    for id in ["amy", "urn:example", "urn:x:amy", "urn:-example:amy", "urn:example:", "urn:example:amy?=q", "urn:example:%zz"] {
+        assert!(matches!(Urn.normalize(id), Err(UserIdError::Illegal { .. })), "{id:?} should not be a valid URN");
+    }
+
+    assert!(Urn.validate("urn:example:users:Amy").is_ok());
+    assert!(matches!(Urn.validate("urn:EXAMPLE:users:Amy"), Err(UserIdError::NotNormalized { normal, .. }) if normal == "urn:example:users:Amy"));
+}
+
+#[test]
+fn test_did() {
+    assert_eq!(Did.normalize("DID:web:example.com:Amy").unwrap(), "did:web:example.com:Amy");
+    assert_eq!(Did.normalize("did:web:example.com%3a8080").unwrap(), "did:web:example.com%3A8080");
+    for id in ["amy", "did:web", "did:Web:example.com", "did:web:", "did:web:example.com:", "did:web:example.com/amy", "did:web:amy#key-1"] {
+        assert!(matches!(Did.normalize(id), Err(UserIdError::Illegal { .. })), "{id:?} should not be a valid DID");
+    }
+
+    assert!(Did.validate("did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK").is_ok());
+    assert!(matches!(Did.validate("Did:key:z6Mk"), Err(UserIdError::NotNormalized { .. })));
+}
+
+#[test]
+fn test_any() {
+    assert_eq!(().normalize("Amy Smith").unwrap(), "Amy Smith");
+    assert!(().validate("").is_ok());
+}
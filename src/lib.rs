@@ -4,7 +4,7 @@
 //  Created:
 //    18 Oct 2024, 17:31:50
 //  Last edited:
-//    06 Dec 2024, 18:01:14
+//    16 Oct 2026, 19:05:36
 //  Auto updated?
 //    Yes
 //
@@ -14,6 +14,11 @@
 //
 
 // Import the libraries
+pub mod clients {
+    #[cfg(feature = "reqwest-client")]
+    pub use reqwest_client as reqwest;
+}
+
 pub mod servers {
     #[cfg(feature = "axum-server")]
     pub use axum_server as axum;
@@ -21,16 +26,40 @@ pub mod servers {
     pub mod axum {
         pub use axum_server_spec as spec;
     }
+    #[cfg(feature = "tonic-server-spec")]
+    pub mod tonic {
+        pub use tonic_server_spec as spec;
+    }
 }
 
 pub mod auth {
+    #[cfg(feature = "introspection-auth")]
+    pub use introspection_auth as introspection;
     #[cfg(feature = "jwk-auth")]
     pub use jwk_auth as jwk;
     #[cfg(feature = "no-op-auth")]
     pub use no_op_auth as no_op;
+    #[cfg(feature = "static-users-auth")]
+    pub use static_users_auth as static_users;
 }
 
 pub mod databases {
+    #[cfg(feature = "etcd-database")]
+    pub use etcd_database as etcd;
+    #[cfg(feature = "fault-injection-database")]
+    pub use fault_injection_database as fault_injection;
+    #[cfg(feature = "fs-database")]
+    pub use fs_database as fs;
+    #[cfg(feature = "hedged-database")]
+    pub use hedged_database as hedged;
+    #[cfg(feature = "memory-database")]
+    pub use memory_database as memory;
+    #[cfg(feature = "mysql-database")]
+    pub use mysql_database as mysql;
+    #[cfg(feature = "postgres-database")]
+    pub use postgres_database as postgres;
+    #[cfg(feature = "redis-database")]
+    pub use redis_database as redis;
     #[cfg(feature = "sqlite-database")]
     pub use sqlite_database as sqlite;
 }